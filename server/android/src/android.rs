@@ -59,10 +59,16 @@ pub mod android {
             },
             // sync settings need to be configured at runtime
             sync: None,
+            // peer sync relies on DNS-SD discovery which is not available on android
+            peer_sync: None,
+            // mobile data budget is not configurable on android yet
+            sync_bandwidth: None,
             logging: Some(
                 LoggingSettings::new(LogMode::File, service::settings::Level::Info)
                     .with_directory(files_dir.to_string_lossy().to_string()),
             ),
+            temperature_loggers: None,
+            notifications: None,
         };
 
         logging_init(settings.logging.clone(), None);
@@ -0,0 +1,137 @@
+use async_graphql::*;
+use graphql_asset_catalogue::types::asset_catalogue_item::{
+    AssetCatalogueItemNode, AssetEnergySourceType,
+};
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use repository::StorePopulationRow;
+use service::{
+    asset::cce_properties::{
+        UpdateCatalogueItemProperties, UpdateCatalogueItemPropertiesError, UpsertStorePopulation,
+        UpsertStorePopulationError,
+    },
+    auth::{Resource, ResourceAccessRequest},
+};
+
+#[derive(InputObject)]
+pub struct UpsertStorePopulationInput {
+    pub store_id: String,
+    pub population_served: i32,
+}
+
+/// Unset values are cleared
+#[derive(InputObject)]
+pub struct UpdateAssetCatalogueItemPropertiesInput {
+    pub id: String,
+    pub energy_source: Option<AssetEnergySourceType>,
+    /// Net vaccine storage volume in litres
+    pub storage_capacity: Option<f64>,
+}
+
+pub struct StorePopulationNode {
+    pub store_population: StorePopulationRow,
+}
+
+#[Object]
+impl StorePopulationNode {
+    pub async fn store_id(&self) -> &str {
+        &self.store_population.store_id
+    }
+
+    pub async fn population_served(&self) -> i32 {
+        self.store_population.population_served
+    }
+}
+
+pub fn upsert_store_population(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: UpsertStorePopulationInput,
+) -> Result<StorePopulationNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateAsset,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    let store_population = service_provider
+        .asset_service
+        .upsert_store_population(
+            &service_context,
+            UpsertStorePopulation {
+                store_id: input.store_id,
+                population_served: input.population_served,
+            },
+        )
+        .map_err(map_upsert_store_population_error)?;
+
+    Ok(StorePopulationNode { store_population })
+}
+
+pub fn update_asset_catalogue_item_properties(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: UpdateAssetCatalogueItemPropertiesInput,
+) -> Result<AssetCatalogueItemNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateAssetCatalogueItem,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    let catalogue_item = service_provider
+        .asset_service
+        .update_catalogue_item_properties(
+            &service_context,
+            UpdateCatalogueItemProperties {
+                id: input.id,
+                energy_source: input.energy_source.map(AssetEnergySourceType::to_domain),
+                storage_capacity: input.storage_capacity,
+            },
+        )
+        .map_err(map_update_catalogue_item_properties_error)?;
+
+    Ok(AssetCatalogueItemNode::from_domain(catalogue_item))
+}
+
+fn map_upsert_store_population_error(error: UpsertStorePopulationError) -> async_graphql::Error {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        UpsertStorePopulationError::StoreDoesNotExist
+        | UpsertStorePopulationError::PopulationServedNegative => BadUserInput(formatted_error),
+        UpsertStorePopulationError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    graphql_error.extend()
+}
+
+fn map_update_catalogue_item_properties_error(
+    error: UpdateCatalogueItemPropertiesError,
+) -> async_graphql::Error {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        UpdateCatalogueItemPropertiesError::CatalogueItemDoesNotExist
+        | UpdateCatalogueItemPropertiesError::StorageCapacityNegative => {
+            BadUserInput(formatted_error)
+        }
+        UpdateCatalogueItemPropertiesError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    graphql_error.extend()
+}
@@ -0,0 +1,191 @@
+use async_graphql::dataloader::DataLoader;
+use async_graphql::*;
+use graphql_asset_catalogue::types::asset_catalogue_item::{
+    AssetCatalogueItemNode, AssetEnergySourceType,
+};
+use graphql_core::{
+    loader::StoreByIdLoader,
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::StoreNode;
+use repository::assets::asset::AssetFilter;
+use service::{
+    asset::cce_inventory::{
+        CceFacilityInventory, CceInventoryError, CceInventoryInput as ServiceInput,
+        CceInventoryLine,
+    },
+    auth::{Resource, ResourceAccessRequest},
+    usize_to_u32,
+};
+use util::is_central_server;
+
+use crate::types::{AssetFilterInput, AssetNode, StatusType};
+
+#[derive(InputObject)]
+pub struct CceInventoryInput {
+    /// All facilities with equipment or population served when not set
+    pub store_ids: Option<Vec<String>>,
+    /// Vaccine storage volume required per person served, in litres
+    pub litres_per_person: f64,
+    pub filter: Option<AssetFilterInput>,
+}
+
+pub struct CceFacilityInventoryNode {
+    pub facility: CceFacilityInventory,
+}
+
+pub struct CceInventoryLineNode {
+    pub line: CceInventoryLine,
+}
+
+#[derive(SimpleObject)]
+pub struct CceInventoryConnector {
+    total_count: u32,
+    nodes: Vec<CceFacilityInventoryNode>,
+}
+
+#[derive(Union)]
+pub enum CceInventoryResponse {
+    Response(CceInventoryConnector),
+}
+
+pub fn cce_inventory(
+    ctx: &Context<'_>,
+    store_id: String,
+    input: CceInventoryInput,
+) -> Result<CceInventoryResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryAsset,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+    if !is_central_server() {
+        return Err(StandardGraphqlError::from_str("Not a central server"));
+    }
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id, user.user_id)?;
+
+    let facilities = service_provider
+        .asset_service
+        .get_cce_inventory(
+            &service_context.connection,
+            ServiceInput {
+                store_ids: input.store_ids,
+                litres_per_person: input.litres_per_person,
+                filter: input.filter.map(AssetFilter::from),
+            },
+        )
+        .map_err(map_error)?;
+
+    Ok(CceInventoryResponse::Response(CceInventoryConnector {
+        total_count: usize_to_u32(facilities.len()),
+        nodes: facilities
+            .into_iter()
+            .map(|facility| CceFacilityInventoryNode { facility })
+            .collect(),
+    }))
+}
+
+#[Object]
+impl CceFacilityInventoryNode {
+    pub async fn store_id(&self) -> &str {
+        &self.facility.store_id
+    }
+
+    pub async fn store(&self, ctx: &Context<'_>) -> Result<Option<StoreNode>> {
+        let loader = ctx.get_loader::<DataLoader<StoreByIdLoader>>();
+        Ok(loader
+            .load_one(self.facility.store_id.clone())
+            .await?
+            .map(StoreNode::from_domain))
+    }
+
+    pub async fn population_served(&self) -> Option<i32> {
+        self.facility.population_served
+    }
+
+    /// Storage capacity of equipment that is not decommissioned, in litres
+    pub async fn total_storage_capacity(&self) -> f64 {
+        self.facility.total_storage_capacity
+    }
+
+    /// Storage capacity of functioning equipment, in litres
+    pub async fn net_storage_capacity(&self) -> f64 {
+        self.facility.net_storage_capacity
+    }
+
+    /// Population served multiplied by litres per person
+    pub async fn required_storage_capacity(&self) -> Option<f64> {
+        self.facility.required_storage_capacity
+    }
+
+    /// Net minus required storage capacity, negative when the facility has a shortage
+    pub async fn storage_capacity_difference(&self) -> Option<f64> {
+        self.facility.storage_capacity_difference()
+    }
+
+    pub async fn lines(&self) -> Vec<CceInventoryLineNode> {
+        self.facility
+            .lines
+            .iter()
+            .cloned()
+            .map(|line| CceInventoryLineNode { line })
+            .collect()
+    }
+}
+
+#[Object]
+impl CceInventoryLineNode {
+    pub async fn asset(&self) -> AssetNode {
+        AssetNode::from_domain(self.line.asset.clone())
+    }
+
+    pub async fn catalogue_item(&self) -> Option<AssetCatalogueItemNode> {
+        self.line
+            .catalogue_item
+            .clone()
+            .map(AssetCatalogueItemNode::from_domain)
+    }
+
+    /// Status of the latest asset log
+    pub async fn status(&self) -> Option<StatusType> {
+        self.line.status.as_ref().map(StatusType::from_domain)
+    }
+
+    /// Full years since installation
+    pub async fn age_years(&self) -> Option<u32> {
+        self.line.age_years
+    }
+
+    pub async fn energy_source(&self) -> Option<AssetEnergySourceType> {
+        self.line
+            .energy_source()
+            .as_ref()
+            .map(AssetEnergySourceType::from_domain)
+    }
+
+    /// Net vaccine storage volume in litres
+    pub async fn storage_capacity(&self) -> Option<f64> {
+        self.line.storage_capacity()
+    }
+
+    pub async fn is_functioning(&self) -> bool {
+        self.line.is_functioning()
+    }
+}
+
+fn map_error(error: CceInventoryError) -> async_graphql::Error {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        CceInventoryError::LitresPerPersonNegative => BadUserInput(formatted_error),
+        CceInventoryError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    graphql_error.extend()
+}
@@ -2,8 +2,15 @@ mod mutations;
 use self::mutations::*;
 mod logs;
 use self::logs::*;
+mod cce_inventory;
+use self::cce_inventory::*;
+mod maintenance;
+use self::maintenance::*;
+mod lifecycle;
+use self::lifecycle::*;
 
 use async_graphql::*;
+use graphql_asset_catalogue::types::asset_catalogue_item::AssetCatalogueItemNode;
 use graphql_core::{
     pagination::PaginationInput,
     standard_graphql_error::{validate_auth, StandardGraphqlError},
@@ -15,7 +22,7 @@ use service::auth::{Resource, ResourceAccessRequest};
 use types::AssetLogFilterInput;
 use types::AssetLogSortInput;
 use types::AssetLogsResponse;
-use types::{AssetConnector, AssetFilterInput, AssetSortInput, AssetsResponse};
+use types::{AssetConnector, AssetFilterInput, AssetNode, AssetSortInput, AssetsResponse};
 
 #[derive(Default, Clone)]
 pub struct AssetQueries;
@@ -59,6 +66,59 @@ impl AssetQueries {
             assets,
         )))
     }
+
+    /// Cold chain equipment inventory per facility, only available on central server
+    pub async fn cce_inventory(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: CceInventoryInput,
+    ) -> Result<CceInventoryResponse> {
+        cce_inventory(ctx, store_id, input)
+    }
+
+    /// Preventive maintenance schedules of asset types and catalogue items
+    pub async fn maintenance_schedules(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+    ) -> Result<AssetMaintenanceScheduleConnector> {
+        maintenance_schedules(ctx, store_id)
+    }
+
+    /// Maintenance work orders of the store's assets
+    pub async fn asset_work_orders(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        page: Option<PaginationInput>,
+        filter: Option<AssetWorkOrderFilterInput>,
+        sort: Option<Vec<AssetWorkOrderSortInput>>,
+    ) -> Result<AssetWorkOrdersResponse> {
+        asset_work_orders(ctx, store_id, page, filter, sort)
+    }
+
+    /// Asset transfers from or to the store
+    pub async fn asset_transfers(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        page: Option<PaginationInput>,
+        filter: Option<AssetTransferFilterInput>,
+        sort: Option<Vec<AssetTransferSortInput>>,
+    ) -> Result<AssetTransfersResponse> {
+        asset_transfers(ctx, store_id, page, filter, sort)
+    }
+
+    /// Location and ownership history of the asset, from creation to disposal
+    pub async fn asset_history(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        asset_id: String,
+    ) -> Result<AssetHistoryConnector> {
+        asset_history(ctx, store_id, asset_id)
+    }
 }
 
 #[derive(Default, Clone)]
@@ -92,6 +152,105 @@ impl AssetMutations {
     ) -> Result<DeleteAssetResponse> {
         delete_asset(ctx, &store_id, &asset_id)
     }
+
+    async fn upsert_maintenance_schedule(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: UpsertMaintenanceScheduleInput,
+    ) -> Result<AssetMaintenanceScheduleNode> {
+        upsert_maintenance_schedule(ctx, &store_id, input)
+    }
+
+    async fn record_asset_run_hours(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: RecordAssetRunHoursInput,
+    ) -> Result<AssetRunHoursNode> {
+        record_asset_run_hours(ctx, &store_id, input)
+    }
+
+    async fn assign_work_order(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: AssignWorkOrderInput,
+    ) -> Result<AssetWorkOrderNode> {
+        assign_work_order(ctx, &store_id, input)
+    }
+
+    /// Completes the work order and adds an asset log with the notes and parts used
+    async fn complete_work_order(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: CompleteWorkOrderInput,
+    ) -> Result<AssetWorkOrderNode> {
+        complete_work_order(ctx, &store_id, input)
+    }
+
+    async fn insert_asset_transfer(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: InsertAssetTransferInput,
+    ) -> Result<AssetTransferNode> {
+        insert_asset_transfer(ctx, &store_id, input)
+    }
+
+    /// Dispatch or cancel by the source store, receive by the destination store
+    async fn update_asset_transfer(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: UpdateAssetTransferInput,
+    ) -> Result<AssetTransferNode> {
+        update_asset_transfer(ctx, &store_id, input)
+    }
+
+    async fn decommission_asset(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: DecommissionAssetInput,
+    ) -> Result<AssetNode> {
+        decommission_asset(ctx, &store_id, input)
+    }
+
+    async fn insert_asset_disposal(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: InsertAssetDisposalInput,
+    ) -> Result<AssetDisposalNode> {
+        insert_asset_disposal(ctx, &store_id, input)
+    }
+}
+
+/// Central server only, see `CentralServerMutationNode`
+#[derive(Default, Clone)]
+pub struct ColdChainEquipmentMutations;
+
+#[Object]
+impl ColdChainEquipmentMutations {
+    async fn upsert_store_population(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: UpsertStorePopulationInput,
+    ) -> Result<StorePopulationNode> {
+        upsert_store_population(ctx, &store_id, input)
+    }
+
+    async fn update_asset_catalogue_item_properties(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: UpdateAssetCatalogueItemPropertiesInput,
+    ) -> Result<AssetCatalogueItemNode> {
+        update_asset_catalogue_item_properties(ctx, &store_id, input)
+    }
 }
 
 #[derive(Default, Clone)]
@@ -0,0 +1,4 @@
+mod mutations;
+mod query;
+pub use mutations::*;
+pub use query::*;
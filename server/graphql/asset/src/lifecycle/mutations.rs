@@ -0,0 +1,305 @@
+use async_graphql::*;
+use chrono::{NaiveDate, NaiveDateTime};
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use repository::assets::asset_disposal_row::AssetDisposalRow;
+use service::{
+    asset::lifecycle::{
+        disposal::{
+            DecommissionAsset, DecommissionAssetError, InsertAssetDisposal,
+            InsertAssetDisposalError,
+        },
+        transfer::{
+            InsertAssetTransfer, InsertAssetTransferError, UpdateAssetTransfer,
+            UpdateAssetTransferError, UpdateAssetTransferStatus,
+        },
+    },
+    auth::{Resource, ResourceAccessRequest},
+};
+
+use super::{AssetDisposalMethodType, AssetTransferNode};
+use crate::types::AssetNode;
+
+#[derive(InputObject)]
+pub struct InsertAssetTransferInput {
+    pub id: String,
+    pub asset_id: String,
+    pub to_store_id: String,
+    pub comment: Option<String>,
+}
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum UpdateAssetTransferStatusInput {
+    /// Moves the asset to the destination store, only by the source store
+    Dispatched,
+    /// Confirms receipt, only by the destination store
+    Received,
+    /// Only new transfers can be cancelled
+    Cancelled,
+}
+
+#[derive(InputObject)]
+pub struct UpdateAssetTransferInput {
+    pub id: String,
+    pub status: UpdateAssetTransferStatusInput,
+}
+
+#[derive(InputObject)]
+pub struct DecommissionAssetInput {
+    pub asset_id: String,
+    pub decommissioned_date: NaiveDate,
+    pub reason: String,
+}
+
+#[derive(InputObject)]
+pub struct InsertAssetDisposalInput {
+    pub id: String,
+    pub asset_id: String,
+    pub disposal_date: NaiveDate,
+    pub method: AssetDisposalMethodType,
+    pub comment: Option<String>,
+}
+
+pub struct AssetDisposalNode {
+    pub disposal: AssetDisposalRow,
+}
+
+#[Object]
+impl AssetDisposalNode {
+    pub async fn id(&self) -> &str {
+        &self.disposal.id
+    }
+
+    pub async fn asset_id(&self) -> &str {
+        &self.disposal.asset_id
+    }
+
+    pub async fn store_id(&self) -> &str {
+        &self.disposal.store_id
+    }
+
+    pub async fn disposal_date(&self) -> &NaiveDate {
+        &self.disposal.disposal_date
+    }
+
+    pub async fn method(&self) -> AssetDisposalMethodType {
+        AssetDisposalMethodType::from_domain(&self.disposal.method)
+    }
+
+    pub async fn comment(&self) -> &Option<String> {
+        &self.disposal.comment
+    }
+
+    pub async fn created_datetime(&self) -> &NaiveDateTime {
+        &self.disposal.created_datetime
+    }
+}
+
+pub fn insert_asset_transfer(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: InsertAssetTransferInput,
+) -> Result<AssetTransferNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateAsset,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    let transfer = service_provider
+        .asset_service
+        .insert_asset_transfer(
+            &service_context,
+            InsertAssetTransfer {
+                id: input.id,
+                asset_id: input.asset_id,
+                to_store_id: input.to_store_id,
+                comment: input.comment,
+            },
+        )
+        .map_err(map_insert_transfer_error)?;
+
+    Ok(AssetTransferNode { transfer })
+}
+
+pub fn update_asset_transfer(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: UpdateAssetTransferInput,
+) -> Result<AssetTransferNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateAsset,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    let transfer = service_provider
+        .asset_service
+        .update_asset_transfer(
+            &service_context,
+            UpdateAssetTransfer {
+                id: input.id,
+                status: input.status.to_domain(),
+            },
+        )
+        .map_err(map_update_transfer_error)?;
+
+    Ok(AssetTransferNode { transfer })
+}
+
+pub fn decommission_asset(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: DecommissionAssetInput,
+) -> Result<AssetNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateAsset,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    let asset = service_provider
+        .asset_service
+        .decommission_asset(
+            &service_context,
+            DecommissionAsset {
+                asset_id: input.asset_id,
+                decommissioned_date: input.decommissioned_date,
+                reason: input.reason,
+            },
+        )
+        .map_err(map_decommission_error)?;
+
+    Ok(AssetNode::from_domain(asset))
+}
+
+pub fn insert_asset_disposal(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: InsertAssetDisposalInput,
+) -> Result<AssetDisposalNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateAsset,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    let disposal = service_provider
+        .asset_service
+        .insert_asset_disposal(
+            &service_context,
+            InsertAssetDisposal {
+                id: input.id,
+                asset_id: input.asset_id,
+                disposal_date: input.disposal_date,
+                method: input.method.to_domain(),
+                comment: input.comment,
+            },
+        )
+        .map_err(map_insert_disposal_error)?;
+
+    Ok(AssetDisposalNode { disposal })
+}
+
+impl UpdateAssetTransferStatusInput {
+    pub fn to_domain(self) -> UpdateAssetTransferStatus {
+        match self {
+            UpdateAssetTransferStatusInput::Dispatched => UpdateAssetTransferStatus::Dispatched,
+            UpdateAssetTransferStatusInput::Received => UpdateAssetTransferStatus::Received,
+            UpdateAssetTransferStatusInput::Cancelled => UpdateAssetTransferStatus::Cancelled,
+        }
+    }
+}
+
+fn map_insert_transfer_error(error: InsertAssetTransferError) -> async_graphql::Error {
+    use InsertAssetTransferError as ServiceError;
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        ServiceError::AssetTransferAlreadyExists
+        | ServiceError::AssetDoesNotExist
+        | ServiceError::AssetDoesNotBelongToCurrentStore
+        | ServiceError::AssetIsDecommissioned
+        | ServiceError::AssetHasTransferInProgress
+        | ServiceError::ToStoreDoesNotExist
+        | ServiceError::CannotTransferToCurrentStore => BadUserInput(formatted_error),
+        ServiceError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    graphql_error.extend()
+}
+
+fn map_update_transfer_error(error: UpdateAssetTransferError) -> async_graphql::Error {
+    use StandardGraphqlError::*;
+    use UpdateAssetTransferError as ServiceError;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        ServiceError::AssetTransferDoesNotExist
+        | ServiceError::AssetTransferDoesNotBelongToCurrentStore
+        | ServiceError::CannotChangeStatus { .. }
+        | ServiceError::AssetDoesNotExist
+        | ServiceError::AssetIsNotInSourceStore => BadUserInput(formatted_error),
+        ServiceError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    graphql_error.extend()
+}
+
+fn map_decommission_error(error: DecommissionAssetError) -> async_graphql::Error {
+    use DecommissionAssetError as ServiceError;
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        ServiceError::AssetDoesNotExist
+        | ServiceError::AssetDoesNotBelongToCurrentStore
+        | ServiceError::AssetAlreadyDecommissioned
+        | ServiceError::AssetHasTransferInProgress
+        | ServiceError::ReasonIsEmpty => BadUserInput(formatted_error),
+        ServiceError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    graphql_error.extend()
+}
+
+fn map_insert_disposal_error(error: InsertAssetDisposalError) -> async_graphql::Error {
+    use InsertAssetDisposalError as ServiceError;
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        ServiceError::AssetDisposalAlreadyExists
+        | ServiceError::AssetDoesNotExist
+        | ServiceError::AssetDoesNotBelongToCurrentStore
+        | ServiceError::AssetNotDecommissioned
+        | ServiceError::AssetAlreadyDisposed
+        | ServiceError::DisposalDateBeforeDecommissioning => BadUserInput(formatted_error),
+        ServiceError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    graphql_error.extend()
+}
@@ -0,0 +1,431 @@
+use async_graphql::dataloader::DataLoader;
+use async_graphql::*;
+use chrono::NaiveDateTime;
+use graphql_core::{
+    generic_filters::EqualFilterStringInput,
+    loader::{StoreByIdLoader, UserLoader},
+    map_filter,
+    pagination::PaginationInput,
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::{StoreNode, UserNode};
+use repository::{
+    assets::{
+        asset_disposal_row::AssetDisposalMethod,
+        asset_transfer::{
+            AssetTransfer, AssetTransferFilter, AssetTransferSort, AssetTransferSortField,
+        },
+        asset_transfer_row::AssetTransferStatus,
+    },
+    EqualFilter, PaginationOption,
+};
+use service::{
+    asset::lifecycle::history::{AssetHistoryEvent, AssetHistoryEventType, GetAssetHistoryError},
+    auth::{Resource, ResourceAccessRequest},
+    usize_to_u32, ListResult,
+};
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum AssetTransferStatusType {
+    New,
+    Dispatched,
+    Received,
+    Cancelled,
+}
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum AssetDisposalMethodType {
+    Destroyed,
+    Sold,
+    Donated,
+    Recycled,
+    ReturnedToSupplier,
+    Other,
+}
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum AssetHistoryEventTypeNode {
+    Created,
+    TransferDispatched,
+    TransferReceived,
+    Decommissioned,
+    Disposed,
+}
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq)]
+#[graphql(rename_items = "camelCase")]
+pub enum AssetTransferSortFieldInput {
+    CreatedDatetime,
+    DispatchedDatetime,
+    ReceivedDatetime,
+}
+
+#[derive(InputObject)]
+pub struct AssetTransferSortInput {
+    /// Sort query result by `key`
+    key: AssetTransferSortFieldInput,
+    /// Sort query result is sorted descending or ascending (if not provided the default is
+    /// ascending)
+    desc: Option<bool>,
+}
+
+#[derive(InputObject, Clone)]
+pub struct EqualFilterAssetTransferStatusInput {
+    pub equal_to: Option<AssetTransferStatusType>,
+    pub equal_any: Option<Vec<AssetTransferStatusType>>,
+    pub not_equal_to: Option<AssetTransferStatusType>,
+}
+
+#[derive(InputObject, Clone)]
+pub struct AssetTransferFilterInput {
+    pub id: Option<EqualFilterStringInput>,
+    pub asset_id: Option<EqualFilterStringInput>,
+    pub from_store_id: Option<EqualFilterStringInput>,
+    pub to_store_id: Option<EqualFilterStringInput>,
+    pub status: Option<EqualFilterAssetTransferStatusInput>,
+}
+
+pub struct AssetTransferNode {
+    pub transfer: AssetTransfer,
+}
+
+#[derive(SimpleObject)]
+pub struct AssetTransferConnector {
+    total_count: u32,
+    nodes: Vec<AssetTransferNode>,
+}
+
+#[derive(Union)]
+pub enum AssetTransfersResponse {
+    Response(AssetTransferConnector),
+}
+
+pub struct AssetHistoryEventNode {
+    pub event: AssetHistoryEvent,
+}
+
+#[derive(SimpleObject)]
+pub struct AssetHistoryConnector {
+    total_count: u32,
+    nodes: Vec<AssetHistoryEventNode>,
+}
+
+/// Transfers from or to the store
+pub fn asset_transfers(
+    ctx: &Context<'_>,
+    store_id: String,
+    page: Option<PaginationInput>,
+    filter: Option<AssetTransferFilterInput>,
+    sort: Option<Vec<AssetTransferSortInput>>,
+) -> Result<AssetTransfersResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryAsset,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.clone(), user.user_id)?;
+
+    let filter = filter
+        .map(|filter| filter.to_domain())
+        .unwrap_or_default()
+        .store_id(&store_id);
+
+    let transfers = service_provider
+        .asset_service
+        .get_asset_transfers(
+            &service_context.connection,
+            page.map(PaginationOption::from),
+            Some(filter),
+            // Currently only one sort option is supported, use the first from the list.
+            sort.and_then(|mut sort_list| sort_list.pop())
+                .map(|sort| sort.to_domain()),
+        )
+        .map_err(StandardGraphqlError::from_list_error)?;
+
+    Ok(AssetTransfersResponse::Response(
+        AssetTransferConnector::from_domain(transfers),
+    ))
+}
+
+pub fn asset_history(
+    ctx: &Context<'_>,
+    store_id: String,
+    asset_id: String,
+) -> Result<AssetHistoryConnector> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryAsset,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id, user.user_id)?;
+
+    let events = service_provider
+        .asset_service
+        .get_asset_history(&service_context.connection, &asset_id)
+        .map_err(map_history_error)?;
+
+    Ok(AssetHistoryConnector {
+        total_count: usize_to_u32(events.len()),
+        nodes: events
+            .into_iter()
+            .map(|event| AssetHistoryEventNode { event })
+            .collect(),
+    })
+}
+
+fn map_history_error(error: GetAssetHistoryError) -> async_graphql::Error {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        GetAssetHistoryError::AssetDoesNotExist => BadUserInput(formatted_error),
+        GetAssetHistoryError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    graphql_error.extend()
+}
+
+async fn load_store(ctx: &Context<'_>, store_id: &str) -> Result<Option<StoreNode>> {
+    let loader = ctx.get_loader::<DataLoader<StoreByIdLoader>>();
+    Ok(loader
+        .load_one(store_id.to_string())
+        .await?
+        .map(StoreNode::from_domain))
+}
+
+async fn load_user(ctx: &Context<'_>, user_id: Option<&String>) -> Result<Option<UserNode>> {
+    let Some(user_id) = user_id else {
+        return Ok(None);
+    };
+    let loader = ctx.get_loader::<DataLoader<UserLoader>>();
+    Ok(loader
+        .load_one(user_id.clone())
+        .await?
+        .map(UserNode::from_domain))
+}
+
+#[Object]
+impl AssetTransferNode {
+    pub async fn id(&self) -> &str {
+        &self.transfer.id
+    }
+
+    pub async fn asset_id(&self) -> &str {
+        &self.transfer.asset_id
+    }
+
+    pub async fn from_store_id(&self) -> &str {
+        &self.transfer.from_store_id
+    }
+
+    pub async fn from_store(&self, ctx: &Context<'_>) -> Result<Option<StoreNode>> {
+        load_store(ctx, &self.transfer.from_store_id).await
+    }
+
+    pub async fn to_store_id(&self) -> &str {
+        &self.transfer.to_store_id
+    }
+
+    pub async fn to_store(&self, ctx: &Context<'_>) -> Result<Option<StoreNode>> {
+        load_store(ctx, &self.transfer.to_store_id).await
+    }
+
+    pub async fn status(&self) -> AssetTransferStatusType {
+        AssetTransferStatusType::from_domain(&self.transfer.status)
+    }
+
+    pub async fn comment(&self) -> &Option<String> {
+        &self.transfer.comment
+    }
+
+    pub async fn created_datetime(&self) -> &NaiveDateTime {
+        &self.transfer.created_datetime
+    }
+
+    pub async fn created_by(&self, ctx: &Context<'_>) -> Result<Option<UserNode>> {
+        load_user(ctx, Some(&self.transfer.created_by_user_id)).await
+    }
+
+    pub async fn dispatched_datetime(&self) -> &Option<NaiveDateTime> {
+        &self.transfer.dispatched_datetime
+    }
+
+    pub async fn dispatched_by(&self, ctx: &Context<'_>) -> Result<Option<UserNode>> {
+        load_user(ctx, self.transfer.dispatched_by_user_id.as_ref()).await
+    }
+
+    pub async fn received_datetime(&self) -> &Option<NaiveDateTime> {
+        &self.transfer.received_datetime
+    }
+
+    pub async fn received_by(&self, ctx: &Context<'_>) -> Result<Option<UserNode>> {
+        load_user(ctx, self.transfer.received_by_user_id.as_ref()).await
+    }
+}
+
+#[Object]
+impl AssetHistoryEventNode {
+    pub async fn r#type(&self) -> AssetHistoryEventTypeNode {
+        AssetHistoryEventTypeNode::from_domain(&self.event.r#type)
+    }
+
+    pub async fn datetime(&self) -> &NaiveDateTime {
+        &self.event.datetime
+    }
+
+    /// Store owning the asset after the event
+    pub async fn store_id(&self) -> &Option<String> {
+        &self.event.store_id
+    }
+
+    pub async fn store(&self, ctx: &Context<'_>) -> Result<Option<StoreNode>> {
+        let Some(store_id) = &self.event.store_id else {
+            return Ok(None);
+        };
+        load_store(ctx, store_id).await
+    }
+
+    /// Store owning the asset before a transfer
+    pub async fn from_store_id(&self) -> &Option<String> {
+        &self.event.from_store_id
+    }
+
+    pub async fn from_store(&self, ctx: &Context<'_>) -> Result<Option<StoreNode>> {
+        let Some(store_id) = &self.event.from_store_id else {
+            return Ok(None);
+        };
+        load_store(ctx, store_id).await
+    }
+
+    pub async fn user(&self, ctx: &Context<'_>) -> Result<Option<UserNode>> {
+        load_user(ctx, self.event.user_id.as_ref()).await
+    }
+
+    pub async fn comment(&self) -> &Option<String> {
+        &self.event.comment
+    }
+
+    /// Transfer or disposal of the event
+    pub async fn record_id(&self) -> &Option<String> {
+        &self.event.record_id
+    }
+
+    pub async fn disposal_method(&self) -> Option<AssetDisposalMethodType> {
+        self.event
+            .disposal_method
+            .as_ref()
+            .map(AssetDisposalMethodType::from_domain)
+    }
+}
+
+impl AssetTransferConnector {
+    pub fn from_domain(transfers: ListResult<AssetTransfer>) -> AssetTransferConnector {
+        AssetTransferConnector {
+            total_count: transfers.count,
+            nodes: transfers
+                .rows
+                .into_iter()
+                .map(|transfer| AssetTransferNode { transfer })
+                .collect(),
+        }
+    }
+}
+
+impl AssetTransferStatusType {
+    pub fn from_domain(status: &AssetTransferStatus) -> Self {
+        match status {
+            AssetTransferStatus::New => AssetTransferStatusType::New,
+            AssetTransferStatus::Dispatched => AssetTransferStatusType::Dispatched,
+            AssetTransferStatus::Received => AssetTransferStatusType::Received,
+            AssetTransferStatus::Cancelled => AssetTransferStatusType::Cancelled,
+        }
+    }
+
+    pub fn to_domain(self) -> AssetTransferStatus {
+        match self {
+            AssetTransferStatusType::New => AssetTransferStatus::New,
+            AssetTransferStatusType::Dispatched => AssetTransferStatus::Dispatched,
+            AssetTransferStatusType::Received => AssetTransferStatus::Received,
+            AssetTransferStatusType::Cancelled => AssetTransferStatus::Cancelled,
+        }
+    }
+}
+
+impl AssetDisposalMethodType {
+    pub fn from_domain(method: &AssetDisposalMethod) -> Self {
+        match method {
+            AssetDisposalMethod::Destroyed => AssetDisposalMethodType::Destroyed,
+            AssetDisposalMethod::Sold => AssetDisposalMethodType::Sold,
+            AssetDisposalMethod::Donated => AssetDisposalMethodType::Donated,
+            AssetDisposalMethod::Recycled => AssetDisposalMethodType::Recycled,
+            AssetDisposalMethod::ReturnedToSupplier => AssetDisposalMethodType::ReturnedToSupplier,
+            AssetDisposalMethod::Other => AssetDisposalMethodType::Other,
+        }
+    }
+
+    pub fn to_domain(self) -> AssetDisposalMethod {
+        match self {
+            AssetDisposalMethodType::Destroyed => AssetDisposalMethod::Destroyed,
+            AssetDisposalMethodType::Sold => AssetDisposalMethod::Sold,
+            AssetDisposalMethodType::Donated => AssetDisposalMethod::Donated,
+            AssetDisposalMethodType::Recycled => AssetDisposalMethod::Recycled,
+            AssetDisposalMethodType::ReturnedToSupplier => AssetDisposalMethod::ReturnedToSupplier,
+            AssetDisposalMethodType::Other => AssetDisposalMethod::Other,
+        }
+    }
+}
+
+impl AssetHistoryEventTypeNode {
+    pub fn from_domain(r#type: &AssetHistoryEventType) -> Self {
+        match r#type {
+            AssetHistoryEventType::Created => AssetHistoryEventTypeNode::Created,
+            AssetHistoryEventType::TransferDispatched => {
+                AssetHistoryEventTypeNode::TransferDispatched
+            }
+            AssetHistoryEventType::TransferReceived => AssetHistoryEventTypeNode::TransferReceived,
+            AssetHistoryEventType::Decommissioned => AssetHistoryEventTypeNode::Decommissioned,
+            AssetHistoryEventType::Disposed => AssetHistoryEventTypeNode::Disposed,
+        }
+    }
+}
+
+impl AssetTransferFilterInput {
+    pub fn to_domain(self) -> AssetTransferFilter {
+        AssetTransferFilter {
+            id: self.id.map(EqualFilter::from),
+            asset_id: self.asset_id.map(EqualFilter::from),
+            from_store_id: self.from_store_id.map(EqualFilter::from),
+            to_store_id: self.to_store_id.map(EqualFilter::from),
+            store_id: None,
+            status: self
+                .status
+                .map(|s| map_filter!(s, AssetTransferStatusType::to_domain)),
+        }
+    }
+}
+
+impl AssetTransferSortInput {
+    pub fn to_domain(&self) -> AssetTransferSort {
+        use AssetTransferSortField as to;
+        use AssetTransferSortFieldInput as from;
+        let key = match self.key {
+            from::CreatedDatetime => to::CreatedDatetime,
+            from::DispatchedDatetime => to::DispatchedDatetime,
+            from::ReceivedDatetime => to::ReceivedDatetime,
+        };
+
+        AssetTransferSort {
+            key,
+            desc: self.desc,
+        }
+    }
+}
@@ -0,0 +1,257 @@
+use async_graphql::*;
+use chrono::NaiveDateTime;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use repository::assets::asset_run_hours_row::AssetRunHoursRow;
+use service::{
+    asset::maintenance::{
+        schedule::{UpsertMaintenanceSchedule, UpsertMaintenanceScheduleError},
+        work_order::{
+            AssignWorkOrderError, CompleteWorkOrder, CompleteWorkOrderError,
+            RecordAssetRunHoursError,
+        },
+    },
+    auth::{Resource, ResourceAccessRequest},
+};
+
+use super::{AssetMaintenanceScheduleNode, AssetWorkOrderNode};
+use crate::types::AssetLogStatusInput;
+
+#[derive(InputObject)]
+pub struct UpsertMaintenanceScheduleInput {
+    pub id: String,
+    pub name: String,
+    /// Either asset type or catalogue item is required
+    pub asset_type_id: Option<String>,
+    pub catalogue_item_id: Option<String>,
+    pub interval_days: Option<i32>,
+    pub interval_run_hours: Option<f64>,
+    pub is_active: bool,
+}
+
+#[derive(InputObject)]
+pub struct RecordAssetRunHoursInput {
+    pub asset_id: String,
+    /// Run hour meter reading of the asset
+    pub run_hours: f64,
+}
+
+#[derive(InputObject)]
+pub struct AssignWorkOrderInput {
+    pub id: String,
+    /// Removes the assignment when not set
+    pub user_id: Option<String>,
+}
+
+#[derive(InputObject)]
+pub struct CompleteWorkOrderInput {
+    pub id: String,
+    pub parts_used: Option<String>,
+    pub notes: Option<String>,
+    /// Latest recorded run hours are used when not set
+    pub run_hours: Option<f64>,
+    /// Status of the asset log added on completion, functioning when not set
+    pub status: Option<AssetLogStatusInput>,
+}
+
+pub struct AssetRunHoursNode {
+    pub run_hours: AssetRunHoursRow,
+}
+
+#[Object]
+impl AssetRunHoursNode {
+    pub async fn id(&self) -> &str {
+        &self.run_hours.id
+    }
+
+    pub async fn asset_id(&self) -> &str {
+        &self.run_hours.asset_id
+    }
+
+    pub async fn run_hours(&self) -> f64 {
+        self.run_hours.run_hours
+    }
+
+    pub async fn recorded_datetime(&self) -> &NaiveDateTime {
+        &self.run_hours.recorded_datetime
+    }
+}
+
+pub fn upsert_maintenance_schedule(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: UpsertMaintenanceScheduleInput,
+) -> Result<AssetMaintenanceScheduleNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateAsset,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    let schedule = service_provider
+        .asset_service
+        .upsert_maintenance_schedule(
+            &service_context,
+            UpsertMaintenanceSchedule {
+                id: input.id,
+                name: input.name,
+                asset_type_id: input.asset_type_id,
+                catalogue_item_id: input.catalogue_item_id,
+                interval_days: input.interval_days,
+                interval_run_hours: input.interval_run_hours,
+                is_active: input.is_active,
+            },
+        )
+        .map_err(map_upsert_schedule_error)?;
+
+    Ok(AssetMaintenanceScheduleNode { schedule })
+}
+
+pub fn record_asset_run_hours(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: RecordAssetRunHoursInput,
+) -> Result<AssetRunHoursNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateAsset,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    let run_hours = service_provider
+        .asset_service
+        .record_asset_run_hours(&service_context, input.asset_id, input.run_hours)
+        .map_err(map_record_run_hours_error)?;
+
+    Ok(AssetRunHoursNode { run_hours })
+}
+
+pub fn assign_work_order(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: AssignWorkOrderInput,
+) -> Result<AssetWorkOrderNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateAsset,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    let work_order = service_provider
+        .asset_service
+        .assign_work_order(&service_context, input.id, input.user_id)
+        .map_err(map_assign_error)?;
+
+    Ok(AssetWorkOrderNode { work_order })
+}
+
+pub fn complete_work_order(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: CompleteWorkOrderInput,
+) -> Result<AssetWorkOrderNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateAsset,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    let work_order = service_provider
+        .asset_service
+        .complete_work_order(
+            &service_context,
+            CompleteWorkOrder {
+                id: input.id,
+                parts_used: input.parts_used,
+                notes: input.notes,
+                run_hours: input.run_hours,
+                status: input.status.map(AssetLogStatusInput::to_domain),
+            },
+        )
+        .map_err(map_complete_error)?;
+
+    Ok(AssetWorkOrderNode { work_order })
+}
+
+fn map_upsert_schedule_error(error: UpsertMaintenanceScheduleError) -> async_graphql::Error {
+    use StandardGraphqlError::*;
+    use UpsertMaintenanceScheduleError as ServiceError;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        ServiceError::NameIsEmpty
+        | ServiceError::AssetTypeOrCatalogueItemRequired
+        | ServiceError::AssetTypeDoesNotExist
+        | ServiceError::CatalogueItemDoesNotExist
+        | ServiceError::IntervalRequired
+        | ServiceError::IntervalMustBePositive => BadUserInput(formatted_error),
+        ServiceError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    graphql_error.extend()
+}
+
+fn map_record_run_hours_error(error: RecordAssetRunHoursError) -> async_graphql::Error {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        RecordAssetRunHoursError::AssetDoesNotExist
+        | RecordAssetRunHoursError::RunHoursNegative => BadUserInput(formatted_error),
+        RecordAssetRunHoursError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    graphql_error.extend()
+}
+
+fn map_assign_error(error: AssignWorkOrderError) -> async_graphql::Error {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        AssignWorkOrderError::WorkOrderDoesNotExist
+        | AssignWorkOrderError::WorkOrderDoesNotBelongToCurrentStore
+        | AssignWorkOrderError::WorkOrderNotOpen
+        | AssignWorkOrderError::UserDoesNotExist => BadUserInput(formatted_error),
+        AssignWorkOrderError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    graphql_error.extend()
+}
+
+fn map_complete_error(error: CompleteWorkOrderError) -> async_graphql::Error {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        CompleteWorkOrderError::WorkOrderDoesNotExist
+        | CompleteWorkOrderError::WorkOrderDoesNotBelongToCurrentStore
+        | CompleteWorkOrderError::WorkOrderNotOpen
+        | CompleteWorkOrderError::RunHoursNegative => BadUserInput(formatted_error),
+        CompleteWorkOrderError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    graphql_error.extend()
+}
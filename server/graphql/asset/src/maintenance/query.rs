@@ -0,0 +1,329 @@
+use async_graphql::dataloader::DataLoader;
+use async_graphql::*;
+use chrono::{NaiveDate, NaiveDateTime};
+use graphql_core::{
+    generic_filters::{DateFilterInput, EqualFilterStringInput},
+    loader::UserLoader,
+    map_filter,
+    pagination::PaginationInput,
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::UserNode;
+use repository::{
+    assets::{
+        asset_maintenance_schedule_row::AssetMaintenanceScheduleRow,
+        asset_work_order::{
+            AssetWorkOrder, AssetWorkOrderFilter, AssetWorkOrderSort, AssetWorkOrderSortField,
+        },
+        asset_work_order_row::AssetWorkOrderStatus,
+    },
+    DateFilter, EqualFilter, PaginationOption,
+};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    usize_to_u32, ListResult,
+};
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum AssetWorkOrderStatusType {
+    Open,
+    Completed,
+    Cancelled,
+}
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq)]
+#[graphql(rename_items = "camelCase")]
+pub enum AssetWorkOrderSortFieldInput {
+    DueDate,
+    CreatedDatetime,
+}
+
+#[derive(InputObject)]
+pub struct AssetWorkOrderSortInput {
+    /// Sort query result by `key`
+    key: AssetWorkOrderSortFieldInput,
+    /// Sort query result is sorted descending or ascending (if not provided the default is
+    /// ascending)
+    desc: Option<bool>,
+}
+
+#[derive(InputObject, Clone)]
+pub struct EqualFilterAssetWorkOrderStatusInput {
+    pub equal_to: Option<AssetWorkOrderStatusType>,
+    pub equal_any: Option<Vec<AssetWorkOrderStatusType>>,
+    pub not_equal_to: Option<AssetWorkOrderStatusType>,
+}
+
+#[derive(InputObject, Clone)]
+pub struct AssetWorkOrderFilterInput {
+    pub id: Option<EqualFilterStringInput>,
+    pub asset_id: Option<EqualFilterStringInput>,
+    pub schedule_id: Option<EqualFilterStringInput>,
+    pub status: Option<EqualFilterAssetWorkOrderStatusInput>,
+    pub due_date: Option<DateFilterInput>,
+    pub assigned_user_id: Option<EqualFilterStringInput>,
+}
+
+pub struct AssetMaintenanceScheduleNode {
+    pub schedule: AssetMaintenanceScheduleRow,
+}
+
+#[derive(SimpleObject)]
+pub struct AssetMaintenanceScheduleConnector {
+    total_count: u32,
+    nodes: Vec<AssetMaintenanceScheduleNode>,
+}
+
+pub struct AssetWorkOrderNode {
+    pub work_order: AssetWorkOrder,
+}
+
+#[derive(SimpleObject)]
+pub struct AssetWorkOrderConnector {
+    total_count: u32,
+    nodes: Vec<AssetWorkOrderNode>,
+}
+
+#[derive(Union)]
+pub enum AssetWorkOrdersResponse {
+    Response(AssetWorkOrderConnector),
+}
+
+pub fn maintenance_schedules(
+    ctx: &Context<'_>,
+    store_id: String,
+) -> Result<AssetMaintenanceScheduleConnector> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryAsset,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id, user.user_id)?;
+
+    let schedules = service_provider
+        .asset_service
+        .get_maintenance_schedules(&service_context.connection)
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(AssetMaintenanceScheduleConnector {
+        total_count: usize_to_u32(schedules.len()),
+        nodes: schedules
+            .into_iter()
+            .map(|schedule| AssetMaintenanceScheduleNode { schedule })
+            .collect(),
+    })
+}
+
+pub fn asset_work_orders(
+    ctx: &Context<'_>,
+    store_id: String,
+    page: Option<PaginationInput>,
+    filter: Option<AssetWorkOrderFilterInput>,
+    sort: Option<Vec<AssetWorkOrderSortInput>>,
+) -> Result<AssetWorkOrdersResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryAsset,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.clone(), user.user_id)?;
+
+    let filter = filter
+        .map(|filter| filter.to_domain())
+        .unwrap_or_default()
+        .store_id(EqualFilter::equal_to(&store_id));
+
+    let work_orders = service_provider
+        .asset_service
+        .get_work_orders(
+            &service_context.connection,
+            page.map(PaginationOption::from),
+            Some(filter),
+            // Currently only one sort option is supported, use the first from the list.
+            sort.and_then(|mut sort_list| sort_list.pop())
+                .map(|sort| sort.to_domain()),
+        )
+        .map_err(StandardGraphqlError::from_list_error)?;
+
+    Ok(AssetWorkOrdersResponse::Response(
+        AssetWorkOrderConnector::from_domain(work_orders),
+    ))
+}
+
+#[Object]
+impl AssetMaintenanceScheduleNode {
+    pub async fn id(&self) -> &str {
+        &self.schedule.id
+    }
+
+    pub async fn name(&self) -> &str {
+        &self.schedule.name
+    }
+
+    pub async fn asset_type_id(&self) -> &Option<String> {
+        &self.schedule.asset_type_id
+    }
+
+    pub async fn catalogue_item_id(&self) -> &Option<String> {
+        &self.schedule.catalogue_item_id
+    }
+
+    pub async fn interval_days(&self) -> Option<i32> {
+        self.schedule.interval_days
+    }
+
+    pub async fn interval_run_hours(&self) -> Option<f64> {
+        self.schedule.interval_run_hours
+    }
+
+    pub async fn is_active(&self) -> bool {
+        self.schedule.is_active
+    }
+
+    pub async fn created_datetime(&self) -> &NaiveDateTime {
+        &self.schedule.created_datetime
+    }
+}
+
+#[Object]
+impl AssetWorkOrderNode {
+    pub async fn id(&self) -> &str {
+        &self.work_order.id
+    }
+
+    pub async fn asset_id(&self) -> &str {
+        &self.work_order.asset_id
+    }
+
+    pub async fn schedule_id(&self) -> &str {
+        &self.work_order.schedule_id
+    }
+
+    pub async fn status(&self) -> AssetWorkOrderStatusType {
+        AssetWorkOrderStatusType::from_domain(&self.work_order.status)
+    }
+
+    pub async fn due_date(&self) -> &NaiveDate {
+        &self.work_order.due_date
+    }
+
+    pub async fn assigned_user(&self, ctx: &Context<'_>) -> Result<Option<UserNode>> {
+        let Some(user_id) = &self.work_order.assigned_user_id else {
+            return Ok(None);
+        };
+        let loader = ctx.get_loader::<DataLoader<UserLoader>>();
+        Ok(loader
+            .load_one(user_id.clone())
+            .await?
+            .map(UserNode::from_domain))
+    }
+
+    pub async fn created_datetime(&self) -> &NaiveDateTime {
+        &self.work_order.created_datetime
+    }
+
+    pub async fn completed_datetime(&self) -> &Option<NaiveDateTime> {
+        &self.work_order.completed_datetime
+    }
+
+    pub async fn completed_by(&self, ctx: &Context<'_>) -> Result<Option<UserNode>> {
+        let Some(user_id) = &self.work_order.completed_by_user_id else {
+            return Ok(None);
+        };
+        let loader = ctx.get_loader::<DataLoader<UserLoader>>();
+        Ok(loader
+            .load_one(user_id.clone())
+            .await?
+            .map(UserNode::from_domain))
+    }
+
+    /// Run hour meter reading of the asset when the work order was completed
+    pub async fn run_hours(&self) -> Option<f64> {
+        self.work_order.run_hours
+    }
+
+    pub async fn parts_used(&self) -> &Option<String> {
+        &self.work_order.parts_used
+    }
+
+    pub async fn notes(&self) -> &Option<String> {
+        &self.work_order.notes
+    }
+
+    /// Asset log added on completion
+    pub async fn asset_log_id(&self) -> &Option<String> {
+        &self.work_order.asset_log_id
+    }
+}
+
+impl AssetWorkOrderConnector {
+    pub fn from_domain(work_orders: ListResult<AssetWorkOrder>) -> AssetWorkOrderConnector {
+        AssetWorkOrderConnector {
+            total_count: work_orders.count,
+            nodes: work_orders
+                .rows
+                .into_iter()
+                .map(|work_order| AssetWorkOrderNode { work_order })
+                .collect(),
+        }
+    }
+}
+
+impl AssetWorkOrderStatusType {
+    pub fn from_domain(status: &AssetWorkOrderStatus) -> Self {
+        match status {
+            AssetWorkOrderStatus::Open => AssetWorkOrderStatusType::Open,
+            AssetWorkOrderStatus::Completed => AssetWorkOrderStatusType::Completed,
+            AssetWorkOrderStatus::Cancelled => AssetWorkOrderStatusType::Cancelled,
+        }
+    }
+
+    pub fn to_domain(self) -> AssetWorkOrderStatus {
+        match self {
+            AssetWorkOrderStatusType::Open => AssetWorkOrderStatus::Open,
+            AssetWorkOrderStatusType::Completed => AssetWorkOrderStatus::Completed,
+            AssetWorkOrderStatusType::Cancelled => AssetWorkOrderStatus::Cancelled,
+        }
+    }
+}
+
+impl AssetWorkOrderFilterInput {
+    pub fn to_domain(self) -> AssetWorkOrderFilter {
+        AssetWorkOrderFilter {
+            id: self.id.map(EqualFilter::from),
+            store_id: None,
+            asset_id: self.asset_id.map(EqualFilter::from),
+            schedule_id: self.schedule_id.map(EqualFilter::from),
+            status: self
+                .status
+                .map(|s| map_filter!(s, AssetWorkOrderStatusType::to_domain)),
+            due_date: self.due_date.map(DateFilter::from),
+            assigned_user_id: self.assigned_user_id.map(EqualFilter::from),
+        }
+    }
+}
+
+impl AssetWorkOrderSortInput {
+    pub fn to_domain(&self) -> AssetWorkOrderSort {
+        use AssetWorkOrderSortField as to;
+        use AssetWorkOrderSortFieldInput as from;
+        let key = match self.key {
+            from::DueDate => to::DueDate,
+            from::CreatedDatetime => to::CreatedDatetime,
+        };
+
+        AssetWorkOrderSort {
+            key,
+            desc: self.desc,
+        }
+    }
+}
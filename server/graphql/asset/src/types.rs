@@ -133,6 +133,14 @@ impl AssetNode {
         &self.row().modified_datetime
     }
 
+    pub async fn decommissioned_date(&self) -> &Option<chrono::NaiveDate> {
+        &self.row().decommissioned_date
+    }
+
+    pub async fn decommission_reason(&self) -> &Option<String> {
+        &self.row().decommission_reason
+    }
+
     pub async fn store(&self, ctx: &Context<'_>) -> Result<Option<StoreNode>> {
         let store_id = match &self.row().store_id {
             Some(store_id) => store_id,
@@ -6,7 +6,7 @@ use graphql_core::{
     loader::{AssetCategoryLoader, AssetClassLoader, AssetTypeLoader},
     simple_generic_errors::NodeError,
 };
-use repository::assets::asset_catalogue_item_row::AssetCatalogueItemRow;
+use repository::assets::asset_catalogue_item_row::{AssetCatalogueItemRow, AssetEnergySource};
 use service::ListResult;
 
 use super::asset_category::AssetCategoryNode;
@@ -53,6 +53,18 @@ impl AssetCatalogueItemNode {
         &self.row().model
     }
 
+    pub async fn energy_source(&self) -> Option<AssetEnergySourceType> {
+        self.row()
+            .energy_source
+            .as_ref()
+            .map(AssetEnergySourceType::from_domain)
+    }
+
+    /// Net vaccine storage volume in litres
+    pub async fn storage_capacity(&self) -> Option<f64> {
+        self.row().storage_capacity
+    }
+
     pub async fn asset_class(&self, ctx: &Context<'_>) -> Result<Option<AssetClassNode>> {
         let loader = ctx.get_loader::<DataLoader<AssetClassLoader>>();
         Ok(loader
@@ -78,6 +90,37 @@ impl AssetCatalogueItemNode {
     }
 }
 
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum AssetEnergySourceType {
+    Electricity,
+    Solar,
+    Gas,
+    Kerosene,
+    Passive,
+}
+
+impl AssetEnergySourceType {
+    pub fn from_domain(energy_source: &AssetEnergySource) -> Self {
+        match energy_source {
+            AssetEnergySource::Electricity => AssetEnergySourceType::Electricity,
+            AssetEnergySource::Solar => AssetEnergySourceType::Solar,
+            AssetEnergySource::Gas => AssetEnergySourceType::Gas,
+            AssetEnergySource::Kerosene => AssetEnergySourceType::Kerosene,
+            AssetEnergySource::Passive => AssetEnergySourceType::Passive,
+        }
+    }
+
+    pub fn to_domain(self) -> AssetEnergySource {
+        match self {
+            AssetEnergySourceType::Electricity => AssetEnergySource::Electricity,
+            AssetEnergySourceType::Solar => AssetEnergySource::Solar,
+            AssetEnergySourceType::Gas => AssetEnergySource::Gas,
+            AssetEnergySourceType::Kerosene => AssetEnergySource::Kerosene,
+            AssetEnergySourceType::Passive => AssetEnergySource::Passive,
+        }
+    }
+}
+
 #[derive(Union)]
 pub enum AssetCatalogueItemsResponse {
     Response(AssetCatalogueItemConnector),
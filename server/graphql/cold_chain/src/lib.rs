@@ -1,5 +1,7 @@
 pub mod mutations;
+pub(crate) mod temperature_breach_stock_lines;
 pub(crate) mod temperature_chart;
+pub(crate) mod temperature_kpis;
 pub(crate) mod types;
 
 use async_graphql::*;
@@ -9,7 +11,12 @@ use graphql_core::{
     standard_graphql_error::{validate_auth, StandardGraphqlError},
     ContextExt,
 };
-use mutations::{update_sensor, UpdateSensorInput, UpdateSensorResponse};
+use mutations::{
+    assess_temperature_breach_stock_line, create_sensor_api_key, revoke_sensor_api_key,
+    update_sensor, upsert_temperature_breach_config, AssessTemperatureBreachStockLineInput,
+    AssessTemperatureBreachStockLineResponse, UpdateSensorInput, UpdateSensorResponse,
+    UpsertTemperatureBreachConfigInput,
+};
 use repository::{
     temperature_breach::TemperatureBreachFilter, EqualFilter, PaginationOption, SensorFilter,
     TemperatureBreachSortField,
@@ -17,6 +24,11 @@ use repository::{
 use repository::{temperature_log::TemperatureLogFilter, TemperatureBreachSort};
 use service::auth::{Resource, ResourceAccessRequest};
 use temperature_chart::TemperatureChartResponse;
+use temperature_kpis::TemperatureKpiInput;
+use types::sensor_api_key::{NewSensorApiKeyNode, SensorApiKeyNode};
+use types::temperature_breach_config::TemperatureBreachConfigNode;
+use types::temperature_breach_stock_impact::TemperatureBreachStockLineNode;
+use types::temperature_kpi::TemperatureKpiNode;
 use types::{
     sensor::{SensorConnector, SensorFilterInput, SensorsResponse},
     temperature_breach::{
@@ -175,6 +187,62 @@ impl ColdChainQueries {
         ))
     }
 
+    /// Breach and excursion configs of the store
+    pub async fn temperature_breach_configs(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+    ) -> Result<Vec<TemperatureBreachConfigNode>> {
+        let user = validate_auth(
+            ctx,
+            &ResourceAccessRequest {
+                resource: Resource::QueryTemperatureBreach,
+                store_id: Some(store_id.clone()),
+            },
+        )?;
+
+        let service_provider = ctx.service_provider();
+        let service_context = service_provider.context(store_id, user.user_id)?;
+
+        let configs = service_provider
+            .cold_chain_service
+            .get_temperature_breach_configs(&service_context)
+            .map_err(StandardGraphqlError::from_repository_error)?;
+
+        Ok(configs
+            .into_iter()
+            .map(TemperatureBreachConfigNode::from_domain)
+            .collect())
+    }
+
+    /// Api keys used by network connected loggers to push readings
+    pub async fn sensor_api_keys(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+    ) -> Result<Vec<SensorApiKeyNode>> {
+        let user = validate_auth(
+            ctx,
+            &ResourceAccessRequest {
+                resource: Resource::QuerySensor,
+                store_id: Some(store_id.clone()),
+            },
+        )?;
+
+        let service_provider = ctx.service_provider();
+        let service_context = service_provider.context(store_id, user.user_id)?;
+
+        let api_keys = service_provider
+            .sensor_service
+            .get_sensor_api_keys(&service_context)
+            .map_err(StandardGraphqlError::from_repository_error)?;
+
+        Ok(api_keys
+            .into_iter()
+            .map(SensorApiKeyNode::from_domain)
+            .collect())
+    }
+
     /// Query omSupply "sensor" entries
     pub async fn sensors(
         &self,
@@ -237,6 +305,33 @@ impl ColdChainQueries {
             filter,
         )
     }
+
+    /// Stock lines that were in the breached location during the temperature breach, with their
+    /// assessment
+    pub async fn temperature_breach_stock_lines(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        temperature_breach_id: String,
+    ) -> Result<Vec<TemperatureBreachStockLineNode>> {
+        temperature_breach_stock_lines::temperature_breach_stock_lines(
+            ctx,
+            store_id,
+            temperature_breach_id,
+        )
+    }
+
+    /// Mean kinetic temperature, time in range, breaches and data completeness per sensor and
+    /// location
+    pub async fn temperature_kpis(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: TemperatureKpiInput,
+        filter: Option<TemperatureLogFilterInput>,
+    ) -> Result<Vec<TemperatureKpiNode>> {
+        temperature_kpis::temperature_kpis(ctx, store_id, input, filter)
+    }
 }
 
 #[derive(Default, Clone)]
@@ -261,6 +356,42 @@ impl ColdChainMutations {
     ) -> Result<UpdateSensorResponse> {
         update_sensor(ctx, &store_id, input)
     }
+
+    async fn assess_temperature_breach_stock_line(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: AssessTemperatureBreachStockLineInput,
+    ) -> Result<AssessTemperatureBreachStockLineResponse> {
+        assess_temperature_breach_stock_line(ctx, &store_id, input)
+    }
+
+    async fn upsert_temperature_breach_config(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: UpsertTemperatureBreachConfigInput,
+    ) -> Result<TemperatureBreachConfigNode> {
+        upsert_temperature_breach_config(ctx, &store_id, input)
+    }
+
+    async fn create_sensor_api_key(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        serial: String,
+    ) -> Result<NewSensorApiKeyNode> {
+        create_sensor_api_key(ctx, &store_id, serial)
+    }
+
+    async fn revoke_sensor_api_key(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        id: String,
+    ) -> Result<SensorApiKeyNode> {
+        revoke_sensor_api_key(ctx, &store_id, id)
+    }
 }
 
 #[cfg(test)]
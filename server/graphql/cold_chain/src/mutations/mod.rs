@@ -2,3 +2,9 @@ pub mod temperature_breach;
 pub use temperature_breach::*;
 pub mod sensor;
 pub use sensor::*;
+pub mod sensor_api_key;
+pub use sensor_api_key::*;
+pub mod temperature_breach_stock_impact;
+pub use temperature_breach_stock_impact::*;
+pub mod temperature_breach_config;
+pub use temperature_breach_config::*;
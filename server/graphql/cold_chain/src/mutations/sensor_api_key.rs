@@ -0,0 +1,86 @@
+use async_graphql::*;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    sensor::ingest::{CreateSensorApiKeyError, RevokeSensorApiKeyError},
+};
+
+use crate::types::sensor_api_key::{NewSensorApiKeyNode, SensorApiKeyNode};
+
+/// Key for a network connected logger to push readings of the sensor with the serial, other keys
+/// of the serial are revoked
+pub fn create_sensor_api_key(
+    ctx: &Context<'_>,
+    store_id: &str,
+    serial: String,
+) -> Result<NewSensorApiKeyNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateSensor,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    match service_provider
+        .sensor_service
+        .create_sensor_api_key(&service_context, serial)
+    {
+        Ok(api_key) => Ok(NewSensorApiKeyNode::from_domain(api_key)),
+        Err(error) => {
+            let formatted_error = format!("{:#?}", error);
+            let graphql_error = match error {
+                CreateSensorApiKeyError::SerialIsEmpty => {
+                    StandardGraphqlError::BadUserInput(formatted_error)
+                }
+                CreateSensorApiKeyError::DatabaseError(_) => {
+                    StandardGraphqlError::InternalError(formatted_error)
+                }
+            };
+            Err(graphql_error.extend())
+        }
+    }
+}
+
+pub fn revoke_sensor_api_key(
+    ctx: &Context<'_>,
+    store_id: &str,
+    id: String,
+) -> Result<SensorApiKeyNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateSensor,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    match service_provider
+        .sensor_service
+        .revoke_sensor_api_key(&service_context, id)
+    {
+        Ok(api_key) => Ok(SensorApiKeyNode::from_domain(api_key)),
+        Err(error) => {
+            let formatted_error = format!("{:#?}", error);
+            let graphql_error = match error {
+                RevokeSensorApiKeyError::ApiKeyDoesNotExist
+                | RevokeSensorApiKeyError::ApiKeyDoesNotBelongToCurrentStore => {
+                    StandardGraphqlError::BadUserInput(formatted_error)
+                }
+                RevokeSensorApiKeyError::DatabaseError(_) => {
+                    StandardGraphqlError::InternalError(formatted_error)
+                }
+            };
+            Err(graphql_error.extend())
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use async_graphql::*;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    cold_chain::temperature_breach_config::{
+        UpsertTemperatureBreachConfig as ServiceInput,
+        UpsertTemperatureBreachConfigError as ServiceError,
+    },
+};
+
+use crate::types::temperature_breach_config::{
+    TemperatureBreachConfigNode, TemperatureBreachConfigNodeType,
+};
+
+#[derive(InputObject)]
+pub struct UpsertTemperatureBreachConfigInput {
+    pub id: String,
+    pub r#type: TemperatureBreachConfigNodeType,
+    pub description: String,
+    pub is_active: bool,
+    pub duration_milliseconds: i32,
+    pub minimum_temperature: f64,
+    pub maximum_temperature: f64,
+    /// Excursion configs for a location override the store wide config (without location)
+    pub location_id: Option<String>,
+    /// Excursion settings keep their current value (or default for new configs) when not set
+    pub lookback_days: Option<i32>,
+    pub minimum_excursion_duration_milliseconds: Option<i32>,
+    pub hysteresis_temperature: Option<f64>,
+    pub hysteresis_duration_milliseconds: Option<i32>,
+}
+
+pub fn upsert_temperature_breach_config(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: UpsertTemperatureBreachConfigInput,
+) -> Result<TemperatureBreachConfigNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateTemperatureBreach,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    match service_provider
+        .cold_chain_service
+        .upsert_temperature_breach_config(&service_context, input.to_domain())
+    {
+        Ok(config) => Ok(TemperatureBreachConfigNode::from_domain(config)),
+        Err(error) => map_error(error),
+    }
+}
+
+impl UpsertTemperatureBreachConfigInput {
+    pub fn to_domain(self) -> ServiceInput {
+        let UpsertTemperatureBreachConfigInput {
+            id,
+            r#type,
+            description,
+            is_active,
+            duration_milliseconds,
+            minimum_temperature,
+            maximum_temperature,
+            location_id,
+            lookback_days,
+            minimum_excursion_duration_milliseconds,
+            hysteresis_temperature,
+            hysteresis_duration_milliseconds,
+        } = self;
+
+        ServiceInput {
+            id,
+            r#type: r#type.to_domain(),
+            description,
+            is_active,
+            duration_milliseconds,
+            minimum_temperature,
+            maximum_temperature,
+            location_id,
+            lookback_days,
+            minimum_excursion_duration_milliseconds,
+            hysteresis_temperature,
+            hysteresis_duration_milliseconds,
+        }
+    }
+}
+
+fn map_error(error: ServiceError) -> Result<TemperatureBreachConfigNode> {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        ServiceError::TemperatureBreachConfigDoesNotBelongToCurrentStore
+        | ServiceError::LocationDoesNotExist
+        | ServiceError::MinimumTemperatureAboveMaximum
+        | ServiceError::LookbackDaysMustBePositive
+        | ServiceError::DurationOrHysteresisIsNegative => BadUserInput(formatted_error),
+        ServiceError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    Err(graphql_error.extend())
+}
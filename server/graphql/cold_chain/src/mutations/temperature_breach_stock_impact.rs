@@ -0,0 +1,118 @@
+use crate::types::temperature_breach_stock_impact::{
+    TemperatureBreachStockLineNode, TemperatureBreachStockOutcomeNodeType, VvmStatusNodeType,
+};
+use async_graphql::*;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    cold_chain::temperature_breach_stock_impact::{
+        AssessTemperatureBreachStockLine as ServiceInput,
+        AssessTemperatureBreachStockLineError as ServiceError, TemperatureBreachStockLine,
+    },
+};
+
+#[derive(InputObject)]
+pub struct AssessTemperatureBreachStockLineInput {
+    pub temperature_breach_id: String,
+    pub stock_line_id: String,
+    pub outcome: TemperatureBreachStockOutcomeNodeType,
+    pub vvm_status: Option<VvmStatusNodeType>,
+    /// Number of packs to discard, defaults to all available packs
+    pub number_of_packs: Option<f64>,
+    pub comment: Option<String>,
+}
+
+#[derive(Union)]
+pub enum AssessTemperatureBreachStockLineResponse {
+    Response(TemperatureBreachStockLineNode),
+}
+
+pub fn assess_temperature_breach_stock_line(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: AssessTemperatureBreachStockLineInput,
+) -> Result<AssessTemperatureBreachStockLineResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateTemperatureBreach,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+    // Discarded stock is removed with an inventory reduction
+    if input.outcome == TemperatureBreachStockOutcomeNodeType::Discard {
+        validate_auth(
+            ctx,
+            &ResourceAccessRequest {
+                resource: Resource::MutateInventoryAdjustment,
+                store_id: Some(store_id.to_string()),
+            },
+        )?;
+    }
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    map_response(
+        service_provider
+            .cold_chain_service
+            .assess_temperature_breach_stock_line(&service_context, input.to_domain()),
+    )
+}
+
+fn map_response(
+    from: Result<TemperatureBreachStockLine, ServiceError>,
+) -> Result<AssessTemperatureBreachStockLineResponse> {
+    match from {
+        Ok(stock_line) => Ok(AssessTemperatureBreachStockLineResponse::Response(
+            TemperatureBreachStockLineNode::from_domain(stock_line),
+        )),
+        Err(error) => map_error(error),
+    }
+}
+
+impl AssessTemperatureBreachStockLineInput {
+    pub fn to_domain(self) -> ServiceInput {
+        let AssessTemperatureBreachStockLineInput {
+            temperature_breach_id,
+            stock_line_id,
+            outcome,
+            vvm_status,
+            number_of_packs,
+            comment,
+        } = self;
+
+        ServiceInput {
+            temperature_breach_id,
+            stock_line_id,
+            outcome: outcome.to_domain(),
+            vvm_status: vvm_status.map(VvmStatusNodeType::to_domain),
+            number_of_packs,
+            comment,
+        }
+    }
+}
+
+fn map_error(error: ServiceError) -> Result<AssessTemperatureBreachStockLineResponse> {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        ServiceError::TemperatureBreachDoesNotExist
+        | ServiceError::TemperatureBreachDoesNotBelongToCurrentStore
+        | ServiceError::StockLineDoesNotExist
+        | ServiceError::StockLineNotInBreachedLocation
+        | ServiceError::StockLineAlreadyDiscarded
+        | ServiceError::VvmDiscardPointReached
+        | ServiceError::NumberOfPacksMustBePositive
+        | ServiceError::NumberOfPacksAboveAvailable => BadUserInput(formatted_error),
+        ServiceError::InternalError(_) | ServiceError::DatabaseError(_) => {
+            InternalError(formatted_error)
+        }
+    };
+
+    Err(graphql_error.extend())
+}
@@ -0,0 +1,51 @@
+use async_graphql::*;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    cold_chain::temperature_breach_stock_impact::GetTemperatureBreachStockLinesError as ServiceError,
+};
+
+use crate::types::temperature_breach_stock_impact::TemperatureBreachStockLineNode;
+
+pub fn temperature_breach_stock_lines(
+    ctx: &Context<'_>,
+    store_id: String,
+    temperature_breach_id: String,
+) -> Result<Vec<TemperatureBreachStockLineNode>> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryTemperatureBreach,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id, user.user_id)?;
+
+    let stock_lines = service_provider
+        .cold_chain_service
+        .get_temperature_breach_stock_lines(&service_context, &temperature_breach_id)
+        .map_err(map_error)?;
+
+    Ok(stock_lines
+        .into_iter()
+        .map(TemperatureBreachStockLineNode::from_domain)
+        .collect())
+}
+
+fn map_error(error: ServiceError) -> async_graphql::Error {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        ServiceError::TemperatureBreachDoesNotExist => BadUserInput(formatted_error),
+        ServiceError::TemperatureBreachDoesNotBelongToCurrentStore => BadUserInput(formatted_error),
+        ServiceError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    graphql_error.extend()
+}
@@ -0,0 +1,93 @@
+use async_graphql::*;
+use chrono::{DateTime, Utc};
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use repository::TemperatureLogFilter;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    cold_chain::temperature_kpi::{
+        TemperatureKpiError as ServiceError, TemperatureKpiInput as ServiceInput,
+    },
+};
+
+use crate::types::{
+    temperature_kpi::TemperatureKpiNode, temperature_log::TemperatureLogFilterInput,
+};
+
+#[derive(InputObject)]
+pub struct TemperatureKpiInput {
+    /// Must be before toDatetime
+    pub from_datetime: DateTime<Utc>,
+    /// Must be after fromDatetime
+    pub to_datetime: DateTime<Utc>,
+    /// Defaults to 2°C
+    pub minimum_temperature: Option<f64>,
+    /// Defaults to 8°C
+    pub maximum_temperature: Option<f64>,
+    /// In kJ/mol, defaults to 83.144
+    pub activation_energy: Option<f64>,
+}
+
+pub fn temperature_kpis(
+    ctx: &Context<'_>,
+    store_id: String,
+    input: TemperatureKpiInput,
+    filter: Option<TemperatureLogFilterInput>,
+) -> Result<Vec<TemperatureKpiNode>> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryTemperatureLog,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id, user.user_id)?;
+
+    let kpis = service_provider
+        .cold_chain_service
+        .get_temperature_kpis(&service_context, input.to_domain(filter))
+        .map_err(map_error)?;
+
+    Ok(kpis
+        .into_iter()
+        .map(TemperatureKpiNode::from_domain)
+        .collect())
+}
+
+impl TemperatureKpiInput {
+    fn to_domain(self, filter: Option<TemperatureLogFilterInput>) -> ServiceInput {
+        let TemperatureKpiInput {
+            from_datetime,
+            to_datetime,
+            minimum_temperature,
+            maximum_temperature,
+            activation_energy,
+        } = self;
+
+        ServiceInput {
+            from_datetime: from_datetime.naive_utc(),
+            to_datetime: to_datetime.naive_utc(),
+            filter: filter.map(TemperatureLogFilter::from),
+            minimum_temperature,
+            maximum_temperature,
+            activation_energy,
+        }
+    }
+}
+
+fn map_error(error: ServiceError) -> async_graphql::Error {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        ServiceError::ToDateTimeMustBeAfterFromDatetime
+        | ServiceError::MaximumTemperatureMustBeAboveMinimum => BadUserInput(formatted_error),
+        ServiceError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    graphql_error.extend()
+}
@@ -1,4 +1,8 @@
 pub(crate) mod sensor;
+pub(crate) mod sensor_api_key;
 pub(crate) mod temperature_breach;
+pub(crate) mod temperature_breach_config;
+pub(crate) mod temperature_breach_stock_impact;
+pub(crate) mod temperature_kpi;
 pub(crate) mod temperature_log;
 pub(crate) mod temperature_notification;
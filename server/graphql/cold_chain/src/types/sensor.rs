@@ -69,6 +69,7 @@ pub enum SensorNodeType {
     BlueMaestro,
     Laird,
     Berlinger,
+    Generic,
 }
 
 #[Object]
@@ -237,6 +238,7 @@ impl SensorNodeType {
             from::BlueMaestro => to::BlueMaestro,
             from::Laird => to::Laird,
             from::Berlinger => to::Berlinger,
+            from::Generic => to::Generic,
         }
     }
 
@@ -248,6 +250,7 @@ impl SensorNodeType {
             from::BlueMaestro => to::BlueMaestro,
             from::Laird => to::Laird,
             from::Berlinger => to::Berlinger,
+            from::Generic => to::Generic,
         }
     }
 }
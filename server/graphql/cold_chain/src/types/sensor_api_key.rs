@@ -0,0 +1,60 @@
+use async_graphql::*;
+use chrono::{DateTime, Utc};
+use repository::SensorApiKeyRow;
+use service::sensor::ingest::NewSensorApiKey;
+
+pub struct SensorApiKeyNode {
+    pub sensor_api_key: SensorApiKeyRow,
+}
+
+#[Object]
+impl SensorApiKeyNode {
+    pub async fn id(&self) -> &str {
+        &self.row().id
+    }
+
+    /// Serial of the sensor readings are pushed for
+    pub async fn serial(&self) -> &str {
+        &self.row().serial
+    }
+
+    pub async fn is_active(&self) -> bool {
+        self.row().is_active
+    }
+
+    pub async fn created_datetime(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_naive_utc_and_offset(self.row().created_datetime, Utc)
+    }
+
+    pub async fn last_used_datetime(&self) -> Option<DateTime<Utc>> {
+        self.row()
+            .last_used_datetime
+            .map(|datetime| DateTime::<Utc>::from_naive_utc_and_offset(datetime, Utc))
+    }
+}
+
+impl SensorApiKeyNode {
+    pub fn from_domain(sensor_api_key: SensorApiKeyRow) -> SensorApiKeyNode {
+        SensorApiKeyNode { sensor_api_key }
+    }
+
+    pub fn row(&self) -> &SensorApiKeyRow {
+        &self.sensor_api_key
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct NewSensorApiKeyNode {
+    pub api_key: SensorApiKeyNode,
+    /// Only returned when the key is created
+    pub key: String,
+}
+
+impl NewSensorApiKeyNode {
+    pub fn from_domain(NewSensorApiKey { api_key_row, key }: NewSensorApiKey) -> Self {
+        NewSensorApiKeyNode {
+            api_key: SensorApiKeyNode::from_domain(api_key_row),
+            key,
+        }
+    }
+}
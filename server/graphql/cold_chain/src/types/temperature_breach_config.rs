@@ -0,0 +1,124 @@
+use async_graphql::{dataloader::DataLoader, *};
+use graphql_core::{loader::LocationByIdLoader, ContextExt};
+use graphql_types::types::LocationNode;
+use repository::{TemperatureBreachConfig, TemperatureBreachConfigRow, TemperatureBreachRowType};
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq)]
+pub enum TemperatureBreachConfigNodeType {
+    ColdConsecutive,
+    ColdCumulative,
+    HotConsecutive,
+    HotCumulative,
+    Excursion,
+}
+
+#[derive(PartialEq, Debug)]
+pub struct TemperatureBreachConfigNode {
+    pub temperature_breach_config: TemperatureBreachConfig,
+}
+
+#[Object]
+impl TemperatureBreachConfigNode {
+    pub async fn id(&self) -> &str {
+        &self.row().id
+    }
+
+    pub async fn r#type(&self) -> TemperatureBreachConfigNodeType {
+        TemperatureBreachConfigNodeType::from_domain(&self.row().r#type)
+    }
+
+    pub async fn description(&self) -> &str {
+        &self.row().description
+    }
+
+    pub async fn is_active(&self) -> bool {
+        self.row().is_active
+    }
+
+    pub async fn duration_milliseconds(&self) -> i32 {
+        self.row().duration_milliseconds
+    }
+
+    pub async fn minimum_temperature(&self) -> f64 {
+        self.row().minimum_temperature
+    }
+
+    pub async fn maximum_temperature(&self) -> f64 {
+        self.row().maximum_temperature
+    }
+
+    /// Excursion configs for a location override the store wide config (without location)
+    pub async fn location(&self, ctx: &Context<'_>) -> Result<Option<LocationNode>> {
+        let location_id = match &self.row().location_id {
+            Some(location_id) => location_id,
+            None => return Ok(None),
+        };
+
+        let loader = ctx.get_loader::<DataLoader<LocationByIdLoader>>();
+
+        Ok(loader
+            .load_one(location_id.clone())
+            .await?
+            .map(LocationNode::from_domain))
+    }
+
+    /// Excursion configs only: how far back logs are checked for an ongoing excursion
+    pub async fn lookback_days(&self) -> i32 {
+        self.row().lookback_days
+    }
+
+    /// Excursion configs only: out of range logs have to span at least this duration
+    pub async fn minimum_excursion_duration_milliseconds(&self) -> i32 {
+        self.row().minimum_excursion_duration_milliseconds
+    }
+
+    /// Excursion configs only: temperature has to be back within the range by this many degrees
+    /// for `hysteresisDurationMilliseconds` before an excursion is closed
+    pub async fn hysteresis_temperature(&self) -> f64 {
+        self.row().hysteresis_temperature
+    }
+
+    pub async fn hysteresis_duration_milliseconds(&self) -> i32 {
+        self.row().hysteresis_duration_milliseconds
+    }
+}
+
+impl TemperatureBreachConfigNode {
+    pub fn from_domain(temperature_breach_config: TemperatureBreachConfig) -> Self {
+        TemperatureBreachConfigNode {
+            temperature_breach_config,
+        }
+    }
+
+    pub fn row(&self) -> &TemperatureBreachConfigRow {
+        &self.temperature_breach_config.temperature_breach_config_row
+    }
+}
+
+impl TemperatureBreachConfigNodeType {
+    pub fn from_domain(from: &TemperatureBreachRowType) -> TemperatureBreachConfigNodeType {
+        use TemperatureBreachConfigNodeType as to;
+        use TemperatureBreachRowType as from;
+
+        match from {
+            from::ColdConsecutive => to::ColdConsecutive,
+            from::ColdCumulative => to::ColdCumulative,
+            from::HotConsecutive => to::HotConsecutive,
+            from::HotCumulative => to::HotCumulative,
+            from::Excursion => to::Excursion,
+        }
+    }
+
+    pub fn to_domain(self) -> TemperatureBreachRowType {
+        use TemperatureBreachConfigNodeType as from;
+        use TemperatureBreachRowType as to;
+
+        match self {
+            from::ColdConsecutive => to::ColdConsecutive,
+            from::ColdCumulative => to::ColdCumulative,
+            from::HotConsecutive => to::HotConsecutive,
+            from::HotCumulative => to::HotCumulative,
+            from::Excursion => to::Excursion,
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use async_graphql::*;
+use chrono::{DateTime, Utc};
+use graphql_types::types::StockLineNode;
+use repository::{TemperatureBreachStockImpactRow, TemperatureBreachStockOutcome, VvmStatus};
+use service::cold_chain::temperature_breach_stock_impact::TemperatureBreachStockLine;
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq)]
+pub enum TemperatureBreachStockOutcomeNodeType {
+    Usable,
+    Discard,
+    Quarantine,
+}
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq)]
+pub enum VvmStatusNodeType {
+    Stage1,
+    Stage2,
+    Stage3,
+    Stage4,
+}
+
+pub struct TemperatureBreachStockImpactNode {
+    pub row: TemperatureBreachStockImpactRow,
+}
+
+pub struct TemperatureBreachStockLineNode {
+    pub stock_line: TemperatureBreachStockLine,
+}
+
+#[Object]
+impl TemperatureBreachStockImpactNode {
+    pub async fn id(&self) -> &str {
+        &self.row.id
+    }
+
+    pub async fn temperature_breach_id(&self) -> &str {
+        &self.row.temperature_breach_id
+    }
+
+    pub async fn stock_line_id(&self) -> &str {
+        &self.row.stock_line_id
+    }
+
+    pub async fn outcome(&self) -> TemperatureBreachStockOutcomeNodeType {
+        TemperatureBreachStockOutcomeNodeType::from_domain(&self.row.outcome)
+    }
+
+    pub async fn vvm_status(&self) -> Option<VvmStatusNodeType> {
+        self.row
+            .vvm_status
+            .as_ref()
+            .map(VvmStatusNodeType::from_domain)
+    }
+
+    /// Number of packs discarded
+    pub async fn number_of_packs(&self) -> f64 {
+        self.row.number_of_packs
+    }
+
+    pub async fn inventory_reduction_id(&self) -> Option<String> {
+        self.row.inventory_reduction_id.clone()
+    }
+
+    pub async fn comment(&self) -> Option<String> {
+        self.row.comment.clone()
+    }
+
+    pub async fn user_id(&self) -> &str {
+        &self.row.user_id
+    }
+
+    pub async fn assessed_datetime(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_naive_utc_and_offset(self.row.assessed_datetime, Utc)
+    }
+}
+
+#[Object]
+impl TemperatureBreachStockLineNode {
+    pub async fn stock_line(&self) -> StockLineNode {
+        StockLineNode::from_domain(self.stock_line.stock_line.clone())
+    }
+
+    /// Null until the stock line is assessed
+    pub async fn impact(&self) -> Option<TemperatureBreachStockImpactNode> {
+        self.stock_line
+            .impact
+            .clone()
+            .map(|row| TemperatureBreachStockImpactNode { row })
+    }
+}
+
+impl TemperatureBreachStockLineNode {
+    pub fn from_domain(stock_line: TemperatureBreachStockLine) -> TemperatureBreachStockLineNode {
+        TemperatureBreachStockLineNode { stock_line }
+    }
+}
+
+impl TemperatureBreachStockOutcomeNodeType {
+    pub fn from_domain(
+        from: &TemperatureBreachStockOutcome,
+    ) -> TemperatureBreachStockOutcomeNodeType {
+        use TemperatureBreachStockOutcome as from;
+        use TemperatureBreachStockOutcomeNodeType as to;
+
+        match from {
+            from::Usable => to::Usable,
+            from::Discard => to::Discard,
+            from::Quarantine => to::Quarantine,
+        }
+    }
+
+    pub fn to_domain(self) -> TemperatureBreachStockOutcome {
+        use TemperatureBreachStockOutcome as to;
+        use TemperatureBreachStockOutcomeNodeType as from;
+
+        match self {
+            from::Usable => to::Usable,
+            from::Discard => to::Discard,
+            from::Quarantine => to::Quarantine,
+        }
+    }
+}
+
+impl VvmStatusNodeType {
+    pub fn from_domain(from: &VvmStatus) -> VvmStatusNodeType {
+        use VvmStatus as from;
+        use VvmStatusNodeType as to;
+
+        match from {
+            from::Stage1 => to::Stage1,
+            from::Stage2 => to::Stage2,
+            from::Stage3 => to::Stage3,
+            from::Stage4 => to::Stage4,
+        }
+    }
+
+    pub fn to_domain(self) -> VvmStatus {
+        use VvmStatus as to;
+        use VvmStatusNodeType as from;
+
+        match self {
+            from::Stage1 => to::Stage1,
+            from::Stage2 => to::Stage2,
+            from::Stage3 => to::Stage3,
+            from::Stage4 => to::Stage4,
+        }
+    }
+}
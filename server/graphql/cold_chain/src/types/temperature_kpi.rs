@@ -0,0 +1,119 @@
+use async_graphql::{dataloader::DataLoader, *};
+use graphql_core::{
+    loader::{LocationByIdLoader, SensorByIdLoader},
+    ContextExt,
+};
+use graphql_types::types::LocationNode;
+use service::cold_chain::temperature_kpi::{TemperatureBreachKpi, TemperatureKpi};
+
+use super::{sensor::SensorNode, temperature_breach::TemperatureBreachNodeType};
+
+pub struct TemperatureKpiNode {
+    pub kpi: TemperatureKpi,
+}
+
+pub struct TemperatureBreachKpiNode {
+    pub kpi: TemperatureBreachKpi,
+}
+
+#[Object]
+impl TemperatureKpiNode {
+    pub async fn sensor_id(&self) -> &str {
+        &self.kpi.sensor_id
+    }
+
+    pub async fn sensor(&self, ctx: &Context<'_>) -> Result<Option<SensorNode>> {
+        let loader = ctx.get_loader::<DataLoader<SensorByIdLoader>>();
+
+        Ok(loader
+            .load_one(self.kpi.sensor_id.clone())
+            .await?
+            .map(SensorNode::from_domain))
+    }
+
+    pub async fn location_id(&self) -> Option<String> {
+        self.kpi.location_id.clone()
+    }
+
+    pub async fn location(&self, ctx: &Context<'_>) -> Result<Option<LocationNode>> {
+        let location_id = match &self.kpi.location_id {
+            Some(location_id) => location_id,
+            None => return Ok(None),
+        };
+
+        let loader = ctx.get_loader::<DataLoader<LocationByIdLoader>>();
+
+        Ok(loader
+            .load_one(location_id.clone())
+            .await?
+            .map(LocationNode::from_domain))
+    }
+
+    pub async fn number_of_logs(&self) -> u32 {
+        self.kpi.number_of_logs
+    }
+
+    /// Mean kinetic temperature in °C
+    pub async fn mean_kinetic_temperature(&self) -> f64 {
+        self.kpi.mean_kinetic_temperature
+    }
+
+    pub async fn minimum_temperature(&self) -> f64 {
+        self.kpi.minimum_temperature
+    }
+
+    pub async fn maximum_temperature(&self) -> f64 {
+        self.kpi.maximum_temperature
+    }
+
+    /// Percentage of the logged time with temperature in range
+    pub async fn time_in_range_percentage(&self) -> f64 {
+        self.kpi.time_in_range_percentage
+    }
+
+    /// Breaches by type
+    pub async fn breaches(&self) -> Vec<TemperatureBreachKpiNode> {
+        self.kpi
+            .breaches
+            .iter()
+            .cloned()
+            .map(|kpi| TemperatureBreachKpiNode { kpi })
+            .collect()
+    }
+
+    /// Null when sensor has no log interval
+    pub async fn expected_number_of_intervals(&self) -> Option<u32> {
+        self.kpi.expected_number_of_intervals
+    }
+
+    /// Log intervals without any log, null when sensor has no log interval
+    pub async fn missing_intervals(&self) -> Option<u32> {
+        self.kpi.missing_intervals
+    }
+
+    pub async fn completeness_percentage(&self) -> Option<f64> {
+        self.kpi.completeness_percentage
+    }
+}
+
+#[Object]
+impl TemperatureBreachKpiNode {
+    pub async fn r#type(&self) -> TemperatureBreachNodeType {
+        TemperatureBreachNodeType::from_domain(&self.kpi.r#type)
+    }
+
+    pub async fn count(&self) -> u32 {
+        self.kpi.count
+    }
+
+    /// Total duration of the breaches within the date range
+    pub async fn duration_milliseconds(&self) -> i64 {
+        self.kpi.duration_milliseconds
+    }
+}
+
+impl TemperatureKpiNode {
+    pub fn from_domain(kpi: TemperatureKpi) -> TemperatureKpiNode {
+        TemperatureKpiNode { kpi }
+    }
+}
@@ -146,6 +146,13 @@ pub async fn get_loaders(
         async_std::task::spawn,
     );
 
+    let requisition_line_history_loader = DataLoader::new(
+        RequisitionLineHistoryLoader {
+            service_provider: service_provider.clone(),
+        },
+        async_std::task::spawn,
+    );
+
     let item_stats_for_item_loader = DataLoader::new(
         ItemsStatsForItemLoader {
             service_provider: service_provider.clone(),
@@ -242,6 +249,7 @@ pub async fn get_loaders(
     loaders.insert(item_stats_for_item_loader);
     loaders.insert(stocktake_line_loader);
     loaders.insert(requisition_line_supply_status_loader);
+    loaders.insert(requisition_line_history_loader);
     loaders.insert(requisition_lines_remaining_to_supply_loader);
     loaders.insert(name_row_loader);
     loaders.insert(inventory_adjustment_reason_loader);
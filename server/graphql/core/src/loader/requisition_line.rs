@@ -4,6 +4,7 @@ use actix_web::web::Data;
 use async_graphql::dataloader::*;
 use repository::EqualFilter;
 use repository::{RequisitionLine, RequisitionLineFilter};
+use service::requisition_line::history::RequisitionLineHistory;
 use service::service_provider::ServiceProvider;
 
 use crate::standard_graphql_error::StandardGraphqlError;
@@ -88,3 +89,27 @@ impl Loader<RequisitionAndItemId> for LinkedRequisitionLineLoader {
             .collect())
     }
 }
+
+pub struct RequisitionLineHistoryLoader {
+    pub service_provider: Data<ServiceProvider>,
+}
+
+#[async_trait::async_trait]
+impl Loader<String> for RequisitionLineHistoryLoader {
+    type Value = RequisitionLineHistory;
+    type Error = async_graphql::Error;
+
+    async fn load(
+        &self,
+        requisition_line_ids: &[String],
+    ) -> Result<HashMap<String, Self::Value>, Self::Error> {
+        let service_context = self.service_provider.basic_context()?;
+
+        let result = self
+            .service_provider
+            .requisition_line_service
+            .get_requisition_lines_history(&service_context, requisition_line_ids.to_owned())?;
+
+        Ok(result)
+    }
+}
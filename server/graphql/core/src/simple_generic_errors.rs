@@ -207,6 +207,14 @@ impl CannotEditRequisition {
     }
 }
 
+pub struct MaxItemsReachedForEmergencyOrder;
+#[Object]
+impl MaxItemsReachedForEmergencyOrder {
+    pub async fn description(&self) -> &str {
+        "Maximum number of items reached for emergency order"
+    }
+}
+
 pub struct CannotEditStocktake;
 #[Object]
 impl CannotEditStocktake {
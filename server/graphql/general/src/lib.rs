@@ -26,15 +26,38 @@ use mutations::{
     },
     log::{update_log_level, LogLevelInput, UpsertLogLevelResponse},
     manual_sync::manual_sync,
+    sync_buffer_errors::{
+        ignore_sync_buffer_records, retry_sync_buffer_records, SyncBufferRecordsInput,
+    },
+    sync_bundle::{
+        export_sync_bundle, import_sync_bundle, process_sync_bundle, ImportSyncBundleNode,
+        SyncBundleFileNode,
+    },
+    sync_conflict::resolve_sync_conflict,
+    sync_scope::{update_sync_scope, UpdateSyncScopeInput},
     sync_settings::{update_sync_settings, UpdateSyncSettingsResponse},
     update_user,
+    v6_sync_tables::update_v6_sync_tables,
 };
 use queries::{
     currency::currencies,
     display_settings::{display_settings, DisplaySettingsHash, DisplaySettingsNode},
-    initialisation_status::{initialisation_status, InitialisationStatusNode},
+    initialisation_status::{
+        initialisation_progress, initialisation_status, InitialisationStatusNode,
+        InitialisationTableProgressNode,
+    },
     requisition_line_chart::{ConsumptionOptionsInput, StockEvolutionOptionsInput},
+    sync_buffer_errors::{
+        failed_sync_buffer_rows, sync_buffer_error_summary, SyncBufferErrorSummaryNode,
+        SyncBufferRowConnector,
+    },
+    sync_conflict::{
+        sync_conflicts, SyncConflictConnector, SyncConflictNode, SyncConflictResolutionNode,
+    },
+    sync_health::{sync_health, SiteSyncHealthNode, SyncHealthThresholdsInput},
+    sync_scope::{sync_scope, SyncScopeNode},
     sync_settings::{sync_settings, SyncSettingsNode},
+    v6_sync_tables::{v6_sync_tables, V6SyncTableNode, V6SyncTableStatusNode},
 };
 
 #[derive(Default, Clone)]
@@ -162,6 +185,15 @@ impl GeneralQueries {
         stock_counts(ctx, store_id, timezone_offset, days_till_expired)
     }
 
+    pub async fn asset_maintenance_counts(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        #[graphql(desc = "Timezone offset")] timezone_offset: Option<i32>,
+    ) -> Result<AssetMaintenanceCounts> {
+        asset_maintenance_counts(ctx, store_id, timezone_offset)
+    }
+
     pub async fn requisition_line_chart(
         &self,
         ctx: &Context<'_>,
@@ -205,10 +237,71 @@ impl GeneralQueries {
         latest_sync_status(ctx, true)
     }
 
+    /// Records received per table during initialisation, available without authorisation
+    pub async fn initialisation_progress(
+        &self,
+        ctx: &Context<'_>,
+    ) -> Result<Vec<InitialisationTableProgressNode>> {
+        initialisation_progress(ctx)
+    }
+
     pub async fn number_of_records_in_push_queue(&self, ctx: &Context<'_>) -> Result<u64> {
         number_of_records_in_push_queue(ctx)
     }
 
+    /// Sync records that failed integration, grouped by table and error
+    pub async fn sync_buffer_error_summary(
+        &self,
+        ctx: &Context<'_>,
+    ) -> Result<SyncBufferErrorSummaryNode> {
+        sync_buffer_error_summary(ctx)
+    }
+
+    /// Sync records that failed integration, including raw record data
+    pub async fn failed_sync_buffer_rows(
+        &self,
+        ctx: &Context<'_>,
+        page: Option<PaginationInput>,
+        table_name: Option<String>,
+        error: Option<String>,
+        include_ignored: Option<bool>,
+    ) -> Result<SyncBufferRowConnector> {
+        failed_sync_buffer_rows(ctx, page, table_name, error, include_ignored)
+    }
+
+    /// Concurrent edits of patient and shared-remote records (e.g. barcodes) detected during sync
+    pub async fn sync_conflicts(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        page: Option<PaginationInput>,
+        table_name: Option<String>,
+        is_resolved: Option<bool>,
+    ) -> Result<SyncConflictConnector> {
+        sync_conflicts(ctx, store_id, page, table_name, is_resolved)
+    }
+
+    /// Remote record tables and whether they are synced through open mSupply central server (v6)
+    pub async fn v6_sync_tables(&self, ctx: &Context<'_>) -> Result<Vec<V6SyncTableStatusNode>> {
+        v6_sync_tables(ctx)
+    }
+
+    /// Sync health reported by remote sites to this central server, by default only sites that
+    /// are stale, failing or have a push backlog
+    pub async fn sync_health(
+        &self,
+        ctx: &Context<'_>,
+        thresholds: Option<SyncHealthThresholdsInput>,
+        problems_only: Option<bool>,
+    ) -> Result<Vec<SiteSyncHealthNode>> {
+        sync_health(ctx, thresholds, problems_only)
+    }
+
+    /// Records kept on this site (items, patients and logs), out of scope records are purged
+    pub async fn sync_scope(&self, ctx: &Context<'_>) -> Result<SyncScopeNode> {
+        sync_scope(ctx)
+    }
+
     pub async fn sync_settings(&self, ctx: &Context<'_>) -> Result<Option<SyncSettingsNode>> {
         sync_settings(ctx, true)
     }
@@ -385,6 +478,85 @@ impl GeneralMutations {
         manual_sync(ctx, true)
     }
 
+    /// Export records to a sync bundle file, for sites without network access to central server
+    pub async fn export_sync_bundle(
+        &self,
+        ctx: &Context<'_>,
+        passphrase: String,
+    ) -> Result<SyncBundleFileNode> {
+        export_sync_bundle(ctx, passphrase)
+    }
+
+    /// Import return bundle produced by central server, file content is base64 encoded
+    pub async fn import_sync_bundle(
+        &self,
+        ctx: &Context<'_>,
+        passphrase: String,
+        file_content: String,
+    ) -> Result<ImportSyncBundleNode> {
+        import_sync_bundle(ctx, passphrase, file_content)
+    }
+
+    /// Sync on behalf of remote site using exported sync bundle (central server only),
+    /// file content is base64 encoded
+    pub async fn process_sync_bundle(
+        &self,
+        ctx: &Context<'_>,
+        passphrase: String,
+        file_content: String,
+    ) -> Result<SyncBundleFileNode> {
+        process_sync_bundle(ctx, passphrase, file_content).await
+    }
+
+    /// Queue failed sync records for integration and trigger sync
+    pub async fn retry_sync_buffer_records(
+        &self,
+        ctx: &Context<'_>,
+        input: SyncBufferRecordsInput,
+    ) -> Result<u64> {
+        retry_sync_buffer_records(ctx, input)
+    }
+
+    /// Mark failed sync records as ignored
+    pub async fn ignore_sync_buffer_records(
+        &self,
+        ctx: &Context<'_>,
+        input: SyncBufferRecordsInput,
+    ) -> Result<u64> {
+        ignore_sync_buffer_records(ctx, input)
+    }
+
+    /// Keep local or received version of conflicting record, chosen version is synced to other sites
+    pub async fn resolve_sync_conflict(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        id: String,
+        resolution: SyncConflictResolutionNode,
+    ) -> Result<SyncConflictNode> {
+        resolve_sync_conflict(ctx, store_id, id, resolution)
+    }
+
+    /// Switch remote record tables to open mSupply central server (v6) sync, tables not listed
+    /// are synced through legacy central server
+    pub async fn update_v6_sync_tables(
+        &self,
+        ctx: &Context<'_>,
+        tables: Vec<V6SyncTableNode>,
+    ) -> Result<Vec<V6SyncTableStatusNode>> {
+        update_v6_sync_tables(ctx, tables)
+    }
+
+    /// Records purged by previous scope are integrated again on next sync, and purged according
+    /// to the new scope
+    pub async fn update_sync_scope(
+        &self,
+        ctx: &Context<'_>,
+        input: UpdateSyncScopeInput,
+    ) -> Result<SyncScopeNode> {
+        update_sync_scope(ctx, input)
+    }
+
     pub async fn update_display_settings(
         &self,
         ctx: &Context<'_>,
@@ -447,6 +619,14 @@ impl InitialisationQueries {
     ) -> Result<Option<FullSyncStatusNode>> {
         latest_sync_status(ctx, false)
     }
+
+    /// Records received per table during initialisation, available without authorisation
+    pub async fn initialisation_progress(
+        &self,
+        ctx: &Context<'_>,
+    ) -> Result<Vec<InitialisationTableProgressNode>> {
+        initialisation_progress(ctx)
+    }
 }
 /// Auth is not checked during initialisation stage
 #[derive(Default, Clone)]
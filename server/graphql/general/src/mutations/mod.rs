@@ -5,5 +5,10 @@ pub mod initialise_site;
 pub mod label_printer_settings;
 pub mod log;
 pub mod manual_sync;
+pub mod sync_buffer_errors;
+pub mod sync_bundle;
+pub mod sync_conflict;
+pub mod sync_scope;
 pub mod sync_settings;
 pub mod update_user;
+pub mod v6_sync_tables;
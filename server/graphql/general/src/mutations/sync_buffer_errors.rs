@@ -0,0 +1,58 @@
+use async_graphql::*;
+use graphql_core::{standard_graphql_error::StandardGraphqlError, ContextExt};
+use service::sync::sync_buffer_errors::{
+    ignore_sync_buffer_records as service_ignore_sync_buffer_records,
+    retry_sync_buffer_records as service_retry_sync_buffer_records, SyncBufferRecords,
+    UpdateSyncBufferRecordsError,
+};
+
+use crate::queries::sync_buffer_errors::validate_server_admin;
+
+/// Either list of record ids, or table name (all failed records of the table)
+#[derive(InputObject)]
+pub struct SyncBufferRecordsInput {
+    pub record_ids: Option<Vec<String>>,
+    pub table_name: Option<String>,
+}
+
+/// Queue failed records for integration and trigger sync, returns number of records queued
+pub fn retry_sync_buffer_records(ctx: &Context<'_>, input: SyncBufferRecordsInput) -> Result<u64> {
+    validate_server_admin(ctx)?;
+
+    service_retry_sync_buffer_records(ctx.service_provider(), input.to_domain()?).map_err(map_error)
+}
+
+/// Mark failed records as ignored, returns number of records ignored
+pub fn ignore_sync_buffer_records(ctx: &Context<'_>, input: SyncBufferRecordsInput) -> Result<u64> {
+    validate_server_admin(ctx)?;
+
+    let service_ctx = ctx.service_provider().basic_context()?;
+    service_ignore_sync_buffer_records(&service_ctx.connection, input.to_domain()?)
+        .map_err(map_error)
+}
+
+impl SyncBufferRecordsInput {
+    fn to_domain(self) -> Result<SyncBufferRecords> {
+        match (self.record_ids, self.table_name) {
+            (Some(record_ids), None) => Ok(SyncBufferRecords::RecordIds(record_ids)),
+            (None, Some(table_name)) => Ok(SyncBufferRecords::TableName(table_name)),
+            _ => Err(StandardGraphqlError::BadUserInput(
+                "Either recordIds or tableName should be provided".to_string(),
+            )
+            .extend()),
+        }
+    }
+}
+
+fn map_error(error: UpdateSyncBufferRecordsError) -> Error {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        UpdateSyncBufferRecordsError::RecordNotFound(_)
+        | UpdateSyncBufferRecordsError::RecordHasNoError(_) => BadUserInput(formatted_error),
+        UpdateSyncBufferRecordsError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    graphql_error.extend()
+}
@@ -0,0 +1,133 @@
+use async_graphql::*;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    sync::sync_bundle::{
+        central::process_sync_bundle as service_process_sync_bundle,
+        remote::{
+            export_sync_bundle as service_export_sync_bundle,
+            import_sync_bundle as service_import_sync_bundle, ImportSyncBundleResult,
+        },
+        SyncBundleError,
+    },
+};
+
+#[derive(SimpleObject)]
+pub struct SyncBundleFileNode {
+    /// Static file id, file can be downloaded from /files?id=
+    pub file_id: String,
+}
+
+#[derive(SimpleObject)]
+pub struct ImportSyncBundleNode {
+    pub sequence: u64,
+    pub records_received: u64,
+    /// Not all records fitted in return bundle, export and import another bundle
+    pub has_more_records: bool,
+}
+
+/// Export sync bundle on remote site, to be carried to central server
+pub fn export_sync_bundle(ctx: &Context<'_>, passphrase: String) -> Result<SyncBundleFileNode> {
+    validate_server_admin(ctx)?;
+
+    let file_id = service_export_sync_bundle(
+        &ctx.service_provider(),
+        &ctx.get_settings().server.base_dir,
+        &passphrase,
+    )
+    .map_err(map_error)?;
+
+    Ok(SyncBundleFileNode { file_id })
+}
+
+/// Import return bundle produced by central server on remote site
+pub fn import_sync_bundle(
+    ctx: &Context<'_>,
+    passphrase: String,
+    file_content: String,
+) -> Result<ImportSyncBundleNode> {
+    validate_server_admin(ctx)?;
+
+    let ImportSyncBundleResult {
+        sequence,
+        records_received,
+        has_more_records,
+    } = service_import_sync_bundle(
+        &ctx.service_provider(),
+        &passphrase,
+        &decode_file(file_content)?,
+    )
+    .map_err(map_error)?;
+
+    Ok(ImportSyncBundleNode {
+        sequence,
+        records_received,
+        has_more_records,
+    })
+}
+
+/// Process sync bundle from remote site on central server, returns return bundle
+pub async fn process_sync_bundle(
+    ctx: &Context<'_>,
+    passphrase: String,
+    file_content: String,
+) -> Result<SyncBundleFileNode> {
+    validate_server_admin(ctx)?;
+
+    let file_id = service_process_sync_bundle(
+        &ctx.service_provider(),
+        &ctx.get_settings().server.base_dir,
+        &passphrase,
+        &decode_file(file_content)?,
+    )
+    .await
+    .map_err(map_error)?;
+
+    Ok(SyncBundleFileNode { file_id })
+}
+
+fn validate_server_admin(ctx: &Context<'_>) -> Result<()> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::ServerAdmin,
+            store_id: None,
+        },
+    )?;
+    Ok(())
+}
+
+fn decode_file(file_content: String) -> Result<Vec<u8>> {
+    STANDARD.decode(file_content).map_err(|error| {
+        StandardGraphqlError::BadUserInput(format!("Cannot decode file content: {}", error))
+            .extend()
+    })
+}
+
+fn map_error(error: SyncBundleError) -> Error {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{}", error);
+
+    let graphql_error = match error {
+        SyncBundleError::InvalidFile(_)
+        | SyncBundleError::UnsupportedVersion(_)
+        | SyncBundleError::CannotDecrypt
+        | SyncBundleError::InvalidSignature
+        | SyncBundleError::WrongDirection
+        | SyncBundleError::WrongSite
+        | SyncBundleError::AlreadyImported(_)
+        | SyncBundleError::OutOfOrder { .. }
+        | SyncBundleError::UnknownSequence(_)
+        | SyncBundleError::NotInitialised
+        | SyncBundleError::NotACentralServer => BadUserInput(formatted_error),
+        SyncBundleError::DatabaseError(_)
+        | SyncBundleError::SyncError(_)
+        | SyncBundleError::FileError(_) => InternalError(formatted_error),
+    };
+
+    graphql_error.extend()
+}
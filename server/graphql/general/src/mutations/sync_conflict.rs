@@ -0,0 +1,49 @@
+use async_graphql::*;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    sync::sync_conflict::{
+        resolve_sync_conflict as service_resolve_sync_conflict, ResolveSyncConflictError,
+    },
+};
+
+use crate::queries::sync_conflict::{SyncConflictNode, SyncConflictResolutionNode};
+
+pub fn resolve_sync_conflict(
+    ctx: &Context<'_>,
+    store_id: String,
+    id: String,
+    resolution: SyncConflictResolutionNode,
+) -> Result<SyncConflictNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::SyncConflict,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let service_context = ctx.service_provider().context(store_id, user.user_id)?;
+    let conflict = service_resolve_sync_conflict(&service_context, &id, resolution.to_domain())
+        .map_err(map_error)?;
+
+    Ok(SyncConflictNode { conflict })
+}
+
+fn map_error(error: ResolveSyncConflictError) -> Error {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        ResolveSyncConflictError::ConflictDoesNotExist
+        | ResolveSyncConflictError::ConflictAlreadyResolved
+        | ResolveSyncConflictError::IntegrationError(_) => BadUserInput(formatted_error),
+        ResolveSyncConflictError::ConflictRecordNotInStore => Forbidden(formatted_error),
+        ResolveSyncConflictError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    graphql_error.extend()
+}
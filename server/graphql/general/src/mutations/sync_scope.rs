@@ -0,0 +1,41 @@
+use async_graphql::*;
+use graphql_core::{standard_graphql_error::StandardGraphqlError, ContextExt};
+use service::sync::sync_scope::{set_sync_scope, SyncScope};
+
+use crate::queries::{sync_buffer_errors::validate_server_admin, sync_scope::SyncScopeNode};
+
+#[derive(InputObject)]
+pub struct UpdateSyncScopeInput {
+    pub master_list_items_only: bool,
+    pub visible_patients_only: bool,
+    pub temperature_log_months: Option<u32>,
+    pub activity_log_months: Option<u32>,
+}
+
+pub fn update_sync_scope(ctx: &Context<'_>, input: UpdateSyncScopeInput) -> Result<SyncScopeNode> {
+    validate_server_admin(ctx)?;
+
+    let service_ctx = ctx.service_provider().basic_context()?;
+    let scope = set_sync_scope(&service_ctx.connection, input.to_domain())
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(SyncScopeNode::from_domain(scope))
+}
+
+impl UpdateSyncScopeInput {
+    pub fn to_domain(self) -> SyncScope {
+        let UpdateSyncScopeInput {
+            master_list_items_only,
+            visible_patients_only,
+            temperature_log_months,
+            activity_log_months,
+        } = self;
+
+        SyncScope {
+            master_list_items_only,
+            visible_patients_only,
+            temperature_log_months,
+            activity_log_months,
+        }
+    }
+}
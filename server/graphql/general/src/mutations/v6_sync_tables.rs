@@ -0,0 +1,39 @@
+use async_graphql::*;
+use graphql_core::{standard_graphql_error::StandardGraphqlError, ContextExt};
+use service::sync::v6_sync_tables::{
+    set_v6_sync_tables as service_set_v6_sync_tables, SetV6SyncTablesError,
+};
+
+use crate::queries::{
+    sync_buffer_errors::validate_server_admin,
+    v6_sync_tables::{V6SyncTableNode, V6SyncTableStatusNode},
+};
+
+/// Tables listed are synced through open mSupply central server, all other tables through legacy central
+pub fn update_v6_sync_tables(
+    ctx: &Context<'_>,
+    tables: Vec<V6SyncTableNode>,
+) -> Result<Vec<V6SyncTableStatusNode>> {
+    validate_server_admin(ctx)?;
+
+    let service_ctx = ctx.service_provider().basic_context()?;
+    let tables = service_set_v6_sync_tables(
+        &service_ctx.connection,
+        tables.into_iter().map(V6SyncTableNode::to_domain).collect(),
+    )
+    .map_err(map_error)?;
+
+    Ok(V6SyncTableStatusNode::from_domain(tables))
+}
+
+fn map_error(error: SetV6SyncTablesError) -> Error {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        SetV6SyncTablesError::TableCannotSyncThroughV6(_) => BadUserInput(formatted_error),
+        SetV6SyncTablesError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    graphql_error.extend()
+}
@@ -0,0 +1,47 @@
+use async_graphql::*;
+use chrono::{FixedOffset, Utc};
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+
+use service::auth::{Resource, ResourceAccessRequest};
+use util::timezone::offset_to_timezone;
+pub struct AssetMaintenanceCounts {
+    timezone_offset: FixedOffset,
+    store_id: String,
+}
+
+#[Object]
+impl AssetMaintenanceCounts {
+    /// Open maintenance work orders past their due date
+    async fn overdue(&self, ctx: &Context<'_>) -> Result<i64> {
+        let service_provider = ctx.service_provider();
+        let service_ctx = service_provider.basic_context()?;
+        let service = &service_provider.asset_service;
+        let date = Utc::now().with_timezone(&self.timezone_offset).date_naive();
+        Ok(service.count_overdue_work_orders(&service_ctx, &self.store_id, date)?)
+    }
+}
+
+pub fn asset_maintenance_counts(
+    ctx: &Context<'_>,
+    store_id: String,
+    timezone_offset: Option<i32>,
+) -> Result<AssetMaintenanceCounts> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryAsset,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let timezone_offset = offset_to_timezone(&timezone_offset).ok_or(
+        StandardGraphqlError::BadUserInput("Invalid timezone offset".to_string()),
+    )?;
+    Ok(AssetMaintenanceCounts {
+        timezone_offset,
+        store_id,
+    })
+}
@@ -1,7 +1,7 @@
 use async_graphql::Enum;
 pub use async_graphql::*;
 use graphql_core::ContextExt;
-use service::sync::sync_status::status::InitialisationStatus;
+use service::sync::sync_status::status::{InitialisationStatus, InitialisationTableProgress};
 
 #[derive(SimpleObject)]
 pub struct InitialisationStatusNode {
@@ -19,6 +19,39 @@ pub enum InitialisationStatusType {
     PreInitialisation,
 }
 
+#[derive(SimpleObject)]
+pub struct InitialisationTableProgressNode {
+    table_name: String,
+    received: u64,
+    /// Only known for records sent by omSupply central server
+    expected: Option<u64>,
+}
+
+pub(crate) fn initialisation_progress(
+    ctx: &Context<'_>,
+) -> Result<Vec<InitialisationTableProgressNode>> {
+    let service_provider = ctx.service_provider();
+    let ctx = service_provider.basic_context()?;
+    let progress = service_provider
+        .sync_status_service
+        .get_initialisation_progress(&ctx)?;
+
+    Ok(progress
+        .into_iter()
+        .map(
+            |InitialisationTableProgress {
+                 table_name,
+                 received,
+                 expected,
+             }| InitialisationTableProgressNode {
+                table_name,
+                received,
+                expected,
+            },
+        )
+        .collect())
+}
+
 pub(crate) fn initialisation_status(ctx: &Context<'_>) -> Result<InitialisationStatusNode> {
     let service_provider = ctx.service_provider();
     let ctx = service_provider.basic_context()?;
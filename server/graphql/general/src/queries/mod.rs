@@ -20,6 +20,8 @@ pub mod item;
 pub use self::item::*;
 pub mod stock_counts;
 pub use self::stock_counts::*;
+pub mod asset_maintenance_counts;
+pub use self::asset_maintenance_counts::*;
 pub mod store;
 pub use self::store::*;
 pub mod activity_log;
@@ -30,8 +32,13 @@ pub mod display_settings;
 pub mod initialisation_status;
 pub mod requisition_line_chart;
 pub mod response_requisition_line_stats;
+pub mod sync_buffer_errors;
+pub mod sync_conflict;
+pub mod sync_health;
+pub mod sync_scope;
 pub mod sync_settings;
 pub mod sync_status;
+pub mod v6_sync_tables;
 pub use self::response_requisition_line_stats::*;
 pub mod inventory_adjustment_reason;
 pub use self::inventory_adjustment_reason::*;
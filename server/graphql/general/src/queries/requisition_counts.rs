@@ -29,6 +29,17 @@ impl ResponseRequisitionCounts {
 
         Ok(count)
     }
+
+    async fn new_emergency(&self, ctx: &Context<'_>) -> Result<i64> {
+        let service_provider = ctx.service_provider();
+        let service_ctx = service_provider.context(self.store_id.clone(), "".to_string())?;
+        let service = &service_provider.requisition_count_service;
+        let count = service
+            .new_emergency_response_requisition_count(&service_ctx, &self.store_id)
+            .map_err(StandardGraphqlError::from)?;
+
+        Ok(count)
+    }
 }
 
 #[Object]
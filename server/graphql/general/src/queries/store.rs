@@ -141,6 +141,7 @@ impl StoreFilterInput {
             name: name.map(StringFilter::from),
             name_code: name_code.map(StringFilter::from),
             site_id: site_id.map(EqualFilter::from),
+            name_tag_id: None,
         }
     }
 }
@@ -0,0 +1,158 @@
+use async_graphql::*;
+use chrono::{DateTime, Utc};
+use graphql_core::{
+    pagination::PaginationInput,
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use repository::{PaginationOption, SyncBufferRow};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    sync::sync_buffer_errors::{
+        get_failed_sync_buffer_rows, get_sync_buffer_error_summary, SyncBufferErrorGroup,
+        SyncBufferErrorSummary,
+    },
+};
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq)]
+#[graphql(remote = "repository::SyncBufferAction")]
+pub enum SyncBufferActionNode {
+    Upsert,
+    Delete,
+    Merge,
+}
+
+#[derive(SimpleObject)]
+pub struct SyncBufferErrorGroupNode {
+    pub table_name: String,
+    pub error: String,
+    pub count: u64,
+}
+
+#[derive(SimpleObject)]
+pub struct SyncBufferErrorSummaryNode {
+    pub groups: Vec<SyncBufferErrorGroupNode>,
+    pub total_failed: u64,
+    /// Number of failed records increased in each of the last few syncs
+    pub is_failed_count_growing: bool,
+}
+
+#[derive(SimpleObject)]
+pub struct SyncBufferRowNode {
+    pub record_id: String,
+    pub table_name: String,
+    pub action: SyncBufferActionNode,
+    pub received_datetime: DateTime<Utc>,
+    pub integration_datetime: Option<DateTime<Utc>>,
+    pub integration_error: Option<String>,
+    /// Raw JSON record as received
+    pub data: String,
+    pub is_ignored: bool,
+}
+
+#[derive(SimpleObject)]
+pub struct SyncBufferRowConnector {
+    pub total_count: u32,
+    pub nodes: Vec<SyncBufferRowNode>,
+}
+
+/// Records that failed translation or integration, grouped by table and error
+pub fn sync_buffer_error_summary(ctx: &Context<'_>) -> Result<SyncBufferErrorSummaryNode> {
+    validate_server_admin(ctx)?;
+
+    let ctx = ctx.service_provider().basic_context()?;
+    let SyncBufferErrorSummary {
+        groups,
+        total_failed,
+        is_failed_count_growing,
+    } = get_sync_buffer_error_summary(&ctx.connection)
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(SyncBufferErrorSummaryNode {
+        groups: groups
+            .into_iter()
+            .map(
+                |SyncBufferErrorGroup {
+                     table_name,
+                     error,
+                     count,
+                 }| SyncBufferErrorGroupNode {
+                    table_name,
+                    error,
+                    count,
+                },
+            )
+            .collect(),
+        total_failed,
+        is_failed_count_growing,
+    })
+}
+
+pub fn failed_sync_buffer_rows(
+    ctx: &Context<'_>,
+    page: Option<PaginationInput>,
+    table_name: Option<String>,
+    error: Option<String>,
+    include_ignored: Option<bool>,
+) -> Result<SyncBufferRowConnector> {
+    validate_server_admin(ctx)?;
+
+    let ctx = ctx.service_provider().basic_context()?;
+    let result = get_failed_sync_buffer_rows(
+        &ctx.connection,
+        page.map(PaginationOption::from),
+        table_name,
+        error,
+        include_ignored.unwrap_or(false),
+    )
+    .map_err(StandardGraphqlError::from_list_error)?;
+
+    Ok(SyncBufferRowConnector {
+        total_count: result.count,
+        nodes: result
+            .rows
+            .into_iter()
+            .map(SyncBufferRowNode::from_domain)
+            .collect(),
+    })
+}
+
+pub(crate) fn validate_server_admin(ctx: &Context<'_>) -> Result<()> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::ServerAdmin,
+            store_id: None,
+        },
+    )?;
+    Ok(())
+}
+
+impl SyncBufferRowNode {
+    fn from_domain(
+        SyncBufferRow {
+            record_id,
+            received_datetime,
+            integration_datetime,
+            integration_error,
+            table_name,
+            action,
+            data,
+            source_site_id: _,
+            is_ignored,
+            is_out_of_scope: _,
+        }: SyncBufferRow,
+    ) -> Self {
+        SyncBufferRowNode {
+            record_id,
+            table_name,
+            action: SyncBufferActionNode::from(action),
+            received_datetime: DateTime::<Utc>::from_naive_utc_and_offset(received_datetime, Utc),
+            integration_datetime: integration_datetime
+                .map(|datetime| DateTime::<Utc>::from_naive_utc_and_offset(datetime, Utc)),
+            integration_error,
+            data,
+            is_ignored,
+        }
+    }
+}
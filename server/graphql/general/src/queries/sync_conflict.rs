@@ -0,0 +1,125 @@
+use async_graphql::*;
+use chrono::{DateTime, Utc};
+use graphql_core::{
+    pagination::PaginationInput,
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use repository::{PaginationOption, SyncConflictResolution, SyncConflictRow};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    sync::sync_conflict::get_sync_conflicts,
+};
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq)]
+#[graphql(remote = "repository::SyncConflictResolution")]
+pub enum SyncConflictResolutionNode {
+    Local,
+    Remote,
+}
+
+#[derive(PartialEq, Debug)]
+pub struct SyncConflictNode {
+    pub conflict: SyncConflictRow,
+}
+
+#[derive(SimpleObject)]
+pub struct SyncConflictConnector {
+    pub total_count: u32,
+    pub nodes: Vec<SyncConflictNode>,
+}
+
+#[Object]
+impl SyncConflictNode {
+    pub async fn id(&self) -> &str {
+        &self.conflict.id
+    }
+
+    pub async fn table_name(&self) -> &str {
+        &self.conflict.table_name
+    }
+
+    pub async fn record_id(&self) -> &str {
+        &self.conflict.record_id
+    }
+
+    /// Version edited on this site (sync record JSON)
+    pub async fn local_data(&self) -> &str {
+        &self.conflict.local_data
+    }
+
+    /// Version received through sync (sync record JSON)
+    pub async fn remote_data(&self) -> &str {
+        &self.conflict.remote_data
+    }
+
+    /// Sync record fields edited on both sites
+    pub async fn conflicting_fields(&self) -> Vec<String> {
+        serde_json::from_str(&self.conflict.conflicting_fields).unwrap_or_default()
+    }
+
+    pub async fn source_site_id(&self) -> Option<i32> {
+        self.conflict.source_site_id
+    }
+
+    pub async fn created_datetime(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_naive_utc_and_offset(self.conflict.created_datetime, Utc)
+    }
+
+    pub async fn resolution(&self) -> Option<SyncConflictResolutionNode> {
+        self.conflict
+            .resolution
+            .clone()
+            .map(SyncConflictResolutionNode::from)
+    }
+
+    pub async fn resolved_datetime(&self) -> Option<DateTime<Utc>> {
+        self.conflict
+            .resolved_datetime
+            .map(|datetime| DateTime::<Utc>::from_naive_utc_and_offset(datetime, Utc))
+    }
+
+    pub async fn resolved_by_user_id(&self) -> &Option<String> {
+        &self.conflict.resolved_by_user_id
+    }
+}
+
+pub fn sync_conflicts(
+    ctx: &Context<'_>,
+    store_id: String,
+    page: Option<PaginationInput>,
+    table_name: Option<String>,
+    is_resolved: Option<bool>,
+) -> Result<SyncConflictConnector> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::SyncConflict,
+            store_id: Some(store_id),
+        },
+    )?;
+
+    let ctx = ctx.service_provider().basic_context()?;
+    let result = get_sync_conflicts(
+        &ctx.connection,
+        page.map(PaginationOption::from),
+        table_name,
+        is_resolved,
+    )
+    .map_err(StandardGraphqlError::from_list_error)?;
+
+    Ok(SyncConflictConnector {
+        total_count: result.count,
+        nodes: result
+            .rows
+            .into_iter()
+            .map(|conflict| SyncConflictNode { conflict })
+            .collect(),
+    })
+}
+
+impl SyncConflictResolutionNode {
+    pub fn to_domain(self) -> SyncConflictResolution {
+        self.into()
+    }
+}
@@ -0,0 +1,158 @@
+use async_graphql::*;
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use graphql_core::{standard_graphql_error::StandardGraphqlError, ContextExt};
+use repository::SyncHealthRow;
+use service::{
+    i64_to_u64,
+    sync::{
+        sync_health::{get_sync_health, GetSyncHealthError, SiteSyncHealth, SyncHealthThresholds},
+        sync_status::SyncLogError,
+    },
+};
+
+use super::sync_buffer_errors::validate_server_admin;
+use crate::sync_api_error::SyncErrorNode;
+
+/// Thresholds for sites with problems, defaults are used for missing values
+#[derive(InputObject)]
+pub struct SyncHealthThresholdsInput {
+    /// Site is stale when it didn't sync successfully for this many hours (default 24)
+    pub stale_after_hours: Option<u32>,
+    /// Site is backlogged when more records are waiting to be pushed (default 1000)
+    pub max_push_queue_count: Option<u64>,
+}
+
+pub struct SiteSyncHealthNode {
+    health: SiteSyncHealth,
+}
+
+#[Object]
+impl SiteSyncHealthNode {
+    pub async fn site_id(&self) -> i32 {
+        self.health.site_id
+    }
+
+    /// Reported site name, or names of the site's stores when the site never reported
+    pub async fn site_name(&self) -> &str {
+        &self.health.site_name
+    }
+
+    pub async fn app_version(&self) -> Option<&str> {
+        self.row().map(|row| row.app_version.as_str())
+    }
+
+    /// When the site last reported its sync health, null if the site never reported
+    pub async fn reported_datetime(&self) -> Option<DateTime<Utc>> {
+        self.row().map(|row| to_utc(row.reported_datetime))
+    }
+
+    pub async fn last_sync_datetime(&self) -> Option<DateTime<Utc>> {
+        self.row()
+            .and_then(|row| row.last_sync_datetime)
+            .map(to_utc)
+    }
+
+    pub async fn last_successful_sync_datetime(&self) -> Option<DateTime<Utc>> {
+        self.row()
+            .and_then(|row| row.last_successful_sync_datetime)
+            .map(to_utc)
+    }
+
+    /// Error of the latest sync on the site
+    pub async fn error(&self) -> Option<SyncErrorNode> {
+        let row = self.row()?;
+        if row.error_code.is_none() && row.error_message.is_none() {
+            return None;
+        }
+
+        Some(SyncErrorNode::from_sync_log_error(SyncLogError {
+            message: row.error_message.clone().unwrap_or_default(),
+            code: row.error_code.clone(),
+        }))
+    }
+
+    /// Number of records on the site waiting to be pushed
+    pub async fn push_queue_count(&self) -> Option<u64> {
+        self.row().map(|row| i64_to_u64(row.push_queue_count))
+    }
+
+    /// Database size in bytes
+    pub async fn database_size(&self) -> Option<u64> {
+        self.row().map(|row| i64_to_u64(row.database_size))
+    }
+
+    pub async fn is_stale(&self) -> bool {
+        self.health.is_stale
+    }
+
+    pub async fn is_failing(&self) -> bool {
+        self.health.is_failing
+    }
+
+    pub async fn is_backlogged(&self) -> bool {
+        self.health.is_backlogged
+    }
+}
+
+impl SiteSyncHealthNode {
+    fn row(&self) -> Option<&SyncHealthRow> {
+        self.health.row.as_ref()
+    }
+}
+
+pub fn sync_health(
+    ctx: &Context<'_>,
+    thresholds: Option<SyncHealthThresholdsInput>,
+    problems_only: Option<bool>,
+) -> Result<Vec<SiteSyncHealthNode>> {
+    validate_server_admin(ctx)?;
+
+    let service_ctx = ctx.service_provider().basic_context()?;
+    let result = get_sync_health(
+        &service_ctx,
+        &SyncHealthThresholdsInput::to_domain(thresholds),
+        problems_only.unwrap_or(true),
+    )
+    .map_err(map_error)?;
+
+    Ok(result
+        .into_iter()
+        .map(|health| SiteSyncHealthNode { health })
+        .collect())
+}
+
+impl SyncHealthThresholdsInput {
+    fn to_domain(input: Option<SyncHealthThresholdsInput>) -> SyncHealthThresholds {
+        let default = SyncHealthThresholds::default();
+        let Some(SyncHealthThresholdsInput {
+            stale_after_hours,
+            max_push_queue_count,
+        }) = input
+        else {
+            return default;
+        };
+
+        SyncHealthThresholds {
+            stale_after: stale_after_hours
+                .map(|hours| Duration::hours(hours as i64))
+                .unwrap_or(default.stale_after),
+            max_push_queue_count: max_push_queue_count.unwrap_or(default.max_push_queue_count),
+        }
+    }
+}
+
+fn to_utc(datetime: NaiveDateTime) -> DateTime<Utc> {
+    DateTime::<Utc>::from_naive_utc_and_offset(datetime, Utc)
+}
+
+fn map_error(error: GetSyncHealthError) -> async_graphql::Error {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        GetSyncHealthError::NotACentralServer => BadUserInput(formatted_error),
+        GetSyncHealthError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    graphql_error.extend()
+}
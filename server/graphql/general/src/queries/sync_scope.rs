@@ -0,0 +1,46 @@
+use async_graphql::*;
+use graphql_core::{standard_graphql_error::StandardGraphqlError, ContextExt};
+use service::sync::sync_scope::{get_sync_scope, SyncScope};
+
+use super::sync_buffer_errors::validate_server_admin;
+
+/// Records kept on this site, out of scope records are purged after every sync
+#[derive(SimpleObject)]
+pub struct SyncScopeNode {
+    /// Only keep items on master lists of stores on this site
+    pub master_list_items_only: bool,
+    /// Only keep patients visible in stores on this site
+    pub visible_patients_only: bool,
+    /// Only keep temperature logs of the last number of months
+    pub temperature_log_months: Option<u32>,
+    /// Only keep activity logs of the last number of months
+    pub activity_log_months: Option<u32>,
+}
+
+pub fn sync_scope(ctx: &Context<'_>) -> Result<SyncScopeNode> {
+    validate_server_admin(ctx)?;
+
+    let service_ctx = ctx.service_provider().basic_context()?;
+    let scope = get_sync_scope(&service_ctx.connection)
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(SyncScopeNode::from_domain(scope))
+}
+
+impl SyncScopeNode {
+    pub fn from_domain(
+        SyncScope {
+            master_list_items_only,
+            visible_patients_only,
+            temperature_log_months,
+            activity_log_months,
+        }: SyncScope,
+    ) -> SyncScopeNode {
+        SyncScopeNode {
+            master_list_items_only,
+            visible_patients_only,
+            temperature_log_months,
+            activity_log_months,
+        }
+    }
+}
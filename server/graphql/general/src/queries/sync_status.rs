@@ -68,6 +68,9 @@ pub struct FullSyncStatusNode {
     push: Option<SyncStatusWithProgressNode>,
     push_v6: Option<SyncStatusWithProgressNode>,
     last_successful_sync: Option<SyncStatusNode>,
+    /// Number of sync records failing integration increased in each of the last few syncs,
+    /// see sync buffer errors
+    is_integration_error_count_growing: bool,
 }
 
 pub fn latest_sync_status(
@@ -91,6 +94,9 @@ pub fn latest_sync_status(
         .sync_status_service
         .get_latest_successful_sync_status(&ctx)
         .unwrap_or(None);
+    let is_integration_error_count_growing = service_provider
+        .sync_status_service
+        .is_integration_error_count_growing(&ctx)?;
 
     let FullSyncStatus {
         is_syncing,
@@ -159,6 +165,7 @@ pub fn latest_sync_status(
             total: status.total,
             done: status.done,
         }),
+        is_integration_error_count_growing,
     };
 
     Ok(Some(result))
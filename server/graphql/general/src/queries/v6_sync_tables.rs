@@ -0,0 +1,90 @@
+use async_graphql::*;
+use graphql_core::{standard_graphql_error::StandardGraphqlError, ContextExt};
+use repository::ChangelogTableName;
+use service::sync::v6_sync_tables::get_v6_sync_tables;
+
+use super::sync_buffer_errors::validate_server_admin;
+
+/// Remote record tables that can be synced through open mSupply central server instead of legacy central
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum V6SyncTableNode {
+    Location,
+    LocationMovement,
+    StockLine,
+    Invoice,
+    InvoiceLine,
+    Stocktake,
+    StocktakeLine,
+    Requisition,
+    RequisitionLine,
+}
+
+#[derive(SimpleObject)]
+pub struct V6SyncTableStatusNode {
+    pub table: V6SyncTableNode,
+    /// Table is synced through open mSupply central server (v6), otherwise through legacy central
+    pub is_v6: bool,
+}
+
+pub fn v6_sync_tables(ctx: &Context<'_>) -> Result<Vec<V6SyncTableStatusNode>> {
+    validate_server_admin(ctx)?;
+
+    let service_ctx = ctx.service_provider().basic_context()?;
+    let tables = get_v6_sync_tables(&service_ctx.connection)
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(V6SyncTableStatusNode::from_domain(tables))
+}
+
+impl V6SyncTableStatusNode {
+    pub fn from_domain(v6_sync_tables: Vec<ChangelogTableName>) -> Vec<V6SyncTableStatusNode> {
+        ChangelogTableName::v6_switchable_tables()
+            .into_iter()
+            .filter_map(|table| {
+                Some(V6SyncTableStatusNode {
+                    is_v6: v6_sync_tables.contains(&table),
+                    table: V6SyncTableNode::from_domain(&table)?,
+                })
+            })
+            .collect()
+    }
+}
+
+impl V6SyncTableNode {
+    pub fn from_domain(table: &ChangelogTableName) -> Option<V6SyncTableNode> {
+        use ChangelogTableName as from;
+        use V6SyncTableNode as to;
+
+        let result = match table {
+            from::Location => to::Location,
+            from::LocationMovement => to::LocationMovement,
+            from::StockLine => to::StockLine,
+            from::Invoice => to::Invoice,
+            from::InvoiceLine => to::InvoiceLine,
+            from::Stocktake => to::Stocktake,
+            from::StocktakeLine => to::StocktakeLine,
+            from::Requisition => to::Requisition,
+            from::RequisitionLine => to::RequisitionLine,
+            _ => return None,
+        };
+
+        Some(result)
+    }
+
+    pub fn to_domain(self) -> ChangelogTableName {
+        use ChangelogTableName as to;
+        use V6SyncTableNode as from;
+
+        match self {
+            from::Location => to::Location,
+            from::LocationMovement => to::LocationMovement,
+            from::StockLine => to::StockLine,
+            from::Invoice => to::Invoice,
+            from::InvoiceLine => to::InvoiceLine,
+            from::Stocktake => to::Stocktake,
+            from::StocktakeLine => to::StocktakeLine,
+            from::Requisition => to::Requisition,
+            from::RequisitionLine => to::RequisitionLine,
+        }
+    }
+}
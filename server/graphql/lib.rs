@@ -21,7 +21,9 @@ use graphql_general::{
     InitialisationQueries,
 };
 
-use graphql_asset::{AssetLogMutations, AssetLogQueries, AssetMutations, AssetQueries};
+use graphql_asset::{
+    AssetLogMutations, AssetLogQueries, AssetMutations, AssetQueries, ColdChainEquipmentMutations,
+};
 use graphql_asset_catalogue::AssetCatalogueQueries;
 use graphql_cold_chain::{ColdChainMutations, ColdChainQueries};
 use graphql_inventory_adjustment::InventoryAdjustmentMutations;
@@ -61,6 +63,10 @@ impl CentralServerMutationNode {
     async fn pack_variant(&self) -> PackVariantMutations {
         PackVariantMutations
     }
+
+    async fn cold_chain_equipment(&self) -> ColdChainEquipmentMutations {
+        ColdChainEquipmentMutations
+    }
 }
 
 #[derive(Default, Clone)]
@@ -0,0 +1,194 @@
+use async_graphql::*;
+use chrono::NaiveDate;
+use graphql_core::{
+    generic_filters::{DatetimeFilterInput, EqualFilterStringInput},
+    standard_graphql_error::validate_auth,
+    ContextExt,
+};
+use repository::{DatetimeFilter, EqualFilter};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    requisition::fill_rate::{
+        FillRateFilter, FillRateStats, FillRates, ItemFillRate, PeriodFillRate, SupplierFillRate,
+    },
+};
+
+#[derive(InputObject)]
+pub struct FillRateFilterInput {
+    pub supplier_id: Option<EqualFilterStringInput>,
+    pub item_id: Option<EqualFilterStringInput>,
+    pub sent_datetime: Option<DatetimeFilterInput>,
+}
+
+#[derive(SimpleObject)]
+pub struct FillRateStatsNode {
+    pub requisition_count: i64,
+    pub filled_requisition_count: i64,
+    pub line_count: i64,
+    pub filled_line_count: i64,
+    pub requested_quantity: f64,
+    pub supplied_quantity: f64,
+    /// Ratio of requested lines supplied in full
+    pub line_fill_rate: f64,
+    /// Ratio of requisitions with all requested lines supplied in full
+    pub order_fill_rate: f64,
+    pub quantity_fill_rate: f64,
+    /// Average days from requisition being sent to linked inbound shipment being delivered
+    pub average_lead_time_days: Option<f64>,
+}
+
+#[derive(SimpleObject)]
+pub struct SupplierFillRateNode {
+    pub supplier_id: String,
+    pub supplier_name: String,
+    pub stats: FillRateStatsNode,
+    /// Emergency requisitions, not included in stats
+    pub emergency_stats: FillRateStatsNode,
+}
+
+#[derive(SimpleObject)]
+pub struct ItemFillRateNode {
+    pub item_id: String,
+    pub item_code: String,
+    pub item_name: String,
+    pub stats: FillRateStatsNode,
+    /// Emergency requisitions, not included in stats
+    pub emergency_stats: FillRateStatsNode,
+}
+
+/// Period is the calendar month requisitions were sent in
+#[derive(SimpleObject)]
+pub struct PeriodFillRateNode {
+    pub period_start: NaiveDate,
+    pub period_end: NaiveDate,
+    pub stats: FillRateStatsNode,
+    /// Emergency requisitions, not included in stats
+    pub emergency_stats: FillRateStatsNode,
+}
+
+#[derive(SimpleObject)]
+pub struct FillRatesNode {
+    pub by_supplier: Vec<SupplierFillRateNode>,
+    pub by_item: Vec<ItemFillRateNode>,
+    pub by_period: Vec<PeriodFillRateNode>,
+}
+
+pub fn get_fill_rates(
+    ctx: &Context<'_>,
+    store_id: &str,
+    filter: Option<FillRateFilterInput>,
+) -> Result<FillRatesNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryRequisition,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    let fill_rates = service_provider.requisition_service.get_fill_rates(
+        &service_context,
+        store_id,
+        filter
+            .map(FillRateFilterInput::to_domain)
+            .unwrap_or_default(),
+    )?;
+
+    Ok(FillRatesNode::from_domain(fill_rates))
+}
+
+impl FillRateFilterInput {
+    pub fn to_domain(self) -> FillRateFilter {
+        let FillRateFilterInput {
+            supplier_id,
+            item_id,
+            sent_datetime,
+        } = self;
+
+        FillRateFilter {
+            supplier_id: supplier_id.map(EqualFilter::from),
+            item_id: item_id.map(EqualFilter::from),
+            sent_datetime: sent_datetime.map(DatetimeFilter::from),
+        }
+    }
+}
+
+impl FillRatesNode {
+    pub fn from_domain(
+        FillRates {
+            by_supplier,
+            by_item,
+            by_period,
+        }: FillRates,
+    ) -> FillRatesNode {
+        FillRatesNode {
+            by_supplier: by_supplier
+                .into_iter()
+                .map(
+                    |SupplierFillRate {
+                         supplier,
+                         stats,
+                         emergency_stats,
+                     }| SupplierFillRateNode {
+                        supplier_id: supplier.id,
+                        supplier_name: supplier.name,
+                        stats: FillRateStatsNode::from_domain(stats),
+                        emergency_stats: FillRateStatsNode::from_domain(emergency_stats),
+                    },
+                )
+                .collect(),
+            by_item: by_item
+                .into_iter()
+                .map(
+                    |ItemFillRate {
+                         item,
+                         stats,
+                         emergency_stats,
+                     }| ItemFillRateNode {
+                        item_id: item.id,
+                        item_code: item.code,
+                        item_name: item.name,
+                        stats: FillRateStatsNode::from_domain(stats),
+                        emergency_stats: FillRateStatsNode::from_domain(emergency_stats),
+                    },
+                )
+                .collect(),
+            by_period: by_period
+                .into_iter()
+                .map(
+                    |PeriodFillRate {
+                         period_start,
+                         period_end,
+                         stats,
+                         emergency_stats,
+                     }| PeriodFillRateNode {
+                        period_start,
+                        period_end,
+                        stats: FillRateStatsNode::from_domain(stats),
+                        emergency_stats: FillRateStatsNode::from_domain(emergency_stats),
+                    },
+                )
+                .collect(),
+        }
+    }
+}
+
+impl FillRateStatsNode {
+    pub fn from_domain(stats: FillRateStats) -> FillRateStatsNode {
+        FillRateStatsNode {
+            line_fill_rate: stats.line_fill_rate(),
+            order_fill_rate: stats.order_fill_rate(),
+            quantity_fill_rate: stats.quantity_fill_rate(),
+            average_lead_time_days: stats.average_lead_time_days(),
+            requisition_count: stats.requisition_count,
+            filled_requisition_count: stats.filled_requisition_count,
+            line_count: stats.line_count,
+            filled_line_count: stats.filled_line_count,
+            requested_quantity: stats.requested_quantity,
+            supplied_quantity: stats.supplied_quantity,
+        }
+    }
+}
@@ -1,12 +1,16 @@
+mod fill_rate;
 pub mod mutations;
 mod program_settings;
+mod reporting_rate;
 mod requisition_queries;
 use async_graphql::*;
+use fill_rate::{get_fill_rates, FillRateFilterInput, FillRatesNode};
 use graphql_core::pagination::PaginationInput;
 use graphql_types::types::RequisitionNodeType;
 use program_settings::{get_program_requisition_settings, ProgramRequisitionSettingNode};
+use reporting_rate::{get_reporting_rates, ReportingRateFilterInput, ReportingRateNode};
 
-use self::mutations::{request_requisition, response_requisition};
+use self::mutations::{import_requisition, request_requisition, response_requisition};
 use self::requisition_queries::*;
 #[derive(Default, Clone)]
 pub struct RequisitionQueries;
@@ -50,6 +54,28 @@ impl RequisitionQueries {
     ) -> Result<Vec<ProgramRequisitionSettingNode>> {
         get_program_requisition_settings(ctx, &store_id)
     }
+
+    /// Reporting rate, on time rate and completeness of program request requisitions,
+    /// per program, name tag and period. Only includes stores where the user can query
+    /// requisitions
+    pub async fn reporting_rates(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        filter: Option<ReportingRateFilterInput>,
+    ) -> Result<Vec<ReportingRateNode>> {
+        get_reporting_rates(ctx, &store_id, filter)
+    }
+
+    /// Fill rates and lead times of sent request requisitions, by supplier and by item
+    pub async fn fill_rates(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        filter: Option<FillRateFilterInput>,
+    ) -> Result<FillRatesNode> {
+        get_fill_rates(ctx, &store_id, filter)
+    }
 }
 
 #[derive(Default, Clone)]
@@ -113,6 +139,16 @@ impl RequisitionMutations {
         request_requisition::add_from_master_list::add_from_master_list(ctx, &store_id, input)
     }
 
+    /// Create request or response requisition from a CSV or XLSX file of item codes and quantities
+    async fn import_requisition(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: import_requisition::ImportRequisitionInput,
+    ) -> Result<import_requisition::ImportRequisitionResponse> {
+        import_requisition::import_requisition(ctx, &store_id, input)
+    }
+
     async fn update_response_requisition(
         &self,
         ctx: &Context<'_>,
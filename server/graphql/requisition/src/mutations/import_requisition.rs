@@ -0,0 +1,258 @@
+use async_graphql::*;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::NaiveDate;
+use graphql_core::{
+    simple_generic_errors::{OtherPartyNotACustomer, OtherPartyNotASupplier, OtherPartyNotVisible},
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::{RequisitionNode, RequisitionNodeType};
+use repository::Requisition;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    requisition::import::{
+        ImportRequisition as ServiceInput, ImportRequisitionError as ServiceError,
+        ImportRequisitionFileType, ImportRequisitionRowError, ImportRequisitionRowErrorKind,
+    },
+};
+use util::{constants::expected_delivery_date_offset, date_now_with_offset};
+
+use super::request_requisition::{
+    insert::{self, InsertErrorInterface},
+    insert_program::{self, MaxOrdersReachedForPeriod},
+};
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq)]
+pub enum ImportRequisitionFileTypeInput {
+    Csv,
+    Xlsx,
+}
+
+#[derive(InputObject)]
+pub struct ImportRequisitionInput {
+    pub id: String,
+    pub r#type: RequisitionNodeType,
+    pub other_party_id: String,
+    pub file_type: ImportRequisitionFileTypeInput,
+    /// Base64 encoded file, first row is the header row with "code" and "quantity" columns
+    /// and an optional "comment" column
+    pub file_content: String,
+    pub their_reference: Option<String>,
+    pub comment: Option<String>,
+    /// Defaults to 2 weeks from now
+    pub expected_delivery_date: Option<NaiveDate>,
+    pub max_months_of_stock: f64,
+    pub min_months_of_stock: f64,
+    /// Request requisition is created as a program requisition when program order type and
+    /// period are specified
+    pub program_order_type_id: Option<String>,
+    pub period_id: Option<String>,
+    pub emergency_reason: Option<String>,
+}
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq)]
+pub enum ImportRequisitionRowErrorType {
+    ItemCodeMissing,
+    ItemDoesNotExist,
+    ItemNotInMasterList,
+    ItemNotInProgram,
+    DuplicateItem,
+    InvalidQuantity,
+}
+
+#[derive(SimpleObject)]
+pub struct ImportRequisitionRowErrorNode {
+    pub row_number: u32,
+    pub item_code: String,
+    pub error: ImportRequisitionRowErrorType,
+}
+
+pub struct InvalidImportRows(Vec<ImportRequisitionRowError>);
+#[Object]
+impl InvalidImportRows {
+    pub async fn description(&self) -> &str {
+        "Some rows in the import file are invalid"
+    }
+
+    pub async fn rows(&self) -> Vec<ImportRequisitionRowErrorNode> {
+        self.0
+            .iter()
+            .map(|row| ImportRequisitionRowErrorNode {
+                row_number: row.row_number as u32,
+                item_code: row.item_code.clone(),
+                error: ImportRequisitionRowErrorType::from_domain(&row.error),
+            })
+            .collect()
+    }
+}
+
+#[derive(Interface)]
+#[graphql(field(name = "description", type = "String"))]
+pub enum ImportRequisitionErrorInterface {
+    InvalidImportRows(InvalidImportRows),
+    OtherPartyNotVisible(OtherPartyNotVisible),
+    OtherPartyNotASupplier(OtherPartyNotASupplier),
+    OtherPartyNotACustomer(OtherPartyNotACustomer),
+    MaxOrdersReachedForPeriod(MaxOrdersReachedForPeriod),
+}
+
+#[derive(SimpleObject)]
+pub struct ImportRequisitionError {
+    pub error: ImportRequisitionErrorInterface,
+}
+
+#[derive(Union)]
+pub enum ImportRequisitionResponse {
+    Error(ImportRequisitionError),
+    Response(RequisitionNode),
+}
+
+pub fn import_requisition(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: ImportRequisitionInput,
+) -> Result<ImportRequisitionResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateRequisition,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    map_response(
+        service_provider
+            .requisition_service
+            .import_requisition(&service_context, input.to_domain()?),
+    )
+}
+
+pub fn map_response(from: Result<Requisition, ServiceError>) -> Result<ImportRequisitionResponse> {
+    let result = match from {
+        Ok(requisition) => {
+            ImportRequisitionResponse::Response(RequisitionNode::from_domain(requisition))
+        }
+        Err(error) => ImportRequisitionResponse::Error(ImportRequisitionError {
+            error: map_error(error)?,
+        }),
+    };
+
+    Ok(result)
+}
+
+impl ImportRequisitionInput {
+    pub fn to_domain(self) -> Result<ServiceInput> {
+        let ImportRequisitionInput {
+            id,
+            r#type,
+            other_party_id,
+            file_type,
+            file_content,
+            their_reference,
+            comment,
+            expected_delivery_date,
+            max_months_of_stock,
+            min_months_of_stock,
+            program_order_type_id,
+            period_id,
+            emergency_reason,
+        } = self;
+
+        let file = STANDARD.decode(file_content).map_err(|error| {
+            StandardGraphqlError::BadUserInput(format!("Cannot decode file content: {}", error))
+                .extend()
+        })?;
+
+        Ok(ServiceInput {
+            id,
+            r#type: r#type.to_domain(),
+            other_party_id,
+            file_type: match file_type {
+                ImportRequisitionFileTypeInput::Csv => ImportRequisitionFileType::Csv,
+                ImportRequisitionFileTypeInput::Xlsx => ImportRequisitionFileType::Xlsx,
+            },
+            file,
+            their_reference,
+            comment,
+            expected_delivery_date: expected_delivery_date
+                .or(Some(date_now_with_offset(expected_delivery_date_offset()))),
+            max_months_of_stock,
+            min_months_of_stock,
+            program_order_type_id,
+            period_id,
+            emergency_reason,
+        })
+    }
+}
+
+impl ImportRequisitionRowErrorType {
+    pub fn from_domain(error: &ImportRequisitionRowErrorKind) -> Self {
+        use ImportRequisitionRowErrorKind as Kind;
+        match error {
+            Kind::ItemCodeMissing => Self::ItemCodeMissing,
+            Kind::ItemDoesNotExist => Self::ItemDoesNotExist,
+            Kind::ItemNotInMasterList => Self::ItemNotInMasterList,
+            Kind::ItemNotInProgram => Self::ItemNotInProgram,
+            Kind::DuplicateItem => Self::DuplicateItem,
+            Kind::InvalidQuantity(_) => Self::InvalidQuantity,
+        }
+    }
+}
+
+fn map_error(error: ServiceError) -> Result<ImportRequisitionErrorInterface> {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        // Structured Errors
+        ServiceError::RowErrors(rows) => {
+            return Ok(ImportRequisitionErrorInterface::InvalidImportRows(
+                InvalidImportRows(rows),
+            ))
+        }
+        ServiceError::OtherPartyNotVisible => {
+            return Ok(ImportRequisitionErrorInterface::OtherPartyNotVisible(
+                OtherPartyNotVisible,
+            ))
+        }
+        ServiceError::OtherPartyNotACustomer => {
+            return Ok(ImportRequisitionErrorInterface::OtherPartyNotACustomer(
+                OtherPartyNotACustomer,
+            ))
+        }
+        ServiceError::InsertRequestRequisition(error) => {
+            return insert::map_error(error).map(|error| match error {
+                InsertErrorInterface::OtherPartyNotVisible(error) => {
+                    ImportRequisitionErrorInterface::OtherPartyNotVisible(error)
+                }
+                InsertErrorInterface::OtherPartyNotASupplier(error) => {
+                    ImportRequisitionErrorInterface::OtherPartyNotASupplier(error)
+                }
+            })
+        }
+        ServiceError::InsertProgramRequestRequisition(error) => {
+            return insert_program::map_error(error).map(|error| match error {
+                insert_program::InsertErrorInterface::MaxOrdersReachedForPeriod(error) => {
+                    ImportRequisitionErrorInterface::MaxOrdersReachedForPeriod(error)
+                }
+            })
+        }
+        // Standard Graphql Errors
+        ServiceError::CannotParseFile(_) => BadUserInput(formatted_error),
+        ServiceError::ItemCodeColumnMissing => BadUserInput(formatted_error),
+        ServiceError::QuantityColumnMissing => BadUserInput(formatted_error),
+        ServiceError::NoRowsToImport => BadUserInput(formatted_error),
+        ServiceError::MaxItemsReachedForEmergencyOrder => BadUserInput(formatted_error),
+        ServiceError::RequisitionAlreadyExists => BadUserInput(formatted_error),
+        ServiceError::OtherPartyDoesNotExist => BadUserInput(formatted_error),
+        ServiceError::ProgramNotSupportedForResponseRequisition => BadUserInput(formatted_error),
+
+        ServiceError::NewlyCreatedRequisitionDoesNotExist => InternalError(formatted_error),
+        ServiceError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    Err(graphql_error.extend())
+}
@@ -1,3 +1,4 @@
 pub mod errors;
+pub mod import_requisition;
 pub mod request_requisition;
 pub mod response_requisition;
@@ -1,7 +1,8 @@
 use async_graphql::*;
 use graphql_core::{
     simple_generic_errors::{
-        CannotEditRequisition, MasterListNotFoundForThisStore, RecordNotFound,
+        CannotEditRequisition, MasterListNotFoundForThisStore, MaxItemsReachedForEmergencyOrder,
+        RecordNotFound,
     },
     standard_graphql_error::validate_auth,
     standard_graphql_error::StandardGraphqlError,
@@ -28,6 +29,7 @@ pub enum DeleteErrorInterface {
     RecordNotFound(RecordNotFound),
     MasterListNotFoundForThisStore(MasterListNotFoundForThisStore),
     CannotEditRequisition(CannotEditRequisition),
+    MaxItemsReachedForEmergencyOrder(MaxItemsReachedForEmergencyOrder),
 }
 
 #[derive(SimpleObject)]
@@ -106,6 +108,11 @@ fn map_error(error: ServiceError) -> Result<DeleteErrorInterface> {
                 MasterListNotFoundForThisStore {},
             ))
         }
+        ServiceError::MaxItemsReachedForEmergencyOrder => {
+            return Ok(DeleteErrorInterface::MaxItemsReachedForEmergencyOrder(
+                MaxItemsReachedForEmergencyOrder {},
+            ))
+        }
         // Standard Graphql Errors
         ServiceError::NotThisStoreRequisition => BadUserInput(formatted_error),
         ServiceError::NotARequestRequisition => BadUserInput(formatted_error),
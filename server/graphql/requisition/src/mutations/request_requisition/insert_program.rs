@@ -26,6 +26,8 @@ pub struct InsertProgramRequestRequisitionInput {
     pub period_id: String,
     /// Defaults to 2 weeks from now
     pub expected_delivery_date: Option<NaiveDate>,
+    /// Required for emergency order types
+    pub emergency_reason: Option<String>,
 }
 
 #[derive(Interface)]
@@ -97,6 +99,7 @@ pub fn map_error(error: ServiceError) -> Result<InsertErrorInterface> {
         ServiceError::RequisitionAlreadyExists => BadUserInput(formatted_error),
         ServiceError::SupplierNotValid => BadUserInput(formatted_error),
         ServiceError::ProgramOrderTypeDoesNotExist => BadUserInput(formatted_error),
+        ServiceError::EmergencyReasonRequired => BadUserInput(formatted_error),
 
         ServiceError::NewlyCreatedRequisitionDoesNotExist => InternalError(formatted_error),
         ServiceError::DatabaseError(_) => InternalError(formatted_error),
@@ -116,6 +119,7 @@ impl InsertProgramRequestRequisitionInput {
             expected_delivery_date,
             program_order_type_id,
             period_id,
+            emergency_reason,
         } = self;
 
         InsertProgramRequestRequisition {
@@ -128,6 +132,7 @@ impl InsertProgramRequestRequisitionInput {
                 .or(Some(date_now_with_offset(expected_delivery_date_offset()))),
             program_order_type_id,
             period_id,
+            emergency_reason,
         }
     }
 }
@@ -262,6 +267,7 @@ mod test {
                     expected_delivery_date: Some(NaiveDate::from_ymd_opt(2022, 1, 3).unwrap()),
                     program_order_type_id: "program_order_type_id".to_string(),
                     period_id: "period_id".to_string(),
+                    emergency_reason: Some("emergency reason input".to_string()),
                 }
             );
             Ok(inline_init(|r: &mut Requisition| {
@@ -279,6 +285,7 @@ mod test {
             "expectedDeliveryDate": "2022-01-03",
             "programOrderTypeId": "program_order_type_id",
             "periodId": "period_id",
+            "emergencyReason": "emergency reason input",
           },
           "storeId": "store_a"
         });
@@ -12,6 +12,8 @@ pub struct ProgramRequisitionOrderTypeNode {
     pub name: String,
     pub id: String,
     pub available_periods: Vec<PeriodNode>,
+    pub is_emergency: bool,
+    pub max_items_in_emergency_order: i32,
 }
 
 #[derive(SimpleObject)]
@@ -68,6 +70,8 @@ pub fn get_program_requisition_settings(
                          }: OrderType| ProgramRequisitionOrderTypeNode {
                             name: order_type.name,
                             id: order_type.id,
+                            is_emergency: order_type.is_emergency,
+                            max_items_in_emergency_order: order_type.max_items_in_emergency_order,
                             available_periods: available_periods
                                 .into_iter()
                                 .map(PeriodNode::from_domain)
@@ -0,0 +1,130 @@
+use async_graphql::*;
+use chrono::{DateTime, NaiveDate, Utc};
+use graphql_core::{standard_graphql_error::validate_auth, ContextExt};
+use graphql_types::types::{PeriodNode, StoreNode};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    requisition::reporting_rate::{ReportingRate, ReportingRateInput, StoreReportingStatus},
+};
+
+#[derive(InputObject)]
+pub struct ReportingRateFilterInput {
+    pub program_id: Option<String>,
+    pub name_tag_id: Option<String>,
+    /// Defaults to all periods that have started
+    pub period_ids: Option<Vec<String>>,
+    /// Number of days after period end date that a requisition can be sent and still be on time
+    pub deadline_days: Option<u32>,
+}
+
+#[derive(SimpleObject)]
+pub struct StoreReportingStatusNode {
+    pub store: StoreNode,
+    pub has_requisition: bool,
+    pub sent_datetime: Option<DateTime<Utc>>,
+    pub is_on_time: bool,
+    pub completeness: f64,
+}
+
+#[derive(SimpleObject)]
+pub struct ReportingRateNode {
+    pub program_id: String,
+    pub program_name: String,
+    pub name_tag_id: String,
+    pub name_tag_name: String,
+    pub period: PeriodNode,
+    pub deadline: NaiveDate,
+    pub expected_count: i64,
+    pub submitted_count: i64,
+    pub on_time_count: i64,
+    pub reporting_rate: f64,
+    pub on_time_rate: f64,
+    pub completeness: f64,
+    pub stores: Vec<StoreReportingStatusNode>,
+}
+
+pub fn get_reporting_rates(
+    ctx: &Context<'_>,
+    store_id: &str,
+    filter: Option<ReportingRateFilterInput>,
+) -> Result<Vec<ReportingRateNode>> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryRequisition,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    let reporting_rates = service_provider.requisition_service.get_reporting_rates(
+        &service_context,
+        filter
+            .map(ReportingRateFilterInput::to_domain)
+            .unwrap_or_default(),
+    )?;
+
+    Ok(reporting_rates
+        .into_iter()
+        .map(ReportingRateNode::from_domain)
+        .collect())
+}
+
+impl ReportingRateFilterInput {
+    pub fn to_domain(self) -> ReportingRateInput {
+        let ReportingRateFilterInput {
+            program_id,
+            name_tag_id,
+            period_ids,
+            deadline_days,
+        } = self;
+
+        ReportingRateInput {
+            program_id,
+            name_tag_id,
+            period_ids,
+            deadline_days,
+        }
+    }
+}
+
+impl ReportingRateNode {
+    pub fn from_domain(reporting_rate: ReportingRate) -> ReportingRateNode {
+        ReportingRateNode {
+            expected_count: reporting_rate.expected_count(),
+            submitted_count: reporting_rate.submitted_count(),
+            on_time_count: reporting_rate.on_time_count(),
+            reporting_rate: reporting_rate.reporting_rate(),
+            on_time_rate: reporting_rate.on_time_rate(),
+            completeness: reporting_rate.completeness(),
+            program_id: reporting_rate.program_id,
+            program_name: reporting_rate.program_name,
+            name_tag_id: reporting_rate.name_tag.id,
+            name_tag_name: reporting_rate.name_tag.name,
+            period: PeriodNode::from_domain(reporting_rate.period),
+            deadline: reporting_rate.deadline,
+            stores: reporting_rate
+                .stores
+                .into_iter()
+                .map(
+                    |StoreReportingStatus {
+                         store,
+                         has_requisition,
+                         sent_datetime,
+                         is_on_time,
+                         completeness,
+                     }| StoreReportingStatusNode {
+                        store: StoreNode::from_domain(store),
+                        has_requisition,
+                        sent_datetime: sent_datetime
+                            .map(|v| DateTime::<Utc>::from_naive_utc_and_offset(v, Utc)),
+                        is_on_time,
+                        completeness,
+                    },
+                )
+                .collect(),
+        }
+    }
+}
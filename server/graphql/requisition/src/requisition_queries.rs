@@ -75,6 +75,7 @@ pub struct RequisitionFilterInput {
     pub their_reference: Option<StringFilterInput>,
     pub comment: Option<StringFilterInput>,
     pub order_type: Option<EqualFilterStringInput>,
+    pub is_emergency: Option<bool>,
 }
 
 #[derive(Union)]
@@ -239,6 +240,9 @@ impl RequisitionFilterInput {
             linked_requisition_id: None,
             store_id: None,
             order_type: self.order_type.map(EqualFilter::from),
+            is_emergency: self.is_emergency,
+            program_id: None,
+            period_id: None,
         }
     }
 }
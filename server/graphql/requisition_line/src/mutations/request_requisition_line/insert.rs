@@ -1,6 +1,8 @@
 use async_graphql::*;
 use graphql_core::{
-    simple_generic_errors::{CannotEditRequisition, ForeignKey, ForeignKeyError},
+    simple_generic_errors::{
+        CannotEditRequisition, ForeignKey, ForeignKeyError, MaxItemsReachedForEmergencyOrder,
+    },
     standard_graphql_error::{validate_auth, StandardGraphqlError},
     ContextExt,
 };
@@ -31,6 +33,7 @@ pub enum InsertErrorInterface {
     RequisitionDoesNotExist(ForeignKeyError),
     CannotEditRequisition(CannotEditRequisition),
     RequisitionLineWithItemIdExists(RequisitionLineWithItemIdExists),
+    MaxItemsReachedForEmergencyOrder(MaxItemsReachedForEmergencyOrder),
 }
 
 #[derive(SimpleObject)]
@@ -118,12 +121,18 @@ fn map_error(error: ServiceError) -> Result<InsertErrorInterface> {
                 CannotEditRequisition {},
             ))
         }
+        ServiceError::MaxItemsReachedForEmergencyOrder => {
+            return Ok(InsertErrorInterface::MaxItemsReachedForEmergencyOrder(
+                MaxItemsReachedForEmergencyOrder {},
+            ))
+        }
         // Standard Graphql Errors
         ServiceError::RequisitionLineAlreadyExists => BadUserInput(formatted_error),
         ServiceError::NotThisStoreRequisition => BadUserInput(formatted_error),
         ServiceError::NotARequestRequisition => BadUserInput(formatted_error),
         ServiceError::ItemDoesNotExist => BadUserInput(formatted_error),
         ServiceError::CannotAddItemToProgramRequisition => BadUserInput(formatted_error),
+        ServiceError::ItemNotInProgramMasterList => BadUserInput(formatted_error),
         ServiceError::CannotFindItemStatusForRequisitionLine => InternalError(formatted_error),
         ServiceError::NewlyCreatedRequisitionLineDoesNotExist => InternalError(formatted_error),
         ServiceError::DatabaseError(_) => InternalError(formatted_error),
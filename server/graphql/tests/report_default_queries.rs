@@ -2,6 +2,7 @@
 mod tests {
 
     use async_graphql::{EmptyMutation, MergedObject};
+    use graphql_cold_chain::ColdChainQueries;
     use graphql_core::{assert_graphql_query, test_helpers::setup_graphql_test};
     use graphql_general::GeneralQueries;
     use graphql_invoice::InvoiceQueries;
@@ -27,6 +28,7 @@ mod tests {
         pub StocktakeLineQueries,
         pub GeneralQueries,
         pub RequisitionQueries,
+        pub ColdChainQueries,
     );
 
     fn full_query() -> FullQuery {
@@ -38,6 +40,7 @@ mod tests {
             StocktakeLineQueries,
             GeneralQueries,
             RequisitionQueries,
+            ColdChainQueries,
         )
     }
 
@@ -119,5 +122,38 @@ mod tests {
             "dataId": mock_requisition.id,
         }));
         assert_graphql_query!(&settings, &query, &variables, &expected, None);
+
+        // requisition reporting rate
+        let query = get_default_gql_query(DefaultQuery::RequisitionReportingRate).query;
+        let expected = json!({
+          "reportingRates": [],
+          "store": {
+            "id": mock_requisition.store_id
+          }
+        });
+        let variables = Some(json!({
+            "storeId": mock_requisition.store_id,
+            "filter": {
+                "programId": "invalid_program_id"
+            }
+        }));
+        assert_graphql_query!(&settings, &query, &variables, &expected, None);
+
+        // temperature kpi
+        let query = get_default_gql_query(DefaultQuery::TemperatureKpi).query;
+        let expected = json!({
+          "temperatureKpis": [],
+          "store": {
+            "id": mock_requisition.store_id
+          }
+        });
+        let variables = Some(json!({
+            "storeId": mock_requisition.store_id,
+            "input": {
+                "fromDatetime": "2000-01-01T00:00:00+00:00",
+                "toDatetime": "2000-01-02T00:00:00+00:00"
+            }
+        }));
+        assert_graphql_query!(&settings, &query, &variables, &expected, None);
     }
 }
@@ -13,6 +13,9 @@ pub use self::requisition::*;
 pub mod requisition_line;
 pub use self::requisition_line::*;
 
+pub mod requisition_line_history;
+pub use self::requisition_line_history::*;
+
 pub mod stock_line;
 pub use self::stock_line::*;
 
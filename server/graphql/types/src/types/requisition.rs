@@ -236,6 +236,14 @@ impl RequisitionNode {
         &self.row().order_type
     }
 
+    pub async fn is_emergency(&self) -> bool {
+        self.row().is_emergency
+    }
+
+    pub async fn emergency_reason(&self) -> &Option<String> {
+        &self.row().emergency_reason
+    }
+
     pub async fn period(&self) -> Option<PeriodNode> {
         self.requisition
             .period
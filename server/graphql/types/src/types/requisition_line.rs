@@ -9,13 +9,14 @@ use service::{item_stats::ItemStats, usize_to_u32, ListResult};
 use graphql_core::{
     loader::{
         InvoiceLineForRequisitionLine, ItemLoader, ItemStatsLoaderInput, ItemsStatsForItemLoader,
-        LinkedRequisitionLineLoader, RequisitionAndItemId, RequisitionLineSupplyStatusLoader,
+        LinkedRequisitionLineLoader, RequisitionAndItemId, RequisitionLineHistoryLoader,
+        RequisitionLineSupplyStatusLoader,
     },
     standard_graphql_error::StandardGraphqlError,
     ContextExt,
 };
 
-use super::{InvoiceLineConnector, ItemNode, ItemStatsNode};
+use super::{InvoiceLineConnector, ItemNode, ItemStatsNode, RequisitionLineHistoryNode};
 
 #[derive(PartialEq, Debug)]
 pub struct RequisitionLineNode {
@@ -203,6 +204,19 @@ impl RequisitionLineNode {
             .unwrap_or(0.0))
     }
 
+    /// Requested, approved and supplied quantities of the item in previous periods from the
+    /// same customer. Only available in response requisition, request requisition returns null
+    pub async fn history(&self, ctx: &Context<'_>) -> Result<Option<RequisitionLineHistoryNode>> {
+        if self.requisition_row().r#type == RequisitionRowType::Request {
+            return Ok(None);
+        }
+
+        let loader = ctx.get_loader::<DataLoader<RequisitionLineHistoryLoader>>();
+        let result_option = loader.load_one(self.row().id.clone()).await?;
+
+        Ok(result_option.map(RequisitionLineHistoryNode::from_domain))
+    }
+
     pub async fn linked_requisition_line(
         &self,
         ctx: &Context<'_>,
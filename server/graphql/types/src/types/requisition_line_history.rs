@@ -0,0 +1,50 @@
+use async_graphql::*;
+use service::requisition_line::history::{PreviousRequisitionLine, RequisitionLineHistory};
+
+use super::RequisitionNode;
+
+#[derive(SimpleObject)]
+pub struct PreviousRequisitionLineNode {
+    pub requisition: RequisitionNode,
+    pub requested_quantity: i32,
+    pub approved_quantity: i32,
+    pub supplied_quantity: f64,
+    pub average_monthly_consumption: i32,
+}
+
+#[derive(SimpleObject)]
+pub struct RequisitionLineHistoryNode {
+    /// Most recent period first
+    pub previous: Vec<PreviousRequisitionLineNode>,
+    pub average_requested_quantity: Option<f64>,
+    /// Requested quantity is above the previous average by more than the store's
+    /// over order threshold
+    pub is_over_ordered: bool,
+}
+
+impl RequisitionLineHistoryNode {
+    pub fn from_domain(
+        RequisitionLineHistory {
+            previous,
+            average_requested_quantity,
+            is_over_ordered,
+        }: RequisitionLineHistory,
+    ) -> RequisitionLineHistoryNode {
+        RequisitionLineHistoryNode {
+            previous: previous
+                .into_iter()
+                .map(
+                    |line: PreviousRequisitionLine| PreviousRequisitionLineNode {
+                        requested_quantity: line.requested_quantity(),
+                        approved_quantity: line.approved_quantity(),
+                        average_monthly_consumption: line.average_monthly_consumption(),
+                        supplied_quantity: line.supplied_quantity,
+                        requisition: RequisitionNode::from_domain(line.requisition),
+                    },
+                )
+                .collect(),
+            average_requested_quantity,
+            is_over_ordered,
+        }
+    }
+}
@@ -38,6 +38,14 @@ impl StorePreferenceNode {
     pub async fn issue_in_foreign_currency(&self) -> &bool {
         &self.store_preference.issue_in_foreign_currency
     }
+
+    pub async fn use_lead_time_in_suggested_quantity(&self) -> &bool {
+        &self.store_preference.use_lead_time_in_suggested_quantity
+    }
+
+    pub async fn requisition_over_order_threshold_percentage(&self) -> &i32 {
+        &self.store_preference.requisition_over_order_threshold_percentage
+    }
 }
 
 impl StorePreferenceNode {
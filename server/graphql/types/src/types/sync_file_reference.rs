@@ -1,7 +1,10 @@
 use async_graphql::*;
 use chrono::NaiveDateTime;
 use graphql_core::simple_generic_errors::NodeError;
-use repository::sync_file_reference::SyncFileReference;
+use repository::{
+    sync_file_reference::SyncFileReference,
+    sync_file_reference_row::{SyncFileDirection, SyncFileStatus},
+};
 use service::{usize_to_u32, ListResult};
 
 #[derive(PartialEq, Debug)]
@@ -9,6 +12,20 @@ pub struct SyncFileReferenceNode {
     pub sync_file_reference: SyncFileReference,
 }
 
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum SyncFileDirectionNode {
+    Upload,
+    Download,
+}
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum SyncFileStatusNode {
+    Pending,
+    InProgress,
+    Done,
+    Error,
+}
+
 #[derive(SimpleObject)]
 pub struct SyncFileReferenceConnector {
     total_count: u32,
@@ -40,6 +57,56 @@ impl SyncFileReferenceNode {
     pub async fn created_datetime(&self) -> &NaiveDateTime {
         &self.row().sync_file_reference_row.created_datetime
     }
+
+    /// Upload to or download from central server, status and progress are of this transfer
+    pub async fn direction(&self) -> SyncFileDirectionNode {
+        SyncFileDirectionNode::from_domain(&self.row().sync_file_reference_row.direction)
+    }
+
+    pub async fn status(&self) -> SyncFileStatusNode {
+        SyncFileStatusNode::from_domain(&self.row().sync_file_reference_row.status)
+    }
+
+    pub async fn total_bytes(&self) -> i64 {
+        self.row().sync_file_reference_row.total_bytes
+    }
+
+    pub async fn transferred_bytes(&self) -> i64 {
+        self.row().sync_file_reference_row.transferred_bytes
+    }
+
+    pub async fn retries(&self) -> i32 {
+        self.row().sync_file_reference_row.retries
+    }
+
+    /// Last transfer error, transfer is retried at `retry_datetime`
+    pub async fn error(&self) -> &Option<String> {
+        &self.row().sync_file_reference_row.error
+    }
+
+    pub async fn retry_datetime(&self) -> &Option<NaiveDateTime> {
+        &self.row().sync_file_reference_row.retry_datetime
+    }
+}
+
+impl SyncFileDirectionNode {
+    pub fn from_domain(from: &SyncFileDirection) -> SyncFileDirectionNode {
+        match from {
+            SyncFileDirection::Upload => SyncFileDirectionNode::Upload,
+            SyncFileDirection::Download => SyncFileDirectionNode::Download,
+        }
+    }
+}
+
+impl SyncFileStatusNode {
+    pub fn from_domain(from: &SyncFileStatus) -> SyncFileStatusNode {
+        match from {
+            SyncFileStatus::Pending => SyncFileStatusNode::Pending,
+            SyncFileStatus::InProgress => SyncFileStatusNode::InProgress,
+            SyncFileStatus::Done => SyncFileStatusNode::Done,
+            SyncFileStatus::Error => SyncFileStatusNode::Error,
+        }
+    }
 }
 
 #[derive(Union)]
@@ -33,6 +33,8 @@ fn parse_default_query(input: &str) -> anyhow::Result<DefaultQuery> {
         "invoice" => DefaultQuery::Invoice,
         "stocktake" => DefaultQuery::Stocktake,
         "requisition" => DefaultQuery::Requisition,
+        "requisition_reporting_rate" => DefaultQuery::RequisitionReportingRate,
+        "temperature_kpi" => DefaultQuery::TemperatureKpi,
         _ => {
             return Err(anyhow::Error::msg(format!(
                 "Invalid default query: {}",
@@ -35,7 +35,8 @@ pub struct BuildArgs {
     /// Name of the file containing a graphql query
     #[clap(long)]
     pub query_gql: Option<String>,
-    /// Default query type, one of: "invoice" | "stocktake" | "requisition",
+    /// Default query type, one of: "invoice" | "stocktake" | "requisition" |
+    /// "requisition_reporting_rate" | "temperature_kpi",
     #[clap(long)]
     pub query_default: Option<String>,
 
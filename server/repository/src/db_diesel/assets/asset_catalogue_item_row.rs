@@ -1,5 +1,6 @@
 use super::asset_catalogue_item_row::asset_catalogue_item::dsl::*;
 
+use diesel_derive_enum::DbEnum;
 use serde::{Deserialize, Serialize};
 
 use crate::RepositoryError;
@@ -17,11 +18,24 @@ table! {
         manufacturer -> Nullable<Text>,
         model -> Text,
         asset_type_id -> Text,
+        energy_source -> Nullable<crate::db_diesel::assets::asset_catalogue_item_row::AssetEnergySourceMapping>,
+        storage_capacity -> Nullable<Double>,
     }
 }
 
+#[derive(DbEnum, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+pub enum AssetEnergySource {
+    Electricity,
+    Solar,
+    Gas,
+    Kerosene,
+    Passive,
+}
+
 #[derive(
-    Clone, Insertable, Queryable, Debug, PartialEq, AsChangeset, Eq, Default, Serialize, Deserialize,
+    Clone, Insertable, Queryable, Debug, PartialEq, AsChangeset, Default, Serialize, Deserialize,
 )]
 #[table_name = "asset_catalogue_item"]
 #[changeset_options(treat_none_as_null = "true")]
@@ -36,6 +50,11 @@ pub struct AssetCatalogueItemRow {
     pub model: String,
     #[column_name = "asset_type_id"]
     pub type_id: String,
+    #[serde(default)]
+    pub energy_source: Option<AssetEnergySource>,
+    /// Net vaccine storage volume in litres
+    #[serde(default)]
+    pub storage_capacity: Option<f64>,
 }
 
 pub struct AssetCatalogueItemRowRepository<'a> {
@@ -0,0 +1,141 @@
+use super::asset_disposal_row::asset_disposal::dsl::*;
+
+use crate::ChangeLogInsertRow;
+use crate::ChangelogAction;
+use crate::ChangelogRepository;
+use crate::ChangelogTableName;
+use crate::RepositoryError;
+use crate::StorageConnection;
+use crate::Upsert;
+
+use chrono::{NaiveDate, NaiveDateTime};
+use diesel::prelude::*;
+
+use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
+
+table! {
+    asset_disposal (id) {
+        id -> Text,
+        asset_id -> Text,
+        store_id -> Text,
+        disposal_date -> Date,
+        method -> crate::db_diesel::assets::asset_disposal_row::AssetDisposalMethodMapping,
+        comment -> Nullable<Text>,
+        user_id -> Text,
+        created_datetime -> Timestamp,
+    }
+}
+
+#[derive(DbEnum, Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+pub enum AssetDisposalMethod {
+    Destroyed,
+    Sold,
+    Donated,
+    Recycled,
+    ReturnedToSupplier,
+    #[default]
+    Other,
+}
+
+/// Final disposal of a decommissioned asset
+#[derive(
+    Clone, Insertable, Queryable, Debug, PartialEq, AsChangeset, Eq, Default, Serialize, Deserialize,
+)]
+#[changeset_options(treat_none_as_null = "true")]
+#[table_name = "asset_disposal"]
+pub struct AssetDisposalRow {
+    pub id: String,
+    pub asset_id: String,
+    pub store_id: String,
+    pub disposal_date: NaiveDate,
+    pub method: AssetDisposalMethod,
+    pub comment: Option<String>,
+    pub user_id: String,
+    pub created_datetime: NaiveDateTime,
+}
+
+pub struct AssetDisposalRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> AssetDisposalRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        AssetDisposalRowRepository { connection }
+    }
+
+    #[cfg(feature = "postgres")]
+    fn _upsert_one(&self, row: &AssetDisposalRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(asset_disposal)
+            .values(row)
+            .on_conflict(id)
+            .do_update()
+            .set(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "postgres"))]
+    fn _upsert_one(&self, row: &AssetDisposalRow) -> Result<(), RepositoryError> {
+        diesel::replace_into(asset_disposal)
+            .values(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    pub fn upsert_one(&self, row: &AssetDisposalRow) -> Result<i64, RepositoryError> {
+        self._upsert_one(row)?;
+        let changelog = ChangeLogInsertRow {
+            table_name: ChangelogTableName::AssetDisposal,
+            record_id: row.id.clone(),
+            row_action: ChangelogAction::Upsert,
+            store_id: Some(row.store_id.clone()),
+            name_link_id: None,
+        };
+        ChangelogRepository::new(self.connection).insert(&changelog)
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        disposal_id: &str,
+    ) -> Result<Option<AssetDisposalRow>, RepositoryError> {
+        let result = asset_disposal
+            .filter(id.eq(disposal_id))
+            .first(&self.connection.connection)
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_one_by_asset_id(
+        &self,
+        asset_id_value: &str,
+    ) -> Result<Option<AssetDisposalRow>, RepositoryError> {
+        let result = asset_disposal
+            .filter(asset_id.eq(asset_id_value))
+            .first(&self.connection.connection)
+            .optional()?;
+        Ok(result)
+    }
+}
+
+impl Upsert for AssetDisposalRow {
+    fn upsert_sync(&self, con: &StorageConnection) -> Result<(), RepositoryError> {
+        let _change_log_id = AssetDisposalRowRepository::new(con).upsert_one(self)?;
+        Ok(())
+    }
+
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        let cursor_id = AssetDisposalRowRepository::new(con).upsert_one(self)?;
+        Ok(Some(cursor_id))
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            AssetDisposalRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
@@ -181,8 +181,9 @@ fn create_latest_filtered_query(filter: Option<AssetLogFilter>) -> BoxedLatestAs
     let mut query = latest_asset_log_dsl::latest_asset_log.into_boxed();
 
     if let Some(f) = filter {
-        let AssetLogFilter { id, .. } = f;
+        let AssetLogFilter { id, asset_id, .. } = f;
         apply_equal_filter!(query, id, latest_asset_log_dsl::id);
+        apply_equal_filter!(query, asset_id, latest_asset_log_dsl::asset_id);
     }
     query
 }
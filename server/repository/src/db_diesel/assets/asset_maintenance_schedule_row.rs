@@ -0,0 +1,91 @@
+use super::asset_maintenance_schedule_row::asset_maintenance_schedule::dsl::*;
+
+use crate::{RepositoryError, StorageConnection};
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+table! {
+    asset_maintenance_schedule (id) {
+        id -> Text,
+        name -> Text,
+        asset_type_id -> Nullable<Text>,
+        asset_catalogue_item_id -> Nullable<Text>,
+        interval_days -> Nullable<Integer>,
+        interval_run_hours -> Nullable<Double>,
+        is_active -> Bool,
+        created_datetime -> Timestamp,
+    }
+}
+
+/// Preventive maintenance of assets of the asset type or catalogue item, due every
+/// `interval_days` and/or every `interval_run_hours`
+#[derive(Clone, Insertable, Queryable, Debug, PartialEq, AsChangeset, Default)]
+#[table_name = "asset_maintenance_schedule"]
+#[changeset_options(treat_none_as_null = "true")]
+pub struct AssetMaintenanceScheduleRow {
+    pub id: String,
+    pub name: String,
+    pub asset_type_id: Option<String>,
+    #[column_name = "asset_catalogue_item_id"]
+    pub catalogue_item_id: Option<String>,
+    pub interval_days: Option<i32>,
+    pub interval_run_hours: Option<f64>,
+    pub is_active: bool,
+    pub created_datetime: NaiveDateTime,
+}
+
+pub struct AssetMaintenanceScheduleRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> AssetMaintenanceScheduleRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        AssetMaintenanceScheduleRowRepository { connection }
+    }
+
+    #[cfg(feature = "postgres")]
+    pub fn upsert_one(&self, row: &AssetMaintenanceScheduleRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(asset_maintenance_schedule)
+            .values(row)
+            .on_conflict(id)
+            .do_update()
+            .set(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "postgres"))]
+    pub fn upsert_one(&self, row: &AssetMaintenanceScheduleRow) -> Result<(), RepositoryError> {
+        diesel::replace_into(asset_maintenance_schedule)
+            .values(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        record_id: &str,
+    ) -> Result<Option<AssetMaintenanceScheduleRow>, RepositoryError> {
+        let result = asset_maintenance_schedule
+            .filter(id.eq(record_id))
+            .first(&self.connection.connection)
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_all(&self) -> Result<Vec<AssetMaintenanceScheduleRow>, RepositoryError> {
+        let result = asset_maintenance_schedule
+            .order(name.asc())
+            .load(&self.connection.connection)?;
+        Ok(result)
+    }
+
+    pub fn find_many_active(&self) -> Result<Vec<AssetMaintenanceScheduleRow>, RepositoryError> {
+        let result = asset_maintenance_schedule
+            .filter(is_active.eq(true))
+            .order(name.asc())
+            .load(&self.connection.connection)?;
+        Ok(result)
+    }
+}
@@ -30,6 +30,8 @@ table! {
         created_datetime -> Timestamp,
         modified_datetime -> Timestamp,
         deleted_datetime -> Nullable<Timestamp>,
+        decommissioned_date -> Nullable<Date>,
+        decommission_reason -> Nullable<Text>,
     }
 }
 
@@ -54,6 +56,10 @@ pub struct AssetRow {
     pub created_datetime: NaiveDateTime,
     pub modified_datetime: NaiveDateTime,
     pub deleted_datetime: Option<NaiveDateTime>,
+    #[serde(default)]
+    pub decommissioned_date: Option<NaiveDate>,
+    #[serde(default)]
+    pub decommission_reason: Option<String>,
 }
 
 pub struct AssetRowRepository<'a> {
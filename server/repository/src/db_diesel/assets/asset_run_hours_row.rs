@@ -0,0 +1,54 @@
+use super::asset_run_hours_row::asset_run_hours::dsl::*;
+
+use crate::{RepositoryError, StorageConnection};
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+table! {
+    asset_run_hours (id) {
+        id -> Text,
+        asset_id -> Text,
+        run_hours -> Double,
+        recorded_datetime -> Timestamp,
+    }
+}
+
+/// Run hour meter reading of an asset
+#[derive(Clone, Insertable, Queryable, Debug, PartialEq, AsChangeset, Default)]
+#[table_name = "asset_run_hours"]
+pub struct AssetRunHoursRow {
+    pub id: String,
+    pub asset_id: String,
+    pub run_hours: f64,
+    pub recorded_datetime: NaiveDateTime,
+}
+
+pub struct AssetRunHoursRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> AssetRunHoursRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        AssetRunHoursRowRepository { connection }
+    }
+
+    pub fn insert_one(&self, row: &AssetRunHoursRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(asset_run_hours)
+            .values(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    pub fn find_latest_by_asset_id(
+        &self,
+        asset: &str,
+    ) -> Result<Option<AssetRunHoursRow>, RepositoryError> {
+        let result = asset_run_hours
+            .filter(asset_id.eq(asset))
+            .order(recorded_datetime.desc())
+            .first(&self.connection.connection)
+            .optional()?;
+        Ok(result)
+    }
+}
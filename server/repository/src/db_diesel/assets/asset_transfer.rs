@@ -0,0 +1,251 @@
+use super::asset_transfer_row::{
+    asset_transfer::{self, dsl as asset_transfer_dsl},
+    AssetTransferRow, AssetTransferStatus,
+};
+
+use diesel::{dsl::IntoBoxed, prelude::*};
+
+use crate::{
+    diesel_macros::{apply_equal_filter, apply_sort},
+    repository_error::RepositoryError,
+    DBType, EqualFilter, Pagination, Sort, StorageConnection,
+};
+
+pub type AssetTransfer = AssetTransferRow;
+
+pub enum AssetTransferSortField {
+    CreatedDatetime,
+    DispatchedDatetime,
+    ReceivedDatetime,
+}
+
+pub type AssetTransferSort = Sort<AssetTransferSortField>;
+
+#[derive(Clone, Default)]
+pub struct AssetTransferFilter {
+    pub id: Option<EqualFilter<String>>,
+    pub asset_id: Option<EqualFilter<String>>,
+    pub from_store_id: Option<EqualFilter<String>>,
+    pub to_store_id: Option<EqualFilter<String>>,
+    /// Transfers from or to the store
+    pub store_id: Option<String>,
+    pub status: Option<EqualFilter<AssetTransferStatus>>,
+}
+
+impl AssetTransferFilter {
+    pub fn new() -> AssetTransferFilter {
+        Self::default()
+    }
+
+    pub fn id(mut self, filter: EqualFilter<String>) -> Self {
+        self.id = Some(filter);
+        self
+    }
+
+    pub fn asset_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.asset_id = Some(filter);
+        self
+    }
+
+    pub fn from_store_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.from_store_id = Some(filter);
+        self
+    }
+
+    pub fn to_store_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.to_store_id = Some(filter);
+        self
+    }
+
+    pub fn store_id(mut self, value: &str) -> Self {
+        self.store_id = Some(value.to_string());
+        self
+    }
+
+    pub fn status(mut self, filter: EqualFilter<AssetTransferStatus>) -> Self {
+        self.status = Some(filter);
+        self
+    }
+}
+
+pub struct AssetTransferRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> AssetTransferRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        AssetTransferRepository { connection }
+    }
+
+    pub fn count(&self, filter: Option<AssetTransferFilter>) -> Result<i64, RepositoryError> {
+        let query = create_filtered_query(filter);
+        Ok(query.count().get_result(&self.connection.connection)?)
+    }
+
+    pub fn query_one(
+        &self,
+        filter: AssetTransferFilter,
+    ) -> Result<Option<AssetTransfer>, RepositoryError> {
+        Ok(self.query_by_filter(filter)?.pop())
+    }
+
+    pub fn query_by_filter(
+        &self,
+        filter: AssetTransferFilter,
+    ) -> Result<Vec<AssetTransfer>, RepositoryError> {
+        self.query(Pagination::all(), Some(filter), None)
+    }
+
+    pub fn query(
+        &self,
+        pagination: Pagination,
+        filter: Option<AssetTransferFilter>,
+        sort: Option<AssetTransferSort>,
+    ) -> Result<Vec<AssetTransfer>, RepositoryError> {
+        let mut query = create_filtered_query(filter);
+
+        if let Some(sort) = sort {
+            match sort.key {
+                AssetTransferSortField::CreatedDatetime => {
+                    apply_sort!(query, sort, asset_transfer_dsl::created_datetime);
+                }
+                AssetTransferSortField::DispatchedDatetime => {
+                    apply_sort!(query, sort, asset_transfer_dsl::dispatched_datetime);
+                }
+                AssetTransferSortField::ReceivedDatetime => {
+                    apply_sort!(query, sort, asset_transfer_dsl::received_datetime);
+                }
+            }
+        } else {
+            query = query.order(asset_transfer_dsl::created_datetime.asc())
+        }
+
+        let result = query
+            .offset(pagination.offset as i64)
+            .limit(pagination.limit as i64)
+            .load::<AssetTransfer>(&self.connection.connection)?;
+
+        Ok(result)
+    }
+}
+
+type BoxedAssetTransferQuery = IntoBoxed<'static, asset_transfer::table, DBType>;
+
+fn create_filtered_query(filter: Option<AssetTransferFilter>) -> BoxedAssetTransferQuery {
+    let mut query = asset_transfer_dsl::asset_transfer.into_boxed();
+
+    if let Some(f) = filter {
+        let AssetTransferFilter {
+            id,
+            asset_id,
+            from_store_id,
+            to_store_id,
+            store_id,
+            status,
+        } = f;
+
+        apply_equal_filter!(query, id, asset_transfer_dsl::id);
+        apply_equal_filter!(query, asset_id, asset_transfer_dsl::asset_id);
+        apply_equal_filter!(query, from_store_id, asset_transfer_dsl::from_store_id);
+        apply_equal_filter!(query, to_store_id, asset_transfer_dsl::to_store_id);
+        apply_equal_filter!(query, status, asset_transfer_dsl::status);
+
+        if let Some(store_id) = store_id {
+            query = query.filter(
+                asset_transfer_dsl::from_store_id
+                    .eq(store_id.clone())
+                    .or(asset_transfer_dsl::to_store_id.eq(store_id)),
+            );
+        }
+    }
+    query
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use crate::{
+        assets::{
+            asset_transfer::{AssetTransferFilter, AssetTransferRepository},
+            asset_transfer_row::{
+                AssetTransferRow, AssetTransferRowRepository, AssetTransferStatus,
+            },
+        },
+        mock::{mock_asset_b, mock_store_a, mock_store_b, mock_store_c, MockDataInserts},
+        test_db, EqualFilter,
+    };
+
+    #[actix_rt::test]
+    async fn test_asset_transfer_query_repository() {
+        let (_, connection, _, _) = test_db::setup_all(
+            "test_asset_transfer_query_repository",
+            MockDataInserts::none().names().stores().assets(),
+        )
+        .await;
+
+        let transfer =
+            |id: &str, from_store_id: String, to_store_id: String, status, day| AssetTransferRow {
+                id: id.to_string(),
+                asset_id: mock_asset_b().id,
+                from_store_id,
+                to_store_id,
+                status,
+                created_datetime: NaiveDate::from_ymd_opt(2024, 1, day)
+                    .unwrap()
+                    .and_hms_opt(0, 0, 0)
+                    .unwrap(),
+                ..Default::default()
+            };
+        for row in [
+            transfer(
+                "transfer_1",
+                mock_store_a().id,
+                mock_store_b().id,
+                AssetTransferStatus::Received,
+                2,
+            ),
+            transfer(
+                "transfer_2",
+                mock_store_b().id,
+                mock_store_c().id,
+                AssetTransferStatus::Dispatched,
+                1,
+            ),
+        ] {
+            AssetTransferRowRepository::new(&connection)
+                .upsert_one(&row)
+                .unwrap();
+        }
+
+        let repo = AssetTransferRepository::new(&connection);
+        let ids = |filter| {
+            repo.query_by_filter(filter)
+                .unwrap()
+                .into_iter()
+                .map(|transfer| transfer.id)
+                .collect::<Vec<String>>()
+        };
+
+        // Ordered by created datetime
+        assert_eq!(
+            ids(AssetTransferFilter::new().asset_id(EqualFilter::equal_to(&mock_asset_b().id))),
+            vec!["transfer_2", "transfer_1"]
+        );
+        // From or to store
+        assert_eq!(
+            ids(AssetTransferFilter::new().store_id(&mock_store_a().id)),
+            vec!["transfer_1"]
+        );
+        assert_eq!(
+            ids(AssetTransferFilter::new().store_id(&mock_store_b().id)),
+            vec!["transfer_2", "transfer_1"]
+        );
+        assert_eq!(
+            ids(AssetTransferFilter::new()
+                .store_id(&mock_store_b().id)
+                .status(AssetTransferStatus::Dispatched.equal_to())),
+            vec!["transfer_2"]
+        );
+    }
+}
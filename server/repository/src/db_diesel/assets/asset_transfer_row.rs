@@ -0,0 +1,158 @@
+use super::asset_transfer_row::asset_transfer::dsl::*;
+
+use crate::ChangeLogInsertRow;
+use crate::ChangelogAction;
+use crate::ChangelogRepository;
+use crate::ChangelogTableName;
+use crate::EqualFilter;
+use crate::RepositoryError;
+use crate::StorageConnection;
+use crate::StoreRowRepository;
+use crate::Upsert;
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
+
+table! {
+    asset_transfer (id) {
+        id -> Text,
+        asset_id -> Text,
+        from_store_id -> Text,
+        to_store_id -> Text,
+        status -> crate::db_diesel::assets::asset_transfer_row::AssetTransferStatusMapping,
+        comment -> Nullable<Text>,
+        created_datetime -> Timestamp,
+        created_by_user_id -> Text,
+        dispatched_datetime -> Nullable<Timestamp>,
+        dispatched_by_user_id -> Nullable<Text>,
+        received_datetime -> Nullable<Timestamp>,
+        received_by_user_id -> Nullable<Text>,
+    }
+}
+
+#[derive(DbEnum, Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+pub enum AssetTransferStatus {
+    #[default]
+    New,
+    Dispatched,
+    Received,
+    Cancelled,
+}
+
+impl AssetTransferStatus {
+    pub fn equal_to(&self) -> EqualFilter<AssetTransferStatus> {
+        EqualFilter {
+            equal_to: Some(self.clone()),
+            not_equal_to: None,
+            equal_any: None,
+            not_equal_all: None,
+            equal_any_or_null: None,
+            is_null: None,
+        }
+    }
+}
+
+/// Movement of an asset between stores, synced to the sites of both stores
+#[derive(
+    Clone, Insertable, Queryable, Debug, PartialEq, AsChangeset, Eq, Default, Serialize, Deserialize,
+)]
+#[changeset_options(treat_none_as_null = "true")]
+#[table_name = "asset_transfer"]
+pub struct AssetTransferRow {
+    pub id: String,
+    pub asset_id: String,
+    pub from_store_id: String,
+    pub to_store_id: String,
+    pub status: AssetTransferStatus,
+    pub comment: Option<String>,
+    pub created_datetime: NaiveDateTime,
+    pub created_by_user_id: String,
+    pub dispatched_datetime: Option<NaiveDateTime>,
+    pub dispatched_by_user_id: Option<String>,
+    pub received_datetime: Option<NaiveDateTime>,
+    pub received_by_user_id: Option<String>,
+}
+
+pub struct AssetTransferRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> AssetTransferRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        AssetTransferRowRepository { connection }
+    }
+
+    #[cfg(feature = "postgres")]
+    fn _upsert_one(&self, row: &AssetTransferRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(asset_transfer)
+            .values(row)
+            .on_conflict(id)
+            .do_update()
+            .set(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "postgres"))]
+    fn _upsert_one(&self, row: &AssetTransferRow) -> Result<(), RepositoryError> {
+        diesel::replace_into(asset_transfer)
+            .values(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    pub fn upsert_one(&self, row: &AssetTransferRow) -> Result<i64, RepositoryError> {
+        self._upsert_one(row)?;
+        self.insert_changelog(row)
+    }
+
+    /// Changelog is matched to the site of the source store by store id and to the site of the
+    /// destination store by name id, see `ChangeLogSyncStyle::Transfer`
+    fn insert_changelog(&self, row: &AssetTransferRow) -> Result<i64, RepositoryError> {
+        let to_store = StoreRowRepository::new(self.connection).find_one_by_id(&row.to_store_id)?;
+        let row = ChangeLogInsertRow {
+            table_name: ChangelogTableName::AssetTransfer,
+            record_id: row.id.clone(),
+            row_action: ChangelogAction::Upsert,
+            store_id: Some(row.from_store_id.clone()),
+            name_link_id: to_store.map(|store| store.name_id),
+        };
+        ChangelogRepository::new(self.connection).insert(&row)
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        transfer_id: &str,
+    ) -> Result<Option<AssetTransferRow>, RepositoryError> {
+        let result = asset_transfer
+            .filter(id.eq(transfer_id))
+            .first(&self.connection.connection)
+            .optional()?;
+        Ok(result)
+    }
+}
+
+impl Upsert for AssetTransferRow {
+    fn upsert_sync(&self, con: &StorageConnection) -> Result<(), RepositoryError> {
+        let _change_log_id = AssetTransferRowRepository::new(con).upsert_one(self)?;
+        Ok(())
+    }
+
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        let cursor_id = AssetTransferRowRepository::new(con).upsert_one(self)?;
+        Ok(Some(cursor_id))
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            AssetTransferRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
@@ -0,0 +1,243 @@
+use super::asset_work_order_row::{
+    asset_work_order::{self, dsl as asset_work_order_dsl},
+    AssetWorkOrderRow, AssetWorkOrderStatus,
+};
+
+use diesel::{dsl::IntoBoxed, prelude::*};
+
+use crate::{
+    diesel_macros::{apply_date_filter, apply_equal_filter, apply_sort},
+    repository_error::RepositoryError,
+    DBType, DateFilter, EqualFilter, Pagination, Sort, StorageConnection,
+};
+
+pub type AssetWorkOrder = AssetWorkOrderRow;
+
+pub enum AssetWorkOrderSortField {
+    DueDate,
+    CreatedDatetime,
+}
+
+pub type AssetWorkOrderSort = Sort<AssetWorkOrderSortField>;
+
+#[derive(Clone, Default)]
+pub struct AssetWorkOrderFilter {
+    pub id: Option<EqualFilter<String>>,
+    pub store_id: Option<EqualFilter<String>>,
+    pub asset_id: Option<EqualFilter<String>>,
+    pub schedule_id: Option<EqualFilter<String>>,
+    pub status: Option<EqualFilter<AssetWorkOrderStatus>>,
+    pub due_date: Option<DateFilter>,
+    pub assigned_user_id: Option<EqualFilter<String>>,
+}
+
+impl AssetWorkOrderFilter {
+    pub fn new() -> AssetWorkOrderFilter {
+        Self::default()
+    }
+
+    pub fn id(mut self, filter: EqualFilter<String>) -> Self {
+        self.id = Some(filter);
+        self
+    }
+
+    pub fn store_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.store_id = Some(filter);
+        self
+    }
+
+    pub fn asset_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.asset_id = Some(filter);
+        self
+    }
+
+    pub fn schedule_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.schedule_id = Some(filter);
+        self
+    }
+
+    pub fn status(mut self, filter: EqualFilter<AssetWorkOrderStatus>) -> Self {
+        self.status = Some(filter);
+        self
+    }
+
+    pub fn due_date(mut self, filter: DateFilter) -> Self {
+        self.due_date = Some(filter);
+        self
+    }
+
+    pub fn assigned_user_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.assigned_user_id = Some(filter);
+        self
+    }
+}
+
+pub struct AssetWorkOrderRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> AssetWorkOrderRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        AssetWorkOrderRepository { connection }
+    }
+
+    pub fn count(&self, filter: Option<AssetWorkOrderFilter>) -> Result<i64, RepositoryError> {
+        let query = create_filtered_query(filter);
+        Ok(query.count().get_result(&self.connection.connection)?)
+    }
+
+    pub fn query_one(
+        &self,
+        filter: AssetWorkOrderFilter,
+    ) -> Result<Option<AssetWorkOrder>, RepositoryError> {
+        Ok(self.query_by_filter(filter)?.pop())
+    }
+
+    pub fn query_by_filter(
+        &self,
+        filter: AssetWorkOrderFilter,
+    ) -> Result<Vec<AssetWorkOrder>, RepositoryError> {
+        self.query(Pagination::all(), Some(filter), None)
+    }
+
+    pub fn query(
+        &self,
+        pagination: Pagination,
+        filter: Option<AssetWorkOrderFilter>,
+        sort: Option<AssetWorkOrderSort>,
+    ) -> Result<Vec<AssetWorkOrder>, RepositoryError> {
+        let mut query = create_filtered_query(filter);
+
+        if let Some(sort) = sort {
+            match sort.key {
+                AssetWorkOrderSortField::DueDate => {
+                    apply_sort!(query, sort, asset_work_order_dsl::due_date);
+                }
+                AssetWorkOrderSortField::CreatedDatetime => {
+                    apply_sort!(query, sort, asset_work_order_dsl::created_datetime);
+                }
+            }
+        } else {
+            query = query.order(asset_work_order_dsl::due_date.asc())
+        }
+
+        let result = query
+            .offset(pagination.offset as i64)
+            .limit(pagination.limit as i64)
+            .load::<AssetWorkOrder>(&self.connection.connection)?;
+
+        Ok(result)
+    }
+}
+
+type BoxedAssetWorkOrderQuery = IntoBoxed<'static, asset_work_order::table, DBType>;
+
+fn create_filtered_query(filter: Option<AssetWorkOrderFilter>) -> BoxedAssetWorkOrderQuery {
+    let mut query = asset_work_order_dsl::asset_work_order.into_boxed();
+
+    if let Some(f) = filter {
+        let AssetWorkOrderFilter {
+            id,
+            store_id,
+            asset_id,
+            schedule_id,
+            status,
+            due_date,
+            assigned_user_id,
+        } = f;
+
+        apply_equal_filter!(query, id, asset_work_order_dsl::id);
+        apply_equal_filter!(query, store_id, asset_work_order_dsl::store_id);
+        apply_equal_filter!(query, asset_id, asset_work_order_dsl::asset_id);
+        apply_equal_filter!(query, schedule_id, asset_work_order_dsl::schedule_id);
+        apply_equal_filter!(query, status, asset_work_order_dsl::status);
+        apply_date_filter!(query, due_date, asset_work_order_dsl::due_date);
+        apply_equal_filter!(
+            query,
+            assigned_user_id,
+            asset_work_order_dsl::assigned_user_id
+        );
+    }
+    query
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use crate::{
+        assets::{
+            asset_maintenance_schedule_row::{
+                AssetMaintenanceScheduleRow, AssetMaintenanceScheduleRowRepository,
+            },
+            asset_work_order::{AssetWorkOrderFilter, AssetWorkOrderRepository},
+            asset_work_order_row::{
+                AssetWorkOrderRow, AssetWorkOrderRowRepository, AssetWorkOrderStatus,
+            },
+        },
+        mock::{mock_asset_b, mock_store_a, MockDataInserts},
+        test_db, DateFilter, EqualFilter,
+    };
+
+    #[actix_rt::test]
+    async fn test_asset_work_order_query_repository() {
+        let (_, connection, _, _) = test_db::setup_all(
+            "test_asset_work_order_query_repository",
+            MockDataInserts::none().stores().assets(),
+        )
+        .await;
+
+        AssetMaintenanceScheduleRowRepository::new(&connection)
+            .upsert_one(&AssetMaintenanceScheduleRow {
+                id: "schedule".to_string(),
+                name: "Defrost".to_string(),
+                interval_days: Some(30),
+                is_active: true,
+                ..Default::default()
+            })
+            .unwrap();
+
+        let work_order = |id: &str, status, day| AssetWorkOrderRow {
+            id: id.to_string(),
+            store_id: mock_store_a().id,
+            asset_id: mock_asset_b().id,
+            schedule_id: "schedule".to_string(),
+            status,
+            due_date: NaiveDate::from_ymd_opt(2024, 1, day).unwrap(),
+            ..Default::default()
+        };
+        let completed = work_order("completed", AssetWorkOrderStatus::Completed, 1);
+        let open = work_order("open", AssetWorkOrderStatus::Open, 31);
+        let row_repo = AssetWorkOrderRowRepository::new(&connection);
+        row_repo.upsert_one(&open).unwrap();
+        row_repo.upsert_one(&completed).unwrap();
+
+        let repo = AssetWorkOrderRepository::new(&connection);
+        assert_eq!(
+            repo.query_by_filter(
+                AssetWorkOrderFilter::new().store_id(EqualFilter::equal_to(&mock_store_a().id))
+            ),
+            Ok(vec![completed.clone(), open.clone()])
+        );
+        assert_eq!(
+            repo.query_by_filter(
+                AssetWorkOrderFilter::new()
+                    .status(AssetWorkOrderStatus::Open.equal_to())
+                    .due_date(DateFilter::before_or_equal_to(
+                        NaiveDate::from_ymd_opt(2024, 1, 31).unwrap()
+                    ))
+            ),
+            Ok(vec![open.clone()])
+        );
+        assert_eq!(
+            repo.count(Some(AssetWorkOrderFilter::new().due_date(
+                DateFilter::before_or_equal_to(NaiveDate::from_ymd_opt(2024, 1, 30).unwrap())
+            ))),
+            Ok(1)
+        );
+        assert_eq!(
+            row_repo.find_many_by_asset_and_schedule(&mock_asset_b().id, "schedule"),
+            Ok(vec![open, completed])
+        );
+    }
+}
@@ -0,0 +1,142 @@
+use super::asset_work_order_row::asset_work_order::dsl::*;
+
+use crate::{EqualFilter, RepositoryError, StorageConnection};
+
+use chrono::{NaiveDate, NaiveDateTime};
+use diesel::prelude::*;
+use diesel_derive_enum::DbEnum;
+
+table! {
+    asset_work_order (id) {
+        id -> Text,
+        store_id -> Text,
+        asset_id -> Text,
+        schedule_id -> Text,
+        status -> crate::db_diesel::assets::asset_work_order_row::AssetWorkOrderStatusMapping,
+        due_date -> Date,
+        assigned_user_id -> Nullable<Text>,
+        created_datetime -> Timestamp,
+        completed_datetime -> Nullable<Timestamp>,
+        completed_by_user_id -> Nullable<Text>,
+        run_hours -> Nullable<Double>,
+        parts_used -> Nullable<Text>,
+        notes -> Nullable<Text>,
+        asset_log_id -> Nullable<Text>,
+    }
+}
+
+#[derive(DbEnum, Debug, Clone, PartialEq, Eq)]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+pub enum AssetWorkOrderStatus {
+    Open,
+    Completed,
+    Cancelled,
+}
+
+impl AssetWorkOrderStatus {
+    pub fn equal_to(&self) -> EqualFilter<AssetWorkOrderStatus> {
+        EqualFilter {
+            equal_to: Some(self.clone()),
+            not_equal_to: None,
+            equal_any: None,
+            not_equal_all: None,
+            equal_any_or_null: None,
+            is_null: None,
+        }
+    }
+}
+
+impl Default for AssetWorkOrderStatus {
+    fn default() -> Self {
+        Self::Open
+    }
+}
+
+/// Maintenance of an asset due by a maintenance schedule
+#[derive(Clone, Insertable, Queryable, Debug, PartialEq, AsChangeset, Default)]
+#[table_name = "asset_work_order"]
+#[changeset_options(treat_none_as_null = "true")]
+pub struct AssetWorkOrderRow {
+    pub id: String,
+    pub store_id: String,
+    pub asset_id: String,
+    pub schedule_id: String,
+    pub status: AssetWorkOrderStatus,
+    pub due_date: NaiveDate,
+    /// Technician responsible for the maintenance
+    pub assigned_user_id: Option<String>,
+    pub created_datetime: NaiveDateTime,
+    pub completed_datetime: Option<NaiveDateTime>,
+    pub completed_by_user_id: Option<String>,
+    /// Run hours of the asset when the maintenance was completed
+    pub run_hours: Option<f64>,
+    pub parts_used: Option<String>,
+    pub notes: Option<String>,
+    /// Asset log recorded on completion
+    pub asset_log_id: Option<String>,
+}
+
+pub struct AssetWorkOrderRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> AssetWorkOrderRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        AssetWorkOrderRowRepository { connection }
+    }
+
+    #[cfg(feature = "postgres")]
+    pub fn upsert_one(&self, row: &AssetWorkOrderRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(asset_work_order)
+            .values(row)
+            .on_conflict(id)
+            .do_update()
+            .set(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "postgres"))]
+    pub fn upsert_one(&self, row: &AssetWorkOrderRow) -> Result<(), RepositoryError> {
+        diesel::replace_into(asset_work_order)
+            .values(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        record_id: &str,
+    ) -> Result<Option<AssetWorkOrderRow>, RepositoryError> {
+        let result = asset_work_order
+            .filter(id.eq(record_id))
+            .first(&self.connection.connection)
+            .optional()?;
+        Ok(result)
+    }
+
+    /// Work orders of the asset and schedule, latest due date first
+    pub fn find_many_by_asset_and_schedule(
+        &self,
+        asset: &str,
+        schedule: &str,
+    ) -> Result<Vec<AssetWorkOrderRow>, RepositoryError> {
+        let result = asset_work_order
+            .filter(asset_id.eq(asset))
+            .filter(schedule_id.eq(schedule))
+            .order(due_date.desc())
+            .load(&self.connection.connection)?;
+        Ok(result)
+    }
+
+    pub fn find_open_by_asset_id(
+        &self,
+        asset: &str,
+    ) -> Result<Vec<AssetWorkOrderRow>, RepositoryError> {
+        let result = asset_work_order
+            .filter(asset_id.eq(asset))
+            .filter(status.eq(AssetWorkOrderStatus::Open))
+            .load(&self.connection.connection)?;
+        Ok(result)
+    }
+}
@@ -5,10 +5,17 @@ pub mod asset_category;
 pub mod asset_category_row;
 pub mod asset_class;
 pub mod asset_class_row;
+pub mod asset_disposal_row;
 pub mod asset_internal_location;
 pub mod asset_internal_location_row;
 pub mod asset_log;
 pub mod asset_log_row;
+pub mod asset_maintenance_schedule_row;
 pub mod asset_row;
+pub mod asset_run_hours_row;
+pub mod asset_transfer;
+pub mod asset_transfer_row;
 pub mod asset_type;
 pub mod asset_type_row;
+pub mod asset_work_order;
+pub mod asset_work_order_row;
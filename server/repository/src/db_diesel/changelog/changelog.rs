@@ -92,14 +92,21 @@ pub enum ChangelogTableName {
     SyncFileReference,
     Asset,
     AssetLog,
+    AssetTransfer,
+    AssetDisposal,
 }
 
 pub(crate) enum ChangeLogSyncStyle {
     Legacy,
     Central,
     Remote,
+    /// Remote record that syncs through legacy mSupply central server, unless remote site
+    /// switched the table to open mSupply central server (v6), see `can_sync_through_v6`
+    MigratingRemote,
     File,
-    // Transfer,
+    /// Remote record synced to the sites of both the source store (store_id) and the
+    /// destination store (name_id of the store)
+    Transfer,
     // Patient??  etc
 }
 // When adding a new change log record type, specify how it should be synced
@@ -108,15 +115,15 @@ impl ChangelogTableName {
     pub(crate) fn sync_style(&self) -> ChangeLogSyncStyle {
         match self {
             ChangelogTableName::Number => ChangeLogSyncStyle::Legacy,
-            ChangelogTableName::Location => ChangeLogSyncStyle::Legacy,
-            ChangelogTableName::LocationMovement => ChangeLogSyncStyle::Legacy,
-            ChangelogTableName::StockLine => ChangeLogSyncStyle::Legacy,
-            ChangelogTableName::Invoice => ChangeLogSyncStyle::Legacy,
-            ChangelogTableName::InvoiceLine => ChangeLogSyncStyle::Legacy,
-            ChangelogTableName::Stocktake => ChangeLogSyncStyle::Legacy,
-            ChangelogTableName::StocktakeLine => ChangeLogSyncStyle::Legacy,
-            ChangelogTableName::Requisition => ChangeLogSyncStyle::Legacy,
-            ChangelogTableName::RequisitionLine => ChangeLogSyncStyle::Legacy,
+            ChangelogTableName::Location => ChangeLogSyncStyle::MigratingRemote,
+            ChangelogTableName::LocationMovement => ChangeLogSyncStyle::MigratingRemote,
+            ChangelogTableName::StockLine => ChangeLogSyncStyle::MigratingRemote,
+            ChangelogTableName::Invoice => ChangeLogSyncStyle::MigratingRemote,
+            ChangelogTableName::InvoiceLine => ChangeLogSyncStyle::MigratingRemote,
+            ChangelogTableName::Stocktake => ChangeLogSyncStyle::MigratingRemote,
+            ChangelogTableName::StocktakeLine => ChangeLogSyncStyle::MigratingRemote,
+            ChangelogTableName::Requisition => ChangeLogSyncStyle::MigratingRemote,
+            ChangelogTableName::RequisitionLine => ChangeLogSyncStyle::MigratingRemote,
             ChangelogTableName::ActivityLog => ChangeLogSyncStyle::Legacy,
             ChangelogTableName::InventoryAdjustmentReason => ChangeLogSyncStyle::Legacy,
             ChangelogTableName::Barcode => ChangeLogSyncStyle::Legacy,
@@ -138,8 +145,21 @@ impl ChangelogTableName {
             ChangelogTableName::Asset => ChangeLogSyncStyle::Remote,
             ChangelogTableName::SyncFileReference => ChangeLogSyncStyle::File,
             ChangelogTableName::AssetLog => ChangeLogSyncStyle::Remote,
+            ChangelogTableName::AssetTransfer => ChangeLogSyncStyle::Transfer,
+            ChangelogTableName::AssetDisposal => ChangeLogSyncStyle::Remote,
         }
     }
+
+    /// Remote record table that can be switched from legacy to open mSupply central server (v6) sync
+    pub fn can_sync_through_v6(&self) -> bool {
+        matches!(self.sync_style(), ChangeLogSyncStyle::MigratingRemote)
+    }
+
+    pub fn v6_switchable_tables() -> Vec<ChangelogTableName> {
+        ChangelogTableName::iter()
+            .filter(ChangelogTableName::can_sync_through_v6)
+            .collect()
+    }
 }
 
 #[derive(Debug, PartialEq, Insertable)]
@@ -242,8 +262,17 @@ impl<'a> ChangelogRepository<'a> {
         batch_size: u32,
         sync_site_id: i32,
         is_initialized: bool,
+        v6_sync_tables: &[ChangelogTableName],
+        table_name: Option<EqualFilter<ChangelogTableName>>,
     ) -> Result<Vec<ChangelogRow>, RepositoryError> {
-        let query = create_filtered_outgoing_sync_query(earliest, sync_site_id, is_initialized)
+        let mut query = create_filtered_outgoing_sync_query(
+            earliest,
+            sync_site_id,
+            is_initialized,
+            v6_sync_tables,
+        );
+        apply_equal_filter!(query, table_name, changelog_deduped::table_name);
+        let query = query
             .order(changelog_deduped::cursor.asc())
             .limit(batch_size.into());
 
@@ -277,13 +306,67 @@ impl<'a> ChangelogRepository<'a> {
         earliest: u64,
         sync_site_id: i32,
         is_initialized: bool,
+        v6_sync_tables: &[ChangelogTableName],
+        table_name: Option<EqualFilter<ChangelogTableName>>,
     ) -> Result<u64, RepositoryError> {
-        let result = create_filtered_outgoing_sync_query(earliest, sync_site_id, is_initialized)
+        let mut query = create_filtered_outgoing_sync_query(
+            earliest,
+            sync_site_id,
+            is_initialized,
+            v6_sync_tables,
+        );
+        apply_equal_filter!(query, table_name, changelog_deduped::table_name);
+        let result = query
             .count()
             .get_result::<i64>(&self.connection.connection)?;
         Ok(result as u64)
     }
 
+    /// Number of changelogs per table that would be sent to the site during initialisation,
+    /// tables without any changelogs are omitted
+    pub fn count_outgoing_sync_records_from_central_by_table(
+        &self,
+        sync_site_id: i32,
+        v6_sync_tables: &[ChangelogTableName],
+    ) -> Result<Vec<(ChangelogTableName, u64)>, RepositoryError> {
+        let mut result = Vec::new();
+
+        for table_name in ChangelogTableName::iter() {
+            let count = create_filtered_outgoing_sync_query(0, sync_site_id, false, v6_sync_tables)
+                .filter(changelog_deduped::table_name.eq(table_name.clone()))
+                .count()
+                .get_result::<i64>(&self.connection.connection)?;
+
+            if count > 0 {
+                result.push((table_name, count as u64));
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Record would be sent to the site by central server (e.g. to check site can access files of
+    /// the record), remote records switched to v6 sync are included
+    pub fn is_record_visible_to_site(
+        &self,
+        table_name: ChangelogTableName,
+        record_id: &str,
+        sync_site_id: i32,
+    ) -> Result<bool, RepositoryError> {
+        let count = create_filtered_outgoing_sync_query(
+            0,
+            sync_site_id,
+            false,
+            &ChangelogTableName::v6_switchable_tables(),
+        )
+        .filter(changelog_deduped::table_name.eq(table_name))
+        .filter(changelog_deduped::record_id.eq(record_id))
+        .count()
+        .get_result::<i64>(&self.connection.connection)?;
+
+        Ok(count > 0)
+    }
+
     /// Returns latest change log
     /// After initial sync we use this method to get the latest cursor to make sure we don't try to push any records that were synced to this site on initialisation
     pub fn latest_cursor(&self) -> Result<u64, RepositoryError> {
@@ -326,6 +409,29 @@ impl<'a> ChangelogRepository<'a> {
         Ok(())
     }
 
+    /// Used when sync integration results in a change that should be pushed, i.e. record
+    /// was merged with local changes
+    pub fn set_latest_is_sync_update(
+        &self,
+        table_name: ChangelogTableName,
+        record_id: &str,
+        is_sync_update: bool,
+    ) -> Result<(), RepositoryError> {
+        let latest_cursor = changelog::table
+            .select(diesel::dsl::max(changelog::cursor))
+            .filter(changelog::table_name.eq(table_name))
+            .filter(changelog::record_id.eq(record_id))
+            .first::<Option<i64>>(&self.connection.connection)?;
+
+        if let Some(latest_cursor) = latest_cursor {
+            diesel::update(changelog::table)
+                .set(changelog::is_sync_update.eq(is_sync_update))
+                .filter(changelog::cursor.eq(latest_cursor))
+                .execute(&self.connection.connection)?;
+        }
+        Ok(())
+    }
+
     /// Inserts a changelog record, and returns the cursor of the inserted record
     #[cfg(feature = "postgres")]
     pub fn insert(&self, row: &ChangeLogInsertRow) -> Result<i64, RepositoryError> {
@@ -396,7 +502,9 @@ fn create_filtered_query(earliest: u64, filter: Option<ChangelogFilter>) -> Boxe
 // (
 // 	table_name in {central_record_names}
 //  OR
-// 	(table_name in {transfer record names}  AND name_id IN {name_ids of active stores on remote site})
+// 	(table_name in {remote record names switched to v6 by remote site} AND (store_id IN {active stores on remote site} OR name_id IN {name_ids of active stores on remote site}))
+//  OR
+// 	(table_name in {transfer record names} AND (store_id IN {active stores on remote site} OR name_id IN {name_ids of active stores on remote site}))
 //  OR
 // 	// Special cases
 // 	(table_name in {patient record name} AND patient_id IN {select name_id from name_store_join where store_id in {active stores on remote site})
@@ -408,6 +516,7 @@ fn create_filtered_outgoing_sync_query(
     earliest: u64,
     sync_site_id: i32,
     is_initialized: bool,
+    v6_sync_tables: &[ChangelogTableName],
 ) -> BoxedChangelogQuery {
     let mut query = changelog_deduped::table
         .left_join(name_link::table)
@@ -438,10 +547,36 @@ fn create_filtered_outgoing_sync_query(
         .filter(|table| matches!(table.sync_style(), ChangeLogSyncStyle::Remote))
         .collect();
 
-    let active_stores_for_site = store::table
-        .filter(store::site_id.eq(sync_site_id))
-        .select(store::id.nullable())
-        .into_boxed();
+    // Remote records that are synced to the sites of both stores
+    let transfer_sync_table_names: Vec<ChangelogTableName> = ChangelogTableName::iter()
+        .filter(|table| matches!(table.sync_style(), ChangeLogSyncStyle::Transfer))
+        .collect();
+
+    // Remote records that are synced through legacy central server by default, only included
+    // if remote site switched the table to v6
+    let migrating_remote_sync_table_names: Vec<ChangelogTableName> = v6_sync_tables
+        .iter()
+        .filter(|table| table.can_sync_through_v6())
+        .cloned()
+        .collect();
+
+    // Transfers pushed to Open mSupply central server by sites that switched the table are not
+    // pushed to legacy central server, so they are sent to receiving site even if it didn't switch the table
+    let all_migrating_remote_table_names = ChangelogTableName::v6_switchable_tables();
+
+    let active_stores_for_site = || {
+        store::table
+            .filter(store::site_id.eq(sync_site_id))
+            .select(store::id.nullable())
+            .into_boxed()
+    };
+    // Transfer records (e.g. outbound shipment to a store on the site) are matched by name
+    let active_store_names_for_site = || {
+        store::table
+            .filter(store::site_id.eq(sync_site_id))
+            .select(store::name_id)
+            .into_boxed()
+    };
 
     // Filter the query for the matching records for each type
     query = query.filter(
@@ -450,7 +585,25 @@ fn create_filtered_outgoing_sync_query(
             .or(changelog_deduped::table_name.eq(ChangelogTableName::SyncFileReference)) // All sites get all sync file references (not necessarily files)
             .or(changelog_deduped::table_name
                 .eq_any(remote_sync_table_names)
-                .and(changelog_deduped::store_id.eq_any(active_stores_for_site))),
+                .and(changelog_deduped::store_id.eq_any(active_stores_for_site())))
+            .or(changelog_deduped::table_name
+                .eq_any(migrating_remote_sync_table_names)
+                .and(
+                    changelog_deduped::store_id
+                        .eq_any(active_stores_for_site())
+                        .or(name_link::name_id.eq_any(active_store_names_for_site())),
+                ))
+            .or(changelog_deduped::table_name
+                .eq_any(all_migrating_remote_table_names)
+                .and(changelog_deduped::source_site_id.is_not_null())
+                .and(name_link::name_id.eq_any(active_store_names_for_site())))
+            .or(changelog_deduped::table_name
+                .eq_any(transfer_sync_table_names)
+                .and(
+                    changelog_deduped::store_id
+                        .eq_any(active_stores_for_site())
+                        .or(name_link::name_id.eq_any(active_store_names_for_site())),
+                )),
         // Any other special cases could be handled here...
     );
 
@@ -5,6 +5,7 @@ use util::{inline_edit, inline_init};
 use crate::{
     asset_class_row::AssetClassRow,
     asset_row::AssetRow,
+    assets::asset_transfer_row::AssetTransferRow,
     mock::{
         mock_item_a, mock_location_1, mock_location_2, mock_location_in_another_store,
         mock_location_on_hold, mock_store_a, mock_store_b, MockData, MockDataInserts,
@@ -626,7 +627,7 @@ async fn test_changelog_outgoing_sync_records() {
     let repo = ChangelogRepository::new(&connection);
 
     let outgoing_results = repo
-        .outgoing_sync_records_from_central(0, 10, 1, true)
+        .outgoing_sync_records_from_central(0, 10, 1, true, &[], None)
         .unwrap();
     assert_eq!(outgoing_results.len(), 0); // Nothing to send to the remote site yet...
 
@@ -646,7 +647,7 @@ async fn test_changelog_outgoing_sync_records() {
     let _result = row.upsert(&connection).unwrap();
 
     let outgoing_results = repo
-        .outgoing_sync_records_from_central(0, 1000, 1, true)
+        .outgoing_sync_records_from_central(0, 1000, 1, true, &[], None)
         .unwrap();
     // outgoing_results should contain the changelog record for the asset class
     assert_eq!(outgoing_results.len(), 1);
@@ -671,7 +672,7 @@ async fn test_changelog_outgoing_sync_records() {
     // The asset class and the asset
 
     let outgoing_results = repo
-        .outgoing_sync_records_from_central(0, 1000, site1_id, false)
+        .outgoing_sync_records_from_central(0, 1000, site1_id, false, &[], None)
         .unwrap();
     assert_eq!(outgoing_results.len(), 2);
     assert_eq!(outgoing_results[0].record_id, asset_class_id);
@@ -679,15 +680,153 @@ async fn test_changelog_outgoing_sync_records() {
 
     // If not during initialisation, we should only get the asset_class as the asset was synced from the site already
     let outgoing_results = repo
-        .outgoing_sync_records_from_central(0, 1000, site1_id, true)
+        .outgoing_sync_records_from_central(0, 1000, site1_id, true, &[], None)
         .unwrap();
     assert_eq!(outgoing_results.len(), 1);
     assert_eq!(outgoing_results[0].record_id, asset_class_id);
 
     // Site 2 should only get the asset_class
     let outgoing_results = repo
-        .outgoing_sync_records_from_central(0, 1000, site2_id, true)
+        .outgoing_sync_records_from_central(0, 1000, site2_id, true, &[], None)
         .unwrap();
     assert_eq!(outgoing_results.len(), 1);
     assert_eq!(outgoing_results[0].record_id, asset_class_id);
+
+    // Insert a transfer from site 1 to store on site 2
+    let invoice_id = "transfer_invoice_id".to_string();
+    InvoiceRowRepository::new(&connection)
+        .upsert_one(&inline_init(|r: &mut InvoiceRow| {
+            r.id = invoice_id.clone();
+            r.name_link_id = mock_store_b().name_id;
+            r.store_id = site1_store_id.clone();
+        }))
+        .unwrap();
+
+    // Invoices are synced through legacy central server unless the table is switched to v6
+    let outgoing_results = repo
+        .outgoing_sync_records_from_central(0, 1000, site2_id, true, &[], None)
+        .unwrap();
+    assert_eq!(outgoing_results.len(), 1);
+
+    // Site 2 gets the transfer (matched by name of store on site 2)
+    let outgoing_results = repo
+        .outgoing_sync_records_from_central(
+            0,
+            1000,
+            site2_id,
+            true,
+            &[ChangelogTableName::Invoice],
+            None,
+        )
+        .unwrap();
+    assert_eq!(outgoing_results.len(), 2);
+    assert_eq!(outgoing_results[1].record_id, invoice_id);
+
+    // Site 1 gets the invoice (matched by store on site 1) during initialisation
+    let outgoing_results = repo
+        .outgoing_sync_records_from_central(
+            0,
+            1000,
+            site1_id,
+            false,
+            &[ChangelogTableName::Invoice],
+            None,
+        )
+        .unwrap();
+    assert_eq!(outgoing_results.len(), 3);
+    assert_eq!(outgoing_results[2].record_id, invoice_id);
+
+    // Tables that can't be switched to v6 are ignored
+    let outgoing_results = repo
+        .outgoing_sync_records_from_central(
+            0,
+            1000,
+            site2_id,
+            true,
+            &[ChangelogTableName::Name],
+            None,
+        )
+        .unwrap();
+    assert_eq!(outgoing_results.len(), 1);
+
+    // Asset transfer from store on site 1 to store on site 2 is sent to both sites
+    let asset_transfer_id = "asset_transfer_id".to_string();
+    AssetTransferRow {
+        id: asset_transfer_id.clone(),
+        asset_id: asset_id.clone(),
+        from_store_id: site1_store_id.clone(),
+        to_store_id: mock_store_b().id,
+        ..Default::default()
+    }
+    .upsert(&connection)
+    .unwrap();
+
+    let outgoing_results = repo
+        .outgoing_sync_records_from_central(0, 1000, site2_id, true, &[], None)
+        .unwrap();
+    assert_eq!(outgoing_results.len(), 2);
+    assert_eq!(outgoing_results[1].record_id, asset_transfer_id);
+
+    let outgoing_results = repo
+        .outgoing_sync_records_from_central(0, 1000, site1_id, true, &[], None)
+        .unwrap();
+    assert_eq!(outgoing_results.len(), 2);
+    assert_eq!(outgoing_results[1].record_id, asset_transfer_id);
+
+    // Asset is only visible to the site of its store, invoice is visible once switched to v6
+    assert!(repo
+        .is_record_visible_to_site(ChangelogTableName::Asset, &asset_id, site1_id)
+        .unwrap());
+    assert!(!repo
+        .is_record_visible_to_site(ChangelogTableName::Asset, &asset_id, site2_id)
+        .unwrap());
+    assert!(repo
+        .is_record_visible_to_site(ChangelogTableName::Invoice, &invoice_id, site2_id)
+        .unwrap());
+    assert!(!repo
+        .is_record_visible_to_site(ChangelogTableName::Asset, "unknown", site1_id)
+        .unwrap());
+
+    // Transfer pushed by site 1 through v6 is sent to site 2 even if it didn't switch the table
+    let invoice_changelog = repo
+        .changelogs(
+            0,
+            1000,
+            Some(ChangelogFilter::new().record_id(EqualFilter::equal_to(&invoice_id))),
+        )
+        .unwrap()
+        .pop()
+        .unwrap();
+    repo.set_source_site_id_and_is_sync_update(invoice_changelog.cursor, Some(site1_id))
+        .unwrap();
+
+    let outgoing_results = repo
+        .outgoing_sync_records_from_central(0, 1000, site2_id, true, &[], None)
+        .unwrap();
+    assert_eq!(outgoing_results.len(), 3);
+    assert_eq!(outgoing_results[1].record_id, invoice_id);
+
+    // But not back to site 1 (unless switched and initialising)
+    let outgoing_results = repo
+        .outgoing_sync_records_from_central(0, 1000, site1_id, true, &[], None)
+        .unwrap();
+    assert_eq!(outgoing_results.len(), 2);
+
+    // Table filter (used to defer tables while site is over bandwidth budget)
+    let outgoing_results = repo
+        .outgoing_sync_records_from_central(
+            0,
+            1000,
+            site2_id,
+            true,
+            &[],
+            Some(inline_init(|r: &mut EqualFilter<ChangelogTableName>| {
+                r.not_equal_all = Some(vec![ChangelogTableName::Invoice])
+            })),
+        )
+        .unwrap();
+    assert_eq!(outgoing_results.len(), 2);
+    assert!(outgoing_results
+        .iter()
+        .all(|changelog| changelog.record_id != invoice_id));
 }
@@ -27,6 +27,19 @@ pub enum KeyValueType {
     RemoteSyncPushCursor,
     ShipmentTransferProcessorCursor,
     RequisitionTransferProcessorCursor,
+    TemperatureBreachProcessorCursor,
+    SyncBundleExportSequence,
+    SyncBundleImportSequence,
+    SyncBundlePendingAcknowledgements,
+    SyncInitialisationExpectedCounts,
+    SyncBandwidthDailyUsage,
+    RemoteSyncPushDeferredCursor,
+    SyncPushDeferredCursorV6,
+    SyncPullDeferredCursorV6,
+    ColdChainNotificationStartDatetime,
+    SyncBufferFailedCountHistory,
+    SyncV6Tables,
+    SyncScope,
 
     SettingsSyncUrl,
     SettingsSyncUsername,
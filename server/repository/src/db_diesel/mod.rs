@@ -57,10 +57,12 @@ pub mod name_store_join;
 pub mod name_tag;
 pub mod name_tag_join;
 mod name_tag_row;
+mod notification_log_row;
 mod number_row;
 pub mod pack_variant;
 mod pack_variant_row;
 mod patient;
+mod peer_sync_site_row;
 pub mod period;
 pub mod plugin_data;
 mod plugin_data_row;
@@ -76,6 +78,7 @@ pub mod requisition_line;
 pub mod return_reason;
 mod return_reason_row;
 pub mod sensor;
+mod sensor_api_key_row;
 mod sensor_row;
 pub mod stock_line;
 mod stock_line_row;
@@ -87,15 +90,22 @@ mod stocktake_line_row;
 mod stocktake_row;
 mod storage_connection;
 pub mod store;
+mod store_population_row;
 mod store_preference_row;
 mod store_row;
 pub mod sync_buffer;
+mod sync_bundle_site_row;
+mod sync_conflict_row;
+mod sync_health_row;
 pub mod sync_log;
 mod sync_log_row;
+mod sync_record_version_row;
+mod sync_scope;
 pub mod temperature_breach;
 pub mod temperature_breach_config;
 mod temperature_breach_config_row;
 mod temperature_breach_row;
+mod temperature_breach_stock_impact_row;
 pub mod temperature_chart;
 mod temperature_excursion;
 pub mod temperature_log;
@@ -151,10 +161,12 @@ pub use name_store_join::*;
 pub use name_tag::*;
 pub use name_tag_join::*;
 pub use name_tag_row::*;
+pub use notification_log_row::*;
 pub use number_row::*;
 pub use pack_variant::*;
 pub use pack_variant_row::*;
 pub use patient::*;
+pub use peer_sync_site_row::*;
 pub use period::*;
 pub use plugin_data::*;
 pub use plugin_data_row::*;
@@ -170,6 +182,7 @@ pub use requisition::*;
 pub use requisition_line::*;
 pub use return_reason_row::*;
 pub use sensor::*;
+pub use sensor_api_key_row::*;
 pub use sensor_row::*;
 pub use stock_line::*;
 pub use stock_line_row::*;
@@ -181,17 +194,24 @@ pub use stocktake_line_row::*;
 pub use stocktake_row::*;
 pub use storage_connection::*;
 pub use store::*;
+pub use store_population_row::*;
 pub use store_preference_row::*;
 pub use store_row::*;
 pub use sync_buffer::*;
+pub use sync_bundle_site_row::*;
+pub use sync_conflict_row::*;
 pub use sync_file_reference::*;
 pub use sync_file_reference_row::*;
+pub use sync_health_row::*;
 pub use sync_log::*;
 pub use sync_log_row::*;
+pub use sync_record_version_row::*;
+pub use sync_scope::*;
 pub use temperature_breach::*;
 pub use temperature_breach_config::*;
 pub use temperature_breach_config_row::*;
 pub use temperature_breach_row::*;
+pub use temperature_breach_stock_impact_row::*;
 pub use temperature_chart::*;
 pub use temperature_excursion::*;
 pub use temperature_log::*;
@@ -0,0 +1,218 @@
+use super::notification_log_row::notification_log::dsl::*;
+
+use crate::{RepositoryError, StorageConnection};
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel_derive_enum::DbEnum;
+
+table! {
+    notification_log (id) {
+        id -> Text,
+        store_id -> Text,
+        event_type -> crate::db_diesel::notification_log_row::NotificationEventTypeMapping,
+        record_id -> Text,
+        channel -> Text,
+        recipient -> Nullable<Text>,
+        status -> crate::db_diesel::notification_log_row::NotificationStatusMapping,
+        message -> Text,
+        error -> Nullable<Text>,
+        created_datetime -> Timestamp,
+    }
+}
+
+#[derive(
+    DbEnum, Debug, Clone, PartialEq, Eq, Hash, Default, serde::Serialize, serde::Deserialize,
+)]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+pub enum NotificationEventType {
+    /// Record is the temperature breach
+    #[default]
+    TemperatureBreach,
+    /// Record is the sensor
+    SensorBatteryLow,
+    /// Record is the sensor
+    SensorNotReporting,
+    /// Temperature breach still unacknowledged after the escalation time, record is the breach
+    TemperatureBreachEscalation,
+}
+
+#[derive(DbEnum, Debug, Clone, PartialEq, Eq, Default)]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+pub enum NotificationStatus {
+    #[default]
+    Sent,
+    Failed,
+}
+
+/// Notification sent (or attempted) through a configured channel, local to the site
+#[derive(Clone, Insertable, Queryable, Debug, PartialEq, AsChangeset, Default)]
+#[table_name = "notification_log"]
+#[changeset_options(treat_none_as_null = "true")]
+pub struct NotificationLogRow {
+    pub id: String,
+    pub store_id: String,
+    pub event_type: NotificationEventType,
+    pub record_id: String,
+    /// Name of the channel in notification settings
+    pub channel: String,
+    /// Set for channels that notify each recipient separately (SMS)
+    pub recipient: Option<String>,
+    pub status: NotificationStatus,
+    pub message: String,
+    pub error: Option<String>,
+    pub created_datetime: NaiveDateTime,
+}
+
+pub struct NotificationLogRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> NotificationLogRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        NotificationLogRowRepository { connection }
+    }
+
+    pub fn insert_one(&self, row: &NotificationLogRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(notification_log)
+            .values(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    pub fn find_many_by_store_id(
+        &self,
+        store: &str,
+        limit: u32,
+    ) -> Result<Vec<NotificationLogRow>, RepositoryError> {
+        let result = notification_log
+            .filter(store_id.eq(store))
+            .order(created_datetime.desc())
+            .limit(limit as i64)
+            .load(&self.connection.connection)?;
+        Ok(result)
+    }
+
+    /// Logs of an event for a record, channel and recipient, oldest first
+    pub fn find_many_by_event(
+        &self,
+        r#type: &NotificationEventType,
+        record: &str,
+        channel_name: &str,
+        recipient_name: Option<&str>,
+    ) -> Result<Vec<NotificationLogRow>, RepositoryError> {
+        let mut query = notification_log
+            .filter(event_type.eq(r#type))
+            .filter(record_id.eq(record))
+            .filter(channel.eq(channel_name))
+            .into_boxed();
+        query = match recipient_name {
+            Some(recipient_name) => query.filter(recipient.eq(recipient_name)),
+            None => query.filter(recipient.is_null()),
+        };
+
+        let result = query
+            .order(created_datetime.asc())
+            .load(&self.connection.connection)?;
+        Ok(result)
+    }
+
+    /// Number of notifications sent through a channel since `from`, used for rate limiting
+    pub fn count_sent(
+        &self,
+        channel_name: &str,
+        from: NaiveDateTime,
+    ) -> Result<i64, RepositoryError> {
+        let result = notification_log
+            .filter(channel.eq(channel_name))
+            .filter(status.eq(NotificationStatus::Sent))
+            .filter(created_datetime.ge(from))
+            .count()
+            .get_result(&self.connection.connection)?;
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{Duration, NaiveDate};
+
+    use crate::{
+        mock::MockDataInserts, test_db::setup_all, NotificationEventType, NotificationLogRow,
+        NotificationLogRowRepository, NotificationStatus,
+    };
+
+    #[actix_rt::test]
+    async fn notification_log_row() {
+        let (_, connection, _, _) =
+            setup_all("notification_log_row", MockDataInserts::none()).await;
+        let repo = NotificationLogRowRepository::new(&connection);
+
+        let datetime = NaiveDate::from_ymd_opt(2024, 1, 1)
+            .unwrap()
+            .and_hms_opt(10, 0, 0)
+            .unwrap();
+        let sent = NotificationLogRow {
+            id: "sent".to_string(),
+            store_id: "store".to_string(),
+            event_type: NotificationEventType::TemperatureBreach,
+            record_id: "breach".to_string(),
+            channel: "email".to_string(),
+            recipient: None,
+            status: NotificationStatus::Sent,
+            message: "Breach".to_string(),
+            error: None,
+            created_datetime: datetime,
+        };
+        let failed = NotificationLogRow {
+            id: "failed".to_string(),
+            status: NotificationStatus::Failed,
+            error: Some("Connection refused".to_string()),
+            created_datetime: datetime + Duration::minutes(5),
+            ..sent.clone()
+        };
+        let sms = NotificationLogRow {
+            id: "sms".to_string(),
+            channel: "sms".to_string(),
+            recipient: Some("+64211111111".to_string()),
+            ..sent.clone()
+        };
+        repo.insert_one(&failed).unwrap();
+        repo.insert_one(&sent).unwrap();
+        repo.insert_one(&sms).unwrap();
+
+        assert_eq!(
+            repo.find_many_by_event(
+                &NotificationEventType::TemperatureBreach,
+                "breach",
+                "email",
+                None
+            ),
+            Ok(vec![sent.clone(), failed.clone()])
+        );
+        assert_eq!(
+            repo.find_many_by_event(
+                &NotificationEventType::TemperatureBreach,
+                "breach",
+                "sms",
+                Some("+64211111111")
+            ),
+            Ok(vec![sms])
+        );
+        assert_eq!(
+            repo.find_many_by_event(
+                &NotificationEventType::TemperatureBreach,
+                "breach",
+                "sms",
+                Some("+64222222222")
+            ),
+            Ok(vec![])
+        );
+        assert_eq!(repo.count_sent("email", datetime), Ok(1));
+        assert_eq!(
+            repo.count_sent("email", datetime + Duration::minutes(1)),
+            Ok(0)
+        );
+        assert_eq!(repo.find_many_by_store_id("store", 1), Ok(vec![failed]));
+    }
+}
@@ -0,0 +1,88 @@
+use super::peer_sync_site_row::peer_sync_site::dsl::*;
+
+use crate::{RepositoryError, StorageConnection};
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+table! {
+    peer_sync_site (id) {
+        id -> Text,
+        url -> Text,
+        site_id -> Nullable<Integer>,
+        last_seen_datetime -> Timestamp,
+        pull_cursor -> BigInt,
+        last_sync_datetime -> Nullable<Timestamp>,
+        last_error -> Nullable<Text>,
+    }
+}
+
+/// Remote site discovered on the local network, used for peer to peer sync of transfer records.
+/// This table is local to the site and is not synced
+#[derive(Clone, Insertable, Queryable, Debug, PartialEq, AsChangeset, Default)]
+#[table_name = "peer_sync_site"]
+#[changeset_options(treat_none_as_null = "true")]
+pub struct PeerSyncSiteRow {
+    /// Hardware id advertised by the peer
+    pub id: String,
+    pub url: String,
+    /// Set after first successful pull from the peer
+    pub site_id: Option<i32>,
+    pub last_seen_datetime: NaiveDateTime,
+    /// Changelog cursor on the peer to pull from
+    pub pull_cursor: i64,
+    pub last_sync_datetime: Option<NaiveDateTime>,
+    pub last_error: Option<String>,
+}
+
+pub struct PeerSyncSiteRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> PeerSyncSiteRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        PeerSyncSiteRowRepository { connection }
+    }
+
+    #[cfg(feature = "postgres")]
+    pub fn upsert_one(&self, row: &PeerSyncSiteRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(peer_sync_site)
+            .values(row)
+            .on_conflict(id)
+            .do_update()
+            .set(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "postgres"))]
+    pub fn upsert_one(&self, row: &PeerSyncSiteRow) -> Result<(), RepositoryError> {
+        diesel::replace_into(peer_sync_site)
+            .values(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        peer_id: &str,
+    ) -> Result<Option<PeerSyncSiteRow>, RepositoryError> {
+        let result = peer_sync_site
+            .filter(id.eq(peer_id))
+            .first(&self.connection.connection)
+            .optional()?;
+        Ok(result)
+    }
+
+    /// Peers seen on the network since `seen_since`
+    pub fn find_many_seen_since(
+        &self,
+        seen_since: NaiveDateTime,
+    ) -> Result<Vec<PeerSyncSiteRow>, RepositoryError> {
+        let result = peer_sync_site
+            .filter(last_seen_datetime.ge(seen_since))
+            .order(id.asc())
+            .load(&self.connection.connection)?;
+        Ok(result)
+    }
+}
@@ -15,6 +15,7 @@ table! {
         order_type -> Text,
         count -> BigInt,
         #[sql_name = "type"] type_ -> crate::db_diesel::requisition::requisition_row::RequisitionRowTypeMapping,
+        is_emergency -> Bool,
     }
 }
 
@@ -25,6 +26,7 @@ pub struct RequisitionsInPeriodFilter {
     pub store_id: Option<EqualFilter<String>>,
     pub order_type: Option<StringFilter>,
     pub r#type: Option<EqualFilter<RequisitionRowType>>,
+    pub is_emergency: Option<bool>,
 }
 
 #[derive(Clone, Queryable, AsChangeset, Insertable, Debug, PartialEq)]
@@ -33,11 +35,12 @@ pub struct RequisitionsInPeriod {
     id: String,
     pub program_id: String,
     pub period_id: String,
-    store_id: String,
+    pub store_id: String,
     pub order_type: String,
     pub count: i64,
     #[column_name = "type_"]
     pub r#type: RequisitionRowType,
+    pub is_emergency: bool,
 }
 
 impl Default for RequisitionsInPeriod {
@@ -51,6 +54,7 @@ impl Default for RequisitionsInPeriod {
             store_id: Default::default(),
             order_type: Default::default(),
             count: Default::default(),
+            is_emergency: Default::default(),
         }
     }
 }
@@ -74,6 +78,7 @@ impl<'a> RequisitionsInPeriodRepository<'a> {
             store_id,
             order_type,
             r#type,
+            is_emergency,
         }: RequisitionsInPeriodFilter,
     ) -> Result<Vec<RequisitionsInPeriod>, RepositoryError> {
         let mut query = requisitions_in_period_dsl::requisitions_in_period.into_boxed();
@@ -84,6 +89,10 @@ impl<'a> RequisitionsInPeriodRepository<'a> {
         apply_equal_filter!(query, r#type, requisitions_in_period_dsl::type_);
         apply_string_filter!(query, order_type, requisitions_in_period_dsl::order_type);
 
+        if let Some(is_emergency) = is_emergency {
+            query = query.filter(requisitions_in_period_dsl::is_emergency.eq(is_emergency));
+        }
+
         //  Debug diesel query
         // println!(
         //     "{}",
@@ -125,6 +134,11 @@ impl RequisitionsInPeriodFilter {
         self.r#type = Some(filter);
         self
     }
+
+    pub fn is_emergency(mut self, value: bool) -> Self {
+        self.is_emergency = Some(value);
+        self
+    }
 }
 
 #[cfg(test)]
@@ -312,6 +326,7 @@ mod test {
                     order_type: "Order Type 1".to_string(),
                     count: 2,
                     r#type: RequisitionRowType::Request,
+                    is_emergency: false,
                 },
                 RequisitionsInPeriod {
                     id: "n/a".to_string(),
@@ -321,6 +336,7 @@ mod test {
                     order_type: "Order Type 2".to_string(),
                     count: 1,
                     r#type: RequisitionRowType::Request,
+                    is_emergency: false,
                 },
             ]
         );
@@ -345,6 +361,7 @@ mod test {
                     order_type: "Order Type 1".to_string(),
                     count: 2,
                     r#type: RequisitionRowType::Request,
+                    is_emergency: false,
                 },
                 RequisitionsInPeriod {
                     id: "n/a".to_string(),
@@ -354,6 +371,7 @@ mod test {
                     order_type: "Order Type 1".to_string(),
                     count: 1,
                     r#type: RequisitionRowType::Request,
+                    is_emergency: false,
                 },
                 RequisitionsInPeriod {
                     id: "n/a".to_string(),
@@ -363,6 +381,7 @@ mod test {
                     order_type: "Order Type 2".to_string(),
                     count: 1,
                     r#type: RequisitionRowType::Request,
+                    is_emergency: false,
                 },
             ]
         );
@@ -387,6 +406,7 @@ mod test {
                 order_type: "Order Type 2".to_string(),
                 count: 1,
                 r#type: RequisitionRowType::Request,
+                is_emergency: false,
             }]
         );
     }
@@ -27,6 +27,9 @@ pub struct RequisitionFilter {
     pub store_id: Option<EqualFilter<String>>,
     pub linked_requisition_id: Option<EqualFilter<String>>,
     pub order_type: Option<EqualFilter<String>>,
+    pub is_emergency: Option<bool>,
+    pub program_id: Option<EqualFilter<String>>,
+    pub period_id: Option<EqualFilter<String>>,
 }
 
 #[derive(PartialEq, Debug)]
@@ -133,6 +136,21 @@ impl RequisitionFilter {
         self
     }
 
+    pub fn is_emergency(mut self, value: bool) -> Self {
+        self.is_emergency = Some(value);
+        self
+    }
+
+    pub fn program_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.program_id = Some(filter);
+        self
+    }
+
+    pub fn period_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.period_id = Some(filter);
+        self
+    }
+
     pub fn by_id(id: &str) -> RequisitionFilter {
         RequisitionFilter::new().id(EqualFilter::equal_to(id))
     }
@@ -155,6 +173,10 @@ impl RequisitionRowStatus {
     pub fn not_equal_to(&self) -> EqualFilter<Self> {
         inline_init(|r: &mut EqualFilter<Self>| r.not_equal_to = Some(self.clone()))
     }
+
+    pub fn equal_any(value: Vec<Self>) -> EqualFilter<Self> {
+        inline_init(|r: &mut EqualFilter<Self>| r.equal_any = Some(value))
+    }
 }
 
 impl RequisitionRowType {
@@ -177,6 +177,9 @@ fn create_filtered_query(
         store_id,
         linked_requisition_id,
         order_type,
+        is_emergency,
+        program_id,
+        period_id,
     }) = filter
     {
         apply_equal_filter!(query, id, requisition_dsl::id);
@@ -214,7 +217,13 @@ fn create_filtered_query(
         apply_string_filter!(query, comment, requisition_dsl::comment);
 
         apply_equal_filter!(query, store_id, requisition_dsl::store_id);
-        apply_equal_filter!(query, order_type, requisition_dsl::order_type)
+        apply_equal_filter!(query, order_type, requisition_dsl::order_type);
+        apply_equal_filter!(query, program_id, requisition_dsl::program_id);
+        apply_equal_filter!(query, period_id, requisition_dsl::period_id);
+
+        if let Some(is_emergency) = is_emergency {
+            query = query.filter(requisition_dsl::is_emergency.eq(is_emergency));
+        }
     }
 
     Ok(query)
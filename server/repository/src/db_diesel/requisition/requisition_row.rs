@@ -38,6 +38,8 @@ table! {
         program_id -> Nullable<Text>,
         period_id -> Nullable<Text>,
         order_type -> Nullable<Text>,
+        is_emergency -> Bool,
+        emergency_reason -> Nullable<Text>,
     }
 }
 
@@ -111,6 +113,8 @@ pub struct RequisitionRow {
     pub program_id: Option<String>,
     pub period_id: Option<String>,
     pub order_type: Option<String>,
+    pub is_emergency: bool,
+    pub emergency_reason: Option<String>,
 }
 
 impl Default for RequisitionRow {
@@ -138,6 +142,8 @@ impl Default for RequisitionRow {
             program_id: None,
             period_id: None,
             order_type: None,
+            is_emergency: false,
+            emergency_reason: None,
         }
     }
 }
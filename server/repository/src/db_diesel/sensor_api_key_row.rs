@@ -0,0 +1,144 @@
+use super::sensor_api_key_row::sensor_api_key::dsl::*;
+
+use crate::{RepositoryError, StorageConnection};
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+table! {
+    sensor_api_key (id) {
+        id -> Text,
+        store_id -> Text,
+        serial -> Text,
+        key_hash -> Text,
+        is_active -> Bool,
+        created_datetime -> Timestamp,
+        last_used_datetime -> Nullable<Timestamp>,
+    }
+}
+
+/// API key used by a network connected logger to push readings of the sensor with the serial,
+/// local to the site that receives the readings
+#[derive(Clone, Insertable, Queryable, Debug, PartialEq, AsChangeset, Default)]
+#[table_name = "sensor_api_key"]
+#[changeset_options(treat_none_as_null = "true")]
+pub struct SensorApiKeyRow {
+    pub id: String,
+    /// Store of the sensor, unknown serials are registered in this store
+    pub store_id: String,
+    pub serial: String,
+    /// Only the sha256 hash of the key is stored
+    pub key_hash: String,
+    pub is_active: bool,
+    pub created_datetime: NaiveDateTime,
+    pub last_used_datetime: Option<NaiveDateTime>,
+}
+
+pub struct SensorApiKeyRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> SensorApiKeyRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        SensorApiKeyRowRepository { connection }
+    }
+
+    #[cfg(feature = "postgres")]
+    pub fn upsert_one(&self, row: &SensorApiKeyRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(sensor_api_key)
+            .values(row)
+            .on_conflict(id)
+            .do_update()
+            .set(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "postgres"))]
+    pub fn upsert_one(&self, row: &SensorApiKeyRow) -> Result<(), RepositoryError> {
+        diesel::replace_into(sensor_api_key)
+            .values(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        record_id: &str,
+    ) -> Result<Option<SensorApiKeyRow>, RepositoryError> {
+        let result = sensor_api_key
+            .filter(id.eq(record_id))
+            .first(&self.connection.connection)
+            .optional()?;
+        Ok(result)
+    }
+
+    /// Active key with the hash
+    pub fn find_one_active_by_key_hash(
+        &self,
+        hash: &str,
+    ) -> Result<Option<SensorApiKeyRow>, RepositoryError> {
+        let result = sensor_api_key
+            .filter(key_hash.eq(hash))
+            .filter(is_active.eq(true))
+            .first(&self.connection.connection)
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_many_by_store_id(
+        &self,
+        store: &str,
+    ) -> Result<Vec<SensorApiKeyRow>, RepositoryError> {
+        let result = sensor_api_key
+            .filter(store_id.eq(store))
+            .order(created_datetime.asc())
+            .load(&self.connection.connection)?;
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+
+    use crate::{
+        mock::MockDataInserts, test_db::setup_all, SensorApiKeyRow, SensorApiKeyRowRepository,
+    };
+
+    #[actix_rt::test]
+    async fn sensor_api_key_row() {
+        let (_, connection, _, _) = setup_all("sensor_api_key_row", MockDataInserts::none()).await;
+        let repo = SensorApiKeyRowRepository::new(&connection);
+
+        let key = SensorApiKeyRow {
+            id: "key".to_string(),
+            store_id: "store".to_string(),
+            serial: "serial".to_string(),
+            key_hash: "hash".to_string(),
+            is_active: true,
+            created_datetime: NaiveDate::from_ymd_opt(2024, 1, 1)
+                .unwrap()
+                .and_hms_opt(10, 0, 0)
+                .unwrap(),
+            last_used_datetime: None,
+        };
+        repo.upsert_one(&key).unwrap();
+
+        assert_eq!(
+            repo.find_one_active_by_key_hash("hash"),
+            Ok(Some(key.clone()))
+        );
+        assert_eq!(repo.find_one_active_by_key_hash("other"), Ok(None));
+
+        let revoked = SensorApiKeyRow {
+            is_active: false,
+            ..key.clone()
+        };
+        repo.upsert_one(&revoked).unwrap();
+
+        assert_eq!(repo.find_one_active_by_key_hash("hash"), Ok(None));
+        assert_eq!(repo.find_one_by_id("key"), Ok(Some(revoked.clone())));
+        assert_eq!(repo.find_many_by_store_id("store"), Ok(vec![revoked]));
+    }
+}
@@ -34,6 +34,8 @@ pub enum SensorType {
     BlueMaestro,
     Laird,
     Berlinger,
+    /// Sensor of temperature logger files read with a configured parser
+    Generic,
 }
 
 // TODO put this somewhere more sensible
@@ -43,6 +45,7 @@ pub fn get_sensor_type(serial: &str) -> SensorType {
         Some("BLUE_MAESTRO") => SensorType::BlueMaestro,
         Some("LAIRD") => SensorType::Laird,
         Some("BERLINGER") => SensorType::Berlinger,
+        Some("GENERIC") => SensorType::Generic,
         _ => SensorType::BlueMaestro,
     }
 }
@@ -1,5 +1,7 @@
 use super::{
+    name_link_row::name_link::dsl as name_link_dsl,
     name_row::{name, name::dsl as name_dsl},
+    name_tag_join::name_tag_join::dsl as name_tag_join_dsl,
     store_row::{store, store::dsl as store_dsl},
     NameRow, StorageConnection, StoreRow,
 };
@@ -26,6 +28,7 @@ pub struct StoreFilter {
     pub name: Option<StringFilter>,
     pub name_code: Option<StringFilter>,
     pub site_id: Option<EqualFilter<i32>>,
+    pub name_tag_id: Option<EqualFilter<String>>,
 }
 
 #[derive(PartialEq, Debug)]
@@ -73,6 +76,11 @@ impl StoreFilter {
         self.site_id = Some(filter);
         self
     }
+
+    pub fn name_tag_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.name_tag_id = Some(filter);
+        self
+    }
 }
 
 pub struct StoreRepository<'a> {
@@ -142,6 +150,7 @@ fn create_filtered_query(filter: Option<StoreFilter>) -> BoxedStoreQuery {
             name,
             name_code,
             site_id,
+            name_tag_id,
         } = f;
 
         apply_equal_filter!(query, id, store_dsl::id);
@@ -150,6 +159,17 @@ fn create_filtered_query(filter: Option<StoreFilter>) -> BoxedStoreQuery {
         apply_string_filter!(query, name, name_dsl::name_);
         apply_string_filter!(query, name_code, name_dsl::code);
         apply_equal_filter!(query, site_id, store_dsl::site_id);
+
+        if name_tag_id.is_some() {
+            let mut name_tag_query = name_tag_join_dsl::name_tag_join
+                .inner_join(name_link_dsl::name_link)
+                .select(name_link_dsl::name_id)
+                .into_boxed();
+
+            apply_equal_filter!(name_tag_query, name_tag_id, name_tag_join_dsl::name_tag_id);
+
+            query = query.filter(store_dsl::name_id.eq_any(name_tag_query));
+        }
     }
 
     query
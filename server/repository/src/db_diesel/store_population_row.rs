@@ -0,0 +1,110 @@
+use super::store_population_row::store_population::dsl::*;
+
+use crate::{RepositoryError, StorageConnection};
+
+use diesel::prelude::*;
+
+table! {
+    store_population (store_id) {
+        store_id -> Text,
+        population_served -> Integer,
+    }
+}
+
+/// Population served by a facility, used to calculate required cold chain storage capacity
+#[derive(Clone, Insertable, Queryable, Debug, PartialEq, Eq, AsChangeset, Default)]
+#[table_name = "store_population"]
+pub struct StorePopulationRow {
+    pub store_id: String,
+    pub population_served: i32,
+}
+
+pub struct StorePopulationRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> StorePopulationRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        StorePopulationRowRepository { connection }
+    }
+
+    #[cfg(feature = "postgres")]
+    pub fn upsert_one(&self, row: &StorePopulationRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(store_population)
+            .values(row)
+            .on_conflict(store_id)
+            .do_update()
+            .set(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "postgres"))]
+    pub fn upsert_one(&self, row: &StorePopulationRow) -> Result<(), RepositoryError> {
+        diesel::replace_into(store_population)
+            .values(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    pub fn find_one_by_store_id(
+        &self,
+        store: &str,
+    ) -> Result<Option<StorePopulationRow>, RepositoryError> {
+        let result = store_population
+            .filter(store_id.eq(store))
+            .first(&self.connection.connection)
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_all(&self) -> Result<Vec<StorePopulationRow>, RepositoryError> {
+        let result = store_population
+            .order(store_id.asc())
+            .load(&self.connection.connection)?;
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        mock::{mock_store_a, mock_store_b, MockDataInserts},
+        test_db::setup_all,
+        StorePopulationRow, StorePopulationRowRepository,
+    };
+
+    #[actix_rt::test]
+    async fn store_population_row() {
+        let (_, connection, _, _) = setup_all(
+            "store_population_row",
+            MockDataInserts::none().names().stores(),
+        )
+        .await;
+        let repo = StorePopulationRowRepository::new(&connection);
+
+        let row_a = StorePopulationRow {
+            store_id: mock_store_a().id,
+            population_served: 1000,
+        };
+        let row_b = StorePopulationRow {
+            store_id: mock_store_b().id,
+            population_served: 500,
+        };
+        repo.upsert_one(&row_b).unwrap();
+        repo.upsert_one(&row_a).unwrap();
+
+        let row_a = StorePopulationRow {
+            population_served: 2000,
+            ..row_a
+        };
+        repo.upsert_one(&row_a).unwrap();
+
+        assert_eq!(
+            repo.find_one_by_store_id(&mock_store_a().id),
+            Ok(Some(row_a.clone()))
+        );
+        assert_eq!(repo.find_one_by_store_id("unknown"), Ok(None));
+        assert_eq!(repo.find_all(), Ok(vec![row_a, row_b]));
+    }
+}
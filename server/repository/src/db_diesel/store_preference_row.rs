@@ -20,6 +20,8 @@ table! {
         om_program_module -> Bool,
         vaccine_module -> Bool,
         issue_in_foreign_currency -> Bool,
+        use_lead_time_in_suggested_quantity -> Bool,
+        requisition_over_order_threshold_percentage -> Integer,
     }
 }
 
@@ -47,6 +49,12 @@ pub struct StorePreferenceRow {
     pub om_program_module: bool,
     pub vaccine_module: bool,
     pub issue_in_foreign_currency: bool,
+    /// Measured supplier lead time is added to months of stock when calculating suggested
+    /// quantity of request requisition lines
+    pub use_lead_time_in_suggested_quantity: bool,
+    /// Response requisition lines requesting more than this percentage above the average
+    /// requested in previous periods are flagged as over ordered, 0 turns the flag off
+    pub requisition_over_order_threshold_percentage: i32,
 }
 
 impl Default for StorePreferenceRow {
@@ -60,6 +68,8 @@ impl Default for StorePreferenceRow {
             om_program_module: Default::default(),
             vaccine_module: Default::default(),
             issue_in_foreign_currency: Default::default(),
+            use_lead_time_in_suggested_quantity: Default::default(),
+            requisition_over_order_threshold_percentage: Default::default(),
         }
     }
 }
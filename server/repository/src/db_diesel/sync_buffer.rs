@@ -2,10 +2,15 @@ use super::StorageConnection;
 use crate::{
     diesel_macros::{apply_date_time_filter, apply_equal_filter},
     repository_error::RepositoryError,
-    DBType, DatetimeFilter, EqualFilter,
+    DBType, DatetimeFilter, EqualFilter, Pagination,
 };
 use chrono::NaiveDateTime;
-use diesel::{dsl::IntoBoxed, prelude::*};
+use diesel::{
+    dsl::IntoBoxed,
+    prelude::*,
+    sql_query,
+    sql_types::{BigInt, Text},
+};
 use diesel_derive_enum::DbEnum;
 
 #[derive(DbEnum, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -26,6 +31,8 @@ table! {
         action -> crate::SyncBufferActionMapping,
         data -> Text,
         source_site_id -> Nullable<Integer>,
+        is_ignored -> Bool,
+        is_out_of_scope -> Bool,
     }
 }
 
@@ -43,6 +50,13 @@ pub struct SyncBufferRow {
     pub action: SyncBufferAction,
     pub data: String,
     pub source_site_id: Option<i32>,
+    /// Failed record was marked as ignored by admin, it's not retried until it's received again
+    #[serde(default)]
+    pub is_ignored: bool,
+    /// Integrated record was purged since it's out of sync scope of the site, it's integrated
+    /// again when sync scope changes
+    #[serde(default)]
+    pub is_out_of_scope: bool,
 }
 
 impl Default for SyncBufferRow {
@@ -56,6 +70,8 @@ impl Default for SyncBufferRow {
             action: SyncBufferAction::Upsert,
             data: Default::default(),
             source_site_id: Default::default(),
+            is_ignored: false,
+            is_out_of_scope: false,
         }
     }
 }
@@ -117,8 +133,46 @@ impl<'a> SyncBufferRowRepository<'a> {
             .optional()?;
         Ok(result)
     }
+
+    /// Mark records for integration on next sync (clears error and ignored flag)
+    pub fn reset_integration(&self, record_ids: &[String]) -> Result<(), RepositoryError> {
+        for chunk in record_ids.chunks(UPDATE_CHUNK_SIZE) {
+            diesel::update(sync_buffer_dsl::sync_buffer)
+                .filter(sync_buffer_dsl::record_id.eq_any(chunk))
+                .set((
+                    sync_buffer_dsl::integration_datetime.eq(None::<NaiveDateTime>),
+                    sync_buffer_dsl::integration_error.eq(None::<String>),
+                    sync_buffer_dsl::is_ignored.eq(false),
+                ))
+                .execute(&self.connection.connection)?;
+        }
+        Ok(())
+    }
+
+    pub fn delete(&self, table_name: &str, record_ids: &[String]) -> Result<(), RepositoryError> {
+        for chunk in record_ids.chunks(UPDATE_CHUNK_SIZE) {
+            diesel::delete(sync_buffer_dsl::sync_buffer)
+                .filter(sync_buffer_dsl::table_name.eq(table_name))
+                .filter(sync_buffer_dsl::record_id.eq_any(chunk))
+                .execute(&self.connection.connection)?;
+        }
+        Ok(())
+    }
+
+    pub fn set_ignored(&self, record_ids: &[String]) -> Result<(), RepositoryError> {
+        for chunk in record_ids.chunks(UPDATE_CHUNK_SIZE) {
+            diesel::update(sync_buffer_dsl::sync_buffer)
+                .filter(sync_buffer_dsl::record_id.eq_any(chunk))
+                .set(sync_buffer_dsl::is_ignored.eq(true))
+                .execute(&self.connection.connection)?;
+        }
+        Ok(())
+    }
 }
 
+// Keep number of bound parameters well below sqlite limit
+const UPDATE_CHUNK_SIZE: usize = 1000;
+
 #[derive(Clone, Default)]
 pub struct SyncBufferFilter {
     pub record_id: Option<EqualFilter<String>>,
@@ -126,6 +180,8 @@ pub struct SyncBufferFilter {
     pub integration_error: Option<EqualFilter<String>>,
     pub action: Option<EqualFilter<SyncBufferAction>>,
     pub table_name: Option<EqualFilter<String>>,
+    pub is_ignored: Option<bool>,
+    pub is_out_of_scope: Option<bool>,
 }
 
 impl SyncBufferFilter {
@@ -157,6 +213,16 @@ impl SyncBufferFilter {
         self.action = Some(filter);
         self
     }
+
+    pub fn is_ignored(mut self, value: bool) -> Self {
+        self.is_ignored = Some(value);
+        self
+    }
+
+    pub fn is_out_of_scope(mut self, value: bool) -> Self {
+        self.is_out_of_scope = Some(value);
+        self
+    }
 }
 
 impl SyncBufferAction {
@@ -193,6 +259,99 @@ impl<'a> SyncBufferRepository<'a> {
 
         Ok(result)
     }
+
+    /// Ordered by received datetime
+    pub fn query_with_pagination(
+        &self,
+        pagination: Pagination,
+        filter: Option<SyncBufferFilter>,
+    ) -> Result<Vec<SyncBuffer>, RepositoryError> {
+        let result = create_filtered_query(filter)
+            .order((
+                sync_buffer_dsl::received_datetime.asc(),
+                sync_buffer_dsl::record_id.asc(),
+            ))
+            .offset(pagination.offset as i64)
+            .limit(pagination.limit as i64)
+            .load::<SyncBuffer>(&self.connection.connection)?;
+
+        Ok(result)
+    }
+
+    pub fn count(&self, filter: Option<SyncBufferFilter>) -> Result<u64, RepositoryError> {
+        let result = create_filtered_query(filter)
+            .count()
+            .get_result::<i64>(&self.connection.connection)?;
+        Ok(result as u64)
+    }
+
+    pub fn query_record_ids(
+        &self,
+        filter: Option<SyncBufferFilter>,
+    ) -> Result<Vec<String>, RepositoryError> {
+        let result = create_filtered_query(filter)
+            .select(sync_buffer_dsl::record_id)
+            .order(sync_buffer_dsl::record_id.asc())
+            .load::<String>(&self.connection.connection)?;
+        Ok(result)
+    }
+
+    /// Number of failed (and not ignored) records per table and error, as (table name, error, count)
+    pub fn count_failed_by_table_name_and_error(
+        &self,
+    ) -> Result<Vec<(String, String, u64)>, RepositoryError> {
+        #[derive(QueryableByName)]
+        struct FailedCount {
+            #[sql_type = "Text"]
+            table_name: String,
+            #[sql_type = "Text"]
+            integration_error: String,
+            #[sql_type = "BigInt"]
+            count: i64,
+        }
+
+        let result = sql_query(
+            r#"
+            SELECT table_name, integration_error, COUNT(*) AS count FROM sync_buffer
+            WHERE integration_error IS NOT NULL AND NOT is_ignored
+            GROUP BY table_name, integration_error
+            ORDER BY table_name, integration_error;
+            "#,
+        )
+        .load::<FailedCount>(&self.connection.connection)?;
+
+        Ok(result
+            .into_iter()
+            .map(
+                |FailedCount {
+                     table_name,
+                     integration_error,
+                     count,
+                 }| (table_name, integration_error, count as u64),
+            )
+            .collect())
+    }
+
+    /// Number of records received per table, including records that were already integrated
+    pub fn count_by_table_name(&self) -> Result<Vec<(String, u64)>, RepositoryError> {
+        #[derive(QueryableByName)]
+        struct TableCount {
+            #[sql_type = "Text"]
+            table_name: String,
+            #[sql_type = "BigInt"]
+            count: i64,
+        }
+
+        let result = sql_query(
+            "SELECT table_name, COUNT(*) AS count FROM sync_buffer GROUP BY table_name ORDER BY table_name;",
+        )
+        .load::<TableCount>(&self.connection.connection)?;
+
+        Ok(result
+            .into_iter()
+            .map(|TableCount { table_name, count }| (table_name, count as u64))
+            .collect())
+    }
 }
 
 type BoxedSyncBufferQuery = IntoBoxed<'static, sync_buffer::table, DBType>;
@@ -207,6 +366,8 @@ fn create_filtered_query(filter: Option<SyncBufferFilter>) -> BoxedSyncBufferQue
             action,
             table_name,
             record_id,
+            is_ignored,
+            is_out_of_scope,
         } = f;
 
         apply_equal_filter!(query, record_id, sync_buffer_dsl::record_id);
@@ -218,6 +379,13 @@ fn create_filtered_query(filter: Option<SyncBufferFilter>) -> BoxedSyncBufferQue
         apply_equal_filter!(query, integration_error, sync_buffer_dsl::integration_error);
         apply_equal_filter!(query, action, sync_buffer_dsl::action);
         apply_equal_filter!(query, table_name, sync_buffer_dsl::table_name);
+
+        if let Some(is_ignored) = is_ignored {
+            query = query.filter(sync_buffer_dsl::is_ignored.eq(is_ignored));
+        }
+        if let Some(is_out_of_scope) = is_out_of_scope {
+            query = query.filter(sync_buffer_dsl::is_out_of_scope.eq(is_out_of_scope));
+        }
     }
 
     query
@@ -316,4 +484,101 @@ mod test {
             vec![new_a]
         );
     }
+
+    #[actix_rt::test]
+    async fn test_sync_buffer_count_by_table_name() {
+        let (_, connection, _, _) = test_db::setup_all_with_data(
+            "test_sync_buffer_count_by_table_name",
+            MockDataInserts::none(),
+            inline_init(|r: &mut MockData| {
+                r.sync_buffer_rows = vec![
+                    inline_edit(&row_a(), |mut r| {
+                        r.table_name = "store".to_string();
+                        r
+                    }),
+                    inline_edit(&row_b(), |mut r| {
+                        r.table_name = "store".to_string();
+                        r
+                    }),
+                    inline_edit(&row_c(), |mut r| {
+                        r.table_name = "item".to_string();
+                        r
+                    }),
+                ];
+            }),
+        )
+        .await;
+
+        assert_eq!(
+            SyncBufferRepository::new(&connection)
+                .count_by_table_name()
+                .unwrap(),
+            vec![("item".to_string(), 1), ("store".to_string(), 2)]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_sync_buffer_failed_records() {
+        let failed = |record_id: &str, table_name: &str, error: &str| {
+            inline_init(|r: &mut SyncBufferRow| {
+                r.record_id = record_id.to_string();
+                r.table_name = table_name.to_string();
+                r.integration_datetime = Some(Defaults::naive_date_time());
+                r.integration_error = Some(error.to_string());
+            })
+        };
+        let (_, connection, _, _) = test_db::setup_all_with_data(
+            "test_sync_buffer_failed_records",
+            MockDataInserts::none(),
+            inline_init(|r: &mut MockData| {
+                r.sync_buffer_rows = vec![
+                    row_a(),
+                    failed("invoice1", "transact", "error1"),
+                    failed("invoice2", "transact", "error1"),
+                    failed("invoice3", "transact", "error2"),
+                    failed("item1", "item", "error1"),
+                ];
+            }),
+        )
+        .await;
+
+        let repo = SyncBufferRepository::new(&connection);
+        let row_repo = SyncBufferRowRepository::new(&connection);
+
+        assert_eq!(
+            repo.count_failed_by_table_name_and_error().unwrap(),
+            vec![
+                ("item".to_string(), "error1".to_string(), 1),
+                ("transact".to_string(), "error1".to_string(), 2),
+                ("transact".to_string(), "error2".to_string(), 1)
+            ]
+        );
+
+        row_repo
+            .set_ignored(&["invoice1".to_string(), "item1".to_string()])
+            .unwrap();
+        assert_eq!(
+            repo.count_failed_by_table_name_and_error().unwrap(),
+            vec![
+                ("transact".to_string(), "error1".to_string(), 1),
+                ("transact".to_string(), "error2".to_string(), 1)
+            ]
+        );
+        assert_eq!(
+            repo.query_record_ids(Some(SyncBufferFilter::new().is_ignored(true)))
+                .unwrap(),
+            vec!["invoice1".to_string(), "item1".to_string()]
+        );
+
+        row_repo
+            .reset_integration(&["invoice1".to_string()])
+            .unwrap();
+        assert_eq!(
+            row_repo.find_one_by_record_id("invoice1").unwrap(),
+            Some(inline_init(|r: &mut SyncBufferRow| {
+                r.record_id = "invoice1".to_string();
+                r.table_name = "transact".to_string();
+            }))
+        );
+    }
 }
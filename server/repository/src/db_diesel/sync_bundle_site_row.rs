@@ -0,0 +1,64 @@
+use super::sync_bundle_site_row::sync_bundle_site::dsl::*;
+
+use crate::{RepositoryError, StorageConnection};
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+table! {
+    sync_bundle_site (site_id) {
+        site_id -> Integer,
+        last_sequence -> BigInt,
+        last_imported_datetime -> Timestamp,
+    }
+}
+
+/// Last sync bundle imported on central server for a site, used to reject duplicate
+/// and out of order bundles. This table is local to central server and is not synced
+#[derive(Clone, Insertable, Queryable, Debug, PartialEq, AsChangeset, Default)]
+#[table_name = "sync_bundle_site"]
+pub struct SyncBundleSiteRow {
+    pub site_id: i32,
+    pub last_sequence: i64,
+    pub last_imported_datetime: NaiveDateTime,
+}
+
+pub struct SyncBundleSiteRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> SyncBundleSiteRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        SyncBundleSiteRowRepository { connection }
+    }
+
+    #[cfg(feature = "postgres")]
+    pub fn upsert_one(&self, row: &SyncBundleSiteRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(sync_bundle_site)
+            .values(row)
+            .on_conflict(site_id)
+            .do_update()
+            .set(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "postgres"))]
+    pub fn upsert_one(&self, row: &SyncBundleSiteRow) -> Result<(), RepositoryError> {
+        diesel::replace_into(sync_bundle_site)
+            .values(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    pub fn find_one_by_site_id(
+        &self,
+        bundle_site_id: i32,
+    ) -> Result<Option<SyncBundleSiteRow>, RepositoryError> {
+        let result = sync_bundle_site
+            .filter(site_id.eq(bundle_site_id))
+            .first(&self.connection.connection)
+            .optional()?;
+        Ok(result)
+    }
+}
@@ -0,0 +1,142 @@
+use super::sync_conflict_row::sync_conflict::dsl::*;
+
+use crate::{RepositoryError, StorageConnection};
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel_derive_enum::DbEnum;
+
+table! {
+    sync_conflict (id) {
+        id -> Text,
+        table_name -> Text,
+        record_id -> Text,
+        local_data -> Text,
+        remote_data -> Text,
+        conflicting_fields -> Text,
+        source_site_id -> Nullable<Integer>,
+        created_datetime -> Timestamp,
+        resolution -> Nullable<crate::db_diesel::sync_conflict_row::SyncConflictResolutionMapping>,
+        resolved_datetime -> Nullable<Timestamp>,
+        resolved_by_user_id -> Nullable<Text>,
+    }
+}
+
+#[derive(DbEnum, Debug, Clone, PartialEq, Eq)]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+pub enum SyncConflictResolution {
+    /// Keep version edited on this site
+    Local,
+    /// Use version received through sync
+    Remote,
+}
+
+/// Concurrent edit of shared-remote or patient record, detected when integrating sync record.
+/// Local version is kept until conflict is resolved
+#[derive(Clone, Insertable, Queryable, Debug, PartialEq, AsChangeset, Default)]
+#[table_name = "sync_conflict"]
+#[changeset_options(treat_none_as_null = "true")]
+pub struct SyncConflictRow {
+    pub id: String,
+    /// Sync (legacy) table name
+    pub table_name: String,
+    pub record_id: String,
+    /// Local record translated to sync record data
+    pub local_data: String,
+    /// Sync record data as received
+    pub remote_data: String,
+    /// JSON array of sync record fields edited on both sites
+    pub conflicting_fields: String,
+    pub source_site_id: Option<i32>,
+    pub created_datetime: NaiveDateTime,
+    pub resolution: Option<SyncConflictResolution>,
+    pub resolved_datetime: Option<NaiveDateTime>,
+    pub resolved_by_user_id: Option<String>,
+}
+
+pub struct SyncConflictRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> SyncConflictRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        SyncConflictRowRepository { connection }
+    }
+
+    #[cfg(feature = "postgres")]
+    pub fn upsert_one(&self, row: &SyncConflictRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(sync_conflict)
+            .values(row)
+            .on_conflict(id)
+            .do_update()
+            .set(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "postgres"))]
+    pub fn upsert_one(&self, row: &SyncConflictRow) -> Result<(), RepositoryError> {
+        diesel::replace_into(sync_conflict)
+            .values(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        conflict_id: &str,
+    ) -> Result<Option<SyncConflictRow>, RepositoryError> {
+        let result = sync_conflict
+            .filter(id.eq(conflict_id))
+            .first(&self.connection.connection)
+            .optional()?;
+        Ok(result)
+    }
+
+    /// Unresolved conflict for the record, there is at most one
+    pub fn find_unresolved(
+        &self,
+        table: &str,
+        record: &str,
+    ) -> Result<Option<SyncConflictRow>, RepositoryError> {
+        let result = sync_conflict
+            .filter(table_name.eq(table))
+            .filter(record_id.eq(record))
+            .filter(resolution.is_null())
+            .first(&self.connection.connection)
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn query(
+        &self,
+        table: Option<String>,
+        is_resolved: Option<bool>,
+        offset: u32,
+        limit: u32,
+    ) -> Result<(Vec<SyncConflictRow>, u64), RepositoryError> {
+        let create_query = || {
+            let mut query = sync_conflict.into_boxed();
+            if let Some(table) = &table {
+                query = query.filter(table_name.eq(table.clone()));
+            }
+            match is_resolved {
+                Some(true) => query = query.filter(resolution.is_not_null()),
+                Some(false) => query = query.filter(resolution.is_null()),
+                None => {}
+            }
+            query
+        };
+
+        let rows = create_query()
+            .order((created_datetime.desc(), id.asc()))
+            .offset(offset as i64)
+            .limit(limit as i64)
+            .load(&self.connection.connection)?;
+        let count: i64 = create_query()
+            .count()
+            .get_result(&self.connection.connection)?;
+
+        Ok((rows, count as u64))
+    }
+}
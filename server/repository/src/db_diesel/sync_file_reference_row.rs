@@ -5,6 +5,7 @@ use crate::StorageConnection;
 
 use chrono::NaiveDateTime;
 use diesel::prelude::*;
+use diesel_derive_enum::DbEnum;
 use serde::{Deserialize, Serialize};
 
 use crate::{ChangeLogInsertRow, ChangelogAction, ChangelogRepository, ChangelogTableName, Upsert};
@@ -18,9 +19,38 @@ table! {
         mime_type -> Nullable<Text>,
         created_datetime -> Timestamp,
         deleted_datetime -> Nullable<Timestamp>,
+        direction -> crate::db_diesel::sync_file_reference_row::SyncFileDirectionMapping,
+        status -> crate::db_diesel::sync_file_reference_row::SyncFileStatusMapping,
+        total_bytes -> BigInt,
+        transferred_bytes -> BigInt,
+        hash -> Nullable<Text>,
+        retries -> Integer,
+        retry_datetime -> Nullable<Timestamp>,
+        error -> Nullable<Text>,
     }
 }
 
+#[derive(DbEnum, Debug, Clone, PartialEq, Eq, Default)]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+pub enum SyncFileDirection {
+    /// File was added on this site and is uploaded to central server
+    #[default]
+    Upload,
+    /// File was added on another site and is downloaded from central server
+    Download,
+}
+
+#[derive(DbEnum, Debug, Clone, PartialEq, Eq, Default)]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+pub enum SyncFileStatus {
+    #[default]
+    Pending,
+    InProgress,
+    Done,
+    /// Transfer failed, retried after `retry_datetime`
+    Error,
+}
+
 #[derive(
     Clone, Insertable, Queryable, Debug, PartialEq, AsChangeset, Eq, Default, Serialize, Deserialize,
 )]
@@ -33,6 +63,25 @@ pub struct SyncFileReferenceRow {
     pub mime_type: Option<String>,
     pub created_datetime: NaiveDateTime,
     pub deleted_datetime: Option<NaiveDateTime>,
+    // Transfer state of the file on this site, not synced
+    #[serde(skip)]
+    pub direction: SyncFileDirection,
+    #[serde(skip)]
+    pub status: SyncFileStatus,
+    #[serde(skip)]
+    pub total_bytes: i64,
+    /// Bytes uploaded or downloaded so far, transfer resumes from this offset
+    #[serde(skip)]
+    pub transferred_bytes: i64,
+    /// Sha256 of file content (hex)
+    #[serde(skip)]
+    pub hash: Option<String>,
+    #[serde(skip)]
+    pub retries: i32,
+    #[serde(skip)]
+    pub retry_datetime: Option<NaiveDateTime>,
+    #[serde(skip)]
+    pub error: Option<String>,
 }
 
 pub struct SyncFileReferenceRowRepository<'a> {
@@ -69,7 +118,10 @@ impl<'a> SyncFileReferenceRowRepository<'a> {
         Ok(())
     }
 
-    pub fn upsert_one(&self, sync_file_reference_row: &SyncFileReferenceRow) -> Result<i64, RepositoryError> {
+    pub fn upsert_one(
+        &self,
+        sync_file_reference_row: &SyncFileReferenceRow,
+    ) -> Result<i64, RepositoryError> {
         self._upsert_one(sync_file_reference_row)?;
         self.insert_changelog(
             sync_file_reference_row.id.to_owned(),
@@ -104,14 +156,66 @@ impl<'a> SyncFileReferenceRowRepository<'a> {
         Ok(result)
     }
 
+    /// Update transfer state only, without change log (transfer state is not synced)
+    pub fn update_transfer(
+        &self,
+        sync_file_reference_row: &SyncFileReferenceRow,
+    ) -> Result<(), RepositoryError> {
+        let SyncFileReferenceRow {
+            id: row_id,
+            direction: row_direction,
+            status: row_status,
+            total_bytes: row_total_bytes,
+            transferred_bytes: row_transferred_bytes,
+            hash: row_hash,
+            retries: row_retries,
+            retry_datetime: row_retry_datetime,
+            error: row_error,
+            ..
+        } = sync_file_reference_row;
+
+        diesel::update(sync_file_reference.filter(id.eq(row_id)))
+            .set((
+                direction.eq(row_direction),
+                status.eq(row_status),
+                total_bytes.eq(row_total_bytes),
+                transferred_bytes.eq(row_transferred_bytes),
+                hash.eq(row_hash),
+                retries.eq(row_retries),
+                retry_datetime.eq(row_retry_datetime),
+                error.eq(row_error),
+            ))
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    /// Files waiting to be transferred in `transfer_direction`, including failed transfers due for retry,
+    /// smaller files first
+    pub fn find_pending_transfers(
+        &self,
+        transfer_direction: SyncFileDirection,
+        now: NaiveDateTime,
+    ) -> Result<Vec<SyncFileReferenceRow>, RepositoryError> {
+        let result = sync_file_reference
+            .filter(direction.eq(transfer_direction))
+            .filter(deleted_datetime.is_null())
+            .filter(
+                status
+                    .eq_any(vec![SyncFileStatus::Pending, SyncFileStatus::InProgress])
+                    .or(status
+                        .eq(SyncFileStatus::Error)
+                        .and(retry_datetime.le(now).or(retry_datetime.is_null()))),
+            )
+            .order((total_bytes.asc(), created_datetime.asc()))
+            .load(&self.connection.connection)?;
+        Ok(result)
+    }
+
     pub fn delete(&self, sync_file_reference_id: &str) -> Result<(), RepositoryError> {
         diesel::update(sync_file_reference.filter(id.eq(sync_file_reference_id)))
             .set(deleted_datetime.eq(Some(chrono::Utc::now().naive_utc())))
             .execute(&self.connection.connection)?;
-        self.insert_changelog(
-            sync_file_reference_id.to_owned(),
-            ChangelogAction::Delete,
-        )?;
+        self.insert_changelog(sync_file_reference_id.to_owned(), ChangelogAction::Delete)?;
         Ok(())
     }
 }
@@ -0,0 +1,139 @@
+use super::{sync_health_row::sync_health::dsl::*, SyncLogRowErrorCode};
+
+use crate::{RepositoryError, StorageConnection};
+
+use chrono::NaiveDateTime;
+use diesel::{prelude::*, sql_query, sql_types::BigInt};
+
+table! {
+    sync_health (site_id) {
+        site_id -> Integer,
+        site_name -> Text,
+        app_version -> Text,
+        reported_datetime -> Timestamp,
+        last_sync_datetime -> Nullable<Timestamp>,
+        last_successful_sync_datetime -> Nullable<Timestamp>,
+        error_code -> Nullable<crate::db_diesel::sync_log_row::SyncLogRowErrorCodeMapping>,
+        error_message -> Nullable<Text>,
+        push_queue_count -> BigInt,
+        database_size -> BigInt,
+    }
+}
+
+/// Latest sync health reported by a remote site, only populated on central server.
+/// This table is not synced
+#[derive(Clone, Insertable, Queryable, Debug, PartialEq, AsChangeset, Default)]
+#[table_name = "sync_health"]
+#[changeset_options(treat_none_as_null = "true")]
+pub struct SyncHealthRow {
+    pub site_id: i32,
+    pub site_name: String,
+    pub app_version: String,
+    pub reported_datetime: NaiveDateTime,
+    /// Start of the latest sync on the site
+    pub last_sync_datetime: Option<NaiveDateTime>,
+    pub last_successful_sync_datetime: Option<NaiveDateTime>,
+    /// Error of the latest sync on the site
+    pub error_code: Option<SyncLogRowErrorCode>,
+    pub error_message: Option<String>,
+    /// Change logs on the site waiting to be pushed
+    pub push_queue_count: i64,
+    /// Size of site database in bytes
+    pub database_size: i64,
+}
+
+pub struct SyncHealthRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> SyncHealthRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        SyncHealthRowRepository { connection }
+    }
+
+    #[cfg(feature = "postgres")]
+    pub fn upsert_one(&self, row: &SyncHealthRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(sync_health)
+            .values(row)
+            .on_conflict(site_id)
+            .do_update()
+            .set(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "postgres"))]
+    pub fn upsert_one(&self, row: &SyncHealthRow) -> Result<(), RepositoryError> {
+        diesel::replace_into(sync_health)
+            .values(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    pub fn find_one_by_site_id(&self, site: i32) -> Result<Option<SyncHealthRow>, RepositoryError> {
+        let result = sync_health
+            .filter(site_id.eq(site))
+            .first(&self.connection.connection)
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_all(&self) -> Result<Vec<SyncHealthRow>, RepositoryError> {
+        let result = sync_health
+            .order(site_name.asc())
+            .load(&self.connection.connection)?;
+        Ok(result)
+    }
+
+    /// Size of the whole database in bytes
+    pub fn database_size(&self) -> Result<i64, RepositoryError> {
+        #[derive(QueryableByName)]
+        struct DatabaseSize {
+            #[sql_type = "BigInt"]
+            size: i64,
+        }
+
+        #[cfg(not(feature = "postgres"))]
+        const QUERY: &str =
+            "SELECT page_count * page_size AS size FROM pragma_page_count(), pragma_page_size();";
+        #[cfg(feature = "postgres")]
+        const QUERY: &str = "SELECT pg_database_size(current_database()) AS size;";
+
+        let result = sql_query(QUERY).get_result::<DatabaseSize>(&self.connection.connection)?;
+        Ok(result.size)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use util::inline_init;
+
+    use crate::{
+        mock::MockDataInserts, test_db::setup_all, SyncHealthRow, SyncHealthRowRepository,
+        SyncLogRowErrorCode,
+    };
+
+    #[actix_rt::test]
+    async fn sync_health_row() {
+        let (_, connection, _, _) = setup_all("sync_health_row", MockDataInserts::none()).await;
+
+        let repo = SyncHealthRowRepository::new(&connection);
+        let row = inline_init(|r: &mut SyncHealthRow| {
+            r.site_id = 2;
+            r.site_name = "site_two".to_string();
+            r.error_code = Some(SyncLogRowErrorCode::ConnectionError);
+            r.push_queue_count = 10;
+        });
+        repo.upsert_one(&row).unwrap();
+        // Report replaces previous one
+        let row = SyncHealthRow {
+            error_code: None,
+            ..row
+        };
+        repo.upsert_one(&row).unwrap();
+        assert_eq!(repo.find_all(), Ok(vec![row.clone()]));
+        assert_eq!(repo.find_one_by_site_id(2), Ok(Some(row)));
+
+        assert!(repo.database_size().unwrap() > 0);
+    }
+}
@@ -1,15 +1,17 @@
 use chrono::NaiveDateTime;
 use diesel::prelude::*;
 use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
 use util::Defaults;
 
 use crate::RepositoryError;
 
 use super::{sync_log_row::sync_log::dsl as sync_log_dsl, StorageConnection};
 
-#[derive(DbEnum, Debug, Clone, PartialEq, Eq)]
+#[derive(DbEnum, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(test, derive(strum::EnumIter))]
 #[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum SyncLogRowErrorCode {
     ConnectionError,
     SiteNameNotFound,
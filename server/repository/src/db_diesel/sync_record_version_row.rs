@@ -0,0 +1,75 @@
+use super::sync_record_version_row::sync_record_version::dsl::*;
+
+use crate::{RepositoryError, StorageConnection};
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+table! {
+    sync_record_version (table_name, record_id) {
+        table_name -> Text,
+        record_id -> Text,
+        remote_data -> Text,
+        local_data -> Nullable<Text>,
+        updated_datetime -> Timestamp,
+    }
+}
+
+/// Last version of shared-remote or patient record received through sync, used as common ancestor
+/// when detecting concurrent edits. This table is local to the site and is not synced
+#[derive(Clone, Insertable, Queryable, Debug, PartialEq, AsChangeset, Default)]
+#[table_name = "sync_record_version"]
+#[changeset_options(treat_none_as_null = "true")]
+pub struct SyncRecordVersionRow {
+    /// Sync (legacy) table name
+    pub table_name: String,
+    pub record_id: String,
+    /// Sync record data as received
+    pub remote_data: String,
+    /// Local record translated to sync record data, right after the received version was integrated.
+    /// Only kept when integrated record had local changes, otherwise it's the same as `remote_data`
+    pub local_data: Option<String>,
+    pub updated_datetime: NaiveDateTime,
+}
+
+pub struct SyncRecordVersionRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> SyncRecordVersionRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        SyncRecordVersionRowRepository { connection }
+    }
+
+    #[cfg(feature = "postgres")]
+    pub fn upsert_one(&self, row: &SyncRecordVersionRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(sync_record_version)
+            .values(row)
+            .on_conflict((table_name, record_id))
+            .do_update()
+            .set(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "postgres"))]
+    pub fn upsert_one(&self, row: &SyncRecordVersionRow) -> Result<(), RepositoryError> {
+        diesel::replace_into(sync_record_version)
+            .values(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    pub fn find_one(
+        &self,
+        table: &str,
+        id: &str,
+    ) -> Result<Option<SyncRecordVersionRow>, RepositoryError> {
+        let result = sync_record_version
+            .filter(table_name.eq(table))
+            .filter(record_id.eq(id))
+            .first(&self.connection.connection)
+            .optional()?;
+        Ok(result)
+    }
+}
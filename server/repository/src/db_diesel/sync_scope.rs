@@ -0,0 +1,288 @@
+use super::{
+    activity_log_row::activity_log::dsl as activity_log_dsl,
+    changelog::changelog::changelog::dsl as changelog_dsl,
+    item_link_row::item_link::dsl as item_link_dsl, item_row::item::dsl as item_dsl,
+    master_list_line_row::master_list_line::dsl as master_list_line_dsl,
+    master_list_name_join::master_list_name_join::dsl as master_list_name_join_dsl,
+    master_list_row::master_list::dsl as master_list_dsl,
+    name_link_row::name_link::dsl as name_link_dsl, name_row::name::dsl as name_dsl,
+    name_store_join::name_store_join::dsl as name_store_join_dsl,
+    sync_buffer::sync_buffer::dsl as sync_buffer_dsl,
+    temperature_log_row::temperature_log::dsl as temperature_log_dsl, ChangelogRepository,
+    ChangelogTableName, NameType, StorageConnection, SyncBufferAction,
+};
+
+use crate::RepositoryError;
+
+use chrono::NaiveDateTime;
+use diesel::{prelude::*, sql_query, sql_types::Text};
+use std::collections::HashSet;
+
+// Keep number of bound parameters well below sqlite limit
+const DELETE_CHUNK_SIZE: usize = 1000;
+
+#[derive(QueryableByName)]
+struct RecordId {
+    #[sql_type = "Text"]
+    id: String,
+}
+
+/// Queries used to find and purge records that are out of sync scope of the site (see SyncScope in service).
+/// Purging is local only, change logs added by delete triggers are removed
+pub struct SyncScopeRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> SyncScopeRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        SyncScopeRepository { connection }
+    }
+
+    /// Records of sync buffer `table_name` that were integrated successfully, only these can be purged
+    /// since they can be integrated again from sync buffer
+    pub fn integrated_record_ids(&self, table_name: &str) -> Result<Vec<String>, RepositoryError> {
+        let result = sync_buffer_dsl::sync_buffer
+            .filter(sync_buffer_dsl::table_name.eq(table_name))
+            .filter(sync_buffer_dsl::action.eq(SyncBufferAction::Upsert))
+            .filter(sync_buffer_dsl::integration_datetime.is_not_null())
+            .filter(sync_buffer_dsl::integration_error.is_null())
+            .filter(sync_buffer_dsl::is_out_of_scope.eq(false))
+            .select(sync_buffer_dsl::record_id)
+            .load(&self.connection.connection)?;
+        Ok(result)
+    }
+
+    /// Master lists joined to any of the store names
+    pub fn master_list_ids(
+        &self,
+        store_name_ids: &[String],
+    ) -> Result<Vec<String>, RepositoryError> {
+        let result = master_list_name_join_dsl::master_list_name_join
+            .filter(master_list_name_join_dsl::name_link_id.eq_any(store_name_ids))
+            .select(master_list_name_join_dsl::master_list_id)
+            .distinct()
+            .load(&self.connection.connection)?;
+        Ok(result)
+    }
+
+    pub fn master_list_line_ids_not_on_master_lists(
+        &self,
+        master_list_ids: &[String],
+    ) -> Result<Vec<String>, RepositoryError> {
+        let result = master_list_line_dsl::master_list_line
+            .filter(master_list_line_dsl::master_list_id.ne_all(master_list_ids))
+            .select(master_list_line_dsl::id)
+            .load(&self.connection.connection)?;
+        Ok(result)
+    }
+
+    /// Items on master lists joined to any of the store names
+    pub fn visible_item_ids(
+        &self,
+        store_name_ids: &[String],
+    ) -> Result<Vec<String>, RepositoryError> {
+        let result = item_link_dsl::item_link
+            .select(item_link_dsl::item_id)
+            .inner_join(
+                master_list_line_dsl::master_list_line
+                    .on(master_list_line_dsl::item_link_id.eq(item_link_dsl::id)),
+            )
+            .inner_join(
+                master_list_dsl::master_list
+                    .on(master_list_dsl::id.eq(master_list_line_dsl::master_list_id)),
+            )
+            .inner_join(
+                master_list_name_join_dsl::master_list_name_join
+                    .on(master_list_name_join_dsl::master_list_id.eq(master_list_dsl::id)),
+            )
+            .filter(master_list_name_join_dsl::name_link_id.eq_any(store_name_ids))
+            .distinct()
+            .load(&self.connection.connection)?;
+        Ok(result)
+    }
+
+    /// Patients joined to any of the stores
+    pub fn visible_patient_ids(
+        &self,
+        store_ids: &[String],
+    ) -> Result<Vec<String>, RepositoryError> {
+        let result = name_store_join_dsl::name_store_join
+            .inner_join(name_link_dsl::name_link)
+            .filter(name_store_join_dsl::store_id.eq_any(store_ids))
+            .select(name_link_dsl::name_id)
+            .distinct()
+            .load(&self.connection.connection)?;
+        Ok(result)
+    }
+
+    pub fn patient_ids(&self) -> Result<Vec<String>, RepositoryError> {
+        let result = name_dsl::name
+            .filter(name_dsl::type_.eq(NameType::Patient))
+            .select(name_dsl::id)
+            .load(&self.connection.connection)?;
+        Ok(result)
+    }
+
+    pub fn delete_master_list_lines(&self, ids: &[String]) -> Result<(), RepositoryError> {
+        for chunk in ids.chunks(DELETE_CHUNK_SIZE) {
+            diesel::delete(
+                master_list_line_dsl::master_list_line
+                    .filter(master_list_line_dsl::id.eq_any(chunk)),
+            )
+            .execute(&self.connection.connection)?;
+        }
+        Ok(())
+    }
+
+    /// Items not referenced by any record (directly or through any of their item links), only
+    /// these can be purged
+    pub fn unreferenced_item_ids(&self) -> Result<HashSet<String>, RepositoryError> {
+        let result = sql_query(
+            r#"
+            SELECT id FROM item
+            WHERE NOT EXISTS (SELECT 1 FROM barcode WHERE barcode.item_id = item.id)
+            AND NOT EXISTS (SELECT 1 FROM pack_variant WHERE pack_variant.item_id = item.id)
+            AND NOT EXISTS (
+                SELECT 1 FROM item_link WHERE item_link.item_id = item.id AND (
+                    EXISTS (SELECT 1 FROM stock_line WHERE stock_line.item_link_id = item_link.id)
+                    OR EXISTS (SELECT 1 FROM invoice_line WHERE invoice_line.item_link_id = item_link.id)
+                    OR EXISTS (SELECT 1 FROM requisition_line WHERE requisition_line.item_link_id = item_link.id)
+                    OR EXISTS (SELECT 1 FROM stocktake_line WHERE stocktake_line.item_link_id = item_link.id)
+                    OR EXISTS (SELECT 1 FROM master_list_line WHERE master_list_line.item_link_id = item_link.id)
+                )
+            );
+            "#,
+        )
+        .load::<RecordId>(&self.connection.connection)?;
+        Ok(result.into_iter().map(|RecordId { id }| id).collect())
+    }
+
+    /// Names not referenced by any record (directly or through any of their name links), only
+    /// these can be purged
+    pub fn unreferenced_name_ids(&self) -> Result<HashSet<String>, RepositoryError> {
+        let result = sql_query(
+            r#"
+            SELECT id FROM name
+            WHERE NOT EXISTS (SELECT 1 FROM store WHERE store.name_id = name.id)
+            AND NOT EXISTS (
+                SELECT 1 FROM name_link WHERE name_link.name_id = name.id AND (
+                    EXISTS (SELECT 1 FROM invoice WHERE invoice.name_link_id = name_link.id)
+                    OR EXISTS (SELECT 1 FROM requisition WHERE requisition.name_link_id = name_link.id)
+                    OR EXISTS (SELECT 1 FROM name_store_join WHERE name_store_join.name_link_id = name_link.id)
+                    OR EXISTS (SELECT 1 FROM name_tag_join WHERE name_tag_join.name_link_id = name_link.id)
+                    OR EXISTS (SELECT 1 FROM master_list_name_join WHERE master_list_name_join.name_link_id = name_link.id)
+                    OR EXISTS (SELECT 1 FROM changelog WHERE changelog.name_link_id = name_link.id)
+                    OR EXISTS (SELECT 1 FROM barcode WHERE barcode.manufacturer_link_id = name_link.id)
+                    OR EXISTS (SELECT 1 FROM stock_line WHERE stock_line.supplier_link_id = name_link.id)
+                    OR EXISTS (SELECT 1 FROM document WHERE document.owner_name_link_id = name_link.id)
+                    OR EXISTS (SELECT 1 FROM encounter WHERE encounter.patient_link_id = name_link.id)
+                    OR EXISTS (SELECT 1 FROM program_enrolment WHERE program_enrolment.patient_link_id = name_link.id)
+                    OR EXISTS (SELECT 1 FROM program_event WHERE program_event.patient_link_id = name_link.id)
+                    OR EXISTS (SELECT 1 FROM contact_trace WHERE contact_trace.patient_link_id = name_link.id)
+                    OR EXISTS (SELECT 1 FROM contact_trace WHERE contact_trace.contact_patient_link_id = name_link.id)
+                )
+            );
+            "#,
+        )
+        .load::<RecordId>(&self.connection.connection)?;
+        Ok(result.into_iter().map(|RecordId { id }| id).collect())
+    }
+
+    /// Deletes items with their item links, items must be unreferenced (see `unreferenced_item_ids`)
+    pub fn delete_items(&self, ids: &[String]) -> Result<(), RepositoryError> {
+        for chunk in ids.chunks(DELETE_CHUNK_SIZE) {
+            diesel::delete(item_link_dsl::item_link.filter(item_link_dsl::item_id.eq_any(chunk)))
+                .execute(&self.connection.connection)?;
+            diesel::delete(item_dsl::item.filter(item_dsl::id.eq_any(chunk)))
+                .execute(&self.connection.connection)?;
+        }
+        Ok(())
+    }
+
+    /// Deletes names with their name links, names must be unreferenced (see `unreferenced_name_ids`)
+    pub fn delete_names(&self, ids: &[String]) -> Result<(), RepositoryError> {
+        for chunk in ids.chunks(DELETE_CHUNK_SIZE) {
+            diesel::delete(name_link_dsl::name_link.filter(name_link_dsl::name_id.eq_any(chunk)))
+                .execute(&self.connection.connection)?;
+            diesel::delete(name_dsl::name.filter(name_dsl::id.eq_any(chunk)))
+                .execute(&self.connection.connection)?;
+        }
+        Ok(())
+    }
+
+    /// Deletes temperature logs older than `before`, logs changed at or after `pushed_cursor`
+    /// (not yet pushed) are kept. Returns ids of deleted logs
+    pub fn delete_temperature_logs(
+        &self,
+        before: NaiveDateTime,
+        pushed_cursor: i64,
+    ) -> Result<Vec<String>, RepositoryError> {
+        let not_pushed =
+            self.not_pushed_record_ids(ChangelogTableName::TemperatureLog, pushed_cursor)?;
+        let ids: Vec<String> = temperature_log_dsl::temperature_log
+            .filter(temperature_log_dsl::datetime.lt(before))
+            .select(temperature_log_dsl::id)
+            .load::<String>(&self.connection.connection)?
+            .into_iter()
+            .filter(|id| !not_pushed.contains(id))
+            .collect();
+
+        // Delete trigger adds a changelog for every deleted log, these are removed again so that
+        // purging is not pushed
+        let latest_cursor = ChangelogRepository::new(self.connection).latest_cursor()? as i64;
+        for chunk in ids.chunks(DELETE_CHUNK_SIZE) {
+            diesel::delete(
+                temperature_log_dsl::temperature_log.filter(temperature_log_dsl::id.eq_any(chunk)),
+            )
+            .execute(&self.connection.connection)?;
+            diesel::delete(
+                changelog_dsl::changelog
+                    .filter(changelog_dsl::cursor.gt(latest_cursor))
+                    .filter(changelog_dsl::table_name.eq(ChangelogTableName::TemperatureLog))
+                    .filter(changelog_dsl::record_id.eq_any(chunk)),
+            )
+            .execute(&self.connection.connection)?;
+        }
+        Ok(ids)
+    }
+
+    /// Same as `delete_temperature_logs` for activity logs (activity logs have no delete trigger)
+    pub fn delete_activity_logs(
+        &self,
+        before: NaiveDateTime,
+        pushed_cursor: i64,
+    ) -> Result<Vec<String>, RepositoryError> {
+        let not_pushed =
+            self.not_pushed_record_ids(ChangelogTableName::ActivityLog, pushed_cursor)?;
+        let ids: Vec<String> = activity_log_dsl::activity_log
+            .filter(activity_log_dsl::datetime.lt(before))
+            .select(activity_log_dsl::id)
+            .load::<String>(&self.connection.connection)?
+            .into_iter()
+            .filter(|id| !not_pushed.contains(id))
+            .collect();
+
+        for chunk in ids.chunks(DELETE_CHUNK_SIZE) {
+            diesel::delete(
+                activity_log_dsl::activity_log.filter(activity_log_dsl::id.eq_any(chunk)),
+            )
+            .execute(&self.connection.connection)?;
+        }
+        Ok(ids)
+    }
+
+    /// Changelogs of records received through sync are not pushed
+    fn not_pushed_record_ids(
+        &self,
+        table_name: ChangelogTableName,
+        pushed_cursor: i64,
+    ) -> Result<HashSet<String>, RepositoryError> {
+        let result = changelog_dsl::changelog
+            .filter(changelog_dsl::cursor.ge(pushed_cursor))
+            .filter(changelog_dsl::table_name.eq(table_name))
+            .filter(changelog_dsl::is_sync_update.eq(false))
+            .select(changelog_dsl::record_id)
+            .load::<String>(&self.connection.connection)?;
+        Ok(result.into_iter().collect())
+    }
+}
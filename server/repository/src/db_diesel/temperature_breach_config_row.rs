@@ -18,6 +18,11 @@ table! {
         store_id -> Text,
         minimum_temperature -> Double,
         maximum_temperature -> Double,
+        location_id -> Nullable<Text>,
+        lookback_days -> Integer,
+        minimum_excursion_duration_milliseconds -> Integer,
+        hysteresis_temperature -> Double,
+        hysteresis_duration_milliseconds -> Integer,
     }
 }
 
@@ -33,7 +38,7 @@ joinable!(temperature_breach_config -> store (store_id));
 
 allow_tables_to_appear_in_same_query!(temperature_breach_config, temperature_log);
 
-#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq)]
 #[changeset_options(treat_none_as_null = "true")]
 #[table_name = "temperature_breach_config"]
 pub struct TemperatureBreachConfigRow {
@@ -46,6 +51,37 @@ pub struct TemperatureBreachConfigRow {
     pub store_id: String,
     pub minimum_temperature: f64,
     pub maximum_temperature: f64,
+    /// Excursion configs for a location override the store wide config (without location)
+    pub location_id: Option<String>,
+    /// Excursion configs only: how far back logs are checked for an ongoing excursion
+    pub lookback_days: i32,
+    /// Excursion configs only: out of range logs have to span at least this duration
+    pub minimum_excursion_duration_milliseconds: i32,
+    /// Excursion configs only: temperature has to be back within the range by this many degrees
+    /// for `hysteresis_duration_milliseconds` before an excursion is closed
+    pub hysteresis_temperature: f64,
+    pub hysteresis_duration_milliseconds: i32,
+}
+
+/// Same defaults as the database columns
+impl Default for TemperatureBreachConfigRow {
+    fn default() -> Self {
+        Self {
+            id: Default::default(),
+            duration_milliseconds: Default::default(),
+            r#type: Default::default(),
+            description: Default::default(),
+            is_active: Default::default(),
+            store_id: Default::default(),
+            minimum_temperature: Default::default(),
+            maximum_temperature: Default::default(),
+            location_id: Default::default(),
+            lookback_days: 7,
+            minimum_excursion_duration_milliseconds: Default::default(),
+            hysteresis_temperature: Default::default(),
+            hysteresis_duration_milliseconds: Default::default(),
+        }
+    }
 }
 
 pub struct TemperatureBreachConfigRowRepository<'a> {
@@ -0,0 +1,178 @@
+use super::temperature_breach_stock_impact_row::temperature_breach_stock_impact::dsl::*;
+
+use crate::{RepositoryError, StorageConnection};
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel_derive_enum::DbEnum;
+
+table! {
+    temperature_breach_stock_impact (id) {
+        id -> Text,
+        temperature_breach_id -> Text,
+        stock_line_id -> Text,
+        store_id -> Text,
+        outcome -> crate::db_diesel::temperature_breach_stock_impact_row::TemperatureBreachStockOutcomeMapping,
+        vvm_status -> Nullable<crate::db_diesel::temperature_breach_stock_impact_row::VvmStatusMapping>,
+        number_of_packs -> Double,
+        inventory_reduction_id -> Nullable<Text>,
+        comment -> Nullable<Text>,
+        user_id -> Text,
+        assessed_datetime -> Timestamp,
+    }
+}
+
+#[derive(DbEnum, Debug, Clone, PartialEq, Eq, Default)]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+pub enum TemperatureBreachStockOutcome {
+    #[default]
+    Usable,
+    /// Stock is removed with an inventory reduction
+    Discard,
+    /// Stock line is put on hold until it's assessed again
+    Quarantine,
+}
+
+/// Vaccine vial monitor stage, vials at stage 3 or 4 reached the discard point
+#[derive(DbEnum, Debug, Clone, PartialEq, Eq)]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+pub enum VvmStatus {
+    Stage1,
+    Stage2,
+    Stage3,
+    Stage4,
+}
+
+impl VvmStatus {
+    pub fn is_discard_point_reached(&self) -> bool {
+        matches!(self, VvmStatus::Stage3 | VvmStatus::Stage4)
+    }
+}
+
+/// Outcome of assessing stock that was in the breached location during a temperature breach,
+/// at most one per breach and stock line
+#[derive(Clone, Insertable, Queryable, Debug, PartialEq, AsChangeset, Default)]
+#[table_name = "temperature_breach_stock_impact"]
+#[changeset_options(treat_none_as_null = "true")]
+pub struct TemperatureBreachStockImpactRow {
+    pub id: String,
+    pub temperature_breach_id: String,
+    pub stock_line_id: String,
+    pub store_id: String,
+    pub outcome: TemperatureBreachStockOutcome,
+    pub vvm_status: Option<VvmStatus>,
+    /// Number of packs discarded, 0 unless outcome is discard
+    pub number_of_packs: f64,
+    pub inventory_reduction_id: Option<String>,
+    pub comment: Option<String>,
+    pub user_id: String,
+    pub assessed_datetime: NaiveDateTime,
+}
+
+pub struct TemperatureBreachStockImpactRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> TemperatureBreachStockImpactRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        TemperatureBreachStockImpactRowRepository { connection }
+    }
+
+    #[cfg(feature = "postgres")]
+    pub fn upsert_one(&self, row: &TemperatureBreachStockImpactRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(temperature_breach_stock_impact)
+            .values(row)
+            .on_conflict(id)
+            .do_update()
+            .set(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "postgres"))]
+    pub fn upsert_one(&self, row: &TemperatureBreachStockImpactRow) -> Result<(), RepositoryError> {
+        diesel::replace_into(temperature_breach_stock_impact)
+            .values(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        impact_id: &str,
+    ) -> Result<Option<TemperatureBreachStockImpactRow>, RepositoryError> {
+        let result = temperature_breach_stock_impact
+            .filter(id.eq(impact_id))
+            .first(&self.connection.connection)
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_one_by_stock_line(
+        &self,
+        breach_id: &str,
+        line_id: &str,
+    ) -> Result<Option<TemperatureBreachStockImpactRow>, RepositoryError> {
+        let result = temperature_breach_stock_impact
+            .filter(temperature_breach_id.eq(breach_id))
+            .filter(stock_line_id.eq(line_id))
+            .first(&self.connection.connection)
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_many_by_temperature_breach_id(
+        &self,
+        breach_id: &str,
+    ) -> Result<Vec<TemperatureBreachStockImpactRow>, RepositoryError> {
+        let result = temperature_breach_stock_impact
+            .filter(temperature_breach_id.eq(breach_id))
+            .order(assessed_datetime.asc())
+            .load(&self.connection.connection)?;
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        mock::MockDataInserts, test_db::setup_all, TemperatureBreachStockImpactRow,
+        TemperatureBreachStockImpactRowRepository, TemperatureBreachStockOutcome, VvmStatus,
+    };
+
+    #[actix_rt::test]
+    async fn temperature_breach_stock_impact_row() {
+        let (_, connection, _, _) = setup_all(
+            "temperature_breach_stock_impact_row",
+            MockDataInserts::none(),
+        )
+        .await;
+        let repo = TemperatureBreachStockImpactRowRepository::new(&connection);
+
+        let row = TemperatureBreachStockImpactRow {
+            id: "impact".to_string(),
+            temperature_breach_id: "breach".to_string(),
+            stock_line_id: "stock_line".to_string(),
+            store_id: "store".to_string(),
+            outcome: TemperatureBreachStockOutcome::Discard,
+            vvm_status: Some(VvmStatus::Stage3),
+            number_of_packs: 2.0,
+            ..Default::default()
+        };
+        repo.upsert_one(&row).unwrap();
+
+        assert_eq!(repo.find_one_by_id("impact"), Ok(Some(row.clone())));
+        assert_eq!(
+            repo.find_one_by_stock_line("breach", &row.stock_line_id),
+            Ok(Some(row.clone()))
+        );
+        assert_eq!(
+            repo.find_one_by_stock_line("other_breach", &row.stock_line_id),
+            Ok(None)
+        );
+        assert_eq!(
+            repo.find_many_by_temperature_breach_id("breach"),
+            Ok(vec![row])
+        );
+    }
+}
@@ -4,12 +4,12 @@ use crate::{
         temperature_log_row::temperature_log::dsl as temperature_log_dsl,
     },
     diesel_macros::{apply_date_time_filter, apply_equal_filter},
-    TemperatureBreachRowType, TemperatureLogFilter,
+    TemperatureBreachConfigRow, TemperatureBreachRowType, TemperatureLogFilter, TemperatureLogRow,
 };
 use crate::{RepositoryError, StorageConnection};
 use chrono::NaiveDateTime;
 
-use diesel::{prelude::*, sql_types::Integer};
+use diesel::prelude::*;
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct TemperatureExcursion {
@@ -29,17 +29,6 @@ pub struct TemperatureExcursionRepository<'a> {
     connection: &'a StorageConnection,
 }
 
-type QueryResult = (
-    String,
-    NaiveDateTime,
-    f64,
-    String,
-    String,
-    Option<String>,
-    i32,
-    bool,
-);
-
 #[derive(Debug, PartialEq, Clone)]
 pub struct TemperatureRow {
     pub id: String,
@@ -50,6 +39,8 @@ pub struct TemperatureRow {
     pub location_id: Option<String>,
     pub is_excursion: bool,
     pub duration: i64,
+    /// Excursion config the row was evaluated against
+    pub config: TemperatureBreachConfigRow,
 }
 
 impl<'a> TemperatureExcursionRepository<'a> {
@@ -57,7 +48,8 @@ impl<'a> TemperatureExcursionRepository<'a> {
         TemperatureExcursionRepository { connection }
     }
 
-    /// Result is sorted by datetime descending
+    /// Result is sorted by datetime ascending, with a row for each active excursion config of the
+    /// store (configs for other locations are included, they are matched in the service)
     pub fn query(
         &self,
         filter: TemperatureLogFilter,
@@ -67,19 +59,6 @@ impl<'a> TemperatureExcursionRepository<'a> {
                 temperature_breach_config_dsl::temperature_breach_config
                     .on(temperature_log_dsl::store_id.eq(temperature_breach_config_dsl::store_id)),
             )
-            .select((
-                temperature_log_dsl::id,
-                temperature_log_dsl::datetime,
-                temperature_log_dsl::temperature,
-                temperature_log_dsl::store_id,
-                temperature_log_dsl::sensor_id,
-                temperature_log_dsl::location_id,
-                (temperature_breach_config_dsl::duration_milliseconds / 1000).into_sql::<Integer>(),
-                temperature_log_dsl::temperature.not_between(
-                    temperature_breach_config_dsl::minimum_temperature,
-                    temperature_breach_config_dsl::maximum_temperature,
-                ),
-            ))
             .filter(temperature_log_dsl::temperature_breach_id.is_null())
             .order(temperature_log_dsl::datetime.asc())
             .into_boxed();
@@ -99,7 +78,7 @@ impl<'a> TemperatureExcursionRepository<'a> {
         // println!("{}", diesel::debug_query::<DBType, _>(&query).to_string());
 
         let log_data = query
-            .load::<QueryResult>(&self.connection.connection)?
+            .load::<(TemperatureLogRow, TemperatureBreachConfigRow)>(&self.connection.connection)?
             .into_iter()
             .map(TemperatureRow::from)
             .collect::<Vec<TemperatureRow>>();
@@ -109,18 +88,18 @@ impl<'a> TemperatureExcursionRepository<'a> {
 }
 
 impl TemperatureRow {
-    fn from(
-        (id, datetime, temperature, store_id, sensor_id, location_id, duration, is_excursion): QueryResult,
-    ) -> Self {
+    fn from((log, config): (TemperatureLogRow, TemperatureBreachConfigRow)) -> Self {
         Self {
-            id,
-            datetime,
-            temperature,
-            sensor_id,
-            location_id,
-            duration: duration as i64,
-            store_id,
-            is_excursion,
+            is_excursion: log.temperature < config.minimum_temperature
+                || log.temperature > config.maximum_temperature,
+            duration: (config.duration_milliseconds / 1000) as i64,
+            id: log.id,
+            datetime: log.datetime,
+            temperature: log.temperature,
+            sensor_id: log.sensor_id,
+            location_id: log.location_id,
+            store_id: log.store_id,
+            config,
         }
     }
 }
@@ -87,6 +87,45 @@ impl<'a> TemperatureLogRowRepository<'a> {
             .filter(temperature_log_dsl::id.eq_any(ids))
             .load(&self.connection.connection)?)
     }
+
+    /// Logs of the sensor from `from` (inclusive, all logs if None), sorted by datetime
+    pub fn find_many_by_sensor_id(
+        &self,
+        sensor_id: &str,
+        from: Option<NaiveDateTime>,
+    ) -> Result<Vec<TemperatureLogRow>, RepositoryError> {
+        let mut query = temperature_log_dsl::temperature_log
+            .filter(temperature_log_dsl::sensor_id.eq(sensor_id))
+            .into_boxed();
+        if let Some(from) = from {
+            query = query.filter(temperature_log_dsl::datetime.ge(from));
+        }
+        Ok(query
+            .order((
+                temperature_log_dsl::datetime.asc(),
+                temperature_log_dsl::id.asc(),
+            ))
+            .load(&self.connection.connection)?)
+    }
+
+    /// Latest log of the sensor before `before` with temperature within `minimum` and `maximum` (inclusive)
+    pub fn find_latest_within_range_before(
+        &self,
+        sensor_id: &str,
+        before: NaiveDateTime,
+        minimum: f64,
+        maximum: f64,
+    ) -> Result<Option<TemperatureLogRow>, RepositoryError> {
+        let result = temperature_log_dsl::temperature_log
+            .filter(temperature_log_dsl::sensor_id.eq(sensor_id))
+            .filter(temperature_log_dsl::datetime.lt(before))
+            .filter(temperature_log_dsl::temperature.ge(minimum))
+            .filter(temperature_log_dsl::temperature.le(maximum))
+            .order(temperature_log_dsl::datetime.desc())
+            .first(&self.connection.connection)
+            .optional()?;
+        Ok(result)
+    }
 }
 
 impl Upsert for TemperatureLogRow {
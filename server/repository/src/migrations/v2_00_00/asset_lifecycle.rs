@@ -0,0 +1,80 @@
+use crate::migrations::*;
+
+pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
+    #[cfg(not(feature = "postgres"))]
+    const TRANSFER_STATUS: &str = "TEXT";
+    #[cfg(feature = "postgres")]
+    const TRANSFER_STATUS: &str = "asset_transfer_status";
+    #[cfg(not(feature = "postgres"))]
+    const DISPOSAL_METHOD: &str = "TEXT";
+    #[cfg(feature = "postgres")]
+    const DISPOSAL_METHOD: &str = "asset_disposal_method";
+
+    #[cfg(feature = "postgres")]
+    sql!(
+        connection,
+        r#"
+            CREATE TYPE {TRANSFER_STATUS} AS ENUM (
+                'NEW',
+                'DISPATCHED',
+                'RECEIVED',
+                'CANCELLED'
+            );
+            CREATE TYPE {DISPOSAL_METHOD} AS ENUM (
+                'DESTROYED',
+                'SOLD',
+                'DONATED',
+                'RECYCLED',
+                'RETURNED_TO_SUPPLIER',
+                'OTHER'
+            );
+            ALTER TYPE changelog_table_name ADD VALUE IF NOT EXISTS 'asset_transfer';
+            ALTER TYPE changelog_table_name ADD VALUE IF NOT EXISTS 'asset_disposal';
+        "#
+    )?;
+
+    sql!(
+        connection,
+        r#"
+            ALTER TABLE asset ADD COLUMN decommissioned_date {DATE};
+            ALTER TABLE asset ADD COLUMN decommission_reason TEXT;
+        "#
+    )?;
+
+    // Transfers are synced to the sites of both stores, asset is not referenced as the
+    // destination site only receives the asset once it's dispatched
+    sql!(
+        connection,
+        r#"
+            CREATE TABLE asset_transfer (
+                id TEXT NOT NULL PRIMARY KEY,
+                asset_id TEXT NOT NULL,
+                from_store_id TEXT NOT NULL REFERENCES store(id),
+                to_store_id TEXT NOT NULL REFERENCES store(id),
+                status {TRANSFER_STATUS} NOT NULL,
+                comment TEXT,
+                created_datetime {DATETIME} NOT NULL,
+                created_by_user_id TEXT NOT NULL,
+                dispatched_datetime {DATETIME},
+                dispatched_by_user_id TEXT,
+                received_datetime {DATETIME},
+                received_by_user_id TEXT
+            );
+            CREATE INDEX index_asset_transfer_asset_id ON asset_transfer (asset_id);
+
+            CREATE TABLE asset_disposal (
+                id TEXT NOT NULL PRIMARY KEY,
+                asset_id TEXT NOT NULL REFERENCES asset(id),
+                store_id TEXT NOT NULL REFERENCES store(id),
+                disposal_date {DATE} NOT NULL,
+                method {DISPOSAL_METHOD} NOT NULL,
+                comment TEXT,
+                user_id TEXT NOT NULL,
+                created_datetime {DATETIME} NOT NULL
+            );
+            CREATE INDEX index_asset_disposal_asset_id ON asset_disposal (asset_id);
+        "#
+    )?;
+
+    Ok(())
+}
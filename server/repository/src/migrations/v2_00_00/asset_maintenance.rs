@@ -0,0 +1,66 @@
+use crate::migrations::*;
+
+pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
+    #[cfg(not(feature = "postgres"))]
+    const STATUS: &str = "TEXT";
+    #[cfg(feature = "postgres")]
+    const STATUS: &str = "asset_work_order_status";
+
+    #[cfg(feature = "postgres")]
+    sql!(
+        connection,
+        r#"
+            CREATE TYPE {STATUS} AS ENUM (
+                'OPEN',
+                'COMPLETED',
+                'CANCELLED'
+            );
+        "#
+    )?;
+
+    // Not synced, maintenance is planned and recorded by the site that has the assets. Completed
+    // work orders produce (synced) asset logs
+    sql!(
+        connection,
+        r#"
+            CREATE TABLE asset_maintenance_schedule (
+                id TEXT NOT NULL PRIMARY KEY,
+                name TEXT NOT NULL,
+                asset_type_id TEXT REFERENCES asset_type(id),
+                asset_catalogue_item_id TEXT REFERENCES asset_catalogue_item(id),
+                interval_days INTEGER,
+                interval_run_hours {DOUBLE},
+                is_active BOOLEAN NOT NULL,
+                created_datetime TIMESTAMP NOT NULL
+            );
+
+            CREATE TABLE asset_run_hours (
+                id TEXT NOT NULL PRIMARY KEY,
+                asset_id TEXT NOT NULL REFERENCES asset(id),
+                run_hours {DOUBLE} NOT NULL,
+                recorded_datetime TIMESTAMP NOT NULL
+            );
+            CREATE INDEX index_asset_run_hours_asset_id ON asset_run_hours (asset_id);
+
+            CREATE TABLE asset_work_order (
+                id TEXT NOT NULL PRIMARY KEY,
+                store_id TEXT NOT NULL REFERENCES store(id),
+                asset_id TEXT NOT NULL REFERENCES asset(id),
+                schedule_id TEXT NOT NULL REFERENCES asset_maintenance_schedule(id),
+                status {STATUS} NOT NULL,
+                due_date {DATE} NOT NULL,
+                assigned_user_id TEXT,
+                created_datetime TIMESTAMP NOT NULL,
+                completed_datetime TIMESTAMP,
+                completed_by_user_id TEXT,
+                run_hours {DOUBLE},
+                parts_used TEXT,
+                notes TEXT,
+                asset_log_id TEXT
+            );
+            CREATE INDEX index_asset_work_order_asset_id ON asset_work_order (asset_id);
+        "#
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use crate::{
+    migrations::{sql, DOUBLE},
+    StorageConnection,
+};
+
+pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
+    #[cfg(not(feature = "postgres"))]
+    const ENERGY_SOURCE: &str = "TEXT";
+    #[cfg(feature = "postgres")]
+    const ENERGY_SOURCE: &str = "asset_energy_source";
+
+    #[cfg(feature = "postgres")]
+    sql!(
+        connection,
+        r#"
+            CREATE TYPE {ENERGY_SOURCE} AS ENUM (
+                'ELECTRICITY',
+                'SOLAR',
+                'GAS',
+                'KEROSENE',
+                'PASSIVE'
+            );
+        "#
+    )?;
+
+    // Storage capacity is the net vaccine storage volume in litres
+    sql!(
+        connection,
+        r#"
+            ALTER TABLE asset_catalogue_item ADD COLUMN energy_source {ENERGY_SOURCE};
+            ALTER TABLE asset_catalogue_item ADD COLUMN storage_capacity {DOUBLE};
+        "#
+    )?;
+
+    // Not synced, population served is maintained on central server for CCE inventory reports
+    sql!(
+        connection,
+        r#"
+            CREATE TABLE store_population (
+                store_id TEXT NOT NULL PRIMARY KEY REFERENCES store(id),
+                population_served INTEGER NOT NULL
+            );
+        "#
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,28 @@
+use crate::{migrations::sql, StorageConnection};
+
+pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
+    sql!(
+        connection,
+        r#"
+        ALTER TABLE program_requisition_order_type ADD COLUMN is_emergency BOOLEAN NOT NULL DEFAULT FALSE;
+        ALTER TABLE program_requisition_order_type ADD COLUMN max_items_in_emergency_order INTEGER NOT NULL DEFAULT 0;
+
+        ALTER TABLE requisition ADD COLUMN is_emergency BOOLEAN NOT NULL DEFAULT FALSE;
+        ALTER TABLE requisition ADD COLUMN emergency_reason TEXT;
+        "#
+    )?;
+
+    // Emergency requisitions are grouped separately, so that they don't count towards
+    // max_order_per_period of the (non emergency) order type
+    sql!(
+        connection,
+        r#"
+        DROP VIEW IF EXISTS requisitions_in_period;
+        CREATE VIEW requisitions_in_period AS
+        SELECT 'n/a' as id, program_id, period_id, store_id, order_type, type, is_emergency, count(*) as count FROM requisition
+            GROUP BY 1,2,3,4,5,6,7;
+        "#
+    )?;
+
+    Ok(())
+}
@@ -4,14 +4,37 @@ use crate::StorageConnection;
 
 mod activity_log_add_zero_line;
 mod add_source_site_id;
+mod asset_lifecycle;
+mod asset_maintenance;
 mod assets;
+mod cce_inventory;
 mod central_omsupply;
+mod emergency_orders;
 mod inventory_adjustment_permissions;
 mod linked_shipment;
+mod notification_log;
 mod pack_variant;
+mod peer_sync_site;
 mod returns;
+mod sensor_api_key;
+mod sensor_type_generic;
 mod store_add_created_date;
+mod store_preference_lead_time;
+mod store_preference_over_order_threshold;
+mod sync_bandwidth;
+mod sync_buffer_is_ignored;
+mod sync_bundle;
+mod sync_conflict;
 mod sync_file_reference;
+mod sync_file_transfer;
+mod sync_health;
+mod sync_initialisation_progress;
+mod sync_scope;
+mod sync_v6_tables;
+mod temperature_breach_detection;
+mod temperature_breach_stock_impact;
+mod temperature_excursion_config;
+mod temperature_log_sensor_datetime_index;
 mod user_change_last_synced_to_optional;
 
 pub(crate) struct V2_00_00;
@@ -33,6 +56,29 @@ impl Migration for V2_00_00 {
         linked_shipment::migrate(connection)?;
         sync_file_reference::migrate(connection)?;
         user_change_last_synced_to_optional::migrate(connection)?;
+        emergency_orders::migrate(connection)?;
+        peer_sync_site::migrate(connection)?;
+        sync_bundle::migrate(connection)?;
+        sync_initialisation_progress::migrate(connection)?;
+        sync_bandwidth::migrate(connection)?;
+        sync_buffer_is_ignored::migrate(connection)?;
+        sync_conflict::migrate(connection)?;
+        sync_v6_tables::migrate(connection)?;
+        sync_file_transfer::migrate(connection)?;
+        sync_scope::migrate(connection)?;
+        sync_health::migrate(connection)?;
+        temperature_breach_detection::migrate(connection)?;
+        temperature_breach_stock_impact::migrate(connection)?;
+        sensor_type_generic::migrate(connection)?;
+        notification_log::migrate(connection)?;
+        temperature_excursion_config::migrate(connection)?;
+        sensor_api_key::migrate(connection)?;
+        temperature_log_sensor_datetime_index::migrate(connection)?;
+        cce_inventory::migrate(connection)?;
+        asset_maintenance::migrate(connection)?;
+        asset_lifecycle::migrate(connection)?;
+        store_preference_lead_time::migrate(connection)?;
+        store_preference_over_order_threshold::migrate(connection)?;
         Ok(())
     }
 }
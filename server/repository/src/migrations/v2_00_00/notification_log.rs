@@ -0,0 +1,52 @@
+use crate::migrations::*;
+
+pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
+    #[cfg(not(feature = "postgres"))]
+    const EVENT_TYPE: &str = "TEXT";
+    #[cfg(feature = "postgres")]
+    const EVENT_TYPE: &str = "notification_event_type";
+    #[cfg(not(feature = "postgres"))]
+    const STATUS: &str = "TEXT";
+    #[cfg(feature = "postgres")]
+    const STATUS: &str = "notification_status";
+
+    #[cfg(feature = "postgres")]
+    sql!(
+        connection,
+        r#"
+            CREATE TYPE {EVENT_TYPE} AS ENUM (
+                'TEMPERATURE_BREACH',
+                'SENSOR_BATTERY_LOW',
+                'SENSOR_NOT_REPORTING',
+                'TEMPERATURE_BREACH_ESCALATION'
+            );
+            CREATE TYPE {STATUS} AS ENUM (
+                'SENT',
+                'FAILED'
+            );
+            ALTER TYPE key_type ADD VALUE IF NOT EXISTS 'COLD_CHAIN_NOTIFICATION_START_DATETIME';
+        "#
+    )?;
+
+    // Not synced, notifications are sent by the site that has the sensor data
+    sql!(
+        connection,
+        r#"
+            CREATE TABLE notification_log (
+                id TEXT NOT NULL PRIMARY KEY,
+                store_id TEXT NOT NULL,
+                event_type {EVENT_TYPE} NOT NULL,
+                record_id TEXT NOT NULL,
+                channel TEXT NOT NULL,
+                recipient TEXT,
+                status {STATUS} NOT NULL,
+                message TEXT NOT NULL,
+                error TEXT,
+                created_datetime TIMESTAMP NOT NULL
+            );
+            CREATE INDEX index_notification_log_record_id ON notification_log (record_id);
+        "#
+    )?;
+
+    Ok(())
+}
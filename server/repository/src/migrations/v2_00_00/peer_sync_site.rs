@@ -0,0 +1,20 @@
+use crate::migrations::*;
+
+pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
+    sql!(
+        connection,
+        r#"
+            CREATE TABLE peer_sync_site (
+                id TEXT NOT NULL PRIMARY KEY, -- Hardware id of the peer
+                url TEXT NOT NULL,
+                site_id INTEGER,
+                last_seen_datetime TIMESTAMP NOT NULL,
+                pull_cursor BIGINT NOT NULL DEFAULT 0,
+                last_sync_datetime TIMESTAMP,
+                last_error TEXT
+            );
+        "#,
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,22 @@
+use crate::migrations::*;
+
+pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
+    // Not synced, keys are used by loggers pushing readings to this site
+    sql!(
+        connection,
+        r#"
+            CREATE TABLE sensor_api_key (
+                id TEXT NOT NULL PRIMARY KEY,
+                store_id TEXT NOT NULL REFERENCES store(id),
+                serial TEXT NOT NULL,
+                key_hash TEXT NOT NULL,
+                is_active BOOLEAN NOT NULL,
+                created_datetime TIMESTAMP NOT NULL,
+                last_used_datetime TIMESTAMP
+            );
+            CREATE INDEX index_sensor_api_key_key_hash ON sensor_api_key (key_hash);
+        "#
+    )?;
+
+    Ok(())
+}
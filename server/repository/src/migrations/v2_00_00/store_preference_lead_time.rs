@@ -0,0 +1,12 @@
+use crate::migrations::*;
+
+pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
+    sql!(
+        connection,
+        r#"
+            ALTER TABLE store_preference ADD COLUMN use_lead_time_in_suggested_quantity BOOLEAN NOT NULL DEFAULT FALSE;
+        "#
+    )?;
+
+    Ok(())
+}
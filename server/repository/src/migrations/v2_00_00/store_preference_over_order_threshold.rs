@@ -0,0 +1,12 @@
+use crate::migrations::*;
+
+pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
+    sql!(
+        connection,
+        r#"
+            ALTER TABLE store_preference ADD COLUMN requisition_over_order_threshold_percentage INTEGER NOT NULL DEFAULT 0;
+        "#
+    )?;
+
+    Ok(())
+}
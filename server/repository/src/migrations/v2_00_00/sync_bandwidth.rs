@@ -0,0 +1,17 @@
+use crate::migrations::*;
+
+pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
+    if cfg!(feature = "postgres") {
+        sql!(
+            connection,
+            r#"
+                ALTER TYPE key_type ADD VALUE IF NOT EXISTS 'SYNC_BANDWIDTH_DAILY_USAGE';
+                ALTER TYPE key_type ADD VALUE IF NOT EXISTS 'REMOTE_SYNC_PUSH_DEFERRED_CURSOR';
+                ALTER TYPE key_type ADD VALUE IF NOT EXISTS 'SYNC_PUSH_DEFERRED_CURSOR_V6';
+                ALTER TYPE key_type ADD VALUE IF NOT EXISTS 'SYNC_PULL_DEFERRED_CURSOR_V6';
+            "#
+        )?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,21 @@
+use crate::migrations::*;
+
+pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
+    sql!(
+        connection,
+        r#"
+            ALTER TABLE sync_buffer ADD COLUMN is_ignored BOOLEAN NOT NULL DEFAULT FALSE;
+        "#
+    )?;
+
+    if cfg!(feature = "postgres") {
+        sql!(
+            connection,
+            r#"
+                ALTER TYPE key_type ADD VALUE IF NOT EXISTS 'SYNC_BUFFER_FAILED_COUNT_HISTORY';
+            "#
+        )?;
+    }
+
+    Ok(())
+}
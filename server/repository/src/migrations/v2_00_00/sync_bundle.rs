@@ -0,0 +1,27 @@
+use crate::migrations::*;
+
+pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
+    sql!(
+        connection,
+        r#"
+            CREATE TABLE sync_bundle_site (
+                site_id INTEGER NOT NULL PRIMARY KEY,
+                last_sequence BIGINT NOT NULL,
+                last_imported_datetime TIMESTAMP NOT NULL
+            );
+        "#,
+    )?;
+
+    if cfg!(feature = "postgres") {
+        sql!(
+            connection,
+            r#"
+                ALTER TYPE key_type ADD VALUE IF NOT EXISTS 'SYNC_BUNDLE_EXPORT_SEQUENCE';
+                ALTER TYPE key_type ADD VALUE IF NOT EXISTS 'SYNC_BUNDLE_IMPORT_SEQUENCE';
+                ALTER TYPE key_type ADD VALUE IF NOT EXISTS 'SYNC_BUNDLE_PENDING_ACKNOWLEDGEMENTS';
+            "#
+        )?;
+    }
+
+    Ok(())
+}
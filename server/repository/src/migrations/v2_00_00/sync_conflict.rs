@@ -0,0 +1,54 @@
+use crate::migrations::*;
+
+pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
+    sql!(
+        connection,
+        r#"
+            CREATE TABLE sync_record_version (
+                table_name TEXT NOT NULL,
+                record_id TEXT NOT NULL,
+                remote_data TEXT NOT NULL,
+                local_data TEXT,
+                updated_datetime TIMESTAMP NOT NULL,
+                PRIMARY KEY (table_name, record_id)
+            );
+        "#,
+    )?;
+
+    #[cfg(not(feature = "postgres"))]
+    const SYNC_CONFLICT_RESOLUTION: &str = "TEXT";
+    #[cfg(feature = "postgres")]
+    const SYNC_CONFLICT_RESOLUTION: &str = "sync_conflict_resolution";
+    #[cfg(feature = "postgres")]
+    sql!(
+        connection,
+        r#"
+            CREATE TYPE {SYNC_CONFLICT_RESOLUTION} AS ENUM (
+                'LOCAL',
+                'REMOTE'
+            );
+        "#
+    )?;
+
+    sql!(
+        connection,
+        r#"
+            CREATE TABLE sync_conflict (
+                id TEXT NOT NULL PRIMARY KEY,
+                table_name TEXT NOT NULL,
+                record_id TEXT NOT NULL,
+                local_data TEXT NOT NULL,
+                remote_data TEXT NOT NULL,
+                conflicting_fields TEXT NOT NULL,
+                source_site_id INTEGER,
+                created_datetime TIMESTAMP NOT NULL,
+                resolution {SYNC_CONFLICT_RESOLUTION},
+                resolved_datetime TIMESTAMP,
+                resolved_by_user_id TEXT
+            );
+            CREATE INDEX index_sync_conflict_table_name_record_id ON sync_conflict (table_name, record_id);
+        "#,
+    )?;
+
+    Ok(())
+}
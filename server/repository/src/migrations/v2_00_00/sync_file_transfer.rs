@@ -0,0 +1,46 @@
+use crate::migrations::*;
+
+pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
+    #[cfg(not(feature = "postgres"))]
+    const SYNC_FILE_DIRECTION: &str = "TEXT";
+    #[cfg(feature = "postgres")]
+    const SYNC_FILE_DIRECTION: &str = "sync_file_direction";
+    #[cfg(not(feature = "postgres"))]
+    const SYNC_FILE_STATUS: &str = "TEXT";
+    #[cfg(feature = "postgres")]
+    const SYNC_FILE_STATUS: &str = "sync_file_status";
+    #[cfg(feature = "postgres")]
+    sql!(
+        connection,
+        r#"
+            CREATE TYPE {SYNC_FILE_DIRECTION} AS ENUM (
+                'UPLOAD',
+                'DOWNLOAD'
+            );
+            CREATE TYPE {SYNC_FILE_STATUS} AS ENUM (
+                'PENDING',
+                'IN_PROGRESS',
+                'DONE',
+                'ERROR'
+            );
+        "#
+    )?;
+
+    // Transfer state is local to the site, it's not part of sync record.
+    // Existing references are uploaded, or downloaded if file is not on this site
+    sql!(
+        connection,
+        r#"
+            ALTER TABLE sync_file_reference ADD COLUMN direction {SYNC_FILE_DIRECTION} NOT NULL DEFAULT 'UPLOAD';
+            ALTER TABLE sync_file_reference ADD COLUMN status {SYNC_FILE_STATUS} NOT NULL DEFAULT 'PENDING';
+            ALTER TABLE sync_file_reference ADD COLUMN total_bytes BIGINT NOT NULL DEFAULT 0;
+            ALTER TABLE sync_file_reference ADD COLUMN transferred_bytes BIGINT NOT NULL DEFAULT 0;
+            ALTER TABLE sync_file_reference ADD COLUMN hash TEXT;
+            ALTER TABLE sync_file_reference ADD COLUMN retries INTEGER NOT NULL DEFAULT 0;
+            ALTER TABLE sync_file_reference ADD COLUMN retry_datetime TIMESTAMP;
+            ALTER TABLE sync_file_reference ADD COLUMN error TEXT;
+        "#
+    )?;
+
+    Ok(())
+}
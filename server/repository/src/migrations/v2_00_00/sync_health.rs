@@ -0,0 +1,29 @@
+use crate::migrations::*;
+
+pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
+    #[cfg(not(feature = "postgres"))]
+    const SYNC_API_ERROR_CODE: &str = "TEXT";
+    #[cfg(feature = "postgres")]
+    const SYNC_API_ERROR_CODE: &str = "sync_api_error_code";
+
+    // Only populated on central server, from health reports sent by remote sites
+    sql!(
+        connection,
+        r#"
+            CREATE TABLE sync_health (
+                site_id INTEGER NOT NULL PRIMARY KEY,
+                site_name TEXT NOT NULL,
+                app_version TEXT NOT NULL,
+                reported_datetime TIMESTAMP NOT NULL,
+                last_sync_datetime TIMESTAMP,
+                last_successful_sync_datetime TIMESTAMP,
+                error_code {SYNC_API_ERROR_CODE},
+                error_message TEXT,
+                push_queue_count BIGINT NOT NULL DEFAULT 0,
+                database_size BIGINT NOT NULL DEFAULT 0
+            );
+        "#
+    )?;
+
+    Ok(())
+}
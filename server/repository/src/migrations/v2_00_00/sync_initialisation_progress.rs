@@ -0,0 +1,14 @@
+use crate::migrations::*;
+
+pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
+    if cfg!(feature = "postgres") {
+        sql!(
+            connection,
+            r#"
+                ALTER TYPE key_type ADD VALUE IF NOT EXISTS 'SYNC_INITIALISATION_EXPECTED_COUNTS';
+            "#
+        )?;
+    }
+
+    Ok(())
+}
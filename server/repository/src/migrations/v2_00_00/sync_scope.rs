@@ -0,0 +1,21 @@
+use crate::migrations::*;
+
+pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
+    sql!(
+        connection,
+        r#"
+            ALTER TABLE sync_buffer ADD COLUMN is_out_of_scope BOOLEAN NOT NULL DEFAULT FALSE;
+        "#
+    )?;
+
+    if cfg!(feature = "postgres") {
+        sql!(
+            connection,
+            r#"
+                ALTER TYPE key_type ADD VALUE IF NOT EXISTS 'SYNC_SCOPE';
+            "#
+        )?;
+    }
+
+    Ok(())
+}
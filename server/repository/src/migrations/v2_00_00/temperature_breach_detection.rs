@@ -0,0 +1,14 @@
+use crate::migrations::*;
+
+pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
+    if cfg!(feature = "postgres") {
+        sql!(
+            connection,
+            r#"
+                ALTER TYPE key_type ADD VALUE IF NOT EXISTS 'TEMPERATURE_BREACH_PROCESSOR_CURSOR';
+            "#
+        )?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use crate::migrations::*;
+use util::constants::COLD_CHAIN_BREACH_REASON_ID;
+
+pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
+    #[cfg(not(feature = "postgres"))]
+    const OUTCOME: &str = "TEXT";
+    #[cfg(feature = "postgres")]
+    const OUTCOME: &str = "temperature_breach_stock_outcome";
+    #[cfg(not(feature = "postgres"))]
+    const VVM_STATUS: &str = "TEXT";
+    #[cfg(feature = "postgres")]
+    const VVM_STATUS: &str = "vvm_status";
+
+    #[cfg(feature = "postgres")]
+    sql!(
+        connection,
+        r#"
+            CREATE TYPE {OUTCOME} AS ENUM (
+                'USABLE',
+                'DISCARD',
+                'QUARANTINE'
+            );
+            CREATE TYPE {VVM_STATUS} AS ENUM (
+                'STAGE_1',
+                'STAGE_2',
+                'STAGE_3',
+                'STAGE_4'
+            );
+        "#
+    )?;
+
+    sql!(
+        connection,
+        r#"
+            CREATE TABLE temperature_breach_stock_impact (
+                id TEXT NOT NULL PRIMARY KEY,
+                temperature_breach_id TEXT NOT NULL REFERENCES temperature_breach(id),
+                stock_line_id TEXT NOT NULL REFERENCES stock_line(id),
+                store_id TEXT NOT NULL REFERENCES store(id),
+                outcome {OUTCOME} NOT NULL,
+                vvm_status {VVM_STATUS},
+                number_of_packs {DOUBLE} NOT NULL DEFAULT 0,
+                inventory_reduction_id TEXT REFERENCES invoice(id),
+                comment TEXT,
+                user_id TEXT NOT NULL,
+                assessed_datetime TIMESTAMP NOT NULL,
+                UNIQUE (temperature_breach_id, stock_line_id)
+            );
+        "#
+    )?;
+
+    // Reason of inventory reductions for discarded stock, used to report cold chain wastage.
+    // Same fixed id on every site, skipped if it was already received from central
+    sql!(
+        connection,
+        r#"
+            INSERT INTO inventory_adjustment_reason (id, type, is_active, reason)
+            SELECT '{COLD_CHAIN_BREACH_REASON_ID}', 'NEGATIVE', TRUE, 'Cold chain breach'
+            WHERE NOT EXISTS (
+                SELECT 1 FROM inventory_adjustment_reason WHERE id = '{COLD_CHAIN_BREACH_REASON_ID}'
+            );
+        "#
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,19 @@
+use crate::{
+    migrations::{sql, DOUBLE},
+    StorageConnection,
+};
+
+pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
+    sql!(
+        connection,
+        r#"
+        ALTER TABLE temperature_breach_config ADD COLUMN location_id TEXT REFERENCES location(id);
+        ALTER TABLE temperature_breach_config ADD COLUMN lookback_days INTEGER NOT NULL DEFAULT 7;
+        ALTER TABLE temperature_breach_config ADD COLUMN minimum_excursion_duration_milliseconds INTEGER NOT NULL DEFAULT 0;
+        ALTER TABLE temperature_breach_config ADD COLUMN hysteresis_temperature {DOUBLE} NOT NULL DEFAULT 0;
+        ALTER TABLE temperature_breach_config ADD COLUMN hysteresis_duration_milliseconds INTEGER NOT NULL DEFAULT 0;
+        "#,
+    )?;
+
+    Ok(())
+}
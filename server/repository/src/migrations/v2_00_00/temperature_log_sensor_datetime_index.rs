@@ -0,0 +1,14 @@
+use crate::migrations::*;
+
+pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
+    // Not unique, temperature_log is synced and existing or incoming logs shouldn't be rejected,
+    // used to find logs of a sensor at a datetime when ingesting readings
+    sql!(
+        connection,
+        r#"
+            CREATE INDEX index_temperature_log_sensor_id_datetime ON temperature_log (sensor_id, datetime);
+        "#
+    )?;
+
+    Ok(())
+}
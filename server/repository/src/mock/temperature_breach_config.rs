@@ -10,6 +10,7 @@ pub fn mock_temperature_breach_config_1() -> TemperatureBreachConfigRow {
         store_id: "store_a".to_string(),
         minimum_temperature: -273.0,
         maximum_temperature: 8.0,
+        ..Default::default()
     }
 }
 pub fn mock_temperature_breach_config_2() -> TemperatureBreachConfigRow {
@@ -22,6 +23,7 @@ pub fn mock_temperature_breach_config_2() -> TemperatureBreachConfigRow {
         store_id: "store_a".to_string(),
         minimum_temperature: -273.0,
         maximum_temperature: -20.0,
+        ..Default::default()
     }
 }
 pub fn mock_temperature_breach_config_3() -> TemperatureBreachConfigRow {
@@ -34,6 +36,7 @@ pub fn mock_temperature_breach_config_3() -> TemperatureBreachConfigRow {
         store_id: "store_a".to_string(),
         minimum_temperature: 2.0,
         maximum_temperature: 100.0,
+        ..Default::default()
     }
 }
 
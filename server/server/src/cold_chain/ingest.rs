@@ -0,0 +1,121 @@
+use actix_web::{
+    http::header,
+    web::{self, Data},
+    HttpRequest, HttpResponse,
+};
+use chrono::NaiveDateTime;
+use log::error;
+use mime_guess::mime;
+use service::{
+    sensor::ingest::{IngestSensorReadings, IngestSensorReadingsError, SensorReading},
+    service_provider::ServiceProvider,
+};
+
+/// Readings pushed by a network connected logger, authenticated with the sensor's api key in
+/// the `Authorization: Bearer <key>` header
+#[derive(serde::Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadingBatch {
+    battery_level: Option<i32>,
+    log_interval: Option<i32>,
+    readings: Vec<Reading>,
+}
+
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct Reading {
+    #[serde(rename = "timestamp")]
+    unix_timestamp: i64,
+    temperature: f64,
+}
+
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct IngestResponse {
+    sensor_id: String,
+    is_new_sensor: bool,
+    number_of_logs: u32,
+    /// Timestamps of rejected readings, a log of the sensor already exists for them
+    duplicates: Vec<i64>,
+    number_of_breaches: u32,
+}
+
+pub async fn post_readings(
+    request: HttpRequest,
+    service_provider: Data<ServiceProvider>,
+    batch: web::Json<ReadingBatch>,
+) -> HttpResponse {
+    let Some(api_key) = api_key(&request) else {
+        return HttpResponse::Unauthorized().body("Missing api key");
+    };
+    let input = match to_domain(batch.into_inner()) {
+        Ok(input) => input,
+        Err(error) => return HttpResponse::BadRequest().body(error),
+    };
+
+    let ctx = match service_provider.basic_context() {
+        Ok(ctx) => ctx,
+        Err(error) => return HttpResponse::InternalServerError().body(format!("{:#?}", error)),
+    };
+
+    match service_provider
+        .sensor_service
+        .ingest_sensor_readings(&ctx, &api_key, input)
+    {
+        Ok(result) => HttpResponse::Ok()
+            .append_header(header::ContentType(mime::APPLICATION_JSON))
+            .json(IngestResponse {
+                sensor_id: result.sensor_id,
+                is_new_sensor: result.is_new_sensor,
+                number_of_logs: result.number_of_logs,
+                duplicates: result
+                    .duplicates
+                    .iter()
+                    .map(NaiveDateTime::timestamp)
+                    .collect(),
+                number_of_breaches: result.number_of_breaches,
+            }),
+        Err(IngestSensorReadingsError::InvalidApiKey) => {
+            HttpResponse::Unauthorized().body("Invalid api key")
+        }
+        Err(error @ IngestSensorReadingsError::NoReadings)
+        | Err(error @ IngestSensorReadingsError::TooManyReadings) => {
+            HttpResponse::BadRequest().body(format!("{:#?}", error))
+        }
+        Err(IngestSensorReadingsError::DatabaseError(error)) => {
+            error!("Error ingesting sensor readings {:#?}", error);
+            HttpResponse::InternalServerError().body(format!("{:#?}", error))
+        }
+    }
+}
+
+fn api_key(request: &HttpRequest) -> Option<String> {
+    let value = request
+        .headers()
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?;
+    let key = value.strip_prefix("Bearer ")?.trim();
+    (!key.is_empty()).then(|| key.to_string())
+}
+
+fn to_domain(batch: ReadingBatch) -> Result<IngestSensorReadings, String> {
+    let readings = batch
+        .readings
+        .into_iter()
+        .map(|reading| {
+            let datetime = NaiveDateTime::from_timestamp_opt(reading.unix_timestamp, 0)
+                .filter(|_| reading.unix_timestamp > 0)
+                .ok_or(format!("Invalid timestamp {}", reading.unix_timestamp))?;
+            Ok(SensorReading {
+                datetime,
+                temperature: reading.temperature,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    Ok(IngestSensorReadings {
+        battery_level: batch.battery_level,
+        log_interval: batch.log_interval,
+        readings,
+    })
+}
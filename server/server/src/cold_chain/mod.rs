@@ -9,10 +9,12 @@ use service::{
     user_account::UserAccountService,
 };
 
+mod ingest;
 mod login;
 mod sensor;
 mod temperature_breach;
 mod temperature_log;
+use ingest::post_readings;
 use login::post_login;
 use sensor::put_sensors;
 use temperature_breach::put_breaches;
@@ -32,6 +34,10 @@ pub fn config_cold_chain(cfg: &mut web::ServiceConfig) {
         &format!("{}/temperature-breach", URL_PATH),
         web::put().to(put_breaches),
     );
+    cfg.route(
+        &format!("{}/readings", URL_PATH),
+        web::post().to(post_readings),
+    );
 }
 
 fn validate_request(
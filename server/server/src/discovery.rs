@@ -1,5 +1,14 @@
 use crate::certs::Protocol;
-use {astro_dnssd::DNSServiceBuilder, std::collections::HashMap};
+use service::{service_provider::ServiceProvider, sync::peer_sync::upsert_discovered_peer};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use util::format_error;
+use {
+    astro_dnssd::{DNSServiceBuilder, ServiceBrowserBuilder, ServiceEventType},
+    std::collections::HashMap,
+};
 
 const SERVICE_NAME: &str = "_omsupply._tcp";
 const NAME: &str = "omSupplyServer";
@@ -7,6 +16,8 @@ const PROTOCOL_KEY: &str = "protocol";
 const CLIENT_VERSION_KEY: &str = "client_version";
 const HARDWARE_ID_KEY: &str = "hardware_id";
 const CLIENT_VERSION: &str = "unspecified";
+const BROWSE_TIMEOUT_MILLISECONDS: u64 = 500;
+const BROWSE_RETRY_SECONDS: u64 = 60;
 
 pub(crate) fn start_discovery(protocol: Protocol, port: u16, hardware_id: String) {
     tokio::task::spawn(async move {
@@ -26,3 +37,62 @@ pub(crate) fn start_discovery(protocol: Protocol, port: u16, hardware_id: String
         }
     });
 }
+
+/// Browse for other open mSupply servers on the local network and record them as peers
+/// for peer sync (servers are identified by hardware id, this server is skipped)
+pub(crate) fn start_peer_browsing(service_provider: Arc<ServiceProvider>, hardware_id: String) {
+    // Browser blocks while waiting for events, so it runs on its own thread
+    std::thread::spawn(move || loop {
+        let browser = match ServiceBrowserBuilder::new(SERVICE_NAME).browse() {
+            Ok(browser) => browser,
+            Err(e) => {
+                log::error!("Error browsing for peers: {:?}", e);
+                std::thread::sleep(Duration::from_secs(BROWSE_RETRY_SECONDS));
+                continue;
+            }
+        };
+
+        loop {
+            let timeout = Duration::from_millis(BROWSE_TIMEOUT_MILLISECONDS);
+            let started = Instant::now();
+            let service = match browser.recv_timeout(timeout) {
+                Ok(service) => service,
+                // No events within timeout
+                Err(_) if started.elapsed() >= timeout => continue,
+                // Browser stopped (e.g. disconnected from DNS-SD daemon), it's restarted after a delay
+                Err(e) => {
+                    log::error!("Error receiving discovered peers: {:?}", e);
+                    break;
+                }
+            };
+            if !matches!(service.event_type, ServiceEventType::Added) {
+                continue;
+            }
+
+            let text_record = service.txt_record.unwrap_or_default();
+            let Some(peer_hardware_id) = text_record.get(HARDWARE_ID_KEY) else {
+                continue;
+            };
+            if peer_hardware_id == &hardware_id {
+                continue;
+            }
+            let protocol = text_record
+                .get(PROTOCOL_KEY)
+                .cloned()
+                .unwrap_or_else(|| Protocol::Http.to_string());
+            let url = format!(
+                "{}://{}:{}",
+                protocol,
+                service.hostname.trim_end_matches('.'),
+                service.port
+            );
+
+            log::info!("Discovered peer {} at {}", peer_hardware_id, url);
+            if let Err(error) = upsert_discovered_peer(&service_provider, peer_hardware_id, &url) {
+                log::error!("Problem recording discovered peer {}", format_error(&error));
+            }
+        }
+
+        std::thread::sleep(Duration::from_secs(BROWSE_RETRY_SECONDS));
+    });
+}
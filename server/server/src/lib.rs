@@ -3,8 +3,8 @@ extern crate machine_uid;
 
 use crate::{
     certs::Certificates, cold_chain::config_cold_chain, configuration::get_or_create_token_secret,
-    cors::cors_policy, middleware::central_server_only, print::config_print,
-    serve_frontend::config_serve_frontend, static_files::config_static_files,
+    cors::cors_policy, middleware::central_server_only, peer_sync::config_peer_sync,
+    print::config_print, serve_frontend::config_serve_frontend, static_files::config_static_files,
     support::config_support, sync_on_central::config_sync_on_central,
     upload_fridge_tag::config_upload_fridge_tag,
 };
@@ -21,7 +21,9 @@ use log::info;
 use repository::{get_storage_connection_manager, migrations::migrate};
 
 use service::{
+    asset::maintenance::generate::run_work_order_generation,
     auth_data::AuthData,
+    cold_chain::notification::run_notifications,
     plugin::validation::ValidatedPluginBucket,
     processors::Processors,
     service_provider::ServiceProvider,
@@ -46,6 +48,7 @@ pub mod static_files;
 pub mod support;
 mod upload_fridge_tag;
 pub use self::logging::*;
+mod peer_sync;
 pub mod print;
 mod sync_on_central;
 
@@ -250,7 +253,15 @@ pub async fn start_server(
     #[cfg(not(target_os = "android"))]
     {
         info!("Starting server DNS-SD discovery",);
-        discovery::start_discovery(certificates.protocol(), settings.server.port, machine_uid);
+        discovery::start_discovery(
+            certificates.protocol(),
+            settings.server.port,
+            machine_uid.clone(),
+        );
+        if settings.peer_sync.is_some() {
+            info!("Starting peer sync DNS-SD browsing",);
+            discovery::start_peer_browsing(service_provider.clone().into_inner(), machine_uid);
+        }
     }
 
     info!("Starting discovery graphql server",);
@@ -286,8 +297,22 @@ pub async fn start_server(
     let synchroniser_task = synchroniser_driver.run(
         service_provider.clone().into_inner(),
         force_trigger_sync_on_startup,
+        settings.peer_sync.clone(),
+        settings.sync_bandwidth.clone(),
+        settings.server.base_dir.clone(),
     );
 
+    if let Some(notification_settings) = settings.notifications.clone() {
+        info!("Starting cold chain notifications");
+        actix_web::rt::spawn(run_notifications(
+            service_provider.clone().into_inner(),
+            notification_settings,
+        ));
+    }
+    actix_web::rt::spawn(run_work_order_generation(
+        service_provider.clone().into_inner(),
+    ));
+
     let closure_settings = settings.clone();
     let mut http_server = HttpServer::new(move || {
         App::new()
@@ -306,6 +331,7 @@ pub async fn start_server(
             .configure(config_cold_chain)
             .configure(config_upload_fridge_tag)
             .configure(config_sync_on_central)
+            .configure(config_peer_sync)
             .configure(config_support)
             .configure(config_print)
             // Needs to be last to capture all unmatches routes
@@ -0,0 +1,33 @@
+use actix_web::{
+    post,
+    web::{self, Data, Json},
+    Responder,
+};
+
+use service::{
+    service_provider::ServiceProvider,
+    settings::Settings,
+    sync::peer_sync::{self, PeerSyncPullRequest, PeerSyncPullResponse},
+};
+
+pub fn config_peer_sync(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::scope("peer").service(pull));
+}
+
+#[post("/sync/pull")]
+async fn pull(
+    request: Json<PeerSyncPullRequest>,
+    service_provider: Data<ServiceProvider>,
+    settings: Data<Settings>,
+) -> actix_web::Result<impl Responder> {
+    let response = match peer_sync::pull(
+        &service_provider,
+        settings.peer_sync.as_ref(),
+        request.into_inner(),
+    ) {
+        Ok(batch) => PeerSyncPullResponse::Data(batch),
+        Err(error) => PeerSyncPullResponse::Error(error),
+    };
+
+    Ok(web::Json(response))
+}
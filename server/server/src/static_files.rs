@@ -214,6 +214,8 @@ async fn upload_sync_file(
             created_datetime: chrono::Utc::now().naive_utc(),
             deleted_datetime: None,
             record_id: record_id.clone(),
+            // Uploaded to central server by sync (size and hash are calculated before upload)
+            ..Default::default()
         });
         match result {
             Ok(_) => {}
@@ -1,4 +1,6 @@
 use actix_web::{
+    http::header::ACCEPT_ENCODING,
+    middleware::{Compress, DefaultHeaders},
     post,
     web::{self, Data, Json},
     Responder,
@@ -7,9 +9,16 @@ use actix_web::{
 use crate::central_server_only;
 use service::{
     service_provider::ServiceProvider,
+    settings::Settings,
     sync::{
-        api_v6::{SyncPullRequestV6, SyncPullResponseV6, SyncPushRequestV6, SyncPushResponseV6},
-        sync_on_central,
+        api_v6::{
+            SyncDownloadFileRequestV6, SyncDownloadFileResponseV6,
+            SyncInitialisationSummaryRequestV6, SyncInitialisationSummaryResponseV6,
+            SyncPullRequestV6, SyncPullResponseV6, SyncPushRequestV6, SyncPushResponseV6,
+            SyncReportHealthRequestV6, SyncReportHealthResponseV6, SyncUploadFileRequestV6,
+            SyncUploadFileResponseV6,
+        },
+        sync_on_central, SYNC_ACCEPT_ENCODING,
     },
 };
 
@@ -17,8 +26,16 @@ pub fn config_sync_on_central(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("central")
             .wrap(central_server_only())
+            // Responses are compressed when requested by remote site, zstd and gzip request bodies are
+            // decompressed by Json extractor, this header lets remote sites know they can send them (RFC 7694)
+            .wrap(DefaultHeaders::new().add((ACCEPT_ENCODING, SYNC_ACCEPT_ENCODING)))
+            .wrap(Compress::default())
             .service(pull)
-            .service(push),
+            .service(push)
+            .service(initialisation_summary)
+            .service(upload_file)
+            .service(download_file)
+            .service(report_health),
     );
 }
 
@@ -47,3 +64,75 @@ async fn push(
 
     Ok(web::Json(response))
 }
+
+#[post("/sync/initialisation_summary")]
+async fn initialisation_summary(
+    request: Json<SyncInitialisationSummaryRequestV6>,
+    service_provider: Data<ServiceProvider>,
+) -> actix_web::Result<impl Responder> {
+    let response = match sync_on_central::initialisation_summary(
+        &service_provider,
+        request.into_inner(),
+    )
+    .await
+    {
+        Ok(summary) => SyncInitialisationSummaryResponseV6::Data(summary),
+        Err(error) => SyncInitialisationSummaryResponseV6::Error(error),
+    };
+
+    Ok(web::Json(response))
+}
+
+#[post("/sync/upload_file")]
+async fn upload_file(
+    request: Json<SyncUploadFileRequestV6>,
+    service_provider: Data<ServiceProvider>,
+    settings: Data<Settings>,
+) -> actix_web::Result<impl Responder> {
+    let response = match sync_on_central::upload_file(
+        &service_provider,
+        &settings.server.base_dir,
+        request.into_inner(),
+    )
+    .await
+    {
+        Ok(result) => SyncUploadFileResponseV6::Data(result),
+        Err(error) => SyncUploadFileResponseV6::Error(error),
+    };
+
+    Ok(web::Json(response))
+}
+
+#[post("/sync/download_file")]
+async fn download_file(
+    request: Json<SyncDownloadFileRequestV6>,
+    service_provider: Data<ServiceProvider>,
+    settings: Data<Settings>,
+) -> actix_web::Result<impl Responder> {
+    let response = match sync_on_central::download_file(
+        &service_provider,
+        &settings.server.base_dir,
+        request.into_inner(),
+    )
+    .await
+    {
+        Ok(chunk) => SyncDownloadFileResponseV6::Data(chunk),
+        Err(error) => SyncDownloadFileResponseV6::Error(error),
+    };
+
+    Ok(web::Json(response))
+}
+
+#[post("/sync/report_health")]
+async fn report_health(
+    request: Json<SyncReportHealthRequestV6>,
+    service_provider: Data<ServiceProvider>,
+) -> actix_web::Result<impl Responder> {
+    let response =
+        match sync_on_central::report_health(&service_provider, request.into_inner()).await {
+            Ok(result) => SyncReportHealthResponseV6::Data(result),
+            Err(error) => SyncReportHealthResponseV6::Error(error),
+        };
+
+    Ok(web::Json(response))
+}
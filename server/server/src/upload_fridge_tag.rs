@@ -11,7 +11,10 @@ use anyhow::Context;
 use serde::Deserialize;
 
 use service::{
-    sensor::berlinger::{read_sensor, ReadSensor},
+    sensor::{
+        berlinger::{read_sensor, ReadSensor},
+        logger_file::{import_logger_file, SensorFileParsers},
+    },
     service_provider::ServiceProvider,
     settings::Settings,
 };
@@ -33,6 +36,8 @@ struct UploadForm {
 #[serde(rename_all = "kebab-case")]
 struct UrlParams {
     store_id: String,
+    /// Name of a configured temperature logger, Berlinger file is expected when not set
+    logger: Option<String>,
 }
 
 #[post("/fridge-tag")]
@@ -88,6 +93,28 @@ fn upload_fridge_tag(
 
     move_file(file.file.path(), &new_file_path)?;
 
+    if let Some(logger) = &url_params.logger {
+        let parsers = SensorFileParsers::from_settings(
+            settings.temperature_loggers.as_deref().unwrap_or_default(),
+        );
+        let parser = parsers
+            .get(logger)
+            .with_context(|| format!("Temperature logger {logger} is not configured"))?;
+        let content = std::fs::read(&new_file_path)?;
+        let file_name = new_file_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        return ctx
+            .connection
+            .transaction_sync(|con| {
+                import_logger_file(con, &url_params.store_id, parser, &file_name, &content)
+                    .context("Error while integrating logger file")
+            })
+            .map_err(|error| error.to_inner_error());
+    }
+
     ctx.connection
         .transaction_sync(|con| {
             read_sensor(con, &url_params.store_id, new_file_path)
@@ -0,0 +1,200 @@
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{NaiveDate, Utc};
+use repository::{
+    assets::{
+        asset::{Asset, AssetFilter, AssetRepository},
+        asset_catalogue_item_row::{
+            AssetCatalogueItemRow, AssetCatalogueItemRowRepository, AssetEnergySource,
+        },
+        asset_log::{AssetLogFilter, AssetLogRepository},
+        asset_log_row::{AssetLogRow, AssetLogStatus},
+    },
+    EqualFilter, RepositoryError, StorageConnection, StorePopulationRowRepository,
+};
+
+#[derive(Debug, PartialEq)]
+pub enum CceInventoryError {
+    LitresPerPersonNegative,
+    DatabaseError(RepositoryError),
+}
+
+#[derive(Clone, Default)]
+pub struct CceInventoryInput {
+    /// All facilities with equipment or population when not set
+    pub store_ids: Option<Vec<String>>,
+    /// Vaccine storage volume required per person served, in litres
+    pub litres_per_person: f64,
+    pub filter: Option<AssetFilter>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CceInventoryLine {
+    pub asset: Asset,
+    pub catalogue_item: Option<AssetCatalogueItemRow>,
+    /// Status of the latest asset log
+    pub status: Option<AssetLogStatus>,
+    /// Full years since installation
+    pub age_years: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CceFacilityInventory {
+    pub store_id: String,
+    pub population_served: Option<i32>,
+    pub lines: Vec<CceInventoryLine>,
+    /// Storage capacity of equipment that is not decommissioned, in litres
+    pub total_storage_capacity: f64,
+    /// Storage capacity of functioning equipment, in litres
+    pub net_storage_capacity: f64,
+    /// Population served multiplied by litres per person
+    pub required_storage_capacity: Option<f64>,
+}
+
+impl CceInventoryLine {
+    pub fn energy_source(&self) -> Option<AssetEnergySource> {
+        self.catalogue_item
+            .as_ref()
+            .and_then(|item| item.energy_source.clone())
+    }
+
+    pub fn storage_capacity(&self) -> Option<f64> {
+        self.catalogue_item
+            .as_ref()
+            .and_then(|item| item.storage_capacity)
+    }
+
+    pub fn is_functioning(&self) -> bool {
+        matches!(
+            self.status,
+            Some(AssetLogStatus::Functioning) | Some(AssetLogStatus::FunctioningButNeedsAttention)
+        )
+    }
+}
+
+impl CceFacilityInventory {
+    /// Net minus required storage capacity, negative when the facility has a shortage
+    pub fn storage_capacity_difference(&self) -> Option<f64> {
+        self.required_storage_capacity
+            .map(|required| self.net_storage_capacity - required)
+    }
+}
+
+/// Cold chain equipment inventory per facility. Equipment capacity and energy source come from
+/// the catalogue item of the asset, functional status from the latest asset log
+pub fn get_cce_inventory(
+    connection: &StorageConnection,
+    input: CceInventoryInput,
+) -> Result<Vec<CceFacilityInventory>, CceInventoryError> {
+    cce_inventory(connection, input, Utc::now().naive_utc().date())
+}
+
+pub(crate) fn cce_inventory(
+    connection: &StorageConnection,
+    CceInventoryInput {
+        store_ids,
+        litres_per_person,
+        filter,
+    }: CceInventoryInput,
+    today: NaiveDate,
+) -> Result<Vec<CceFacilityInventory>, CceInventoryError> {
+    if litres_per_person < 0.0 {
+        return Err(CceInventoryError::LitresPerPersonNegative);
+    }
+    let is_included = |store_id: &str| match &store_ids {
+        Some(store_ids) => store_ids.iter().any(|id| id == store_id),
+        None => true,
+    };
+
+    let assets: Vec<Asset> = AssetRepository::new(connection)
+        .query_by_filter(filter.unwrap_or_default())?
+        .into_iter()
+        .filter(|asset| asset.store_id.as_deref().map_or(false, is_included))
+        .collect();
+
+    let asset_ids: Vec<String> = assets.iter().map(|asset| asset.id.clone()).collect();
+    let statuses: HashMap<String, AssetLogStatus> = AssetLogRepository::new(connection)
+        .query_latest(Some(
+            AssetLogFilter::new().asset_id(EqualFilter::equal_any(asset_ids)),
+        ))?
+        .into_iter()
+        .filter_map(|log| {
+            let AssetLogRow {
+                asset_id, status, ..
+            } = log;
+            status.map(|status| (asset_id, status))
+        })
+        .collect();
+    let catalogue_items: HashMap<String, AssetCatalogueItemRow> =
+        AssetCatalogueItemRowRepository::new(connection)
+            .find_all()?
+            .into_iter()
+            .map(|item| (item.id.clone(), item))
+            .collect();
+
+    let mut facilities: BTreeMap<String, CceFacilityInventory> = BTreeMap::new();
+    for population in StorePopulationRowRepository::new(connection).find_all()? {
+        if !is_included(&population.store_id) {
+            continue;
+        }
+        facilities.insert(
+            population.store_id.clone(),
+            new_facility(population.store_id, Some(population.population_served)),
+        );
+    }
+
+    for asset in assets {
+        // Assets are filtered by store above
+        let store_id = asset.store_id.clone().unwrap_or_default();
+        let line = CceInventoryLine {
+            catalogue_item: asset
+                .catalogue_item_id
+                .as_ref()
+                .and_then(|id| catalogue_items.get(id).cloned()),
+            status: statuses.get(&asset.id).cloned(),
+            age_years: asset
+                .installation_date
+                .and_then(|installation_date| today.years_since(installation_date)),
+            asset,
+        };
+
+        let facility = facilities
+            .entry(store_id.clone())
+            .or_insert_with(|| new_facility(store_id, None));
+        let capacity = line.storage_capacity().unwrap_or(0.0);
+        if line.status != Some(AssetLogStatus::Decommissioned) {
+            facility.total_storage_capacity += capacity;
+        }
+        if line.is_functioning() {
+            facility.net_storage_capacity += capacity;
+        }
+        facility.lines.push(line);
+    }
+
+    Ok(facilities
+        .into_values()
+        .map(|facility| CceFacilityInventory {
+            required_storage_capacity: facility
+                .population_served
+                .map(|population| population as f64 * litres_per_person),
+            ..facility
+        })
+        .collect())
+}
+
+fn new_facility(store_id: String, population_served: Option<i32>) -> CceFacilityInventory {
+    CceFacilityInventory {
+        store_id,
+        population_served,
+        lines: Vec::new(),
+        total_storage_capacity: 0.0,
+        net_storage_capacity: 0.0,
+        required_storage_capacity: None,
+    }
+}
+
+impl From<RepositoryError> for CceInventoryError {
+    fn from(error: RepositoryError) -> Self {
+        CceInventoryError::DatabaseError(error)
+    }
+}
@@ -0,0 +1,95 @@
+use repository::{
+    assets::asset_catalogue_item_row::{
+        AssetCatalogueItemRow, AssetCatalogueItemRowRepository, AssetEnergySource,
+    },
+    RepositoryError, StorePopulationRow, StorePopulationRowRepository, StoreRowRepository,
+};
+
+use crate::service_provider::ServiceContext;
+
+#[derive(Debug, PartialEq)]
+pub enum UpsertStorePopulationError {
+    StoreDoesNotExist,
+    PopulationServedNegative,
+    DatabaseError(RepositoryError),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum UpdateCatalogueItemPropertiesError {
+    CatalogueItemDoesNotExist,
+    StorageCapacityNegative,
+    DatabaseError(RepositoryError),
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct UpsertStorePopulation {
+    pub store_id: String,
+    pub population_served: i32,
+}
+
+/// Properties used by CCE inventory reports, unset values are cleared
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct UpdateCatalogueItemProperties {
+    pub id: String,
+    pub energy_source: Option<AssetEnergySource>,
+    pub storage_capacity: Option<f64>,
+}
+
+pub fn upsert_store_population(
+    ctx: &ServiceContext,
+    input: UpsertStorePopulation,
+) -> Result<StorePopulationRow, UpsertStorePopulationError> {
+    if input.population_served < 0 {
+        return Err(UpsertStorePopulationError::PopulationServedNegative);
+    }
+    if StoreRowRepository::new(&ctx.connection)
+        .find_one_by_id(&input.store_id)?
+        .is_none()
+    {
+        return Err(UpsertStorePopulationError::StoreDoesNotExist);
+    }
+
+    let row = StorePopulationRow {
+        store_id: input.store_id,
+        population_served: input.population_served,
+    };
+    StorePopulationRowRepository::new(&ctx.connection).upsert_one(&row)?;
+    Ok(row)
+}
+
+/// Updated catalogue items are synced to remote sites
+pub fn update_catalogue_item_properties(
+    ctx: &ServiceContext,
+    input: UpdateCatalogueItemProperties,
+) -> Result<AssetCatalogueItemRow, UpdateCatalogueItemPropertiesError> {
+    if input
+        .storage_capacity
+        .map_or(false, |capacity| capacity < 0.0)
+    {
+        return Err(UpdateCatalogueItemPropertiesError::StorageCapacityNegative);
+    }
+    let repo = AssetCatalogueItemRowRepository::new(&ctx.connection);
+    let item = repo
+        .find_one_by_id(&input.id)?
+        .ok_or(UpdateCatalogueItemPropertiesError::CatalogueItemDoesNotExist)?;
+
+    let item = AssetCatalogueItemRow {
+        energy_source: input.energy_source,
+        storage_capacity: input.storage_capacity,
+        ..item
+    };
+    repo.upsert_one(&item)?;
+    Ok(item)
+}
+
+impl From<RepositoryError> for UpsertStorePopulationError {
+    fn from(error: RepositoryError) -> Self {
+        UpsertStorePopulationError::DatabaseError(error)
+    }
+}
+
+impl From<RepositoryError> for UpdateCatalogueItemPropertiesError {
+    fn from(error: RepositoryError) -> Self {
+        UpdateCatalogueItemPropertiesError::DatabaseError(error)
+    }
+}
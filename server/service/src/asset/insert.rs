@@ -136,6 +136,8 @@ pub fn generate(
         created_datetime: Utc::now().naive_utc(),
         modified_datetime: Utc::now().naive_utc(),
         deleted_datetime: None,
+        decommissioned_date: None,
+        decommission_reason: None,
         asset_category_id: Some(category_id.unwrap_or_default()),
         asset_class_id: Some(class_id.unwrap_or_default()),
         asset_type_id: Some(type_id.unwrap_or_default()),
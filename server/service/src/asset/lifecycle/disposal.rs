@@ -0,0 +1,176 @@
+use chrono::{NaiveDate, Utc};
+use repository::{
+    asset_log_row::{AssetLogReason, AssetLogRow, AssetLogRowRepository, AssetLogStatus},
+    assets::{
+        asset_disposal_row::{AssetDisposalMethod, AssetDisposalRow, AssetDisposalRowRepository},
+        asset_row::{AssetRow, AssetRowRepository},
+    },
+    ActivityLogType, RepositoryError,
+};
+use util::uuid::uuid;
+
+use super::check_transfer_in_progress;
+use crate::{
+    activity_log::activity_log_entry, asset::maintenance::work_order::cancel_open_work_orders,
+    service_provider::ServiceContext,
+};
+
+#[derive(Debug, PartialEq)]
+pub enum DecommissionAssetError {
+    AssetDoesNotExist,
+    AssetDoesNotBelongToCurrentStore,
+    AssetAlreadyDecommissioned,
+    AssetHasTransferInProgress,
+    ReasonIsEmpty,
+    DatabaseError(RepositoryError),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum InsertAssetDisposalError {
+    AssetDisposalAlreadyExists,
+    AssetDoesNotExist,
+    AssetDoesNotBelongToCurrentStore,
+    AssetNotDecommissioned,
+    AssetAlreadyDisposed,
+    DisposalDateBeforeDecommissioning,
+    DatabaseError(RepositoryError),
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DecommissionAsset {
+    pub asset_id: String,
+    pub decommissioned_date: NaiveDate,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct InsertAssetDisposal {
+    pub id: String,
+    pub asset_id: String,
+    pub disposal_date: NaiveDate,
+    pub method: AssetDisposalMethod,
+    pub comment: Option<String>,
+}
+
+/// Takes the asset out of service and adds a decommissioned asset log with the reason, open
+/// maintenance work orders of the asset are cancelled
+pub fn decommission_asset(
+    ctx: &ServiceContext,
+    input: DecommissionAsset,
+) -> Result<AssetRow, DecommissionAssetError> {
+    let result = ctx
+        .connection
+        .transaction_sync(|connection| -> Result<_, DecommissionAssetError> {
+            use DecommissionAssetError::*;
+
+            let asset = AssetRowRepository::new(connection)
+                .find_one_by_id(&input.asset_id)?
+                .filter(|asset| asset.deleted_datetime.is_none())
+                .ok_or(AssetDoesNotExist)?;
+            if asset.store_id.as_ref() != Some(&ctx.store_id) {
+                return Err(AssetDoesNotBelongToCurrentStore);
+            }
+            if asset.decommissioned_date.is_some() {
+                return Err(AssetAlreadyDecommissioned);
+            }
+            if check_transfer_in_progress(connection, &asset.id)? {
+                return Err(AssetHasTransferInProgress);
+            }
+            if input.reason.trim().is_empty() {
+                return Err(ReasonIsEmpty);
+            }
+
+            let now = Utc::now().naive_utc();
+            let asset = AssetRow {
+                decommissioned_date: Some(input.decommissioned_date),
+                decommission_reason: Some(input.reason.clone()),
+                modified_datetime: now,
+                ..asset
+            };
+            AssetRowRepository::new(connection).upsert_one(&asset)?;
+            cancel_open_work_orders(connection, &asset.id)?;
+
+            let asset_log = AssetLogRow {
+                id: uuid(),
+                asset_id: asset.id.clone(),
+                user_id: ctx.user_id.clone(),
+                status: Some(AssetLogStatus::Decommissioned),
+                comment: Some(input.reason),
+                r#type: None,
+                reason: Some(AssetLogReason::Decommissioned),
+                log_datetime: now,
+            };
+            AssetLogRowRepository::new(connection).upsert_one(&asset_log)?;
+            activity_log_entry(
+                ctx,
+                ActivityLogType::AssetLogCreated,
+                Some(asset_log.id),
+                None,
+                None,
+            )?;
+
+            Ok(asset)
+        })
+        .map_err(|error| error.to_inner_error())?;
+    Ok(result)
+}
+
+/// Records the disposal of a decommissioned asset, only one disposal is allowed per asset
+pub fn insert_asset_disposal(
+    ctx: &ServiceContext,
+    input: InsertAssetDisposal,
+) -> Result<AssetDisposalRow, InsertAssetDisposalError> {
+    let result = ctx
+        .connection
+        .transaction_sync(|connection| -> Result<_, InsertAssetDisposalError> {
+            use InsertAssetDisposalError::*;
+
+            let repo = AssetDisposalRowRepository::new(connection);
+            if repo.find_one_by_id(&input.id)?.is_some() {
+                return Err(AssetDisposalAlreadyExists);
+            }
+            let asset = AssetRowRepository::new(connection)
+                .find_one_by_id(&input.asset_id)?
+                .filter(|asset| asset.deleted_datetime.is_none())
+                .ok_or(AssetDoesNotExist)?;
+            if asset.store_id.as_ref() != Some(&ctx.store_id) {
+                return Err(AssetDoesNotBelongToCurrentStore);
+            }
+            let Some(decommissioned_date) = asset.decommissioned_date else {
+                return Err(AssetNotDecommissioned);
+            };
+            if input.disposal_date < decommissioned_date {
+                return Err(DisposalDateBeforeDecommissioning);
+            }
+            if repo.find_one_by_asset_id(&asset.id)?.is_some() {
+                return Err(AssetAlreadyDisposed);
+            }
+
+            let disposal = AssetDisposalRow {
+                id: input.id,
+                asset_id: asset.id,
+                store_id: ctx.store_id.clone(),
+                disposal_date: input.disposal_date,
+                method: input.method,
+                comment: input.comment,
+                user_id: ctx.user_id.clone(),
+                created_datetime: Utc::now().naive_utc(),
+            };
+            repo.upsert_one(&disposal)?;
+            Ok(disposal)
+        })
+        .map_err(|error| error.to_inner_error())?;
+    Ok(result)
+}
+
+impl From<RepositoryError> for DecommissionAssetError {
+    fn from(error: RepositoryError) -> Self {
+        DecommissionAssetError::DatabaseError(error)
+    }
+}
+
+impl From<RepositoryError> for InsertAssetDisposalError {
+    fn from(error: RepositoryError) -> Self {
+        InsertAssetDisposalError::DatabaseError(error)
+    }
+}
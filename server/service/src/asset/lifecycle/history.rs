@@ -0,0 +1,147 @@
+use chrono::NaiveDateTime;
+use repository::{
+    assets::{
+        asset_disposal_row::{AssetDisposalMethod, AssetDisposalRowRepository},
+        asset_row::AssetRowRepository,
+        asset_transfer::{AssetTransferFilter, AssetTransferRepository},
+        asset_transfer_row::AssetTransferStatus,
+    },
+    EqualFilter, RepositoryError, StorageConnection,
+};
+
+#[derive(Debug, PartialEq)]
+pub enum GetAssetHistoryError {
+    AssetDoesNotExist,
+    DatabaseError(RepositoryError),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AssetHistoryEventType {
+    Created,
+    TransferDispatched,
+    TransferReceived,
+    Decommissioned,
+    Disposed,
+}
+
+/// Location and ownership change of an asset
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssetHistoryEvent {
+    pub r#type: AssetHistoryEventType,
+    pub datetime: NaiveDateTime,
+    /// Store owning the asset after the event
+    pub store_id: Option<String>,
+    /// Store owning the asset before a transfer
+    pub from_store_id: Option<String>,
+    pub user_id: Option<String>,
+    pub comment: Option<String>,
+    /// Transfer or disposal of the event
+    pub record_id: Option<String>,
+    pub disposal_method: Option<AssetDisposalMethod>,
+}
+
+/// Events ordered by datetime, the store the asset was created in is the source store of the
+/// first transfer. Cancelled and new transfers are not included as the asset didn't move
+pub fn get_asset_history(
+    connection: &StorageConnection,
+    asset_id: &str,
+) -> Result<Vec<AssetHistoryEvent>, GetAssetHistoryError> {
+    let asset = AssetRowRepository::new(connection)
+        .find_one_by_id(asset_id)?
+        .ok_or(GetAssetHistoryError::AssetDoesNotExist)?;
+
+    let transfers = AssetTransferRepository::new(connection).query_by_filter(
+        AssetTransferFilter::new()
+            .asset_id(EqualFilter::equal_to(asset_id))
+            .status(EqualFilter {
+                equal_any: Some(vec![
+                    AssetTransferStatus::Dispatched,
+                    AssetTransferStatus::Received,
+                ]),
+                ..Default::default()
+            }),
+    )?;
+
+    let event = |r#type, datetime| AssetHistoryEvent {
+        r#type,
+        datetime,
+        store_id: None,
+        from_store_id: None,
+        user_id: None,
+        comment: None,
+        record_id: None,
+        disposal_method: None,
+    };
+
+    let mut events = vec![AssetHistoryEvent {
+        store_id: transfers
+            .iter()
+            .min_by_key(|transfer| transfer.dispatched_datetime)
+            .map(|transfer| transfer.from_store_id.clone())
+            .or(asset.store_id.clone()),
+        ..event(AssetHistoryEventType::Created, asset.created_datetime)
+    }];
+
+    for transfer in transfers {
+        if let Some(dispatched_datetime) = transfer.dispatched_datetime {
+            events.push(AssetHistoryEvent {
+                store_id: Some(transfer.to_store_id.clone()),
+                from_store_id: Some(transfer.from_store_id.clone()),
+                user_id: transfer.dispatched_by_user_id.clone(),
+                comment: transfer.comment.clone(),
+                record_id: Some(transfer.id.clone()),
+                ..event(
+                    AssetHistoryEventType::TransferDispatched,
+                    dispatched_datetime,
+                )
+            });
+        }
+        if let Some(received_datetime) = transfer.received_datetime {
+            events.push(AssetHistoryEvent {
+                store_id: Some(transfer.to_store_id),
+                from_store_id: Some(transfer.from_store_id),
+                user_id: transfer.received_by_user_id,
+                record_id: Some(transfer.id),
+                ..event(AssetHistoryEventType::TransferReceived, received_datetime)
+            });
+        }
+    }
+
+    if let Some(decommissioned_date) = asset.decommissioned_date {
+        events.push(AssetHistoryEvent {
+            store_id: asset.store_id.clone(),
+            comment: asset.decommission_reason.clone(),
+            ..event(
+                AssetHistoryEventType::Decommissioned,
+                decommissioned_date.and_hms_opt(0, 0, 0).unwrap(),
+            )
+        });
+    }
+
+    if let Some(disposal) =
+        AssetDisposalRowRepository::new(connection).find_one_by_asset_id(asset_id)?
+    {
+        events.push(AssetHistoryEvent {
+            store_id: Some(disposal.store_id),
+            user_id: Some(disposal.user_id),
+            comment: disposal.comment,
+            record_id: Some(disposal.id),
+            disposal_method: Some(disposal.method),
+            ..event(
+                AssetHistoryEventType::Disposed,
+                disposal.disposal_date.and_hms_opt(0, 0, 0).unwrap(),
+            )
+        });
+    }
+
+    // Stable sort, keeps dispatch before receipt and decommissioning before disposal on the
+    // same datetime
+    events.sort_by_key(|event| event.datetime);
+    Ok(events)
+}
+
+impl From<RepositoryError> for GetAssetHistoryError {
+    fn from(error: RepositoryError) -> Self {
+        GetAssetHistoryError::DatabaseError(error)
+    }
+}
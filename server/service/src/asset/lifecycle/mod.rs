@@ -0,0 +1,30 @@
+pub mod disposal;
+pub mod history;
+pub mod transfer;
+
+use repository::{
+    assets::{
+        asset_transfer::{AssetTransferFilter, AssetTransferRepository},
+        asset_transfer_row::AssetTransferStatus,
+    },
+    EqualFilter, RepositoryError, StorageConnection,
+};
+
+/// Asset has a transfer that is not yet received or cancelled
+fn check_transfer_in_progress(
+    connection: &StorageConnection,
+    asset_id: &str,
+) -> Result<bool, RepositoryError> {
+    let count = AssetTransferRepository::new(connection).count(Some(
+        AssetTransferFilter::new()
+            .asset_id(EqualFilter::equal_to(asset_id))
+            .status(EqualFilter {
+                equal_any: Some(vec![
+                    AssetTransferStatus::New,
+                    AssetTransferStatus::Dispatched,
+                ]),
+                ..Default::default()
+            }),
+    ))?;
+    Ok(count > 0)
+}
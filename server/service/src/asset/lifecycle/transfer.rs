@@ -0,0 +1,234 @@
+use chrono::Utc;
+use repository::{
+    assets::{
+        asset_row::{AssetRow, AssetRowRepository},
+        asset_transfer::{
+            AssetTransfer, AssetTransferFilter, AssetTransferRepository, AssetTransferSort,
+        },
+        asset_transfer_row::{AssetTransferRow, AssetTransferRowRepository, AssetTransferStatus},
+    },
+    PaginationOption, RepositoryError, StorageConnection, StoreRowRepository,
+};
+
+use super::check_transfer_in_progress;
+use crate::{
+    asset::maintenance::work_order::cancel_open_work_orders, get_default_pagination, i64_to_u32,
+    service_provider::ServiceContext, ListError, ListResult,
+};
+
+pub const MAX_LIMIT: u32 = 1000;
+pub const MIN_LIMIT: u32 = 1;
+
+#[derive(Debug, PartialEq)]
+pub enum InsertAssetTransferError {
+    AssetTransferAlreadyExists,
+    AssetDoesNotExist,
+    AssetDoesNotBelongToCurrentStore,
+    AssetIsDecommissioned,
+    AssetHasTransferInProgress,
+    ToStoreDoesNotExist,
+    CannotTransferToCurrentStore,
+    DatabaseError(RepositoryError),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum UpdateAssetTransferError {
+    AssetTransferDoesNotExist,
+    /// Transfer can only be dispatched or cancelled by the source store and received by the
+    /// destination store
+    AssetTransferDoesNotBelongToCurrentStore,
+    CannotChangeStatus {
+        from: AssetTransferStatus,
+        to: UpdateAssetTransferStatus,
+    },
+    AssetDoesNotExist,
+    AssetIsNotInSourceStore,
+    DatabaseError(RepositoryError),
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct InsertAssetTransfer {
+    pub id: String,
+    pub asset_id: String,
+    pub to_store_id: String,
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum UpdateAssetTransferStatus {
+    Dispatched,
+    Received,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpdateAssetTransfer {
+    pub id: String,
+    pub status: UpdateAssetTransferStatus,
+}
+
+pub fn get_asset_transfers(
+    connection: &StorageConnection,
+    pagination: Option<PaginationOption>,
+    filter: Option<AssetTransferFilter>,
+    sort: Option<AssetTransferSort>,
+) -> Result<ListResult<AssetTransfer>, ListError> {
+    let pagination = get_default_pagination(pagination, MAX_LIMIT, MIN_LIMIT)?;
+    let repository = AssetTransferRepository::new(connection);
+
+    Ok(ListResult {
+        rows: repository.query(pagination, filter.clone(), sort)?,
+        count: i64_to_u32(repository.count(filter)?),
+    })
+}
+
+/// New transfer of an asset of the current store, asset is moved when the transfer is dispatched
+pub fn insert_asset_transfer(
+    ctx: &ServiceContext,
+    input: InsertAssetTransfer,
+) -> Result<AssetTransferRow, InsertAssetTransferError> {
+    let result = ctx
+        .connection
+        .transaction_sync(|connection| -> Result<_, InsertAssetTransferError> {
+            use InsertAssetTransferError::*;
+
+            let repo = AssetTransferRowRepository::new(connection);
+            if repo.find_one_by_id(&input.id)?.is_some() {
+                return Err(AssetTransferAlreadyExists);
+            }
+            let asset = AssetRowRepository::new(connection)
+                .find_one_by_id(&input.asset_id)?
+                .filter(|asset| asset.deleted_datetime.is_none())
+                .ok_or(AssetDoesNotExist)?;
+            if asset.store_id.as_ref() != Some(&ctx.store_id) {
+                return Err(AssetDoesNotBelongToCurrentStore);
+            }
+            if asset.decommissioned_date.is_some() {
+                return Err(AssetIsDecommissioned);
+            }
+            if check_transfer_in_progress(connection, &asset.id)? {
+                return Err(AssetHasTransferInProgress);
+            }
+            if input.to_store_id == ctx.store_id {
+                return Err(CannotTransferToCurrentStore);
+            }
+            if StoreRowRepository::new(connection)
+                .find_one_by_id(&input.to_store_id)?
+                .is_none()
+            {
+                return Err(ToStoreDoesNotExist);
+            }
+
+            let transfer = AssetTransferRow {
+                id: input.id,
+                asset_id: input.asset_id,
+                from_store_id: ctx.store_id.clone(),
+                to_store_id: input.to_store_id,
+                status: AssetTransferStatus::New,
+                comment: input.comment,
+                created_datetime: Utc::now().naive_utc(),
+                created_by_user_id: ctx.user_id.clone(),
+                ..Default::default()
+            };
+            repo.upsert_one(&transfer)?;
+            Ok(transfer)
+        })
+        .map_err(|error| error.to_inner_error())?;
+    Ok(result)
+}
+
+/// Dispatching moves the asset to the destination store, so that it's synced to the destination
+/// site where the transfer is received. Open work orders of the source store are cancelled
+pub fn update_asset_transfer(
+    ctx: &ServiceContext,
+    input: UpdateAssetTransfer,
+) -> Result<AssetTransferRow, UpdateAssetTransferError> {
+    let result =
+        ctx.connection
+            .transaction_sync(|connection| -> Result<_, UpdateAssetTransferError> {
+                use UpdateAssetTransferError::*;
+
+                let repo = AssetTransferRowRepository::new(connection);
+                let transfer = repo
+                    .find_one_by_id(&input.id)?
+                    .ok_or(AssetTransferDoesNotExist)?;
+
+                let store_id = match input.status {
+                    UpdateAssetTransferStatus::Dispatched
+                    | UpdateAssetTransferStatus::Cancelled => &transfer.from_store_id,
+                    UpdateAssetTransferStatus::Received => &transfer.to_store_id,
+                };
+                if store_id != &ctx.store_id {
+                    return Err(AssetTransferDoesNotBelongToCurrentStore);
+                }
+
+                let required_status = match input.status {
+                    UpdateAssetTransferStatus::Dispatched
+                    | UpdateAssetTransferStatus::Cancelled => AssetTransferStatus::New,
+                    UpdateAssetTransferStatus::Received => AssetTransferStatus::Dispatched,
+                };
+                if transfer.status != required_status {
+                    return Err(CannotChangeStatus {
+                        from: transfer.status,
+                        to: input.status,
+                    });
+                }
+
+                let asset_repo = AssetRowRepository::new(connection);
+                let now = Utc::now().naive_utc();
+                let transfer = match input.status {
+                    UpdateAssetTransferStatus::Dispatched => {
+                        let asset = asset_repo
+                            .find_one_by_id(&transfer.asset_id)?
+                            .ok_or(AssetDoesNotExist)?;
+                        if asset.store_id.as_ref() != Some(&transfer.from_store_id) {
+                            return Err(AssetIsNotInSourceStore);
+                        }
+                        asset_repo.upsert_one(&AssetRow {
+                            store_id: Some(transfer.to_store_id.clone()),
+                            modified_datetime: now,
+                            ..asset
+                        })?;
+                        cancel_open_work_orders(connection, &transfer.asset_id)?;
+
+                        AssetTransferRow {
+                            status: AssetTransferStatus::Dispatched,
+                            dispatched_datetime: Some(now),
+                            dispatched_by_user_id: Some(ctx.user_id.clone()),
+                            ..transfer
+                        }
+                    }
+                    UpdateAssetTransferStatus::Received => {
+                        if asset_repo.find_one_by_id(&transfer.asset_id)?.is_none() {
+                            return Err(AssetDoesNotExist);
+                        }
+                        AssetTransferRow {
+                            status: AssetTransferStatus::Received,
+                            received_datetime: Some(now),
+                            received_by_user_id: Some(ctx.user_id.clone()),
+                            ..transfer
+                        }
+                    }
+                    UpdateAssetTransferStatus::Cancelled => AssetTransferRow {
+                        status: AssetTransferStatus::Cancelled,
+                        ..transfer
+                    },
+                };
+                repo.upsert_one(&transfer)?;
+                Ok(transfer)
+            })
+            .map_err(|error| error.to_inner_error())?;
+    Ok(result)
+}
+
+impl From<RepositoryError> for InsertAssetTransferError {
+    fn from(error: RepositoryError) -> Self {
+        InsertAssetTransferError::DatabaseError(error)
+    }
+}
+
+impl From<RepositoryError> for UpdateAssetTransferError {
+    fn from(error: RepositoryError) -> Self {
+        UpdateAssetTransferError::DatabaseError(error)
+    }
+}
@@ -0,0 +1,167 @@
+use std::sync::Arc;
+
+use chrono::{Duration, NaiveDate, Utc};
+use repository::{
+    assets::{
+        asset::{Asset, AssetFilter, AssetRepository},
+        asset_maintenance_schedule_row::{
+            AssetMaintenanceScheduleRow, AssetMaintenanceScheduleRowRepository,
+        },
+        asset_run_hours_row::AssetRunHoursRowRepository,
+        asset_work_order_row::{
+            AssetWorkOrderRow, AssetWorkOrderRowRepository, AssetWorkOrderStatus,
+        },
+    },
+    EqualFilter, RepositoryError, StorageConnection,
+};
+use util::uuid::uuid;
+
+use super::WORK_ORDER_LEAD_DAYS;
+use crate::{service_provider::ServiceProvider, sync::ActiveStoresOnSite};
+
+/// How often due work orders are generated
+const GENERATION_INTERVAL_SECONDS: u64 = 60 * 60;
+
+/// Generates due work orders for the stores active on this site every hour
+pub async fn run_work_order_generation(service_provider: Arc<ServiceProvider>) {
+    loop {
+        let result = service_provider
+            .basic_context()
+            .map_err(|error| format!("{:?}", error))
+            .and_then(|ctx| {
+                let active_stores = match ActiveStoresOnSite::get(&ctx.connection) {
+                    Ok(active_stores) => active_stores,
+                    // Site is not initialised yet
+                    Err(_) => return Ok(Vec::new()),
+                };
+                let today = Utc::now().naive_utc().date();
+                ctx.connection
+                    .transaction_sync(|connection| {
+                        generate_due_work_orders(connection, &active_stores.store_ids(), today)
+                    })
+                    .map_err(|error| format!("{:?}", error.to_inner_error()))
+            });
+
+        match result {
+            Ok(work_orders) if !work_orders.is_empty() => {
+                log::info!("Generated {} maintenance work orders", work_orders.len())
+            }
+            Ok(_) => {}
+            Err(error) => log::error!("Error generating maintenance work orders: {}", error),
+        }
+
+        tokio::time::sleep(std::time::Duration::from_secs(GENERATION_INTERVAL_SECONDS)).await;
+    }
+}
+
+/// Adds an open work order for each asset of the stores with maintenance due within
+/// `WORK_ORDER_LEAD_DAYS` of `today`. Decommissioned assets and assets with an open work order of
+/// the schedule are skipped.
+/// Should be called in a transaction
+pub fn generate_due_work_orders(
+    connection: &StorageConnection,
+    store_ids: &[String],
+    today: NaiveDate,
+) -> Result<Vec<AssetWorkOrderRow>, RepositoryError> {
+    let work_order_repo = AssetWorkOrderRowRepository::new(connection);
+    let mut result = Vec::new();
+
+    for schedule in AssetMaintenanceScheduleRowRepository::new(connection).find_many_active()? {
+        let filter = match (&schedule.asset_type_id, &schedule.catalogue_item_id) {
+            (_, Some(catalogue_item_id)) => {
+                AssetFilter::new().catalogue_item_id(EqualFilter::equal_to(catalogue_item_id))
+            }
+            (Some(asset_type_id), None) => {
+                AssetFilter::new().type_id(EqualFilter::equal_to(asset_type_id))
+            }
+            (None, None) => continue,
+        };
+
+        for asset in AssetRepository::new(connection).query_by_filter(filter)? {
+            if asset.decommissioned_date.is_some() {
+                continue;
+            }
+            let Some(store_id) = asset
+                .store_id
+                .clone()
+                .filter(|store_id| store_ids.contains(store_id))
+            else {
+                continue;
+            };
+
+            let Some(due_date) = next_due_date(connection, &schedule, &asset, today)? else {
+                continue;
+            };
+            if due_date > today + Duration::days(WORK_ORDER_LEAD_DAYS) {
+                continue;
+            }
+
+            let work_order = AssetWorkOrderRow {
+                id: uuid(),
+                store_id,
+                asset_id: asset.id,
+                schedule_id: schedule.id.clone(),
+                status: AssetWorkOrderStatus::Open,
+                due_date,
+                created_datetime: Utc::now().naive_utc(),
+                ..Default::default()
+            };
+            work_order_repo.upsert_one(&work_order)?;
+            result.push(work_order);
+        }
+    }
+
+    Ok(result)
+}
+
+/// Earliest due date of the day and run hour intervals of the schedule, counted from the last
+/// completed work order (or from installation). None when the asset has an open work order of the
+/// schedule or no interval applies yet
+fn next_due_date(
+    connection: &StorageConnection,
+    schedule: &AssetMaintenanceScheduleRow,
+    asset: &Asset,
+    today: NaiveDate,
+) -> Result<Option<NaiveDate>, RepositoryError> {
+    let work_orders = AssetWorkOrderRowRepository::new(connection)
+        .find_many_by_asset_and_schedule(&asset.id, &schedule.id)?;
+    if work_orders
+        .iter()
+        .any(|work_order| work_order.status == AssetWorkOrderStatus::Open)
+    {
+        return Ok(None);
+    }
+    let last_completed = work_orders
+        .iter()
+        .filter(|work_order| work_order.status == AssetWorkOrderStatus::Completed)
+        .max_by_key(|work_order| work_order.completed_datetime);
+
+    let by_days = schedule.interval_days.map(|interval_days| {
+        let from = last_completed
+            .and_then(|work_order| work_order.completed_datetime)
+            .map(|datetime| datetime.date())
+            .or(asset.installation_date)
+            .unwrap_or(asset.created_datetime.date());
+        from + Duration::days(interval_days as i64)
+    });
+
+    let by_run_hours = match schedule.interval_run_hours {
+        Some(interval_run_hours) => {
+            let current = AssetRunHoursRowRepository::new(connection)
+                .find_latest_by_asset_id(&asset.id)?
+                .map(|reading| reading.run_hours);
+            let from = last_completed
+                .and_then(|work_order| work_order.run_hours)
+                .unwrap_or(0.0);
+            current
+                .filter(|current| current - from >= interval_run_hours)
+                .map(|_| today)
+        }
+        None => None,
+    };
+
+    Ok(match (by_days, by_run_hours) {
+        (Some(by_days), Some(by_run_hours)) => Some(by_days.min(by_run_hours)),
+        (by_days, by_run_hours) => by_days.or(by_run_hours),
+    })
+}
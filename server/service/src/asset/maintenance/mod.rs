@@ -0,0 +1,6 @@
+pub mod generate;
+pub mod schedule;
+pub mod work_order;
+
+/// Work orders are generated when maintenance is due within this number of days
+pub const WORK_ORDER_LEAD_DAYS: i64 = 7;
@@ -0,0 +1,127 @@
+use chrono::Utc;
+use repository::{
+    assets::{
+        asset_catalogue_item_row::AssetCatalogueItemRowRepository,
+        asset_maintenance_schedule_row::{
+            AssetMaintenanceScheduleRow, AssetMaintenanceScheduleRowRepository,
+        },
+        asset_type_row::AssetTypeRowRepository,
+    },
+    RepositoryError, StorageConnection,
+};
+
+use crate::service_provider::ServiceContext;
+
+#[derive(Debug, PartialEq)]
+pub enum UpsertMaintenanceScheduleError {
+    NameIsEmpty,
+    /// Schedule applies to either an asset type or a catalogue item
+    AssetTypeOrCatalogueItemRequired,
+    AssetTypeDoesNotExist,
+    CatalogueItemDoesNotExist,
+    IntervalRequired,
+    IntervalMustBePositive,
+    DatabaseError(RepositoryError),
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct UpsertMaintenanceSchedule {
+    pub id: String,
+    pub name: String,
+    pub asset_type_id: Option<String>,
+    pub catalogue_item_id: Option<String>,
+    pub interval_days: Option<i32>,
+    pub interval_run_hours: Option<f64>,
+    pub is_active: bool,
+}
+
+pub fn upsert_maintenance_schedule(
+    ctx: &ServiceContext,
+    input: UpsertMaintenanceSchedule,
+) -> Result<AssetMaintenanceScheduleRow, UpsertMaintenanceScheduleError> {
+    let result = ctx
+        .connection
+        .transaction_sync(|connection| -> Result<_, UpsertMaintenanceScheduleError> {
+            validate(connection, &input)?;
+
+            let repo = AssetMaintenanceScheduleRowRepository::new(connection);
+            let created_datetime = repo
+                .find_one_by_id(&input.id)?
+                .map(|existing| existing.created_datetime)
+                .unwrap_or_else(|| Utc::now().naive_utc());
+            let UpsertMaintenanceSchedule {
+                id,
+                name,
+                asset_type_id,
+                catalogue_item_id,
+                interval_days,
+                interval_run_hours,
+                is_active,
+            } = input;
+            let row = AssetMaintenanceScheduleRow {
+                id,
+                name: name.trim().to_string(),
+                asset_type_id,
+                catalogue_item_id,
+                interval_days,
+                interval_run_hours,
+                is_active,
+                created_datetime,
+            };
+            repo.upsert_one(&row)?;
+            Ok(row)
+        })
+        .map_err(|error| error.to_inner_error())?;
+    Ok(result)
+}
+
+pub fn get_maintenance_schedules(
+    connection: &StorageConnection,
+) -> Result<Vec<AssetMaintenanceScheduleRow>, RepositoryError> {
+    AssetMaintenanceScheduleRowRepository::new(connection).find_all()
+}
+
+fn validate(
+    connection: &StorageConnection,
+    input: &UpsertMaintenanceSchedule,
+) -> Result<(), UpsertMaintenanceScheduleError> {
+    use UpsertMaintenanceScheduleError::*;
+
+    if input.name.trim().is_empty() {
+        return Err(NameIsEmpty);
+    }
+    match (&input.asset_type_id, &input.catalogue_item_id) {
+        (Some(asset_type_id), None) => {
+            if AssetTypeRowRepository::new(connection)
+                .find_one_by_id(asset_type_id)?
+                .is_none()
+            {
+                return Err(AssetTypeDoesNotExist);
+            }
+        }
+        (None, Some(catalogue_item_id)) => {
+            if AssetCatalogueItemRowRepository::new(connection)
+                .find_one_by_id(catalogue_item_id)?
+                .is_none()
+            {
+                return Err(CatalogueItemDoesNotExist);
+            }
+        }
+        _ => return Err(AssetTypeOrCatalogueItemRequired),
+    }
+    if input.interval_days.is_none() && input.interval_run_hours.is_none() {
+        return Err(IntervalRequired);
+    }
+    if input.interval_days.map_or(false, |days| days <= 0)
+        || input.interval_run_hours.map_or(false, |hours| hours <= 0.0)
+    {
+        return Err(IntervalMustBePositive);
+    }
+    Ok(())
+}
+
+impl From<RepositoryError> for UpsertMaintenanceScheduleError {
+    fn from(error: RepositoryError) -> Self {
+        UpsertMaintenanceScheduleError::DatabaseError(error)
+    }
+}
@@ -0,0 +1,277 @@
+use chrono::{Duration, NaiveDate, Utc};
+use repository::{
+    asset_log_row::{AssetLogRow, AssetLogRowRepository, AssetLogStatus},
+    assets::{
+        asset_maintenance_schedule_row::AssetMaintenanceScheduleRowRepository,
+        asset_row::AssetRowRepository,
+        asset_run_hours_row::{AssetRunHoursRow, AssetRunHoursRowRepository},
+        asset_work_order::{
+            AssetWorkOrder, AssetWorkOrderFilter, AssetWorkOrderRepository, AssetWorkOrderSort,
+        },
+        asset_work_order_row::{
+            AssetWorkOrderRow, AssetWorkOrderRowRepository, AssetWorkOrderStatus,
+        },
+    },
+    ActivityLogType, DateFilter, EqualFilter, PaginationOption, RepositoryError, StorageConnection,
+    UserAccountRowRepository,
+};
+use util::uuid::uuid;
+
+use crate::{
+    activity_log::activity_log_entry, get_default_pagination, i64_to_u32,
+    service_provider::ServiceContext, ListError, ListResult,
+};
+
+pub const MAX_LIMIT: u32 = 1000;
+pub const MIN_LIMIT: u32 = 1;
+
+#[derive(Debug, PartialEq)]
+pub enum RecordAssetRunHoursError {
+    AssetDoesNotExist,
+    RunHoursNegative,
+    DatabaseError(RepositoryError),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum AssignWorkOrderError {
+    WorkOrderDoesNotExist,
+    WorkOrderDoesNotBelongToCurrentStore,
+    WorkOrderNotOpen,
+    UserDoesNotExist,
+    DatabaseError(RepositoryError),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum CompleteWorkOrderError {
+    WorkOrderDoesNotExist,
+    WorkOrderDoesNotBelongToCurrentStore,
+    WorkOrderNotOpen,
+    RunHoursNegative,
+    DatabaseError(RepositoryError),
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CompleteWorkOrder {
+    pub id: String,
+    pub parts_used: Option<String>,
+    pub notes: Option<String>,
+    /// Run hour meter reading of the asset, latest recorded reading is used when not set
+    pub run_hours: Option<f64>,
+    /// Status of the asset log, functioning when not set
+    pub status: Option<AssetLogStatus>,
+}
+
+pub fn get_work_orders(
+    connection: &StorageConnection,
+    pagination: Option<PaginationOption>,
+    filter: Option<AssetWorkOrderFilter>,
+    sort: Option<AssetWorkOrderSort>,
+) -> Result<ListResult<AssetWorkOrder>, ListError> {
+    let pagination = get_default_pagination(pagination, MAX_LIMIT, MIN_LIMIT)?;
+    let repository = AssetWorkOrderRepository::new(connection);
+
+    Ok(ListResult {
+        rows: repository.query(pagination, filter.clone(), sort)?,
+        count: i64_to_u32(repository.count(filter)?),
+    })
+}
+
+/// Open work orders of the store due before `today`
+pub fn count_overdue_work_orders(
+    ctx: &ServiceContext,
+    store_id: &str,
+    today: NaiveDate,
+) -> Result<i64, RepositoryError> {
+    AssetWorkOrderRepository::new(&ctx.connection).count(Some(
+        AssetWorkOrderFilter::new()
+            .store_id(EqualFilter::equal_to(store_id))
+            .status(AssetWorkOrderStatus::Open.equal_to())
+            .due_date(DateFilter::before_or_equal_to(today - Duration::days(1))),
+    ))
+}
+
+pub fn record_asset_run_hours(
+    ctx: &ServiceContext,
+    asset_id: String,
+    run_hours: f64,
+) -> Result<AssetRunHoursRow, RecordAssetRunHoursError> {
+    if run_hours < 0.0 {
+        return Err(RecordAssetRunHoursError::RunHoursNegative);
+    }
+    if AssetRowRepository::new(&ctx.connection)
+        .find_one_by_id(&asset_id)?
+        .is_none()
+    {
+        return Err(RecordAssetRunHoursError::AssetDoesNotExist);
+    }
+
+    let row = AssetRunHoursRow {
+        id: uuid(),
+        asset_id,
+        run_hours,
+        recorded_datetime: Utc::now().naive_utc(),
+    };
+    AssetRunHoursRowRepository::new(&ctx.connection).insert_one(&row)?;
+    Ok(row)
+}
+
+/// Assigns the technician responsible for the work order, `None` removes the assignment
+pub fn assign_work_order(
+    ctx: &ServiceContext,
+    id: String,
+    user_id: Option<String>,
+) -> Result<AssetWorkOrderRow, AssignWorkOrderError> {
+    use AssignWorkOrderError::*;
+
+    let repo = AssetWorkOrderRowRepository::new(&ctx.connection);
+    let work_order = repo.find_one_by_id(&id)?.ok_or(WorkOrderDoesNotExist)?;
+    if work_order.store_id != ctx.store_id {
+        return Err(WorkOrderDoesNotBelongToCurrentStore);
+    }
+    if work_order.status != AssetWorkOrderStatus::Open {
+        return Err(WorkOrderNotOpen);
+    }
+    if let Some(user_id) = &user_id {
+        if UserAccountRowRepository::new(&ctx.connection)
+            .find_one_by_id(user_id)?
+            .is_none()
+        {
+            return Err(UserDoesNotExist);
+        }
+    }
+
+    let work_order = AssetWorkOrderRow {
+        assigned_user_id: user_id,
+        ..work_order
+    };
+    repo.upsert_one(&work_order)?;
+    Ok(work_order)
+}
+
+/// Records the maintenance and adds an asset log with the notes and parts used
+pub fn complete_work_order(
+    ctx: &ServiceContext,
+    input: CompleteWorkOrder,
+) -> Result<AssetWorkOrderRow, CompleteWorkOrderError> {
+    let result = ctx
+        .connection
+        .transaction_sync(|connection| -> Result<_, CompleteWorkOrderError> {
+            use CompleteWorkOrderError::*;
+
+            let repo = AssetWorkOrderRowRepository::new(connection);
+            let work_order = repo
+                .find_one_by_id(&input.id)?
+                .ok_or(WorkOrderDoesNotExist)?;
+            if work_order.store_id != ctx.store_id {
+                return Err(WorkOrderDoesNotBelongToCurrentStore);
+            }
+            if work_order.status != AssetWorkOrderStatus::Open {
+                return Err(WorkOrderNotOpen);
+            }
+
+            let run_hours_repo = AssetRunHoursRowRepository::new(connection);
+            let now = Utc::now().naive_utc();
+            let run_hours = match input.run_hours {
+                Some(run_hours) if run_hours < 0.0 => return Err(RunHoursNegative),
+                Some(run_hours) => {
+                    run_hours_repo.insert_one(&AssetRunHoursRow {
+                        id: uuid(),
+                        asset_id: work_order.asset_id.clone(),
+                        run_hours,
+                        recorded_datetime: now,
+                    })?;
+                    Some(run_hours)
+                }
+                None => run_hours_repo
+                    .find_latest_by_asset_id(&work_order.asset_id)?
+                    .map(|reading| reading.run_hours),
+            };
+
+            let schedule_name = AssetMaintenanceScheduleRowRepository::new(connection)
+                .find_one_by_id(&work_order.schedule_id)?
+                .map(|schedule| schedule.name);
+            let comment = vec![
+                input.notes.clone(),
+                input
+                    .parts_used
+                    .as_ref()
+                    .map(|parts_used| format!("Parts used: {}", parts_used)),
+            ]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<String>>()
+            .join("\n");
+
+            let asset_log = AssetLogRow {
+                id: uuid(),
+                asset_id: work_order.asset_id.clone(),
+                user_id: ctx.user_id.clone(),
+                status: Some(input.status.unwrap_or(AssetLogStatus::Functioning)),
+                comment: (!comment.is_empty()).then_some(comment),
+                r#type: schedule_name,
+                reason: None,
+                log_datetime: now,
+            };
+            AssetLogRowRepository::new(connection).upsert_one(&asset_log)?;
+            activity_log_entry(
+                ctx,
+                ActivityLogType::AssetLogCreated,
+                Some(asset_log.id.clone()),
+                None,
+                None,
+            )?;
+
+            let work_order = AssetWorkOrderRow {
+                status: AssetWorkOrderStatus::Completed,
+                completed_datetime: Some(now),
+                completed_by_user_id: Some(ctx.user_id.clone()),
+                run_hours,
+                parts_used: input.parts_used,
+                notes: input.notes,
+                asset_log_id: Some(asset_log.id),
+                ..work_order
+            };
+            repo.upsert_one(&work_order)?;
+            Ok(work_order)
+        })
+        .map_err(|error| error.to_inner_error())?;
+    Ok(result)
+}
+
+/// Cancels open work orders of the asset, when it's decommissioned or moved to another store
+/// (work orders are generated again by the store the asset is moved to).
+/// Should be called in a transaction
+pub fn cancel_open_work_orders(
+    connection: &StorageConnection,
+    asset_id: &str,
+) -> Result<Vec<AssetWorkOrderRow>, RepositoryError> {
+    let repo = AssetWorkOrderRowRepository::new(connection);
+    let mut result = Vec::new();
+    for work_order in repo.find_open_by_asset_id(asset_id)? {
+        let work_order = AssetWorkOrderRow {
+            status: AssetWorkOrderStatus::Cancelled,
+            ..work_order
+        };
+        repo.upsert_one(&work_order)?;
+        result.push(work_order);
+    }
+    Ok(result)
+}
+
+impl From<RepositoryError> for RecordAssetRunHoursError {
+    fn from(error: RepositoryError) -> Self {
+        RecordAssetRunHoursError::DatabaseError(error)
+    }
+}
+
+impl From<RepositoryError> for AssignWorkOrderError {
+    fn from(error: RepositoryError) -> Self {
+        AssignWorkOrderError::DatabaseError(error)
+    }
+}
+
+impl From<RepositoryError> for CompleteWorkOrderError {
+    fn from(error: RepositoryError) -> Self {
+        CompleteWorkOrderError::DatabaseError(error)
+    }
+}
@@ -1,20 +1,65 @@
+use self::cce_inventory::{
+    get_cce_inventory, CceFacilityInventory, CceInventoryError, CceInventoryInput,
+};
+use self::cce_properties::{
+    update_catalogue_item_properties, upsert_store_population, UpdateCatalogueItemProperties,
+    UpdateCatalogueItemPropertiesError, UpsertStorePopulation, UpsertStorePopulationError,
+};
 use self::delete::{delete_asset, DeleteAssetError};
 use self::insert::{insert_asset, InsertAsset, InsertAssetError};
 use self::insert_log::{insert_asset_log, InsertAssetLog, InsertAssetLogError};
+use self::lifecycle::{
+    disposal::{
+        decommission_asset, insert_asset_disposal, DecommissionAsset, DecommissionAssetError,
+        InsertAssetDisposal, InsertAssetDisposalError,
+    },
+    history::{get_asset_history, AssetHistoryEvent, GetAssetHistoryError},
+    transfer::{
+        get_asset_transfers, insert_asset_transfer, update_asset_transfer, InsertAssetTransfer,
+        InsertAssetTransferError, UpdateAssetTransfer, UpdateAssetTransferError,
+    },
+};
+use self::maintenance::{
+    schedule::{
+        get_maintenance_schedules, upsert_maintenance_schedule, UpsertMaintenanceSchedule,
+        UpsertMaintenanceScheduleError,
+    },
+    work_order::{
+        assign_work_order, complete_work_order, count_overdue_work_orders, get_work_orders,
+        record_asset_run_hours, AssignWorkOrderError, CompleteWorkOrder, CompleteWorkOrderError,
+        RecordAssetRunHoursError,
+    },
+};
 use self::query::{get_asset, get_assets};
 use self::query_log::{get_asset_log, get_asset_logs};
 use self::update::{update_asset, UpdateAsset, UpdateAssetError};
 
 use super::{ListError, ListResult};
 use crate::{service_provider::ServiceContext, SingleRecordError};
+use chrono::NaiveDate;
 use repository::assets::asset::{Asset, AssetFilter, AssetSort};
+use repository::assets::asset_catalogue_item_row::AssetCatalogueItemRow;
+use repository::assets::asset_disposal_row::AssetDisposalRow;
 use repository::assets::asset_log::{AssetLog, AssetLogFilter, AssetLogSort};
-use repository::{PaginationOption, StorageConnection};
+use repository::assets::asset_maintenance_schedule_row::AssetMaintenanceScheduleRow;
+use repository::assets::asset_row::AssetRow;
+use repository::assets::asset_run_hours_row::AssetRunHoursRow;
+use repository::assets::asset_transfer::{AssetTransfer, AssetTransferFilter, AssetTransferSort};
+use repository::assets::asset_transfer_row::AssetTransferRow;
+use repository::assets::asset_work_order::{
+    AssetWorkOrder, AssetWorkOrderFilter, AssetWorkOrderSort,
+};
+use repository::assets::asset_work_order_row::AssetWorkOrderRow;
+use repository::{PaginationOption, RepositoryError, StorageConnection, StorePopulationRow};
 
+pub mod cce_inventory;
+pub mod cce_properties;
 pub mod delete;
 pub mod insert;
 pub mod insert_log;
+pub mod lifecycle;
 pub mod location;
+pub mod maintenance;
 pub mod query;
 pub mod query_log;
 pub mod update;
@@ -80,6 +125,140 @@ pub trait AssetServiceTrait: Sync + Send {
     ) -> Result<AssetLog, InsertAssetLogError> {
         insert_asset_log(ctx, input)
     }
+
+    fn get_cce_inventory(
+        &self,
+        connection: &StorageConnection,
+        input: CceInventoryInput,
+    ) -> Result<Vec<CceFacilityInventory>, CceInventoryError> {
+        get_cce_inventory(connection, input)
+    }
+
+    fn upsert_store_population(
+        &self,
+        ctx: &ServiceContext,
+        input: UpsertStorePopulation,
+    ) -> Result<StorePopulationRow, UpsertStorePopulationError> {
+        upsert_store_population(ctx, input)
+    }
+
+    fn update_catalogue_item_properties(
+        &self,
+        ctx: &ServiceContext,
+        input: UpdateCatalogueItemProperties,
+    ) -> Result<AssetCatalogueItemRow, UpdateCatalogueItemPropertiesError> {
+        update_catalogue_item_properties(ctx, input)
+    }
+
+    fn get_maintenance_schedules(
+        &self,
+        connection: &StorageConnection,
+    ) -> Result<Vec<AssetMaintenanceScheduleRow>, RepositoryError> {
+        get_maintenance_schedules(connection)
+    }
+
+    fn upsert_maintenance_schedule(
+        &self,
+        ctx: &ServiceContext,
+        input: UpsertMaintenanceSchedule,
+    ) -> Result<AssetMaintenanceScheduleRow, UpsertMaintenanceScheduleError> {
+        upsert_maintenance_schedule(ctx, input)
+    }
+
+    fn get_work_orders(
+        &self,
+        connection: &StorageConnection,
+        pagination: Option<PaginationOption>,
+        filter: Option<AssetWorkOrderFilter>,
+        sort: Option<AssetWorkOrderSort>,
+    ) -> Result<ListResult<AssetWorkOrder>, ListError> {
+        get_work_orders(connection, pagination, filter, sort)
+    }
+
+    fn count_overdue_work_orders(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+        today: NaiveDate,
+    ) -> Result<i64, RepositoryError> {
+        count_overdue_work_orders(ctx, store_id, today)
+    }
+
+    fn record_asset_run_hours(
+        &self,
+        ctx: &ServiceContext,
+        asset_id: String,
+        run_hours: f64,
+    ) -> Result<AssetRunHoursRow, RecordAssetRunHoursError> {
+        record_asset_run_hours(ctx, asset_id, run_hours)
+    }
+
+    fn assign_work_order(
+        &self,
+        ctx: &ServiceContext,
+        id: String,
+        user_id: Option<String>,
+    ) -> Result<AssetWorkOrderRow, AssignWorkOrderError> {
+        assign_work_order(ctx, id, user_id)
+    }
+
+    fn complete_work_order(
+        &self,
+        ctx: &ServiceContext,
+        input: CompleteWorkOrder,
+    ) -> Result<AssetWorkOrderRow, CompleteWorkOrderError> {
+        complete_work_order(ctx, input)
+    }
+
+    fn get_asset_transfers(
+        &self,
+        connection: &StorageConnection,
+        pagination: Option<PaginationOption>,
+        filter: Option<AssetTransferFilter>,
+        sort: Option<AssetTransferSort>,
+    ) -> Result<ListResult<AssetTransfer>, ListError> {
+        get_asset_transfers(connection, pagination, filter, sort)
+    }
+
+    fn insert_asset_transfer(
+        &self,
+        ctx: &ServiceContext,
+        input: InsertAssetTransfer,
+    ) -> Result<AssetTransferRow, InsertAssetTransferError> {
+        insert_asset_transfer(ctx, input)
+    }
+
+    fn update_asset_transfer(
+        &self,
+        ctx: &ServiceContext,
+        input: UpdateAssetTransfer,
+    ) -> Result<AssetTransferRow, UpdateAssetTransferError> {
+        update_asset_transfer(ctx, input)
+    }
+
+    fn decommission_asset(
+        &self,
+        ctx: &ServiceContext,
+        input: DecommissionAsset,
+    ) -> Result<AssetRow, DecommissionAssetError> {
+        decommission_asset(ctx, input)
+    }
+
+    fn insert_asset_disposal(
+        &self,
+        ctx: &ServiceContext,
+        input: InsertAssetDisposal,
+    ) -> Result<AssetDisposalRow, InsertAssetDisposalError> {
+        insert_asset_disposal(ctx, input)
+    }
+
+    fn get_asset_history(
+        &self,
+        connection: &StorageConnection,
+        asset_id: &str,
+    ) -> Result<Vec<AssetHistoryEvent>, GetAssetHistoryError> {
+        get_asset_history(connection, asset_id)
+    }
 }
 
 pub struct AssetService {}
@@ -0,0 +1,244 @@
+#[cfg(test)]
+mod query {
+    use chrono::NaiveDate;
+    use repository::{
+        asset_log_row::{AssetLogRow, AssetLogRowRepository, AssetLogStatus},
+        assets::{
+            asset_catalogue_item_row::AssetEnergySource,
+            asset_row::{AssetRow, AssetRowRepository},
+        },
+        mock::{mock_store_a, mock_store_b, mock_user_account_a, MockDataInserts},
+        test_db::setup_all,
+    };
+
+    use crate::{
+        asset::{
+            cce_inventory::{cce_inventory, CceInventoryError, CceInventoryInput},
+            cce_properties::{
+                UpdateCatalogueItemProperties, UpdateCatalogueItemPropertiesError,
+                UpsertStorePopulation, UpsertStorePopulationError,
+            },
+        },
+        service_provider::ServiceProvider,
+    };
+
+    const FREEZER_CATALOGUE_ITEM_ID: &str = "c7d48b5c-74b2-4077-94f5-2b25d67a447b";
+
+    #[actix_rt::test]
+    async fn cce_inventory_service() {
+        let (_, connection, connection_manager, _) = setup_all(
+            "cce_inventory_service",
+            MockDataInserts::none().names().stores().user_accounts(),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let ctx = service_provider
+            .context(mock_store_a().id, mock_user_account_a().id)
+            .unwrap();
+        let service = service_provider.asset_service;
+
+        // Properties validation
+        assert_eq!(
+            service.upsert_store_population(
+                &ctx,
+                UpsertStorePopulation {
+                    store_id: mock_store_a().id,
+                    population_served: -1,
+                },
+            ),
+            Err(UpsertStorePopulationError::PopulationServedNegative)
+        );
+        assert_eq!(
+            service.upsert_store_population(
+                &ctx,
+                UpsertStorePopulation {
+                    store_id: "unknown".to_string(),
+                    population_served: 1,
+                },
+            ),
+            Err(UpsertStorePopulationError::StoreDoesNotExist)
+        );
+        assert_eq!(
+            service.update_catalogue_item_properties(
+                &ctx,
+                UpdateCatalogueItemProperties {
+                    id: "unknown".to_string(),
+                    ..Default::default()
+                },
+            ),
+            Err(UpdateCatalogueItemPropertiesError::CatalogueItemDoesNotExist)
+        );
+        assert_eq!(
+            service.update_catalogue_item_properties(
+                &ctx,
+                UpdateCatalogueItemProperties {
+                    id: FREEZER_CATALOGUE_ITEM_ID.to_string(),
+                    storage_capacity: Some(-1.0),
+                    ..Default::default()
+                },
+            ),
+            Err(UpdateCatalogueItemPropertiesError::StorageCapacityNegative)
+        );
+
+        let catalogue_item = service
+            .update_catalogue_item_properties(
+                &ctx,
+                UpdateCatalogueItemProperties {
+                    id: FREEZER_CATALOGUE_ITEM_ID.to_string(),
+                    energy_source: Some(AssetEnergySource::Electricity),
+                    storage_capacity: Some(100.0),
+                },
+            )
+            .unwrap();
+        for (store_id, population_served) in [(mock_store_a().id, 2000), (mock_store_b().id, 1000)]
+        {
+            service
+                .upsert_store_population(
+                    &ctx,
+                    UpsertStorePopulation {
+                        store_id,
+                        population_served,
+                    },
+                )
+                .unwrap();
+        }
+
+        // Functioning, not functioning and non catalogue equipment in store a
+        let asset = |id: &str, catalogue_item_id: Option<&str>, installation_date| AssetRow {
+            id: id.to_string(),
+            store_id: Some(mock_store_a().id),
+            catalogue_item_id: catalogue_item_id.map(str::to_string),
+            installation_date,
+            ..Default::default()
+        };
+        let fridge_1 = asset(
+            "fridge_1",
+            Some(FREEZER_CATALOGUE_ITEM_ID),
+            NaiveDate::from_ymd_opt(2020, 6, 1),
+        );
+        let fridge_2 = asset(
+            "fridge_2",
+            Some(FREEZER_CATALOGUE_ITEM_ID),
+            NaiveDate::from_ymd_opt(2023, 1, 1),
+        );
+        let fridge_3 = asset("fridge_3", None, None);
+        for asset in [&fridge_1, &fridge_2, &fridge_3] {
+            AssetRowRepository::new(&connection)
+                .upsert_one(asset)
+                .unwrap();
+        }
+
+        let log = |id: &str, asset_id: &str, status, day| AssetLogRow {
+            id: id.to_string(),
+            asset_id: asset_id.to_string(),
+            user_id: mock_user_account_a().id,
+            status: Some(status),
+            comment: None,
+            r#type: None,
+            reason: None,
+            log_datetime: NaiveDate::from_ymd_opt(2024, 1, day)
+                .unwrap()
+                .and_hms_opt(10, 0, 0)
+                .unwrap(),
+        };
+        for log in [
+            log("log_1", "fridge_1", AssetLogStatus::NotFunctioning, 1),
+            log("log_2", "fridge_1", AssetLogStatus::Functioning, 2),
+            log("log_3", "fridge_2", AssetLogStatus::NotFunctioning, 1),
+        ] {
+            AssetLogRowRepository::new(&connection)
+                .upsert_one(&log)
+                .unwrap();
+        }
+
+        let today = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap();
+        assert_eq!(
+            cce_inventory(
+                &connection,
+                CceInventoryInput {
+                    litres_per_person: -0.1,
+                    ..Default::default()
+                },
+                today,
+            ),
+            Err(CceInventoryError::LitresPerPersonNegative)
+        );
+
+        let result = cce_inventory(
+            &connection,
+            CceInventoryInput {
+                litres_per_person: 0.1,
+                ..Default::default()
+            },
+            today,
+        )
+        .unwrap();
+        assert_eq!(result.len(), 2);
+
+        let store_a = &result[0];
+        assert_eq!(store_a.store_id, mock_store_a().id);
+        assert_eq!(store_a.population_served, Some(2000));
+        assert_eq!(
+            store_a
+                .lines
+                .iter()
+                .map(|line| (
+                    line.asset.id.as_str(),
+                    line.status.clone(),
+                    line.age_years,
+                    line.energy_source(),
+                    line.storage_capacity()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "fridge_1",
+                    Some(AssetLogStatus::Functioning),
+                    Some(3),
+                    Some(AssetEnergySource::Electricity),
+                    Some(100.0)
+                ),
+                (
+                    "fridge_2",
+                    Some(AssetLogStatus::NotFunctioning),
+                    Some(1),
+                    Some(AssetEnergySource::Electricity),
+                    Some(100.0)
+                ),
+                ("fridge_3", None, None, None, None),
+            ]
+        );
+        assert_eq!(store_a.lines[0].catalogue_item, Some(catalogue_item));
+        assert_eq!(store_a.total_storage_capacity, 200.0);
+        assert_eq!(store_a.net_storage_capacity, 100.0);
+        assert_eq!(store_a.required_storage_capacity, Some(200.0));
+        assert_eq!(store_a.storage_capacity_difference(), Some(-100.0));
+
+        // Facility without equipment has a shortage of the required capacity
+        let store_b = &result[1];
+        assert_eq!(store_b.store_id, mock_store_b().id);
+        assert!(store_b.lines.is_empty());
+        assert_eq!(store_b.net_storage_capacity, 0.0);
+        assert_eq!(store_b.storage_capacity_difference(), Some(-100.0));
+
+        // Store filter
+        let result = cce_inventory(
+            &connection,
+            CceInventoryInput {
+                store_ids: Some(vec![mock_store_b().id]),
+                litres_per_person: 0.1,
+                ..Default::default()
+            },
+            today,
+        )
+        .unwrap();
+        assert_eq!(
+            result
+                .iter()
+                .map(|facility| facility.store_id.clone())
+                .collect::<Vec<_>>(),
+            vec![mock_store_b().id]
+        );
+    }
+}
@@ -0,0 +1,313 @@
+#[cfg(test)]
+mod query {
+    use chrono::{Duration, NaiveDate, Utc};
+    use repository::{
+        asset_log_row::AssetLogStatus,
+        assets::{
+            asset_disposal_row::AssetDisposalMethod,
+            asset_log::{AssetLogFilter, AssetLogRepository},
+            asset_maintenance_schedule_row::{
+                AssetMaintenanceScheduleRow, AssetMaintenanceScheduleRowRepository,
+            },
+            asset_row::{AssetRow, AssetRowRepository},
+            asset_transfer_row::AssetTransferStatus,
+            asset_work_order_row::{
+                AssetWorkOrderRow, AssetWorkOrderRowRepository, AssetWorkOrderStatus,
+            },
+        },
+        mock::{mock_store_a, mock_store_b, mock_user_account_a, MockDataInserts},
+        test_db::setup_all,
+        EqualFilter,
+    };
+
+    use crate::{
+        asset::lifecycle::{
+            disposal::{
+                DecommissionAsset, DecommissionAssetError, InsertAssetDisposal,
+                InsertAssetDisposalError,
+            },
+            history::AssetHistoryEventType,
+            transfer::{
+                InsertAssetTransfer, InsertAssetTransferError, UpdateAssetTransfer,
+                UpdateAssetTransferError, UpdateAssetTransferStatus,
+            },
+        },
+        service_provider::ServiceProvider,
+    };
+
+    #[actix_rt::test]
+    async fn asset_lifecycle_service() {
+        let (_, connection, connection_manager, _) = setup_all(
+            "asset_lifecycle_service",
+            MockDataInserts::none().names().stores().user_accounts(),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let ctx_a = service_provider
+            .context(mock_store_a().id, mock_user_account_a().id)
+            .unwrap();
+        let ctx_b = service_provider
+            .context(mock_store_b().id, mock_user_account_a().id)
+            .unwrap();
+        let service = service_provider.asset_service;
+
+        let asset_repo = AssetRowRepository::new(&connection);
+        asset_repo
+            .upsert_one(&AssetRow {
+                id: "fridge".to_string(),
+                store_id: Some(mock_store_a().id),
+                created_datetime: NaiveDate::from_ymd_opt(2024, 1, 1)
+                    .unwrap()
+                    .and_hms_opt(0, 0, 0)
+                    .unwrap(),
+                ..Default::default()
+            })
+            .unwrap();
+
+        // Transfer validation
+        let transfer = InsertAssetTransfer {
+            id: "transfer".to_string(),
+            asset_id: "fridge".to_string(),
+            to_store_id: mock_store_b().id,
+            comment: Some("Moved to district".to_string()),
+        };
+        assert_eq!(
+            service.insert_asset_transfer(
+                &ctx_a,
+                InsertAssetTransfer {
+                    to_store_id: mock_store_a().id,
+                    ..transfer.clone()
+                }
+            ),
+            Err(InsertAssetTransferError::CannotTransferToCurrentStore)
+        );
+        assert_eq!(
+            service.insert_asset_transfer(
+                &ctx_a,
+                InsertAssetTransfer {
+                    to_store_id: "unknown".to_string(),
+                    ..transfer.clone()
+                }
+            ),
+            Err(InsertAssetTransferError::ToStoreDoesNotExist)
+        );
+        assert_eq!(
+            service.insert_asset_transfer(&ctx_b, transfer.clone()),
+            Err(InsertAssetTransferError::AssetDoesNotBelongToCurrentStore)
+        );
+        let new_transfer = service
+            .insert_asset_transfer(&ctx_a, transfer.clone())
+            .unwrap();
+        assert_eq!(new_transfer.status, AssetTransferStatus::New);
+        assert_eq!(new_transfer.from_store_id, mock_store_a().id);
+        assert_eq!(
+            service.insert_asset_transfer(
+                &ctx_a,
+                InsertAssetTransfer {
+                    id: "transfer_2".to_string(),
+                    ..transfer.clone()
+                }
+            ),
+            Err(InsertAssetTransferError::AssetHasTransferInProgress)
+        );
+
+        // Dispatch by source store, receipt by destination store
+        let update = |status| UpdateAssetTransfer {
+            id: "transfer".to_string(),
+            status,
+        };
+        assert_eq!(
+            service.update_asset_transfer(&ctx_b, update(UpdateAssetTransferStatus::Dispatched)),
+            Err(UpdateAssetTransferError::AssetTransferDoesNotBelongToCurrentStore)
+        );
+        assert_eq!(
+            service.update_asset_transfer(&ctx_b, update(UpdateAssetTransferStatus::Received)),
+            Err(UpdateAssetTransferError::CannotChangeStatus {
+                from: AssetTransferStatus::New,
+                to: UpdateAssetTransferStatus::Received
+            })
+        );
+        AssetMaintenanceScheduleRowRepository::new(&connection)
+            .upsert_one(&AssetMaintenanceScheduleRow {
+                id: "defrost".to_string(),
+                name: "Defrost".to_string(),
+                interval_days: Some(30),
+                is_active: true,
+                ..Default::default()
+            })
+            .unwrap();
+        let work_order_repo = AssetWorkOrderRowRepository::new(&connection);
+        work_order_repo
+            .upsert_one(&AssetWorkOrderRow {
+                id: "work_order".to_string(),
+                store_id: mock_store_a().id,
+                asset_id: "fridge".to_string(),
+                schedule_id: "defrost".to_string(),
+                status: AssetWorkOrderStatus::Open,
+                ..Default::default()
+            })
+            .unwrap();
+        let dispatched = service
+            .update_asset_transfer(&ctx_a, update(UpdateAssetTransferStatus::Dispatched))
+            .unwrap();
+        assert_eq!(dispatched.status, AssetTransferStatus::Dispatched);
+        assert_eq!(
+            dispatched.dispatched_by_user_id,
+            Some(mock_user_account_a().id)
+        );
+        assert_eq!(
+            asset_repo
+                .find_one_by_id("fridge")
+                .unwrap()
+                .unwrap()
+                .store_id,
+            Some(mock_store_b().id)
+        );
+        // Work orders of the source store are cancelled
+        assert_eq!(
+            work_order_repo
+                .find_one_by_id("work_order")
+                .unwrap()
+                .unwrap()
+                .status,
+            AssetWorkOrderStatus::Cancelled
+        );
+        assert_eq!(
+            service.update_asset_transfer(&ctx_a, update(UpdateAssetTransferStatus::Cancelled)),
+            Err(UpdateAssetTransferError::CannotChangeStatus {
+                from: AssetTransferStatus::Dispatched,
+                to: UpdateAssetTransferStatus::Cancelled
+            })
+        );
+        let received = service
+            .update_asset_transfer(&ctx_b, update(UpdateAssetTransferStatus::Received))
+            .unwrap();
+        assert_eq!(received.status, AssetTransferStatus::Received);
+
+        // Decommissioning
+        let decommissioned_date = Utc::now().naive_utc().date() + Duration::days(1);
+        let decommission = DecommissionAsset {
+            asset_id: "fridge".to_string(),
+            decommissioned_date,
+            reason: "Beyond repair".to_string(),
+        };
+        assert_eq!(
+            service.decommission_asset(&ctx_a, decommission.clone()),
+            Err(DecommissionAssetError::AssetDoesNotBelongToCurrentStore)
+        );
+        assert_eq!(
+            service.decommission_asset(
+                &ctx_b,
+                DecommissionAsset {
+                    reason: " ".to_string(),
+                    ..decommission.clone()
+                }
+            ),
+            Err(DecommissionAssetError::ReasonIsEmpty)
+        );
+        let asset = service
+            .decommission_asset(&ctx_b, decommission.clone())
+            .unwrap();
+        assert_eq!(asset.decommissioned_date, Some(decommissioned_date));
+        assert_eq!(
+            service.decommission_asset(&ctx_b, decommission.clone()),
+            Err(DecommissionAssetError::AssetAlreadyDecommissioned)
+        );
+        let asset_logs = AssetLogRepository::new(&connection)
+            .query_by_filter(AssetLogFilter::new().asset_id(EqualFilter::equal_to("fridge")))
+            .unwrap();
+        assert_eq!(asset_logs.len(), 1);
+        assert_eq!(asset_logs[0].status, Some(AssetLogStatus::Decommissioned));
+        assert_eq!(asset_logs[0].comment, Some("Beyond repair".to_string()));
+
+        assert_eq!(
+            service.insert_asset_transfer(
+                &ctx_b,
+                InsertAssetTransfer {
+                    id: "transfer_2".to_string(),
+                    to_store_id: mock_store_a().id,
+                    ..transfer.clone()
+                }
+            ),
+            Err(InsertAssetTransferError::AssetIsDecommissioned)
+        );
+
+        // Disposal
+        let disposal = InsertAssetDisposal {
+            id: "disposal".to_string(),
+            asset_id: "fridge".to_string(),
+            disposal_date: decommissioned_date + Duration::days(1),
+            method: AssetDisposalMethod::Recycled,
+            comment: None,
+        };
+        assert_eq!(
+            service.insert_asset_disposal(
+                &ctx_b,
+                InsertAssetDisposal {
+                    disposal_date: decommissioned_date - Duration::days(1),
+                    ..disposal.clone()
+                }
+            ),
+            Err(InsertAssetDisposalError::DisposalDateBeforeDecommissioning)
+        );
+        service
+            .insert_asset_disposal(&ctx_b, disposal.clone())
+            .unwrap();
+        assert_eq!(
+            service.insert_asset_disposal(
+                &ctx_b,
+                InsertAssetDisposal {
+                    id: "disposal_2".to_string(),
+                    ..disposal.clone()
+                }
+            ),
+            Err(InsertAssetDisposalError::AssetAlreadyDisposed)
+        );
+
+        // History
+        let history = service.get_asset_history(&connection, "fridge").unwrap();
+        assert_eq!(
+            history
+                .iter()
+                .map(|event| (
+                    event.r#type.clone(),
+                    event.store_id.clone(),
+                    event.from_store_id.clone()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    AssetHistoryEventType::Created,
+                    Some(mock_store_a().id),
+                    None
+                ),
+                (
+                    AssetHistoryEventType::TransferDispatched,
+                    Some(mock_store_b().id),
+                    Some(mock_store_a().id)
+                ),
+                (
+                    AssetHistoryEventType::TransferReceived,
+                    Some(mock_store_b().id),
+                    Some(mock_store_a().id)
+                ),
+                (
+                    AssetHistoryEventType::Decommissioned,
+                    Some(mock_store_b().id),
+                    None
+                ),
+                (
+                    AssetHistoryEventType::Disposed,
+                    Some(mock_store_b().id),
+                    None
+                ),
+            ]
+        );
+        assert_eq!(history[1].comment, Some("Moved to district".to_string()));
+        assert_eq!(
+            history[4].disposal_method,
+            Some(AssetDisposalMethod::Recycled)
+        );
+    }
+}
@@ -0,0 +1,240 @@
+#[cfg(test)]
+mod query {
+    use chrono::{Duration, Utc};
+    use repository::{
+        asset_log_row::{AssetLogRowRepository, AssetLogStatus},
+        assets::{
+            asset_row::{AssetRow, AssetRowRepository},
+            asset_work_order_row::{AssetWorkOrderRowRepository, AssetWorkOrderStatus},
+        },
+        mock::{mock_store_a, mock_store_b, mock_user_account_a, MockDataInserts},
+        test_db::setup_all,
+    };
+
+    use crate::{
+        asset::{
+            lifecycle::disposal::DecommissionAsset,
+            maintenance::{
+                generate::generate_due_work_orders,
+                schedule::{UpsertMaintenanceSchedule, UpsertMaintenanceScheduleError},
+                work_order::{AssignWorkOrderError, CompleteWorkOrder, CompleteWorkOrderError},
+            },
+        },
+        service_provider::ServiceProvider,
+    };
+
+    const FREEZER_TYPE_ID: &str = "710194ce-8c6c-47ab-b7fe-13ba8cf091f6";
+    const REFRIGERATOR_CATALOGUE_ITEM_ID: &str = "c7d48b5c-74b2-4077-94f5-2b25d67a447b";
+
+    #[actix_rt::test]
+    async fn asset_maintenance_service() {
+        let (_, connection, connection_manager, _) = setup_all(
+            "asset_maintenance_service",
+            MockDataInserts::none().names().stores().user_accounts(),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let ctx = service_provider
+            .context(mock_store_a().id, mock_user_account_a().id)
+            .unwrap();
+        let service = service_provider.asset_service;
+
+        // Schedule validation
+        let schedule = UpsertMaintenanceSchedule {
+            id: "defrost".to_string(),
+            name: "Defrost".to_string(),
+            asset_type_id: Some(FREEZER_TYPE_ID.to_string()),
+            interval_days: Some(30),
+            is_active: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            service.upsert_maintenance_schedule(
+                &ctx,
+                UpsertMaintenanceSchedule {
+                    catalogue_item_id: Some(REFRIGERATOR_CATALOGUE_ITEM_ID.to_string()),
+                    ..schedule.clone()
+                }
+            ),
+            Err(UpsertMaintenanceScheduleError::AssetTypeOrCatalogueItemRequired)
+        );
+        assert_eq!(
+            service.upsert_maintenance_schedule(
+                &ctx,
+                UpsertMaintenanceSchedule {
+                    interval_days: None,
+                    ..schedule.clone()
+                }
+            ),
+            Err(UpsertMaintenanceScheduleError::IntervalRequired)
+        );
+        assert_eq!(
+            service.upsert_maintenance_schedule(
+                &ctx,
+                UpsertMaintenanceSchedule {
+                    interval_days: Some(0),
+                    ..schedule.clone()
+                }
+            ),
+            Err(UpsertMaintenanceScheduleError::IntervalMustBePositive)
+        );
+        service
+            .upsert_maintenance_schedule(&ctx, schedule.clone())
+            .unwrap();
+        service
+            .upsert_maintenance_schedule(
+                &ctx,
+                UpsertMaintenanceSchedule {
+                    id: "compressor".to_string(),
+                    name: "Compressor service".to_string(),
+                    catalogue_item_id: Some(REFRIGERATOR_CATALOGUE_ITEM_ID.to_string()),
+                    interval_run_hours: Some(1000.0),
+                    is_active: true,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        // Freezer of the catalogue item in store a, another freezer in store b (not generated)
+        let today = Utc::now().naive_utc().date();
+        let asset = |id: &str, store_id: String| AssetRow {
+            id: id.to_string(),
+            store_id: Some(store_id),
+            asset_type_id: Some(FREEZER_TYPE_ID.to_string()),
+            catalogue_item_id: Some(REFRIGERATOR_CATALOGUE_ITEM_ID.to_string()),
+            installation_date: Some(today - Duration::days(40)),
+            ..Default::default()
+        };
+        for asset in [
+            asset("freezer_a", mock_store_a().id),
+            asset("freezer_b", mock_store_b().id),
+        ] {
+            AssetRowRepository::new(&connection)
+                .upsert_one(&asset)
+                .unwrap();
+        }
+
+        // Defrost due 10 days ago, compressor has no run hours yet
+        let generated = generate_due_work_orders(&connection, &[mock_store_a().id], today).unwrap();
+        assert_eq!(generated.len(), 1);
+        let defrost = generated[0].clone();
+        assert_eq!(defrost.asset_id, "freezer_a");
+        assert_eq!(defrost.schedule_id, "defrost");
+        assert_eq!(defrost.due_date, today - Duration::days(10));
+        assert_eq!(
+            service.count_overdue_work_orders(&ctx, &mock_store_a().id, today),
+            Ok(1)
+        );
+
+        // Open work order is not generated again, compressor is due by run hours
+        service
+            .record_asset_run_hours(&ctx, "freezer_a".to_string(), 1200.0)
+            .unwrap();
+        let generated = generate_due_work_orders(&connection, &[mock_store_a().id], today).unwrap();
+        assert_eq!(
+            generated
+                .iter()
+                .map(|work_order| (work_order.schedule_id.as_str(), work_order.due_date))
+                .collect::<Vec<_>>(),
+            vec![("compressor", today)]
+        );
+
+        // Assign and complete
+        assert_eq!(
+            service.assign_work_order(&ctx, defrost.id.clone(), Some("unknown".to_string())),
+            Err(AssignWorkOrderError::UserDoesNotExist)
+        );
+        let assigned = service
+            .assign_work_order(&ctx, defrost.id.clone(), Some(mock_user_account_a().id))
+            .unwrap();
+        assert_eq!(assigned.assigned_user_id, Some(mock_user_account_a().id));
+
+        let completed = service
+            .complete_work_order(
+                &ctx,
+                CompleteWorkOrder {
+                    id: defrost.id.clone(),
+                    parts_used: Some("Door seal".to_string()),
+                    notes: Some("Defrosted".to_string()),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(completed.status, AssetWorkOrderStatus::Completed);
+        assert_eq!(completed.run_hours, Some(1200.0));
+        assert_eq!(
+            AssetWorkOrderRowRepository::new(&connection).find_one_by_id(&defrost.id),
+            Ok(Some(completed.clone()))
+        );
+
+        let asset_log = AssetLogRowRepository::new(&connection)
+            .find_one_by_id(completed.asset_log_id.as_ref().unwrap())
+            .unwrap()
+            .unwrap();
+        assert_eq!(asset_log.asset_id, "freezer_a");
+        assert_eq!(asset_log.status, Some(AssetLogStatus::Functioning));
+        assert_eq!(asset_log.r#type, Some("Defrost".to_string()));
+        assert_eq!(
+            asset_log.comment,
+            Some("Defrosted\nParts used: Door seal".to_string())
+        );
+
+        assert_eq!(
+            service.complete_work_order(
+                &ctx,
+                CompleteWorkOrder {
+                    id: defrost.id.clone(),
+                    ..Default::default()
+                },
+            ),
+            Err(CompleteWorkOrderError::WorkOrderNotOpen)
+        );
+        assert_eq!(
+            service.count_overdue_work_orders(&ctx, &mock_store_a().id, today),
+            Ok(0)
+        );
+
+        // Next defrost is counted from completion
+        let generated = generate_due_work_orders(&connection, &[mock_store_a().id], today).unwrap();
+        assert!(generated.is_empty());
+        let generated = generate_due_work_orders(
+            &connection,
+            &[mock_store_a().id],
+            today + Duration::days(25),
+        )
+        .unwrap();
+        assert_eq!(generated.len(), 1);
+        assert_eq!(
+            generated[0].due_date,
+            completed.completed_datetime.unwrap().date() + Duration::days(30)
+        );
+
+        // Decommissioning cancels open work orders, no more are generated
+        service
+            .decommission_asset(
+                &ctx,
+                DecommissionAsset {
+                    asset_id: "freezer_a".to_string(),
+                    decommissioned_date: today,
+                    reason: "Beyond repair".to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            AssetWorkOrderRowRepository::new(&connection)
+                .find_one_by_id(&generated[0].id)
+                .unwrap()
+                .unwrap()
+                .status,
+            AssetWorkOrderStatus::Cancelled
+        );
+        let generated = generate_due_work_orders(
+            &connection,
+            &[mock_store_a().id],
+            today + Duration::days(25),
+        )
+        .unwrap();
+        assert!(generated.is_empty());
+    }
+}
@@ -1,6 +1,12 @@
 #[cfg(test)]
+mod cce_inventory;
+#[cfg(test)]
 mod insert;
 #[cfg(test)]
+mod lifecycle;
+#[cfg(test)]
+mod maintenance;
+#[cfg(test)]
 mod query;
 #[cfg(test)]
 mod update;
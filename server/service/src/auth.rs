@@ -111,6 +111,7 @@ pub enum Resource {
     MutateContactTrace,
     SyncInfo,
     ManualSync,
+    SyncConflict,
     QueryInventoryAdjustmentReasons,
     QueryStorePreferences,
     ColdChainApi,
@@ -502,6 +503,8 @@ fn all_permissions() -> HashMap<Resource, PermissionDSL> {
     // sync info and manual sync, not permission needed
     map.insert(Resource::SyncInfo, PermissionDSL::NoPermissionRequired);
     map.insert(Resource::ManualSync, PermissionDSL::NoPermissionRequired);
+    // view and resolve conflicting edits of patient and shared-remote records
+    map.insert(Resource::SyncConflict, PermissionDSL::HasStoreAccess);
 
     map.insert(
         Resource::QueryInventoryAdjustmentReasons,
@@ -1,5 +1,5 @@
 use super::query_temperature_log::get_temperature_log;
-use super::validate::check_temperature_log_does_not_exist;
+use super::validate::{check_sensor_log_datetime_is_unique, check_temperature_log_does_not_exist};
 use crate::{service_provider::ServiceContext, SingleRecordError};
 use chrono::NaiveDateTime;
 use repository::{
@@ -10,6 +10,7 @@ use repository::{
 #[derive(PartialEq, Debug)]
 pub enum InsertTemperatureLogError {
     TemperatureLogAlreadyExists,
+    SensorAlreadyHasLogAtDatetime,
     CreatedRecordNotFound,
     DatabaseError(RepositoryError),
 }
@@ -38,6 +39,10 @@ pub fn insert_temperature_log(
                 .map_err(InsertTemperatureLogError::from)
         })
         .map_err(|error| error.to_inner_error())?;
+
+    ctx.processors_trigger
+        .trigger_temperature_breach_processor();
+
     Ok(temperature_log)
 }
 
@@ -48,6 +53,14 @@ pub fn validate(
     if !check_temperature_log_does_not_exist(&input.id, connection)? {
         return Err(InsertTemperatureLogError::TemperatureLogAlreadyExists);
     }
+    if !check_sensor_log_datetime_is_unique(
+        &input.id,
+        &input.sensor_id,
+        input.datetime,
+        connection,
+    )? {
+        return Err(InsertTemperatureLogError::SensorAlreadyHasLogAtDatetime);
+    }
 
     Ok(())
 }
@@ -10,6 +10,18 @@ use self::insert_temperature_breach::{
     insert_temperature_breach, InsertTemperatureBreach, InsertTemperatureBreachError,
 };
 use self::query_temperature_breach::{get_temperature_breach, temperature_breaches};
+use self::temperature_breach_config::{
+    get_temperature_breach_configs, upsert_temperature_breach_config,
+    UpsertTemperatureBreachConfig, UpsertTemperatureBreachConfigError,
+};
+use self::temperature_breach_stock_impact::{
+    assess_temperature_breach_stock_line, get_temperature_breach_stock_lines,
+    AssessTemperatureBreachStockLine, AssessTemperatureBreachStockLineError,
+    GetTemperatureBreachStockLinesError, TemperatureBreachStockLine,
+};
+use self::temperature_kpi::{
+    get_temperature_kpis, TemperatureKpi, TemperatureKpiError, TemperatureKpiInput,
+};
 use self::update_temperature_breach::{
     update_temperature_breach, update_temperature_breach_acknowledgement, UpdateTemperatureBreach,
     UpdateTemperatureBreachAcknowledgement, UpdateTemperatureBreachError,
@@ -20,12 +32,17 @@ use repository::temperature_breach::{
     TemperatureBreach, TemperatureBreachFilter, TemperatureBreachSort,
 };
 use repository::temperature_log::{TemperatureLog, TemperatureLogFilter, TemperatureLogSort};
-use repository::{PaginationOption, StorageConnection};
+use repository::{PaginationOption, RepositoryError, StorageConnection, TemperatureBreachConfig};
 
 pub mod insert_temperature_breach;
 pub mod insert_temperature_log;
+pub mod notification;
 pub mod query_temperature_breach;
 pub mod query_temperature_log;
+pub mod temperature_breach_config;
+pub mod temperature_breach_detection;
+pub mod temperature_breach_stock_impact;
+pub mod temperature_kpi;
 pub mod update_temperature_breach;
 pub mod update_temperature_log;
 mod validate;
@@ -105,6 +122,45 @@ pub trait ColdChainServiceTrait: Sync + Send {
     ) -> Result<TemperatureBreach, UpdateTemperatureBreachError> {
         update_temperature_breach_acknowledgement(ctx, input)
     }
+
+    fn get_temperature_breach_stock_lines(
+        &self,
+        ctx: &ServiceContext,
+        temperature_breach_id: &str,
+    ) -> Result<Vec<TemperatureBreachStockLine>, GetTemperatureBreachStockLinesError> {
+        get_temperature_breach_stock_lines(ctx, temperature_breach_id)
+    }
+
+    fn assess_temperature_breach_stock_line(
+        &self,
+        ctx: &ServiceContext,
+        input: AssessTemperatureBreachStockLine,
+    ) -> Result<TemperatureBreachStockLine, AssessTemperatureBreachStockLineError> {
+        assess_temperature_breach_stock_line(ctx, input)
+    }
+
+    fn get_temperature_breach_configs(
+        &self,
+        ctx: &ServiceContext,
+    ) -> Result<Vec<TemperatureBreachConfig>, RepositoryError> {
+        get_temperature_breach_configs(ctx)
+    }
+
+    fn upsert_temperature_breach_config(
+        &self,
+        ctx: &ServiceContext,
+        input: UpsertTemperatureBreachConfig,
+    ) -> Result<TemperatureBreachConfig, UpsertTemperatureBreachConfigError> {
+        upsert_temperature_breach_config(ctx, input)
+    }
+
+    fn get_temperature_kpis(
+        &self,
+        ctx: &ServiceContext,
+        input: TemperatureKpiInput,
+    ) -> Result<Vec<TemperatureKpi>, TemperatureKpiError> {
+        get_temperature_kpis(ctx, input)
+    }
 }
 
 pub struct ColdChainService {}
@@ -0,0 +1,235 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use lettre::{
+    message::Mailbox, transport::smtp::authentication::Credentials, AsyncSmtpTransport,
+    AsyncTransport, Message, Tokio1Executor,
+};
+use repository::NotificationEventType;
+use serde::Serialize;
+
+use super::{
+    settings::{
+        EmailChannelSettings, NotificationChannelKind, NotificationChannelSettings,
+        SmsChannelSettings, WebhookChannelSettings,
+    },
+    Notification,
+};
+
+const DEFAULT_SMTP_PORT: u16 = 587;
+/// A hung mail server, webhook or SMS gateway would otherwise block all notifications
+const SEND_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Delivers rendered notifications, channels are created from notification settings
+#[async_trait]
+pub trait NotificationChannel: Send + Sync {
+    fn name(&self) -> &str;
+
+    fn is_subscribed(&self, event_type: &NotificationEventType, store_id: &str) -> bool;
+
+    /// Recipients that are sent to (and logged) separately, so that a failure for one recipient
+    /// doesn't resend to the others. `None` when all recipients are sent to at once
+    fn recipients(&self) -> Vec<Option<String>> {
+        vec![None]
+    }
+
+    /// Error is recorded in notification log
+    async fn send(
+        &self,
+        notification: &Notification,
+        recipient: Option<&str>,
+    ) -> Result<(), String>;
+}
+
+fn http_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(SEND_TIMEOUT)
+        .build()
+        // Same as reqwest::Client::new(), which only fails when TLS backend cannot be initialised
+        .expect("Cannot create notification http client")
+}
+
+pub fn channels_from_settings(
+    settings: &[NotificationChannelSettings],
+) -> Vec<Box<dyn NotificationChannel>> {
+    settings
+        .iter()
+        .map(|channel| -> Box<dyn NotificationChannel> {
+            match &channel.kind {
+                NotificationChannelKind::Email(email) => Box::new(EmailChannel {
+                    channel: channel.clone(),
+                    settings: email.clone(),
+                }),
+                NotificationChannelKind::Webhook(webhook) => Box::new(WebhookChannel {
+                    channel: channel.clone(),
+                    settings: webhook.clone(),
+                    client: http_client(),
+                }),
+                NotificationChannelKind::Sms(sms) => Box::new(SmsChannel {
+                    channel: channel.clone(),
+                    settings: sms.clone(),
+                    client: http_client(),
+                }),
+            }
+        })
+        .collect()
+}
+
+pub struct EmailChannel {
+    channel: NotificationChannelSettings,
+    settings: EmailChannelSettings,
+}
+
+#[async_trait]
+impl NotificationChannel for EmailChannel {
+    fn name(&self) -> &str {
+        &self.channel.name
+    }
+
+    fn is_subscribed(&self, event_type: &NotificationEventType, store_id: &str) -> bool {
+        self.channel.is_subscribed(event_type, store_id)
+    }
+
+    async fn send(&self, notification: &Notification, _: Option<&str>) -> Result<(), String> {
+        let EmailChannelSettings {
+            smtp_host,
+            smtp_port,
+            username,
+            password,
+            from,
+            to,
+        } = &self.settings;
+
+        let mut builder = Message::builder()
+            .from(parse_mailbox(from)?)
+            .subject(&notification.subject);
+        for to in to {
+            builder = builder.to(parse_mailbox(to)?);
+        }
+        let message = builder
+            .body(notification.body.clone())
+            .map_err(|error| error.to_string())?;
+
+        let mut transport = AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(smtp_host)
+            .map_err(|error| error.to_string())?
+            .port(smtp_port.unwrap_or(DEFAULT_SMTP_PORT))
+            .timeout(Some(SEND_TIMEOUT));
+        if let (Some(username), Some(password)) = (username, password) {
+            transport = transport.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+
+        transport
+            .build()
+            .send(message)
+            .await
+            .map_err(|error| error.to_string())?;
+        Ok(())
+    }
+}
+
+fn parse_mailbox(address: &str) -> Result<Mailbox, String> {
+    address
+        .parse()
+        .map_err(|error| format!("Invalid email address {address}: {error}"))
+}
+
+pub struct WebhookChannel {
+    channel: NotificationChannelSettings,
+    settings: WebhookChannelSettings,
+    client: reqwest::Client,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WebhookBody<'a> {
+    event_type: &'a NotificationEventType,
+    store_id: &'a str,
+    record_id: &'a str,
+    subject: &'a str,
+    body: &'a str,
+}
+
+#[async_trait]
+impl NotificationChannel for WebhookChannel {
+    fn name(&self) -> &str {
+        &self.channel.name
+    }
+
+    fn is_subscribed(&self, event_type: &NotificationEventType, store_id: &str) -> bool {
+        self.channel.is_subscribed(event_type, store_id)
+    }
+
+    async fn send(&self, notification: &Notification, _: Option<&str>) -> Result<(), String> {
+        let mut request = self.client.post(&self.settings.url).json(&WebhookBody {
+            event_type: &notification.event_type,
+            store_id: &notification.store_id,
+            record_id: &notification.record_id,
+            subject: &notification.subject,
+            body: &notification.body,
+        });
+        for (name, value) in &self.settings.headers {
+            request = request.header(name, value);
+        }
+
+        request
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|error| error.to_string())?;
+        Ok(())
+    }
+}
+
+pub struct SmsChannel {
+    channel: NotificationChannelSettings,
+    settings: SmsChannelSettings,
+    client: reqwest::Client,
+}
+
+#[derive(Serialize)]
+struct SmsBody<'a> {
+    to: &'a str,
+    from: Option<&'a str>,
+    message: &'a str,
+}
+
+#[async_trait]
+impl NotificationChannel for SmsChannel {
+    fn name(&self) -> &str {
+        &self.channel.name
+    }
+
+    fn is_subscribed(&self, event_type: &NotificationEventType, store_id: &str) -> bool {
+        self.channel.is_subscribed(event_type, store_id)
+    }
+
+    fn recipients(&self) -> Vec<Option<String>> {
+        self.settings.to.iter().cloned().map(Some).collect()
+    }
+
+    /// Subject is not sent, SMS templates should have all details in the body
+    async fn send(
+        &self,
+        notification: &Notification,
+        recipient: Option<&str>,
+    ) -> Result<(), String> {
+        let Some(to) = recipient else {
+            return Err("SMS recipient is not set".to_string());
+        };
+        let mut request = self.client.post(&self.settings.url).json(&SmsBody {
+            to,
+            from: self.settings.from.as_deref(),
+            message: &notification.body,
+        });
+        if let Some(api_key) = &self.settings.api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        request
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|error| error.to_string())?;
+        Ok(())
+    }
+}
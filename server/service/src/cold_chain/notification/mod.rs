@@ -0,0 +1,791 @@
+use std::{collections::HashMap, sync::Arc};
+
+use chrono::{Duration, NaiveDateTime, Utc};
+use repository::{
+    DatetimeFilter, EqualFilter, KeyValueStoreRepository, KeyValueType, NotificationEventType,
+    NotificationLogRow, NotificationLogRowRepository, NotificationStatus, RepositoryError,
+    SensorFilter, SensorRepository, SensorRow, StorageConnection, StoreFilter, StoreRepository,
+    TemperatureBreachFilter, TemperatureBreachRepository,
+};
+use tera::{Context, Tera};
+use util::uuid::uuid;
+
+use self::{
+    channel::{channels_from_settings, NotificationChannel},
+    settings::{NotificationSettings, NotificationTemplate},
+};
+use crate::{service_provider::ServiceProvider, sync::ActiveStoresOnSite};
+
+pub mod channel;
+pub mod settings;
+
+/// Failed notifications are retried on the next runs up to this many attempts
+const MAX_ATTEMPTS: usize = 3;
+/// Low battery is reported again after this time while the battery is still low
+const BATTERY_LOW_REMINDER_HOURS: i64 = 24;
+
+/// Alert condition found in the database.
+///
+/// Template variables of all events: `store_name`, `sensor_name`, `sensor_serial`.
+/// Breach events: `breach_type`, `start_datetime`, `end_datetime`, `threshold_minimum`,
+/// `threshold_maximum`, `minutes_unacknowledged`. Battery low: `battery_level`.
+/// Not reporting: `last_connection_datetime`. Datetimes are UTC
+#[derive(Debug, Clone)]
+pub struct NotificationEvent {
+    pub event_type: NotificationEventType,
+    pub store_id: String,
+    /// Breach or sensor
+    pub record_id: String,
+    /// A channel is notified again when it was last notified before this time, e.g. when a
+    /// sensor reported again and then stopped reporting. Notified once when not set
+    pub notify_after: Option<NaiveDateTime>,
+    pub context: Context,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notification {
+    pub event_type: NotificationEventType,
+    pub store_id: String,
+    pub record_id: String,
+    pub subject: String,
+    pub body: String,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct SendNotificationsResult {
+    pub sent: u32,
+    pub failed: u32,
+    /// Not sent because the channel reached the hourly limit, sent on a later run
+    pub rate_limited: u32,
+}
+
+/// Checks alert conditions every `interval_seconds` and sends notifications for the stores active
+/// on this site
+pub async fn run_notifications(
+    service_provider: Arc<ServiceProvider>,
+    settings: NotificationSettings,
+) {
+    let channels = channels_from_settings(&settings.channels);
+    if let Err(error) = service_provider
+        .basic_context()
+        .and_then(|ctx| seed_start_datetime(&ctx.connection, Utc::now().naive_utc()))
+    {
+        log::error!("Cannot seed cold chain notification start: {:?}", error);
+    }
+    loop {
+        tokio::time::sleep(std::time::Duration::from_secs(settings.interval_seconds)).await;
+
+        let store_ids = match service_provider.basic_context() {
+            Ok(ctx) => match ActiveStoresOnSite::get(&ctx.connection) {
+                Ok(active_stores) => active_stores.store_ids(),
+                // Site is not initialised yet
+                Err(_) => continue,
+            },
+            Err(error) => {
+                log::error!("Cannot check cold chain notifications: {:?}", error);
+                continue;
+            }
+        };
+
+        let now = Utc::now().naive_utc();
+        match send_notifications(&service_provider, &settings, &channels, &store_ids, now).await {
+            Ok(result) if result != SendNotificationsResult::default() => {
+                log::info!("Cold chain notifications {:?}", result)
+            }
+            Ok(_) => {}
+            Err(error) => log::error!("Error sending cold chain notifications: {:?}", error),
+        }
+    }
+}
+
+/// Breaches that started before notifications were first started are not notified, otherwise
+/// every historical unacknowledged breach would be sent on first start
+fn seed_start_datetime(
+    connection: &StorageConnection,
+    now: NaiveDateTime,
+) -> Result<(), RepositoryError> {
+    let repo = KeyValueStoreRepository::new(connection);
+    if get_start_datetime(connection)?.is_none() {
+        repo.set_string(
+            KeyValueType::ColdChainNotificationStartDatetime,
+            serde_json::to_string(&now).ok(),
+        )?;
+    }
+    Ok(())
+}
+
+fn get_start_datetime(
+    connection: &StorageConnection,
+) -> Result<Option<NaiveDateTime>, RepositoryError> {
+    let start_datetime = KeyValueStoreRepository::new(connection)
+        .get_string(KeyValueType::ColdChainNotificationStartDatetime)?;
+
+    Ok(start_datetime.and_then(|datetime| serde_json::from_str(&datetime).ok()))
+}
+
+/// Sends notifications of current events, events already sent through a channel are skipped.
+/// Each attempt is recorded in the notification log
+pub async fn send_notifications(
+    service_provider: &ServiceProvider,
+    settings: &NotificationSettings,
+    channels: &[Box<dyn NotificationChannel>],
+    store_ids: &[String],
+    now: NaiveDateTime,
+) -> Result<SendNotificationsResult, RepositoryError> {
+    let mut result = SendNotificationsResult::default();
+
+    // Connection is not held while sending
+    let pending = {
+        let ctx = service_provider.basic_context()?;
+        let events = get_notification_events(&ctx.connection, settings, store_ids, now)?;
+        pending_notifications(
+            &ctx.connection,
+            settings,
+            channels,
+            &events,
+            now,
+            &mut result,
+        )?
+    };
+
+    for (channel, recipient, notification) in pending {
+        let outcome = channel.send(&notification, recipient.as_deref()).await;
+        let (status, error) = match outcome {
+            Ok(()) => {
+                result.sent += 1;
+                (NotificationStatus::Sent, None)
+            }
+            Err(error) => {
+                log::warn!(
+                    "Cannot send notification through {}: {}",
+                    channel.name(),
+                    error
+                );
+                result.failed += 1;
+                (NotificationStatus::Failed, Some(error))
+            }
+        };
+
+        let ctx = service_provider.basic_context()?;
+        NotificationLogRowRepository::new(&ctx.connection).insert_one(&NotificationLogRow {
+            id: uuid(),
+            store_id: notification.store_id,
+            event_type: notification.event_type,
+            record_id: notification.record_id,
+            channel: channel.name().to_string(),
+            recipient,
+            status,
+            message: format!("{}\n\n{}", notification.subject, notification.body),
+            error,
+            created_datetime: now,
+        })?;
+    }
+
+    Ok(result)
+}
+
+pub(crate) fn get_notification_events(
+    connection: &StorageConnection,
+    settings: &NotificationSettings,
+    store_ids: &[String],
+    now: NaiveDateTime,
+) -> Result<Vec<NotificationEvent>, RepositoryError> {
+    let store_names: HashMap<String, String> = StoreRepository::new(connection)
+        .query_by_filter(StoreFilter::new().id(EqualFilter::equal_any(store_ids.to_vec())))?
+        .into_iter()
+        .map(|store| (store.store_row.id, store.name_row.name))
+        .collect();
+    let sensors: HashMap<String, SensorRow> = SensorRepository::new(connection)
+        .query_by_filter(SensorFilter::new().store_id(EqualFilter::equal_any(store_ids.to_vec())))?
+        .into_iter()
+        .map(|sensor| (sensor.sensor_row.id.clone(), sensor.sensor_row))
+        .collect();
+
+    let base_context = |store_id: &str, sensor_id: &str| {
+        let mut context = Context::new();
+        context.insert(
+            "store_name",
+            store_names
+                .get(store_id)
+                .map(String::as_str)
+                .unwrap_or(store_id),
+        );
+        let (sensor_name, sensor_serial) = match sensors.get(sensor_id) {
+            Some(sensor) => (sensor.name.as_str(), sensor.serial.as_str()),
+            None => (sensor_id, ""),
+        };
+        context.insert("sensor_name", sensor_name);
+        context.insert("sensor_serial", sensor_serial);
+        context
+    };
+
+    let mut events = Vec::new();
+
+    let mut breach_filter = TemperatureBreachFilter::new()
+        .store_id(EqualFilter::equal_any(store_ids.to_vec()))
+        .unacknowledged(true);
+    if let Some(start_datetime) = get_start_datetime(connection)? {
+        breach_filter =
+            breach_filter.start_datetime(DatetimeFilter::after_or_equal_to(start_datetime));
+    }
+    let breaches = TemperatureBreachRepository::new(connection).query_by_filter(breach_filter)?;
+    for breach in breaches {
+        let breach = breach.temperature_breach_row;
+        let minutes_unacknowledged = (now - breach.start_datetime).num_minutes();
+        let mut context = base_context(&breach.store_id, &breach.sensor_id);
+        context.insert("breach_type", &format!("{:?}", breach.r#type));
+        context.insert("start_datetime", &breach.start_datetime.to_string());
+        context.insert(
+            "end_datetime",
+            &breach.end_datetime.map(|datetime| datetime.to_string()),
+        );
+        context.insert("threshold_minimum", &breach.threshold_minimum);
+        context.insert("threshold_maximum", &breach.threshold_maximum);
+        context.insert("minutes_unacknowledged", &minutes_unacknowledged);
+
+        let is_escalated = settings
+            .escalation_minutes
+            .map_or(false, |escalation_minutes| {
+                minutes_unacknowledged >= escalation_minutes
+            });
+        events.push(NotificationEvent {
+            event_type: NotificationEventType::TemperatureBreach,
+            store_id: breach.store_id.clone(),
+            record_id: breach.id.clone(),
+            notify_after: None,
+            context: context.clone(),
+        });
+        if is_escalated {
+            events.push(NotificationEvent {
+                event_type: NotificationEventType::TemperatureBreachEscalation,
+                store_id: breach.store_id,
+                record_id: breach.id,
+                notify_after: None,
+                context,
+            });
+        }
+    }
+
+    for sensor in sensors.values().filter(|sensor| sensor.is_active) {
+        if let Some(battery_level) = sensor.battery_level {
+            if battery_level <= settings.battery_low_percentage {
+                let mut context = base_context(&sensor.store_id, &sensor.id);
+                context.insert("battery_level", &battery_level);
+                events.push(NotificationEvent {
+                    event_type: NotificationEventType::SensorBatteryLow,
+                    store_id: sensor.store_id.clone(),
+                    record_id: sensor.id.clone(),
+                    notify_after: Some(now - Duration::hours(BATTERY_LOW_REMINDER_HOURS)),
+                    context,
+                });
+            }
+        }
+
+        if let (Some(log_interval), Some(last_connection)) =
+            (sensor.log_interval, sensor.last_connection_datetime)
+        {
+            let allowed =
+                Duration::seconds(log_interval as i64 * settings.not_reporting_intervals as i64);
+            if log_interval > 0 && now - last_connection > allowed {
+                let mut context = base_context(&sensor.store_id, &sensor.id);
+                context.insert("last_connection_datetime", &last_connection.to_string());
+                events.push(NotificationEvent {
+                    event_type: NotificationEventType::SensorNotReporting,
+                    store_id: sensor.store_id.clone(),
+                    record_id: sensor.id.clone(),
+                    notify_after: Some(last_connection),
+                    context,
+                });
+            }
+        }
+    }
+
+    Ok(events)
+}
+
+/// Notifications to send per channel and recipient, skipping events already sent or failed too
+/// often and events over the hourly limit of the channel. Template errors are logged as failed
+fn pending_notifications<'a>(
+    connection: &StorageConnection,
+    settings: &NotificationSettings,
+    channels: &'a [Box<dyn NotificationChannel>],
+    events: &[NotificationEvent],
+    now: NaiveDateTime,
+    result: &mut SendNotificationsResult,
+) -> Result<Vec<(&'a dyn NotificationChannel, Option<String>, Notification)>, RepositoryError> {
+    let repo = NotificationLogRowRepository::new(connection);
+    let mut pending = Vec::new();
+
+    for channel in channels {
+        let mut remaining =
+            settings.max_per_hour - repo.count_sent(channel.name(), now - Duration::hours(1))?;
+
+        for (event, recipient) in events.iter().flat_map(|event| {
+            channel
+                .recipients()
+                .into_iter()
+                .map(move |recipient| (event, recipient))
+        }) {
+            if !channel.is_subscribed(&event.event_type, &event.store_id) {
+                continue;
+            }
+
+            let logs: Vec<NotificationLogRow> = repo
+                .find_many_by_event(
+                    &event.event_type,
+                    &event.record_id,
+                    channel.name(),
+                    recipient.as_deref(),
+                )?
+                .into_iter()
+                .filter(|log| match event.notify_after {
+                    Some(notify_after) => log.created_datetime >= notify_after,
+                    None => true,
+                })
+                .collect();
+            let is_sent = logs
+                .iter()
+                .any(|log| log.status == NotificationStatus::Sent);
+            let attempts = logs
+                .iter()
+                .filter(|log| log.status == NotificationStatus::Failed)
+                .count();
+            if is_sent || attempts >= MAX_ATTEMPTS {
+                continue;
+            }
+
+            if remaining <= 0 {
+                result.rate_limited += 1;
+                continue;
+            }
+            remaining -= 1;
+
+            match render(settings, event) {
+                Ok(notification) => pending.push((channel.as_ref(), recipient, notification)),
+                Err(error) => {
+                    log::error!("Cannot render notification template: {}", error);
+                    result.failed += 1;
+                    repo.insert_one(&NotificationLogRow {
+                        id: uuid(),
+                        store_id: event.store_id.clone(),
+                        event_type: event.event_type.clone(),
+                        record_id: event.record_id.clone(),
+                        channel: channel.name().to_string(),
+                        recipient,
+                        status: NotificationStatus::Failed,
+                        message: String::new(),
+                        error: Some(error),
+                        created_datetime: now,
+                    })?;
+                }
+            }
+        }
+    }
+
+    Ok(pending)
+}
+
+fn render(
+    settings: &NotificationSettings,
+    event: &NotificationEvent,
+) -> Result<Notification, String> {
+    let template = settings
+        .templates
+        .get(&event.event_type)
+        .cloned()
+        .unwrap_or_else(|| default_template(&event.event_type));
+    let render = |template: &str| {
+        Tera::one_off(template, &event.context, false).map_err(|error| format!("{:?}", error))
+    };
+
+    Ok(Notification {
+        event_type: event.event_type.clone(),
+        store_id: event.store_id.clone(),
+        record_id: event.record_id.clone(),
+        subject: render(&template.subject)?,
+        body: render(&template.body)?,
+    })
+}
+
+fn default_template(event_type: &NotificationEventType) -> NotificationTemplate {
+    let (subject, body) = match event_type {
+        NotificationEventType::TemperatureBreach => (
+            "Temperature breach: {{ sensor_name }} ({{ store_name }})",
+            "{{ breach_type }} breach on sensor {{ sensor_name }} ({{ sensor_serial }}) in \
+             {{ store_name }} started at {{ start_datetime }} UTC. Allowed range is \
+             {{ threshold_minimum }}°C to {{ threshold_maximum }}°C.",
+        ),
+        NotificationEventType::TemperatureBreachEscalation => (
+            "Unacknowledged temperature breach: {{ sensor_name }} ({{ store_name }})",
+            "{{ breach_type }} breach on sensor {{ sensor_name }} ({{ sensor_serial }}) in \
+             {{ store_name }} started at {{ start_datetime }} UTC and has not been acknowledged \
+             for {{ minutes_unacknowledged }} minutes.",
+        ),
+        NotificationEventType::SensorBatteryLow => (
+            "Sensor battery low: {{ sensor_name }} ({{ store_name }})",
+            "Battery of sensor {{ sensor_name }} ({{ sensor_serial }}) in {{ store_name }} is at \
+             {{ battery_level }}%.",
+        ),
+        NotificationEventType::SensorNotReporting => (
+            "Sensor not reporting: {{ sensor_name }} ({{ store_name }})",
+            "Sensor {{ sensor_name }} ({{ sensor_serial }}) in {{ store_name }} has not reported \
+             since {{ last_connection_datetime }} UTC.",
+        ),
+    };
+
+    NotificationTemplate {
+        subject: subject.to_string(),
+        body: body.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
+
+    use async_trait::async_trait;
+    use chrono::Duration;
+    use repository::{
+        mock::{mock_store_a, MockData, MockDataInserts},
+        test_db::setup_all_with_data,
+        NotificationEventType, NotificationLogRowRepository, NotificationStatus, SensorRow,
+        TemperatureBreachRow,
+    };
+
+    use super::{
+        channel::NotificationChannel,
+        seed_start_datetime, send_notifications,
+        settings::{NotificationSettings, NotificationTemplate},
+        Notification, SendNotificationsResult,
+    };
+    use crate::{service_provider::ServiceProvider, test_helpers::datetime};
+
+    struct MockChannel {
+        name: String,
+        events: Option<Vec<NotificationEventType>>,
+        recipients: Vec<Option<String>>,
+        /// Recipients that sends fail for
+        failing: Vec<Option<String>>,
+        sent: Arc<Mutex<Vec<Notification>>>,
+    }
+
+    #[async_trait]
+    impl NotificationChannel for MockChannel {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        fn is_subscribed(&self, event_type: &NotificationEventType, _: &str) -> bool {
+            match &self.events {
+                Some(events) => events.contains(event_type),
+                None => true,
+            }
+        }
+
+        fn recipients(&self) -> Vec<Option<String>> {
+            self.recipients.clone()
+        }
+
+        async fn send(
+            &self,
+            notification: &Notification,
+            recipient: Option<&str>,
+        ) -> Result<(), String> {
+            if self.failing.contains(&recipient.map(str::to_string)) {
+                return Err("Gateway unavailable".to_string());
+            }
+            self.sent.lock().unwrap().push(notification.clone());
+            Ok(())
+        }
+    }
+
+    #[actix_rt::test]
+    async fn send_cold_chain_notifications() {
+        let store = mock_store_a();
+        // Battery low and not reporting for an hour
+        let sensor = SensorRow {
+            id: "sensor".to_string(),
+            name: "Fridge 1".to_string(),
+            serial: "serial".to_string(),
+            store_id: store.id.clone(),
+            is_active: true,
+            battery_level: Some(10),
+            log_interval: Some(300),
+            last_connection_datetime: Some(datetime(11, 0)),
+            ..Default::default()
+        };
+        let sensor_ok = SensorRow {
+            id: "sensor_ok".to_string(),
+            serial: "serial_ok".to_string(),
+            battery_level: Some(80),
+            last_connection_datetime: Some(datetime(11, 58)),
+            ..sensor.clone()
+        };
+        let breach = TemperatureBreachRow {
+            id: "breach".to_string(),
+            sensor_id: sensor.id.clone(),
+            store_id: store.id.clone(),
+            start_datetime: datetime(10, 0),
+            unacknowledged: true,
+            threshold_minimum: 2.0,
+            threshold_maximum: 8.0,
+            ..Default::default()
+        };
+        let acknowledged = TemperatureBreachRow {
+            id: "acknowledged".to_string(),
+            unacknowledged: false,
+            ..breach.clone()
+        };
+
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "send_cold_chain_notifications",
+            MockDataInserts::none().names().stores(),
+            MockData {
+                sensors: vec![sensor, sensor_ok],
+                temperature_breaches: vec![breach, acknowledged],
+                ..Default::default()
+            },
+        )
+        .await;
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+
+        let settings = NotificationSettings {
+            interval_seconds: 300,
+            battery_low_percentage: 20,
+            not_reporting_intervals: 3,
+            escalation_minutes: Some(60),
+            max_per_hour: 3,
+            channels: Vec::new(),
+            templates: HashMap::from([(
+                NotificationEventType::SensorBatteryLow,
+                NotificationTemplate {
+                    subject: "Battery {{ battery_level }}%".to_string(),
+                    body: "{{ sensor_name }} in {{ store_name }}".to_string(),
+                },
+            )]),
+        };
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let channels: Vec<Box<dyn NotificationChannel>> = vec![
+            Box::new(MockChannel {
+                name: "email".to_string(),
+                events: None,
+                recipients: vec![None],
+                failing: Vec::new(),
+                sent: sent.clone(),
+            }),
+            Box::new(MockChannel {
+                name: "sms".to_string(),
+                events: Some(vec![NotificationEventType::TemperatureBreach]),
+                recipients: vec![None],
+                failing: vec![None],
+                sent: sent.clone(),
+            }),
+        ];
+        let store_ids = vec![store.id.clone()];
+        let now = datetime(12, 0);
+
+        // Breach, escalation, battery low and not reporting, last one is over the hourly limit
+        let result = send_notifications(&service_provider, &settings, &channels, &store_ids, now)
+            .await
+            .unwrap();
+        assert_eq!(
+            result,
+            SendNotificationsResult {
+                sent: 3,
+                failed: 1,
+                rate_limited: 1
+            }
+        );
+        {
+            let sent = sent.lock().unwrap();
+            assert_eq!(
+                sent.iter()
+                    .map(|notification| notification.event_type.clone())
+                    .collect::<Vec<_>>(),
+                vec![
+                    NotificationEventType::TemperatureBreach,
+                    NotificationEventType::TemperatureBreachEscalation,
+                    NotificationEventType::SensorBatteryLow
+                ]
+            );
+            assert_eq!(sent[0].subject, "Temperature breach: Fridge 1 (Store A)");
+            assert_eq!(sent[0].record_id, "breach");
+            assert_eq!(sent[2].subject, "Battery 10%");
+            assert_eq!(sent[2].body, "Fridge 1 in Store A");
+        }
+        let logs = NotificationLogRowRepository::new(&connection)
+            .find_many_by_event(
+                &NotificationEventType::TemperatureBreach,
+                "breach",
+                "sms",
+                None,
+            )
+            .unwrap();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].status, NotificationStatus::Failed);
+        assert_eq!(logs[0].error, Some("Gateway unavailable".to_string()));
+
+        // Sent notifications are not repeated, failed ones are retried
+        let result = send_notifications(&service_provider, &settings, &channels, &store_ids, now)
+            .await
+            .unwrap();
+        assert_eq!(
+            result,
+            SendNotificationsResult {
+                sent: 0,
+                failed: 1,
+                rate_limited: 1
+            }
+        );
+
+        // Next hour rate limited notification is sent, failed notification is attempted last time
+        let now = now + Duration::hours(2);
+        let result = send_notifications(&service_provider, &settings, &channels, &store_ids, now)
+            .await
+            .unwrap();
+        assert_eq!(
+            result,
+            SendNotificationsResult {
+                sent: 1,
+                failed: 1,
+                rate_limited: 0
+            }
+        );
+        assert_eq!(
+            sent.lock().unwrap().last().unwrap().event_type,
+            NotificationEventType::SensorNotReporting
+        );
+
+        let result = send_notifications(&service_provider, &settings, &channels, &store_ids, now)
+            .await
+            .unwrap();
+        assert_eq!(result, SendNotificationsResult::default());
+
+        // Low battery is reported again the next day
+        let now = now + Duration::hours(24);
+        let result = send_notifications(&service_provider, &settings, &channels, &store_ids, now)
+            .await
+            .unwrap();
+        assert_eq!(result.sent, 1);
+        assert_eq!(
+            sent.lock().unwrap().last().unwrap().event_type,
+            NotificationEventType::SensorBatteryLow
+        );
+    }
+
+    #[actix_rt::test]
+    async fn send_cold_chain_notifications_per_recipient() {
+        let sensor = SensorRow {
+            id: "sensor".to_string(),
+            name: "Fridge 1".to_string(),
+            serial: "serial".to_string(),
+            store_id: mock_store_a().id,
+            is_active: true,
+            ..Default::default()
+        };
+        let breach = TemperatureBreachRow {
+            id: "breach".to_string(),
+            sensor_id: sensor.id.clone(),
+            store_id: mock_store_a().id,
+            start_datetime: datetime(11, 30),
+            unacknowledged: true,
+            ..Default::default()
+        };
+        let historical_breach = TemperatureBreachRow {
+            id: "historical_breach".to_string(),
+            start_datetime: datetime(10, 0),
+            ..breach.clone()
+        };
+
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "send_cold_chain_notifications_per_recipient",
+            MockDataInserts::none().names().stores(),
+            MockData {
+                sensors: vec![sensor],
+                temperature_breaches: vec![breach, historical_breach],
+                ..Default::default()
+            },
+        )
+        .await;
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+
+        let settings = NotificationSettings {
+            interval_seconds: 300,
+            battery_low_percentage: 20,
+            not_reporting_intervals: 3,
+            escalation_minutes: None,
+            max_per_hour: 10,
+            channels: Vec::new(),
+            templates: HashMap::new(),
+        };
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let channels: Vec<Box<dyn NotificationChannel>> = vec![Box::new(MockChannel {
+            name: "sms".to_string(),
+            events: Some(vec![NotificationEventType::TemperatureBreach]),
+            recipients: vec![Some("a".to_string()), Some("b".to_string())],
+            failing: vec![Some("b".to_string())],
+            sent: sent.clone(),
+        })];
+        let store_ids = vec![mock_store_a().id];
+        let now = datetime(12, 0);
+
+        // Breaches from before notifications were started are not notified
+        seed_start_datetime(&connection, datetime(11, 0)).unwrap();
+        // Start is only seeded on first start
+        seed_start_datetime(&connection, now).unwrap();
+
+        let result = send_notifications(&service_provider, &settings, &channels, &store_ids, now)
+            .await
+            .unwrap();
+        assert_eq!(
+            result,
+            SendNotificationsResult {
+                sent: 1,
+                failed: 1,
+                rate_limited: 0
+            }
+        );
+        assert_eq!(sent.lock().unwrap()[0].record_id, "breach");
+
+        // Only the failed recipient is retried
+        let result = send_notifications(&service_provider, &settings, &channels, &store_ids, now)
+            .await
+            .unwrap();
+        assert_eq!(
+            result,
+            SendNotificationsResult {
+                sent: 0,
+                failed: 1,
+                rate_limited: 0
+            }
+        );
+        assert_eq!(sent.lock().unwrap().len(), 1);
+
+        let repo = NotificationLogRowRepository::new(&connection);
+        let logs = repo
+            .find_many_by_event(
+                &NotificationEventType::TemperatureBreach,
+                "breach",
+                "sms",
+                Some("b"),
+            )
+            .unwrap();
+        assert_eq!(logs.len(), 2);
+        assert!(logs
+            .iter()
+            .all(|log| log.status == NotificationStatus::Failed));
+        let logs = repo
+            .find_many_by_event(
+                &NotificationEventType::TemperatureBreach,
+                "breach",
+                "sms",
+                Some("a"),
+            )
+            .unwrap();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].status, NotificationStatus::Sent);
+    }
+}
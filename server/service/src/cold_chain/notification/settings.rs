@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+
+use repository::NotificationEventType;
+
+fn default_interval_seconds() -> u64 {
+    5 * 60
+}
+
+fn default_battery_low_percentage() -> i32 {
+    20
+}
+
+fn default_not_reporting_intervals() -> i32 {
+    3
+}
+
+fn default_max_per_hour() -> i64 {
+    20
+}
+
+/// Cold chain alerts, sent for the stores active on this site
+#[derive(serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct NotificationSettings {
+    /// How often alert conditions are checked, defaults to 5 minutes
+    #[serde(default = "default_interval_seconds")]
+    pub interval_seconds: u64,
+    /// Sensors at or below this battery level are reported, defaults to 20 (%)
+    #[serde(default = "default_battery_low_percentage")]
+    pub battery_low_percentage: i32,
+    /// Sensors are reported when they haven't connected for this many log intervals, defaults to 3
+    #[serde(default = "default_not_reporting_intervals")]
+    pub not_reporting_intervals: i32,
+    /// Breaches still unacknowledged after this many minutes are escalated, no escalation when
+    /// not set
+    pub escalation_minutes: Option<i64>,
+    /// Maximum notifications sent through a channel per hour, others are sent in later hours.
+    /// Each SMS recipient counts as a notification. Defaults to 20
+    #[serde(default = "default_max_per_hour")]
+    pub max_per_hour: i64,
+    pub channels: Vec<NotificationChannelSettings>,
+    /// Replaces the default template of an event
+    #[serde(default)]
+    pub templates: HashMap<NotificationEventType, NotificationTemplate>,
+}
+
+/// Tera templates, see `NotificationEvent` for available variables
+#[derive(serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct NotificationTemplate {
+    pub subject: String,
+    pub body: String,
+}
+
+#[derive(serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct NotificationChannelSettings {
+    /// Recorded in notification log, should not change once notifications were sent
+    pub name: String,
+    /// Events sent through the channel, all events when not set
+    pub events: Option<Vec<NotificationEventType>>,
+    /// Stores events are sent for, all stores when not set
+    pub store_ids: Option<Vec<String>>,
+    #[serde(flatten)]
+    pub kind: NotificationChannelKind,
+}
+
+impl NotificationChannelSettings {
+    pub fn is_subscribed(&self, event_type: &NotificationEventType, store_id: &str) -> bool {
+        let event_matches = match &self.events {
+            Some(events) => events.contains(event_type),
+            None => true,
+        };
+        let store_matches = match &self.store_ids {
+            Some(store_ids) => store_ids.iter().any(|id| id == store_id),
+            None => true,
+        };
+        event_matches && store_matches
+    }
+}
+
+#[derive(serde::Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type")]
+pub enum NotificationChannelKind {
+    Email(EmailChannelSettings),
+    Webhook(WebhookChannelSettings),
+    Sms(SmsChannelSettings),
+}
+
+#[derive(serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct EmailChannelSettings {
+    pub smtp_host: String,
+    /// Defaults to 587 (STARTTLS)
+    pub smtp_port: Option<u16>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub from: String,
+    pub to: Vec<String>,
+}
+
+/// Notifications are posted as JSON: `{ eventType, storeId, recordId, subject, body }`
+#[derive(serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct WebhookChannelSettings {
+    pub url: String,
+    /// Extra request headers, e.g. for authorisation
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+}
+
+/// Message body is posted as JSON for each recipient: `{ to, from, message }`
+#[derive(serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct SmsChannelSettings {
+    pub url: String,
+    /// Sent as bearer token
+    pub api_key: Option<String>,
+    pub from: Option<String>,
+    pub to: Vec<String>,
+}
@@ -0,0 +1,240 @@
+use repository::{
+    EqualFilter, LocationRowRepository, RepositoryError, TemperatureBreachConfig,
+    TemperatureBreachConfigFilter, TemperatureBreachConfigRepository, TemperatureBreachConfigRow,
+    TemperatureBreachConfigRowRepository, TemperatureBreachRowType,
+};
+
+use crate::service_provider::ServiceContext;
+
+#[derive(Debug, PartialEq)]
+pub enum UpsertTemperatureBreachConfigError {
+    TemperatureBreachConfigDoesNotBelongToCurrentStore,
+    LocationDoesNotExist,
+    MinimumTemperatureAboveMaximum,
+    LookbackDaysMustBePositive,
+    DurationOrHysteresisIsNegative,
+    DatabaseError(RepositoryError),
+}
+
+/// Excursion settings keep their current value (or the default for new configs) when not set
+#[derive(Debug, Clone, Default)]
+pub struct UpsertTemperatureBreachConfig {
+    pub id: String,
+    pub r#type: TemperatureBreachRowType,
+    pub description: String,
+    pub is_active: bool,
+    pub duration_milliseconds: i32,
+    pub minimum_temperature: f64,
+    pub maximum_temperature: f64,
+    /// Excursion configs for a location override the store wide config (without location)
+    pub location_id: Option<String>,
+    pub lookback_days: Option<i32>,
+    pub minimum_excursion_duration_milliseconds: Option<i32>,
+    pub hysteresis_temperature: Option<f64>,
+    pub hysteresis_duration_milliseconds: Option<i32>,
+}
+
+pub fn get_temperature_breach_configs(
+    ctx: &ServiceContext,
+) -> Result<Vec<TemperatureBreachConfig>, RepositoryError> {
+    TemperatureBreachConfigRepository::new(&ctx.connection).query_by_filter(
+        TemperatureBreachConfigFilter::new().store_id(EqualFilter::equal_to(&ctx.store_id)),
+    )
+}
+
+pub fn upsert_temperature_breach_config(
+    ctx: &ServiceContext,
+    input: UpsertTemperatureBreachConfig,
+) -> Result<TemperatureBreachConfig, UpsertTemperatureBreachConfigError> {
+    let result = ctx
+        .connection
+        .transaction_sync(
+            |connection| -> Result<_, UpsertTemperatureBreachConfigError> {
+                use UpsertTemperatureBreachConfigError::*;
+
+                let repo = TemperatureBreachConfigRowRepository::new(connection);
+                let existing = repo.find_one_by_id(&input.id)?;
+                if let Some(existing) = &existing {
+                    if existing.store_id != ctx.store_id {
+                        return Err(TemperatureBreachConfigDoesNotBelongToCurrentStore);
+                    }
+                }
+                if let Some(location_id) = &input.location_id {
+                    let location = LocationRowRepository::new(connection)
+                        .find_one_by_id(location_id)?
+                        .filter(|location| location.store_id == ctx.store_id);
+                    if location.is_none() {
+                        return Err(LocationDoesNotExist);
+                    }
+                }
+                if input.minimum_temperature > input.maximum_temperature {
+                    return Err(MinimumTemperatureAboveMaximum);
+                }
+
+                let existing = existing.unwrap_or_default();
+                let row = TemperatureBreachConfigRow {
+                    id: input.id,
+                    duration_milliseconds: input.duration_milliseconds,
+                    r#type: input.r#type,
+                    description: input.description,
+                    is_active: input.is_active,
+                    store_id: ctx.store_id.clone(),
+                    minimum_temperature: input.minimum_temperature,
+                    maximum_temperature: input.maximum_temperature,
+                    location_id: input.location_id,
+                    lookback_days: input.lookback_days.unwrap_or(existing.lookback_days),
+                    minimum_excursion_duration_milliseconds: input
+                        .minimum_excursion_duration_milliseconds
+                        .unwrap_or(existing.minimum_excursion_duration_milliseconds),
+                    hysteresis_temperature: input
+                        .hysteresis_temperature
+                        .unwrap_or(existing.hysteresis_temperature),
+                    hysteresis_duration_milliseconds: input
+                        .hysteresis_duration_milliseconds
+                        .unwrap_or(existing.hysteresis_duration_milliseconds),
+                };
+                if row.lookback_days <= 0 {
+                    return Err(LookbackDaysMustBePositive);
+                }
+                if row.duration_milliseconds < 0
+                    || row.minimum_excursion_duration_milliseconds < 0
+                    || row.hysteresis_temperature < 0.0
+                    || row.hysteresis_duration_milliseconds < 0
+                {
+                    return Err(DurationOrHysteresisIsNegative);
+                }
+
+                repo.upsert_one(&row)?;
+                Ok(TemperatureBreachConfig {
+                    temperature_breach_config_row: row,
+                })
+            },
+        )
+        .map_err(|error| error.to_inner_error())?;
+    Ok(result)
+}
+
+impl From<RepositoryError> for UpsertTemperatureBreachConfigError {
+    fn from(error: RepositoryError) -> Self {
+        UpsertTemperatureBreachConfigError::DatabaseError(error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use repository::{
+        mock::{mock_location_1, mock_location_in_another_store, mock_store_a, MockDataInserts},
+        test_db::setup_all,
+        TemperatureBreachRowType,
+    };
+
+    use super::{UpsertTemperatureBreachConfig, UpsertTemperatureBreachConfigError};
+    use crate::service_provider::ServiceProvider;
+
+    #[actix_rt::test]
+    async fn upsert_temperature_breach_config() {
+        let (_, _, connection_manager, _) =
+            setup_all("upsert_temperature_breach_config", MockDataInserts::all()).await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let ctx = service_provider
+            .context(mock_store_a().id, "".to_string())
+            .unwrap();
+        let service = service_provider.cold_chain_service;
+
+        let input = UpsertTemperatureBreachConfig {
+            id: "fridge_excursion".to_string(),
+            r#type: TemperatureBreachRowType::Excursion,
+            description: "Fridge excursion".to_string(),
+            is_active: true,
+            minimum_temperature: 2.0,
+            maximum_temperature: 8.0,
+            location_id: Some(mock_location_1().id),
+            ..Default::default()
+        };
+
+        // Errors
+        assert_eq!(
+            service.upsert_temperature_breach_config(
+                &ctx,
+                UpsertTemperatureBreachConfig {
+                    location_id: Some(mock_location_in_another_store().id),
+                    ..input.clone()
+                }
+            ),
+            Err(UpsertTemperatureBreachConfigError::LocationDoesNotExist)
+        );
+        assert_eq!(
+            service.upsert_temperature_breach_config(
+                &ctx,
+                UpsertTemperatureBreachConfig {
+                    minimum_temperature: 9.0,
+                    ..input.clone()
+                }
+            ),
+            Err(UpsertTemperatureBreachConfigError::MinimumTemperatureAboveMaximum)
+        );
+        assert_eq!(
+            service.upsert_temperature_breach_config(
+                &ctx,
+                UpsertTemperatureBreachConfig {
+                    lookback_days: Some(0),
+                    ..input.clone()
+                }
+            ),
+            Err(UpsertTemperatureBreachConfigError::LookbackDaysMustBePositive)
+        );
+        assert_eq!(
+            service.upsert_temperature_breach_config(
+                &ctx,
+                UpsertTemperatureBreachConfig {
+                    hysteresis_temperature: Some(-0.5),
+                    ..input.clone()
+                }
+            ),
+            Err(UpsertTemperatureBreachConfigError::DurationOrHysteresisIsNegative)
+        );
+
+        // New config gets default lookback
+        let config = service
+            .upsert_temperature_breach_config(&ctx, input.clone())
+            .unwrap()
+            .temperature_breach_config_row;
+        assert_eq!(config.store_id, mock_store_a().id);
+        assert_eq!(config.location_id, Some(mock_location_1().id));
+        assert_eq!(config.lookback_days, 7);
+
+        // Settings that are not set are kept
+        service
+            .upsert_temperature_breach_config(
+                &ctx,
+                UpsertTemperatureBreachConfig {
+                    lookback_days: Some(14),
+                    hysteresis_temperature: Some(0.5),
+                    hysteresis_duration_milliseconds: Some(30 * 60 * 1000),
+                    ..input.clone()
+                },
+            )
+            .unwrap();
+        let config = service
+            .upsert_temperature_breach_config(
+                &ctx,
+                UpsertTemperatureBreachConfig {
+                    maximum_temperature: 7.0,
+                    ..input.clone()
+                },
+            )
+            .unwrap()
+            .temperature_breach_config_row;
+        assert_eq!(config.maximum_temperature, 7.0);
+        assert_eq!(config.lookback_days, 14);
+        assert_eq!(config.hysteresis_temperature, 0.5);
+        assert_eq!(config.hysteresis_duration_milliseconds, 30 * 60 * 1000);
+
+        assert!(service
+            .get_temperature_breach_configs(&ctx)
+            .unwrap()
+            .iter()
+            .any(|config| config.temperature_breach_config_row.id == input.id));
+    }
+}
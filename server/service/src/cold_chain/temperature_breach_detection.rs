@@ -0,0 +1,454 @@
+use std::convert::TryInto;
+
+use chrono::{Duration, NaiveDateTime};
+use repository::{
+    EqualFilter, RepositoryError, SensorFilter, SensorRow, StorageConnection,
+    TemperatureBreachConfigFilter, TemperatureBreachConfigRepository, TemperatureBreachConfigRow,
+    TemperatureBreachFilter, TemperatureBreachRepository, TemperatureBreachRow,
+    TemperatureBreachRowRepository, TemperatureBreachRowType, TemperatureLogRow,
+    TemperatureLogRowRepository,
+};
+use util::uuid::uuid;
+
+/// Breach found in temperature logs, before it's matched to existing breach rows
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct DetectedBreach {
+    pub(crate) start_datetime: NaiveDateTime,
+    /// First log back within range, None while breach is ongoing
+    pub(crate) end_datetime: Option<NaiveDateTime>,
+    /// Time temperature was out of range
+    pub(crate) duration: Duration,
+    /// Out of range logs
+    pub(crate) log_ids: Vec<String>,
+}
+
+/// Detects breaches in logs of the sensor from `from`, against active breach configs of sensor's store.
+///
+/// Detected breaches are matched to existing breaches of the sensor (by overlapping period for
+/// consecutive breaches and by day for cumulative breaches), so logs can be processed again
+/// (e.g. when they arrive late or out of order) without creating duplicates: existing breaches are
+/// extended or closed, and only new breaches are created. Breaches are never deleted.
+/// Returns new and updated breaches
+pub fn detect_temperature_breaches(
+    connection: &StorageConnection,
+    sensor: &SensorRow,
+    from: NaiveDateTime,
+) -> Result<Vec<TemperatureBreachRow>, RepositoryError> {
+    let mut configs = TemperatureBreachConfigRepository::new(connection)
+        .query_by_filter(
+            TemperatureBreachConfigFilter::new()
+                .store_id(EqualFilter::equal_to(&sensor.store_id))
+                .is_active(true),
+        )?
+        .into_iter()
+        .map(|config| config.temperature_breach_config_row)
+        .filter(|config| config.r#type != TemperatureBreachRowType::Excursion)
+        .collect::<Vec<_>>();
+    // Logs are linked to the first breach they are part of, consecutive breaches take precedence
+    configs.sort_by_key(|config| is_cumulative(&config.r#type));
+
+    let log_repo = TemperatureLogRowRepository::new(connection);
+    let mut result = Vec::new();
+
+    for config in configs {
+        let window_start = window_start(connection, sensor, &config, from)?;
+        let logs = log_repo.find_many_by_sensor_id(&sensor.id, window_start)?;
+        let detected = if is_cumulative(&config.r#type) {
+            cumulative_breaches(&logs, &config)
+        } else {
+            consecutive_breaches(&logs, &config)
+        };
+
+        let mut existing = TemperatureBreachRepository::new(connection)
+            .query_by_filter(
+                TemperatureBreachFilter::new()
+                    .sensor(SensorFilter::new().id(EqualFilter::equal_to(&sensor.id)))
+                    .r#type(EqualFilter::equal_to_breach_type(&config.r#type)),
+            )?
+            .into_iter()
+            .map(|breach| breach.temperature_breach_row)
+            .collect::<Vec<_>>();
+
+        for breach in detected {
+            let (row, is_changed) = save_breach(connection, sensor, &config, &breach, &existing)?;
+            link_logs(connection, &logs, &breach, &row.id)?;
+            if is_changed {
+                existing.retain(|existing| existing.id != row.id);
+                existing.push(row.clone());
+                result.push(row);
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+/// Logs from this datetime need to be evaluated to detect breaches including logs from `from`:
+/// start of the day for cumulative breaches, or the last log within range for consecutive breaches
+fn window_start(
+    connection: &StorageConnection,
+    sensor: &SensorRow,
+    config: &TemperatureBreachConfigRow,
+    from: NaiveDateTime,
+) -> Result<Option<NaiveDateTime>, RepositoryError> {
+    if is_cumulative(&config.r#type) {
+        return Ok(from.date().and_hms_opt(0, 0, 0));
+    }
+
+    let (minimum, maximum) = if is_hot(&config.r#type) {
+        (f64::MIN, config.maximum_temperature)
+    } else {
+        (config.minimum_temperature, f64::MAX)
+    };
+    let last_within_range = TemperatureLogRowRepository::new(connection)
+        .find_latest_within_range_before(&sensor.id, from, minimum, maximum)?;
+
+    Ok(last_within_range.map(|log| log.datetime))
+}
+
+fn is_cumulative(r#type: &TemperatureBreachRowType) -> bool {
+    matches!(
+        r#type,
+        TemperatureBreachRowType::HotCumulative | TemperatureBreachRowType::ColdCumulative
+    )
+}
+
+fn is_hot(r#type: &TemperatureBreachRowType) -> bool {
+    matches!(
+        r#type,
+        TemperatureBreachRowType::HotConsecutive | TemperatureBreachRowType::HotCumulative
+    )
+}
+
+fn is_out_of_range(config: &TemperatureBreachConfigRow, log: &TemperatureLogRow) -> bool {
+    match config.r#type {
+        TemperatureBreachRowType::HotConsecutive | TemperatureBreachRowType::HotCumulative => {
+            log.temperature > config.maximum_temperature
+        }
+        TemperatureBreachRowType::ColdConsecutive | TemperatureBreachRowType::ColdCumulative => {
+            log.temperature < config.minimum_temperature
+        }
+        TemperatureBreachRowType::Excursion => false,
+    }
+}
+
+fn threshold(config: &TemperatureBreachConfigRow) -> Duration {
+    Duration::milliseconds(config.duration_milliseconds as i64)
+}
+
+/// Runs of out of range logs lasting at least config duration, `logs` must be sorted by datetime
+pub(crate) fn consecutive_breaches(
+    logs: &[TemperatureLogRow],
+    config: &TemperatureBreachConfigRow,
+) -> Vec<DetectedBreach> {
+    let mut breaches = Vec::new();
+    let mut current: Option<DetectedBreach> = None;
+
+    for log in logs {
+        if is_out_of_range(config, log) {
+            let breach = current.get_or_insert_with(|| DetectedBreach {
+                start_datetime: log.datetime,
+                end_datetime: None,
+                duration: Duration::zero(),
+                log_ids: Vec::new(),
+            });
+            breach.duration = log.datetime - breach.start_datetime;
+            breach.log_ids.push(log.id.clone());
+        } else if let Some(mut breach) = current.take() {
+            breach.end_datetime = Some(log.datetime);
+            breach.duration = log.datetime - breach.start_datetime;
+            breaches.push(breach);
+        }
+    }
+    breaches.extend(current);
+
+    breaches
+        .into_iter()
+        .filter(|breach| breach.duration >= threshold(config))
+        .collect()
+}
+
+/// Days where total out of range time reaches config duration, `logs` must be sorted by datetime.
+/// Temperature of a log applies until the next log, and is counted in the day of the log
+pub(crate) fn cumulative_breaches(
+    logs: &[TemperatureLogRow],
+    config: &TemperatureBreachConfigRow,
+) -> Vec<DetectedBreach> {
+    let mut breaches: Vec<DetectedBreach> = Vec::new();
+
+    for (index, log) in logs.iter().enumerate() {
+        if !is_out_of_range(config, log) {
+            continue;
+        }
+        let next = logs.get(index + 1);
+        let day_end = log.datetime.date().and_hms_opt(0, 0, 0).unwrap() + Duration::days(1);
+        let period_end = next
+            .map(|next| next.datetime.min(day_end))
+            .unwrap_or(log.datetime);
+        let end_datetime = match next {
+            None => None,
+            Some(next) if next.datetime >= day_end => Some(day_end),
+            Some(next) if !is_out_of_range(config, next) => Some(next.datetime),
+            // Still out of range, end is set by following logs
+            Some(_) => None,
+        };
+
+        let is_same_day = breaches
+            .last()
+            .map(|breach| breach.start_datetime.date() == log.datetime.date())
+            .unwrap_or(false);
+        if !is_same_day {
+            breaches.push(DetectedBreach {
+                start_datetime: log.datetime,
+                end_datetime: None,
+                duration: Duration::zero(),
+                log_ids: Vec::new(),
+            });
+        }
+        // Safe to unwrap, breach for the day was added above
+        let breach = breaches.last_mut().unwrap();
+        breach.end_datetime = end_datetime;
+        breach.duration = breach.duration + (period_end - log.datetime);
+        breach.log_ids.push(log.id.clone());
+    }
+
+    breaches
+        .into_iter()
+        .filter(|breach| breach.duration >= threshold(config))
+        .collect()
+}
+
+fn is_matching(
+    r#type: &TemperatureBreachRowType,
+    existing: &TemperatureBreachRow,
+    breach: &DetectedBreach,
+) -> bool {
+    if is_cumulative(r#type) {
+        return existing.start_datetime.date() == breach.start_datetime.date();
+    }
+
+    let existing_end = existing.end_datetime.unwrap_or(NaiveDateTime::MAX);
+    let breach_end = breach.end_datetime.unwrap_or(NaiveDateTime::MAX);
+    existing.start_datetime <= breach_end && breach.start_datetime <= existing_end
+}
+
+/// Returns saved breach and whether it was created or changed
+fn save_breach(
+    connection: &StorageConnection,
+    sensor: &SensorRow,
+    config: &TemperatureBreachConfigRow,
+    breach: &DetectedBreach,
+    existing: &[TemperatureBreachRow],
+) -> Result<(TemperatureBreachRow, bool), RepositoryError> {
+    let duration_milliseconds = breach
+        .duration
+        .num_milliseconds()
+        .try_into()
+        .unwrap_or(i32::MAX);
+    let matching = existing
+        .iter()
+        .find(|existing| is_matching(&config.r#type, existing, breach));
+
+    let row = match matching {
+        // Acknowledgement and comment are kept
+        Some(existing) => TemperatureBreachRow {
+            start_datetime: breach.start_datetime,
+            end_datetime: breach.end_datetime,
+            duration_milliseconds,
+            ..existing.clone()
+        },
+        None => TemperatureBreachRow {
+            id: uuid(),
+            duration_milliseconds,
+            r#type: config.r#type.clone(),
+            sensor_id: sensor.id.clone(),
+            location_id: sensor.location_id.clone(),
+            store_id: sensor.store_id.clone(),
+            start_datetime: breach.start_datetime,
+            end_datetime: breach.end_datetime,
+            unacknowledged: true,
+            threshold_minimum: config.minimum_temperature,
+            threshold_maximum: config.maximum_temperature,
+            threshold_duration_milliseconds: config.duration_milliseconds,
+            comment: None,
+        },
+    };
+
+    let is_changed = matching != Some(&row);
+    if is_changed {
+        TemperatureBreachRowRepository::new(connection).upsert_one(&row)?;
+        log::info!("Detected temperature breach {:?}", row);
+    }
+
+    Ok((row, is_changed))
+}
+
+/// Out of range logs not yet linked to a breach are linked to `breach_id`
+fn link_logs(
+    connection: &StorageConnection,
+    logs: &[TemperatureLogRow],
+    breach: &DetectedBreach,
+    breach_id: &str,
+) -> Result<(), RepositoryError> {
+    let repo = TemperatureLogRowRepository::new(connection);
+    for log in logs {
+        if log.temperature_breach_id.is_some() || !breach.log_ids.contains(&log.id) {
+            continue;
+        }
+        repo.upsert_one(&TemperatureLogRow {
+            temperature_breach_id: Some(breach_id.to_string()),
+            ..log.clone()
+        })?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use repository::{
+        mock::{mock_store_a, MockData, MockDataInserts},
+        test_db::setup_all_with_data,
+        EqualFilter, SensorFilter, SensorRow, StorageConnection, TemperatureBreachConfigRow,
+        TemperatureBreachFilter, TemperatureBreachRepository, TemperatureBreachRow,
+        TemperatureBreachRowType, TemperatureLogRow, TemperatureLogRowRepository,
+    };
+
+    use super::detect_temperature_breaches;
+    use crate::test_helpers::datetime;
+
+    fn add_log(connection: &StorageConnection, sensor: &SensorRow, time: (u32, u32), temp: f64) {
+        TemperatureLogRowRepository::new(connection)
+            .upsert_one(&TemperatureLogRow {
+                id: format!("log_{}_{}", time.0, time.1),
+                temperature: temp,
+                sensor_id: sensor.id.clone(),
+                store_id: sensor.store_id.clone(),
+                datetime: datetime(time.0, time.1),
+                ..Default::default()
+            })
+            .unwrap();
+    }
+
+    fn breaches(
+        connection: &StorageConnection,
+        r#type: TemperatureBreachRowType,
+    ) -> Vec<TemperatureBreachRow> {
+        TemperatureBreachRepository::new(connection)
+            .query_by_filter(
+                TemperatureBreachFilter::new()
+                    .sensor(SensorFilter::new().id(EqualFilter::equal_to("sensor")))
+                    .r#type(EqualFilter::equal_to_breach_type(&r#type)),
+            )
+            .unwrap()
+            .into_iter()
+            .map(|breach| breach.temperature_breach_row)
+            .collect()
+    }
+
+    #[actix_rt::test]
+    async fn detect_temperature_breaches_incrementally() {
+        let store = mock_store_a();
+        let sensor = SensorRow {
+            id: "sensor".to_string(),
+            serial: "sensor".to_string(),
+            store_id: store.id.clone(),
+            ..Default::default()
+        };
+        let hot_consecutive = TemperatureBreachConfigRow {
+            id: "hot_consecutive".to_string(),
+            duration_milliseconds: 30 * 60 * 1000,
+            r#type: TemperatureBreachRowType::HotConsecutive,
+            is_active: true,
+            store_id: store.id.clone(),
+            minimum_temperature: 2.0,
+            maximum_temperature: 8.0,
+            ..Default::default()
+        };
+        let hot_cumulative = TemperatureBreachConfigRow {
+            id: "hot_cumulative".to_string(),
+            duration_milliseconds: 60 * 60 * 1000,
+            r#type: TemperatureBreachRowType::HotCumulative,
+            ..hot_consecutive.clone()
+        };
+
+        let (_, connection, _, _) = setup_all_with_data(
+            "detect_temperature_breaches_incrementally",
+            MockDataInserts::none().names().stores(),
+            MockData {
+                sensors: vec![sensor.clone()],
+                temperature_breach_configs: vec![hot_consecutive, hot_cumulative],
+                ..Default::default()
+            },
+        )
+        .await;
+
+        // Ongoing breach, hot for 30 minutes
+        add_log(&connection, &sensor, (10, 0), 5.0);
+        add_log(&connection, &sensor, (10, 10), 9.0);
+        add_log(&connection, &sensor, (10, 20), 10.0);
+        add_log(&connection, &sensor, (10, 30), 11.0);
+        add_log(&connection, &sensor, (10, 40), 12.0);
+        let result = detect_temperature_breaches(&connection, &sensor, datetime(10, 0)).unwrap();
+        assert_eq!(result.len(), 1);
+
+        let breach = breaches(&connection, TemperatureBreachRowType::HotConsecutive)
+            .pop()
+            .unwrap();
+        assert_eq!(breach.start_datetime, datetime(10, 10));
+        assert_eq!(breach.end_datetime, None);
+        assert_eq!(breach.duration_milliseconds, 30 * 60 * 1000);
+        assert!(breach.unacknowledged);
+        assert_eq!(breach.threshold_maximum, 8.0);
+        assert_eq!(
+            TemperatureLogRowRepository::new(&connection)
+                .find_one_by_id("log_10_10")
+                .unwrap()
+                .unwrap()
+                .temperature_breach_id,
+            Some(breach.id.clone())
+        );
+        // Not enough for cumulative breach (last log doesn't count)
+        assert_eq!(
+            breaches(&connection, TemperatureBreachRowType::HotCumulative),
+            vec![]
+        );
+
+        // Back in range, breach is closed
+        add_log(&connection, &sensor, (10, 50), 6.0);
+        detect_temperature_breaches(&connection, &sensor, datetime(10, 50)).unwrap();
+        let closed = breaches(&connection, TemperatureBreachRowType::HotConsecutive);
+        assert_eq!(closed.len(), 1);
+        assert_eq!(closed[0].id, breach.id);
+        assert_eq!(closed[0].end_datetime, Some(datetime(10, 50)));
+        assert_eq!(closed[0].duration_milliseconds, 40 * 60 * 1000);
+
+        // Processing same logs again doesn't change anything
+        let result = detect_temperature_breaches(&connection, &sensor, datetime(10, 0)).unwrap();
+        assert_eq!(result, vec![]);
+
+        // Late log extends start of the breach
+        add_log(&connection, &sensor, (10, 5), 9.0);
+        detect_temperature_breaches(&connection, &sensor, datetime(10, 5)).unwrap();
+        let extended = breaches(&connection, TemperatureBreachRowType::HotConsecutive);
+        assert_eq!(extended.len(), 1);
+        assert_eq!(extended[0].id, breach.id);
+        assert_eq!(extended[0].start_datetime, datetime(10, 5));
+        assert_eq!(extended[0].duration_milliseconds, 45 * 60 * 1000);
+
+        // Short hot periods add up to cumulative breach (45 + 10 + 10 minutes)
+        add_log(&connection, &sensor, (11, 0), 9.0);
+        add_log(&connection, &sensor, (11, 10), 5.0);
+        add_log(&connection, &sensor, (11, 20), 9.0);
+        add_log(&connection, &sensor, (11, 30), 5.0);
+        detect_temperature_breaches(&connection, &sensor, datetime(11, 0)).unwrap();
+        assert_eq!(
+            breaches(&connection, TemperatureBreachRowType::HotConsecutive).len(),
+            1
+        );
+        let cumulative = breaches(&connection, TemperatureBreachRowType::HotCumulative);
+        assert_eq!(cumulative.len(), 1);
+        assert_eq!(cumulative[0].start_datetime, datetime(10, 5));
+        assert_eq!(cumulative[0].end_datetime, Some(datetime(11, 30)));
+        assert_eq!(cumulative[0].duration_milliseconds, 65 * 60 * 1000);
+    }
+}
@@ -0,0 +1,585 @@
+use chrono::Utc;
+use repository::{
+    location_movement::{LocationMovementFilter, LocationMovementRepository},
+    ActivityLogType, CurrencyFilter, CurrencyRepository, DatetimeFilter, EqualFilter,
+    InvoiceLineRow, InvoiceLineRowRepository, InvoiceLineRowType, InvoiceRow, InvoiceRowRepository,
+    InvoiceRowStatus, InvoiceRowType, NameRowRepository, NumberRowType, RepositoryError, StockLine,
+    StockLineFilter, StockLineRepository, StockLineRow, StockLineRowRepository, StorageConnection,
+    TemperatureBreachRow, TemperatureBreachStockImpactRow,
+    TemperatureBreachStockImpactRowRepository, TemperatureBreachStockOutcome, VvmStatus,
+};
+use util::{
+    constants::{COLD_CHAIN_BREACH_REASON_ID, INVENTORY_ADJUSTMENT_NAME_CODE},
+    uuid::uuid,
+};
+
+use super::validate::check_temperature_breach_exists;
+use crate::{
+    activity_log::activity_log_entry, number::next_number, service_provider::ServiceContext,
+};
+
+/// Stock line that was in the breached location during the breach
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemperatureBreachStockLine {
+    pub stock_line: StockLine,
+    /// None until the stock line is assessed
+    pub impact: Option<TemperatureBreachStockImpactRow>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum GetTemperatureBreachStockLinesError {
+    TemperatureBreachDoesNotExist,
+    TemperatureBreachDoesNotBelongToCurrentStore,
+    DatabaseError(RepositoryError),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AssessTemperatureBreachStockLine {
+    pub temperature_breach_id: String,
+    pub stock_line_id: String,
+    pub outcome: TemperatureBreachStockOutcome,
+    pub vvm_status: Option<VvmStatus>,
+    /// Number of packs to discard, defaults to all available packs
+    pub number_of_packs: Option<f64>,
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum AssessTemperatureBreachStockLineError {
+    TemperatureBreachDoesNotExist,
+    TemperatureBreachDoesNotBelongToCurrentStore,
+    StockLineDoesNotExist,
+    StockLineNotInBreachedLocation,
+    /// Discard can't be reverted, inventory reduction was already created
+    StockLineAlreadyDiscarded,
+    /// Vials past VVM discard point can only be discarded
+    VvmDiscardPointReached,
+    NumberOfPacksMustBePositive,
+    NumberOfPacksAboveAvailable,
+    InternalError(String),
+    DatabaseError(RepositoryError),
+}
+
+/// Stock lines in breach location during the breach, from location movements of the store.
+/// Includes stock lines that were already assessed (and possibly discarded)
+pub fn get_temperature_breach_stock_lines(
+    ctx: &ServiceContext,
+    temperature_breach_id: &str,
+) -> Result<Vec<TemperatureBreachStockLine>, GetTemperatureBreachStockLinesError> {
+    use GetTemperatureBreachStockLinesError as Error;
+
+    let breach = check_temperature_breach_exists(temperature_breach_id, &ctx.connection)?
+        .ok_or(Error::TemperatureBreachDoesNotExist)?;
+    if breach.store_id != ctx.store_id {
+        return Err(Error::TemperatureBreachDoesNotBelongToCurrentStore);
+    }
+
+    let stock_line_ids = exposed_stock_line_ids(&ctx.connection, &breach)?;
+    let impacts = TemperatureBreachStockImpactRowRepository::new(&ctx.connection)
+        .find_many_by_temperature_breach_id(&breach.id)?;
+
+    let result = StockLineRepository::new(&ctx.connection)
+        .query_by_filter(
+            StockLineFilter::new().id(EqualFilter::equal_any(stock_line_ids)),
+            Some(ctx.store_id.clone()),
+        )?
+        .into_iter()
+        .map(|stock_line| {
+            let impact = impacts
+                .iter()
+                .find(|impact| impact.stock_line_id == stock_line.stock_line_row.id)
+                .cloned();
+            TemperatureBreachStockLine { stock_line, impact }
+        })
+        .collect();
+
+    Ok(result)
+}
+
+/// Records outcome of stock line assessment: usable stock is released from quarantine,
+/// quarantined stock is put on hold and discarded stock is removed with an inventory reduction
+/// (with cold chain breach reason). Assessment can be changed until stock is discarded
+pub fn assess_temperature_breach_stock_line(
+    ctx: &ServiceContext,
+    input: AssessTemperatureBreachStockLine,
+) -> Result<TemperatureBreachStockLine, AssessTemperatureBreachStockLineError> {
+    let result = ctx
+        .connection
+        .transaction_sync(
+            |connection| -> Result<_, AssessTemperatureBreachStockLineError> {
+                let (stock_line, existing) = validate(connection, &ctx.store_id, &input)?;
+                let GenerateResult {
+                    impact,
+                    stock_line,
+                    inventory_reduction,
+                } = generate(ctx, input, stock_line, existing)?;
+
+                if let Some((invoice, invoice_line)) = inventory_reduction {
+                    InvoiceRowRepository::new(connection).upsert_one(&invoice)?;
+                    InvoiceLineRowRepository::new(connection).upsert_one(&invoice_line)?;
+                }
+                StockLineRowRepository::new(connection).upsert_one(&stock_line.new)?;
+                TemperatureBreachStockImpactRowRepository::new(connection).upsert_one(&impact)?;
+
+                if stock_line.existing.on_hold != stock_line.new.on_hold {
+                    let log_type = if stock_line.new.on_hold {
+                        ActivityLogType::StockOnHold
+                    } else {
+                        ActivityLogType::StockOffHold
+                    };
+                    activity_log_entry(ctx, log_type, Some(stock_line.new.id.clone()), None, None)?;
+                }
+
+                let stock_line = StockLineRepository::new(connection)
+                    .query_by_filter(
+                        StockLineFilter::new().id(EqualFilter::equal_to(&stock_line.new.id)),
+                        Some(ctx.store_id.clone()),
+                    )?
+                    .pop()
+                    .ok_or(RepositoryError::NotFound)?;
+
+                Ok(TemperatureBreachStockLine {
+                    stock_line,
+                    impact: Some(impact),
+                })
+            },
+        )
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(result)
+}
+
+/// Stock lines with location movement into breach location before the end of the breach, and out
+/// of it after the start of the breach (or still in the location)
+fn exposed_stock_line_ids(
+    connection: &StorageConnection,
+    breach: &TemperatureBreachRow,
+) -> Result<Vec<String>, RepositoryError> {
+    let Some(location_id) = &breach.location_id else {
+        return Ok(Vec::new());
+    };
+    let end_datetime = breach
+        .end_datetime
+        .unwrap_or_else(|| Utc::now().naive_utc());
+
+    let movements = LocationMovementRepository::new(connection).query_by_filter(
+        LocationMovementFilter::new()
+            .store_id(EqualFilter::equal_to(&breach.store_id))
+            .location_id(EqualFilter::equal_to(location_id))
+            .enter_datetime(DatetimeFilter::before_or_equal_to(end_datetime)),
+    )?;
+
+    let mut stock_line_ids = Vec::new();
+    for movement in movements.into_iter().map(|m| m.location_movement_row) {
+        let is_exposed = movement
+            .exit_datetime
+            .map_or(true, |exit_datetime| exit_datetime >= breach.start_datetime);
+        if is_exposed && !stock_line_ids.contains(&movement.stock_line_id) {
+            stock_line_ids.push(movement.stock_line_id);
+        }
+    }
+
+    Ok(stock_line_ids)
+}
+
+fn validate(
+    connection: &StorageConnection,
+    store_id: &str,
+    input: &AssessTemperatureBreachStockLine,
+) -> Result<
+    (StockLineRow, Option<TemperatureBreachStockImpactRow>),
+    AssessTemperatureBreachStockLineError,
+> {
+    use AssessTemperatureBreachStockLineError as Error;
+
+    let breach = check_temperature_breach_exists(&input.temperature_breach_id, connection)?
+        .ok_or(Error::TemperatureBreachDoesNotExist)?;
+    if breach.store_id != store_id {
+        return Err(Error::TemperatureBreachDoesNotBelongToCurrentStore);
+    }
+
+    let stock_line = StockLineRowRepository::new(connection)
+        .find_one_by_id_option(&input.stock_line_id)?
+        .filter(|stock_line| stock_line.store_id == store_id)
+        .ok_or(Error::StockLineDoesNotExist)?;
+    if !exposed_stock_line_ids(connection, &breach)?.contains(&stock_line.id) {
+        return Err(Error::StockLineNotInBreachedLocation);
+    }
+
+    let existing = TemperatureBreachStockImpactRowRepository::new(connection)
+        .find_one_by_stock_line(&breach.id, &stock_line.id)?;
+    if existing.as_ref().map_or(false, |impact| {
+        impact.outcome == TemperatureBreachStockOutcome::Discard
+    }) {
+        return Err(Error::StockLineAlreadyDiscarded);
+    }
+
+    let is_discard_point_reached = input
+        .vvm_status
+        .as_ref()
+        .map_or(false, VvmStatus::is_discard_point_reached);
+    if is_discard_point_reached && input.outcome != TemperatureBreachStockOutcome::Discard {
+        return Err(Error::VvmDiscardPointReached);
+    }
+
+    if input.outcome == TemperatureBreachStockOutcome::Discard {
+        let number_of_packs = input
+            .number_of_packs
+            .unwrap_or(stock_line.available_number_of_packs);
+        if number_of_packs <= 0.0 {
+            return Err(Error::NumberOfPacksMustBePositive);
+        }
+        if number_of_packs > stock_line.available_number_of_packs {
+            return Err(Error::NumberOfPacksAboveAvailable);
+        }
+    }
+
+    Ok((stock_line, existing))
+}
+
+struct StockLineUpdate {
+    existing: StockLineRow,
+    new: StockLineRow,
+}
+
+struct GenerateResult {
+    impact: TemperatureBreachStockImpactRow,
+    stock_line: StockLineUpdate,
+    inventory_reduction: Option<(InvoiceRow, InvoiceLineRow)>,
+}
+
+fn generate(
+    ctx: &ServiceContext,
+    AssessTemperatureBreachStockLine {
+        temperature_breach_id,
+        stock_line_id,
+        outcome,
+        vvm_status,
+        number_of_packs,
+        comment,
+    }: AssessTemperatureBreachStockLine,
+    existing_stock_line: StockLineRow,
+    existing: Option<TemperatureBreachStockImpactRow>,
+) -> Result<GenerateResult, AssessTemperatureBreachStockLineError> {
+    let now = Utc::now().naive_utc();
+    let was_quarantined = existing.as_ref().map_or(false, |impact| {
+        impact.outcome == TemperatureBreachStockOutcome::Quarantine
+    });
+
+    let mut stock_line = existing_stock_line.clone();
+    let mut inventory_reduction = None;
+    let mut discarded_number_of_packs = 0.0;
+    match outcome {
+        TemperatureBreachStockOutcome::Usable => {
+            if was_quarantined {
+                stock_line.on_hold = false;
+            }
+        }
+        TemperatureBreachStockOutcome::Quarantine => stock_line.on_hold = true,
+        TemperatureBreachStockOutcome::Discard => {
+            discarded_number_of_packs =
+                number_of_packs.unwrap_or(stock_line.available_number_of_packs);
+            stock_line.available_number_of_packs -= discarded_number_of_packs;
+            stock_line.total_number_of_packs -= discarded_number_of_packs;
+            inventory_reduction = Some(generate_inventory_reduction(
+                ctx,
+                &existing_stock_line,
+                discarded_number_of_packs,
+                comment.clone(),
+            )?);
+        }
+    }
+
+    let impact = TemperatureBreachStockImpactRow {
+        id: existing.map(|impact| impact.id).unwrap_or_else(uuid),
+        temperature_breach_id,
+        stock_line_id,
+        store_id: ctx.store_id.clone(),
+        outcome,
+        vvm_status,
+        number_of_packs: discarded_number_of_packs,
+        inventory_reduction_id: inventory_reduction
+            .as_ref()
+            .map(|(invoice, _)| invoice.id.clone()),
+        comment,
+        user_id: ctx.user_id.clone(),
+        assessed_datetime: now,
+    };
+
+    Ok(GenerateResult {
+        impact,
+        stock_line: StockLineUpdate {
+            existing: existing_stock_line,
+            new: stock_line,
+        },
+        inventory_reduction,
+    })
+}
+
+fn generate_inventory_reduction(
+    ctx: &ServiceContext,
+    stock_line: &StockLineRow,
+    number_of_packs: f64,
+    comment: Option<String>,
+) -> Result<(InvoiceRow, InvoiceLineRow), AssessTemperatureBreachStockLineError> {
+    use AssessTemperatureBreachStockLineError as Error;
+    let connection = &ctx.connection;
+    let now = Utc::now().naive_utc();
+
+    let inventory_adjustment_name = NameRowRepository::new(connection)
+        .find_one_by_code(INVENTORY_ADJUSTMENT_NAME_CODE)?
+        .ok_or(Error::InternalError(
+            "Missing inventory adjustment name".to_string(),
+        ))?;
+    let currency = CurrencyRepository::new(connection)
+        .query_by_filter(CurrencyFilter::new().is_home_currency(true))?
+        .pop()
+        .ok_or(Error::DatabaseError(RepositoryError::NotFound))?;
+    let item = StockLineRepository::new(connection)
+        .query_by_filter(
+            StockLineFilter::new().id(EqualFilter::equal_to(&stock_line.id)),
+            Some(ctx.store_id.clone()),
+        )?
+        .pop()
+        .ok_or(Error::StockLineDoesNotExist)?
+        .item_row;
+
+    let invoice = InvoiceRow {
+        id: uuid(),
+        name_link_id: inventory_adjustment_name.id,
+        name_store_id: None,
+        store_id: ctx.store_id.clone(),
+        user_id: Some(ctx.user_id.clone()),
+        invoice_number: next_number(
+            connection,
+            &NumberRowType::InventoryReduction,
+            &ctx.store_id,
+        )?,
+        r#type: InvoiceRowType::InventoryReduction,
+        status: InvoiceRowStatus::Verified,
+        on_hold: false,
+        comment,
+        their_reference: None,
+        transport_reference: None,
+        created_datetime: now,
+        allocated_datetime: None,
+        picked_datetime: None,
+        shipped_datetime: None,
+        delivered_datetime: None,
+        verified_datetime: Some(now),
+        colour: None,
+        requisition_id: None,
+        linked_invoice_id: None,
+        tax: None,
+        currency_id: Some(currency.currency_row.id),
+        currency_rate: 1.0,
+        clinician_link_id: None,
+        original_shipment_id: None,
+    };
+
+    let invoice_line = InvoiceLineRow {
+        id: uuid(),
+        invoice_id: invoice.id.clone(),
+        item_link_id: stock_line.item_link_id.clone(),
+        item_name: item.name,
+        item_code: item.code,
+        stock_line_id: Some(stock_line.id.clone()),
+        location_id: stock_line.location_id.clone(),
+        batch: stock_line.batch.clone(),
+        expiry_date: stock_line.expiry_date,
+        pack_size: stock_line.pack_size,
+        cost_price_per_pack: stock_line.cost_price_per_pack,
+        sell_price_per_pack: stock_line.sell_price_per_pack,
+        total_before_tax: 0.0,
+        total_after_tax: 0.0,
+        tax: None,
+        r#type: InvoiceLineRowType::StockOut,
+        number_of_packs,
+        note: stock_line.note.clone(),
+        inventory_adjustment_reason_id: Some(COLD_CHAIN_BREACH_REASON_ID.to_string()),
+        return_reason_id: None,
+        foreign_currency_price_before_tax: None,
+    };
+
+    Ok((invoice, invoice_line))
+}
+
+impl From<RepositoryError> for GetTemperatureBreachStockLinesError {
+    fn from(error: RepositoryError) -> Self {
+        GetTemperatureBreachStockLinesError::DatabaseError(error)
+    }
+}
+
+impl From<RepositoryError> for AssessTemperatureBreachStockLineError {
+    fn from(error: RepositoryError) -> Self {
+        AssessTemperatureBreachStockLineError::DatabaseError(error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use repository::{
+        mock::{
+            mock_location_1, mock_stock_line_a, mock_stock_line_b, mock_store_a,
+            mock_temperature_breach_1, mock_user_account_a, MockDataInserts,
+        },
+        test_db::setup_all,
+        InvoiceLineRowRepository, InvoiceRowRepository, InvoiceRowType, LocationMovementRow,
+        LocationMovementRowRepository, TemperatureBreachRow, TemperatureBreachRowRepository,
+        TemperatureBreachStockOutcome, VvmStatus,
+    };
+    use util::constants::COLD_CHAIN_BREACH_REASON_ID;
+
+    use super::{AssessTemperatureBreachStockLine, AssessTemperatureBreachStockLineError};
+    use crate::{service_provider::ServiceProvider, test_helpers::datetime};
+
+    #[actix_rt::test]
+    async fn temperature_breach_stock_impact() {
+        let (_, connection, connection_manager, _) =
+            setup_all("temperature_breach_stock_impact", MockDataInserts::all()).await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let ctx = service_provider
+            .context(mock_store_a().id, mock_user_account_a().id)
+            .unwrap();
+        let service = service_provider.cold_chain_service;
+
+        let breach = TemperatureBreachRow {
+            id: "breach".to_string(),
+            location_id: Some(mock_location_1().id),
+            start_datetime: datetime(10, 0),
+            end_datetime: Some(datetime(12, 0)),
+            ..mock_temperature_breach_1()
+        };
+        TemperatureBreachRowRepository::new(&connection)
+            .upsert_one(&breach)
+            .unwrap();
+        let movement_repo = LocationMovementRowRepository::new(&connection);
+        // Still in the location
+        movement_repo
+            .upsert_one(&LocationMovementRow {
+                id: "movement_a".to_string(),
+                store_id: mock_store_a().id,
+                stock_line_id: mock_stock_line_a().id,
+                location_id: Some(mock_location_1().id),
+                enter_datetime: Some(datetime(9, 0)),
+                exit_datetime: None,
+            })
+            .unwrap();
+        // Moved out before the breach
+        movement_repo
+            .upsert_one(&LocationMovementRow {
+                id: "movement_b".to_string(),
+                store_id: mock_store_a().id,
+                stock_line_id: mock_stock_line_b().id,
+                location_id: Some(mock_location_1().id),
+                enter_datetime: Some(datetime(8, 0)),
+                exit_datetime: Some(datetime(9, 0)),
+            })
+            .unwrap();
+
+        let stock_lines = service
+            .get_temperature_breach_stock_lines(&ctx, &breach.id)
+            .unwrap();
+        assert_eq!(stock_lines.len(), 1);
+        assert_eq!(
+            stock_lines[0].stock_line.stock_line_row.id,
+            mock_stock_line_a().id
+        );
+        assert_eq!(stock_lines[0].impact, None);
+
+        let input = AssessTemperatureBreachStockLine {
+            temperature_breach_id: breach.id.clone(),
+            stock_line_id: mock_stock_line_a().id,
+            ..Default::default()
+        };
+
+        // Errors
+        assert_eq!(
+            service.assess_temperature_breach_stock_line(
+                &ctx,
+                AssessTemperatureBreachStockLine {
+                    stock_line_id: mock_stock_line_b().id,
+                    ..input.clone()
+                }
+            ),
+            Err(AssessTemperatureBreachStockLineError::StockLineNotInBreachedLocation)
+        );
+        assert_eq!(
+            service.assess_temperature_breach_stock_line(
+                &ctx,
+                AssessTemperatureBreachStockLine {
+                    vvm_status: Some(VvmStatus::Stage3),
+                    ..input.clone()
+                }
+            ),
+            Err(AssessTemperatureBreachStockLineError::VvmDiscardPointReached)
+        );
+        assert_eq!(
+            service.assess_temperature_breach_stock_line(
+                &ctx,
+                AssessTemperatureBreachStockLine {
+                    outcome: TemperatureBreachStockOutcome::Discard,
+                    number_of_packs: Some(31.0),
+                    ..input.clone()
+                }
+            ),
+            Err(AssessTemperatureBreachStockLineError::NumberOfPacksAboveAvailable)
+        );
+
+        // Quarantine
+        let quarantined = service
+            .assess_temperature_breach_stock_line(
+                &ctx,
+                AssessTemperatureBreachStockLine {
+                    outcome: TemperatureBreachStockOutcome::Quarantine,
+                    vvm_status: Some(VvmStatus::Stage2),
+                    ..input.clone()
+                },
+            )
+            .unwrap();
+        assert!(quarantined.stock_line.stock_line_row.on_hold);
+        let impact = quarantined.impact.unwrap();
+        assert_eq!(impact.outcome, TemperatureBreachStockOutcome::Quarantine);
+        assert_eq!(impact.inventory_reduction_id, None);
+
+        // Discard
+        let discarded = service
+            .assess_temperature_breach_stock_line(
+                &ctx,
+                AssessTemperatureBreachStockLine {
+                    outcome: TemperatureBreachStockOutcome::Discard,
+                    number_of_packs: Some(10.0),
+                    ..input.clone()
+                },
+            )
+            .unwrap();
+        let stock_line = discarded.stock_line.stock_line_row;
+        assert_eq!(stock_line.available_number_of_packs, 20.0);
+        assert_eq!(stock_line.total_number_of_packs, 30.0);
+        let discarded_impact = discarded.impact.unwrap();
+        assert_eq!(discarded_impact.id, impact.id);
+        assert_eq!(discarded_impact.number_of_packs, 10.0);
+
+        let reduction_id = discarded_impact.inventory_reduction_id.unwrap();
+        let reduction = InvoiceRowRepository::new(&connection)
+            .find_one_by_id(&reduction_id)
+            .unwrap();
+        assert_eq!(reduction.r#type, InvoiceRowType::InventoryReduction);
+        let reduction_lines = InvoiceLineRowRepository::new(&connection)
+            .find_many_by_invoice_id(&reduction_id)
+            .unwrap();
+        assert_eq!(reduction_lines.len(), 1);
+        assert_eq!(reduction_lines[0].number_of_packs, 10.0);
+        assert_eq!(
+            reduction_lines[0].inventory_adjustment_reason_id,
+            Some(COLD_CHAIN_BREACH_REASON_ID.to_string())
+        );
+
+        // Discard can't be changed
+        assert_eq!(
+            service.assess_temperature_breach_stock_line(&ctx, input),
+            Err(AssessTemperatureBreachStockLineError::StockLineAlreadyDiscarded)
+        );
+    }
+}
@@ -0,0 +1,460 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use chrono::{Duration, NaiveDateTime};
+use repository::{
+    temperature_breach::{TemperatureBreachFilter, TemperatureBreachRepository},
+    temperature_log::{
+        TemperatureLogFilter, TemperatureLogRepository, TemperatureLogSort, TemperatureLogSortField,
+    },
+    DatetimeFilter, EqualFilter, Pagination, RepositoryError, SensorFilter, SensorRowRepository,
+    TemperatureBreachRowType, TemperatureLogRow,
+};
+
+use crate::service_provider::ServiceContext;
+
+/// Activation energy used for mean kinetic temperature (kJ/mol), typical value for vaccines and
+/// other pharmaceutical products
+pub const DEFAULT_ACTIVATION_ENERGY: f64 = 83.144;
+/// Recommended storage range of vaccines, used when range is not specified
+pub const DEFAULT_MINIMUM_TEMPERATURE: f64 = 2.0;
+pub const DEFAULT_MAXIMUM_TEMPERATURE: f64 = 8.0;
+/// kJ/(mol K)
+const GAS_CONSTANT: f64 = 0.0083144;
+const KELVIN_OFFSET: f64 = 273.15;
+
+#[derive(Debug, PartialEq)]
+pub enum TemperatureKpiError {
+    ToDateTimeMustBeAfterFromDatetime,
+    MaximumTemperatureMustBeAboveMinimum,
+    DatabaseError(RepositoryError),
+}
+
+impl From<RepositoryError> for TemperatureKpiError {
+    fn from(error: RepositoryError) -> Self {
+        TemperatureKpiError::DatabaseError(error)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TemperatureKpiInput {
+    pub from_datetime: NaiveDateTime,
+    pub to_datetime: NaiveDateTime,
+    /// Logs are always filtered by store and date range
+    pub filter: Option<TemperatureLogFilter>,
+    /// Range for time in range, defaults to 2°C to 8°C
+    pub minimum_temperature: Option<f64>,
+    pub maximum_temperature: Option<f64>,
+    /// kJ/mol, defaults to `DEFAULT_ACTIVATION_ENERGY`
+    pub activation_energy: Option<f64>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TemperatureBreachKpi {
+    pub r#type: TemperatureBreachRowType,
+    pub count: u32,
+    /// Part of the breaches within the date range
+    pub duration_milliseconds: i64,
+}
+
+/// Metrics of the logs of a sensor while it was in a location
+#[derive(Clone, Debug, PartialEq)]
+pub struct TemperatureKpi {
+    pub sensor_id: String,
+    pub location_id: Option<String>,
+    pub number_of_logs: u32,
+    pub mean_kinetic_temperature: f64,
+    pub minimum_temperature: f64,
+    pub maximum_temperature: f64,
+    /// Share of the logged time with temperature in range. Each log covers the time until the
+    /// next log, at most one log interval of the sensor
+    pub time_in_range_percentage: f64,
+    pub breaches: Vec<TemperatureBreachKpi>,
+    /// Log intervals of the sensor in the date range, None when sensor has no log interval
+    pub expected_number_of_intervals: Option<u32>,
+    /// Log intervals without any log
+    pub missing_intervals: Option<u32>,
+    pub completeness_percentage: Option<f64>,
+}
+
+pub fn get_temperature_kpis(
+    ctx: &ServiceContext,
+    input: TemperatureKpiInput,
+) -> Result<Vec<TemperatureKpi>, TemperatureKpiError> {
+    let TemperatureKpiInput {
+        from_datetime,
+        to_datetime,
+        filter,
+        minimum_temperature,
+        maximum_temperature,
+        activation_energy,
+    } = input;
+    let minimum_temperature = minimum_temperature.unwrap_or(DEFAULT_MINIMUM_TEMPERATURE);
+    let maximum_temperature = maximum_temperature.unwrap_or(DEFAULT_MAXIMUM_TEMPERATURE);
+    let activation_energy = activation_energy.unwrap_or(DEFAULT_ACTIVATION_ENERGY);
+
+    if from_datetime >= to_datetime {
+        return Err(TemperatureKpiError::ToDateTimeMustBeAfterFromDatetime);
+    }
+    if minimum_temperature >= maximum_temperature {
+        return Err(TemperatureKpiError::MaximumTemperatureMustBeAboveMinimum);
+    }
+
+    let connection = &ctx.connection;
+    let filter = filter
+        .unwrap_or_default()
+        .store_id(EqualFilter::equal_to(&ctx.store_id))
+        .datetime(DatetimeFilter::date_range(from_datetime, to_datetime));
+    let logs = TemperatureLogRepository::new(connection).query(
+        Pagination::all(),
+        Some(filter),
+        Some(TemperatureLogSort {
+            key: TemperatureLogSortField::Datetime,
+            desc: Some(false),
+        }),
+    )?;
+
+    // Sorted by sensor and location, logs stay sorted by datetime
+    let mut groups: BTreeMap<(String, Option<String>), Vec<TemperatureLogRow>> = BTreeMap::new();
+    for log in logs {
+        let log = log.temperature_log_row;
+        groups
+            .entry((log.sensor_id.clone(), log.location_id.clone()))
+            .or_default()
+            .push(log);
+    }
+    if groups.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let sensor_ids: Vec<String> = groups
+        .keys()
+        .map(|(sensor_id, _)| sensor_id.clone())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    let log_intervals: HashMap<String, i32> = SensorRowRepository::new(connection)
+        .find_many_by_id(&sensor_ids)?
+        .into_iter()
+        .filter_map(|sensor| Some((sensor.id, sensor.log_interval.filter(|i| *i > 0)?)))
+        .collect();
+
+    let breaches = TemperatureBreachRepository::new(connection).query_by_filter(
+        TemperatureBreachFilter::new()
+            .store_id(EqualFilter::equal_to(&ctx.store_id))
+            .sensor(SensorFilter::new().id(EqualFilter::equal_any(sensor_ids)))
+            .start_datetime(DatetimeFilter::before_or_equal_to(to_datetime)),
+    )?;
+
+    let kpis = groups
+        .into_iter()
+        .map(|((sensor_id, location_id), logs)| {
+            let log_interval = log_intervals
+                .get(&sensor_id)
+                .map(|seconds| Duration::seconds(*seconds as i64));
+
+            let mut breach_kpis: Vec<TemperatureBreachKpi> = Vec::new();
+            for breach in &breaches {
+                let breach = &breach.temperature_breach_row;
+                if breach.sensor_id != sensor_id || breach.location_id != location_id {
+                    continue;
+                }
+                let start = breach.start_datetime.max(from_datetime);
+                let end = breach.end_datetime.unwrap_or(to_datetime).min(to_datetime);
+                if end < start {
+                    continue;
+                }
+                let duration_milliseconds = (end - start).num_milliseconds();
+                match breach_kpis
+                    .iter_mut()
+                    .find(|kpi| kpi.r#type == breach.r#type)
+                {
+                    Some(kpi) => {
+                        kpi.count += 1;
+                        kpi.duration_milliseconds += duration_milliseconds;
+                    }
+                    None => breach_kpis.push(TemperatureBreachKpi {
+                        r#type: breach.r#type.clone(),
+                        count: 1,
+                        duration_milliseconds,
+                    }),
+                }
+            }
+
+            let (expected_number_of_intervals, missing_intervals, completeness_percentage) =
+                match log_interval {
+                    Some(log_interval) => {
+                        let (expected, missing) = count_missing_intervals(
+                            &logs,
+                            from_datetime,
+                            to_datetime,
+                            log_interval,
+                        );
+                        let completeness = if expected > 0 {
+                            (expected - missing) as f64 / expected as f64 * 100.0
+                        } else {
+                            100.0
+                        };
+                        (Some(expected), Some(missing), Some(completeness))
+                    }
+                    None => (None, None, None),
+                };
+
+            TemperatureKpi {
+                number_of_logs: logs.len() as u32,
+                mean_kinetic_temperature: mean_kinetic_temperature(&logs, activation_energy),
+                minimum_temperature: logs
+                    .iter()
+                    .map(|log| log.temperature)
+                    .fold(f64::INFINITY, f64::min),
+                maximum_temperature: logs
+                    .iter()
+                    .map(|log| log.temperature)
+                    .fold(f64::NEG_INFINITY, f64::max),
+                time_in_range_percentage: time_in_range_percentage(
+                    &logs,
+                    to_datetime,
+                    log_interval,
+                    minimum_temperature,
+                    maximum_temperature,
+                ),
+                breaches: breach_kpis,
+                expected_number_of_intervals,
+                missing_intervals,
+                completeness_percentage,
+                sensor_id,
+                location_id,
+            }
+        })
+        .collect();
+
+    Ok(kpis)
+}
+
+/// MKT = (ΔH/R) / -ln(Σ e^(-ΔH/(R·T)) / n) in Kelvin, converted to °C. Logs must not be empty
+pub(crate) fn mean_kinetic_temperature(logs: &[TemperatureLogRow], activation_energy: f64) -> f64 {
+    let ratio = activation_energy / GAS_CONSTANT;
+    let mean = logs
+        .iter()
+        .map(|log| (-ratio / (log.temperature + KELVIN_OFFSET)).exp())
+        .sum::<f64>()
+        / logs.len() as f64;
+
+    ratio / -mean.ln() - KELVIN_OFFSET
+}
+
+fn time_in_range_percentage(
+    logs: &[TemperatureLogRow],
+    to_datetime: NaiveDateTime,
+    log_interval: Option<Duration>,
+    minimum_temperature: f64,
+    maximum_temperature: f64,
+) -> f64 {
+    let mut total = Duration::zero();
+    let mut in_range = Duration::zero();
+    for (index, log) in logs.iter().enumerate() {
+        let until = logs
+            .get(index + 1)
+            .map(|next| next.datetime)
+            .unwrap_or(to_datetime);
+        let mut duration = until - log.datetime;
+        if let Some(log_interval) = log_interval {
+            duration = duration.min(log_interval);
+        }
+
+        total = total + duration;
+        if log.temperature >= minimum_temperature && log.temperature <= maximum_temperature {
+            in_range = in_range + duration;
+        }
+    }
+
+    // Single log at the end of the range
+    if total.is_zero() {
+        let is_in_range = logs.iter().all(|log| {
+            log.temperature >= minimum_temperature && log.temperature <= maximum_temperature
+        });
+        return if is_in_range { 100.0 } else { 0.0 };
+    }
+
+    in_range.num_milliseconds() as f64 / total.num_milliseconds() as f64 * 100.0
+}
+
+/// Returns expected number of log intervals in the range and the number of intervals without logs
+fn count_missing_intervals(
+    logs: &[TemperatureLogRow],
+    from_datetime: NaiveDateTime,
+    to_datetime: NaiveDateTime,
+    log_interval: Duration,
+) -> (u32, u32) {
+    let interval_seconds = log_interval.num_seconds();
+    let expected = ((to_datetime - from_datetime).num_seconds() / interval_seconds) as u32;
+    let logged: HashSet<i64> = logs
+        .iter()
+        .map(|log| (log.datetime - from_datetime).num_seconds() / interval_seconds)
+        .filter(|interval| *interval < expected as i64)
+        .collect();
+
+    (expected, expected - logged.len() as u32)
+}
+
+#[cfg(test)]
+mod test {
+    use repository::{
+        mock::{mock_store_a, MockData, MockDataInserts},
+        LocationRow, SensorRow, TemperatureBreachRow, TemperatureBreachRowType, TemperatureLogRow,
+    };
+
+    use super::{
+        mean_kinetic_temperature, TemperatureBreachKpi, TemperatureKpiError, TemperatureKpiInput,
+    };
+    use crate::test_helpers::{
+        datetime, setup_all_with_data_and_service_provider, ServiceTestContext,
+    };
+
+    fn log(id: &str, time: (u32, u32), temperature: f64, location_id: &str) -> TemperatureLogRow {
+        TemperatureLogRow {
+            id: id.to_string(),
+            temperature,
+            sensor_id: "sensor".to_string(),
+            location_id: Some(location_id.to_string()),
+            store_id: mock_store_a().id,
+            datetime: datetime(time.0, time.1),
+            temperature_breach_id: None,
+        }
+    }
+
+    #[test]
+    fn mean_kinetic_temperature_is_weighted_to_higher_temperatures() {
+        let logs = vec![
+            log("log1", (10, 0), 5.0, "fridge"),
+            log("log2", (10, 10), 5.0, "fridge"),
+        ];
+        assert!((mean_kinetic_temperature(&logs, 83.144) - 5.0).abs() < 1e-9);
+
+        let logs = vec![
+            log("log1", (10, 0), 2.0, "fridge"),
+            log("log2", (10, 10), 12.0, "fridge"),
+        ];
+        let mkt = mean_kinetic_temperature(&logs, 83.144);
+        // Above arithmetic mean of 7°C
+        assert!(mkt > 7.0 && mkt < 12.0, "{}", mkt);
+        assert!((mkt - 8.41).abs() < 0.01, "{}", mkt);
+    }
+
+    #[actix_rt::test]
+    async fn temperature_kpis() {
+        let store = mock_store_a();
+        let fridge = LocationRow {
+            id: "fridge".to_string(),
+            store_id: store.id.clone(),
+            ..Default::default()
+        };
+        let freezer = LocationRow {
+            id: "freezer".to_string(),
+            ..fridge.clone()
+        };
+        let sensor = SensorRow {
+            id: "sensor".to_string(),
+            serial: "sensor".to_string(),
+            store_id: store.id.clone(),
+            log_interval: Some(600),
+            ..Default::default()
+        };
+        let breach = TemperatureBreachRow {
+            id: "breach".to_string(),
+            r#type: TemperatureBreachRowType::HotConsecutive,
+            sensor_id: sensor.id.clone(),
+            location_id: Some(fridge.id.clone()),
+            store_id: store.id.clone(),
+            start_datetime: datetime(9, 50),
+            end_datetime: Some(datetime(10, 30)),
+            ..Default::default()
+        };
+
+        let ServiceTestContext {
+            service_provider, ..
+        } = setup_all_with_data_and_service_provider(
+            "temperature_kpis",
+            MockDataInserts::none().names().stores(),
+            MockData {
+                locations: vec![fridge, freezer],
+                sensors: vec![sensor],
+                temperature_breaches: vec![breach],
+                // 10:00 to 11:00 in the fridge with the 10:40 log missing, then the freezer
+                temperature_logs: vec![
+                    log("log1", (10, 0), 5.0, "fridge"),
+                    log("log2", (10, 10), 9.0, "fridge"),
+                    log("log3", (10, 20), 10.0, "fridge"),
+                    log("log4", (10, 30), 6.0, "fridge"),
+                    log("log5", (10, 50), 4.0, "fridge"),
+                    log("log6", (11, 0), -20.0, "freezer"),
+                ],
+                ..Default::default()
+            },
+        )
+        .await;
+        let ctx = service_provider
+            .context(store.id.clone(), "".to_string())
+            .unwrap();
+        let service = &service_provider.cold_chain_service;
+        let input = TemperatureKpiInput {
+            from_datetime: datetime(10, 0),
+            to_datetime: datetime(11, 0),
+            filter: None,
+            minimum_temperature: None,
+            maximum_temperature: None,
+            activation_energy: None,
+        };
+
+        assert_eq!(
+            service.get_temperature_kpis(
+                &ctx,
+                TemperatureKpiInput {
+                    to_datetime: datetime(9, 0),
+                    ..input.clone()
+                }
+            ),
+            Err(TemperatureKpiError::ToDateTimeMustBeAfterFromDatetime)
+        );
+        assert_eq!(
+            service.get_temperature_kpis(
+                &ctx,
+                TemperatureKpiInput {
+                    minimum_temperature: Some(8.0),
+                    maximum_temperature: Some(2.0),
+                    ..input.clone()
+                }
+            ),
+            Err(TemperatureKpiError::MaximumTemperatureMustBeAboveMinimum)
+        );
+
+        let kpis = service.get_temperature_kpis(&ctx, input).unwrap();
+        assert_eq!(kpis.len(), 2);
+        let freezer = &kpis[0];
+        assert_eq!(freezer.location_id, Some("freezer".to_string()));
+        assert_eq!(freezer.number_of_logs, 1);
+        assert_eq!(freezer.time_in_range_percentage, 0.0);
+        assert_eq!(freezer.breaches, vec![]);
+
+        let fridge = &kpis[1];
+        assert_eq!(fridge.sensor_id, "sensor");
+        assert_eq!(fridge.location_id, Some("fridge".to_string()));
+        assert_eq!(fridge.number_of_logs, 5);
+        assert_eq!(fridge.minimum_temperature, 4.0);
+        assert_eq!(fridge.maximum_temperature, 10.0);
+        assert!(fridge.mean_kinetic_temperature > 6.8);
+        // 20 of 50 logged minutes out of range, gap after 10:30 counts one log interval
+        assert_eq!(fridge.time_in_range_percentage, 60.0);
+        // Breach from 9:50 is counted from the start of the range
+        assert_eq!(
+            fridge.breaches,
+            vec![TemperatureBreachKpi {
+                r#type: TemperatureBreachRowType::HotConsecutive,
+                count: 1,
+                duration_milliseconds: 30 * 60 * 1000,
+            }]
+        );
+        assert_eq!(fridge.expected_number_of_intervals, Some(6));
+        assert_eq!(fridge.missing_intervals, Some(1));
+        assert_eq!(fridge.completeness_percentage, Some(5.0 / 6.0 * 100.0));
+    }
+}
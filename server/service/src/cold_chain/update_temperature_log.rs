@@ -1,4 +1,7 @@
-use super::{query_temperature_log::get_temperature_log, validate::check_temperature_log_exists};
+use super::{
+    query_temperature_log::get_temperature_log,
+    validate::{check_sensor_log_datetime_is_unique, check_temperature_log_exists},
+};
 use crate::{service_provider::ServiceContext, SingleRecordError};
 use chrono::NaiveDateTime;
 use repository::{
@@ -10,6 +13,7 @@ use repository::{
 pub enum UpdateTemperatureLogError {
     TemperatureLogDoesNotExist,
     TemperatureLogDoesNotBelongToCurrentStore,
+    SensorAlreadyHasLogAtDatetime,
     UpdatedRecordNotFound,
     LocationIsOnHold,
     DatabaseError(RepositoryError),
@@ -40,6 +44,10 @@ pub fn update_temperature_log(
                 .map_err(UpdateTemperatureLogError::from)
         })
         .map_err(|error| error.to_inner_error())?;
+
+    ctx.processors_trigger
+        .trigger_temperature_breach_processor();
+
     Ok(temperature_log)
 }
 
@@ -55,6 +63,14 @@ pub fn validate(
     if temperature_log_row.store_id != *store_id {
         return Err(UpdateTemperatureLogError::TemperatureLogDoesNotBelongToCurrentStore);
     }
+    if !check_sensor_log_datetime_is_unique(
+        &input.id,
+        &input.sensor_id,
+        input.datetime,
+        connection,
+    )? {
+        return Err(UpdateTemperatureLogError::SensorAlreadyHasLogAtDatetime);
+    }
 
     Ok(temperature_log_row)
 }
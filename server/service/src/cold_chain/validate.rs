@@ -1,7 +1,9 @@
+use chrono::NaiveDateTime;
 use repository::{
-    EqualFilter, RepositoryError, StorageConnection, TemperatureBreachFilter,
-    TemperatureBreachRepository, TemperatureBreachRow, TemperatureBreachRowRepository,
-    TemperatureLogFilter, TemperatureLogRepository, TemperatureLogRow, TemperatureLogRowRepository,
+    DatetimeFilter, EqualFilter, RepositoryError, SensorFilter, StorageConnection,
+    TemperatureBreachFilter, TemperatureBreachRepository, TemperatureBreachRow,
+    TemperatureBreachRowRepository, TemperatureLogFilter, TemperatureLogRepository,
+    TemperatureLogRow, TemperatureLogRowRepository,
 };
 
 pub fn check_temperature_log_does_not_exist(
@@ -14,6 +16,23 @@ pub fn check_temperature_log_does_not_exist(
     Ok(temperature_logs.is_empty())
 }
 
+/// Sensor can only have one log per datetime (other than the log with `id`)
+pub fn check_sensor_log_datetime_is_unique(
+    id: &str,
+    sensor_id: &str,
+    datetime: NaiveDateTime,
+    connection: &StorageConnection,
+) -> Result<bool, RepositoryError> {
+    let temperature_logs = TemperatureLogRepository::new(connection).query_by_filter(
+        TemperatureLogFilter::new()
+            .id(EqualFilter::not_equal_to(id))
+            .sensor(SensorFilter::new().id(EqualFilter::equal_to(sensor_id)))
+            .datetime(DatetimeFilter::equal_to(datetime)),
+    )?;
+
+    Ok(temperature_logs.is_empty())
+}
+
 pub fn check_temperature_log_exists(
     id: &str,
     connection: &StorageConnection,
@@ -14,6 +14,14 @@ pub trait RequisitionCountServiceTrait: Send + Sync {
         RequisitionCountService {}.new_response_requisition_count(ctx, store_id)
     }
 
+    fn new_emergency_response_requisition_count(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+    ) -> Result<i64, RepositoryError> {
+        RequisitionCountService {}.new_emergency_response_requisition_count(ctx, store_id)
+    }
+
     fn draft_request_requisition_count(
         &self,
         ctx: &ServiceContext,
@@ -40,6 +48,21 @@ impl RequisitionCountServiceTrait for RequisitionCountService {
         ))
     }
 
+    fn new_emergency_response_requisition_count(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+    ) -> Result<i64, RepositoryError> {
+        let repo = RequisitionRepository::new(&ctx.connection);
+        repo.count(Some(
+            RequisitionFilter::new()
+                .store_id(EqualFilter::equal_to(store_id))
+                .r#type(RequisitionRowType::Response.equal_to())
+                .status(RequisitionRowStatus::New.equal_to())
+                .is_emergency(true),
+        ))
+    }
+
     fn draft_request_requisition_count(
         &self,
         ctx: &ServiceContext,
@@ -6,12 +6,14 @@ use tokio::task::JoinHandle;
 
 use crate::service_provider::ServiceProvider;
 
+use self::temperature_breach::{process_temperature_breaches, ProcessTemperatureBreachesError};
 use self::transfer::requisition::ProcessRequisitionTransfersError;
 use self::transfer::shipment::ProcessShipmentTransfersError;
 use self::transfer::{
     requisition::process_requisition_transfers, shipment::process_shipment_transfers,
 };
 
+pub(crate) mod temperature_breach;
 #[cfg(test)]
 mod test_helpers;
 pub(crate) mod transfer;
@@ -22,12 +24,14 @@ const CHANNEL_BUFFER_SIZE: usize = 30;
 pub struct ProcessorsTrigger {
     requisition_transfer: Sender<()>,
     shipment_transfer: Sender<()>,
+    temperature_breach: Sender<()>,
     await_process_queue: Sender<oneshot::Sender<()>>,
 }
 
 pub struct Processors {
     requisition_transfer: Receiver<()>,
     shipment_transfer: Receiver<()>,
+    temperature_breach: Receiver<()>,
     await_process_queue: Receiver<oneshot::Sender<()>>,
 }
 
@@ -37,6 +41,8 @@ enum ProcessorsError {
     ShipmentTransfer(ProcessShipmentTransfersError),
     #[error("Error in requisition transfer processor ({0})")]
     RequisitionTransfer(ProcessRequisitionTransfersError),
+    #[error("Error in temperature breach processor ({0})")]
+    TemperatureBreach(ProcessTemperatureBreachesError),
     #[error("Error when waiting for the process queue to be processed")]
     AwaitProcessQueue(()),
 }
@@ -49,17 +55,22 @@ impl Processors {
         let (shipment_transfer_sender, shipment_transfer_receiver) =
             mpsc::channel(CHANNEL_BUFFER_SIZE);
 
+        let (temperature_breach_sender, temperature_breach_receiver) =
+            mpsc::channel(CHANNEL_BUFFER_SIZE);
+
         let (request_check_sender, request_check_receiver) = mpsc::channel(CHANNEL_BUFFER_SIZE);
 
         (
             ProcessorsTrigger {
                 requisition_transfer: requisition_transfer_sender,
                 shipment_transfer: shipment_transfer_sender,
+                temperature_breach: temperature_breach_sender,
                 await_process_queue: request_check_sender,
             },
             Processors {
                 requisition_transfer: requisition_transfer_receiver,
                 shipment_transfer: shipment_transfer_receiver,
+                temperature_breach: temperature_breach_receiver,
                 await_process_queue: request_check_receiver,
             },
         )
@@ -69,6 +80,7 @@ impl Processors {
         let Processors {
             mut requisition_transfer,
             mut shipment_transfer,
+            mut temperature_breach,
             mut await_process_queue,
         } = self;
 
@@ -86,6 +98,9 @@ impl Processors {
                     Some(_) = shipment_transfer.recv() => {
                         process_shipment_transfers(&service_provider).map_err(ProcessorsError::ShipmentTransfer)
                     },
+                    Some(_) = temperature_breach.recv() => {
+                        process_temperature_breaches(&service_provider).map_err(ProcessorsError::TemperatureBreach)
+                    },
                     Some(sender) = await_process_queue.recv() => {
                         sender.send(()).map_err(ProcessorsError::AwaitProcessQueue)
                     },
@@ -120,6 +135,15 @@ impl ProcessorsTrigger {
         }
     }
 
+    pub(crate) fn trigger_temperature_breach_processor(&self) {
+        if let Err(error) = self.temperature_breach.try_send(()) {
+            log::error!(
+                "Problem triggering temperature breach processor {:#?}",
+                error
+            )
+        }
+    }
+
     /// Waits till all current events in the processor queue are handled.
     /// Its guaranteed that all queued processor events that where in the queue before calling
     /// this method are handled when this method returns.
@@ -148,6 +172,7 @@ impl ProcessorsTrigger {
         ProcessorsTrigger {
             requisition_transfer: mpsc::channel(1).0,
             shipment_transfer: mpsc::channel(1).0,
+            temperature_breach: mpsc::channel(1).0,
             await_process_queue: mpsc::channel(1).0,
         }
     }
@@ -0,0 +1,193 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDateTime;
+use repository::{
+    ChangelogAction, ChangelogFilter, ChangelogRepository, ChangelogTableName, EqualFilter,
+    KeyValueType, RepositoryError, SensorRowRepository, SensorType, TemperatureLogRowRepository,
+};
+use thiserror::Error;
+
+use crate::{
+    cold_chain::temperature_breach_detection::detect_temperature_breaches,
+    cursor_controller::CursorController,
+    service_provider::ServiceProvider,
+    sync::{ActiveStoresOnSite, GetActiveStoresOnSiteError},
+};
+
+const CHANGELOG_BATCH_SIZE: u32 = 100;
+
+#[derive(Error, Debug)]
+pub(crate) enum ProcessTemperatureBreachesError {
+    #[error("{0}")]
+    GetActiveStoresOnSiteError(GetActiveStoresOnSiteError),
+    #[error("{0:?}")]
+    DatabaseError(RepositoryError),
+}
+
+/// Detects temperature breaches for temperature logs added or changed on this site (logs received
+/// through sync were already processed where they were created). Breaches of Berlinger sensors are
+/// read from the sensor files, their logs are not processed
+pub(crate) fn process_temperature_breaches(
+    service_provider: &ServiceProvider,
+) -> Result<(), ProcessTemperatureBreachesError> {
+    use ProcessTemperatureBreachesError as Error;
+
+    let ctx = service_provider
+        .basic_context()
+        .map_err(Error::DatabaseError)?;
+
+    let active_stores =
+        ActiveStoresOnSite::get(&ctx.connection).map_err(Error::GetActiveStoresOnSiteError)?;
+
+    let changelog_repo = ChangelogRepository::new(&ctx.connection);
+    let cursor_controller = CursorController::new(KeyValueType::TemperatureBreachProcessorCursor);
+    let filter = ChangelogFilter::new()
+        .table_name(ChangelogTableName::TemperatureLog.equal_to())
+        .action(ChangelogAction::Upsert.equal_to())
+        .is_sync_update(EqualFilter::equal_or_null_bool(false));
+
+    loop {
+        let cursor = cursor_controller
+            .get(&ctx.connection)
+            .map_err(Error::DatabaseError)?;
+
+        let logs = changelog_repo
+            .changelogs(cursor, CHANGELOG_BATCH_SIZE, Some(filter.clone()))
+            .map_err(Error::DatabaseError)?;
+
+        let Some(last_cursor) = logs.last().map(|log| log.cursor) else {
+            break;
+        };
+
+        // Earliest changed log per sensor, breaches are detected from there.
+        // Temperature log changelogs don't have store_id, logs of other stores are skipped here
+        let record_ids: Vec<String> = logs.into_iter().map(|log| log.record_id).collect();
+        let store_ids = active_stores.store_ids();
+        let mut from_by_sensor: HashMap<String, NaiveDateTime> = HashMap::new();
+        for temperature_log in TemperatureLogRowRepository::new(&ctx.connection)
+            .find_many_by_id(&record_ids)
+            .map_err(Error::DatabaseError)?
+        {
+            if !store_ids.contains(&temperature_log.store_id) {
+                continue;
+            }
+            let datetime = temperature_log.datetime;
+            from_by_sensor
+                .entry(temperature_log.sensor_id)
+                .and_modify(|from| *from = (*from).min(datetime))
+                .or_insert(datetime);
+        }
+
+        ctx.connection
+            .transaction_sync(|connection| -> Result<_, RepositoryError> {
+                let sensor_repo = SensorRowRepository::new(connection);
+                for (sensor_id, from) in from_by_sensor.iter() {
+                    let Some(sensor) = sensor_repo.find_one_by_id(sensor_id)? else {
+                        continue;
+                    };
+                    if sensor.r#type == SensorType::Berlinger {
+                        continue;
+                    }
+                    detect_temperature_breaches(connection, &sensor, *from)?;
+                }
+
+                cursor_controller.update(connection, (last_cursor + 1) as u64)
+            })
+            .map_err(|error| Error::DatabaseError(error.to_inner_error()))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{Duration, NaiveDateTime};
+    use repository::{
+        mock::{
+            mock_sensor_1, mock_store_a, mock_store_b, mock_temperature_breach_config_1, MockData,
+            MockDataInserts,
+        },
+        test_db::setup_all_with_data,
+        EqualFilter, KeyValueStoreRow, KeyValueType, SensorFilter, SensorRow,
+        TemperatureBreachConfigRow, TemperatureBreachFilter, TemperatureBreachRepository,
+        TemperatureLogRow, TemperatureLogRowRepository,
+    };
+    use util::inline_init;
+
+    use crate::service_provider::ServiceProvider;
+
+    use super::process_temperature_breaches;
+
+    #[actix_rt::test]
+    async fn process_temperature_breaches_from_changelog() {
+        let site_id_settings = inline_init(|r: &mut KeyValueStoreRow| {
+            r.id = KeyValueType::SettingsSyncSiteId;
+            r.value_int = Some(mock_store_a().site_id);
+        });
+        let sensor_in_other_site = SensorRow {
+            id: "sensor_in_other_site".to_string(),
+            serial: "sensor_in_other_site".to_string(),
+            store_id: mock_store_b().id,
+            ..Default::default()
+        };
+
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "process_temperature_breaches_from_changelog",
+            MockDataInserts::none().names().stores(),
+            inline_init(|r: &mut MockData| {
+                r.sensors = vec![mock_sensor_1(), sensor_in_other_site.clone()];
+                r.temperature_breach_configs = vec![
+                    mock_temperature_breach_config_1(),
+                    TemperatureBreachConfigRow {
+                        id: "config_in_other_site".to_string(),
+                        store_id: mock_store_b().id,
+                        ..mock_temperature_breach_config_1()
+                    },
+                ];
+                r.key_value_store_rows = vec![site_id_settings];
+            }),
+        )
+        .await;
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+
+        // Hot for 3 minutes, above the 2 minute config, logs are inserted like they would be
+        // on this site (changelogs are added by the trigger)
+        let start =
+            NaiveDateTime::parse_from_str("2024-01-01T10:00:00", "%Y-%m-%dT%H:%M:%S").unwrap();
+        let log_repo = TemperatureLogRowRepository::new(&connection);
+        for sensor in [mock_sensor_1(), sensor_in_other_site.clone()] {
+            for (index, minutes) in [0, 3].iter().enumerate() {
+                log_repo
+                    .upsert_one(&TemperatureLogRow {
+                        id: format!("{}_log_{}", sensor.id, index),
+                        temperature: 10.0,
+                        sensor_id: sensor.id.clone(),
+                        store_id: sensor.store_id.clone(),
+                        datetime: start + Duration::minutes(*minutes),
+                        ..Default::default()
+                    })
+                    .unwrap();
+            }
+        }
+
+        process_temperature_breaches(&service_provider).unwrap();
+
+        let breaches = |sensor_id: &str| {
+            TemperatureBreachRepository::new(&connection)
+                .query_by_filter(
+                    TemperatureBreachFilter::new()
+                        .sensor(SensorFilter::new().id(EqualFilter::equal_to(sensor_id))),
+                )
+                .unwrap()
+        };
+        let result = breaches(&mock_sensor_1().id);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].temperature_breach_row.start_datetime, start);
+        // Logs of stores not active on this site are not processed
+        assert_eq!(breaches(&sensor_in_other_site.id).len(), 0);
+
+        // Processing again doesn't create duplicates
+        process_temperature_breaches(&service_provider).unwrap();
+        assert_eq!(breaches(&mock_sensor_1().id).len(), 1);
+    }
+}
@@ -118,25 +118,32 @@ fn generate_response_requisition(
     let requisition_number =
         next_number(connection, &NumberRowType::ResponseRequisition, &store_id)?;
 
+    // Emergency orders are flagged in reference and comment, for visibility in the supplying store
+    let order_description = if request_requisition_row.is_emergency {
+        "emergency internal order"
+    } else {
+        "internal order"
+    };
+
     let their_ref = match &request_requisition_row.their_reference {
         Some(reference) => format!(
-            "From internal order {} ({})",
-            request_requisition_row.requisition_number, reference
+            "From {} {} ({})",
+            order_description, request_requisition_row.requisition_number, reference
         ),
         None => format!(
-            "From internal order {}",
-            request_requisition_row.requisition_number,
+            "From {} {}",
+            order_description, request_requisition_row.requisition_number,
         ),
     };
 
     let comment = match &request_requisition_row.comment {
         Some(comment) => format!(
-            "From internal order {} ({})",
-            request_requisition_row.requisition_number, comment
+            "From {} {} ({})",
+            order_description, request_requisition_row.requisition_number, comment
         ),
         None => format!(
-            "From internal order {}",
-            request_requisition_row.requisition_number,
+            "From {} {}",
+            order_description, request_requisition_row.requisition_number,
         ),
     };
 
@@ -158,6 +165,8 @@ fn generate_response_requisition(
         program_id: request_requisition_row.program_id.clone(),
         period_id: request_requisition_row.period_id.clone(),
         order_type: request_requisition_row.order_type.clone(),
+        is_emergency: request_requisition_row.is_emergency,
+        emergency_reason: request_requisition_row.emergency_reason.clone(),
         // Default
         user_id: None,
         approval_status: None,
@@ -348,6 +348,14 @@ impl RequisitionTransferTester {
             response_requisition.expected_delivery_date,
             self.request_requisition.expected_delivery_date
         );
+        assert_eq!(
+            response_requisition.is_emergency,
+            self.request_requisition.is_emergency
+        );
+        assert_eq!(
+            response_requisition.emergency_reason,
+            self.request_requisition.emergency_reason
+        );
 
         assert_eq!(
             RequisitionLineRepository::new(connection)
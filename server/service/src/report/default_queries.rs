@@ -14,6 +14,14 @@ pub fn get_default_gql_query(query: DefaultQuery) -> GraphQlQuery {
             query: REQUISITION_QUERY.to_string(),
             variables: None,
         },
+        DefaultQuery::RequisitionReportingRate => GraphQlQuery {
+            query: REQUISITION_REPORTING_RATE_QUERY.to_string(),
+            variables: None,
+        },
+        DefaultQuery::TemperatureKpi => GraphQlQuery {
+            query: TEMPERATURE_KPI_QUERY.to_string(),
+            variables: None,
+        },
     }
 }
 
@@ -353,3 +361,99 @@ const REQUISITION_QUERY: &str = r#"query RequisitionQuery($storeId: String, $dat
     }
   }
 }"#;
+
+/// `filter` (ReportingRateFilterInput) is provided through the report arguments
+const REQUISITION_REPORTING_RATE_QUERY: &str = r#"
+query RequisitionReportingRateQuery($storeId: String, $filter: ReportingRateFilterInput) {
+  reportingRates(storeId: $storeId, filter: $filter) {
+    programId
+    programName
+    nameTagId
+    nameTagName
+    period {
+      id
+      name
+      startDate
+      endDate
+    }
+    deadline
+    expectedCount
+    submittedCount
+    onTimeCount
+    reportingRate
+    onTimeRate
+    completeness
+    stores {
+      store {
+        id
+        code
+        storeName
+      }
+      hasRequisition
+      sentDatetime
+      isOnTime
+      completeness
+    }
+  }
+  store(id: $storeId) {
+    ... on StoreNode {
+      id
+      code
+      storeName
+      logo
+    }
+    ... on NodeError {
+      __typename
+      error {
+        description
+      }
+    }
+  }
+}"#;
+
+/// `input` (TemperatureKpiInput) and optional `filter` (TemperatureLogFilterInput) are provided
+/// through the report arguments
+const TEMPERATURE_KPI_QUERY: &str = r#"
+query TemperatureKpiQuery($storeId: String!, $input: TemperatureKpiInput!, $filter: TemperatureLogFilterInput) {
+  temperatureKpis(storeId: $storeId, input: $input, filter: $filter) {
+    sensorId
+    sensor {
+      id
+      name
+      serial
+    }
+    locationId
+    location {
+      id
+      code
+      name
+    }
+    numberOfLogs
+    meanKineticTemperature
+    minimumTemperature
+    maximumTemperature
+    timeInRangePercentage
+    breaches {
+      type
+      count
+      durationMilliseconds
+    }
+    expectedNumberOfIntervals
+    missingIntervals
+    completenessPercentage
+  }
+  store(id: $storeId) {
+    ... on StoreNode {
+      id
+      code
+      storeName
+      logo
+    }
+    ... on NodeError {
+      __typename
+      error {
+        description
+      }
+    }
+  }
+}"#;
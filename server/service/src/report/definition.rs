@@ -30,6 +30,8 @@ pub enum DefaultQuery {
     Invoice,
     Stocktake,
     Requisition,
+    RequisitionReportingRate,
+    TemperatureKpi,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
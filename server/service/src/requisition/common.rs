@@ -3,8 +3,10 @@ use repository::{
     RequisitionLineRepository, RequisitionRowRepository, StorageConnection,
 };
 use repository::{
-    EqualFilter, Requisition, RequisitionFilter, RequisitionRepository,
-    RequisitionRowApprovalStatus,
+    EqualFilter, MasterListFilter, MasterListLineFilter, MasterListLineRepository, NameTagFilter,
+    ProgramRequisitionOrderTypeRowRepository, ProgramRequisitionSettingsFilter,
+    ProgramRequisitionSettingsRepository, ProgramRowRepository, Requisition, RequisitionFilter,
+    RequisitionRepository, RequisitionRowApprovalStatus,
 };
 use util::inline_edit;
 
@@ -62,3 +64,67 @@ pub fn check_approval_status(requisition_row: &RequisitionRow) -> bool {
     }
     false
 }
+
+/// Maximum number of items (lines) allowed in an emergency requisition, as per matching emergency
+/// order type of the program settings that apply to the requisition store (same as when creating
+/// the requisition, by store name tags and program master list visibility). None if requisition
+/// is not an emergency requisition or if emergency order type is not limited
+/// (max_items_in_emergency_order = 0).
+/// There is no cap by value, order types synced from central only configure number of items
+/// (`maxEmergencyItems`)
+pub fn get_emergency_order_max_items(
+    connection: &StorageConnection,
+    requisition_row: &RequisitionRow,
+) -> Result<Option<i32>, RepositoryError> {
+    if !requisition_row.is_emergency {
+        return Ok(None);
+    }
+
+    let (program_id, order_type) = match (&requisition_row.program_id, &requisition_row.order_type)
+    {
+        (Some(program_id), Some(order_type)) => (program_id, order_type),
+        _ => return Ok(None),
+    };
+
+    let equal_to_store_id = EqualFilter::equal_to(&requisition_row.store_id);
+    let filter = ProgramRequisitionSettingsFilter::new()
+        .master_list(MasterListFilter::new().exists_for_store_id(equal_to_store_id.clone()))
+        .name_tag(NameTagFilter::new().store_id(equal_to_store_id));
+    let program_requisition_settings_ids: Vec<String> =
+        ProgramRequisitionSettingsRepository::new(connection)
+            .query(Some(filter))?
+            .into_iter()
+            .filter(|settings| &settings.program_row.id == program_id)
+            .map(|settings| settings.program_settings_row.id)
+            .collect();
+
+    // Case insensitive match for order_type (same as when checking available periods)
+    let max_items = ProgramRequisitionOrderTypeRowRepository::new(connection)
+        .find_many_by_program_requisition_settings_ids(&program_requisition_settings_ids)?
+        .into_iter()
+        .find(|row| row.is_emergency && row.name.to_lowercase() == order_type.to_lowercase())
+        .map(|row| row.max_items_in_emergency_order)
+        .filter(|max_items| *max_items > 0);
+
+    Ok(max_items)
+}
+
+/// Items added to program requisitions need to be on the program master list
+pub fn check_item_in_program_master_list(
+    connection: &StorageConnection,
+    program_id: &str,
+    item_id: &str,
+) -> Result<bool, RepositoryError> {
+    let program = match ProgramRowRepository::new(connection).find_one_by_id(program_id)? {
+        Some(program) => program,
+        None => return Ok(false),
+    };
+
+    let count = MasterListLineRepository::new(connection).count(Some(
+        MasterListLineFilter::new()
+            .master_list_id(EqualFilter::equal_to(&program.master_list_id))
+            .item_id(EqualFilter::equal_to(item_id)),
+    ))?;
+
+    Ok(count > 0)
+}
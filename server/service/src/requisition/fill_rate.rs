@@ -0,0 +1,606 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use repository::{
+    DatetimeFilter, EqualFilter, InvoiceFilter, InvoiceRepository, InvoiceRowStatus,
+    InvoiceRowType, ItemRow, NameLinkRowRepository, NameRow, RepositoryError, Requisition,
+    RequisitionFilter, RequisitionLineFilter, RequisitionLineRepository, RequisitionRepository,
+    RequisitionRowStatus, RequisitionRowType, StorageConnection,
+};
+use util::{
+    constants::{DEFAULT_LEAD_TIME_LOOKBACK_MONTHS, NUMBER_OF_DAYS_IN_A_MONTH},
+    date_now_with_offset, first_day_of_the_month, last_day_of_the_month,
+};
+
+use crate::service_provider::ServiceContext;
+
+use super::requisition_supply_status::{
+    get_requisitions_supply_statuses, RequisitionLineSupplyStatus,
+};
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct FillRateFilter {
+    /// Supplier name id
+    pub supplier_id: Option<EqualFilter<String>>,
+    pub item_id: Option<EqualFilter<String>>,
+    pub sent_datetime: Option<DatetimeFilter>,
+}
+
+impl FillRateFilter {
+    pub fn new() -> FillRateFilter {
+        Self::default()
+    }
+
+    pub fn supplier_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.supplier_id = Some(filter);
+        self
+    }
+
+    pub fn item_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.item_id = Some(filter);
+        self
+    }
+
+    pub fn sent_datetime(mut self, filter: DatetimeFilter) -> Self {
+        self.sent_datetime = Some(filter);
+        self
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct FillRateStats {
+    pub requisition_count: i64,
+    /// Requisitions where all requested lines were supplied in full
+    pub filled_requisition_count: i64,
+    pub line_count: i64,
+    /// Lines where supplied quantity is at least requested quantity
+    pub filled_line_count: i64,
+    pub requested_quantity: f64,
+    pub supplied_quantity: f64,
+    /// Lead times in days, from requisition sent_datetime to first delivered inbound shipment
+    pub lead_times: Vec<f64>,
+}
+
+impl FillRateStats {
+    pub fn line_fill_rate(&self) -> f64 {
+        ratio(self.filled_line_count as f64, self.line_count as f64)
+    }
+
+    pub fn order_fill_rate(&self) -> f64 {
+        ratio(
+            self.filled_requisition_count as f64,
+            self.requisition_count as f64,
+        )
+    }
+
+    pub fn quantity_fill_rate(&self) -> f64 {
+        ratio(self.supplied_quantity, self.requested_quantity)
+    }
+
+    pub fn average_lead_time_days(&self) -> Option<f64> {
+        if self.lead_times.is_empty() {
+            return None;
+        }
+        Some(self.lead_times.iter().sum::<f64>() / self.lead_times.len() as f64)
+    }
+
+    fn add(&mut self, other: &FillRateStats) {
+        self.requisition_count += other.requisition_count;
+        self.filled_requisition_count += other.filled_requisition_count;
+        self.line_count += other.line_count;
+        self.filled_line_count += other.filled_line_count;
+        self.requested_quantity += other.requested_quantity;
+        self.supplied_quantity += other.supplied_quantity;
+        self.lead_times.extend_from_slice(&other.lead_times);
+    }
+}
+
+fn ratio(numerator: f64, denominator: f64) -> f64 {
+    if denominator == 0.0 {
+        return 0.0;
+    }
+    numerator / denominator
+}
+
+/// Emergency requisitions are counted separately from requisitions made in the period schedule
+#[derive(Clone, Debug, PartialEq)]
+pub struct SupplierFillRate {
+    pub supplier: NameRow,
+    pub stats: FillRateStats,
+    pub emergency_stats: FillRateStats,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ItemFillRate {
+    pub item: ItemRow,
+    pub stats: FillRateStats,
+    pub emergency_stats: FillRateStats,
+}
+
+/// Period is the calendar month requisitions were sent in
+#[derive(Clone, Debug, PartialEq)]
+pub struct PeriodFillRate {
+    pub period_start: NaiveDate,
+    pub period_end: NaiveDate,
+    pub stats: FillRateStats,
+    pub emergency_stats: FillRateStats,
+}
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct FillRates {
+    pub by_supplier: Vec<SupplierFillRate>,
+    pub by_item: Vec<ItemFillRate>,
+    pub by_period: Vec<PeriodFillRate>,
+}
+
+/// Fill rates and lead times of sent request requisitions in a store, supplied quantity is
+/// taken from delivered or verified inbound shipments linked to the request requisition
+pub fn get_fill_rates(
+    ctx: &ServiceContext,
+    store_id: &str,
+    filter: FillRateFilter,
+) -> Result<FillRates, RepositoryError> {
+    let connection = &ctx.connection;
+    let FillRateFilter {
+        supplier_id,
+        item_id,
+        sent_datetime,
+    } = filter;
+
+    let mut requisition_filter = sent_request_requisitions(store_id);
+    requisition_filter.name_id = supplier_id;
+    requisition_filter.sent_datetime = sent_datetime;
+
+    let requisitions =
+        RequisitionRepository::new(connection).query_by_filter(requisition_filter)?;
+    let lead_times = get_lead_times(connection, &requisitions)?;
+
+    let requisition_ids: Vec<String> = requisitions
+        .iter()
+        .map(|r| r.requisition_row.id.clone())
+        .collect();
+    let mut supply_statuses: HashMap<String, Vec<RequisitionLineSupplyStatus>> = HashMap::new();
+    for status in get_requisitions_supply_statuses(connection, requisition_ids.clone())? {
+        supply_statuses
+            .entry(
+                status
+                    .requisition_line
+                    .requisition_line_row
+                    .requisition_id
+                    .clone(),
+            )
+            .or_default()
+            .push(status);
+    }
+
+    let filtered_line_ids: Option<HashSet<String>> = match item_id {
+        Some(item_id) => Some(
+            RequisitionLineRepository::new(connection)
+                .query_by_filter(
+                    RequisitionLineFilter::new()
+                        .requisition_id(EqualFilter::equal_any(requisition_ids))
+                        .item_id(item_id),
+                )?
+                .into_iter()
+                .map(|line| line.requisition_line_row.id)
+                .collect(),
+        ),
+        None => None,
+    };
+
+    let mut by_supplier: HashMap<String, SupplierFillRate> = HashMap::new();
+    let mut by_item: HashMap<String, ItemFillRate> = HashMap::new();
+    let mut by_period: HashMap<NaiveDate, PeriodFillRate> = HashMap::new();
+
+    for requisition in requisitions {
+        let requisition_id = &requisition.requisition_row.id;
+        let lead_time = lead_times.get(requisition_id).copied();
+        let is_emergency = requisition.requisition_row.is_emergency;
+
+        let mut requisition_stats = FillRateStats::default();
+        let mut is_filled = true;
+
+        let lines = supply_statuses
+            .get(requisition_id)
+            .into_iter()
+            .flatten()
+            .filter(|status| {
+                status
+                    .requisition_line
+                    .requisition_line_row
+                    .requested_quantity
+                    > 0
+                    && match &filtered_line_ids {
+                        Some(line_ids) => {
+                            line_ids.contains(&status.requisition_line.requisition_line_row.id)
+                        }
+                        None => true,
+                    }
+            });
+
+        for status in lines {
+            let requested = status
+                .requisition_line
+                .requisition_line_row
+                .requested_quantity as f64;
+            let supplied = status
+                .invoice_lines
+                .iter()
+                .filter(|line| {
+                    line.invoice_row.r#type == InvoiceRowType::InboundShipment
+                        && matches!(
+                            line.invoice_row.status,
+                            InvoiceRowStatus::Delivered | InvoiceRowStatus::Verified
+                        )
+                })
+                .fold(0.0, |sum, line| {
+                    sum + line.invoice_line_row.pack_size as f64
+                        * line.invoice_line_row.number_of_packs
+                });
+            let line_is_filled = supplied >= requested;
+            is_filled = is_filled && line_is_filled;
+
+            let item = by_item
+                .entry(status.item_id().to_string())
+                .or_insert_with(|| ItemFillRate {
+                    item: status.requisition_line.item_row.clone(),
+                    stats: FillRateStats::default(),
+                    emergency_stats: FillRateStats::default(),
+                });
+            let item_stats = if is_emergency {
+                &mut item.emergency_stats
+            } else {
+                &mut item.stats
+            };
+
+            for stats in [&mut requisition_stats, &mut *item_stats] {
+                stats.line_count += 1;
+                stats.filled_line_count += line_is_filled as i64;
+                stats.requested_quantity += requested;
+                stats.supplied_quantity += supplied;
+            }
+
+            item_stats.requisition_count += 1;
+            item_stats.filled_requisition_count += line_is_filled as i64;
+            // Item lead time is only counted when the item was received
+            if let Some(lead_time) = lead_time.filter(|_| supplied > 0.0) {
+                item_stats.lead_times.push(lead_time);
+            }
+        }
+
+        if requisition_stats.line_count == 0 {
+            continue;
+        }
+
+        requisition_stats.requisition_count = 1;
+        requisition_stats.filled_requisition_count = is_filled as i64;
+        requisition_stats.lead_times.extend(lead_time);
+
+        let supplier = by_supplier
+            .entry(requisition.name_row.id.clone())
+            .or_insert_with(|| SupplierFillRate {
+                supplier: requisition.name_row.clone(),
+                stats: FillRateStats::default(),
+                emergency_stats: FillRateStats::default(),
+            });
+
+        let sent_date = requisition
+            .requisition_row
+            .sent_datetime
+            .unwrap_or(requisition.requisition_row.created_datetime)
+            .date();
+        let period = by_period
+            .entry(first_day_of_the_month(&sent_date))
+            .or_insert_with(|| PeriodFillRate {
+                period_start: first_day_of_the_month(&sent_date),
+                period_end: last_day_of_the_month(&sent_date),
+                stats: FillRateStats::default(),
+                emergency_stats: FillRateStats::default(),
+            });
+
+        if is_emergency {
+            supplier.emergency_stats.add(&requisition_stats);
+            period.emergency_stats.add(&requisition_stats);
+        } else {
+            supplier.stats.add(&requisition_stats);
+            period.stats.add(&requisition_stats);
+        }
+    }
+
+    let mut by_supplier: Vec<SupplierFillRate> = by_supplier.into_values().collect();
+    by_supplier.sort_by(|a, b| a.supplier.name.cmp(&b.supplier.name));
+    let mut by_item: Vec<ItemFillRate> = by_item.into_values().collect();
+    by_item.sort_by(|a, b| a.item.name.cmp(&b.item.name));
+    let mut by_period: Vec<PeriodFillRate> = by_period.into_values().collect();
+    by_period.sort_by(|a, b| a.period_start.cmp(&b.period_start));
+
+    Ok(FillRates {
+        by_supplier,
+        by_item,
+        by_period,
+    })
+}
+
+/// Average lead time (in months) of request requisitions sent from store to supplier, in the
+/// last DEFAULT_LEAD_TIME_LOOKBACK_MONTHS, None if no requisition was delivered yet.
+/// Emergency requisitions are expedited, so they are not included
+pub fn get_supplier_lead_time_months(
+    connection: &StorageConnection,
+    store_id: &str,
+    supplier_name_link_id: &str,
+) -> Result<Option<f64>, RepositoryError> {
+    let Some(name_link) =
+        NameLinkRowRepository::new(connection).find_one_by_id(supplier_name_link_id)?
+    else {
+        return Ok(None);
+    };
+
+    let lookback_start = date_now_with_offset(Duration::days(
+        -(DEFAULT_LEAD_TIME_LOOKBACK_MONTHS as f64 * NUMBER_OF_DAYS_IN_A_MONTH) as i64,
+    ));
+
+    let requisitions = RequisitionRepository::new(connection).query_by_filter(
+        sent_request_requisitions(store_id)
+            .name_id(EqualFilter::equal_to(&name_link.name_id))
+            .is_emergency(false)
+            .sent_datetime(DatetimeFilter::after_or_equal_to(
+                lookback_start.and_hms_opt(0, 0, 0).unwrap(),
+            )),
+    )?;
+
+    let lead_times = get_lead_times(connection, &requisitions)?;
+    if lead_times.is_empty() {
+        return Ok(None);
+    }
+
+    let average_days = lead_times.values().sum::<f64>() / lead_times.len() as f64;
+    Ok(Some(average_days / NUMBER_OF_DAYS_IN_A_MONTH))
+}
+
+fn sent_request_requisitions(store_id: &str) -> RequisitionFilter {
+    RequisitionFilter::new()
+        .store_id(EqualFilter::equal_to(store_id))
+        .r#type(RequisitionRowType::Request.equal_to())
+        .status(RequisitionRowStatus::equal_any(vec![
+            RequisitionRowStatus::Sent,
+            RequisitionRowStatus::Finalised,
+        ]))
+}
+
+/// Lead time in days by requisition id, from sent_datetime to the earliest delivered_datetime
+/// of inbound shipments linked to the requisition
+fn get_lead_times(
+    connection: &StorageConnection,
+    requisitions: &[Requisition],
+) -> Result<HashMap<String, f64>, RepositoryError> {
+    let inbound_shipments = InvoiceRepository::new(connection).query_by_filter(
+        InvoiceFilter::new()
+            .r#type(InvoiceRowType::InboundShipment.equal_to())
+            .requisition_id(EqualFilter::equal_any(
+                requisitions
+                    .iter()
+                    .map(|r| r.requisition_row.id.clone())
+                    .collect(),
+            )),
+    )?;
+
+    let mut first_delivered: HashMap<String, NaiveDateTime> = HashMap::new();
+    for invoice in inbound_shipments {
+        let invoice = invoice.invoice_row;
+        let (Some(requisition_id), Some(delivered_datetime)) =
+            (invoice.requisition_id, invoice.delivered_datetime)
+        else {
+            continue;
+        };
+        let entry = first_delivered
+            .entry(requisition_id)
+            .or_insert(delivered_datetime);
+        if delivered_datetime < *entry {
+            *entry = delivered_datetime;
+        }
+    }
+
+    Ok(requisitions
+        .iter()
+        .filter_map(|r| {
+            let row = &r.requisition_row;
+            let sent_datetime = row.sent_datetime?;
+            let delivered_datetime = first_delivered.get(&row.id)?;
+            let days = (*delivered_datetime - sent_datetime).num_seconds() as f64 / 86400.0;
+            Some((row.id.clone(), days.max(0.0)))
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{Duration, NaiveDate, Utc};
+    use repository::{
+        mock::{
+            mock_item_a, mock_item_b, mock_name_store_b, mock_store_a, MockData, MockDataInserts,
+        },
+        test_db::setup_all_with_data,
+        InvoiceLineRow, InvoiceLineRowType, InvoiceRow, InvoiceRowStatus, InvoiceRowType,
+        RequisitionLineRow, RequisitionRow, RequisitionRowStatus, RequisitionRowType,
+    };
+    use util::{
+        constants::NUMBER_OF_DAYS_IN_A_MONTH, first_day_of_the_month, inline_edit, inline_init,
+    };
+
+    use crate::{
+        requisition::{
+            fill_rate::{get_supplier_lead_time_months, FillRateFilter},
+            request_requisition::{generate_suggested_quantity, GenerateSuggestedQuantity},
+        },
+        service_provider::ServiceProvider,
+    };
+
+    #[actix_rt::test]
+    async fn fill_rates() {
+        let sent_datetime = Utc::now().naive_utc() - Duration::days(20);
+        let delivered_datetime = sent_datetime + Duration::days(15);
+
+        let requisition = inline_init(|r: &mut RequisitionRow| {
+            r.id = "fill_rate_requisition".to_string();
+            r.requisition_number = 1;
+            r.name_link_id = mock_name_store_b().id;
+            r.store_id = mock_store_a().id;
+            r.r#type = RequisitionRowType::Request;
+            r.status = RequisitionRowStatus::Sent;
+            r.created_datetime = sent_datetime;
+            r.sent_datetime = Some(sent_datetime);
+        });
+        // Delivered the next day, counted separately and not used for lead time of the supplier
+        let emergency_requisition = inline_edit(&requisition, |mut r| {
+            r.id = "fill_rate_emergency_requisition".to_string();
+            r.requisition_number = 2;
+            r.is_emergency = true;
+            r
+        });
+        let inbound_shipment = inline_init(|r: &mut InvoiceRow| {
+            r.id = "fill_rate_inbound_shipment".to_string();
+            r.name_link_id = mock_name_store_b().id;
+            r.store_id = mock_store_a().id;
+            r.r#type = InvoiceRowType::InboundShipment;
+            r.status = InvoiceRowStatus::Delivered;
+            r.created_datetime = delivered_datetime;
+            r.delivered_datetime = Some(delivered_datetime);
+            r.requisition_id = Some(requisition.id.clone());
+        });
+        let emergency_inbound_shipment = inline_edit(&inbound_shipment, |mut r| {
+            r.id = "fill_rate_emergency_inbound_shipment".to_string();
+            r.created_datetime = sent_datetime + Duration::days(1);
+            r.delivered_datetime = Some(sent_datetime + Duration::days(1));
+            r.requisition_id = Some(emergency_requisition.id.clone());
+            r
+        });
+
+        let (_, _, connection_manager, _) = setup_all_with_data(
+            "fill_rates",
+            MockDataInserts::all(),
+            inline_init(|r: &mut MockData| {
+                r.requisitions = vec![requisition.clone(), emergency_requisition.clone()];
+                r.requisition_lines = vec![
+                    inline_init(|r: &mut RequisitionLineRow| {
+                        r.id = "fill_rate_requisition_line_a".to_string();
+                        r.requisition_id = requisition.id.clone();
+                        r.item_link_id = mock_item_a().id;
+                        r.requested_quantity = 10;
+                    }),
+                    inline_init(|r: &mut RequisitionLineRow| {
+                        r.id = "fill_rate_requisition_line_b".to_string();
+                        r.requisition_id = requisition.id.clone();
+                        r.item_link_id = mock_item_b().id;
+                        r.requested_quantity = 5;
+                    }),
+                    inline_init(|r: &mut RequisitionLineRow| {
+                        r.id = "fill_rate_emergency_requisition_line_a".to_string();
+                        r.requisition_id = emergency_requisition.id.clone();
+                        r.item_link_id = mock_item_a().id;
+                        r.requested_quantity = 4;
+                    }),
+                ];
+                r.invoices = vec![inbound_shipment.clone(), emergency_inbound_shipment.clone()];
+                r.invoice_lines = vec![
+                    inline_init(|r: &mut InvoiceLineRow| {
+                        r.id = "fill_rate_invoice_line_a".to_string();
+                        r.invoice_id = inbound_shipment.id.clone();
+                        r.item_link_id = mock_item_a().id;
+                        r.r#type = InvoiceLineRowType::StockIn;
+                        r.pack_size = 1;
+                        r.number_of_packs = 10.0;
+                    }),
+                    inline_init(|r: &mut InvoiceLineRow| {
+                        r.id = "fill_rate_invoice_line_b".to_string();
+                        r.invoice_id = inbound_shipment.id.clone();
+                        r.item_link_id = mock_item_b().id;
+                        r.r#type = InvoiceLineRowType::StockIn;
+                        r.pack_size = 1;
+                        r.number_of_packs = 2.0;
+                    }),
+                ];
+            }),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, "".to_string())
+            .unwrap();
+        let service = service_provider.requisition_service;
+
+        let result = service
+            .get_fill_rates(&context, &mock_store_a().id, FillRateFilter::new())
+            .unwrap();
+
+        let supplier = result
+            .by_supplier
+            .iter()
+            .find(|s| s.supplier.id == mock_name_store_b().id)
+            .unwrap();
+        assert_eq!(supplier.stats.requisition_count, 1);
+        assert_eq!(supplier.stats.filled_requisition_count, 0);
+        assert_eq!(supplier.stats.line_fill_rate(), 0.5);
+        assert_eq!(supplier.stats.quantity_fill_rate(), 12.0 / 15.0);
+        assert_eq!(supplier.stats.average_lead_time_days(), Some(15.0));
+        assert_eq!(supplier.emergency_stats.requisition_count, 1);
+        assert_eq!(supplier.emergency_stats.line_fill_rate(), 0.0);
+        assert_eq!(supplier.emergency_stats.average_lead_time_days(), Some(1.0));
+
+        let item_a = result
+            .by_item
+            .iter()
+            .find(|i| i.item.id == mock_item_a().id)
+            .unwrap();
+        assert_eq!(item_a.stats.line_fill_rate(), 1.0);
+        assert_eq!(item_a.stats.order_fill_rate(), 1.0);
+        assert_eq!(item_a.emergency_stats.line_count, 1);
+        assert_eq!(item_a.emergency_stats.requested_quantity, 4.0);
+        let item_b = result
+            .by_item
+            .iter()
+            .find(|i| i.item.id == mock_item_b().id)
+            .unwrap();
+        assert_eq!(item_b.stats.line_fill_rate(), 0.0);
+        assert_eq!(item_b.stats.supplied_quantity, 2.0);
+
+        // Mock sent requisition (without sent_datetime) is in the month it was created
+        assert_eq!(
+            result.by_period[0].period_start,
+            NaiveDate::from_ymd_opt(2021, 1, 1).unwrap()
+        );
+        assert_eq!(
+            result.by_period[0].period_end,
+            NaiveDate::from_ymd_opt(2021, 1, 31).unwrap()
+        );
+        let period = result
+            .by_period
+            .iter()
+            .find(|p| p.period_start == first_day_of_the_month(&sent_datetime.date()))
+            .unwrap();
+        assert_eq!(period.stats, supplier.stats);
+        assert_eq!(period.emergency_stats, supplier.emergency_stats);
+
+        // Lead time feeds back into suggested quantity
+        let lead_time_months = get_supplier_lead_time_months(
+            &context.connection,
+            &mock_store_a().id,
+            &mock_name_store_b().id,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(lead_time_months, 15.0 / NUMBER_OF_DAYS_IN_A_MONTH);
+
+        let suggested_quantity = |lead_time_months: Option<f64>| {
+            generate_suggested_quantity(GenerateSuggestedQuantity {
+                average_monthly_consumption: 30,
+                available_stock_on_hand: 30,
+                min_months_of_stock: 0.0,
+                max_months_of_stock: 2.0,
+                lead_time_months,
+            })
+        };
+        assert_eq!(suggested_quantity(None), 30);
+        assert_eq!(suggested_quantity(Some(lead_time_months)), 45);
+    }
+}
@@ -0,0 +1,745 @@
+use std::collections::{HashMap, HashSet};
+use std::io::Cursor;
+
+use calamine::{open_workbook_from_rs, Reader, Xlsx};
+use chrono::{NaiveDate, Utc};
+use repository::{
+    requisition_row::{RequisitionRow, RequisitionRowStatus, RequisitionRowType},
+    ActivityLogType, EqualFilter, ItemFilter, ItemRepository, ItemRowType, MasterListFilter,
+    MasterListLineFilter, MasterListLineRepository, MasterListRepository, NumberRowType,
+    ProgramRowRepository, RepositoryError, Requisition, RequisitionLineRow,
+    RequisitionLineRowRepository, RequisitionRowRepository, StorageConnection, StringFilter,
+};
+use util::uuid::uuid;
+
+use crate::{
+    activity_log::activity_log_entry,
+    number::next_number,
+    requisition::{
+        common::{
+            check_requisition_row_exists, get_emergency_order_max_items, get_lines_for_requisition,
+        },
+        query::get_requisition,
+        request_requisition::{
+            generate_requisition_lines, insert_program_request_requisition,
+            insert_request_requisition, InsertProgramRequestRequisition,
+            InsertProgramRequestRequisitionError, InsertRequestRequisition,
+            InsertRequestRequisitionError,
+        },
+    },
+    service_provider::ServiceContext,
+    validate::{check_other_party, CheckOtherPartyType, OtherPartyErrors},
+};
+
+const ITEM_CODE_COLUMNS: &[&str] = &["code", "item_code"];
+const QUANTITY_COLUMNS: &[&str] = &["quantity", "requested_quantity"];
+const COMMENT_COLUMNS: &[&str] = &["comment"];
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub enum ImportRequisitionFileType {
+    #[default]
+    Csv,
+    Xlsx,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ImportRequisition {
+    pub id: String,
+    pub r#type: RequisitionRowType,
+    pub other_party_id: String,
+    pub file_type: ImportRequisitionFileType,
+    /// Raw file content, first row of the first sheet is the header row. Must have an item code
+    /// column ("code" or "item code") and a quantity column ("quantity" or "requested quantity"),
+    /// "comment" column is optional
+    pub file: Vec<u8>,
+    pub their_reference: Option<String>,
+    pub comment: Option<String>,
+    pub expected_delivery_date: Option<NaiveDate>,
+    /// Only used when importing a request requisition without a program order type
+    pub max_months_of_stock: f64,
+    /// Only used when importing a request requisition without a program order type
+    pub min_months_of_stock: f64,
+    /// Request requisition is created as a program requisition when program order type and
+    /// period are specified
+    pub program_order_type_id: Option<String>,
+    pub period_id: Option<String>,
+    pub emergency_reason: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ImportRequisitionRowErrorKind {
+    ItemCodeMissing,
+    ItemDoesNotExist,
+    /// Item is not on a master list visible to the store (request) or customer (response)
+    ItemNotInMasterList,
+    /// Item is not on the master list of the requisition program
+    ItemNotInProgram,
+    DuplicateItem,
+    InvalidQuantity(String),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ImportRequisitionRowError {
+    /// Row number in the file, header row is row 1
+    pub row_number: usize,
+    pub item_code: String,
+    pub error: ImportRequisitionRowErrorKind,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ImportRequisitionError {
+    // File validation
+    CannotParseFile(String),
+    ItemCodeColumnMissing,
+    QuantityColumnMissing,
+    NoRowsToImport,
+    RowErrors(Vec<ImportRequisitionRowError>),
+    // Request requisition validation
+    InsertRequestRequisition(InsertRequestRequisitionError),
+    InsertProgramRequestRequisition(InsertProgramRequestRequisitionError),
+    MaxItemsReachedForEmergencyOrder,
+    // Response requisition validation
+    RequisitionAlreadyExists,
+    OtherPartyNotACustomer,
+    OtherPartyDoesNotExist,
+    OtherPartyNotVisible,
+    ProgramNotSupportedForResponseRequisition,
+    // Internal
+    NewlyCreatedRequisitionDoesNotExist,
+    DatabaseError(RepositoryError),
+}
+
+type OutError = ImportRequisitionError;
+
+#[derive(Debug, PartialEq, Clone)]
+struct ImportRow {
+    row_number: usize,
+    item_code: String,
+    quantity: String,
+    comment: Option<String>,
+}
+
+struct ValidatedRow {
+    item_id: String,
+    quantity: i32,
+    comment: Option<String>,
+}
+
+/// Creates a request or response requisition from a CSV or XLSX file of item codes and
+/// quantities. All rows are validated before anything is saved, all row errors are returned
+/// together in `RowErrors`
+pub fn import_requisition(
+    ctx: &ServiceContext,
+    input: ImportRequisition,
+) -> Result<Requisition, OutError> {
+    let rows = parse_rows(&input.file_type, &input.file)?;
+
+    let requisition = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let requisition = match input.r#type {
+                RequisitionRowType::Request => insert_request(ctx, &input)?,
+                RequisitionRowType::Response => insert_response(ctx, &input)?,
+            };
+            let requisition_row = &requisition.requisition_row;
+
+            let lines = validate_rows(connection, &ctx.store_id, requisition_row, &rows)?;
+            let line_rows = generate_lines(ctx, requisition_row, lines)?;
+
+            let requisition_line_repository = RequisitionLineRowRepository::new(connection);
+            for line_row in line_rows {
+                requisition_line_repository.upsert_one(&line_row)?;
+            }
+
+            // Transaction is rolled back if emergency order would exceed max items
+            if let Some(max_items) = get_emergency_order_max_items(connection, requisition_row)? {
+                let line_count = get_lines_for_requisition(connection, &requisition_row.id)?.len();
+                if line_count > max_items as usize {
+                    return Err(OutError::MaxItemsReachedForEmergencyOrder);
+                }
+            }
+
+            Ok(requisition)
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(requisition)
+}
+
+fn insert_request(
+    ctx: &ServiceContext,
+    input: &ImportRequisition,
+) -> Result<Requisition, OutError> {
+    let ImportRequisition {
+        id,
+        other_party_id,
+        their_reference,
+        comment,
+        expected_delivery_date,
+        max_months_of_stock,
+        min_months_of_stock,
+        program_order_type_id,
+        period_id,
+        emergency_reason,
+        ..
+    } = input;
+
+    match (program_order_type_id, period_id) {
+        (Some(program_order_type_id), Some(period_id)) => insert_program_request_requisition(
+            ctx,
+            InsertProgramRequestRequisition {
+                id: id.clone(),
+                other_party_id: other_party_id.clone(),
+                colour: None,
+                their_reference: their_reference.clone(),
+                comment: comment.clone(),
+                expected_delivery_date: *expected_delivery_date,
+                program_order_type_id: program_order_type_id.clone(),
+                period_id: period_id.clone(),
+                emergency_reason: emergency_reason.clone(),
+            },
+        )
+        .map_err(OutError::InsertProgramRequestRequisition),
+        _ => insert_request_requisition(
+            ctx,
+            InsertRequestRequisition {
+                id: id.clone(),
+                other_party_id: other_party_id.clone(),
+                colour: None,
+                their_reference: their_reference.clone(),
+                comment: comment.clone(),
+                max_months_of_stock: *max_months_of_stock,
+                min_months_of_stock: *min_months_of_stock,
+                expected_delivery_date: *expected_delivery_date,
+            },
+        )
+        .map_err(OutError::InsertRequestRequisition),
+    }
+}
+
+fn insert_response(
+    ctx: &ServiceContext,
+    input: &ImportRequisition,
+) -> Result<Requisition, OutError> {
+    let connection = &ctx.connection;
+
+    if input.program_order_type_id.is_some() || input.period_id.is_some() {
+        return Err(OutError::ProgramNotSupportedForResponseRequisition);
+    }
+
+    if let Some(_) = check_requisition_row_exists(connection, &input.id)? {
+        return Err(OutError::RequisitionAlreadyExists);
+    }
+
+    check_other_party(
+        connection,
+        &ctx.store_id,
+        &input.other_party_id,
+        CheckOtherPartyType::Customer,
+    )
+    .map_err(|e| match e {
+        OtherPartyErrors::OtherPartyDoesNotExist => OutError::OtherPartyDoesNotExist,
+        OtherPartyErrors::OtherPartyNotVisible => OutError::OtherPartyNotVisible,
+        OtherPartyErrors::TypeMismatched => OutError::OtherPartyNotACustomer,
+        OtherPartyErrors::DatabaseError(repository_error) => {
+            OutError::DatabaseError(repository_error)
+        }
+    })?;
+
+    let new_requisition = RequisitionRow {
+        id: input.id.clone(),
+        user_id: Some(ctx.user_id.clone()),
+        requisition_number: next_number(
+            connection,
+            &NumberRowType::ResponseRequisition,
+            &ctx.store_id,
+        )?,
+        name_link_id: input.other_party_id.clone(),
+        store_id: ctx.store_id.clone(),
+        r#type: RequisitionRowType::Response,
+        status: RequisitionRowStatus::New,
+        created_datetime: Utc::now().naive_utc(),
+        their_reference: input.their_reference.clone(),
+        comment: input.comment.clone(),
+        expected_delivery_date: input.expected_delivery_date,
+        max_months_of_stock: input.max_months_of_stock,
+        min_months_of_stock: input.min_months_of_stock,
+        // Default
+        colour: None,
+        sent_datetime: None,
+        approval_status: None,
+        finalised_datetime: None,
+        linked_requisition_id: None,
+        program_id: None,
+        period_id: None,
+        order_type: None,
+        is_emergency: false,
+        emergency_reason: None,
+    };
+    RequisitionRowRepository::new(connection).upsert_one(&new_requisition)?;
+
+    activity_log_entry(
+        ctx,
+        ActivityLogType::RequisitionCreated,
+        Some(new_requisition.id.to_owned()),
+        None,
+        None,
+    )?;
+
+    get_requisition(ctx, None, &new_requisition.id)?
+        .ok_or(OutError::NewlyCreatedRequisitionDoesNotExist)
+}
+
+fn parse_rows(
+    file_type: &ImportRequisitionFileType,
+    file: &[u8],
+) -> Result<Vec<ImportRow>, OutError> {
+    let cells = match file_type {
+        ImportRequisitionFileType::Csv => read_csv(file),
+        ImportRequisitionFileType::Xlsx => read_xlsx(file),
+    }
+    .map_err(OutError::CannotParseFile)?;
+
+    let mut cells = cells.into_iter();
+    let header: Vec<String> = cells
+        .next()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|column| column.trim().to_lowercase().replace(' ', "_"))
+        .collect();
+
+    let find_column = |names: &[&str]| header.iter().position(|c| names.contains(&c.as_str()));
+    let item_code_column = find_column(ITEM_CODE_COLUMNS).ok_or(OutError::ItemCodeColumnMissing)?;
+    let quantity_column = find_column(QUANTITY_COLUMNS).ok_or(OutError::QuantityColumnMissing)?;
+    let comment_column = find_column(COMMENT_COLUMNS);
+
+    let cell = |row: &Vec<String>, column: usize| {
+        row.get(column)
+            .map(|value| value.trim().to_string())
+            .unwrap_or_default()
+    };
+
+    let rows: Vec<ImportRow> = cells
+        .enumerate()
+        // Header is row 1
+        .map(|(index, row)| (index + 2, row))
+        .filter(|(_, row)| row.iter().any(|value| !value.trim().is_empty()))
+        .map(|(row_number, row)| ImportRow {
+            row_number,
+            item_code: cell(&row, item_code_column),
+            quantity: cell(&row, quantity_column),
+            comment: comment_column
+                .map(|column| cell(&row, column))
+                .filter(|comment| !comment.is_empty()),
+        })
+        .collect();
+
+    if rows.is_empty() {
+        return Err(OutError::NoRowsToImport);
+    }
+
+    Ok(rows)
+}
+
+fn read_csv(file: &[u8]) -> Result<Vec<Vec<String>>, String> {
+    csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(file)
+        .records()
+        .map(|record| {
+            record
+                .map(|record| record.iter().map(str::to_string).collect())
+                .map_err(|error| error.to_string())
+        })
+        .collect()
+}
+
+fn read_xlsx(file: &[u8]) -> Result<Vec<Vec<String>>, String> {
+    let mut workbook: Xlsx<_> =
+        open_workbook_from_rs(Cursor::new(file)).map_err(|error| format!("{}", error))?;
+    let sheet = workbook
+        .worksheet_range_at(0)
+        .ok_or("Workbook has no sheets".to_string())?
+        .map_err(|error| format!("{}", error))?;
+
+    Ok(sheet
+        .rows()
+        .map(|row| row.iter().map(|cell| cell.to_string()).collect())
+        .collect())
+}
+
+fn validate_rows(
+    connection: &StorageConnection,
+    store_id: &str,
+    requisition_row: &RequisitionRow,
+    rows: &[ImportRow],
+) -> Result<Vec<ValidatedRow>, OutError> {
+    let item_codes: Vec<String> = rows.iter().map(|row| row.item_code.clone()).collect();
+    let item_ids_by_code: HashMap<String, String> = ItemRepository::new(connection)
+        .query_by_filter(
+            ItemFilter::new().code(StringFilter::equal_any(item_codes)),
+            Some(store_id.to_string()),
+        )?
+        .into_iter()
+        .map(|item| (item.item_row.code, item.item_row.id))
+        .collect();
+    let importable_item_ids = get_importable_item_ids(connection, store_id, requisition_row)?;
+
+    let mut errors = Vec::new();
+    let mut validated_rows = Vec::new();
+    let mut imported_item_ids = HashSet::new();
+
+    for row in rows {
+        let error = |error| ImportRequisitionRowError {
+            row_number: row.row_number,
+            item_code: row.item_code.clone(),
+            error,
+        };
+
+        if row.item_code.is_empty() {
+            errors.push(error(ImportRequisitionRowErrorKind::ItemCodeMissing));
+            continue;
+        }
+
+        let Some(item_id) = item_ids_by_code.get(&row.item_code) else {
+            errors.push(error(ImportRequisitionRowErrorKind::ItemDoesNotExist));
+            continue;
+        };
+
+        if !importable_item_ids.contains(item_id) {
+            errors.push(error(match requisition_row.program_id {
+                Some(_) => ImportRequisitionRowErrorKind::ItemNotInProgram,
+                None => ImportRequisitionRowErrorKind::ItemNotInMasterList,
+            }));
+            continue;
+        }
+
+        if !imported_item_ids.insert(item_id.clone()) {
+            errors.push(error(ImportRequisitionRowErrorKind::DuplicateItem));
+            continue;
+        }
+
+        let quantity = match parse_quantity(&row.quantity) {
+            Some(quantity) => quantity,
+            None => {
+                errors.push(error(ImportRequisitionRowErrorKind::InvalidQuantity(
+                    row.quantity.clone(),
+                )));
+                continue;
+            }
+        };
+
+        validated_rows.push(ValidatedRow {
+            item_id: item_id.clone(),
+            quantity,
+            comment: row.comment.clone(),
+        });
+    }
+
+    if !errors.is_empty() {
+        return Err(OutError::RowErrors(errors));
+    }
+
+    Ok(validated_rows)
+}
+
+/// Quantity must be a non negative whole number, spreadsheets may store it as a float
+fn parse_quantity(quantity: &str) -> Option<i32> {
+    let quantity: f64 = quantity.parse().ok()?;
+    if !quantity.is_finite()
+        || quantity < 0.0
+        || quantity.fract() != 0.0
+        || quantity > i32::MAX as f64
+    {
+        return None;
+    }
+    Some(quantity as i32)
+}
+
+/// Items on the program master list for program requisitions, otherwise items on master lists
+/// visible to the store (request) or customer (response), same as add from master list
+fn get_importable_item_ids(
+    connection: &StorageConnection,
+    store_id: &str,
+    requisition_row: &RequisitionRow,
+) -> Result<HashSet<String>, RepositoryError> {
+    let master_list_ids = match &requisition_row.program_id {
+        Some(program_id) => ProgramRowRepository::new(connection)
+            .find_one_by_id(program_id)?
+            .map(|program| vec![program.master_list_id])
+            .unwrap_or_default(),
+        None => {
+            let filter = MasterListFilter::new().is_program(false);
+            let filter = match requisition_row.r#type {
+                RequisitionRowType::Request => {
+                    filter.exists_for_store_id(EqualFilter::equal_to(store_id))
+                }
+                RequisitionRowType::Response => {
+                    filter.exists_for_name_id(EqualFilter::equal_to(&requisition_row.name_link_id))
+                }
+            };
+            MasterListRepository::new(connection)
+                .query_by_filter(filter)?
+                .into_iter()
+                .map(|master_list| master_list.id)
+                .collect()
+        }
+    };
+
+    let item_ids = MasterListLineRepository::new(connection)
+        .query_by_filter(
+            MasterListLineFilter::new()
+                .master_list_id(EqualFilter::equal_any(master_list_ids))
+                .item_type(ItemRowType::Stock.equal_to()),
+        )?
+        .into_iter()
+        .map(|master_list_line| master_list_line.item_id)
+        .collect();
+
+    Ok(item_ids)
+}
+
+fn generate_lines(
+    ctx: &ServiceContext,
+    requisition_row: &RequisitionRow,
+    rows: Vec<ValidatedRow>,
+) -> Result<Vec<RequisitionLineRow>, RepositoryError> {
+    let mut line_rows: HashMap<String, RequisitionLineRow> =
+        get_lines_for_requisition(&ctx.connection, &requisition_row.id)?
+            .into_iter()
+            .map(|line| (line.item_row.id, line.requisition_line_row))
+            .collect();
+
+    let new_item_ids: Vec<String> = rows
+        .iter()
+        .filter(|row| !line_rows.contains_key(&row.item_id))
+        .map(|row| row.item_id.clone())
+        .collect();
+
+    match requisition_row.r#type {
+        // Request lines are generated with this store's stats and suggested quantity
+        RequisitionRowType::Request => {
+            for line_row in
+                generate_requisition_lines(ctx, &ctx.store_id, requisition_row, new_item_ids)?
+            {
+                line_rows.insert(line_row.item_link_id.clone(), line_row);
+            }
+        }
+        // Customer stats are not known for imported response requisitions
+        RequisitionRowType::Response => {
+            for item_id in new_item_ids {
+                line_rows.insert(
+                    item_id.clone(),
+                    RequisitionLineRow {
+                        id: uuid(),
+                        requisition_id: requisition_row.id.clone(),
+                        item_link_id: item_id,
+                        snapshot_datetime: Some(Utc::now().naive_utc()),
+                        // Default
+                        requested_quantity: 0,
+                        suggested_quantity: 0,
+                        supply_quantity: 0,
+                        available_stock_on_hand: 0,
+                        average_monthly_consumption: 0,
+                        approved_quantity: 0,
+                        approval_comment: None,
+                        comment: None,
+                    },
+                );
+            }
+        }
+    }
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|row| {
+            let mut line_row = line_rows.remove(&row.item_id)?;
+            line_row.requested_quantity = row.quantity;
+            line_row.comment = row.comment.or(line_row.comment);
+            Some(line_row)
+        })
+        .collect())
+}
+
+impl From<RepositoryError> for ImportRequisitionError {
+    fn from(error: RepositoryError) -> Self {
+        ImportRequisitionError::DatabaseError(error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use repository::{
+        mock::{
+            common::FullMockMasterList, mock_item_a, mock_item_b, mock_name_store_a,
+            mock_name_store_c, mock_store_a, mock_user_account_a, MockData, MockDataInserts,
+        },
+        requisition_row::RequisitionRowType,
+        test_db::setup_all_with_data,
+        MasterListLineRow, MasterListNameJoinRow, MasterListRow,
+    };
+    use util::inline_init;
+
+    use crate::{
+        requisition::{
+            common::get_lines_for_requisition,
+            import::{
+                ImportRequisition, ImportRequisitionError as ServiceError,
+                ImportRequisitionFileType, ImportRequisitionRowError,
+                ImportRequisitionRowErrorKind,
+            },
+            request_requisition::InsertRequestRequisitionError,
+        },
+        service_provider::ServiceProvider,
+    };
+
+    fn master_list() -> FullMockMasterList {
+        FullMockMasterList {
+            master_list: MasterListRow {
+                id: "import_master_list".to_string(),
+                name: "import_master_list".to_string(),
+                code: "import_master_list".to_string(),
+                description: "import_master_list".to_string(),
+                is_active: true,
+            },
+            joins: vec![MasterListNameJoinRow {
+                id: "import_master_list_join".to_string(),
+                master_list_id: "import_master_list".to_string(),
+                name_link_id: mock_name_store_a().id,
+            }],
+            lines: vec![MasterListLineRow {
+                id: "import_master_list_line".to_string(),
+                item_link_id: mock_item_a().id,
+                master_list_id: "import_master_list".to_string(),
+            }],
+        }
+    }
+
+    fn input(file: &str) -> ImportRequisition {
+        ImportRequisition {
+            id: "imported_requisition".to_string(),
+            r#type: RequisitionRowType::Request,
+            other_party_id: mock_name_store_c().id,
+            file_type: ImportRequisitionFileType::Csv,
+            file: file.as_bytes().to_vec(),
+            their_reference: None,
+            comment: None,
+            expected_delivery_date: None,
+            max_months_of_stock: 2.0,
+            min_months_of_stock: 1.0,
+            program_order_type_id: None,
+            period_id: None,
+            emergency_reason: None,
+        }
+    }
+
+    #[actix_rt::test]
+    async fn import_requisition() {
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "import_requisition",
+            MockDataInserts::all(),
+            inline_init(|r: &mut MockData| {
+                r.full_master_lists = vec![master_list()];
+            }),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, mock_user_account_a().id)
+            .unwrap();
+        let service = service_provider.requisition_service;
+
+        // QuantityColumnMissing
+        assert_eq!(
+            service.import_requisition(&context, input("Item Code,Comment\nitem_a_code,a\n")),
+            Err(ServiceError::QuantityColumnMissing)
+        );
+
+        // NoRowsToImport
+        assert_eq!(
+            service.import_requisition(&context, input("code,quantity\n,\n")),
+            Err(ServiceError::NoRowsToImport)
+        );
+
+        // InsertRequestRequisition
+        assert_eq!(
+            service.import_requisition(
+                &context,
+                ImportRequisition {
+                    other_party_id: "invalid".to_string(),
+                    ..input("code,quantity\nitem_a_code,10\n")
+                }
+            ),
+            Err(ServiceError::InsertRequestRequisition(
+                InsertRequestRequisitionError::OtherPartyDoesNotExist
+            ))
+        );
+
+        // RowErrors
+        let file = format!(
+            "code,quantity\n\
+            item_a_code,1.5\n\
+            {},10\n\
+            invalid,10\n\
+            ,10\n\
+            item_a_code,5\n",
+            mock_item_b().code
+        );
+        assert_eq!(
+            service.import_requisition(&context, input(&file)),
+            Err(ServiceError::RowErrors(vec![
+                ImportRequisitionRowError {
+                    row_number: 2,
+                    item_code: mock_item_a().code,
+                    error: ImportRequisitionRowErrorKind::InvalidQuantity("1.5".to_string()),
+                },
+                ImportRequisitionRowError {
+                    row_number: 3,
+                    item_code: mock_item_b().code,
+                    error: ImportRequisitionRowErrorKind::ItemNotInMasterList,
+                },
+                ImportRequisitionRowError {
+                    row_number: 4,
+                    item_code: "invalid".to_string(),
+                    error: ImportRequisitionRowErrorKind::ItemDoesNotExist,
+                },
+                ImportRequisitionRowError {
+                    row_number: 5,
+                    item_code: "".to_string(),
+                    error: ImportRequisitionRowErrorKind::ItemCodeMissing,
+                },
+                ImportRequisitionRowError {
+                    row_number: 6,
+                    item_code: mock_item_a().code,
+                    error: ImportRequisitionRowErrorKind::DuplicateItem,
+                },
+            ]))
+        );
+        // Nothing is saved when rows are invalid
+        assert_eq!(
+            get_lines_for_requisition(&connection, "imported_requisition").unwrap(),
+            vec![]
+        );
+
+        // Success
+        let result = service
+            .import_requisition(
+                &context,
+                input("Item Code,Requested Quantity,Comment\nitem_a_code,10,urgent\n"),
+            )
+            .unwrap();
+        assert_eq!(result.requisition_row.id, "imported_requisition");
+        assert_eq!(result.requisition_row.r#type, RequisitionRowType::Request);
+
+        let lines = get_lines_for_requisition(&connection, "imported_requisition").unwrap();
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].item_row.id, mock_item_a().id);
+        assert_eq!(lines[0].requisition_line_row.requested_quantity, 10);
+        assert_eq!(
+            lines[0].requisition_line_row.comment,
+            Some("urgent".to_string())
+        );
+    }
+}
@@ -1,6 +1,9 @@
 use self::{
+    fill_rate::{get_fill_rates, FillRateFilter, FillRates},
+    import::{import_requisition, ImportRequisition, ImportRequisitionError},
     program_settings::{get_program_requisition_settings, ProgramSettings},
     query::{get_requisition, get_requisition_by_number, get_requisitions},
+    reporting_rate::{get_reporting_rates, ReportingRate, ReportingRateInput},
     request_requisition::{
         add_from_master_list, batch_request_requisition, delete_request_requisition,
         insert_program_request_requisition, insert_request_requisition, update_request_requisition,
@@ -27,8 +30,11 @@ use repository::{
 };
 
 pub mod common;
+pub mod fill_rate;
+pub mod import;
 pub mod program_settings;
 pub mod query;
+pub mod reporting_rate;
 pub mod request_requisition;
 pub mod requisition_supply_status;
 pub mod response_requisition;
@@ -159,6 +165,31 @@ pub trait RequisitionServiceTrait: Sync + Send {
     ) -> Result<Vec<ProgramSettings>, RepositoryError> {
         get_program_requisition_settings(ctx, store_id)
     }
+
+    fn get_reporting_rates(
+        &self,
+        ctx: &ServiceContext,
+        input: ReportingRateInput,
+    ) -> Result<Vec<ReportingRate>, RepositoryError> {
+        get_reporting_rates(ctx, input)
+    }
+
+    fn get_fill_rates(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+        filter: FillRateFilter,
+    ) -> Result<FillRates, RepositoryError> {
+        get_fill_rates(ctx, store_id, filter)
+    }
+
+    fn import_requisition(
+        &self,
+        ctx: &ServiceContext,
+        input: ImportRequisition,
+    ) -> Result<Requisition, ImportRequisitionError> {
+        import_requisition(ctx, input)
+    }
 }
 
 pub struct RequisitionService {}
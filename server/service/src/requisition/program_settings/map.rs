@@ -73,6 +73,7 @@ pub(super) fn map(
 /// Deduce if period is available for order_type based on
 /// matching period_schedule_id and number of requisition that exists for this
 /// order_type and program_id is within order_type.max_order_per_period
+/// emergency order types are available for any period in the schedule
 /// note: lowercase match for order type
 fn period_is_available(
     period: &PeriodRow,
@@ -84,6 +85,10 @@ fn period_is_available(
         return false;
     }
 
+    if order_type.is_emergency {
+        return true;
+    }
+
     // requisitions_in_period already has a count of how many requisitions are in a period
     // there should only be one requistions_in_period entry for one program period, see
     // requisitions_in_period view
@@ -61,12 +61,14 @@ pub(super) fn prepare(
 
     let period_ids = periods.iter().map(|p| p.id.clone()).collect();
 
-    // Requisitions in Period (for all periods and store)
+    // Requisitions in Period (for all periods and store), emergency requisitions are not
+    // counted towards max_order_per_period
     let filter = RequisitionsInPeriodFilter::new()
         .store_id(equal_to_store_id)
         .program_id(EqualFilter::equal_any(program_ids.clone()))
         .period_id(EqualFilter::equal_any(period_ids))
-        .r#type(RequisitionRowType::Request.equal_to());
+        .r#type(RequisitionRowType::Request.equal_to())
+        .is_emergency(false);
 
     let requisitions_in_periods =
         RequisitionsInPeriodRepository::new(&ctx.connection).query(filter)?;
@@ -0,0 +1,531 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{Duration, NaiveDate, NaiveDateTime, Utc};
+use repository::{
+    EqualFilter, MasterListLineFilter, MasterListLineRepository, NameTagRow, NameTagRowRepository,
+    PeriodRow, PeriodRowRepository, Permission, ProgramRequisitionSettingsRepository,
+    RepositoryError, RequisitionFilter, RequisitionLineFilter, RequisitionLineRepository,
+    RequisitionRepository, RequisitionRowStatus, RequisitionRowType, RequisitionsInPeriodFilter,
+    RequisitionsInPeriodRepository, Store, StoreFilter, StoreRepository, UserPermissionFilter,
+    UserPermissionRepository,
+};
+
+use crate::service_provider::ServiceContext;
+
+/// Number of days after the end of a period that a requisition can be sent and still be on time
+pub const DEFAULT_REPORTING_DEADLINE_DAYS: u32 = 10;
+
+#[derive(Debug, Default, Clone)]
+pub struct ReportingRateInput {
+    pub program_id: Option<String>,
+    pub name_tag_id: Option<String>,
+    /// When not specified, all periods of the program schedule that have started
+    pub period_ids: Option<Vec<String>>,
+    /// Defaults to DEFAULT_REPORTING_DEADLINE_DAYS
+    pub deadline_days: Option<u32>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct StoreReportingStatus {
+    pub store: Store,
+    /// True if there is a (non emergency) request requisition for the period in any status
+    pub has_requisition: bool,
+    /// Earliest sent datetime of request requisitions for the period
+    pub sent_datetime: Option<NaiveDateTime>,
+    pub is_on_time: bool,
+    /// Ratio of program master list items present in sent requisitions
+    pub completeness: f64,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ReportingRate {
+    pub program_id: String,
+    pub program_name: String,
+    pub name_tag: NameTagRow,
+    pub period: PeriodRow,
+    pub deadline: NaiveDate,
+    pub stores: Vec<StoreReportingStatus>,
+}
+
+impl ReportingRate {
+    pub fn expected_count(&self) -> i64 {
+        self.stores.len() as i64
+    }
+
+    pub fn submitted_count(&self) -> i64 {
+        self.stores
+            .iter()
+            .filter(|s| s.sent_datetime.is_some())
+            .count() as i64
+    }
+
+    pub fn on_time_count(&self) -> i64 {
+        self.stores.iter().filter(|s| s.is_on_time).count() as i64
+    }
+
+    /// Submitted / expected
+    pub fn reporting_rate(&self) -> f64 {
+        ratio(self.submitted_count(), self.expected_count())
+    }
+
+    /// Submitted on time / expected
+    pub fn on_time_rate(&self) -> f64 {
+        ratio(self.on_time_count(), self.expected_count())
+    }
+
+    /// Average completeness of submitted requisitions
+    pub fn completeness(&self) -> f64 {
+        let submitted: Vec<&StoreReportingStatus> = self
+            .stores
+            .iter()
+            .filter(|s| s.sent_datetime.is_some())
+            .collect();
+
+        if submitted.is_empty() {
+            return 0.0;
+        }
+
+        submitted.iter().map(|s| s.completeness).sum::<f64>() / submitted.len() as f64
+    }
+}
+
+fn ratio(numerator: i64, denominator: i64) -> f64 {
+    if denominator == 0 {
+        return 0.0;
+    }
+    numerator as f64 / denominator as f64
+}
+
+/// Reporting rates are calculated for each program requisition settings (program and name tag)
+/// and each period in the program's period schedule.
+/// Stores are expected to report if their name has the settings name tag, a request requisition
+/// is submitted once it's sent (status Sent or Finalised), emergency requisitions are not counted.
+/// Only stores where the user (of the service context) can query requisitions are included
+pub fn get_reporting_rates(
+    ctx: &ServiceContext,
+    input: ReportingRateInput,
+) -> Result<Vec<ReportingRate>, RepositoryError> {
+    let connection = &ctx.connection;
+    let deadline_days = input
+        .deadline_days
+        .unwrap_or(DEFAULT_REPORTING_DEADLINE_DAYS);
+    let today = Utc::now().naive_utc().date();
+    let permitted_store_ids: Vec<String> = UserPermissionRepository::new(connection)
+        .query_by_filter(
+            UserPermissionFilter::new()
+                .user_id(EqualFilter::equal_to(&ctx.user_id))
+                .permission(Permission::RequisitionQuery.equal_to()),
+        )?
+        .into_iter()
+        .filter_map(|permission| permission.store_id)
+        .collect();
+
+    let settings = ProgramRequisitionSettingsRepository::new(connection)
+        .query(None)?
+        .into_iter()
+        .filter(|s| match &input.program_id {
+            Some(program_id) => &s.program_row.id == program_id,
+            None => true,
+        })
+        .filter(|s| match &input.name_tag_id {
+            Some(name_tag_id) => &s.program_settings_row.name_tag_id == name_tag_id,
+            None => true,
+        });
+
+    let mut result = Vec::new();
+    for settings in settings {
+        let program_id = settings.program_row.id.clone();
+        let Some(name_tag) = NameTagRowRepository::new(connection)
+            .find_one_by_id(&settings.program_settings_row.name_tag_id)?
+        else {
+            continue;
+        };
+
+        let mut periods: Vec<PeriodRow> = PeriodRowRepository::new(connection)
+            .find_many_by_program_schedule_ids(vec![
+                &settings.program_settings_row.period_schedule_id,
+            ])?
+            .into_iter()
+            .filter(|p| match &input.period_ids {
+                Some(period_ids) => period_ids.contains(&p.id),
+                None => p.start_date <= today,
+            })
+            .collect();
+        periods.sort_by_key(|a| a.start_date);
+
+        if periods.is_empty() {
+            continue;
+        }
+
+        let stores = StoreRepository::new(connection).query_by_filter(
+            StoreFilter::new()
+                .name_tag_id(EqualFilter::equal_to(&name_tag.id))
+                .id(EqualFilter::equal_any(permitted_store_ids.clone())),
+        )?;
+
+        let store_ids: Vec<String> = stores.iter().map(|s| s.store_row.id.clone()).collect();
+        let period_ids: Vec<String> = periods.iter().map(|p| p.id.clone()).collect();
+
+        // Stores that have started a requisition for the period
+        let started: HashSet<(String, String)> = RequisitionsInPeriodRepository::new(connection)
+            .query(
+                RequisitionsInPeriodFilter::new()
+                    .program_id(EqualFilter::equal_to(&program_id))
+                    .period_id(EqualFilter::equal_any(period_ids.clone()))
+                    .store_id(EqualFilter::equal_any(store_ids.clone()))
+                    .r#type(RequisitionRowType::Request.equal_to())
+                    .is_emergency(false),
+            )?
+            .into_iter()
+            .filter(|r| r.count > 0)
+            .map(|r| (r.store_id, r.period_id))
+            .collect();
+
+        let sent_requisitions = RequisitionRepository::new(connection).query_by_filter(
+            RequisitionFilter::new()
+                .program_id(EqualFilter::equal_to(&program_id))
+                .period_id(EqualFilter::equal_any(period_ids))
+                .store_id(EqualFilter::equal_any(store_ids))
+                .r#type(RequisitionRowType::Request.equal_to())
+                .status(RequisitionRowStatus::equal_any(vec![
+                    RequisitionRowStatus::Sent,
+                    RequisitionRowStatus::Finalised,
+                ]))
+                .is_emergency(false),
+        )?;
+
+        let program_item_ids: HashSet<String> = MasterListLineRepository::new(connection)
+            .query_by_filter(
+                MasterListLineFilter::new()
+                    .master_list_id(EqualFilter::equal_to(&settings.master_list.id)),
+            )?
+            .into_iter()
+            .map(|l| l.item_id)
+            .collect();
+
+        let requisition_lines = RequisitionLineRepository::new(connection).query_by_filter(
+            RequisitionLineFilter::new().requisition_id(EqualFilter::equal_any(
+                sent_requisitions
+                    .iter()
+                    .map(|r| r.requisition_row.id.clone())
+                    .collect(),
+            )),
+        )?;
+
+        // Earliest sent datetime and requested program items, by store and period
+        let mut submissions: HashMap<(String, String), (NaiveDateTime, HashSet<String>)> =
+            HashMap::new();
+        let requisition_store_and_period: HashMap<String, (String, String)> = sent_requisitions
+            .iter()
+            .filter_map(|r| {
+                let row = &r.requisition_row;
+                Some((
+                    row.id.clone(),
+                    (row.store_id.clone(), row.period_id.clone()?),
+                ))
+            })
+            .collect();
+
+        for requisition in sent_requisitions {
+            let row = requisition.requisition_row;
+            let (Some(period_id), Some(sent_datetime)) = (row.period_id, row.sent_datetime) else {
+                continue;
+            };
+            let entry = submissions
+                .entry((row.store_id, period_id))
+                .or_insert((sent_datetime, HashSet::new()));
+            if sent_datetime < entry.0 {
+                entry.0 = sent_datetime;
+            }
+        }
+
+        for line in requisition_lines {
+            if !program_item_ids.contains(&line.item_row.id) {
+                continue;
+            }
+            let Some(key) =
+                requisition_store_and_period.get(&line.requisition_line_row.requisition_id)
+            else {
+                continue;
+            };
+            if let Some((_, item_ids)) = submissions.get_mut(key) {
+                item_ids.insert(line.item_row.id);
+            }
+        }
+
+        for period in periods {
+            let deadline = period.end_date + Duration::days(deadline_days as i64);
+
+            let store_statuses = stores
+                .iter()
+                .map(|store| {
+                    let key = (store.store_row.id.clone(), period.id.clone());
+                    let submission = submissions.get(&key);
+                    let sent_datetime = submission.map(|(sent_datetime, _)| *sent_datetime);
+
+                    StoreReportingStatus {
+                        store: store.clone(),
+                        has_requisition: started.contains(&key),
+                        sent_datetime,
+                        is_on_time: sent_datetime
+                            .map(|sent_datetime| sent_datetime.date() <= deadline)
+                            .unwrap_or(false),
+                        completeness: match submission {
+                            Some(_) if program_item_ids.is_empty() => 1.0,
+                            Some((_, item_ids)) => {
+                                item_ids.len() as f64 / program_item_ids.len() as f64
+                            }
+                            None => 0.0,
+                        },
+                    }
+                })
+                .collect();
+
+            result.push(ReportingRate {
+                program_id: program_id.clone(),
+                program_name: settings.program_row.name.clone(),
+                name_tag: name_tag.clone(),
+                period,
+                deadline,
+                stores: store_statuses,
+            });
+        }
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+    use repository::{
+        mock::{
+            common::FullMockMasterList, context_program_a, mock_item_a, mock_item_b,
+            mock_name_store_a, mock_name_store_b, mock_store_a, mock_store_b, mock_user_account_a,
+            mock_user_account_b, MockData, MockDataInserts,
+        },
+        test_db::setup_all_with_data,
+        MasterListLineRow, MasterListNameJoinRow, MasterListRow, NameTagJoinRow, NameTagRow,
+        PeriodRow, PeriodScheduleRow, Permission, ProgramRequisitionSettingsRow, ProgramRow,
+        RequisitionLineRow, RequisitionRow, RequisitionRowRepository, RequisitionRowStatus,
+        RequisitionRowType, UserPermissionRow,
+    };
+    use util::inline_init;
+
+    use crate::{
+        requisition::reporting_rate::ReportingRateInput, service_provider::ServiceProvider,
+    };
+
+    #[actix_rt::test]
+    async fn reporting_rate() {
+        let master_list = MasterListRow {
+            id: "reporting_master_list".to_string(),
+            name: "reporting_master_list".to_string(),
+            code: "reporting_master_list".to_string(),
+            description: "reporting_master_list".to_string(),
+            is_active: true,
+        };
+        let program = ProgramRow {
+            id: "reporting_program".to_string(),
+            master_list_id: master_list.id.clone(),
+            name: "reporting_program".to_string(),
+            context_id: context_program_a().id,
+        };
+        let name_tag = NameTagRow {
+            id: "reporting_name_tag".to_string(),
+            name: "reporting_name_tag".to_string(),
+        };
+        let period_schedule = PeriodScheduleRow {
+            id: "reporting_schedule".to_string(),
+            name: "reporting_schedule".to_string(),
+        };
+        let period = PeriodRow {
+            id: "reporting_period".to_string(),
+            period_schedule_id: period_schedule.id.clone(),
+            name: "reporting_period".to_string(),
+            start_date: NaiveDate::from_ymd_opt(2020, 1, 1).unwrap(),
+            end_date: NaiveDate::from_ymd_opt(2020, 1, 31).unwrap(),
+        };
+        let settings = ProgramRequisitionSettingsRow {
+            id: "reporting_settings".to_string(),
+            name_tag_id: name_tag.id.clone(),
+            program_id: program.id.clone(),
+            period_schedule_id: period_schedule.id.clone(),
+        };
+        // Store a sent a requisition on time, with one of the two program items
+        let requisition = inline_init(|r: &mut RequisitionRow| {
+            r.id = "reporting_requisition".to_string();
+            r.name_link_id = mock_name_store_b().id;
+            r.store_id = mock_store_a().id;
+            r.r#type = RequisitionRowType::Request;
+            r.status = RequisitionRowStatus::Sent;
+            r.program_id = Some(program.id.clone());
+            r.period_id = Some(period.id.clone());
+            r.order_type = Some("reporting_order_type".to_string());
+            r.created_datetime = NaiveDate::from_ymd_opt(2020, 2, 1)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap();
+            r.sent_datetime = Some(
+                NaiveDate::from_ymd_opt(2020, 2, 5)
+                    .unwrap()
+                    .and_hms_opt(0, 0, 0)
+                    .unwrap(),
+            );
+        });
+
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "reporting_rate",
+            MockDataInserts::all(),
+            inline_init(|r: &mut MockData| {
+                r.full_master_lists = vec![FullMockMasterList {
+                    master_list: master_list.clone(),
+                    joins: vec![MasterListNameJoinRow {
+                        id: "reporting_master_list_name_join".to_string(),
+                        master_list_id: master_list.id.clone(),
+                        name_link_id: mock_name_store_a().id,
+                    }],
+                    lines: vec![
+                        MasterListLineRow {
+                            id: "reporting_master_list_line_a".to_string(),
+                            item_link_id: mock_item_a().id,
+                            master_list_id: master_list.id.clone(),
+                        },
+                        MasterListLineRow {
+                            id: "reporting_master_list_line_b".to_string(),
+                            item_link_id: mock_item_b().id,
+                            master_list_id: master_list.id.clone(),
+                        },
+                    ],
+                }];
+                r.programs = vec![program.clone()];
+                r.name_tags = vec![name_tag.clone()];
+                r.name_tag_joins = vec![
+                    NameTagJoinRow {
+                        id: "reporting_name_tag_join_a".to_string(),
+                        name_link_id: mock_name_store_a().id,
+                        name_tag_id: name_tag.id.clone(),
+                    },
+                    NameTagJoinRow {
+                        id: "reporting_name_tag_join_b".to_string(),
+                        name_link_id: mock_name_store_b().id,
+                        name_tag_id: name_tag.id.clone(),
+                    },
+                ];
+                r.period_schedules = vec![period_schedule.clone()];
+                r.periods = vec![period.clone()];
+                r.program_requisition_settings = vec![settings.clone()];
+                r.requisitions = vec![requisition.clone()];
+                r.requisition_lines = vec![inline_init(|r: &mut RequisitionLineRow| {
+                    r.id = "reporting_requisition_line".to_string();
+                    r.requisition_id = requisition.id.clone();
+                    r.item_link_id = mock_item_a().id;
+                })];
+                // User a can already query requisitions in store a
+                r.user_permissions = vec![UserPermissionRow {
+                    id: "reporting_permission_store_b".to_string(),
+                    user_id: mock_user_account_a().id,
+                    store_id: Some(mock_store_b().id),
+                    permission: Permission::RequisitionQuery,
+                    context_id: None,
+                }];
+            }),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, mock_user_account_a().id)
+            .unwrap();
+        let service = &service_provider.requisition_service;
+
+        let result = service
+            .get_reporting_rates(
+                &context,
+                ReportingRateInput {
+                    program_id: Some(program.id.clone()),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        assert_eq!(result.len(), 1);
+        let rate = &result[0];
+        assert_eq!(rate.period, period);
+        assert_eq!(rate.name_tag, name_tag);
+        assert_eq!(rate.expected_count(), 2);
+        assert_eq!(rate.submitted_count(), 1);
+        assert_eq!(rate.on_time_count(), 1);
+        assert_eq!(rate.reporting_rate(), 0.5);
+        assert_eq!(rate.on_time_rate(), 0.5);
+        assert_eq!(rate.completeness(), 0.5);
+
+        let store_a = rate
+            .stores
+            .iter()
+            .find(|s| s.store.store_row.id == mock_store_a().id)
+            .unwrap();
+        assert!(store_a.has_requisition);
+        let store_b = rate
+            .stores
+            .iter()
+            .find(|s| s.store.store_row.id == mock_store_b().id)
+            .unwrap();
+        assert!(!store_b.has_requisition);
+        assert_eq!(store_b.sent_datetime, None);
+
+        // Shorter deadline, sent requisition is late
+        let result = service
+            .get_reporting_rates(
+                &context,
+                ReportingRateInput {
+                    program_id: Some(program.id.clone()),
+                    deadline_days: Some(1),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        assert_eq!(result[0].submitted_count(), 1);
+        assert_eq!(result[0].on_time_count(), 0);
+
+        // Not sent requisitions are not counted as submitted
+        RequisitionRowRepository::new(&connection)
+            .upsert_one(&inline_init(|r: &mut RequisitionRow| {
+                *r = requisition.clone();
+                r.status = RequisitionRowStatus::Draft;
+                r.sent_datetime = None;
+            }))
+            .unwrap();
+
+        let result = service
+            .get_reporting_rates(
+                &context,
+                ReportingRateInput {
+                    program_id: Some(program.id.clone()),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        assert_eq!(result[0].submitted_count(), 0);
+        assert_eq!(result[0].reporting_rate(), 0.0);
+        assert_eq!(result[0].completeness(), 0.0);
+
+        // User b can't query requisitions in any of the stores
+        let context = service_provider
+            .context(mock_store_a().id, mock_user_account_b().id)
+            .unwrap();
+        let result = service
+            .get_reporting_rates(
+                &context,
+                ReportingRateInput {
+                    program_id: Some(program.id.clone()),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        assert_eq!(result[0].expected_count(), 0);
+    }
+}
@@ -1,5 +1,7 @@
 use crate::{
-    requisition::common::{check_requisition_row_exists, get_lines_for_requisition},
+    requisition::common::{
+        check_requisition_row_exists, get_emergency_order_max_items, get_lines_for_requisition,
+    },
     service_provider::ServiceContext,
 };
 use repository::{
@@ -25,6 +27,7 @@ pub enum AddFromMasterListError {
     CannotEditRequisition,
     MasterListNotFoundForThisStore,
     NotARequestRequisition,
+    MaxItemsReachedForEmergencyOrder,
     DatabaseError(RepositoryError),
 }
 
@@ -38,6 +41,8 @@ pub fn add_from_master_list(
         .connection
         .transaction_sync(|connection| {
             let requisition_row = validate(connection, &ctx.store_id, &input)?;
+            let emergency_order_max_items =
+                get_emergency_order_max_items(connection, &requisition_row)?;
             let new_requisition_line_rows = generate(ctx, &ctx.store_id, requisition_row, &input)?;
 
             let requisition_line_row_repository = RequisitionLineRowRepository::new(&connection);
@@ -50,7 +55,13 @@ pub fn add_from_master_list(
                 RequisitionLineFilter::new()
                     .requisition_id(EqualFilter::equal_to(&input.request_requisition_id)),
             ) {
-                Ok(lines) => Ok(lines),
+                // Transaction is rolled back if emergency order would exceed max items
+                Ok(lines) => match emergency_order_max_items {
+                    Some(max_items) if lines.len() > max_items as usize => {
+                        Err(OutError::MaxItemsReachedForEmergencyOrder)
+                    }
+                    _ => Ok(lines),
+                },
                 Err(error) => Err(OutError::DatabaseError(error)),
             }
         })
@@ -1,15 +1,23 @@
 use chrono::Utc;
-use repository::{EqualFilter, RepositoryError, RequisitionLineRow, RequisitionRow};
+use repository::{
+    EqualFilter, RepositoryError, RequisitionLineRow, RequisitionRow, StorageConnection,
+};
 use util::uuid::uuid;
 
 use crate::item_stats::{get_item_stats, ItemStatsFilter};
+use crate::requisition::fill_rate::get_supplier_lead_time_months;
 use crate::service_provider::ServiceContext;
+use crate::store_preference::get_store_preferences;
 
 pub struct GenerateSuggestedQuantity {
     pub average_monthly_consumption: i32,
     pub available_stock_on_hand: i32,
     pub min_months_of_stock: f64,
     pub max_months_of_stock: f64,
+    /// Measured supplier lead time, stock consumed while waiting for delivery is added to
+    /// both the threshold and the target months of stock. None when lead time is not used by
+    /// the store (`use_lead_time_in_suggested_quantity` preference) or was never measured
+    pub lead_time_months: Option<f64>,
 }
 
 pub fn generate_suggested_quantity(
@@ -18,11 +26,13 @@ pub fn generate_suggested_quantity(
         available_stock_on_hand,
         min_months_of_stock,
         max_months_of_stock,
+        lead_time_months,
     }: GenerateSuggestedQuantity,
 ) -> i32 {
     if average_monthly_consumption == 0 {
         return 0;
     }
+    let lead_time_months = lead_time_months.unwrap_or(0.0);
     let months_of_stock = available_stock_on_hand as f64 / average_monthly_consumption as f64;
 
     let default_min_months_of_stock = if min_months_of_stock == 0.0 {
@@ -31,11 +41,26 @@ pub fn generate_suggested_quantity(
         min_months_of_stock
     };
 
-    if max_months_of_stock == 0.0 || (months_of_stock > default_min_months_of_stock) {
+    if max_months_of_stock == 0.0
+        || (months_of_stock > default_min_months_of_stock + lead_time_months)
+    {
         return 0;
     }
 
-    ((max_months_of_stock - months_of_stock) * average_monthly_consumption as f64) as i32
+    ((max_months_of_stock + lead_time_months - months_of_stock)
+        * average_monthly_consumption as f64) as i32
+}
+
+/// Supplier lead time of the requisition, if the store uses lead time in suggested quantity
+pub(crate) fn get_lead_time_months(
+    connection: &StorageConnection,
+    requisition_row: &RequisitionRow,
+) -> Result<Option<f64>, RepositoryError> {
+    let store_id = &requisition_row.store_id;
+    if !get_store_preferences(connection, store_id)?.use_lead_time_in_suggested_quantity {
+        return Ok(None);
+    }
+    get_supplier_lead_time_months(connection, store_id, &requisition_row.name_link_id)
 }
 
 pub fn generate_requisition_lines(
@@ -44,6 +69,8 @@ pub fn generate_requisition_lines(
     requisition_row: &RequisitionRow,
     item_ids: Vec<String>,
 ) -> Result<Vec<RequisitionLineRow>, RepositoryError> {
+    let lead_time_months = get_lead_time_months(&ctx.connection, requisition_row)?;
+
     let item_stats_rows = get_item_stats(
         ctx,
         store_id,
@@ -61,6 +88,7 @@ pub fn generate_requisition_lines(
                 available_stock_on_hand,
                 min_months_of_stock: requisition_row.min_months_of_stock,
                 max_months_of_stock: requisition_row.max_months_of_stock,
+                lead_time_months,
             });
 
             RequisitionLineRow {
@@ -83,3 +111,38 @@ pub fn generate_requisition_lines(
 
     Ok(result)
 }
+
+#[cfg(test)]
+mod test {
+    use super::{generate_suggested_quantity, GenerateSuggestedQuantity};
+
+    fn suggested_quantity(
+        available_stock_on_hand: i32,
+        min_months_of_stock: f64,
+        lead_time_months: Option<f64>,
+    ) -> i32 {
+        generate_suggested_quantity(GenerateSuggestedQuantity {
+            average_monthly_consumption: 30,
+            available_stock_on_hand,
+            min_months_of_stock,
+            max_months_of_stock: 2.0,
+            lead_time_months,
+        })
+    }
+
+    #[test]
+    fn suggested_quantity_lead_time() {
+        // Missing and zero lead time don't change suggested quantity
+        assert_eq!(suggested_quantity(30, 0.0, None), 30);
+        assert_eq!(suggested_quantity(30, 0.0, Some(0.0)), 30);
+        assert_eq!(suggested_quantity(60, 0.0, None), 0);
+        assert_eq!(suggested_quantity(45, 1.0, None), 0);
+
+        // Fractional lead time (half a month) is added to target months of stock
+        assert_eq!(suggested_quantity(30, 0.0, Some(0.5)), 45);
+        assert_eq!(suggested_quantity(60, 0.0, Some(0.5)), 15);
+        // and to threshold months of stock
+        assert_eq!(suggested_quantity(45, 1.0, Some(0.5)), 30);
+        assert_eq!(suggested_quantity(46, 1.0, Some(0.5)), 0);
+    }
+}
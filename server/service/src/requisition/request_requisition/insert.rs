@@ -139,6 +139,8 @@ fn generate(
         program_id: None,
         period_id: None,
         order_type: None,
+        is_emergency: false,
+        emergency_reason: None,
     };
 
     Ok(result)
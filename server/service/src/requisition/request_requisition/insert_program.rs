@@ -25,6 +25,7 @@ pub enum InsertProgramRequestRequisitionError {
     // Program validation
     ProgramOrderTypeDoesNotExist,
     MaxOrdersReachedForPeriod,
+    EmergencyReasonRequired,
     // Internal
     NewlyCreatedRequisitionDoesNotExist,
     DatabaseError(RepositoryError),
@@ -40,6 +41,8 @@ pub struct InsertProgramRequestRequisition {
     pub expected_delivery_date: Option<NaiveDate>,
     pub program_order_type_id: String,
     pub period_id: String,
+    /// Required when program order type is an emergency order type
+    pub emergency_reason: Option<String>,
 }
 
 type OutError = InsertProgramRequestRequisitionError;
@@ -104,6 +107,16 @@ fn validate(
         return Err(OutError::MaxOrdersReachedForPeriod);
     }
 
+    if order_type.order_type.is_emergency
+        && input
+            .emergency_reason
+            .as_ref()
+            .map(|reason| reason.trim().is_empty())
+            .unwrap_or(true)
+    {
+        return Err(OutError::EmergencyReasonRequired);
+    }
+
     if program_setting
         .suppliers
         .iter()
@@ -135,6 +148,7 @@ fn generate(
         expected_delivery_date,
         program_order_type_id: _,
         period_id,
+        emergency_reason,
     }: InsertProgramRequestRequisition,
 ) -> Result<(RequisitionRow, Vec<RequisitionLineRow>), RepositoryError> {
     let connection = &ctx.connection;
@@ -161,6 +175,8 @@ fn generate(
         program_id: Some(program.id),
         period_id: Some(period_id),
         order_type: Some(order_type.name),
+        is_emergency: order_type.is_emergency,
+        emergency_reason: order_type.is_emergency.then(|| emergency_reason).flatten(),
         // Default
         sent_datetime: None,
         approval_status: None,
@@ -168,6 +184,11 @@ fn generate(
         linked_requisition_id: None,
     };
 
+    // Emergency orders start empty, items are added individually (up to max_items_in_emergency_order)
+    if requisition.is_emergency {
+        return Ok((requisition, Vec::new()));
+    }
+
     let program_item_ids: Vec<String> = MasterListLineRepository::new(connection)
         .query_by_filter(
             MasterListLineFilter::new()
@@ -191,6 +212,9 @@ impl From<RepositoryError> for InsertProgramRequestRequisitionError {
 
 #[cfg(test)]
 mod test_insert {
+    use crate::requisition_line::request_requisition_line::{
+        InsertRequestRequisitionLine, InsertRequestRequisitionLineError,
+    };
     use crate::{
         requisition::request_requisition::{
             InsertProgramRequestRequisition, InsertProgramRequestRequisitionError as ServiceError,
@@ -199,13 +223,14 @@ mod test_insert {
     };
     use repository::{
         mock::{
-            mock_name_store_b, mock_period, mock_program_a, mock_program_order_types_a,
+            mock_item_a, mock_name_store_b, mock_name_tag_2, mock_period, mock_program_a,
+            mock_program_order_types_a, mock_program_requisition_setting_a,
             mock_request_draft_requisition, mock_user_account_a, program_master_list_store,
             MockData, MockDataInserts,
         },
         test_db::{setup_all, setup_all_with_data},
-        EqualFilter, NameRow, RequisitionLineFilter, RequisitionLineRepository,
-        RequisitionRowRepository,
+        EqualFilter, NameRow, ProgramRequisitionOrderTypeRow, ProgramRequisitionSettingsRow,
+        RequisitionLineFilter, RequisitionLineRepository, RequisitionRowRepository,
     };
     use util::inline_init;
 
@@ -329,4 +354,139 @@ mod test_insert {
             Err(ServiceError::MaxOrdersReachedForPeriod)
         );
     }
+
+    #[actix_rt::test]
+    async fn insert_program_emergency_request_requisition() {
+        // Would not be available for any period if it wasn't an emergency order type
+        fn emergency_order_type() -> ProgramRequisitionOrderTypeRow {
+            ProgramRequisitionOrderTypeRow {
+                id: "emergency_order_type".to_string(),
+                program_requisition_settings_id: mock_program_requisition_setting_a().id,
+                name: "Emergency".to_string(),
+                threshold_mos: 2.0,
+                max_mos: 4.0,
+                max_order_per_period: 0,
+                is_emergency: true,
+                max_items_in_emergency_order: 1,
+            }
+        }
+        // Settings of the same program for stores with another name tag, with a different cap
+        fn other_settings() -> ProgramRequisitionSettingsRow {
+            ProgramRequisitionSettingsRow {
+                id: "other_settings".to_string(),
+                name_tag_id: mock_name_tag_2().id,
+                ..mock_program_requisition_setting_a()
+            }
+        }
+        fn other_emergency_order_type() -> ProgramRequisitionOrderTypeRow {
+            ProgramRequisitionOrderTypeRow {
+                id: "other_emergency_order_type".to_string(),
+                program_requisition_settings_id: other_settings().id,
+                max_items_in_emergency_order: 5,
+                ..emergency_order_type()
+            }
+        }
+
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "insert_program_emergency_request_requisition",
+            MockDataInserts::all(),
+            inline_init(|r: &mut MockData| {
+                r.program_requisition_settings = vec![other_settings()];
+                r.program_order_types = vec![other_emergency_order_type(), emergency_order_type()];
+            }),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(program_master_list_store().id, mock_user_account_a().id)
+            .unwrap();
+        let service = service_provider.requisition_service;
+        let line_service = service_provider.requisition_line_service;
+
+        // EmergencyReasonRequired
+        assert_eq!(
+            service.insert_program_request_requisition(
+                &context,
+                inline_init(|r: &mut InsertProgramRequestRequisition| {
+                    r.id = "emergency_requisition".to_owned();
+                    r.other_party_id = mock_name_store_b().id.clone();
+                    r.program_order_type_id = emergency_order_type().id;
+                    r.period_id = mock_period().id;
+                    r.emergency_reason = Some(" ".to_owned());
+                })
+            ),
+            Err(ServiceError::EmergencyReasonRequired)
+        );
+
+        // Success, emergency orders are not limited by max_order_per_period
+        for id in ["emergency_requisition", "emergency_requisition2"] {
+            let result = service
+                .insert_program_request_requisition(
+                    &context,
+                    inline_init(|r: &mut InsertProgramRequestRequisition| {
+                        r.id = id.to_owned();
+                        r.other_party_id = mock_name_store_b().id.clone();
+                        r.program_order_type_id = emergency_order_type().id;
+                        r.period_id = mock_period().id;
+                        r.emergency_reason = Some("Stock out".to_owned());
+                    }),
+                )
+                .unwrap();
+
+            let new_row = RequisitionRowRepository::new(&connection)
+                .find_one_by_id(&result.requisition_row.id)
+                .unwrap()
+                .unwrap();
+            assert!(new_row.is_emergency);
+            assert_eq!(new_row.emergency_reason, Some("Stock out".to_owned()));
+        }
+
+        // Emergency requisitions start empty
+        let requisition_lines = RequisitionLineRepository::new(&connection)
+            .query_by_filter(
+                RequisitionLineFilter::new()
+                    .requisition_id(EqualFilter::equal_to("emergency_requisition")),
+            )
+            .unwrap();
+        assert_eq!(requisition_lines.len(), 0);
+
+        // Only items on the program master list can be added
+        assert_eq!(
+            line_service.insert_request_requisition_line(
+                &context,
+                inline_init(|r: &mut InsertRequestRequisitionLine| {
+                    r.id = "emergency_line1".to_owned();
+                    r.requisition_id = "emergency_requisition".to_owned();
+                    r.item_id = mock_item_a().id;
+                }),
+            ),
+            Err(InsertRequestRequisitionLineError::ItemNotInProgramMasterList)
+        );
+
+        // Items can be added up to max_items_in_emergency_order (of the settings for the store)
+        line_service
+            .insert_request_requisition_line(
+                &context,
+                inline_init(|r: &mut InsertRequestRequisitionLine| {
+                    r.id = "emergency_line1".to_owned();
+                    r.requisition_id = "emergency_requisition".to_owned();
+                    r.item_id = "item_query_test1".to_owned();
+                    r.requested_quantity = Some(10);
+                }),
+            )
+            .unwrap();
+
+        assert_eq!(
+            line_service.insert_request_requisition_line(
+                &context,
+                inline_init(|r: &mut InsertRequestRequisitionLine| {
+                    r.id = "emergency_line2".to_owned();
+                    r.requisition_id = "emergency_requisition".to_owned();
+                    r.item_id = mock_item_a().id;
+                }),
+            ),
+            Err(InsertRequestRequisitionLineError::MaxItemsReachedForEmergencyOrder)
+        );
+    }
 }
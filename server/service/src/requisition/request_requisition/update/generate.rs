@@ -1,7 +1,9 @@
 use super::{UpdateRequestRequisition, UpdateRequestRequisitionStatus};
 use crate::requisition::{
     common::get_lines_for_requisition,
-    request_requisition::{generate_suggested_quantity, GenerateSuggestedQuantity},
+    request_requisition::{
+        generate_suggested_quantity, get_lead_time_months, GenerateSuggestedQuantity,
+    },
 };
 use chrono::Utc;
 use repository::{
@@ -65,12 +67,7 @@ pub fn generate(
     });
 
     let updated_requisition_lines = if should_recalculate {
-        generate_updated_lines(
-            connection,
-            &updated_requisition_row.id,
-            updated_requisition_row.min_months_of_stock,
-            updated_requisition_row.max_months_of_stock,
-        )?
+        generate_updated_lines(connection, &updated_requisition_row)?
     } else {
         vec![]
     };
@@ -84,11 +81,10 @@ pub fn generate(
 
 pub fn generate_updated_lines(
     connection: &StorageConnection,
-    requisition_id: &str,
-    min_months_of_stock: f64,
-    max_months_of_stock: f64,
+    requisition: &RequisitionRow,
 ) -> Result<Vec<RequisitionLineRow>, RepositoryError> {
-    let lines = get_lines_for_requisition(connection, requisition_id)?;
+    let lines = get_lines_for_requisition(connection, &requisition.id)?;
+    let lead_time_months = get_lead_time_months(connection, requisition)?;
 
     let result = lines
         .into_iter()
@@ -102,8 +98,9 @@ pub fn generate_updated_lines(
                         average_monthly_consumption: requisition_line_row
                             .average_monthly_consumption,
                         available_stock_on_hand: requisition_line_row.available_stock_on_hand,
-                        min_months_of_stock,
-                        max_months_of_stock,
+                        min_months_of_stock: requisition.min_months_of_stock,
+                        max_months_of_stock: requisition.max_months_of_stock,
+                        lead_time_months,
                     });
                 requisition_line_row
             },
@@ -0,0 +1,376 @@
+use std::collections::{BTreeMap, HashMap};
+
+use repository::{
+    DatetimeFilter, EqualFilter, Pagination, RepositoryError, Requisition, RequisitionFilter,
+    RequisitionLine, RequisitionLineFilter, RequisitionLineRepository, RequisitionLineRow,
+    RequisitionRepository, RequisitionRowType, RequisitionSort, RequisitionSortField,
+    StorageConnection,
+};
+
+use crate::{
+    requisition::requisition_supply_status::get_requisitions_supply_statuses,
+    service_provider::ServiceContext, store_preference::get_store_preferences,
+};
+
+/// Number of previous periods (or requisitions, for non program requisitions) to show
+pub const HISTORY_NUMBER_OF_PERIODS: usize = 3;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct PreviousRequisitionLine {
+    pub requisition: Requisition,
+    /// None if item was not requested in previous requisition
+    pub requisition_line: Option<RequisitionLineRow>,
+    /// Quantity in outbound shipments linked to previous requisition
+    pub supplied_quantity: f64,
+}
+
+impl PreviousRequisitionLine {
+    pub fn requested_quantity(&self) -> i32 {
+        self.requisition_line
+            .as_ref()
+            .map(|line| line.requested_quantity)
+            .unwrap_or(0)
+    }
+
+    pub fn approved_quantity(&self) -> i32 {
+        self.requisition_line
+            .as_ref()
+            .map(|line| line.approved_quantity)
+            .unwrap_or(0)
+    }
+
+    pub fn average_monthly_consumption(&self) -> i32 {
+        self.requisition_line
+            .as_ref()
+            .map(|line| line.average_monthly_consumption)
+            .unwrap_or(0)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct RequisitionLineHistory {
+    /// Most recent first
+    pub previous: Vec<PreviousRequisitionLine>,
+    pub average_requested_quantity: Option<f64>,
+    /// Requested quantity exceeds previous average requested quantity by more than the
+    /// `requisition_over_order_threshold_percentage` store preference
+    pub is_over_ordered: bool,
+}
+
+/// Requested, approved, supplied quantities and AMC of the same item from previous response
+/// requisitions from the same customer, keyed by requisition line id. For program requisitions
+/// previous periods of the same program are used, emergency requisitions are not included.
+/// Lines of request requisitions are skipped
+pub fn get_requisition_lines_history(
+    ctx: &ServiceContext,
+    requisition_line_ids: Vec<String>,
+) -> Result<HashMap<String, RequisitionLineHistory>, RepositoryError> {
+    let connection = &ctx.connection;
+    let requisition_lines = RequisitionLineRepository::new(connection).query_by_filter(
+        RequisitionLineFilter::new()
+            .id(EqualFilter::equal_any(requisition_line_ids))
+            .r#type(RequisitionRowType::Response.equal_to()),
+    )?;
+
+    let mut lines_by_requisition: BTreeMap<String, Vec<RequisitionLine>> = BTreeMap::new();
+    for line in requisition_lines {
+        lines_by_requisition
+            .entry(line.requisition_row.id.clone())
+            .or_default()
+            .push(line);
+    }
+
+    let mut over_order_thresholds: HashMap<String, i32> = HashMap::new();
+    let mut result = HashMap::new();
+    for (requisition_id, lines) in lines_by_requisition {
+        let store_id = &lines[0].requisition_row.store_id;
+        let over_order_threshold_percentage = match over_order_thresholds.get(store_id) {
+            Some(percentage) => *percentage,
+            None => {
+                let percentage = get_store_preferences(connection, store_id)?
+                    .requisition_over_order_threshold_percentage;
+                over_order_thresholds.insert(store_id.clone(), percentage);
+                percentage
+            }
+        };
+
+        let previous_requisitions = previous_requisitions(connection, &requisition_id)?;
+        let previous_requisition_ids: Vec<String> = previous_requisitions
+            .iter()
+            .map(|r| r.requisition_row.id.clone())
+            .collect();
+        let item_ids: Vec<String> = lines.iter().map(|line| line.item_row.id.clone()).collect();
+
+        let previous_lines = RequisitionLineRepository::new(connection).query_by_filter(
+            RequisitionLineFilter::new()
+                .requisition_id(EqualFilter::equal_any(previous_requisition_ids.clone()))
+                .item_id(EqualFilter::equal_any(item_ids)),
+        )?;
+        let supply_statuses =
+            get_requisitions_supply_statuses(connection, previous_requisition_ids)?;
+
+        for line in lines {
+            let item_id = &line.item_row.id;
+            let previous: Vec<PreviousRequisitionLine> = previous_requisitions
+                .iter()
+                .map(|requisition| {
+                    let requisition_id = &requisition.requisition_row.id;
+                    PreviousRequisitionLine {
+                        requisition_line: previous_lines
+                            .iter()
+                            .find(|previous_line| {
+                                &previous_line.requisition_line_row.requisition_id == requisition_id
+                                    && &previous_line.item_row.id == item_id
+                            })
+                            .map(|previous_line| previous_line.requisition_line_row.clone()),
+                        supplied_quantity: supply_statuses
+                            .iter()
+                            .find(|status| {
+                                &status.requisition_line.requisition_line_row.requisition_id
+                                    == requisition_id
+                                    && status.item_id() == item_id
+                            })
+                            .map(|status| status.quantity_in_invoices())
+                            .unwrap_or(0.0),
+                        requisition: requisition.clone(),
+                    }
+                })
+                .collect();
+
+            let history = generate_history(
+                previous,
+                line.requisition_line_row.requested_quantity,
+                over_order_threshold_percentage,
+            );
+            result.insert(line.requisition_line_row.id, history);
+        }
+    }
+
+    Ok(result)
+}
+
+fn generate_history(
+    previous: Vec<PreviousRequisitionLine>,
+    requested_quantity: i32,
+    over_order_threshold_percentage: i32,
+) -> RequisitionLineHistory {
+    let average_requested_quantity = if previous.is_empty() {
+        None
+    } else {
+        Some(
+            previous
+                .iter()
+                .map(|p| p.requested_quantity() as f64)
+                .sum::<f64>()
+                / previous.len() as f64,
+        )
+    };
+
+    let requested_quantity = requested_quantity as f64;
+    let threshold = 1.0 + over_order_threshold_percentage as f64 / 100.0;
+    let is_over_ordered = match average_requested_quantity {
+        // Flag is turned off for the store
+        _ if over_order_threshold_percentage <= 0 => false,
+        Some(average) => requested_quantity > average * threshold,
+        None => false,
+    };
+
+    RequisitionLineHistory {
+        previous,
+        average_requested_quantity,
+        is_over_ordered,
+    }
+}
+
+fn previous_requisitions(
+    connection: &StorageConnection,
+    requisition_id: &str,
+) -> Result<Vec<Requisition>, RepositoryError> {
+    let current = RequisitionRepository::new(connection)
+        .query_one(RequisitionFilter::by_id(requisition_id))?;
+    let Some(current) = current else {
+        return Ok(Vec::new());
+    };
+    let current_row = &current.requisition_row;
+
+    let mut filter = RequisitionFilter::new()
+        .store_id(EqualFilter::equal_to(&current_row.store_id))
+        .r#type(RequisitionRowType::Response.equal_to())
+        .name_id(EqualFilter::equal_to(&current.name_row.id))
+        .id(EqualFilter::not_equal_to(&current_row.id))
+        .created_datetime(DatetimeFilter::before_or_equal_to(
+            current_row.created_datetime,
+        ))
+        .is_emergency(false);
+
+    if let Some(program_id) = &current_row.program_id {
+        filter = filter.program_id(EqualFilter::equal_to(program_id));
+    }
+
+    let requisitions = RequisitionRepository::new(connection).query(
+        Pagination::all(),
+        Some(filter),
+        Some(RequisitionSort {
+            key: RequisitionSortField::CreatedDatetime,
+            desc: Some(true),
+        }),
+    )?;
+
+    // Program requisitions are compared by period, only the latest requisition for each
+    // previous period is used
+    let result = match &current.period {
+        Some(current_period) => {
+            let mut result: Vec<Requisition> = Vec::new();
+            for requisition in requisitions {
+                let Some(period) = &requisition.period else {
+                    continue;
+                };
+                if period.end_date >= current_period.start_date
+                    || result
+                        .iter()
+                        .any(|r| r.period.as_ref().map(|p| &p.id) == Some(&period.id))
+                {
+                    continue;
+                }
+                result.push(requisition);
+            }
+            result.sort_by(|a, b| {
+                let start_date = |r: &Requisition| r.period.as_ref().map(|p| p.start_date);
+                start_date(b).cmp(&start_date(a))
+            });
+            result
+        }
+        None => requisitions,
+    };
+
+    Ok(result.into_iter().take(HISTORY_NUMBER_OF_PERIODS).collect())
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{Duration, Utc};
+    use repository::{
+        mock::{mock_item_a, mock_name_c, mock_store_a, MockData, MockDataInserts},
+        test_db::setup_all_with_data,
+        InvoiceLineRow, InvoiceLineRowType, InvoiceRow, InvoiceRowStatus, InvoiceRowType,
+        RequisitionLineRow, RequisitionRow, RequisitionRowStatus, RequisitionRowType,
+        StorePreferenceRow, StorePreferenceRowRepository,
+    };
+    use util::inline_init;
+
+    use crate::service_provider::ServiceProvider;
+
+    fn response_requisition(id: &str, days_ago: i64) -> RequisitionRow {
+        inline_init(|r: &mut RequisitionRow| {
+            r.id = id.to_string();
+            r.store_id = mock_store_a().id;
+            r.name_link_id = mock_name_c().id;
+            r.r#type = RequisitionRowType::Response;
+            r.status = RequisitionRowStatus::Finalised;
+            r.created_datetime = Utc::now().naive_utc() - Duration::days(days_ago);
+        })
+    }
+
+    fn requisition_line(requisition_id: &str, requested_quantity: i32) -> RequisitionLineRow {
+        inline_init(|r: &mut RequisitionLineRow| {
+            r.id = format!("{}_line", requisition_id);
+            r.requisition_id = requisition_id.to_string();
+            r.item_link_id = mock_item_a().id;
+            r.requested_quantity = requested_quantity;
+            r.approved_quantity = requested_quantity;
+            r.average_monthly_consumption = 10;
+        })
+    }
+
+    #[actix_rt::test]
+    async fn requisition_line_history() {
+        let outbound_shipment = inline_init(|r: &mut InvoiceRow| {
+            r.id = "history_outbound_shipment".to_string();
+            r.store_id = mock_store_a().id;
+            r.name_link_id = mock_name_c().id;
+            r.r#type = InvoiceRowType::OutboundShipment;
+            r.status = InvoiceRowStatus::Shipped;
+            r.requisition_id = Some("history_previous_two".to_string());
+        });
+
+        let (_, _, connection_manager, _) = setup_all_with_data(
+            "requisition_line_history",
+            MockDataInserts::all(),
+            inline_init(|r: &mut MockData| {
+                r.requisitions = vec![
+                    response_requisition("history_previous_one", 60),
+                    response_requisition("history_previous_two", 30),
+                    response_requisition("history_current", 0),
+                ];
+                r.requisition_lines = vec![
+                    requisition_line("history_previous_one", 10),
+                    requisition_line("history_previous_two", 20),
+                    requisition_line("history_current", 40),
+                ];
+                r.invoices = vec![outbound_shipment.clone()];
+                r.invoice_lines = vec![inline_init(|r: &mut InvoiceLineRow| {
+                    r.id = "history_outbound_shipment_line".to_string();
+                    r.invoice_id = outbound_shipment.id.clone();
+                    r.item_link_id = mock_item_a().id;
+                    r.r#type = InvoiceLineRowType::StockOut;
+                    r.pack_size = 1;
+                    r.number_of_packs = 15.0;
+                })];
+            }),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, "".to_string())
+            .unwrap();
+        let service = service_provider.requisition_line_service;
+
+        let set_threshold = |percentage: i32| {
+            StorePreferenceRowRepository::new(&context.connection)
+                .upsert_one(&StorePreferenceRow {
+                    id: mock_store_a().id,
+                    requisition_over_order_threshold_percentage: percentage,
+                    ..StorePreferenceRow::default()
+                })
+                .unwrap();
+        };
+        let get_history = |requisition_line_id: &str| {
+            service
+                .get_requisition_lines_history(&context, vec![requisition_line_id.to_string()])
+                .unwrap()
+                .remove(requisition_line_id)
+        };
+
+        assert_eq!(get_history("n/a"), None);
+
+        // Flag is off by default
+        let history = get_history("history_current_line").unwrap();
+        assert_eq!(history.previous.len(), 2);
+        assert_eq!(
+            history.previous[0].requisition.requisition_row.id,
+            "history_previous_two"
+        );
+        assert_eq!(history.previous[0].requested_quantity(), 20);
+        assert_eq!(history.previous[0].approved_quantity(), 20);
+        assert_eq!(history.previous[0].average_monthly_consumption(), 10);
+        assert_eq!(history.previous[0].supplied_quantity, 15.0);
+        assert_eq!(history.previous[1].supplied_quantity, 0.0);
+        assert_eq!(history.average_requested_quantity, Some(15.0));
+        assert!(!history.is_over_ordered);
+
+        // 40 requested is more than 150% above average of 15
+        set_threshold(150);
+        assert!(get_history("history_current_line").unwrap().is_over_ordered);
+
+        // 40 requested is less than 200% above average of 15
+        set_threshold(200);
+        assert!(!get_history("history_current_line").unwrap().is_over_ordered);
+
+        // Previous requisitions have no earlier history
+        let history = get_history("history_previous_one_line").unwrap();
+        assert_eq!(history.previous.len(), 0);
+        assert_eq!(history.average_requested_quantity, None);
+        assert!(!history.is_over_ordered);
+    }
+}
@@ -3,6 +3,7 @@ use self::{
         get_requisition_line_chart, ConsumptionHistoryOptions, ItemChart,
         RequisitionLineChartError, StockEvolutionOptions,
     },
+    history::{get_requisition_lines_history, RequisitionLineHistory},
     query::get_requisition_lines,
     request_requisition_line::{
         delete_request_requisition_line, insert_request_requisition_line,
@@ -24,10 +25,12 @@ use self::{
 use super::{ListError, ListResult};
 use crate::service_provider::ServiceContext;
 
-use repository::{RequisitionLine, RequisitionLineFilter};
+use repository::{RepositoryError, RequisitionLine, RequisitionLineFilter};
+use std::collections::HashMap;
 
 pub mod chart;
 pub mod common;
+pub mod history;
 pub mod query;
 pub mod request_requisition_line;
 pub mod response_line_stats;
@@ -96,6 +99,14 @@ pub trait RequisitionLineServiceTrait: Sync + Send {
     ) -> Result<ResponseRequisitionStats, ResponseRequisitionStatsError> {
         get_response_requisition_line_stats(ctx, requisition_line_id)
     }
+
+    fn get_requisition_lines_history(
+        &self,
+        ctx: &ServiceContext,
+        requisition_line_ids: Vec<String>,
+    ) -> Result<HashMap<String, RequisitionLineHistory>, RepositoryError> {
+        get_requisition_lines_history(ctx, requisition_line_ids)
+    }
 }
 
 pub struct RequisitionLineService {}
@@ -1,7 +1,11 @@
 use crate::{
     item::check_item_exists,
     requisition::{
-        common::check_requisition_row_exists, request_requisition::generate_requisition_lines,
+        common::{
+            check_item_in_program_master_list, check_requisition_row_exists,
+            get_emergency_order_max_items, get_lines_for_requisition,
+        },
+        request_requisition::generate_requisition_lines,
     },
     requisition_line::{
         common::{check_item_exists_in_requisition, check_requisition_line_exists},
@@ -33,6 +37,8 @@ pub enum InsertRequestRequisitionLineError {
     ItemDoesNotExist,
     // TODO  ItemIsNotVisibleInThisStore,
     CannotAddItemToProgramRequisition,
+    ItemNotInProgramMasterList,
+    MaxItemsReachedForEmergencyOrder,
     RequisitionDoesNotExist,
     NotThisStoreRequisition,
     CannotEditRequisition,
@@ -77,7 +83,8 @@ fn validate(
     let requisition_row = check_requisition_row_exists(connection, &input.requisition_id)?
         .ok_or(OutError::RequisitionDoesNotExist)?;
 
-    if requisition_row.program_id.is_some() {
+    // Items can only be added manually to emergency program requisitions
+    if requisition_row.program_id.is_some() && !requisition_row.is_emergency {
         return Err(OutError::CannotAddItemToProgramRequisition);
     }
 
@@ -93,6 +100,13 @@ fn validate(
         return Err(OutError::NotARequestRequisition);
     }
 
+    if let Some(max_items) = get_emergency_order_max_items(connection, &requisition_row)? {
+        let number_of_lines = get_lines_for_requisition(connection, &requisition_row.id)?.len();
+        if number_of_lines >= max_items as usize {
+            return Err(OutError::MaxItemsReachedForEmergencyOrder);
+        }
+    }
+
     if let Some(_) =
         check_item_exists_in_requisition(connection, &input.requisition_id, &input.item_id)?
     {
@@ -103,6 +117,12 @@ fn validate(
         return Err(OutError::ItemDoesNotExist);
     }
 
+    if let Some(program_id) = &requisition_row.program_id {
+        if !check_item_in_program_master_list(connection, program_id, &input.item_id)? {
+            return Err(OutError::ItemNotInProgramMasterList);
+        }
+    }
+
     Ok(requisition_row)
 }
 
@@ -225,6 +225,7 @@ fn sensor_add_breach_config_if_new(
         r#type: breach_row_type,
         minimum_temperature: temperature_breach_config.minimum_temperature,
         maximum_temperature: temperature_breach_config.maximum_temperature,
+        ..Default::default()
     };
     TemperatureBreachConfigRowRepository::new(connection)
         .upsert_one(&new_temperature_breach_config)?;
@@ -270,9 +271,9 @@ fn sensor_add_if_new(
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ReadSensor {
-    new_sensor_id: Option<String>,
-    number_of_logs: u32,
-    number_of_breaches: u32,
+    pub(crate) new_sensor_id: Option<String>,
+    pub(crate) number_of_logs: u32,
+    pub(crate) number_of_breaches: u32,
 }
 
 #[derive(Debug, Error)]
@@ -0,0 +1,279 @@
+use std::collections::HashSet;
+
+use chrono::{NaiveDateTime, Utc};
+use rand::Rng;
+use repository::{
+    EqualFilter, RepositoryError, SensorApiKeyRow, SensorApiKeyRowRepository, SensorFilter,
+    SensorRepository, SensorRow, SensorRowRepository, SensorType, StorageConnection,
+    TemperatureLogRow, TemperatureLogRowRepository,
+};
+use util::{hash::sha256, uuid::uuid};
+
+use crate::{
+    cold_chain::temperature_breach_detection::detect_temperature_breaches,
+    service_provider::ServiceContext,
+};
+
+/// Maximum number of readings in one batch
+pub const MAX_READINGS_PER_BATCH: usize = 10_000;
+
+#[derive(Debug, PartialEq)]
+pub enum CreateSensorApiKeyError {
+    SerialIsEmpty,
+    DatabaseError(RepositoryError),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum RevokeSensorApiKeyError {
+    ApiKeyDoesNotExist,
+    ApiKeyDoesNotBelongToCurrentStore,
+    DatabaseError(RepositoryError),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum IngestSensorReadingsError {
+    InvalidApiKey,
+    NoReadings,
+    TooManyReadings,
+    DatabaseError(RepositoryError),
+}
+
+/// Key is only returned when it's created, only its hash is stored
+#[derive(Debug, PartialEq)]
+pub struct NewSensorApiKey {
+    pub api_key_row: SensorApiKeyRow,
+    pub key: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SensorReading {
+    pub datetime: NaiveDateTime,
+    pub temperature: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct IngestSensorReadings {
+    pub battery_level: Option<i32>,
+    pub log_interval: Option<i32>,
+    pub readings: Vec<SensorReading>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct IngestSensorReadingsResult {
+    pub sensor_id: String,
+    /// Sensor was registered by this batch
+    pub is_new_sensor: bool,
+    pub number_of_logs: u32,
+    /// Readings with the same timestamp as an existing log of the sensor (or an earlier reading
+    /// in the batch) are rejected
+    pub duplicates: Vec<NaiveDateTime>,
+    pub number_of_breaches: u32,
+}
+
+/// Creates a key for the serial in the current store, other active keys of the serial are
+/// revoked
+pub fn create_sensor_api_key(
+    ctx: &ServiceContext,
+    serial: String,
+) -> Result<NewSensorApiKey, CreateSensorApiKeyError> {
+    let serial = serial.trim().to_string();
+    if serial.is_empty() {
+        return Err(CreateSensorApiKeyError::SerialIsEmpty);
+    }
+
+    let result = ctx
+        .connection
+        .transaction_sync(|connection| -> Result<_, CreateSensorApiKeyError> {
+            let repo = SensorApiKeyRowRepository::new(connection);
+            for existing in repo.find_many_by_store_id(&ctx.store_id)? {
+                if existing.serial == serial && existing.is_active {
+                    repo.upsert_one(&SensorApiKeyRow {
+                        is_active: false,
+                        ..existing
+                    })?;
+                }
+            }
+
+            let key = hex::encode(rand::thread_rng().gen::<[u8; 32]>());
+            let api_key_row = SensorApiKeyRow {
+                id: uuid(),
+                store_id: ctx.store_id.clone(),
+                serial,
+                key_hash: sha256(&key),
+                is_active: true,
+                created_datetime: Utc::now().naive_utc(),
+                last_used_datetime: None,
+            };
+            repo.upsert_one(&api_key_row)?;
+
+            Ok(NewSensorApiKey { api_key_row, key })
+        })
+        .map_err(|error| error.to_inner_error())?;
+    Ok(result)
+}
+
+pub fn revoke_sensor_api_key(
+    ctx: &ServiceContext,
+    id: String,
+) -> Result<SensorApiKeyRow, RevokeSensorApiKeyError> {
+    let repo = SensorApiKeyRowRepository::new(&ctx.connection);
+    let api_key_row = repo
+        .find_one_by_id(&id)?
+        .ok_or(RevokeSensorApiKeyError::ApiKeyDoesNotExist)?;
+    if api_key_row.store_id != ctx.store_id {
+        return Err(RevokeSensorApiKeyError::ApiKeyDoesNotBelongToCurrentStore);
+    }
+
+    let api_key_row = SensorApiKeyRow {
+        is_active: false,
+        ..api_key_row
+    };
+    repo.upsert_one(&api_key_row)?;
+    Ok(api_key_row)
+}
+
+pub fn get_sensor_api_keys(ctx: &ServiceContext) -> Result<Vec<SensorApiKeyRow>, RepositoryError> {
+    SensorApiKeyRowRepository::new(&ctx.connection).find_many_by_store_id(&ctx.store_id)
+}
+
+/// Adds readings pushed by a network connected logger as temperature logs of the sensor of the
+/// api key. Unknown serials are registered as inactive sensors without location, awaiting
+/// assignment. Breaches are detected from the new logs
+pub fn ingest_sensor_readings(
+    ctx: &ServiceContext,
+    api_key: &str,
+    input: IngestSensorReadings,
+) -> Result<IngestSensorReadingsResult, IngestSensorReadingsError> {
+    if input.readings.is_empty() {
+        return Err(IngestSensorReadingsError::NoReadings);
+    }
+    if input.readings.len() > MAX_READINGS_PER_BATCH {
+        return Err(IngestSensorReadingsError::TooManyReadings);
+    }
+
+    let result = ctx
+        .connection
+        .transaction_sync(|connection| ingest(connection, api_key, input))
+        .map_err(|error| error.to_inner_error())?;
+    Ok(result)
+}
+
+fn ingest(
+    connection: &StorageConnection,
+    api_key: &str,
+    IngestSensorReadings {
+        battery_level,
+        log_interval,
+        mut readings,
+    }: IngestSensorReadings,
+) -> Result<IngestSensorReadingsResult, IngestSensorReadingsError> {
+    let key_repo = SensorApiKeyRowRepository::new(connection);
+    let api_key_row = key_repo
+        .find_one_active_by_key_hash(&sha256(api_key))?
+        .ok_or(IngestSensorReadingsError::InvalidApiKey)?;
+    let now = Utc::now().naive_utc();
+    key_repo.upsert_one(&SensorApiKeyRow {
+        last_used_datetime: Some(now),
+        ..api_key_row.clone()
+    })?;
+
+    // Only sensors of the store of the key, the same serial could be registered in another store
+    let existing_sensor = SensorRepository::new(connection)
+        .query_by_filter(
+            SensorFilter::new()
+                .serial(EqualFilter::equal_to(&api_key_row.serial))
+                .store_id(EqualFilter::equal_to(&api_key_row.store_id)),
+        )?
+        .pop()
+        .map(|sensor| sensor.sensor_row);
+    let is_new_sensor = existing_sensor.is_none();
+    let sensor = match existing_sensor {
+        Some(sensor) => SensorRow {
+            battery_level: battery_level.or(sensor.battery_level),
+            log_interval: log_interval.or(sensor.log_interval),
+            last_connection_datetime: Some(now),
+            ..sensor
+        },
+        None => SensorRow {
+            id: uuid(),
+            name: api_key_row.serial.clone(),
+            serial: api_key_row.serial.clone(),
+            location_id: None,
+            store_id: api_key_row.store_id.clone(),
+            battery_level,
+            log_interval,
+            is_active: false,
+            last_connection_datetime: Some(now),
+            r#type: SensorType::Generic,
+        },
+    };
+    SensorRowRepository::new(connection).upsert_one(&sensor)?;
+    if is_new_sensor {
+        log::info!("Registered sensor {:?} from pushed readings", sensor);
+    }
+
+    readings.sort_by_key(|reading| reading.datetime);
+    // Readings are not empty, safe to unwrap
+    let from = readings.first().unwrap().datetime;
+    let log_repo = TemperatureLogRowRepository::new(connection);
+    // Existing logs of the sensor (synced or ingested) take precedence over readings with the
+    // same datetime
+    let mut existing: HashSet<NaiveDateTime> = log_repo
+        .find_many_by_sensor_id(&sensor.id, Some(from))?
+        .into_iter()
+        .map(|log| log.datetime)
+        .collect();
+
+    let mut duplicates = Vec::new();
+    let mut new_from = None;
+    let mut number_of_logs = 0;
+    for reading in readings {
+        if !existing.insert(reading.datetime) {
+            duplicates.push(reading.datetime);
+            continue;
+        }
+
+        log_repo.upsert_one(&TemperatureLogRow {
+            id: uuid(),
+            temperature: reading.temperature,
+            sensor_id: sensor.id.clone(),
+            location_id: sensor.location_id.clone(),
+            datetime: reading.datetime,
+            store_id: sensor.store_id.clone(),
+            temperature_breach_id: None,
+        })?;
+        new_from.get_or_insert(reading.datetime);
+        number_of_logs += 1;
+    }
+
+    let number_of_breaches = match new_from {
+        Some(from) => detect_temperature_breaches(connection, &sensor, from)?.len(),
+        None => 0,
+    };
+
+    Ok(IngestSensorReadingsResult {
+        sensor_id: sensor.id,
+        is_new_sensor,
+        number_of_logs,
+        duplicates,
+        number_of_breaches: number_of_breaches as u32,
+    })
+}
+
+impl From<RepositoryError> for CreateSensorApiKeyError {
+    fn from(error: RepositoryError) -> Self {
+        CreateSensorApiKeyError::DatabaseError(error)
+    }
+}
+
+impl From<RepositoryError> for RevokeSensorApiKeyError {
+    fn from(error: RepositoryError) -> Self {
+        RevokeSensorApiKeyError::DatabaseError(error)
+    }
+}
+
+impl From<RepositoryError> for IngestSensorReadingsError {
+    fn from(error: RepositoryError) -> Self {
+        IngestSensorReadingsError::DatabaseError(error)
+    }
+}
@@ -0,0 +1,100 @@
+use super::{
+    logger_file, settings::TemperatureLoggerSettings, LogValueParser, LoggerFile, LoggerFileLog,
+    ParseLoggerFileError, SensorFileParser,
+};
+
+pub struct CsvSensorFileParser {
+    settings: TemperatureLoggerSettings,
+}
+
+impl CsvSensorFileParser {
+    pub fn new(settings: TemperatureLoggerSettings) -> Self {
+        CsvSensorFileParser { settings }
+    }
+}
+
+impl SensorFileParser for CsvSensorFileParser {
+    fn name(&self) -> &str {
+        &self.settings.name
+    }
+
+    fn parse(&self, file_name: &str, file: &[u8]) -> Result<LoggerFile, ParseLoggerFileError> {
+        let settings = &self.settings;
+        let value_parser = LogValueParser::new(settings)?;
+
+        let delimiter = match settings.delimiter {
+            None => b',',
+            Some(delimiter) if delimiter.is_ascii() => delimiter as u8,
+            Some(delimiter) => {
+                return Err(ParseLoggerFileError::CannotReadFile(format!(
+                    "Delimiter {delimiter} is not an ASCII character"
+                )))
+            }
+        };
+
+        // Skip logger summary lines before the header row
+        let mut content = file;
+        for _ in 0..settings.skip_lines {
+            content = match content.iter().position(|byte| *byte == b'\n') {
+                Some(position) => &content[position + 1..],
+                None => &[],
+            };
+        }
+
+        let mut reader = ::csv::ReaderBuilder::new()
+            .delimiter(delimiter)
+            .flexible(true)
+            .trim(::csv::Trim::All)
+            .from_reader(content);
+
+        let headers = reader
+            .headers()
+            .map_err(|error| ParseLoggerFileError::CannotReadFile(error.to_string()))?
+            .clone();
+        let column_index = |column: &str| {
+            headers
+                .iter()
+                .position(|header| header == column)
+                .ok_or(ParseLoggerFileError::ColumnMissing(column.to_string()))
+        };
+        let columns = &settings.columns;
+        let timestamp_index = column_index(&columns.timestamp)?;
+        let temperature_index = column_index(&columns.temperature)?;
+        let serial_index = columns.serial.as_deref().map(column_index).transpose()?;
+
+        let mut serial = None;
+        let mut logs = Vec::new();
+        for (index, record) in reader.records().enumerate() {
+            // Header is the first row after skipped lines
+            let row = settings.skip_lines + index + 2;
+            let record = record.map_err(|error| ParseLoggerFileError::InvalidRow {
+                row,
+                error: error.to_string(),
+            })?;
+            if record.iter().all(str::is_empty) {
+                continue;
+            }
+
+            if serial.is_none() {
+                serial = serial_index
+                    .and_then(|index| record.get(index))
+                    .filter(|value| !value.is_empty())
+                    .map(str::to_string);
+            }
+
+            let value = |index: usize| record.get(index).unwrap_or_default();
+            let log = value_parser
+                .datetime(value(timestamp_index))
+                .and_then(|datetime| {
+                    Ok(LoggerFileLog {
+                        datetime,
+                        temperature: value_parser.temperature(value(temperature_index))?,
+                    })
+                })
+                .map_err(|error| ParseLoggerFileError::InvalidRow { row, error })?;
+            logs.push(log);
+        }
+
+        logger_file(file_name, serial, logs)
+    }
+}
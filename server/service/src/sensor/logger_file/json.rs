@@ -0,0 +1,90 @@
+use serde_json::Value;
+
+use super::{
+    logger_file, settings::TemperatureLoggerSettings, LogValueParser, LoggerFile, LoggerFileLog,
+    ParseLoggerFileError, SensorFileParser,
+};
+
+pub struct JsonSensorFileParser {
+    settings: TemperatureLoggerSettings,
+}
+
+impl JsonSensorFileParser {
+    pub fn new(settings: TemperatureLoggerSettings) -> Self {
+        JsonSensorFileParser { settings }
+    }
+}
+
+impl SensorFileParser for JsonSensorFileParser {
+    fn name(&self) -> &str {
+        &self.settings.name
+    }
+
+    fn parse(&self, file_name: &str, file: &[u8]) -> Result<LoggerFile, ParseLoggerFileError> {
+        let settings = &self.settings;
+        let value_parser = LogValueParser::new(settings)?;
+
+        let document: Value = serde_json::from_slice(file)
+            .map_err(|error| ParseLoggerFileError::CannotReadFile(error.to_string()))?;
+
+        let logs_value = match settings.logs_path.as_deref() {
+            Some(path) => lookup(&document, path)
+                .ok_or(ParseLoggerFileError::ColumnMissing(path.to_string()))?,
+            None => &document,
+        };
+        let log_values = logs_value
+            .as_array()
+            .ok_or(ParseLoggerFileError::CannotReadFile(
+                "Logs are not an array".to_string(),
+            ))?;
+
+        let serial = settings
+            .columns
+            .serial
+            .as_deref()
+            .and_then(|path| lookup(&document, path))
+            .and_then(value_to_string);
+
+        let columns = &settings.columns;
+        let mut logs = Vec::new();
+        for (index, log_value) in log_values.iter().enumerate() {
+            let row = index + 1;
+            let value = |column: &str| {
+                log_value.get(column).and_then(value_to_string).ok_or(
+                    ParseLoggerFileError::InvalidRow {
+                        row,
+                        error: format!("{column} is missing"),
+                    },
+                )
+            };
+            let timestamp = value(&columns.timestamp)?;
+            let temperature = value(&columns.temperature)?;
+
+            let log = value_parser
+                .datetime(&timestamp)
+                .and_then(|datetime| {
+                    Ok(LoggerFileLog {
+                        datetime,
+                        temperature: value_parser.temperature(&temperature)?,
+                    })
+                })
+                .map_err(|error| ParseLoggerFileError::InvalidRow { row, error })?;
+            logs.push(log);
+        }
+
+        logger_file(file_name, serial, logs)
+    }
+}
+
+/// Dot separated path, e.g. "device.serial"
+fn lookup<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').try_fold(value, |value, key| value.get(key))
+}
+
+fn value_to_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(value) => Some(value.clone()),
+        Value::Number(value) => Some(value.to_string()),
+        _ => None,
+    }
+}
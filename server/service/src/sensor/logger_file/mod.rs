@@ -0,0 +1,311 @@
+use std::{collections::HashSet, path::Path};
+
+use chrono::{DateTime, FixedOffset, Local, LocalResult, NaiveDateTime, TimeZone};
+use repository::{
+    RepositoryError, SensorRow, SensorRowRepository, SensorType, StorageConnection,
+    TemperatureLogRow, TemperatureLogRowRepository,
+};
+use thiserror::Error;
+use util::uuid::uuid;
+
+use self::{
+    csv::CsvSensorFileParser,
+    json::JsonSensorFileParser,
+    settings::{LoggerFileFormat, TemperatureLoggerSettings, TemperatureUnit},
+};
+use super::berlinger::{get_matching_sensor_serial, ReadSensor};
+use crate::cold_chain::temperature_breach_detection::detect_temperature_breaches;
+
+pub mod csv;
+pub mod json;
+pub mod settings;
+
+/// Log read from a logger file, in UTC and degrees Celsius
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoggerFileLog {
+    pub datetime: NaiveDateTime,
+    pub temperature: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoggerFile {
+    pub serial: String,
+    /// Sorted by datetime
+    pub logs: Vec<LoggerFileLog>,
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum ParseLoggerFileError {
+    #[error("Cannot read file: {0}")]
+    CannotReadFile(String),
+    #[error("Column {0} is missing")]
+    ColumnMissing(String),
+    /// Row number in the file, for CSV files header row is row 1
+    #[error("Row {row}: {error}")]
+    InvalidRow { row: usize, error: String },
+    #[error("Invalid timezone {0}")]
+    InvalidTimezone(String),
+    #[error("File has no logs")]
+    NoLogs,
+}
+
+/// Reads temperature logs of a logger export. Parsers for CSV and JSON files are created from
+/// `temperature_loggers` settings, other parsers can be registered in `SensorFileParsers`
+pub trait SensorFileParser: Send + Sync {
+    /// Used to select the parser when uploading a file
+    fn name(&self) -> &str;
+
+    /// `file_name` is used as serial when file doesn't have one
+    fn parse(&self, file_name: &str, file: &[u8]) -> Result<LoggerFile, ParseLoggerFileError>;
+}
+
+#[derive(Default)]
+pub struct SensorFileParsers {
+    parsers: Vec<Box<dyn SensorFileParser>>,
+}
+
+impl SensorFileParsers {
+    pub fn from_settings(settings: &[TemperatureLoggerSettings]) -> Self {
+        let mut parsers = SensorFileParsers::default();
+        for logger in settings {
+            match logger.format {
+                LoggerFileFormat::Csv => {
+                    parsers.register(Box::new(CsvSensorFileParser::new(logger.clone())))
+                }
+                LoggerFileFormat::Json => {
+                    parsers.register(Box::new(JsonSensorFileParser::new(logger.clone())))
+                }
+            }
+        }
+        parsers
+    }
+
+    /// Replaces parser with the same name
+    pub fn register(&mut self, parser: Box<dyn SensorFileParser>) {
+        self.parsers
+            .retain(|existing| existing.name() != parser.name());
+        self.parsers.push(parser);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn SensorFileParser> {
+        self.parsers
+            .iter()
+            .find(|parser| parser.name() == name)
+            .map(|parser| parser.as_ref())
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ImportLoggerFileError {
+    #[error(transparent)]
+    ParseError(#[from] ParseLoggerFileError),
+    #[error(transparent)]
+    DatabaseError(#[from] RepositoryError),
+}
+
+/// Adds sensor (matched by serial) and logs that are not in the database yet, breaches are
+/// detected from the logs with breach configs of the store.
+/// Should be called in a transaction
+pub fn import_logger_file(
+    connection: &StorageConnection,
+    store_id: &str,
+    parser: &dyn SensorFileParser,
+    file_name: &str,
+    file: &[u8],
+) -> Result<ReadSensor, ImportLoggerFileError> {
+    let LoggerFile { serial, logs } = parser.parse(file_name, file)?;
+
+    let (sensor, new_sensor_id) = match get_matching_sensor_serial(connection, &serial)?.pop() {
+        Some(sensor) => (sensor.sensor_row, None),
+        None => {
+            let sensor = SensorRow {
+                id: uuid(),
+                name: serial.clone(),
+                serial,
+                store_id: store_id.to_string(),
+                is_active: true,
+                log_interval: log_interval(&logs),
+                r#type: SensorType::Generic,
+                ..Default::default()
+            };
+            SensorRowRepository::new(connection).upsert_one(&sensor)?;
+            log::info!("Added sensor {:?} ", sensor);
+            (sensor.clone(), Some(sensor.id))
+        }
+    };
+
+    // Logs are sorted, safe to unwrap
+    let from = logs.first().unwrap().datetime;
+    let log_repo = TemperatureLogRowRepository::new(connection);
+    let existing: HashSet<NaiveDateTime> = log_repo
+        .find_many_by_sensor_id(&sensor.id, Some(from))?
+        .into_iter()
+        .map(|log| log.datetime)
+        .collect();
+
+    let mut new_logs = logs
+        .iter()
+        .filter(|log| !existing.contains(&log.datetime))
+        .peekable();
+    let new_from = new_logs.peek().map(|log| log.datetime);
+    let mut number_of_logs = 0;
+    for log in new_logs {
+        log_repo.upsert_one(&TemperatureLogRow {
+            id: uuid(),
+            temperature: log.temperature,
+            sensor_id: sensor.id.clone(),
+            location_id: sensor.location_id.clone(),
+            datetime: log.datetime,
+            store_id: sensor.store_id.clone(),
+            temperature_breach_id: None,
+        })?;
+        number_of_logs += 1;
+    }
+
+    // Safe to unwrap, logs are not empty
+    let last_log_datetime = logs.last().unwrap().datetime;
+    let sensor = if sensor.last_connection_datetime < Some(last_log_datetime) {
+        let sensor = SensorRow {
+            last_connection_datetime: Some(last_log_datetime),
+            ..sensor
+        };
+        SensorRowRepository::new(connection).upsert_one(&sensor)?;
+        sensor
+    } else {
+        sensor
+    };
+
+    let number_of_breaches = match new_from {
+        Some(from) => detect_temperature_breaches(connection, &sensor, from)?.len(),
+        None => 0,
+    };
+
+    Ok(ReadSensor {
+        new_sensor_id,
+        number_of_logs,
+        number_of_breaches: number_of_breaches as u32,
+    })
+}
+
+/// Interval between the first two logs in seconds
+fn log_interval(logs: &[LoggerFileLog]) -> Option<i32> {
+    match logs {
+        [first, second, ..] => Some((second.datetime - first.datetime).num_seconds() as i32),
+        _ => None,
+    }
+}
+
+enum Timezone {
+    Local,
+    Fixed(FixedOffset),
+}
+
+/// Parses log values as configured in logger settings
+pub(crate) struct LogValueParser {
+    timestamp_format: Option<String>,
+    timezone: Timezone,
+    unit: TemperatureUnit,
+}
+
+impl LogValueParser {
+    pub(crate) fn new(settings: &TemperatureLoggerSettings) -> Result<Self, ParseLoggerFileError> {
+        let timezone = match settings.timezone.as_deref() {
+            None => Timezone::Local,
+            Some(timezone) if timezone.eq_ignore_ascii_case("local") => Timezone::Local,
+            Some(timezone) if timezone.eq_ignore_ascii_case("utc") => {
+                Timezone::Fixed(FixedOffset::east_opt(0).unwrap())
+            }
+            Some(timezone) => Timezone::Fixed(
+                parse_offset(timezone)
+                    .ok_or(ParseLoggerFileError::InvalidTimezone(timezone.to_string()))?,
+            ),
+        };
+
+        Ok(LogValueParser {
+            timestamp_format: settings.timestamp_format.clone(),
+            timezone,
+            unit: settings.unit.clone(),
+        })
+    }
+
+    /// UTC datetime, timezone of the settings is used for timestamps without offset
+    pub(crate) fn datetime(&self, value: &str) -> Result<NaiveDateTime, String> {
+        let value = value.trim();
+        let with_offset = match &self.timestamp_format {
+            Some(format) => DateTime::parse_from_str(value, format),
+            None => DateTime::parse_from_rfc3339(value),
+        };
+        if let Ok(datetime) = with_offset {
+            return Ok(datetime.naive_utc());
+        }
+
+        let datetime = match &self.timestamp_format {
+            Some(format) => NaiveDateTime::parse_from_str(value, format),
+            None => NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+                .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S")),
+        }
+        .map_err(|error| format!("Invalid timestamp {value}: {error}"))?;
+
+        let result = match &self.timezone {
+            Timezone::Local => Local.from_local_datetime(&datetime).map(|r| r.naive_utc()),
+            Timezone::Fixed(offset) => offset.from_local_datetime(&datetime).map(|r| r.naive_utc()),
+        };
+        match result {
+            LocalResult::Single(datetime) | LocalResult::Ambiguous(datetime, _) => Ok(datetime),
+            LocalResult::None => Err(format!("Timestamp {value} does not exist in timezone")),
+        }
+    }
+
+    /// Temperature in degrees Celsius
+    pub(crate) fn temperature(&self, value: &str) -> Result<f64, String> {
+        let value = value.trim();
+        let temperature: f64 = value
+            .parse()
+            .map_err(|_| format!("Invalid temperature {value}"))?;
+
+        Ok(match self.unit {
+            TemperatureUnit::Celsius => temperature,
+            TemperatureUnit::Fahrenheit => (temperature - 32.0) * 5.0 / 9.0,
+        })
+    }
+}
+
+/// Offset like "+10:00" or "-0530"
+fn parse_offset(value: &str) -> Option<FixedOffset> {
+    let (sign, rest) = match value.chars().next()? {
+        '+' => (1, &value[1..]),
+        '-' => (-1, &value[1..]),
+        _ => return None,
+    };
+    let digits: String = rest.chars().filter(|c| *c != ':').collect();
+    if digits.len() != 4 || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let hours: i32 = digits[..2].parse().ok()?;
+    let minutes: i32 = digits[2..].parse().ok()?;
+
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+/// Sorts logs and uses file name as serial when file doesn't have one
+pub(crate) fn logger_file(
+    file_name: &str,
+    serial: Option<String>,
+    mut logs: Vec<LoggerFileLog>,
+) -> Result<LoggerFile, ParseLoggerFileError> {
+    if logs.is_empty() {
+        return Err(ParseLoggerFileError::NoLogs);
+    }
+    logs.sort_by_key(|log| log.datetime);
+
+    let serial = serial
+        .filter(|serial| !serial.is_empty())
+        .unwrap_or_else(|| {
+            Path::new(file_name)
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_else(|| file_name.to_string())
+        });
+
+    Ok(LoggerFile { serial, logs })
+}
@@ -0,0 +1,45 @@
+#[derive(serde::Deserialize, Clone, Debug, PartialEq)]
+pub enum LoggerFileFormat {
+    Csv,
+    Json,
+}
+
+#[derive(serde::Deserialize, Clone, Debug, PartialEq, Default)]
+pub enum TemperatureUnit {
+    #[default]
+    Celsius,
+    Fahrenheit,
+}
+
+#[derive(serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct LoggerFileColumns {
+    pub timestamp: String,
+    pub temperature: String,
+    /// Serial of the logger, for JSON files this is a dot separated path from the document root.
+    /// File name (without extension) is used as serial when not set or empty
+    pub serial: Option<String>,
+}
+
+/// Column mapping of a temperature logger export, used to read CSV or JSON logger files
+#[derive(serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct TemperatureLoggerSettings {
+    /// Used to select the parser when uploading a file
+    pub name: String,
+    pub format: LoggerFileFormat,
+    /// CSV header names, or keys of JSON log objects
+    pub columns: LoggerFileColumns,
+    /// CSV only, lines before the header row (e.g. logger summary)
+    #[serde(default)]
+    pub skip_lines: usize,
+    /// CSV only, defaults to ','
+    pub delimiter: Option<char>,
+    /// JSON only, dot separated path to the array of logs, document root when not set
+    pub logs_path: Option<String>,
+    /// chrono format string, e.g. "%d/%m/%Y %H:%M". When not set timestamps can be RFC 3339 or
+    /// "%Y-%m-%d %H:%M:%S"
+    pub timestamp_format: Option<String>,
+    /// Timezone of timestamps without offset: "Local" (default), "UTC" or offset like "+10:00"
+    pub timezone: Option<String>,
+    #[serde(default)]
+    pub unit: TemperatureUnit,
+}
@@ -1,5 +1,10 @@
 use self::{
     // no delete: sensors can't be deleted - just made inactive
+    ingest::{
+        create_sensor_api_key, get_sensor_api_keys, ingest_sensor_readings, revoke_sensor_api_key,
+        CreateSensorApiKeyError, IngestSensorReadings, IngestSensorReadingsError,
+        IngestSensorReadingsResult, NewSensorApiKey, RevokeSensorApiKeyError,
+    },
     insert::{insert_sensor, InsertSensor, InsertSensorError},
     query::{get_sensor, get_sensors},
     update::{update_sensor, UpdateSensor, UpdateSensorError},
@@ -7,10 +12,14 @@ use self::{
 
 use super::{ListError, ListResult};
 use crate::{service_provider::ServiceContext, SingleRecordError};
-use repository::{PaginationOption, Sensor, SensorFilter, SensorSort};
+use repository::{
+    PaginationOption, RepositoryError, Sensor, SensorApiKeyRow, SensorFilter, SensorSort,
+};
 
 pub mod berlinger;
+pub mod ingest;
 pub mod insert;
+pub mod logger_file;
 pub mod query;
 pub mod update;
 mod validate;
@@ -45,6 +54,38 @@ pub trait SensorServiceTrait: Sync + Send {
     ) -> Result<Sensor, UpdateSensorError> {
         update_sensor(ctx, input)
     }
+
+    fn get_sensor_api_keys(
+        &self,
+        ctx: &ServiceContext,
+    ) -> Result<Vec<SensorApiKeyRow>, RepositoryError> {
+        get_sensor_api_keys(ctx)
+    }
+
+    fn create_sensor_api_key(
+        &self,
+        ctx: &ServiceContext,
+        serial: String,
+    ) -> Result<NewSensorApiKey, CreateSensorApiKeyError> {
+        create_sensor_api_key(ctx, serial)
+    }
+
+    fn revoke_sensor_api_key(
+        &self,
+        ctx: &ServiceContext,
+        id: String,
+    ) -> Result<SensorApiKeyRow, RevokeSensorApiKeyError> {
+        revoke_sensor_api_key(ctx, id)
+    }
+
+    fn ingest_sensor_readings(
+        &self,
+        ctx: &ServiceContext,
+        api_key: &str,
+        input: IngestSensorReadings,
+    ) -> Result<IngestSensorReadingsResult, IngestSensorReadingsError> {
+        ingest_sensor_readings(ctx, api_key, input)
+    }
 }
 
 pub struct SensorService {}
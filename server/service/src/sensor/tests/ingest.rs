@@ -0,0 +1,200 @@
+#[cfg(test)]
+mod ingest {
+    use chrono::NaiveDateTime;
+    use repository::{
+        mock::{mock_store_a, mock_store_b, MockData, MockDataInserts},
+        test_db::setup_all_with_data,
+        EqualFilter, SensorFilter, SensorRepository, SensorRowRepository, SensorType,
+        TemperatureBreachConfigRow, TemperatureBreachRowType, TemperatureLogRowRepository,
+    };
+
+    use crate::{
+        sensor::ingest::{
+            CreateSensorApiKeyError, IngestSensorReadings, IngestSensorReadingsError,
+            RevokeSensorApiKeyError, SensorReading,
+        },
+        service_provider::ServiceProvider,
+        test_helpers::datetime,
+    };
+
+    fn reading(minute: u32, temperature: f64) -> SensorReading {
+        SensorReading {
+            datetime: datetime(10, minute),
+            temperature,
+        }
+    }
+
+    #[actix_rt::test]
+    async fn ingest_sensor_readings() {
+        let store = mock_store_a();
+        let other_store = mock_store_b();
+        let hot_consecutive = TemperatureBreachConfigRow {
+            id: "hot_consecutive".to_string(),
+            duration_milliseconds: 10 * 60 * 1000,
+            r#type: TemperatureBreachRowType::HotConsecutive,
+            is_active: true,
+            store_id: store.id.clone(),
+            minimum_temperature: 2.0,
+            maximum_temperature: 8.0,
+            ..Default::default()
+        };
+
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "ingest_sensor_readings",
+            MockDataInserts::none().names().stores(),
+            MockData {
+                temperature_breach_configs: vec![hot_consecutive],
+                ..Default::default()
+            },
+        )
+        .await;
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(store.id.clone(), "".to_string())
+            .unwrap();
+        let service = &service_provider.sensor_service;
+
+        assert_eq!(
+            service.create_sensor_api_key(&context, " ".to_string()),
+            Err(CreateSensorApiKeyError::SerialIsEmpty)
+        );
+        let old_key = service
+            .create_sensor_api_key(&context, "LOGGER-1".to_string())
+            .unwrap();
+        let new_key = service
+            .create_sensor_api_key(&context, "LOGGER-1".to_string())
+            .unwrap();
+        assert!(!new_key.key.is_empty());
+        assert_ne!(new_key.api_key_row.key_hash, new_key.key);
+
+        // Creating a key revokes previous key of the serial
+        let input = IngestSensorReadings {
+            battery_level: Some(90),
+            log_interval: Some(600),
+            readings: vec![reading(20, 9.0), reading(0, 5.0), reading(10, 9.0)],
+        };
+        assert_eq!(
+            service.ingest_sensor_readings(&context, &old_key.key, input.clone()),
+            Err(IngestSensorReadingsError::InvalidApiKey)
+        );
+        assert_eq!(
+            service.ingest_sensor_readings(
+                &context,
+                &new_key.key,
+                IngestSensorReadings {
+                    readings: Vec::new(),
+                    ..input.clone()
+                }
+            ),
+            Err(IngestSensorReadingsError::NoReadings)
+        );
+
+        // Unknown serial is registered as inactive sensor
+        let result = service
+            .ingest_sensor_readings(&context, &new_key.key, input)
+            .unwrap();
+        assert!(result.is_new_sensor);
+        assert_eq!(result.number_of_logs, 3);
+        assert_eq!(result.duplicates, Vec::<NaiveDateTime>::new());
+        assert_eq!(result.number_of_breaches, 1);
+
+        let sensor = SensorRowRepository::new(&connection)
+            .find_one_by_id(&result.sensor_id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(sensor.serial, "LOGGER-1");
+        assert_eq!(sensor.store_id, store.id);
+        assert_eq!(sensor.r#type, SensorType::Generic);
+        assert!(!sensor.is_active);
+        assert_eq!(sensor.location_id, None);
+        assert_eq!(sensor.battery_level, Some(90));
+        assert_eq!(sensor.log_interval, Some(600));
+        assert!(sensor.last_connection_datetime.is_some());
+
+        // Readings with the timestamp of an existing log or an earlier reading are rejected
+        let result = service
+            .ingest_sensor_readings(
+                &context,
+                &new_key.key,
+                IngestSensorReadings {
+                    battery_level: Some(80),
+                    log_interval: None,
+                    readings: vec![reading(20, 9.0), reading(30, 4.0), reading(30, 4.5)],
+                },
+            )
+            .unwrap();
+        assert!(!result.is_new_sensor);
+        assert_eq!(result.number_of_logs, 1);
+        assert_eq!(result.duplicates, vec![datetime(10, 20), datetime(10, 30)]);
+
+        let sensors = SensorRepository::new(&connection)
+            .query_by_filter(SensorFilter::new().serial(EqualFilter::equal_to("LOGGER-1")))
+            .unwrap();
+        assert_eq!(sensors.len(), 1);
+        assert_eq!(sensors[0].sensor_row.battery_level, Some(80));
+        assert_eq!(sensors[0].sensor_row.log_interval, Some(600));
+        assert_eq!(
+            TemperatureLogRowRepository::new(&connection)
+                .find_many_by_sensor_id(&result.sensor_id, None)
+                .unwrap()
+                .len(),
+            4
+        );
+
+        // Same serial in another store is registered as a separate sensor of that store
+        let other_context = service_provider
+            .context(other_store.id.clone(), "".to_string())
+            .unwrap();
+        let other_key = service
+            .create_sensor_api_key(&other_context, "LOGGER-1".to_string())
+            .unwrap();
+        let other_result = service
+            .ingest_sensor_readings(
+                &other_context,
+                &other_key.key,
+                IngestSensorReadings {
+                    battery_level: None,
+                    log_interval: None,
+                    readings: vec![reading(20, 4.0)],
+                },
+            )
+            .unwrap();
+        assert!(other_result.is_new_sensor);
+        assert_ne!(other_result.sensor_id, result.sensor_id);
+        assert_eq!(other_result.number_of_logs, 1);
+        assert_eq!(
+            SensorRowRepository::new(&connection)
+                .find_one_by_id(&other_result.sensor_id)
+                .unwrap()
+                .unwrap()
+                .store_id,
+            other_store.id
+        );
+
+        // Revoked key can't be used
+        assert_eq!(
+            service.revoke_sensor_api_key(&other_context, new_key.api_key_row.id.clone()),
+            Err(RevokeSensorApiKeyError::ApiKeyDoesNotBelongToCurrentStore)
+        );
+        assert_eq!(
+            service.revoke_sensor_api_key(&context, "invalid".to_string()),
+            Err(RevokeSensorApiKeyError::ApiKeyDoesNotExist)
+        );
+        service
+            .revoke_sensor_api_key(&context, new_key.api_key_row.id.clone())
+            .unwrap();
+        assert_eq!(
+            service.ingest_sensor_readings(
+                &context,
+                &new_key.key,
+                IngestSensorReadings {
+                    battery_level: None,
+                    log_interval: None,
+                    readings: vec![reading(40, 4.0)],
+                },
+            ),
+            Err(IngestSensorReadingsError::InvalidApiKey)
+        );
+        assert_eq!(service.get_sensor_api_keys(&context).unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,219 @@
+#[cfg(test)]
+mod logger_file {
+    use repository::{
+        mock::{mock_store_a, MockData, MockDataInserts},
+        test_db::setup_all_with_data,
+        EqualFilter, SensorFilter, SensorRepository, SensorType, TemperatureBreachConfigRow,
+        TemperatureBreachRowType, TemperatureLogRowRepository,
+    };
+
+    use crate::sensor::logger_file::{
+        import_logger_file,
+        settings::{
+            LoggerFileColumns, LoggerFileFormat, TemperatureLoggerSettings, TemperatureUnit,
+        },
+        LoggerFileLog, ParseLoggerFileError, SensorFileParsers,
+    };
+    use crate::test_helpers::datetime;
+
+    fn csv_logger() -> TemperatureLoggerSettings {
+        TemperatureLoggerSettings {
+            name: "csv".to_string(),
+            format: LoggerFileFormat::Csv,
+            columns: LoggerFileColumns {
+                timestamp: "Date Time".to_string(),
+                temperature: "Temp".to_string(),
+                serial: Some("Serial".to_string()),
+            },
+            skip_lines: 1,
+            delimiter: Some(';'),
+            logs_path: None,
+            timestamp_format: Some("%d/%m/%Y %H:%M".to_string()),
+            timezone: Some("+02:00".to_string()),
+            unit: TemperatureUnit::Fahrenheit,
+        }
+    }
+
+    fn json_logger() -> TemperatureLoggerSettings {
+        TemperatureLoggerSettings {
+            name: "json".to_string(),
+            format: LoggerFileFormat::Json,
+            columns: LoggerFileColumns {
+                timestamp: "time".to_string(),
+                temperature: "value".to_string(),
+                serial: Some("device.serial".to_string()),
+            },
+            skip_lines: 0,
+            delimiter: None,
+            logs_path: Some("data.logs".to_string()),
+            timestamp_format: None,
+            timezone: Some("UTC".to_string()),
+            unit: TemperatureUnit::Celsius,
+        }
+    }
+
+    #[test]
+    fn parse_logger_files() {
+        let parsers = SensorFileParsers::from_settings(&[csv_logger(), json_logger()]);
+        assert!(parsers.get("unknown").is_none());
+
+        // Summary line, unsorted logs and a blank row, timestamps in +02:00 and °F
+        let file = "Logger summary\n\
+                    Serial;Date Time;Temp\n\
+                    LOG-1;01/01/2024 12:10;50\n\
+                    ;01/01/2024 12:00;41\n\
+                    ;;\n";
+        let result = parsers
+            .get("csv")
+            .unwrap()
+            .parse("export.csv", file.as_bytes())
+            .unwrap();
+        assert_eq!(result.serial, "LOG-1");
+        assert_eq!(
+            result.logs,
+            vec![
+                LoggerFileLog {
+                    datetime: datetime(10, 0),
+                    temperature: 5.0
+                },
+                LoggerFileLog {
+                    datetime: datetime(10, 10),
+                    temperature: 10.0
+                }
+            ]
+        );
+
+        let file = "Logger summary\nSerial;Date Time;Temp\nLOG-1;yesterday;50\n";
+        assert!(matches!(
+            parsers
+                .get("csv")
+                .unwrap()
+                .parse("export.csv", file.as_bytes()),
+            Err(ParseLoggerFileError::InvalidRow { row: 3, .. })
+        ));
+
+        let file = "Logger summary\nSerial;Date;Temp\n";
+        assert_eq!(
+            parsers
+                .get("csv")
+                .unwrap()
+                .parse("export.csv", file.as_bytes()),
+            Err(ParseLoggerFileError::ColumnMissing("Date Time".to_string()))
+        );
+
+        // Serial from nested path, numbers and strings, with and without offset
+        let file = r#"{
+            "device": { "serial": 1234 },
+            "data": { "logs": [
+                { "time": "2024-01-01T10:00:00Z", "value": 4.5 },
+                { "time": "2024-01-01 10:10:00", "value": "5" }
+            ] }
+        }"#;
+        let result = parsers
+            .get("json")
+            .unwrap()
+            .parse("export.json", file.as_bytes())
+            .unwrap();
+        assert_eq!(result.serial, "1234");
+        assert_eq!(
+            result.logs,
+            vec![
+                LoggerFileLog {
+                    datetime: datetime(10, 0),
+                    temperature: 4.5
+                },
+                LoggerFileLog {
+                    datetime: datetime(10, 10),
+                    temperature: 5.0
+                }
+            ]
+        );
+
+        // File name is used without serial
+        let file = r#"{ "data": { "logs": [{ "time": "2024-01-01T10:00:00Z", "value": 4 }] } }"#;
+        let result = parsers
+            .get("json")
+            .unwrap()
+            .parse("LOG-2.json", file.as_bytes())
+            .unwrap();
+        assert_eq!(result.serial, "LOG-2");
+
+        let file = r#"{ "data": { "logs": [] } }"#;
+        assert_eq!(
+            parsers
+                .get("json")
+                .unwrap()
+                .parse("LOG-2.json", file.as_bytes()),
+            Err(ParseLoggerFileError::NoLogs)
+        );
+    }
+
+    #[actix_rt::test]
+    async fn import_logger_file_logs_and_breaches() {
+        let store = mock_store_a();
+        let hot_consecutive = TemperatureBreachConfigRow {
+            id: "hot_consecutive".to_string(),
+            duration_milliseconds: 20 * 60 * 1000,
+            r#type: TemperatureBreachRowType::HotConsecutive,
+            is_active: true,
+            store_id: store.id.clone(),
+            minimum_temperature: 2.0,
+            maximum_temperature: 8.0,
+            ..Default::default()
+        };
+
+        let (_, connection, _, _) = setup_all_with_data(
+            "import_logger_file_logs_and_breaches",
+            MockDataInserts::none().names().stores(),
+            MockData {
+                temperature_breach_configs: vec![hot_consecutive],
+                ..Default::default()
+            },
+        )
+        .await;
+
+        let parsers = SensorFileParsers::from_settings(&[json_logger()]);
+        let parser = parsers.get("json").unwrap();
+        let file = r#"{
+            "device": { "serial": "LOG-3" },
+            "data": { "logs": [
+                { "time": "2024-01-01T10:00:00Z", "value": 5 },
+                { "time": "2024-01-01T10:10:00Z", "value": 9 },
+                { "time": "2024-01-01T10:20:00Z", "value": 10 },
+                { "time": "2024-01-01T10:30:00Z", "value": 11 }
+            ] }
+        }"#;
+
+        let result =
+            import_logger_file(&connection, &store.id, parser, "file.json", file.as_bytes())
+                .unwrap();
+        assert_eq!(result.number_of_logs, 4);
+        assert_eq!(result.number_of_breaches, 1);
+
+        let sensor = SensorRepository::new(&connection)
+            .query_by_filter(SensorFilter::new().serial(EqualFilter::equal_to("LOG-3")))
+            .unwrap()
+            .pop()
+            .unwrap()
+            .sensor_row;
+        assert_eq!(result.new_sensor_id, Some(sensor.id.clone()));
+        assert_eq!(sensor.r#type, SensorType::Generic);
+        assert_eq!(sensor.log_interval, Some(600));
+        assert_eq!(sensor.last_connection_datetime, Some(datetime(10, 30)));
+
+        // Existing logs are not duplicated
+        let result =
+            import_logger_file(&connection, &store.id, parser, "file.json", file.as_bytes())
+                .unwrap();
+        assert_eq!(result.new_sensor_id, None);
+        assert_eq!(result.number_of_logs, 0);
+        assert_eq!(result.number_of_breaches, 0);
+        assert_eq!(
+            TemperatureLogRowRepository::new(&connection)
+                .find_many_by_sensor_id(&sensor.id, None)
+                .unwrap()
+                .len(),
+            4
+        );
+    }
+}
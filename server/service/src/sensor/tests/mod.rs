@@ -1,6 +1,10 @@
 #[cfg(test)]
+mod ingest;
+#[cfg(test)]
 mod insert;
 #[cfg(test)]
+mod logger_file;
+#[cfg(test)]
 mod query;
 #[cfg(test)]
 mod update;
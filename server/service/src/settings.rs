@@ -2,14 +2,22 @@ use std::fmt::{Display, Formatter, Result};
 
 use repository::database_settings::DatabaseSettings;
 
-use crate::sync::settings::SyncSettings;
+use crate::cold_chain::notification::settings::NotificationSettings;
+use crate::sensor::logger_file::settings::TemperatureLoggerSettings;
+use crate::sync::settings::{PeerSyncSettings, SyncBandwidthSettings, SyncSettings};
 
 #[derive(serde::Deserialize, Clone)]
 pub struct Settings {
     pub server: ServerSettings,
     pub database: DatabaseSettings,
     pub sync: Option<SyncSettings>,
+    pub peer_sync: Option<PeerSyncSettings>,
+    pub sync_bandwidth: Option<SyncBandwidthSettings>,
     pub logging: Option<LoggingSettings>,
+    /// Column mappings of CSV/JSON temperature logger exports that can be uploaded
+    pub temperature_loggers: Option<Vec<TemperatureLoggerSettings>>,
+    /// Cold chain alerts through email, webhook or SMS channels
+    pub notifications: Option<NotificationSettings>,
 }
 
 #[derive(serde::Deserialize, Clone)]
@@ -20,16 +20,35 @@ pub enum StaticFileCategory {
 }
 
 impl StaticFileCategory {
-    pub fn to_path_buf(&self) -> PathBuf {
+    /// Table name and record id come from requests, only plain names are allowed so the path
+    /// can't escape the static file directory
+    pub fn to_path_buf(&self) -> anyhow::Result<PathBuf> {
         match self {
-            StaticFileCategory::Temporary => PathBuf::from("tmp"),
+            StaticFileCategory::Temporary => Ok(PathBuf::from("tmp")),
             StaticFileCategory::SyncFile(table_name, record_id) => {
-                PathBuf::from("sync_files").join(table_name).join(record_id)
+                check_path_component(table_name)?;
+                check_path_component(record_id)?;
+                Ok(PathBuf::from("sync_files").join(table_name).join(record_id))
             }
         }
     }
 }
 
+/// Only `[A-Za-z0-9_-]` is allowed in ids and names used to build static file paths
+fn check_path_component(component: &str) -> anyhow::Result<()> {
+    let is_valid = !component.is_empty()
+        && component
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if !is_valid {
+        return Err(anyhow::anyhow!(
+            "Invalid static file path component: {}",
+            component
+        ));
+    }
+    Ok(())
+}
+
 /// Stores files in a temp storage and associate an id with each file.
 /// This can, for example, be used to deposition a file for a user and the user can pick up the file
 /// by id within a certain time frame.
@@ -75,7 +94,7 @@ impl StaticFileService {
     ) -> anyhow::Result<StaticFile> {
         let id = uuid();
 
-        let dir = self.dir.join(category.to_path_buf());
+        let dir = self.dir.join(category.to_path_buf()?);
 
         std::fs::create_dir_all(&dir)?;
         let file_path = dir.join(format!("{}_{}", id, file_name));
@@ -94,7 +113,7 @@ impl StaticFileService {
     ) -> anyhow::Result<StaticFile> {
         let id = uuid();
 
-        let dir = self.dir.join(category.to_path_buf());
+        let dir = self.dir.join(category.to_path_buf()?);
 
         std::fs::create_dir_all(&dir)?;
         let file_path = dir.join(format!("{}_{}", id, file_name));
@@ -107,12 +126,31 @@ impl StaticFileService {
         Ok(file)
     }
 
+    /// Path of existing file with the id, or path for a new file with the id (e.g. sync file
+    /// added on another site)
+    pub fn file_path(
+        &self,
+        id: &str,
+        file_name: &str,
+        category: &StaticFileCategory,
+    ) -> anyhow::Result<String> {
+        check_path_component(id)?;
+        let dir = self.dir.join(category.to_path_buf()?);
+        std::fs::create_dir_all(&dir)?;
+
+        let file_path = match find_file_in_dir(id, &dir)? {
+            Some(path) => path,
+            None => dir.join(format!("{}_{}", id, file_name.replace(['/', '\\'], "_"))),
+        };
+        Ok(file_path.to_string_lossy().to_string())
+    }
+
     pub fn find_file(
         &self,
         id: &str,
         category: StaticFileCategory,
     ) -> anyhow::Result<Option<StaticFile>> {
-        let dir = self.dir.join(category.to_path_buf());
+        let dir = self.dir.join(category.to_path_buf()?);
         std::fs::create_dir_all(&dir)?;
         // clean up the static file directory
         match category {
@@ -266,6 +304,28 @@ mod test {
             .unwrap()
             .is_some());
 
+        // Table name and record id can't escape the static file directory
+        assert!(service
+            .store_file(
+                "test_sync_file",
+                StaticFileCategory::SyncFile("..".to_string(), "asset_id".to_string()),
+                "data".as_bytes(),
+            )
+            .is_err());
+        assert!(service
+            .find_file(
+                &sync_file_in.id,
+                StaticFileCategory::SyncFile("asset".to_string(), "../../tmp".to_string())
+            )
+            .is_err());
+        assert!(service
+            .file_path(
+                "../sync_file_id",
+                "test_sync_file",
+                &StaticFileCategory::SyncFile("asset".to_string(), "asset_id".to_string())
+            )
+            .is_err());
+
         // Clean up
         fs::remove_dir_all(&test_dir).unwrap();
     }
@@ -20,7 +20,7 @@ pub(crate) struct CommonSyncRecord {
     pub(crate) record_data: serde_json::Value,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub(crate) struct RemoteSyncRecordV5 {
     #[serde(rename = "syncOutId")]
     pub(crate) sync_id: String,
@@ -98,6 +98,8 @@ impl CommonSyncRecord {
             integration_datetime: None,
             integration_error: None,
             source_site_id,
+            is_ignored: false,
+            is_out_of_scope: false,
         })
     }
 }
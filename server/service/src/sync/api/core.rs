@@ -1,6 +1,12 @@
 use std::{collections::HashMap, convert::TryInto};
 
-use crate::{service_provider::ServiceProvider, sync::settings::SyncSettings};
+use crate::{
+    service_provider::ServiceProvider,
+    sync::{
+        bandwidth::{BandwidthMeter, SyncTransport},
+        settings::SyncSettings,
+    },
+};
 use repository::migrations::Version;
 use reqwest::{header::HeaderMap, Client, Response, Url};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
@@ -32,6 +38,7 @@ pub struct SyncApiSettings {
 pub struct SyncApiV5 {
     pub url: Url,
     pub settings: SyncApiSettings,
+    pub(crate) transport: SyncTransport,
 }
 
 fn tuple_vec_to_header(tuple_vec: Vec<(&str, &str)>) -> HeaderMap {
@@ -86,9 +93,18 @@ impl SyncApiV5 {
                 SyncApiV5CreatingError::CannotParseSyncUrl(settings.server_url.clone(), error)
             })?,
             settings,
+            transport: SyncTransport::default(),
         })
     }
 
+    /// Meter bytes sent and received by this api instance with shared meter (for bandwidth budget)
+    pub(crate) fn with_bandwidth_meter(self, meter: BandwidthMeter) -> Self {
+        Self {
+            transport: SyncTransport::new(meter),
+            ..self
+        }
+    }
+
     #[cfg(test)]
     pub(crate) fn new_test(url: &str, site_name: &str, password: &str, hardware_id: &str) -> Self {
         use crate::sync::settings::SYNC_VERSION;
@@ -105,6 +121,7 @@ impl SyncApiV5 {
                 app_version: Version::from_package_json().to_string(),
                 app_name: APP_NAME.to_string(),
             },
+            transport: SyncTransport::default(),
         }
     }
 
@@ -127,7 +144,7 @@ impl SyncApiV5 {
             .join(route)
             .map_err(|error| self.api_error(route, error.into()))?;
 
-        let result = Client::new()
+        let request = Client::new()
             .get(url.clone())
             .headers(tuple_vec_to_header(vec![
                 ("msupply-site-uuid", site_uuid),
@@ -136,8 +153,10 @@ impl SyncApiV5 {
                 ("version", sync_version),
             ]))
             .basic_auth(username, Some(password_sha256))
-            .query(query)
-            .send()
+            .query(query);
+        let result = self
+            .transport
+            .response(self.transport.request(request).send().await)
             .await;
 
         response_or_err(result)
@@ -164,7 +183,7 @@ impl SyncApiV5 {
             .join(route)
             .map_err(|error| self.api_error(route, error.into()))?;
 
-        let result = Client::new()
+        let request = Client::new()
             .post(url.clone())
             .headers(tuple_vec_to_header(vec![
                 ("msupply-site-uuid", site_uuid),
@@ -172,12 +191,13 @@ impl SyncApiV5 {
                 ("app-name", app_name),
                 ("version", sync_version),
             ]))
-            .basic_auth(username, Some(password_sha256))
-            // Re unwrap, from to_string documentation:
-            // Serialization can fail if T's implementation of Serialize decides to fail, or if T contains a map with non-string keys.
-            .body(serde_json::to_string(&body).unwrap())
-            .send()
-            .await;
+            .basic_auth(username, Some(password_sha256));
+        // Re unwrap, from to_vec documentation:
+        // Serialization can fail if T's implementation of Serialize decides to fail, or if T contains a map with non-string keys.
+        let request = self
+            .transport
+            .request_with_body(request, serde_json::to_vec(&body).unwrap());
+        let result = self.transport.response(request.send().await).await;
 
         response_or_err(result)
             .await
@@ -1,8 +1,10 @@
-use reqwest::{Client, Response};
+use reqwest::{header::CONTENT_TYPE, Client, Response};
+use serde::{de::DeserializeOwned, Serialize};
 use thiserror::Error;
 use url::ParseError;
 
 use super::*;
+use crate::sync::bandwidth::{BandwidthMeter, SyncTransport};
 
 // For now port_offset of 2
 pub(crate) static PORT_OFFSET: u16 = 2;
@@ -11,6 +13,7 @@ pub(crate) static PORT_OFFSET: u16 = 2;
 pub(crate) struct SyncApiV6 {
     pub(crate) url: Url,
     pub(crate) sync_v5_settings: SyncApiSettings,
+    pub(crate) transport: SyncTransport,
 }
 
 #[derive(Error, Debug)]
@@ -21,9 +24,9 @@ pub enum SyncApiV6CreatingError {
     Other(#[source] anyhow::Error),
 }
 
-async fn response_or_err(
+async fn response_or_err<T: DeserializeOwned>(
     result: Result<Response, reqwest::Error>,
-) -> Result<SyncPullResponseV6, SyncApiErrorVariantV6> {
+) -> Result<T, SyncApiErrorVariantV6> {
     let response = match result {
         Ok(result) => result,
         Err(error) => {
@@ -101,18 +104,46 @@ impl SyncApiV6 {
         Ok(Self {
             url,
             sync_v5_settings,
+            transport: SyncTransport::default(),
         })
     }
 
+    /// Meter bytes sent and received by this api instance with shared meter (for bandwidth budget)
+    pub(crate) fn with_bandwidth_meter(self, meter: BandwidthMeter) -> Self {
+        Self {
+            transport: SyncTransport::new(meter),
+            ..self
+        }
+    }
+
+    async fn do_post<T: Serialize>(
+        &self,
+        url: &Url,
+        request: &T,
+    ) -> Result<Response, reqwest::Error> {
+        let builder = Client::new()
+            .post(url.clone())
+            .header(CONTENT_TYPE, "application/json");
+        // Re unwrap, see SyncApiV5::do_post
+        let builder = self
+            .transport
+            .request_with_body(builder, serde_json::to_vec(request).unwrap());
+
+        self.transport.response(builder.send().await).await
+    }
+
     pub async fn pull(
         &self,
         cursor: u64,
         batch_size: u32,
         is_initialised: bool,
+        v6_sync_tables: Vec<ChangelogTableName>,
+        table_filter: Option<SyncPullTableFilterV6>,
     ) -> Result<SyncBatchV6, SyncApiErrorV6> {
         let Self {
             sync_v5_settings,
             url,
+            ..
         } = self;
 
         let route = "pull";
@@ -123,9 +154,11 @@ impl SyncApiV6 {
             batch_size,
             sync_v5_settings: sync_v5_settings.clone(),
             is_initialised,
+            v6_sync_tables,
+            table_filter,
         };
 
-        let result = Client::new().post(url.clone()).json(&request).send().await;
+        let result = self.do_post(&url, &request).await;
 
         let error = match response_or_err(result).await {
             Ok(SyncPullResponseV6::Data(data)) => return Ok(data),
@@ -140,10 +173,146 @@ impl SyncApiV6 {
         })
     }
 
+    /// Per table record counts for site initialisation
+    pub async fn initialisation_summary(
+        &self,
+        v6_sync_tables: Vec<ChangelogTableName>,
+    ) -> Result<SyncInitialisationSummaryV6, SyncApiErrorV6> {
+        let Self {
+            sync_v5_settings,
+            url,
+            ..
+        } = self;
+
+        let route = "initialisation_summary";
+        let url = url.join(route).unwrap();
+
+        let request = SyncInitialisationSummaryRequestV6 {
+            sync_v5_settings: sync_v5_settings.clone(),
+            v6_sync_tables,
+        };
+
+        let result = self.do_post(&url, &request).await;
+
+        let error = match response_or_err(result).await {
+            Ok(SyncInitialisationSummaryResponseV6::Data(data)) => return Ok(data),
+            Ok(SyncInitialisationSummaryResponseV6::Error(error)) => error.into(),
+            Err(error) => error,
+        };
+
+        Err(SyncApiErrorV6 {
+            url,
+            route: route.to_string(),
+            source: error,
+        })
+    }
+
+    /// Upload chunk of sync file, returns number of bytes received by central server
+    pub async fn upload_file_chunk(
+        &self,
+        chunk: SyncFileChunkV6,
+    ) -> Result<SyncUploadFileSuccessV6, SyncApiErrorV6> {
+        let Self {
+            sync_v5_settings,
+            url,
+            ..
+        } = self;
+
+        let route = "upload_file";
+        let url = url.join(route).unwrap();
+
+        let request = SyncUploadFileRequestV6 {
+            chunk,
+            sync_v5_settings: sync_v5_settings.clone(),
+        };
+
+        let result = self.do_post(&url, &request).await;
+
+        let error = match response_or_err(result).await {
+            Ok(SyncUploadFileResponseV6::Data(data)) => return Ok(data),
+            Ok(SyncUploadFileResponseV6::Error(error)) => error.into(),
+            Err(error) => error,
+        };
+
+        Err(SyncApiErrorV6 {
+            url,
+            route: route.to_string(),
+            source: error,
+        })
+    }
+
+    pub async fn download_file_chunk(
+        &self,
+        file_id: &str,
+        offset: u64,
+        chunk_size: u32,
+    ) -> Result<SyncFileChunkV6, SyncApiErrorV6> {
+        let Self {
+            sync_v5_settings,
+            url,
+            ..
+        } = self;
+
+        let route = "download_file";
+        let url = url.join(route).unwrap();
+
+        let request = SyncDownloadFileRequestV6 {
+            file_id: file_id.to_string(),
+            offset,
+            chunk_size,
+            sync_v5_settings: sync_v5_settings.clone(),
+        };
+
+        let result = self.do_post(&url, &request).await;
+
+        let error = match response_or_err(result).await {
+            Ok(SyncDownloadFileResponseV6::Data(data)) => return Ok(data),
+            Ok(SyncDownloadFileResponseV6::Error(error)) => error.into(),
+            Err(error) => error,
+        };
+
+        Err(SyncApiErrorV6 {
+            url,
+            route: route.to_string(),
+            source: error,
+        })
+    }
+
+    pub async fn report_health(&self, report: SyncHealthReportV6) -> Result<(), SyncApiErrorV6> {
+        let Self {
+            sync_v5_settings,
+            url,
+            ..
+        } = self;
+
+        let route = "report_health";
+        let url = url.join(route).unwrap();
+
+        let request = SyncReportHealthRequestV6 {
+            report,
+            sync_v5_settings: sync_v5_settings.clone(),
+        };
+
+        let result = self.do_post(&url, &request).await;
+
+        let error = match response_or_err(result).await {
+            Ok(SyncReportHealthResponseV6::Data(_)) => return Ok(()),
+            Ok(SyncReportHealthResponseV6::Error(error)) => error.into(),
+            Err(error) => error,
+        };
+
+        Err(SyncApiErrorV6 {
+            url,
+            route: route.to_string(),
+            source: error,
+        })
+    }
+
     pub async fn push(&self, batch: SyncBatchV6) -> Result<SyncPushSuccessV6, SyncApiErrorV6> {
         let Self {
             sync_v5_settings,
             url,
+            ..
         } = self;
 
         let route = "push";
@@ -154,7 +323,7 @@ impl SyncApiV6 {
             sync_v5_settings: sync_v5_settings.clone(),
         };
 
-        let result = Client::new().post(url.clone()).json(&request).send().await;
+        let result = self.do_post(&url, &request).await;
 
         let error = match response_or_err_push(result).await {
             Ok(SyncPushResponseV6::Data(data)) => return Ok(data),
@@ -1,10 +1,11 @@
 mod core;
 
-use repository::RepositoryError;
+use chrono::NaiveDateTime;
+use repository::{ChangelogTableName, RepositoryError, SyncLogRowErrorCode};
 use reqwest::Url;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
-use util::format_error;
+use util::{format_error, hash::sha256};
 
 use crate::i64_to_u64;
 
@@ -34,6 +35,16 @@ pub enum SyncParsedErrorV6 {
     NotACentralServer,
     #[error("Could not parse record to sync buffer row: {0}")]
     ParsingSyncRecordError(String),
+    #[error("Batch checksum does not match records")]
+    ChecksumMismatch,
+    #[error("Sync file reference not found: {0}")]
+    SyncFileNotFound(String),
+    #[error("Sync file is not yet uploaded to central server: {0}")]
+    SyncFileNotAvailable(String),
+    #[error("Uploaded file hash does not match, upload restarts from the beginning")]
+    SyncFileHashMismatch,
+    #[error("Site does not have access to the record of sync file: {0}")]
+    SyncFileAccessDenied(String),
 }
 
 impl From<SyncApiError> for SyncParsedErrorV6 {
@@ -115,6 +126,14 @@ pub struct SyncBatchV6 {
     // Including records in this batch
     pub(crate) total_records: u64,
     pub(crate) records: Vec<SyncRecordV6>,
+    /// See `batch_checksum`, optional for compatibility with older servers
+    #[serde(default)]
+    pub(crate) checksum: Option<String>,
+}
+
+/// Checksum of serialised records in a batch, to make sure batch was not truncated or altered in transit
+pub(crate) fn batch_checksum(records: &[SyncRecordV6]) -> String {
+    sha256(&serde_json::to_string(records).unwrap_or_default())
 }
 
 impl From<PushSyncRecord> for SyncRecordV6 {
@@ -132,6 +151,20 @@ pub struct SyncPullRequestV6 {
     pub(crate) batch_size: u32,
     pub(crate) sync_v5_settings: SyncApiSettings,
     pub(crate) is_initialised: bool,
+    /// Remote record tables the site switched from legacy to v6 sync
+    #[serde(default)]
+    pub(crate) v6_sync_tables: Vec<ChangelogTableName>,
+    /// Tables deferred while site is over bandwidth budget are excluded, and pulled on their own
+    /// once site is back under budget
+    #[serde(default)]
+    pub(crate) table_filter: Option<SyncPullTableFilterV6>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum SyncPullTableFilterV6 {
+    Only(Vec<ChangelogTableName>),
+    Exclude(Vec<ChangelogTableName>),
 }
 
 #[derive(Serialize, Deserialize)]
@@ -140,3 +173,115 @@ pub struct SyncPushRequestV6 {
     pub(crate) batch: SyncBatchV6,
     pub(crate) sync_v5_settings: SyncApiSettings,
 }
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncInitialisationSummaryRequestV6 {
+    pub(crate) sync_v5_settings: SyncApiSettings,
+    #[serde(default)]
+    pub(crate) v6_sync_tables: Vec<ChangelogTableName>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncTableCountV6 {
+    /// Sync record table name, matches sync buffer table_name
+    pub table_name: String,
+    pub count: u64,
+}
+
+/// Number of records per table central server will send to the site during initialisation
+#[derive(Deserialize, Debug, Default, Serialize)]
+pub struct SyncInitialisationSummaryV6 {
+    pub(crate) tables: Vec<SyncTableCountV6>,
+}
+
+#[derive(Deserialize, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SyncInitialisationSummaryResponseV6 {
+    Data(SyncInitialisationSummaryV6),
+    Error(SyncParsedErrorV6),
+}
+
+/// Chunk of sync file, data is base64 encoded
+#[derive(Deserialize, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncFileChunkV6 {
+    pub(crate) file_id: String,
+    /// Position of `data` in the file
+    pub(crate) offset: u64,
+    pub(crate) total_bytes: u64,
+    /// Sha256 of whole file (hex), checked once all chunks are received
+    pub(crate) hash: String,
+    pub(crate) data: String,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncUploadFileRequestV6 {
+    pub(crate) chunk: SyncFileChunkV6,
+    pub(crate) sync_v5_settings: SyncApiSettings,
+}
+
+/// Bytes of the file received by central server, upload resumes from this offset
+#[derive(Deserialize, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncUploadFileSuccessV6 {
+    pub(crate) received_bytes: u64,
+}
+
+#[derive(Deserialize, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SyncUploadFileResponseV6 {
+    Data(SyncUploadFileSuccessV6),
+    Error(SyncParsedErrorV6),
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncDownloadFileRequestV6 {
+    pub(crate) file_id: String,
+    pub(crate) offset: u64,
+    pub(crate) chunk_size: u32,
+    pub(crate) sync_v5_settings: SyncApiSettings,
+}
+
+#[derive(Deserialize, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SyncDownloadFileResponseV6 {
+    Data(SyncFileChunkV6),
+    Error(SyncParsedErrorV6),
+}
+
+/// Sync health of remote site, reported to central server after every sync
+#[derive(Deserialize, Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncHealthReportV6 {
+    /// Start of the latest sync
+    pub(crate) last_sync_datetime: Option<NaiveDateTime>,
+    pub(crate) last_successful_sync_datetime: Option<NaiveDateTime>,
+    /// Error of the latest sync
+    pub(crate) error_code: Option<SyncLogRowErrorCode>,
+    pub(crate) error_message: Option<String>,
+    /// Change logs waiting to be pushed
+    pub(crate) push_queue_count: u64,
+    /// Size of site database in bytes
+    pub(crate) database_size: u64,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncReportHealthRequestV6 {
+    pub(crate) report: SyncHealthReportV6,
+    pub(crate) sync_v5_settings: SyncApiSettings,
+}
+
+#[derive(Deserialize, Debug, Default, Serialize)]
+pub struct SyncReportHealthSuccessV6 {}
+
+#[derive(Deserialize, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SyncReportHealthResponseV6 {
+    Data(SyncReportHealthSuccessV6),
+    Error(SyncParsedErrorV6),
+}
@@ -0,0 +1,709 @@
+use std::{
+    io::{Read, Write},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use chrono::{NaiveDate, Utc};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use repository::{
+    ChangelogFilter, ChangelogRepository, ChangelogRow, ChangelogTableName, EqualFilter,
+    KeyValueStoreRepository, KeyValueType, RepositoryError, StorageConnection,
+};
+use reqwest::{
+    header::{ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_LENGTH},
+    RequestBuilder, Response,
+};
+use serde::{Deserialize, Serialize};
+use util::inline_init;
+
+use crate::cursor_controller::CursorController;
+
+use super::{api_v6::SyncPullTableFilterV6, settings::SyncBandwidthSettings};
+
+/// Supported encodings in order of preference
+const SYNC_ENCODINGS: [SyncEncoding; 2] = [SyncEncoding::Zstd, SyncEncoding::Gzip];
+/// Accept-Encoding header value listing SYNC_ENCODINGS, also advertised by central server for request bodies
+pub const SYNC_ACCEPT_ENCODING: &str = "zstd, gzip";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum SyncEncoding {
+    Zstd,
+    Gzip,
+}
+
+impl SyncEncoding {
+    fn name(&self) -> &'static str {
+        match self {
+            SyncEncoding::Zstd => "zstd",
+            SyncEncoding::Gzip => "gzip",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        SYNC_ENCODINGS
+            .iter()
+            .copied()
+            .find(|encoding| encoding.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// Most preferred of the encodings listed in Accept-Encoding header (quality values are ignored)
+    fn preferred(accept_encoding: &str) -> Option<Self> {
+        let accepted: Vec<SyncEncoding> = accept_encoding
+            .split(',')
+            .filter_map(|value| value.split(';').next().and_then(SyncEncoding::from_name))
+            .collect();
+
+        SYNC_ENCODINGS
+            .iter()
+            .copied()
+            .find(|encoding| accepted.contains(encoding))
+    }
+
+    pub(crate) fn encode(&self, bytes: &[u8]) -> std::io::Result<Vec<u8>> {
+        match self {
+            SyncEncoding::Zstd => zstd::stream::encode_all(bytes, 0),
+            SyncEncoding::Gzip => gzip(bytes),
+        }
+    }
+
+    pub(crate) fn decode(&self, bytes: &[u8]) -> std::io::Result<Vec<u8>> {
+        match self {
+            SyncEncoding::Zstd => zstd::stream::decode_all(bytes),
+            SyncEncoding::Gzip => gunzip(bytes),
+        }
+    }
+}
+
+/// Not needed urgently by central server or other sites, these are not pushed (or pulled from v6 central server) while
+/// daily bandwidth budget is exceeded
+pub(crate) const DEFERRABLE_TABLES: [ChangelogTableName; 3] = [
+    ChangelogTableName::ActivityLog,
+    ChangelogTableName::AssetLog,
+    ChangelogTableName::TemperatureLog,
+];
+
+/// Bytes sent and received over the wire (after compression), shared by sync api instances
+#[derive(Debug, Clone, Default)]
+pub(crate) struct BandwidthMeter(Arc<AtomicU64>);
+
+impl BandwidthMeter {
+    pub(crate) fn add(&self, bytes: usize) {
+        self.0.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    /// Bytes metered since last take
+    pub(crate) fn take(&self) -> u64 {
+        self.0.swap(0, Ordering::Relaxed)
+    }
+}
+
+/// Negotiates compression with sync server and meters bandwidth.
+///
+/// Responses are requested with `Accept-Encoding: zstd, gzip`, request bodies are only compressed after server
+/// advertised which encodings it accepts for request bodies, with `Accept-Encoding` response header (RFC 7694),
+/// this way sync still works with servers that don't support compression (i.e. older legacy central servers)
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SyncTransport {
+    meter: BandwidthMeter,
+    request_body_encoding: Arc<Mutex<Option<SyncEncoding>>>,
+}
+
+impl SyncTransport {
+    pub(crate) fn new(meter: BandwidthMeter) -> Self {
+        Self {
+            meter,
+            request_body_encoding: Default::default(),
+        }
+    }
+
+    pub(crate) fn request(&self, builder: RequestBuilder) -> RequestBuilder {
+        builder.header(ACCEPT_ENCODING, SYNC_ACCEPT_ENCODING)
+    }
+
+    pub(crate) fn request_with_body(
+        &self,
+        builder: RequestBuilder,
+        body: Vec<u8>,
+    ) -> RequestBuilder {
+        let builder = self.request(builder);
+
+        let encoding = *self.request_body_encoding.lock().unwrap();
+        let (builder, body) = match encoding {
+            Some(encoding) => match encoding.encode(&body) {
+                Ok(compressed) => (
+                    builder.header(CONTENT_ENCODING, encoding.name()),
+                    compressed,
+                ),
+                Err(error) => {
+                    log::error!("Failed to compress sync request body {}", error);
+                    (builder, body)
+                }
+            },
+            None => (builder, body),
+        };
+
+        self.meter.add(body.len());
+        builder.body(body)
+    }
+
+    /// Read (and meter) response body, decompressing it if needed, response is re-created with decompressed body
+    pub(crate) async fn response(
+        &self,
+        result: Result<Response, reqwest::Error>,
+    ) -> Result<Response, reqwest::Error> {
+        let response = result?;
+        let request_body_encoding = response
+            .headers()
+            .get(ACCEPT_ENCODING)
+            .and_then(|value| value.to_str().ok())
+            .and_then(SyncEncoding::preferred);
+        *self.request_body_encoding.lock().unwrap() = request_body_encoding;
+
+        let status = response.status();
+        let version = response.version();
+        let mut headers = response.headers().clone();
+        let encoding = headers
+            .get(CONTENT_ENCODING)
+            .and_then(|value| value.to_str().ok())
+            .and_then(SyncEncoding::from_name);
+
+        let bytes = response.bytes().await?;
+        self.meter.add(bytes.len());
+
+        let body = match encoding {
+            Some(encoding) => match encoding.decode(&bytes) {
+                Ok(body) => {
+                    headers.remove(CONTENT_ENCODING);
+                    headers.remove(CONTENT_LENGTH);
+                    body
+                }
+                Err(error) => {
+                    // Body will fail to parse and raw response is shown in parsing error
+                    log::error!("Failed to decompress sync response body {}", error);
+                    bytes.to_vec()
+                }
+            },
+            None => bytes.to_vec(),
+        };
+
+        let mut rebuilt = http::Response::new(body);
+        *rebuilt.status_mut() = status;
+        *rebuilt.version_mut() = version;
+        *rebuilt.headers_mut() = headers;
+
+        Ok(Response::from(rebuilt))
+    }
+}
+
+pub(crate) fn gzip(bytes: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(bytes)?;
+    encoder.finish()
+}
+
+pub(crate) fn gunzip(bytes: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut result = Vec::new();
+    GzDecoder::new(bytes).read_to_end(&mut result)?;
+    Ok(result)
+}
+
+// Aim for requests that are long enough to keep the link busy, but short enough to not have
+// to resend too much when connection drops
+const TARGET_REQUEST_DURATION: Duration = Duration::from_secs(5);
+const MAX_GROWTH_FACTOR: u32 = 4;
+
+/// Records per request adjusted to observed throughput, starting from configured batch size
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct AdaptiveBatchSize {
+    current: u32,
+    min: u32,
+    max: u32,
+}
+
+impl AdaptiveBatchSize {
+    pub(crate) fn new(initial: u32) -> Self {
+        let initial = initial.max(1);
+        Self {
+            current: initial,
+            min: (initial / 20).max(1),
+            max: initial * MAX_GROWTH_FACTOR,
+        }
+    }
+
+    pub(crate) fn get(&self) -> u32 {
+        self.current
+    }
+
+    /// Update batch size from duration of a request with `records` number of records,
+    /// batch size changes by at most a factor of 2 per request and only grows after full batches
+    pub(crate) fn update(&mut self, elapsed: Duration, records: u32) {
+        if records == 0 {
+            return;
+        }
+
+        let seconds = elapsed.as_secs_f64().max(0.001);
+        let target = (records as f64 / seconds * TARGET_REQUEST_DURATION.as_secs_f64()) as u32;
+
+        let upper = match records >= self.current {
+            true => self.current.saturating_mul(2),
+            false => self.current,
+        };
+        let lower = self.current / 2;
+
+        self.current = target.clamp(lower, upper).clamp(self.min, self.max);
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+struct DailyUsage {
+    date: NaiveDate,
+    bytes: u64,
+}
+
+fn get_daily_usage(connection: &StorageConnection) -> Result<DailyUsage, RepositoryError> {
+    let today = Utc::now().naive_utc().date();
+    let usage = KeyValueStoreRepository::new(connection)
+        .get_string(KeyValueType::SyncBandwidthDailyUsage)?
+        .and_then(|usage| serde_json::from_str::<DailyUsage>(&usage).ok())
+        .filter(|usage| usage.date == today);
+
+    Ok(usage.unwrap_or(DailyUsage {
+        date: today,
+        bytes: 0,
+    }))
+}
+
+/// Bytes used by sync today (UTC)
+pub(crate) fn daily_usage(connection: &StorageConnection) -> Result<u64, RepositoryError> {
+    Ok(get_daily_usage(connection)?.bytes)
+}
+
+pub(crate) fn add_daily_usage(
+    connection: &StorageConnection,
+    bytes: u64,
+) -> Result<(), RepositoryError> {
+    let mut usage = get_daily_usage(connection)?;
+    usage.bytes += bytes;
+
+    KeyValueStoreRepository::new(connection).set_string(
+        KeyValueType::SyncBandwidthDailyUsage,
+        serde_json::to_string(&usage).ok(),
+    )
+}
+
+pub(crate) fn is_over_budget(
+    connection: &StorageConnection,
+    settings: &Option<SyncBandwidthSettings>,
+) -> Result<bool, RepositoryError> {
+    let Some(settings) = settings else {
+        return Ok(false);
+    };
+
+    Ok(daily_usage(connection)? >= settings.daily_budget_bytes())
+}
+
+pub(crate) struct PushBatch {
+    pub(crate) changelogs: Vec<ChangelogRow>,
+    /// Changelogs remaining to be pushed, including this batch
+    pub(crate) remaining: u64,
+    is_deferred: bool,
+}
+
+/// Changelogs to push, taking bandwidth budget into account.
+///
+/// While over budget DEFERRABLE_TABLES are skipped and the cursor at which they were first skipped is kept in
+/// deferred cursor. Once back under budget deferred changelogs (between deferred cursor and push cursor) are pushed
+/// first, before continuing with the push cursor
+pub(crate) struct PushQueue {
+    cursor_controller: CursorController,
+    deferred_cursor_type: KeyValueType,
+    filter: Option<ChangelogFilter>,
+    is_over_budget: bool,
+}
+
+impl PushQueue {
+    pub(crate) fn new(
+        cursor_type: KeyValueType,
+        deferred_cursor_type: KeyValueType,
+        filter: Option<ChangelogFilter>,
+        is_over_budget: bool,
+    ) -> Self {
+        Self {
+            cursor_controller: CursorController::new(cursor_type),
+            deferred_cursor_type,
+            filter,
+            is_over_budget,
+        }
+    }
+
+    pub(crate) fn next_batch(
+        &self,
+        connection: &StorageConnection,
+        batch_size: u32,
+    ) -> Result<PushBatch, RepositoryError> {
+        let changelog_repo = ChangelogRepository::new(connection);
+        let key_value_store = KeyValueStoreRepository::new(connection);
+        let cursor = self.cursor_controller.get(connection)?;
+        let deferred_cursor = key_value_store.get_i32(self.deferred_cursor_type.clone())?;
+
+        if let (false, Some(deferred_cursor)) = (self.is_over_budget, deferred_cursor) {
+            let deferred_filter =
+                self.filter_tables(inline_init(|r: &mut EqualFilter<ChangelogTableName>| {
+                    r.equal_any = Some(DEFERRABLE_TABLES.to_vec())
+                }));
+            // Changelogs past push cursor are pushed as part of normal push
+            let changelogs: Vec<ChangelogRow> = changelog_repo
+                .changelogs(deferred_cursor as u64, batch_size, deferred_filter.clone())?
+                .into_iter()
+                .filter(|changelog| (changelog.cursor as u64) < cursor)
+                .collect();
+
+            if !changelogs.is_empty() {
+                // Deferred changelogs past push cursor are counted as part of normal push
+                let deferred_remaining = changelog_repo
+                    .count(deferred_cursor as u64, deferred_filter.clone())?
+                    .saturating_sub(changelog_repo.count(cursor, deferred_filter)?);
+                let remaining =
+                    deferred_remaining + changelog_repo.count(cursor, self.filter.clone())?;
+                return Ok(PushBatch {
+                    changelogs,
+                    remaining,
+                    is_deferred: true,
+                });
+            }
+
+            key_value_store.set_i32(self.deferred_cursor_type.clone(), None)?;
+        }
+
+        let filter = match self.is_over_budget {
+            true => {
+                if deferred_cursor.is_none() {
+                    key_value_store
+                        .set_i32(self.deferred_cursor_type.clone(), Some(cursor as i32))?;
+                }
+                self.filter_tables(inline_init(|r: &mut EqualFilter<ChangelogTableName>| {
+                    r.not_equal_all = Some(DEFERRABLE_TABLES.to_vec())
+                }))
+            }
+            false => self.filter.clone(),
+        };
+
+        Ok(PushBatch {
+            changelogs: changelog_repo.changelogs(cursor, batch_size, filter.clone())?,
+            remaining: changelog_repo.count(cursor, filter)?,
+            is_deferred: false,
+        })
+    }
+
+    /// Update cursor after batch was pushed
+    pub(crate) fn advance(
+        &self,
+        connection: &StorageConnection,
+        batch: &PushBatch,
+    ) -> Result<(), RepositoryError> {
+        let Some(last_pushed_cursor) = batch.changelogs.last().map(|log| log.cursor) else {
+            return Ok(());
+        };
+
+        match batch.is_deferred {
+            true => KeyValueStoreRepository::new(connection).set_i32(
+                self.deferred_cursor_type.clone(),
+                Some(last_pushed_cursor as i32 + 1),
+            ),
+            false => self
+                .cursor_controller
+                .update(connection, last_pushed_cursor as u64 + 1),
+        }
+    }
+
+    fn filter_tables(
+        &self,
+        table_name: EqualFilter<ChangelogTableName>,
+    ) -> Option<ChangelogFilter> {
+        Some(
+            self.filter
+                .clone()
+                .unwrap_or_default()
+                .table_name(table_name),
+        )
+    }
+}
+
+pub(crate) struct PullRequest {
+    pub(crate) cursor: u64,
+    pub(crate) table_filter: Option<SyncPullTableFilterV6>,
+    /// Pull cursor, or deferred cursor when pulling deferred records
+    pub(crate) cursor_controller: CursorController,
+    /// Pull cursor when pulling deferred records, records from it are pulled by normal pull
+    end_cursor: Option<u64>,
+}
+
+impl PullRequest {
+    pub(crate) fn is_deferred(&self) -> bool {
+        self.end_cursor.is_some()
+    }
+
+    /// Record with this cursor should be saved (deferred records past pull cursor are saved by normal pull)
+    pub(crate) fn includes(&self, cursor: u64) -> bool {
+        self.end_cursor
+            .map(|end_cursor| cursor < end_cursor)
+            .unwrap_or(true)
+    }
+}
+
+/// V6 pull requests, taking bandwidth budget into account.
+///
+/// Same as PushQueue, while over budget DEFERRABLE_TABLES are excluded by central server and the cursor at which
+/// they were first excluded is kept in deferred cursor. Once back under budget deferred tables are pulled first
+/// (from deferred cursor up to pull cursor), before continuing with the pull cursor
+pub(crate) struct PullQueue {
+    cursor_type: KeyValueType,
+    deferred_cursor_type: KeyValueType,
+    is_over_budget: bool,
+}
+
+impl PullQueue {
+    pub(crate) fn new(
+        cursor_type: KeyValueType,
+        deferred_cursor_type: KeyValueType,
+        is_over_budget: bool,
+    ) -> Self {
+        Self {
+            cursor_type,
+            deferred_cursor_type,
+            is_over_budget,
+        }
+    }
+
+    pub(crate) fn next_request(
+        &self,
+        connection: &StorageConnection,
+    ) -> Result<PullRequest, RepositoryError> {
+        let key_value_store = KeyValueStoreRepository::new(connection);
+        let cursor = CursorController::new(self.cursor_type.clone()).get(connection)?;
+        let deferred_cursor = key_value_store.get_i32(self.deferred_cursor_type.clone())?;
+
+        if let (false, Some(deferred_cursor)) = (self.is_over_budget, deferred_cursor) {
+            if (deferred_cursor as u64) < cursor {
+                return Ok(PullRequest {
+                    cursor: deferred_cursor as u64,
+                    table_filter: Some(SyncPullTableFilterV6::Only(DEFERRABLE_TABLES.to_vec())),
+                    cursor_controller: CursorController::new(self.deferred_cursor_type.clone()),
+                    end_cursor: Some(cursor),
+                });
+            }
+
+            key_value_store.set_i32(self.deferred_cursor_type.clone(), None)?;
+        }
+
+        let table_filter = match self.is_over_budget {
+            true => {
+                if deferred_cursor.is_none() {
+                    key_value_store
+                        .set_i32(self.deferred_cursor_type.clone(), Some(cursor as i32))?;
+                }
+                Some(SyncPullTableFilterV6::Exclude(DEFERRABLE_TABLES.to_vec()))
+            }
+            false => None,
+        };
+
+        Ok(PullRequest {
+            cursor,
+            table_filter,
+            cursor_controller: CursorController::new(self.cursor_type.clone()),
+            end_cursor: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use repository::{
+        mock::MockDataInserts, test_db::setup_all, ChangeLogInsertRow, ChangelogAction,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_encoding_round_trip() {
+        let body = serde_json::to_vec(&vec!["record"; 1000]).unwrap();
+
+        for encoding in SYNC_ENCODINGS.iter() {
+            let compressed = encoding.encode(&body).unwrap();
+            assert!(compressed.len() < body.len());
+            assert_eq!(encoding.decode(&compressed).unwrap(), body);
+        }
+    }
+
+    #[test]
+    fn test_preferred_encoding() {
+        assert_eq!(
+            SyncEncoding::preferred(SYNC_ACCEPT_ENCODING),
+            Some(SyncEncoding::Zstd)
+        );
+        assert_eq!(
+            SyncEncoding::preferred("gzip;q=1.0, br"),
+            Some(SyncEncoding::Gzip)
+        );
+        assert_eq!(
+            SyncEncoding::preferred("GZIP, ZSTD"),
+            Some(SyncEncoding::Zstd)
+        );
+        assert_eq!(SyncEncoding::preferred("identity"), None);
+    }
+
+    #[test]
+    fn test_adaptive_batch_size() {
+        let mut batch_size = AdaptiveBatchSize::new(500);
+
+        // Slow connection, halves at most
+        batch_size.update(Duration::from_secs(60), 500);
+        assert_eq!(batch_size.get(), 250);
+        batch_size.update(Duration::from_secs(10), 250);
+        assert_eq!(batch_size.get(), 125);
+
+        // Partial batch doesn't grow batch size
+        batch_size.update(Duration::from_millis(100), 10);
+        assert_eq!(batch_size.get(), 125);
+
+        // Fast connection, doubles at most, up to 4 times initial
+        batch_size.update(Duration::from_millis(100), 125);
+        assert_eq!(batch_size.get(), 250);
+        for _ in 0..5 {
+            let current = batch_size.get();
+            batch_size.update(Duration::from_millis(100), current);
+        }
+        assert_eq!(batch_size.get(), 2000);
+
+        // Very slow connection, never below 1/20 of initial
+        for _ in 0..10 {
+            let current = batch_size.get();
+            batch_size.update(Duration::from_secs(600), current);
+        }
+        assert_eq!(batch_size.get(), 25);
+    }
+
+    #[actix_rt::test]
+    async fn test_push_queue_defers_tables() {
+        let (_, connection, _, _) =
+            setup_all("test_push_queue_defers_tables", MockDataInserts::none()).await;
+
+        let insert = |table_name: ChangelogTableName, record_id: &str| {
+            ChangelogRepository::new(&connection)
+                .insert(&ChangeLogInsertRow {
+                    table_name,
+                    record_id: record_id.to_string(),
+                    row_action: ChangelogAction::Upsert,
+                    name_link_id: None,
+                    store_id: None,
+                })
+                .unwrap()
+        };
+        let record_ids = |batch: &PushBatch| -> Vec<String> {
+            batch
+                .changelogs
+                .iter()
+                .map(|log| log.record_id.clone())
+                .collect()
+        };
+        let queue = |is_over_budget: bool| {
+            PushQueue::new(
+                KeyValueType::RemoteSyncPushCursor,
+                KeyValueType::RemoteSyncPushDeferredCursor,
+                None,
+                is_over_budget,
+            )
+        };
+
+        insert(ChangelogTableName::Location, "location1");
+        insert(ChangelogTableName::ActivityLog, "activity_log1");
+        insert(ChangelogTableName::Location, "location2");
+
+        // Over budget, activity log is skipped
+        let over_budget = queue(true);
+        let batch = over_budget.next_batch(&connection, 10).unwrap();
+        assert_eq!(record_ids(&batch), vec!["location1", "location2"]);
+        assert_eq!(batch.remaining, 2);
+        over_budget.advance(&connection, &batch).unwrap();
+        assert_eq!(
+            over_budget.next_batch(&connection, 10).unwrap().remaining,
+            0
+        );
+
+        insert(ChangelogTableName::ActivityLog, "activity_log2");
+
+        // Back under budget, deferred records are pushed first
+        let under_budget = queue(false);
+        let batch = under_budget.next_batch(&connection, 10).unwrap();
+        assert_eq!(record_ids(&batch), vec!["activity_log1"]);
+        assert_eq!(batch.remaining, 2);
+        under_budget.advance(&connection, &batch).unwrap();
+
+        let batch = under_budget.next_batch(&connection, 10).unwrap();
+        assert_eq!(record_ids(&batch), vec!["activity_log2"]);
+        under_budget.advance(&connection, &batch).unwrap();
+
+        let batch = under_budget.next_batch(&connection, 10).unwrap();
+        assert_eq!(batch.remaining, 0);
+        assert_eq!(
+            KeyValueStoreRepository::new(&connection)
+                .get_i32(KeyValueType::RemoteSyncPushDeferredCursor)
+                .unwrap(),
+            None
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_pull_queue_defers_tables() {
+        let (_, connection, _, _) =
+            setup_all("test_pull_queue_defers_tables", MockDataInserts::none()).await;
+
+        let queue = |is_over_budget: bool| {
+            PullQueue::new(
+                KeyValueType::SyncPullCursorV6,
+                KeyValueType::SyncPullDeferredCursorV6,
+                is_over_budget,
+            )
+        };
+        let pull_cursor = CursorController::new(KeyValueType::SyncPullCursorV6);
+        pull_cursor.update(&connection, 5).unwrap();
+
+        // Over budget, deferrable tables are excluded from the pull cursor
+        let request = queue(true).next_request(&connection).unwrap();
+        assert_eq!(request.cursor, 5);
+        assert_eq!(
+            request.table_filter,
+            Some(SyncPullTableFilterV6::Exclude(DEFERRABLE_TABLES.to_vec()))
+        );
+        assert!(!request.is_deferred());
+        request.cursor_controller.update(&connection, 10).unwrap();
+
+        // Back under budget, deferrable tables are pulled from where they were first excluded
+        let request = queue(false).next_request(&connection).unwrap();
+        assert_eq!(request.cursor, 5);
+        assert_eq!(
+            request.table_filter,
+            Some(SyncPullTableFilterV6::Only(DEFERRABLE_TABLES.to_vec()))
+        );
+        assert!(request.includes(9));
+        assert!(!request.includes(10));
+        request.cursor_controller.update(&connection, 11).unwrap();
+        assert_eq!(pull_cursor.get(&connection).unwrap(), 10);
+
+        // Deferred records caught up, continue with pull cursor
+        let request = queue(false).next_request(&connection).unwrap();
+        assert_eq!(request.cursor, 10);
+        assert_eq!(request.table_filter, None);
+        assert!(!request.is_deferred());
+        assert_eq!(
+            KeyValueStoreRepository::new(&connection)
+                .get_i32(KeyValueType::SyncPullDeferredCursorV6)
+                .unwrap(),
+            None
+        );
+    }
+}
@@ -1,5 +1,9 @@
+use std::time::Instant;
+
 use super::{
     api::{ParsingSyncRecordError, SyncApiError, SyncApiV5},
+    bandwidth::AdaptiveBatchSize,
+    sync_buffer::SyncBuffer,
     sync_status::logger::{SyncLogger, SyncLoggerError, SyncStepProgress},
 };
 use crate::{cursor_controller::CursorController, sync::api::CentralSyncBatchV5};
@@ -18,6 +22,8 @@ pub(crate) enum CentralPullError {
     ParsingRecordError(#[from] ParsingSyncRecordError),
     #[error(transparent)]
     SyncLoggerError(#[from] SyncLoggerError),
+    #[error("Saved sync buffer rows do not match pulled batch, batch starting at cursor {0} will be pulled again")]
+    ChecksumMismatch(u64),
 }
 
 pub(crate) struct CentralDataSynchroniser {
@@ -34,23 +40,34 @@ impl CentralDataSynchroniser {
         // TODO protection from infinite loop
 
         let cursor_controller = CursorController::new(KeyValueType::CentralSyncPullCursor);
+        let mut batch_size = AdaptiveBatchSize::new(batch_size);
 
         loop {
-            let mut cursor = cursor_controller.get(&connection)?;
+            let batch_cursor = cursor_controller.get(&connection)?;
+            let mut cursor = batch_cursor;
 
+            let start = Instant::now();
             let CentralSyncBatchV5 { max_cursor, data } = self
                 .sync_api_v5
-                .get_central_records(cursor, batch_size)
+                .get_central_records(cursor, batch_size.get())
                 .await?;
             let batch_length = data.len();
+            batch_size.update(start.elapsed(), batch_length as u32);
 
             logger.progress(SyncStepProgress::PullCentral, max_cursor - cursor)?;
 
+            let mut buffer_rows = Vec::new();
             for sync_record in data {
                 cursor = sync_record.cursor.clone();
                 let buffer_row = sync_record.record.to_buffer_row(None)?;
 
                 insert_one_and_update_cursor(connection, &cursor_controller, &buffer_row, cursor)?;
+                buffer_rows.push(buffer_row);
+            }
+
+            if !SyncBuffer::new(connection).is_batch_saved(&buffer_rows)? {
+                cursor_controller.update(&connection, batch_cursor)?;
+                return Err(CentralPullError::ChecksumMismatch(batch_cursor));
             }
 
             logger.progress(SyncStepProgress::PullCentral, max_cursor - cursor)?;
@@ -1,7 +1,10 @@
+use std::time::Instant;
+
 use crate::{
     cursor_controller::CursorController,
     sync::{
-        api_v6::{SyncBatchV6, SyncRecordV6},
+        api_v6::{batch_checksum, SyncBatchV6, SyncRecordV6, SyncTableCountV6},
+        bandwidth::{AdaptiveBatchSize, PullQueue, PushQueue},
         sync_status::logger::SyncStepProgress,
     },
 };
@@ -14,14 +17,16 @@ use super::{
     translations::{
         translate_changelogs_to_sync_records, PushTranslationError, ToSyncRecordTranslationType,
     },
+    v6_sync_tables::get_v6_sync_tables,
     GetActiveStoresOnSiteError,
 };
 
 use log::debug;
 use repository::{
-    ChangelogRepository, KeyValueType, RepositoryError, StorageConnection, SyncBufferRow,
-    SyncBufferRowRepository,
+    KeyValueStoreRepository, KeyValueType, RepositoryError, StorageConnection,
+    SyncBufferRepository, SyncBufferRow, SyncBufferRowRepository,
 };
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -34,6 +39,8 @@ pub(crate) enum CentralPullErrorV6 {
     ParsingRecordError(#[from] ParsingSyncRecordError),
     #[error(transparent)]
     SyncLoggerError(#[from] SyncLoggerError),
+    #[error("Batch checksum does not match records, batch starting at cursor {0} was discarded")]
+    ChecksumMismatch(u64),
 }
 
 #[derive(Error, Debug)]
@@ -61,69 +68,183 @@ pub(crate) struct CentralDataSynchroniserV6 {
     pub(crate) sync_api_v6: SyncApiV6,
 }
 
+/// Expected record counts per table during initialisation, as reported by central server
+#[derive(Deserialize, Serialize, Default, Debug)]
+pub(crate) struct InitialisationSummary {
+    pub(crate) tables: Vec<SyncTableCountV6>,
+    /// All v6 records are pulled again when records are missing at the end of initialisation,
+    /// this is only done once to avoid initialisation never finishing
+    pub(crate) is_repull_requested: bool,
+}
+
 impl CentralDataSynchroniserV6 {
     pub(crate) async fn pull<'a>(
         &self,
         connection: &StorageConnection,
         batch_size: u32,
         is_initialised: bool,
+        is_over_budget: bool,
         logger: &mut SyncLogger<'a>,
     ) -> Result<(), CentralPullErrorV6> {
-        let cursor_controller = CursorController::new(KeyValueType::SyncPullCursorV6);
+        let pull_queue = PullQueue::new(
+            KeyValueType::SyncPullCursorV6,
+            KeyValueType::SyncPullDeferredCursorV6,
+            is_over_budget,
+        );
+        let mut batch_size = AdaptiveBatchSize::new(batch_size);
+        let v6_sync_tables = get_v6_sync_tables(connection)?;
         // TODO protection from infinite loop
         loop {
-            let cursor = cursor_controller.get(&connection)?;
+            let request = pull_queue.next_request(connection)?;
+            let cursor = request.cursor;
 
+            let start = Instant::now();
             let SyncBatchV6 {
                 end_cursor,
                 total_records,
                 records,
+                checksum,
             } = self
                 .sync_api_v6
-                .pull(cursor, batch_size, is_initialised)
+                .pull(
+                    cursor,
+                    batch_size.get(),
+                    is_initialised,
+                    v6_sync_tables.clone(),
+                    request.table_filter.clone(),
+                )
                 .await?;
+            batch_size.update(start.elapsed(), records.len() as u32);
+
+            // Nothing from this batch is saved if checksum doesn't match, next sync will resume from this batch
+            if let Some(checksum) = checksum {
+                if checksum != batch_checksum(&records) {
+                    return Err(CentralPullErrorV6::ChecksumMismatch(cursor));
+                }
+            }
 
             logger.progress(SyncStepProgress::PullCentralV6, total_records)?;
 
             let is_empty = records.is_empty();
 
             for SyncRecordV6 { cursor, record } in records {
+                if !request.includes(cursor as u64) {
+                    continue;
+                }
                 let buffer_row = record.to_buffer_row(None)?;
 
                 insert_one_and_update_cursor(
                     connection,
-                    &cursor_controller,
+                    &request.cursor_controller,
                     &buffer_row,
                     cursor as u64,
                 )?;
             }
 
-            cursor_controller.update(&connection, end_cursor + 1)?;
+            request
+                .cursor_controller
+                .update(&connection, end_cursor + 1)?;
 
-            if is_empty && total_records == 0 {
+            // Deferred records are followed by normal pull
+            if is_empty && total_records == 0 && !request.is_deferred() {
                 break;
             }
         }
         Ok(())
     }
 
+    /// Request per table record counts for initialisation from central server and store them
+    /// for initialisation progress reporting
+    pub(crate) async fn update_initialisation_summary(
+        &self,
+        connection: &StorageConnection,
+    ) -> Result<(), CentralPullErrorV6> {
+        let tables = self
+            .sync_api_v6
+            .initialisation_summary(get_v6_sync_tables(connection)?)
+            .await?
+            .tables;
+        let is_repull_requested = get_initialisation_summary(connection)?
+            .map(|summary| summary.is_repull_requested)
+            .unwrap_or(false);
+
+        set_initialisation_summary(
+            connection,
+            &InitialisationSummary {
+                tables,
+                is_repull_requested,
+            },
+        )?;
+        Ok(())
+    }
+
+    /// Compare records received during initialisation with expected counts from central server.
+    /// Returns tables with missing records, in which case v6 pull cursor is reset to pull all v6 records again
+    /// (only done once, returns empty list if records are still missing after the repull)
+    pub(crate) async fn verify_initialisation(
+        &self,
+        connection: &StorageConnection,
+    ) -> Result<Vec<String>, CentralPullErrorV6> {
+        self.update_initialisation_summary(connection).await?;
+        let Some(summary) = get_initialisation_summary(connection)? else {
+            return Ok(Vec::new());
+        };
+
+        let incomplete_tables: Vec<String> = initialisation_progress(connection, &summary)?
+            .into_iter()
+            .filter(|(_, received, expected)| received < expected)
+            .map(|(table_name, received, expected)| {
+                format!("{} ({}/{})", table_name, received, expected)
+            })
+            .collect();
+
+        if incomplete_tables.is_empty() {
+            return Ok(incomplete_tables);
+        }
+
+        if summary.is_repull_requested {
+            log::warn!(
+                "Records are still missing after initialisation repull: {}",
+                incomplete_tables.join(", ")
+            );
+            return Ok(Vec::new());
+        }
+
+        CursorController::new(KeyValueType::SyncPullCursorV6).update(connection, 0)?;
+        set_initialisation_summary(
+            connection,
+            &InitialisationSummary {
+                is_repull_requested: true,
+                ..summary
+            },
+        )?;
+
+        Ok(incomplete_tables)
+    }
+
     // Push all (relevant) records in change log to open-mSupply central server
     pub(crate) async fn push<'a>(
         &self,
         connection: &StorageConnection,
         batch_size: u32,
+        is_over_budget: bool,
         logger: &mut SyncLogger<'a>,
     ) -> Result<(), RemotePushErrorV6> {
-        let changelog_repo = ChangelogRepository::new(connection);
-        let change_log_filter = get_sync_push_changelogs_filter(connection)?;
-        let cursor_controller = CursorController::new(KeyValueType::SyncPushCursorV6);
+        let push_queue = PushQueue::new(
+            KeyValueType::SyncPushCursorV6,
+            KeyValueType::SyncPushDeferredCursorV6,
+            get_sync_push_changelogs_filter(
+                connection,
+                &ToSyncRecordTranslationType::PushToOmSupplyCentral,
+            )?,
+            is_over_budget,
+        );
+        let mut batch_size = AdaptiveBatchSize::new(batch_size);
 
         loop {
             // TODO inside transaction
-            let cursor = cursor_controller.get(connection)?;
-            let changelogs =
-                changelog_repo.changelogs(cursor, batch_size, change_log_filter.clone())?;
-            let change_logs_total = changelog_repo.count(cursor, change_log_filter.clone())?;
+            let batch = push_queue.next_batch(connection, batch_size.get())?;
+            let change_logs_total = batch.remaining;
 
             logger.progress(SyncStepProgress::PushCentralV6, change_logs_total)?;
 
@@ -131,36 +252,38 @@ impl CentralDataSynchroniserV6 {
                 break; // Nothing more to do, break out of the loop
             };
 
-            let last_pushed_cursor = changelogs.last().map(|log| log.cursor);
+            let last_pushed_cursor = batch.changelogs.last().map(|log| log.cursor);
             log::info!(
                 "Pushing {}/{} records to v6 central server",
-                changelogs.len(),
+                batch.changelogs.len(),
                 change_logs_total
             );
-            log::debug!("Records: {:#?}", changelogs);
+            log::debug!("Records: {:#?}", batch.changelogs);
 
-            let records = translate_changelogs_to_sync_records(
+            let records: Vec<SyncRecordV6> = translate_changelogs_to_sync_records(
                 connection,
-                changelogs,
+                batch.changelogs.clone(),
                 ToSyncRecordTranslationType::PushToOmSupplyCentral,
             )?
             .into_iter()
             .map(SyncRecordV6::from)
             .collect();
+            let number_of_records = records.len() as u32;
 
-            let batch = SyncBatchV6 {
+            let sync_batch = SyncBatchV6 {
                 total_records: change_logs_total,
                 end_cursor: last_pushed_cursor.unwrap_or(0) as u64,
+                checksum: Some(batch_checksum(&records)),
                 records,
             };
 
-            let response = self.sync_api_v6.push(batch).await?;
+            let start = Instant::now();
+            let response = self.sync_api_v6.push(sync_batch).await?;
+            batch_size.update(start.elapsed(), number_of_records);
             debug!("V6 Push response: {:#?}", response);
 
             // Update cursor only if record for that cursor has been pushed/processed
-            if let Some(last_pushed_cursor_id) = last_pushed_cursor {
-                cursor_controller.update(connection, last_pushed_cursor_id as u64 + 1)?;
-            };
+            push_queue.advance(connection, &batch)?;
 
             // TODO Wait for integration to start??? Or somehow control when/if we should continue to do pull and other actions...
         }
@@ -168,6 +291,46 @@ impl CentralDataSynchroniserV6 {
         Ok(())
     }
 }
+pub(crate) fn get_initialisation_summary(
+    connection: &StorageConnection,
+) -> Result<Option<InitialisationSummary>, RepositoryError> {
+    let summary = KeyValueStoreRepository::new(connection)
+        .get_string(KeyValueType::SyncInitialisationExpectedCounts)?;
+
+    Ok(summary.and_then(|summary| serde_json::from_str(&summary).ok()))
+}
+
+fn set_initialisation_summary(
+    connection: &StorageConnection,
+    summary: &InitialisationSummary,
+) -> Result<(), RepositoryError> {
+    KeyValueStoreRepository::new(connection).set_string(
+        KeyValueType::SyncInitialisationExpectedCounts,
+        serde_json::to_string(summary).ok(),
+    )
+}
+
+/// Records received (in sync buffer) and expected per table, as (table name, received, expected)
+pub(crate) fn initialisation_progress(
+    connection: &StorageConnection,
+    summary: &InitialisationSummary,
+) -> Result<Vec<(String, u64, u64)>, RepositoryError> {
+    let received = SyncBufferRepository::new(connection).count_by_table_name()?;
+
+    Ok(summary
+        .tables
+        .iter()
+        .map(|SyncTableCountV6 { table_name, count }| {
+            let received_count = received
+                .iter()
+                .find(|(received_table_name, _)| received_table_name == table_name)
+                .map(|(_, count)| *count)
+                .unwrap_or(0);
+            (table_name.clone(), received_count, *count)
+        })
+        .collect())
+}
+
 fn insert_one_and_update_cursor(
     connection: &StorageConnection,
     cursor_controller: &CursorController,
@@ -181,3 +344,150 @@ fn insert_one_and_update_cursor(
         })
         .map_err(|e| e.to_inner_error())
 }
+
+#[cfg(test)]
+mod test {
+    use httpmock::{Method::POST, MockServer};
+    use repository::{
+        mock::MockDataInserts, test_db::setup_all, KeyValueType, SyncBufferRow,
+        SyncBufferRowRepository,
+    };
+    use reqwest::Url;
+    use serde_json::json;
+    use util::{assert_matches, inline_init};
+
+    use super::*;
+    use crate::sync::{
+        api::{CommonSyncRecord, SyncAction, SyncApiV5},
+        api_v6::{
+            SyncInitialisationSummaryResponseV6, SyncInitialisationSummaryV6, SyncPullResponseV6,
+        },
+        bandwidth::SyncTransport,
+    };
+
+    fn create_api(mock_server: &MockServer) -> CentralDataSynchroniserV6 {
+        let sync_api_v5 = SyncApiV5::new_test(&mock_server.base_url(), "", "", "");
+
+        CentralDataSynchroniserV6 {
+            sync_api_v6: SyncApiV6 {
+                url: Url::parse(&mock_server.url("/central/sync/")).unwrap(),
+                sync_v5_settings: sync_api_v5.settings,
+                transport: SyncTransport::default(),
+            },
+        }
+    }
+
+    fn unit_buffer_row(record_id: &str) -> SyncBufferRow {
+        inline_init(|r: &mut SyncBufferRow| {
+            r.record_id = record_id.to_string();
+            r.table_name = "unit".to_string();
+        })
+    }
+
+    #[actix_rt::test]
+    async fn test_pull_checksum_mismatch() {
+        let (_, connection, _, _) =
+            setup_all("test_pull_checksum_mismatch", MockDataInserts::none()).await;
+
+        let mock_server = MockServer::start();
+        let batch = SyncBatchV6 {
+            end_cursor: 1,
+            total_records: 1,
+            records: vec![SyncRecordV6 {
+                cursor: 1,
+                record: CommonSyncRecord {
+                    table_name: "unit".to_string(),
+                    record_id: "unit1".to_string(),
+                    action: SyncAction::Update,
+                    record_data: json!({}),
+                },
+            }],
+            checksum: Some("altered".to_string()),
+        };
+        mock_server.mock(|when, then| {
+            when.method(POST).path("/central/sync/pull");
+            then.status(200)
+                .json_body_obj(&SyncPullResponseV6::Data(batch));
+        });
+
+        let mut logger = SyncLogger::start(&connection).unwrap();
+        let result = create_api(&mock_server)
+            .pull(&connection, 20, false, false, &mut logger)
+            .await;
+
+        // Batch is discarded, pull resumes from the same cursor
+        assert_matches!(result, Err(CentralPullErrorV6::ChecksumMismatch(0)));
+        assert_eq!(
+            CursorController::new(KeyValueType::SyncPullCursorV6)
+                .get(&connection)
+                .unwrap(),
+            0
+        );
+        assert_eq!(
+            SyncBufferRowRepository::new(&connection).get_all().unwrap(),
+            Vec::new()
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_verify_initialisation_repull() {
+        let (_, connection, _, _) =
+            setup_all("test_verify_initialisation_repull", MockDataInserts::none()).await;
+
+        let mock_server = MockServer::start();
+        mock_server.mock(|when, then| {
+            when.method(POST)
+                .path("/central/sync/initialisation_summary");
+            then.status(200)
+                .json_body_obj(&SyncInitialisationSummaryResponseV6::Data(
+                    SyncInitialisationSummaryV6 {
+                        tables: vec![SyncTableCountV6 {
+                            table_name: "unit".to_string(),
+                            count: 2,
+                        }],
+                    },
+                ));
+        });
+        let central_v6 = create_api(&mock_server);
+        let pull_cursor = CursorController::new(KeyValueType::SyncPullCursorV6);
+
+        SyncBufferRowRepository::new(&connection)
+            .upsert_one(&unit_buffer_row("unit1"))
+            .unwrap();
+        pull_cursor.update(&connection, 10).unwrap();
+
+        // Missing records, v6 records are pulled again
+        assert_eq!(
+            central_v6.verify_initialisation(&connection).await.unwrap(),
+            vec!["unit (1/2)".to_string()]
+        );
+        assert_eq!(pull_cursor.get(&connection).unwrap(), 0);
+        assert!(
+            get_initialisation_summary(&connection)
+                .unwrap()
+                .unwrap()
+                .is_repull_requested
+        );
+
+        // Records still missing after repull, initialisation is not blocked
+        pull_cursor.update(&connection, 10).unwrap();
+        assert_eq!(
+            central_v6.verify_initialisation(&connection).await.unwrap(),
+            Vec::<String>::new()
+        );
+        assert_eq!(pull_cursor.get(&connection).unwrap(), 10);
+
+        // All records received
+        SyncBufferRowRepository::new(&connection)
+            .upsert_one(&unit_buffer_row("unit2"))
+            .unwrap();
+        assert_eq!(
+            initialisation_progress(
+                &connection,
+                &get_initialisation_summary(&connection).unwrap().unwrap()
+            )
+            .unwrap(),
+            vec![("unit".to_string(), 2, 2)]
+        );
+    }
+}
@@ -3,14 +3,22 @@ pub(crate) mod test;
 
 pub mod api;
 pub mod api_v6;
+pub(crate) mod bandwidth;
 pub(crate) mod central_data_synchroniser;
 pub(crate) mod central_data_synchroniser_v6;
 mod integrate_document;
+pub mod peer_sync;
 pub(crate) mod remote_data_synchroniser;
 pub mod settings;
 pub mod site_info;
 mod sync_buffer;
+pub mod sync_buffer_errors;
+pub mod sync_bundle;
+pub mod sync_conflict;
+pub(crate) mod sync_file_transfer;
+pub mod sync_health;
 pub mod sync_on_central;
+pub mod sync_scope;
 pub(crate) mod sync_serde;
 pub mod sync_status;
 pub mod sync_user;
@@ -18,13 +26,17 @@ pub mod synchroniser;
 pub mod synchroniser_driver;
 pub(crate) mod translation_and_integration;
 pub(crate) mod translations;
+pub mod v6_sync_tables;
 
 use repository::{
     ChangelogFilter, EqualFilter, KeyValueStoreRepository, RepositoryError, StorageConnection,
     Store, StoreFilter, StoreRepository,
 };
 
+pub use bandwidth::SYNC_ACCEPT_ENCODING;
 use thiserror::Error;
+use translations::ToSyncRecordTranslationType;
+use v6_sync_tables::v6_sync_tables_push_filter;
 
 pub(crate) struct ActiveStoresOnSite {
     stores: Vec<Store>,
@@ -32,16 +44,21 @@ pub(crate) struct ActiveStoresOnSite {
 
 /// Returns changelog filter to filter out records that are not active on site
 /// It is possible to have entries for foreign records in change log (other half of transfers)
-/// these should be filtered out in sync push operation
+/// these should be filtered out in sync push operation.
+/// Remote record tables synced through the other central server are also filtered out
 pub(crate) fn get_sync_push_changelogs_filter(
     connection: &StorageConnection,
+    r#type: &ToSyncRecordTranslationType,
 ) -> Result<Option<ChangelogFilter>, GetActiveStoresOnSiteError> {
     let active_stores = ActiveStoresOnSite::get(&connection)?;
 
+    let filter = ChangelogFilter::new()
+        .store_id(EqualFilter::equal_any_or_null(active_stores.store_ids()))
+        .is_sync_update(EqualFilter::equal_or_null_bool(false));
+
     Ok(Some(
-        ChangelogFilter::new()
-            .store_id(EqualFilter::equal_any_or_null(active_stores.store_ids()))
-            .is_sync_update(EqualFilter::equal_or_null_bool(false)),
+        v6_sync_tables_push_filter(connection, filter, r#type)
+            .map_err(GetActiveStoresOnSiteError::DatabaseError)?,
     ))
 }
 
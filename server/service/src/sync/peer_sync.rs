@@ -0,0 +1,769 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::Mutex,
+};
+
+use chrono::{Duration, Utc};
+use hmac::{Hmac, Mac};
+use repository::{
+    ChangelogFilter, ChangelogRepository, ChangelogTableName, EqualFilter, KeyValueStoreRepository,
+    KeyValueType, PeerSyncSiteRow, PeerSyncSiteRowRepository, RepositoryError, StorageConnection,
+    StoreFilter, StoreRepository, SyncBufferRowRepository,
+};
+use reqwest::{Client, Url};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use thiserror::Error;
+use util::{format_error, inline_init, uuid::uuid};
+
+use crate::service_provider::ServiceProvider;
+
+use super::{
+    api::{CommonSyncRecord, ParsingSyncRecordError},
+    settings::PeerSyncSettings,
+    synchroniser::integrate_sync_buffer,
+    translations::{translate_changelogs_to_sync_records, ToSyncRecordTranslationType},
+    ActiveStoresOnSite, GetActiveStoresOnSiteError,
+};
+
+/// Only transfer records are exchanged between peers, everything else still comes from central
+const PEER_SYNC_TABLES: [ChangelogTableName; 4] = [
+    ChangelogTableName::Invoice,
+    ChangelogTableName::InvoiceLine,
+    ChangelogTableName::Requisition,
+    ChangelogTableName::RequisitionLine,
+];
+/// Legacy table names of PEER_SYNC_TABLES, as they appear in sync records
+const PEER_SYNC_LEGACY_TABLES: [&str; 4] =
+    ["transact", "trans_line", "requisition", "requisition_line"];
+/// Peers that were not advertised on the network recently are skipped
+const PEER_SEEN_WITHIN_MINUTES: i64 = 30;
+const PEER_PULL_ROUTE: &str = "peer/sync/pull";
+/// Requests older than this (or this far in the future) are rejected, nonces are kept for as long
+const PEER_REQUEST_MAX_AGE_SECONDS: i64 = 5 * 60;
+
+/// Nonces of accepted pull requests with their timestamp, a request can't be replayed
+static PEER_REQUEST_NONCES: Mutex<BTreeMap<String, i64>> = Mutex::new(BTreeMap::new());
+
+#[derive(Deserialize, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerSyncPullRequest {
+    /// Site id of requesting site
+    pub(crate) site_id: i32,
+    pub(crate) cursor: u64,
+    pub(crate) batch_size: u32,
+    /// Unix timestamp (seconds) of the request
+    pub(crate) timestamp: i64,
+    pub(crate) nonce: String,
+    /// HMAC (sha256, hex) of the request with the shared key, see `PeerSyncPullRequest::message`
+    pub(crate) signature: String,
+}
+
+#[derive(Deserialize, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PeerSyncRecord {
+    pub(crate) cursor: u64,
+    /// Store owning the record (on the serving site)
+    pub(crate) store_id: String,
+    /// Other party of the transfer (a store on the requesting site)
+    pub(crate) name_id: String,
+    pub(crate) record: CommonSyncRecord,
+}
+
+#[derive(Deserialize, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerSyncBatch {
+    /// Site id of serving site
+    pub(crate) site_id: i32,
+    pub(crate) end_cursor: u64,
+    // Number of records in changelog to pull, including records in this batch
+    pub(crate) total_records: u64,
+    pub(crate) records: Vec<PeerSyncRecord>,
+    /// HMAC (sha256, hex) of the batch and request nonce with the shared key, see `PeerSyncBatch::message`
+    pub(crate) signature: String,
+}
+
+#[derive(Deserialize, Debug, Error, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum PeerSyncParsedError {
+    #[error("Peer sync is not enabled on this site")]
+    PeerSyncNotEnabled,
+    #[error("Request signature does not match shared key")]
+    InvalidSharedKey,
+    #[error("Request timestamp is too old or in the future")]
+    RequestExpired,
+    #[error("Request was already received")]
+    RequestReplayed,
+    #[error("Site is not initialised")]
+    NotInitialised,
+    #[error("Site cannot sync with itself")]
+    SameSite,
+    #[error("Other server error: {0}")]
+    OtherServerError(String),
+}
+
+impl From<RepositoryError> for PeerSyncParsedError {
+    fn from(from: RepositoryError) -> Self {
+        PeerSyncParsedError::OtherServerError(format_error(&from))
+    }
+}
+
+#[derive(Deserialize, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PeerSyncPullResponse {
+    Data(PeerSyncBatch),
+    Error(PeerSyncParsedError),
+}
+
+fn sign(shared_key: &str, message: &str) -> String {
+    // Unwrap is safe, HMAC accepts keys of any length
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(shared_key.as_bytes()).unwrap();
+    mac.update(message.as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
+fn verify(shared_key: &str, message: &str, signature: &str) -> bool {
+    let Ok(signature) = hex::decode(signature) else {
+        return false;
+    };
+    // Unwrap is safe, HMAC accepts keys of any length
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(shared_key.as_bytes()).unwrap();
+    mac.update(message.as_bytes());
+    mac.verify_slice(&signature).is_ok()
+}
+
+impl PeerSyncPullRequest {
+    /// Signed request, the shared key itself is never sent
+    pub(crate) fn new(shared_key: &str, site_id: i32, cursor: u64, batch_size: u32) -> Self {
+        let mut request = PeerSyncPullRequest {
+            site_id,
+            cursor,
+            batch_size,
+            timestamp: Utc::now().timestamp(),
+            nonce: uuid(),
+            signature: String::new(),
+        };
+        request.signature = sign(shared_key, &request.message());
+        request
+    }
+
+    fn message(&self) -> String {
+        format!(
+            "{}:{}:{}:{}:{}",
+            self.site_id, self.cursor, self.batch_size, self.timestamp, self.nonce
+        )
+    }
+
+    /// Checks signature, that the request is recent and that it wasn't received before
+    fn verify(&self, shared_key: &str) -> Result<(), PeerSyncParsedError> {
+        use PeerSyncParsedError as Error;
+
+        if !verify(shared_key, &self.message(), &self.signature) {
+            return Err(Error::InvalidSharedKey);
+        }
+        let now = Utc::now().timestamp();
+        if (now - self.timestamp).abs() > PEER_REQUEST_MAX_AGE_SECONDS {
+            return Err(Error::RequestExpired);
+        }
+
+        let mut nonces = PEER_REQUEST_NONCES
+            .lock()
+            .map_err(|e| Error::OtherServerError(e.to_string()))?;
+        nonces.retain(|_, timestamp| (now - *timestamp).abs() <= PEER_REQUEST_MAX_AGE_SECONDS);
+        if nonces.insert(self.nonce.clone(), self.timestamp).is_some() {
+            return Err(Error::RequestReplayed);
+        }
+        Ok(())
+    }
+}
+
+impl PeerSyncBatch {
+    /// Batch is signed together with request nonce, so it can't be altered or replayed to
+    /// another request (peers use self signed certificates)
+    fn message(&self, nonce: &str) -> String {
+        format!(
+            "{}:{}:{}:{}:{}",
+            nonce,
+            self.site_id,
+            self.end_cursor,
+            self.total_records,
+            serde_json::to_string(&self.records).unwrap_or_default()
+        )
+    }
+}
+
+/// Send transfer records owned by this site to a peer site on the same network.
+///
+/// Only records of active stores on this site, where the other party is a store on the requesting site,
+/// are sent. Records received via sync (including records received from other peers) are not sent,
+/// the owning site is responsible for them
+pub fn pull(
+    service_provider: &ServiceProvider,
+    settings: Option<&PeerSyncSettings>,
+    request: PeerSyncPullRequest,
+) -> Result<PeerSyncBatch, PeerSyncParsedError> {
+    use PeerSyncParsedError as Error;
+
+    let settings = settings.ok_or(Error::PeerSyncNotEnabled)?;
+    request.verify(&settings.shared_key)?;
+    let PeerSyncPullRequest {
+        site_id: peer_site_id,
+        cursor,
+        batch_size,
+        nonce,
+        ..
+    } = request;
+
+    let ctx = service_provider.basic_context()?;
+    let site_id = KeyValueStoreRepository::new(&ctx.connection)
+        .get_i32(KeyValueType::SettingsSyncSiteId)?
+        .ok_or(Error::NotInitialised)?;
+    if !service_provider.sync_status_service.is_initialised(&ctx)? {
+        return Err(Error::NotInitialised);
+    }
+    if site_id == peer_site_id {
+        return Err(Error::SameSite);
+    }
+
+    let active_stores = ActiveStoresOnSite::get(&ctx.connection)
+        .map_err(|e| Error::OtherServerError(format_error(&e)))?;
+    let peer_name_ids = StoreRepository::new(&ctx.connection)
+        .query_by_filter(StoreFilter::new().site_id(EqualFilter::equal_to_i32(peer_site_id)))?
+        .into_iter()
+        .map(|store| store.name_row.id)
+        .collect();
+
+    let filter = ChangelogFilter::new()
+        .table_name(inline_init(|r: &mut EqualFilter<ChangelogTableName>| {
+            r.equal_any = Some(PEER_SYNC_TABLES.to_vec())
+        }))
+        .store_id(EqualFilter::equal_any(active_stores.store_ids()))
+        .name_id(EqualFilter::equal_any(peer_name_ids))
+        .is_sync_update(EqualFilter::equal_or_null_bool(false));
+
+    let changelog_repo = ChangelogRepository::new(&ctx.connection);
+    let changelogs = changelog_repo.changelogs(cursor, batch_size, Some(filter.clone()))?;
+    let total_records = changelog_repo.count(cursor, Some(filter))?;
+    let end_cursor = changelogs
+        .last()
+        .map(|log| log.cursor as u64)
+        .unwrap_or(changelog_repo.latest_cursor()?);
+
+    // Store and name are matched by cursor, since translated records don't carry them
+    let owners: HashMap<i64, (String, String)> = changelogs
+        .iter()
+        .filter_map(|log| Some((log.cursor, (log.store_id.clone()?, log.name_id.clone()?))))
+        .collect();
+
+    let records: Vec<PeerSyncRecord> = translate_changelogs_to_sync_records(
+        &ctx.connection,
+        changelogs,
+        ToSyncRecordTranslationType::PushToLegacyCentral,
+    )
+    .map_err(|e| Error::OtherServerError(format_error(&e)))?
+    .into_iter()
+    .filter_map(|push_record| {
+        let (store_id, name_id) = owners.get(&push_record.cursor)?.clone();
+        Some(PeerSyncRecord {
+            cursor: push_record.cursor as u64,
+            store_id,
+            name_id,
+            record: push_record.record,
+        })
+    })
+    .collect();
+
+    log::info!(
+        "Sending {} transfer records to peer site {}",
+        records.len(),
+        peer_site_id
+    );
+
+    let mut batch = PeerSyncBatch {
+        site_id,
+        end_cursor,
+        total_records,
+        records,
+        signature: String::new(),
+    };
+    batch.signature = sign(&settings.shared_key, &batch.message(&nonce));
+    Ok(batch)
+}
+
+/// Record or update peer discovered on the local network
+pub fn upsert_discovered_peer(
+    service_provider: &ServiceProvider,
+    hardware_id: &str,
+    url: &str,
+) -> Result<(), RepositoryError> {
+    let ctx = service_provider.basic_context()?;
+    let repo = PeerSyncSiteRowRepository::new(&ctx.connection);
+
+    let row = match repo.find_one_by_id(hardware_id)? {
+        Some(existing) => PeerSyncSiteRow {
+            url: url.to_string(),
+            last_seen_datetime: Utc::now().naive_utc(),
+            ..existing
+        },
+        None => PeerSyncSiteRow {
+            id: hardware_id.to_string(),
+            url: url.to_string(),
+            last_seen_datetime: Utc::now().naive_utc(),
+            ..Default::default()
+        },
+    };
+
+    repo.upsert_one(&row)
+}
+
+#[derive(Error, Debug)]
+pub(crate) enum PeerSyncApiError {
+    #[error("Cannot parse peer url '{0}'")]
+    CannotParseUrl(String),
+    #[error("Connection problem")]
+    ConnectionError(#[from] reqwest::Error),
+    #[error(transparent)]
+    PeerError(#[from] PeerSyncParsedError),
+    #[error("Peer response signature does not match shared key")]
+    InvalidSignature,
+}
+
+#[derive(Error, Debug)]
+pub(crate) enum PeerSyncError {
+    #[error("Database error while syncing with peer")]
+    DatabaseError(#[from] RepositoryError),
+    #[error(transparent)]
+    GetActiveStoresOnSiteError(#[from] GetActiveStoresOnSiteError),
+    #[error("Error while pulling records from peer")]
+    PullError(#[from] PeerSyncApiError),
+    #[error(transparent)]
+    ParsingSyncRecordError(#[from] ParsingSyncRecordError),
+    #[error("Error while integrating peer records")]
+    IntegrationError(#[source] anyhow::Error),
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct PeerSyncResult {
+    pub(crate) received: u64,
+    /// Same record already received (from central or peer)
+    pub(crate) duplicates: u64,
+    /// Records that don't belong to the peer or are not addressed to this site
+    pub(crate) rejected: u64,
+    /// Record ids with a pending (not yet integrated) version from another source,
+    /// the other version is kept since central is authoritative
+    pub(crate) conflicts: Vec<String>,
+}
+
+struct PeerSyncApi<'a> {
+    url: Url,
+    shared_key: &'a str,
+}
+
+impl<'a> PeerSyncApi<'a> {
+    fn new(peer_url: &str, shared_key: &'a str) -> Result<Self, PeerSyncApiError> {
+        let url = Url::parse(peer_url)
+            .and_then(|url| url.join(PEER_PULL_ROUTE))
+            .map_err(|_| PeerSyncApiError::CannotParseUrl(peer_url.to_string()))?;
+        Ok(PeerSyncApi { url, shared_key })
+    }
+
+    async fn pull(&self, request: &PeerSyncPullRequest) -> Result<PeerSyncBatch, PeerSyncApiError> {
+        // Sites generate self signed certificates, so peers are authenticated by signing requests
+        // and responses with the shared key instead
+        let response = Client::builder()
+            .danger_accept_invalid_certs(true)
+            .build()?
+            .post(self.url.clone())
+            .json(request)
+            .send()
+            .await?
+            .json::<PeerSyncPullResponse>()
+            .await?;
+
+        match response {
+            PeerSyncPullResponse::Data(batch) => {
+                if !verify(
+                    self.shared_key,
+                    &batch.message(&request.nonce),
+                    &batch.signature,
+                ) {
+                    return Err(PeerSyncApiError::InvalidSignature);
+                }
+                Ok(batch)
+            }
+            PeerSyncPullResponse::Error(error) => Err(error.into()),
+        }
+    }
+}
+
+/// Pull transfer records from peers discovered on the local network, used when central server
+/// is not reachable. Records are received as sync updates (they are never pushed to central by this site),
+/// central receives authoritative copy from the owning site when connectivity returns.
+///
+/// Must not be called concurrently with Synchroniser (both integrate sync buffer)
+pub(crate) struct PeerSynchroniser<'a> {
+    pub(crate) settings: &'a PeerSyncSettings,
+}
+
+impl<'a> PeerSynchroniser<'a> {
+    pub(crate) async fn sync(
+        &self,
+        service_provider: &ServiceProvider,
+    ) -> Result<(), PeerSyncError> {
+        let ctx = service_provider.basic_context()?;
+        if !service_provider.sync_status_service.is_initialised(&ctx)? {
+            return Ok(());
+        }
+
+        let seen_since = Utc::now().naive_utc() - Duration::minutes(PEER_SEEN_WITHIN_MINUTES);
+        let peers =
+            PeerSyncSiteRowRepository::new(&ctx.connection).find_many_seen_since(seen_since)?;
+
+        if peers.is_empty() {
+            return Ok(());
+        }
+
+        for peer in &peers {
+            let result = self.sync_peer(&ctx.connection, peer).await;
+            let repo = PeerSyncSiteRowRepository::new(&ctx.connection);
+            // Re-query, sync_peer updates cursor after each batch
+            let peer = repo
+                .find_one_by_id(&peer.id)?
+                .unwrap_or_else(|| peer.clone());
+
+            let last_error = match result {
+                Ok(result) => {
+                    log::info!("Peer sync with {} result: {:?}", peer.url, result);
+                    (!result.conflicts.is_empty()).then(|| {
+                        format!(
+                            "Conflicting records not applied: {}",
+                            result.conflicts.join(", ")
+                        )
+                    })
+                }
+                Err(error) => {
+                    let error = format_error(&error);
+                    log::warn!("Peer sync with {} failed: {}", peer.url, error);
+                    Some(error)
+                }
+            };
+
+            repo.upsert_one(&PeerSyncSiteRow {
+                last_sync_datetime: Some(Utc::now().naive_utc()),
+                last_error,
+                ..peer
+            })?;
+        }
+
+        // Records from failed peers that were already added to sync buffer are integrated as well
+        integrate_sync_buffer(&ctx.connection, true, None)
+            .map_err(PeerSyncError::IntegrationError)?;
+        ctx.processors_trigger
+            .trigger_requisition_transfer_processors();
+        ctx.processors_trigger
+            .trigger_shipment_transfer_processors();
+
+        Ok(())
+    }
+
+    async fn sync_peer(
+        &self,
+        connection: &StorageConnection,
+        peer: &PeerSyncSiteRow,
+    ) -> Result<PeerSyncResult, PeerSyncError> {
+        let site_id = KeyValueStoreRepository::new(connection)
+            .get_i32(KeyValueType::SettingsSyncSiteId)?
+            .ok_or(GetActiveStoresOnSiteError::SiteIdNotSet)?;
+        let api = PeerSyncApi::new(&peer.url, &self.settings.shared_key)?;
+        let repo = PeerSyncSiteRowRepository::new(connection);
+
+        let mut peer = peer.clone();
+        let mut result = PeerSyncResult::default();
+        loop {
+            let batch = api
+                .pull(&PeerSyncPullRequest::new(
+                    &self.settings.shared_key,
+                    site_id,
+                    peer.pull_cursor as u64,
+                    self.settings.batch_size,
+                ))
+                .await?;
+
+            // Peer hardware was re-initialised as another site, start from the beginning
+            if peer.site_id.is_some() && peer.site_id != Some(batch.site_id) {
+                peer.site_id = Some(batch.site_id);
+                peer.pull_cursor = 0;
+                repo.upsert_one(&peer)?;
+                continue;
+            }
+
+            let PeerSyncBatch {
+                site_id: peer_site_id,
+                end_cursor,
+                total_records,
+                records,
+                ..
+            } = batch;
+            let is_last_batch = total_records <= records.len() as u64;
+
+            receive_peer_records(connection, peer_site_id, records, &mut result)?;
+
+            peer.site_id = Some(peer_site_id);
+            peer.pull_cursor = end_cursor as i64 + 1;
+            repo.upsert_one(&peer)?;
+
+            if is_last_batch {
+                break;
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+/// Validate ownership of peer records and add them to sync buffer
+pub(crate) fn receive_peer_records(
+    connection: &StorageConnection,
+    peer_site_id: i32,
+    records: Vec<PeerSyncRecord>,
+    result: &mut PeerSyncResult,
+) -> Result<(), PeerSyncError> {
+    let peer_store_ids: HashSet<String> = StoreRepository::new(connection)
+        .query_by_filter(StoreFilter::new().site_id(EqualFilter::equal_to_i32(peer_site_id)))?
+        .into_iter()
+        .map(|store| store.store_row.id)
+        .collect();
+    let name_ids_on_site: HashSet<String> = ActiveStoresOnSite::get(connection)?
+        .name_ids()
+        .into_iter()
+        .collect();
+    let buffer_repo = SyncBufferRowRepository::new(connection);
+
+    for PeerSyncRecord {
+        store_id,
+        name_id,
+        record,
+        ..
+    } in records
+    {
+        let is_owned_by_peer = peer_store_ids.contains(&store_id)
+            && name_ids_on_site.contains(&name_id)
+            && PEER_SYNC_LEGACY_TABLES.contains(&record.table_name.as_str());
+        if !is_owned_by_peer {
+            log::warn!(
+                "Rejecting {} record {} from peer site {}, store {} does not belong to peer or is not transferring to this site",
+                record.table_name,
+                record.record_id,
+                peer_site_id,
+                store_id
+            );
+            result.rejected += 1;
+            continue;
+        }
+
+        let buffer_row = record.to_buffer_row(Some(peer_site_id))?;
+
+        if let Some(existing) = buffer_repo.find_one_by_record_id(&buffer_row.record_id)? {
+            if existing.data == buffer_row.data && existing.action == buffer_row.action {
+                result.duplicates += 1;
+                continue;
+            }
+            if existing.integration_datetime.is_none()
+                && existing.source_site_id != Some(peer_site_id)
+            {
+                log::warn!(
+                    "Conflict for {} record {}, pending version from {:?} is kept over peer site {}",
+                    buffer_row.table_name,
+                    buffer_row.record_id,
+                    existing.source_site_id,
+                    peer_site_id
+                );
+                result.conflicts.push(buffer_row.record_id);
+                continue;
+            }
+        }
+
+        buffer_repo.upsert_one(&buffer_row)?;
+        result.received += 1;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use repository::{
+        mock::{mock_store_a, mock_store_b, MockData, MockDataInserts},
+        test_db::setup_all_with_data,
+        KeyValueStoreRow, KeyValueType, NameRow, StoreRow, SyncBufferRow, SyncBufferRowRepository,
+    };
+    use serde_json::json;
+    use util::inline_init;
+
+    use crate::sync::api::{CommonSyncRecord, SyncAction};
+
+    use super::{
+        receive_peer_records, sign, verify, PeerSyncBatch, PeerSyncParsedError,
+        PeerSyncPullRequest, PeerSyncRecord, PeerSyncResult, PEER_REQUEST_MAX_AGE_SECONDS,
+    };
+
+    const PEER_SITE_ID: i32 = 3;
+
+    fn peer_record(
+        record_id: &str,
+        store_id: &str,
+        name_id: &str,
+        comment: &str,
+    ) -> PeerSyncRecord {
+        PeerSyncRecord {
+            cursor: 1,
+            store_id: store_id.to_string(),
+            name_id: name_id.to_string(),
+            record: CommonSyncRecord {
+                table_name: "transact".to_string(),
+                record_id: record_id.to_string(),
+                action: SyncAction::Update,
+                record_data: json!({ "ID": record_id, "comment": comment }),
+            },
+        }
+    }
+
+    #[actix_rt::test]
+    async fn test_receive_peer_records() {
+        let peer_store_name = inline_init(|r: &mut NameRow| {
+            r.id = "peer_store_name".to_string();
+        });
+        let peer_store = inline_init(|r: &mut StoreRow| {
+            r.id = "peer_store".to_string();
+            r.name_id = peer_store_name.id.clone();
+            r.site_id = PEER_SITE_ID;
+        });
+
+        let (_, connection, _, _) = setup_all_with_data(
+            "test_receive_peer_records",
+            MockDataInserts::none().names().stores(),
+            inline_init(|r: &mut MockData| {
+                r.names = vec![peer_store_name];
+                r.stores = vec![peer_store.clone()];
+                r.key_value_store_rows = vec![inline_init(|r: &mut KeyValueStoreRow| {
+                    r.id = KeyValueType::SettingsSyncSiteId;
+                    r.value_int = Some(mock_store_b().site_id);
+                })];
+                // Pending version of record from central
+                r.sync_buffer_rows = vec![inline_init(|r: &mut SyncBufferRow| {
+                    r.record_id = "conflicting".to_string();
+                    r.table_name = "transact".to_string();
+                    r.data = "{}".to_string();
+                })];
+            }),
+        )
+        .await;
+
+        let on_site_name_id = mock_store_b().name_id;
+        let mut result = PeerSyncResult::default();
+        receive_peer_records(
+            &connection,
+            PEER_SITE_ID,
+            vec![
+                peer_record("valid", &peer_store.id, &on_site_name_id, "a"),
+                // Store does not belong to peer site
+                peer_record("wrong_store", &mock_store_a().id, &on_site_name_id, "a"),
+                // Other party is not a store on this site
+                peer_record("wrong_name", &peer_store.id, &mock_store_a().name_id, "a"),
+                peer_record("conflicting", &peer_store.id, &on_site_name_id, "a"),
+            ],
+            &mut result,
+        )
+        .unwrap();
+
+        assert_eq!(
+            result,
+            PeerSyncResult {
+                received: 1,
+                duplicates: 0,
+                rejected: 2,
+                conflicts: vec!["conflicting".to_string()],
+            }
+        );
+
+        let repo = SyncBufferRowRepository::new(&connection);
+        let received = repo.find_one_by_record_id("valid").unwrap().unwrap();
+        assert_eq!(received.source_site_id, Some(PEER_SITE_ID));
+        assert_eq!(repo.find_one_by_record_id("wrong_store").unwrap(), None);
+        assert_eq!(
+            repo.find_one_by_record_id("conflicting")
+                .unwrap()
+                .unwrap()
+                .data,
+            "{}"
+        );
+
+        // Same record again is a duplicate, updated record is received
+        let mut result = PeerSyncResult::default();
+        receive_peer_records(
+            &connection,
+            PEER_SITE_ID,
+            vec![
+                peer_record("valid", &peer_store.id, &on_site_name_id, "a"),
+                peer_record("valid", &peer_store.id, &on_site_name_id, "b"),
+            ],
+            &mut result,
+        )
+        .unwrap();
+        assert_eq!(result.duplicates, 1);
+        assert_eq!(result.received, 1);
+    }
+
+    #[test]
+    fn test_peer_sync_signatures() {
+        let key = "district-key";
+
+        // Valid request is accepted once
+        let request = PeerSyncPullRequest::new(key, PEER_SITE_ID, 10, 500);
+        assert_eq!(request.verify(key), Ok(()));
+        assert_eq!(
+            request.verify(key),
+            Err(PeerSyncParsedError::RequestReplayed)
+        );
+
+        // Wrong key or altered request
+        let request = PeerSyncPullRequest::new("other-key", PEER_SITE_ID, 10, 500);
+        assert_eq!(
+            request.verify(key),
+            Err(PeerSyncParsedError::InvalidSharedKey)
+        );
+        let mut request = PeerSyncPullRequest::new(key, PEER_SITE_ID, 10, 500);
+        request.cursor = 0;
+        assert_eq!(
+            request.verify(key),
+            Err(PeerSyncParsedError::InvalidSharedKey)
+        );
+
+        // Old request, signed with the right key
+        let mut request = PeerSyncPullRequest::new(key, PEER_SITE_ID, 10, 500);
+        request.timestamp -= PEER_REQUEST_MAX_AGE_SECONDS + 1;
+        request.signature = sign(key, &request.message());
+        assert_eq!(
+            request.verify(key),
+            Err(PeerSyncParsedError::RequestExpired)
+        );
+
+        // Batch signature is tied to request nonce
+        let mut batch = PeerSyncBatch {
+            site_id: PEER_SITE_ID,
+            end_cursor: 1,
+            total_records: 1,
+            records: vec![peer_record("valid", "store", "name", "a")],
+            signature: String::new(),
+        };
+        batch.signature = sign(key, &batch.message("nonce"));
+        assert!(verify(key, &batch.message("nonce"), &batch.signature));
+        assert!(!verify(
+            key,
+            &batch.message("other_nonce"),
+            &batch.signature
+        ));
+        batch.records[0].store_id = "other_store".to_string();
+        assert!(!verify(key, &batch.message("nonce"), &batch.signature));
+    }
+}
@@ -1,15 +1,18 @@
-use std::time::{Duration, SystemTime};
+use std::time::{Duration, Instant, SystemTime};
 
 use crate::{
     cursor_controller::CursorController,
     sync::{
-        get_sync_push_changelogs_filter, sync_status::logger::SyncStepProgress,
+        bandwidth::{AdaptiveBatchSize, PushQueue},
+        get_sync_push_changelogs_filter,
+        sync_status::logger::SyncStepProgress,
         GetActiveStoresOnSiteError,
     },
 };
 
 use super::{
     api::*,
+    sync_buffer::SyncBuffer,
     sync_status::logger::{SyncLogger, SyncLoggerError},
     translations::{
         translate_changelogs_to_sync_records, PushSyncRecord, PushTranslationError,
@@ -46,6 +49,8 @@ pub(crate) enum RemotePullError {
     ParsingRecordError(#[from] ParsingSyncRecordError),
     #[error(transparent)]
     SyncLoggerError(#[from] SyncLoggerError),
+    #[error("Saved sync buffer rows do not match pulled batch, batch was not acknowledged")]
+    ChecksumMismatch,
 }
 
 #[derive(Error, Debug)]
@@ -133,8 +138,15 @@ impl RemoteDataSynchroniser {
         let step_progress = SyncStepProgress::PullRemote;
         let sync_buffer_repository = SyncBufferRowRepository::new(connection);
 
+        let mut batch_size = AdaptiveBatchSize::new(batch_size);
+
         loop {
-            let sync_batch = self.sync_api_v5.get_queued_records(batch_size).await?;
+            let start = Instant::now();
+            let sync_batch = self
+                .sync_api_v5
+                .get_queued_records(batch_size.get())
+                .await?;
+            batch_size.update(start.elapsed(), sync_batch.data.len() as u32);
 
             // queued_length is number of remote pull records awaiting acknowledgement
             // at this point it's number of records waiting to be pulled including records in this pull batch
@@ -149,6 +161,10 @@ impl RemoteDataSynchroniser {
             if number_of_pulled_records > 0 {
                 sync_buffer_repository.upsert_many(&sync_buffer_rows)?;
 
+                // Records that are not acknowledged are sent again in next pull
+                if !SyncBuffer::new(connection).is_batch_saved(&sync_buffer_rows)? {
+                    return Err(RemotePullError::ChecksumMismatch);
+                }
                 self.sync_api_v5.post_acknowledged_records(sync_ids).await?;
             } else {
                 break;
@@ -165,41 +181,46 @@ impl RemoteDataSynchroniser {
         &self,
         connection: &StorageConnection,
         batch_size: u32,
+        is_over_budget: bool,
         logger: &mut SyncLogger<'a>,
     ) -> Result<(), RemotePushError> {
-        let changelog_repo = ChangelogRepository::new(connection);
-        let change_log_filter = get_sync_push_changelogs_filter(connection)?;
-        let cursor_controller = CursorController::new(KeyValueType::RemoteSyncPushCursor);
+        let push_queue = PushQueue::new(
+            KeyValueType::RemoteSyncPushCursor,
+            KeyValueType::RemoteSyncPushDeferredCursor,
+            get_sync_push_changelogs_filter(
+                connection,
+                &ToSyncRecordTranslationType::PushToLegacyCentral,
+            )?,
+            is_over_budget,
+        );
+        let mut batch_size = AdaptiveBatchSize::new(batch_size);
 
         loop {
             // TODO inside transaction
-            let cursor = cursor_controller.get(connection)?;
-            let changelogs =
-                changelog_repo.changelogs(cursor, batch_size, change_log_filter.clone())?;
-            let change_logs_total = changelog_repo.count(cursor, change_log_filter.clone())?;
+            let batch = push_queue.next_batch(connection, batch_size.get())?;
+            let change_logs_total = batch.remaining;
 
             logger.progress(SyncStepProgress::Push, change_logs_total)?;
 
-            let last_pushed_cursor = changelogs.last().map(|log| log.cursor);
-
-            let records = translate_changelogs_to_sync_records(
+            let records: Vec<RemoteSyncRecordV5> = translate_changelogs_to_sync_records(
                 connection,
-                changelogs,
+                batch.changelogs.clone(),
                 ToSyncRecordTranslationType::PushToLegacyCentral,
             )?
             .into_iter()
             .map(RemoteSyncRecordV5::from)
             .collect();
+            let number_of_records = records.len() as u32;
 
+            let start = Instant::now();
             let response = self
                 .sync_api_v5
                 .post_queued_records(change_logs_total, records)
                 .await?;
+            batch_size.update(start.elapsed(), number_of_records);
 
             // Update cursor only if record for that cursor has been pushed/processed
-            if let Some(last_pushed_cursor_id) = last_pushed_cursor {
-                cursor_controller.update(connection, last_pushed_cursor_id as u64 + 1)?;
-            };
+            push_queue.advance(connection, &batch)?;
 
             match (response.integration_started, change_logs_total) {
                 (true, 0) => break,
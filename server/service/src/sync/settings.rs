@@ -43,3 +43,30 @@ impl SyncSettings {
         !equal
     }
 }
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub struct PeerSyncSettings {
+    /// Key shared by all sites allowed to exchange transfer records directly (e.g. all sites in
+    /// a district), key is never sent, requests and responses are signed with it (HMAC).
+    /// Peers are synced after every sync with central (SyncSettings.interval_seconds)
+    pub shared_key: String,
+    #[serde(default = "default_peer_sync_batch_size")]
+    pub batch_size: u32,
+}
+
+fn default_peer_sync_batch_size() -> u32 {
+    500
+}
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub struct SyncBandwidthSettings {
+    /// Megabytes that sync can send and receive per day (UTC), once exceeded non urgent records
+    /// (logs and temperature data) are not pushed until usage is back under budget
+    pub daily_budget_mb: u64,
+}
+
+impl SyncBandwidthSettings {
+    pub(crate) fn daily_budget_bytes(&self) -> u64 {
+        self.daily_budget_mb * 1024 * 1024
+    }
+}
@@ -1,9 +1,11 @@
+use std::collections::BTreeMap;
+
 use chrono::Utc;
 use repository::{
     DatetimeFilter, EqualFilter, RepositoryError, StorageConnection, SyncBufferAction,
     SyncBufferFilter, SyncBufferRepository, SyncBufferRow, SyncBufferRowRepository,
 };
-use util::inline_edit;
+use util::{hash::sha256, inline_edit};
 
 pub(crate) struct SyncBuffer<'a> {
     query_repository: SyncBufferRepository<'a>,
@@ -69,6 +71,32 @@ impl<'a> SyncBuffer<'a> {
 
         Ok(result)
     }
+
+    /// Legacy central server doesn't send batch checksums, instead checksum of received batch is compared
+    /// to checksum of the rows saved in sync buffer, batch is only acknowledged (or its cursor kept) when they match
+    pub(crate) fn is_batch_saved(&self, rows: &[SyncBufferRow]) -> Result<bool, RepositoryError> {
+        let record_ids = rows.iter().map(|row| row.record_id.clone()).collect();
+        let saved_rows = self.query_repository.query_by_filter(
+            SyncBufferFilter::new().record_id(EqualFilter::equal_any(record_ids)),
+        )?;
+
+        Ok(sync_buffer_checksum(rows) == sync_buffer_checksum(&saved_rows))
+    }
+}
+
+/// Checksum of received content of sync buffer rows, rows are keyed by record id (last row wins, like upsert)
+fn sync_buffer_checksum(rows: &[SyncBufferRow]) -> String {
+    let rows: BTreeMap<&str, (&str, &SyncBufferAction, &str)> = rows
+        .iter()
+        .map(|row| {
+            (
+                row.record_id.as_str(),
+                (row.table_name.as_str(), &row.action, row.data.as_str()),
+            )
+        })
+        .collect();
+
+    sha256(&serde_json::to_string(&rows).unwrap_or_default())
 }
 
 #[cfg(test)]
@@ -78,7 +106,7 @@ mod test {
         test_db::setup_all_with_data,
         SyncBufferAction, SyncBufferRow, SyncBufferRowRepository,
     };
-    use util::{inline_init, Defaults};
+    use util::{inline_edit, inline_init, Defaults};
 
     use crate::sync::translations::{all_translators, pull_integration_order};
 
@@ -204,4 +232,29 @@ mod test {
 
         assert_eq!(result, vec![]);
     }
+
+    #[actix_rt::test]
+    async fn test_is_batch_saved() {
+        let (_, connection, _, _) = setup_all_with_data(
+            "test_is_batch_saved",
+            MockDataInserts::none(),
+            inline_init(|r: &mut MockData| {
+                r.sync_buffer_rows = vec![row_1(), row_2()];
+            }),
+        )
+        .await;
+
+        let buffer = SyncBuffer::new(&connection);
+        assert!(buffer.is_batch_saved(&[row_1(), row_2()]).unwrap());
+
+        // Row is missing
+        assert!(!buffer.is_batch_saved(&[row_1(), row_3()]).unwrap());
+
+        // Saved data differs from received
+        let received_row_2 = inline_edit(&row_2(), |mut r| {
+            r.data = r#"{"ID": "2"}"#.to_string();
+            r
+        });
+        assert!(!buffer.is_batch_saved(&[row_1(), received_row_2]).unwrap());
+    }
 }
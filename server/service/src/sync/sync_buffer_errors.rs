@@ -0,0 +1,308 @@
+use repository::{
+    EqualFilter, KeyValueStoreRepository, KeyValueType, PaginationOption, RepositoryError,
+    StorageConnection, SyncBufferFilter, SyncBufferRepository, SyncBufferRow,
+    SyncBufferRowRepository,
+};
+
+use crate::{get_default_pagination, service_provider::ServiceProvider, ListError, ListResult};
+
+pub const MAX_LIMIT: u32 = 1000;
+pub const MIN_LIMIT: u32 = 1;
+
+// Alert when failed count increased in each of the last 3 syncs
+const GROWING_SYNC_RUNS: usize = 3;
+
+/// Failed (not ignored) records with the same table and error
+#[derive(Debug, PartialEq)]
+pub struct SyncBufferErrorGroup {
+    pub table_name: String,
+    pub error: String,
+    pub count: u64,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct SyncBufferErrorSummary {
+    pub groups: Vec<SyncBufferErrorGroup>,
+    pub total_failed: u64,
+    /// Failed count kept growing over the last few syncs, i.e. new records keep failing
+    pub is_failed_count_growing: bool,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SyncBufferRecords {
+    RecordIds(Vec<String>),
+    /// All failed (not ignored) records of the table
+    TableName(String),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum UpdateSyncBufferRecordsError {
+    RecordNotFound(String),
+    RecordHasNoError(String),
+    DatabaseError(RepositoryError),
+}
+
+pub fn get_sync_buffer_error_summary(
+    connection: &StorageConnection,
+) -> Result<SyncBufferErrorSummary, RepositoryError> {
+    let groups: Vec<SyncBufferErrorGroup> = SyncBufferRepository::new(connection)
+        .count_failed_by_table_name_and_error()?
+        .into_iter()
+        .map(|(table_name, error, count)| SyncBufferErrorGroup {
+            table_name,
+            error,
+            count,
+        })
+        .collect();
+
+    Ok(SyncBufferErrorSummary {
+        total_failed: groups.iter().map(|group| group.count).sum(),
+        is_failed_count_growing: is_failed_count_growing(connection)?,
+        groups,
+    })
+}
+
+/// Failed records with raw data, optionally filtered by table and exact error
+pub fn get_failed_sync_buffer_rows(
+    connection: &StorageConnection,
+    pagination: Option<PaginationOption>,
+    table_name: Option<String>,
+    error: Option<String>,
+    include_ignored: bool,
+) -> Result<ListResult<SyncBufferRow>, ListError> {
+    let pagination = get_default_pagination(pagination, MAX_LIMIT, MIN_LIMIT)?;
+    let repository = SyncBufferRepository::new(connection);
+
+    let mut filter = SyncBufferFilter::new().integration_error(match error {
+        Some(error) => EqualFilter::equal_to(&error),
+        None => EqualFilter::is_null(false),
+    });
+    if let Some(table_name) = table_name {
+        filter = filter.table_name(EqualFilter::equal_to(&table_name));
+    }
+    if !include_ignored {
+        filter = filter.is_ignored(false);
+    }
+
+    Ok(ListResult {
+        rows: repository.query_with_pagination(pagination, Some(filter.clone()))?,
+        count: repository.count(Some(filter))? as u32,
+    })
+}
+
+/// Queue failed records for integration and trigger sync (integration only happens as part of sync,
+/// to avoid integrating sync buffer concurrently). Returns number of records queued
+pub fn retry_sync_buffer_records(
+    service_provider: &ServiceProvider,
+    records: SyncBufferRecords,
+) -> Result<u64, UpdateSyncBufferRecordsError> {
+    let ctx = service_provider.basic_context()?;
+    let record_ids = ctx
+        .connection
+        .transaction_sync(|connection| -> Result<_, UpdateSyncBufferRecordsError> {
+            let record_ids = get_record_ids(connection, records)?;
+            SyncBufferRowRepository::new(connection).reset_integration(&record_ids)?;
+            Ok(record_ids)
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    if !record_ids.is_empty() {
+        service_provider.sync_trigger.trigger();
+    }
+
+    Ok(record_ids.len() as u64)
+}
+
+/// Ignored records are not shown in summary, they are integrated again if they are received again
+/// or explicitly retried. Returns number of records ignored
+pub fn ignore_sync_buffer_records(
+    connection: &StorageConnection,
+    records: SyncBufferRecords,
+) -> Result<u64, UpdateSyncBufferRecordsError> {
+    let record_ids = connection
+        .transaction_sync(|connection| -> Result<_, UpdateSyncBufferRecordsError> {
+            let record_ids = get_record_ids(connection, records)?;
+            SyncBufferRowRepository::new(connection).set_ignored(&record_ids)?;
+            Ok(record_ids)
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(record_ids.len() as u64)
+}
+
+fn get_record_ids(
+    connection: &StorageConnection,
+    records: SyncBufferRecords,
+) -> Result<Vec<String>, UpdateSyncBufferRecordsError> {
+    use UpdateSyncBufferRecordsError as Error;
+
+    let record_ids = match records {
+        SyncBufferRecords::TableName(table_name) => {
+            return Ok(SyncBufferRepository::new(connection).query_record_ids(Some(
+                SyncBufferFilter::new()
+                    .table_name(EqualFilter::equal_to(&table_name))
+                    .integration_error(EqualFilter::is_null(false))
+                    .is_ignored(false),
+            ))?)
+        }
+        SyncBufferRecords::RecordIds(record_ids) => record_ids,
+    };
+
+    let repository = SyncBufferRowRepository::new(connection);
+    for record_id in &record_ids {
+        let row = repository
+            .find_one_by_record_id(record_id)?
+            .ok_or_else(|| Error::RecordNotFound(record_id.clone()))?;
+        if row.integration_error.is_none() {
+            return Err(Error::RecordHasNoError(record_id.clone()));
+        }
+    }
+
+    Ok(record_ids)
+}
+
+/// Failed count increased after each of the last few sync integrations
+pub fn is_failed_count_growing(connection: &StorageConnection) -> Result<bool, RepositoryError> {
+    Ok(is_growing(&get_failed_count_history(connection)?))
+}
+
+/// Record number of failed records after sync integration, returns true if failed count keeps growing
+pub(crate) fn update_failed_count_history(
+    connection: &StorageConnection,
+) -> Result<bool, RepositoryError> {
+    let failed_count = SyncBufferRepository::new(connection).count(Some(
+        SyncBufferFilter::new()
+            .integration_error(EqualFilter::is_null(false))
+            .is_ignored(false),
+    ))?;
+
+    let mut history = get_failed_count_history(connection)?;
+    history.push(failed_count);
+    if history.len() > GROWING_SYNC_RUNS + 1 {
+        history.remove(0);
+    }
+
+    KeyValueStoreRepository::new(connection).set_string(
+        KeyValueType::SyncBufferFailedCountHistory,
+        serde_json::to_string(&history).ok(),
+    )?;
+
+    Ok(is_growing(&history))
+}
+
+fn get_failed_count_history(connection: &StorageConnection) -> Result<Vec<u64>, RepositoryError> {
+    let history = KeyValueStoreRepository::new(connection)
+        .get_string(KeyValueType::SyncBufferFailedCountHistory)?;
+
+    Ok(history
+        .and_then(|history| serde_json::from_str(&history).ok())
+        .unwrap_or_default())
+}
+
+fn is_growing(history: &[u64]) -> bool {
+    history.len() > GROWING_SYNC_RUNS && history.windows(2).all(|pair| pair[0] < pair[1])
+}
+
+impl From<RepositoryError> for UpdateSyncBufferRecordsError {
+    fn from(error: RepositoryError) -> Self {
+        UpdateSyncBufferRecordsError::DatabaseError(error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use repository::{
+        mock::{MockData, MockDataInserts},
+        test_db::setup_all_with_data,
+        SyncBufferRow, SyncBufferRowRepository,
+    };
+    use util::{inline_init, Defaults};
+
+    use super::*;
+
+    fn failed(record_id: &str) -> SyncBufferRow {
+        inline_init(|r: &mut SyncBufferRow| {
+            r.record_id = record_id.to_string();
+            r.table_name = "transact".to_string();
+            r.integration_datetime = Some(Defaults::naive_date_time());
+            r.integration_error = Some("error".to_string());
+        })
+    }
+
+    #[actix_rt::test]
+    async fn test_sync_buffer_errors() {
+        let (_, connection, _, _) = setup_all_with_data(
+            "test_sync_buffer_errors",
+            MockDataInserts::none(),
+            inline_init(|r: &mut MockData| {
+                r.sync_buffer_rows = vec![
+                    failed("invoice1"),
+                    failed("invoice2"),
+                    inline_init(|r: &mut SyncBufferRow| r.record_id = "integrated".to_string()),
+                ];
+            }),
+        )
+        .await;
+
+        assert_eq!(
+            get_sync_buffer_error_summary(&connection).unwrap(),
+            SyncBufferErrorSummary {
+                groups: vec![SyncBufferErrorGroup {
+                    table_name: "transact".to_string(),
+                    error: "error".to_string(),
+                    count: 2
+                }],
+                total_failed: 2,
+                is_failed_count_growing: false
+            }
+        );
+
+        // Only failed records can be retried or ignored
+        assert_eq!(
+            ignore_sync_buffer_records(
+                &connection,
+                SyncBufferRecords::RecordIds(vec!["integrated".to_string()])
+            ),
+            Err(UpdateSyncBufferRecordsError::RecordHasNoError(
+                "integrated".to_string()
+            ))
+        );
+        assert_eq!(
+            ignore_sync_buffer_records(
+                &connection,
+                SyncBufferRecords::RecordIds(vec!["invoice1".to_string()])
+            ),
+            Ok(1)
+        );
+        assert_eq!(
+            get_failed_sync_buffer_rows(&connection, None, None, None, false)
+                .unwrap()
+                .rows,
+            vec![failed("invoice2")]
+        );
+
+        // Failed count growing
+        for record_id in ["invoice3", "invoice4", "invoice5"] {
+            update_failed_count_history(&connection).unwrap();
+            SyncBufferRowRepository::new(&connection)
+                .upsert_one(&failed(record_id))
+                .unwrap();
+        }
+        assert!(update_failed_count_history(&connection).unwrap());
+        assert!(
+            get_sync_buffer_error_summary(&connection)
+                .unwrap()
+                .is_failed_count_growing
+        );
+
+        // Not growing after records of the table are ignored
+        assert_eq!(
+            ignore_sync_buffer_records(
+                &connection,
+                SyncBufferRecords::TableName("transact".to_string())
+            ),
+            Ok(4)
+        );
+        assert!(!update_failed_count_history(&connection).unwrap());
+    }
+}
@@ -0,0 +1,262 @@
+use chrono::Utc;
+use repository::{SyncBundleSiteRow, SyncBundleSiteRowRepository};
+use util::is_central_server;
+
+use crate::{
+    service_provider::ServiceProvider,
+    static_files::{StaticFileCategory, StaticFileService},
+    sync::{
+        api::{CentralSyncBatchV5, CentralSyncRecordV5, RemoteSyncRecordV5, SyncApiV5},
+        api_v6::{batch_checksum, SyncBatchV6, SyncPullRequestV6, SyncPushRequestV6, SyncRecordV6},
+        remote_data_synchroniser::RemoteDataSynchroniser,
+        sync_on_central,
+    },
+};
+
+use super::{
+    check_sequence, open_bundle, write_bundle, FromCentralBundle, SyncBundleDirection,
+    SyncBundleError, SyncBundleHeader, ToCentralBundle, MAX_BUNDLE_RECORDS,
+    SYNC_BUNDLE_FILE_EXTENSION, SYNC_BUNDLE_VERSION,
+};
+
+const PUSH_BATCH_SIZE: usize = 1024;
+const PULL_BATCH_SIZE: u32 = 500;
+const INTEGRATION_POLL_PERIOD_SECONDS: u64 = 5;
+const INTEGRATION_TIMEOUT_SECONDS: u64 = 30 * 60;
+
+/// Process bundle exported by remote site on Open mSupply central server: push and pull
+/// on behalf of the site (using site credentials from the bundle, so legacy central server still checks
+/// site hardware id) and produce return bundle, returns static file id of return bundle
+pub async fn process_sync_bundle(
+    service_provider: &ServiceProvider,
+    base_dir: &Option<String>,
+    passphrase: &str,
+    file: &[u8],
+) -> Result<String, SyncBundleError> {
+    use SyncBundleError as Error;
+
+    if !is_central_server() {
+        return Err(Error::NotACentralServer);
+    }
+
+    let bundle = open_bundle(file, passphrase)?;
+    if bundle.header.direction != SyncBundleDirection::ToCentral {
+        return Err(Error::WrongDirection);
+    }
+    let ToCentralBundle {
+        sync_v5_settings,
+        push_v5,
+        push_v6,
+        acknowledged_sync_ids,
+        central_pull_cursor,
+        pull_v6_cursor,
+        v6_sync_tables,
+    } = bundle.parse()?;
+    bundle.verify(&sync_v5_settings.password_sha256)?;
+    if sync_v5_settings.site_uuid != bundle.header.site_uuid {
+        return Err(Error::WrongSite);
+    }
+    let sequence = bundle.header.sequence;
+
+    // Check credentials and hardware id against legacy central server
+    let sync_api_v5 = SyncApiV5::new(sync_v5_settings.clone()).map_err(Error::sync_error)?;
+    let site_info = sync_api_v5
+        .get_site_info()
+        .await
+        .map_err(Error::sync_error)?;
+
+    // Connection is not held across awaits
+    {
+        let ctx = service_provider.basic_context()?;
+        let last = SyncBundleSiteRowRepository::new(&ctx.connection)
+            .find_one_by_site_id(site_info.site_id)?;
+        if let Some(last) = last {
+            check_sequence(sequence, last.last_sequence as u64)?;
+        }
+    }
+
+    log::info!(
+        "Processing sync bundle {} from site {}",
+        sequence,
+        site_info.site_id
+    );
+
+    // PUSH
+    let push_v5_end_cursor = push_v5.end_cursor;
+    let remote_push_records: Vec<RemoteSyncRecordV5> = push_v5
+        .records
+        .into_iter()
+        .map(|SyncRecordV6 { cursor, record }| RemoteSyncRecordV5 {
+            sync_id: cursor.to_string(),
+            record,
+        })
+        .collect();
+    let mut remaining = remote_push_records.len() as u64;
+    for batch in remote_push_records.chunks(PUSH_BATCH_SIZE) {
+        sync_api_v5
+            .post_queued_records(remaining, batch.to_vec())
+            .await
+            .map_err(Error::sync_error)?;
+        remaining -= batch.len() as u64;
+    }
+    // Empty push starts integration on legacy central server
+    sync_api_v5
+        .post_queued_records(0, Vec::new())
+        .await
+        .map_err(Error::sync_error)?;
+    let remote = RemoteDataSynchroniser {
+        sync_api_v5: sync_api_v5.clone(),
+    };
+    remote
+        .wait_for_sync_operation(INTEGRATION_POLL_PERIOD_SECONDS, INTEGRATION_TIMEOUT_SECONDS)
+        .await
+        .map_err(Error::sync_error)?;
+
+    if !acknowledged_sync_ids.is_empty() {
+        sync_api_v5
+            .post_acknowledged_records(acknowledged_sync_ids.clone())
+            .await
+            .map_err(Error::sync_error)?;
+    }
+
+    let push_v6_end_cursor = push_v6.end_cursor;
+    if !push_v6.records.is_empty() {
+        sync_on_central::push(
+            service_provider,
+            SyncPushRequestV6 {
+                batch: SyncBatchV6 {
+                    end_cursor: push_v6_end_cursor.unwrap_or(0),
+                    total_records: push_v6.records.len() as u64,
+                    checksum: Some(batch_checksum(&push_v6.records)),
+                    records: push_v6.records,
+                },
+                sync_v5_settings: sync_v5_settings.clone(),
+            },
+        )
+        .await
+        .map_err(Error::sync_error)?;
+    }
+
+    // PULL
+    let (central_records, central_pull_cursor) =
+        pull_central_records(&sync_api_v5, central_pull_cursor).await?;
+
+    // Remote records stay in legacy queue until acknowledged in next bundle
+    let remote_records = sync_api_v5
+        .get_queued_records(MAX_BUNDLE_RECORDS)
+        .await
+        .map_err(Error::sync_error)?
+        .data;
+
+    let mut pull_v6_records = Vec::new();
+    let mut pull_v6_cursor = pull_v6_cursor;
+    loop {
+        let SyncBatchV6 {
+            end_cursor,
+            total_records,
+            records,
+            ..
+        } = sync_on_central::pull(
+            service_provider,
+            SyncPullRequestV6 {
+                cursor: pull_v6_cursor,
+                batch_size: PULL_BATCH_SIZE,
+                sync_v5_settings: sync_v5_settings.clone(),
+                is_initialised: true,
+                v6_sync_tables: v6_sync_tables.clone(),
+                table_filter: None,
+            },
+        )
+        .await
+        .map_err(Error::sync_error)?;
+
+        let is_last_batch = total_records <= records.len() as u64;
+        pull_v6_records.extend(records);
+        pull_v6_cursor = end_cursor + 1;
+
+        if is_last_batch || pull_v6_records.len() as u32 >= MAX_BUNDLE_RECORDS {
+            break;
+        }
+    }
+
+    let return_bundle = FromCentralBundle {
+        site_id: site_info.site_id,
+        push_v5_end_cursor,
+        push_v6_end_cursor,
+        acknowledged_sync_ids,
+        central_records,
+        central_pull_cursor,
+        remote_records,
+        pull_v6_records,
+        pull_v6_cursor,
+    };
+    let header = SyncBundleHeader {
+        version: SYNC_BUNDLE_VERSION,
+        direction: SyncBundleDirection::FromCentral,
+        site_uuid: sync_v5_settings.site_uuid.clone(),
+        sequence,
+    };
+    let file = write_bundle(
+        header,
+        &return_bundle,
+        passphrase,
+        &sync_v5_settings.password_sha256,
+    )?;
+
+    let static_file = StaticFileService::new(base_dir)
+        .and_then(|service| {
+            service.store_file(
+                &format!(
+                    "{}_{}_return.{}",
+                    sync_v5_settings.username, sequence, SYNC_BUNDLE_FILE_EXTENSION
+                ),
+                StaticFileCategory::Temporary,
+                &file,
+            )
+        })
+        .map_err(|e| Error::FileError(e.to_string()))?;
+
+    let ctx = service_provider.basic_context()?;
+    SyncBundleSiteRowRepository::new(&ctx.connection).upsert_one(&SyncBundleSiteRow {
+        site_id: site_info.site_id,
+        last_sequence: sequence as i64,
+        last_imported_datetime: Utc::now().naive_utc(),
+    })?;
+
+    Ok(static_file.id)
+}
+
+/// Same as CentralDataSynchroniser.pull, but records are collected for return bundle
+async fn pull_central_records(
+    sync_api_v5: &SyncApiV5,
+    mut cursor: u64,
+) -> Result<(Vec<CentralSyncRecordV5>, u64), SyncBundleError> {
+    let mut result = Vec::new();
+
+    loop {
+        let CentralSyncBatchV5 { max_cursor, data } = sync_api_v5
+            .get_central_records(cursor, PULL_BATCH_SIZE)
+            .await
+            .map_err(SyncBundleError::sync_error)?;
+        let batch_length = data.len();
+
+        for sync_record in data {
+            cursor = sync_record.cursor;
+            result.push(sync_record);
+        }
+
+        match (batch_length, cursor < max_cursor) {
+            (0, false) => break,
+            // It's possible for batch_length in response to be zero even though we haven't reached max_cursor
+            // in this case we should increment cursor manually
+            (0, true) => cursor += 1,
+            _ => {}
+        }
+
+        if result.len() as u32 >= MAX_BUNDLE_RECORDS {
+            break;
+        }
+    }
+
+    Ok((result, cursor))
+}
@@ -0,0 +1,373 @@
+//! Sneakernet sync, for sites that never have connectivity.
+//!
+//! Remote site exports a bundle (records to push, acknowledgements and cursors), bundle is carried
+//! to Open mSupply central server which pushes/pulls on behalf of the site and produces a return bundle,
+//! return bundle is carried back and imported on the remote site. Cursors on remote site are only advanced
+//! when return bundle is imported, so a lost bundle is simply replaced by the next export.
+//!
+//! Bundle file is JSON with plain text header (direction, hardware id and sequence), payload is
+//! compressed, signed with HMAC (keyed by site password hash) and encrypted with AES-GCM (keyed by passphrase,
+//! with header as associated data, so header cannot be altered).
+pub mod central;
+pub mod remote;
+
+use std::io::{Read, Write};
+
+use aes_gcm::{
+    aead::{Aead, AeadCore, KeyInit, OsRng, Payload},
+    Aes256Gcm, Key, Nonce,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use hmac::{Hmac, Mac};
+use repository::{ChangelogTableName, RepositoryError};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use util::format_error;
+
+use super::{
+    api::{CentralSyncRecordV5, RemoteSyncRecordV5, SyncApiSettings},
+    api_v6::SyncRecordV6,
+};
+
+const SYNC_BUNDLE_VERSION: u32 = 1;
+const NONCE_LENGTH: usize = 12;
+pub const SYNC_BUNDLE_FILE_EXTENSION: &str = "omsync";
+/// Maximum number of records per record type in one bundle, remaining records are sent in next bundle
+const MAX_BUNDLE_RECORDS: u32 = 50_000;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum SyncBundleError {
+    #[error("Database error")]
+    DatabaseError(RepositoryError),
+    #[error("Sync bundle file is not valid: {0}")]
+    InvalidFile(String),
+    #[error("Sync bundle version {0} is not supported")]
+    UnsupportedVersion(u32),
+    #[error("Cannot decrypt sync bundle, passphrase is incorrect or file was modified")]
+    CannotDecrypt,
+    #[error("Sync bundle signature is not valid")]
+    InvalidSignature,
+    #[error("Sync bundle was not created for this server")]
+    WrongDirection,
+    #[error("Sync bundle is for a different site")]
+    WrongSite,
+    #[error("Sync bundle {0} was already imported")]
+    AlreadyImported(u64),
+    #[error("Sync bundle {sequence} is older than last imported bundle {last_sequence}")]
+    OutOfOrder { sequence: u64, last_sequence: u64 },
+    #[error("Sync bundle {0} is a reply to a bundle that was not exported from this site")]
+    UnknownSequence(u64),
+    #[error("Site is not initialised")]
+    NotInitialised,
+    #[error("Not a central server")]
+    NotACentralServer,
+    #[error("Problem syncing on behalf of site: {0}")]
+    SyncError(String),
+    #[error("Problem saving sync bundle file: {0}")]
+    FileError(String),
+}
+
+impl From<RepositoryError> for SyncBundleError {
+    fn from(error: RepositoryError) -> Self {
+        SyncBundleError::DatabaseError(error)
+    }
+}
+
+impl SyncBundleError {
+    pub(crate) fn sync_error<E: std::error::Error + 'static>(error: E) -> Self {
+        SyncBundleError::SyncError(format_error(&error))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum SyncBundleDirection {
+    ToCentral,
+    FromCentral,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SyncBundleHeader {
+    pub(crate) version: u32,
+    pub(crate) direction: SyncBundleDirection,
+    /// Hardware id of remote site
+    pub(crate) site_uuid: String,
+    /// Sequence of bundle exported by remote site (return bundle has sequence of bundle it replies to)
+    pub(crate) sequence: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SyncBundleFile {
+    header: SyncBundleHeader,
+    /// Base64 AES-GCM nonce
+    nonce: String,
+    /// Base64 encrypted payload
+    payload: String,
+}
+
+/// Decrypted payload, signature is included in encrypted part
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SignedPayload {
+    /// Base64 gzipped json
+    data: String,
+    /// Hex HMAC-SHA256 of data
+    signature: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BundlePushRecords {
+    /// Changelog cursor of last record in `records`
+    pub(crate) end_cursor: Option<u64>,
+    /// Records still to be pushed after this bundle
+    pub(crate) remaining: u64,
+    pub(crate) records: Vec<SyncRecordV6>,
+}
+
+/// Bundle exported by remote site
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ToCentralBundle {
+    /// Credentials are used by central to check the site (including hardware id) against legacy central server
+    pub(crate) sync_v5_settings: SyncApiSettings,
+    pub(crate) push_v5: BundlePushRecords,
+    pub(crate) push_v6: BundlePushRecords,
+    /// Sync ids of remote records received in previous return bundle
+    pub(crate) acknowledged_sync_ids: Vec<String>,
+    pub(crate) central_pull_cursor: u64,
+    pub(crate) pull_v6_cursor: u64,
+    /// Remote record tables the site switched from legacy to v6 sync
+    #[serde(default)]
+    pub(crate) v6_sync_tables: Vec<ChangelogTableName>,
+}
+
+/// Return bundle produced by central server
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct FromCentralBundle {
+    pub(crate) site_id: i32,
+    /// Push records accepted by central, remote advances push cursors to these
+    pub(crate) push_v5_end_cursor: Option<u64>,
+    pub(crate) push_v6_end_cursor: Option<u64>,
+    pub(crate) acknowledged_sync_ids: Vec<String>,
+    pub(crate) central_records: Vec<CentralSyncRecordV5>,
+    pub(crate) central_pull_cursor: u64,
+    pub(crate) remote_records: Vec<RemoteSyncRecordV5>,
+    pub(crate) pull_v6_records: Vec<SyncRecordV6>,
+    pub(crate) pull_v6_cursor: u64,
+}
+
+fn cipher(passphrase: &str) -> Aes256Gcm {
+    let key = Sha256::digest(passphrase.as_bytes());
+    Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key))
+}
+
+fn sign(data: &[u8], signing_key: &str) -> Vec<u8> {
+    // Unwrap is safe, HMAC accepts keys of any length
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(signing_key.as_bytes()).unwrap();
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+impl SyncBundleHeader {
+    fn associated_data(&self) -> Result<Vec<u8>, SyncBundleError> {
+        serde_json::to_vec(self).map_err(|e| SyncBundleError::InvalidFile(e.to_string()))
+    }
+}
+
+/// Compress, sign and encrypt payload and serialise the bundle file
+pub(crate) fn write_bundle<T: Serialize>(
+    header: SyncBundleHeader,
+    payload: &T,
+    passphrase: &str,
+    signing_key: &str,
+) -> Result<Vec<u8>, SyncBundleError> {
+    use SyncBundleError as Error;
+    let to_error = |e: std::io::Error| Error::FileError(e.to_string());
+
+    let json = serde_json::to_vec(payload).map_err(|e| Error::FileError(e.to_string()))?;
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&json).map_err(to_error)?;
+    let data = encoder.finish().map_err(to_error)?;
+
+    let signed = serde_json::to_vec(&SignedPayload {
+        signature: hex::encode(sign(&data, signing_key)),
+        data: STANDARD.encode(data),
+    })
+    .map_err(|e| Error::FileError(e.to_string()))?;
+
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let encrypted = cipher(passphrase)
+        .encrypt(
+            &nonce,
+            Payload {
+                msg: &signed,
+                aad: &header.associated_data()?,
+            },
+        )
+        .map_err(|e| Error::FileError(e.to_string()))?;
+
+    serde_json::to_vec(&SyncBundleFile {
+        header,
+        nonce: STANDARD.encode(nonce),
+        payload: STANDARD.encode(encrypted),
+    })
+    .map_err(|e| Error::FileError(e.to_string()))
+}
+
+/// Decrypted bundle, signature needs to be checked with `verify` before using the payload
+pub(crate) struct OpenedSyncBundle {
+    pub(crate) header: SyncBundleHeader,
+    data: Vec<u8>,
+    signature: Vec<u8>,
+}
+
+/// Deserialise and decrypt bundle file
+pub(crate) fn open_bundle(
+    file: &[u8],
+    passphrase: &str,
+) -> Result<OpenedSyncBundle, SyncBundleError> {
+    use SyncBundleError as Error;
+    let invalid = |e: String| Error::InvalidFile(e);
+
+    let SyncBundleFile {
+        header,
+        nonce,
+        payload,
+    } = serde_json::from_slice(file).map_err(|e| invalid(e.to_string()))?;
+
+    if header.version != SYNC_BUNDLE_VERSION {
+        return Err(Error::UnsupportedVersion(header.version));
+    }
+
+    let nonce = STANDARD.decode(nonce).map_err(|e| invalid(e.to_string()))?;
+    if nonce.len() != NONCE_LENGTH {
+        return Err(invalid("nonce has incorrect length".to_string()));
+    }
+    let payload = STANDARD
+        .decode(payload)
+        .map_err(|e| invalid(e.to_string()))?;
+
+    let decrypted = cipher(passphrase)
+        .decrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: &payload,
+                aad: &header.associated_data()?,
+            },
+        )
+        .map_err(|_| Error::CannotDecrypt)?;
+
+    let SignedPayload { data, signature } =
+        serde_json::from_slice(&decrypted).map_err(|e| invalid(e.to_string()))?;
+
+    Ok(OpenedSyncBundle {
+        header,
+        data: STANDARD.decode(data).map_err(|e| invalid(e.to_string()))?,
+        signature: hex::decode(signature).map_err(|_| Error::InvalidSignature)?,
+    })
+}
+
+impl OpenedSyncBundle {
+    pub(crate) fn verify(&self, signing_key: &str) -> Result<(), SyncBundleError> {
+        // Unwrap is safe, HMAC accepts keys of any length
+        let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(signing_key.as_bytes()).unwrap();
+        mac.update(&self.data);
+        mac.verify_slice(&self.signature)
+            .map_err(|_| SyncBundleError::InvalidSignature)
+    }
+
+    /// Payload can be parsed before verification, since central server gets signing key from payload
+    pub(crate) fn parse<T: DeserializeOwned>(&self) -> Result<T, SyncBundleError> {
+        let mut json = Vec::new();
+        GzDecoder::new(self.data.as_slice())
+            .read_to_end(&mut json)
+            .map_err(|e| SyncBundleError::InvalidFile(e.to_string()))?;
+
+        serde_json::from_slice(&json).map_err(|e| SyncBundleError::InvalidFile(e.to_string()))
+    }
+}
+
+/// Check bundle sequence against last imported sequence, to reject duplicate and out of order bundles
+pub(crate) fn check_sequence(sequence: u64, last_sequence: u64) -> Result<(), SyncBundleError> {
+    match sequence {
+        sequence if sequence == last_sequence => Err(SyncBundleError::AlreadyImported(sequence)),
+        sequence if sequence < last_sequence => Err(SyncBundleError::OutOfOrder {
+            sequence,
+            last_sequence,
+        }),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct TestPayload {
+        value: String,
+    }
+
+    fn header(sequence: u64) -> SyncBundleHeader {
+        SyncBundleHeader {
+            version: SYNC_BUNDLE_VERSION,
+            direction: SyncBundleDirection::ToCentral,
+            site_uuid: "hardware_id".to_string(),
+            sequence,
+        }
+    }
+
+    #[test]
+    fn test_sync_bundle_file() {
+        let payload = TestPayload {
+            value: "records".to_string(),
+        };
+        let file = write_bundle(header(2), &payload, "passphrase", "password_sha256").unwrap();
+
+        // Round trip
+        let opened = open_bundle(&file, "passphrase").unwrap();
+        assert_eq!(opened.header, header(2));
+        assert_eq!(opened.verify("password_sha256"), Ok(()));
+        assert_eq!(opened.parse::<TestPayload>().unwrap(), payload);
+
+        // Wrong passphrase
+        assert_eq!(
+            open_bundle(&file, "other").err(),
+            Some(SyncBundleError::CannotDecrypt)
+        );
+
+        // Wrong signing key
+        assert_eq!(
+            opened.verify("other"),
+            Err(SyncBundleError::InvalidSignature)
+        );
+
+        // Altered header (e.g. to replay bundle with another sequence)
+        let mut altered: serde_json::Value = serde_json::from_slice(&file).unwrap();
+        altered["header"]["sequence"] = serde_json::json!(3);
+        assert_eq!(
+            open_bundle(&serde_json::to_vec(&altered).unwrap(), "passphrase").err(),
+            Some(SyncBundleError::CannotDecrypt)
+        );
+
+        // Sequence
+        assert_eq!(check_sequence(3, 2), Ok(()));
+        assert_eq!(
+            check_sequence(2, 2),
+            Err(SyncBundleError::AlreadyImported(2))
+        );
+        assert_eq!(
+            check_sequence(1, 2),
+            Err(SyncBundleError::OutOfOrder {
+                sequence: 1,
+                last_sequence: 2
+            })
+        );
+    }
+}
@@ -0,0 +1,302 @@
+use std::collections::HashSet;
+
+use chrono::Utc;
+use repository::{
+    ChangelogRepository, KeyValueStoreRepository, KeyValueType, StorageConnection,
+    SyncBufferRowRepository,
+};
+
+use crate::{
+    cursor_controller::CursorController,
+    service_provider::ServiceProvider,
+    static_files::{StaticFileCategory, StaticFileService},
+    sync::{
+        api::SyncApiV5,
+        api_v6::SyncRecordV6,
+        get_sync_push_changelogs_filter,
+        settings::SYNC_VERSION,
+        synchroniser::integrate_sync_buffer,
+        translations::{translate_changelogs_to_sync_records, ToSyncRecordTranslationType},
+        v6_sync_tables::get_v6_sync_tables,
+    },
+};
+
+use super::{
+    check_sequence, open_bundle, write_bundle, BundlePushRecords, FromCentralBundle,
+    SyncBundleDirection, SyncBundleError, SyncBundleHeader, ToCentralBundle, MAX_BUNDLE_RECORDS,
+    SYNC_BUNDLE_FILE_EXTENSION, SYNC_BUNDLE_VERSION,
+};
+
+#[derive(Debug, PartialEq)]
+pub struct ImportSyncBundleResult {
+    /// Sequence of exported bundle this return bundle replied to
+    pub sequence: u64,
+    pub records_received: u64,
+    /// Records that could not be sent in one bundle, another export/import is needed
+    pub has_more_records: bool,
+}
+
+/// Export records to push, acknowledgements and cursors to a bundle file to be carried to central server.
+/// Cursors are not advanced until return bundle is imported, returns static file id
+pub fn export_sync_bundle(
+    service_provider: &ServiceProvider,
+    base_dir: &Option<String>,
+    passphrase: &str,
+) -> Result<String, SyncBundleError> {
+    let ctx = service_provider.basic_context()?;
+    if !service_provider.sync_status_service.is_initialised(&ctx)? {
+        return Err(SyncBundleError::NotInitialised);
+    }
+    let sync_settings = service_provider
+        .settings
+        .sync_settings(&ctx)?
+        .ok_or(SyncBundleError::NotInitialised)?;
+    let sync_v5_settings = SyncApiV5::new_settings(&sync_settings, service_provider, SYNC_VERSION)
+        .map_err(SyncBundleError::sync_error)?;
+
+    let connection = &ctx.connection;
+    let sequence_controller = CursorController::new(KeyValueType::SyncBundleExportSequence);
+    let sequence = sequence_controller.get(connection)? + 1;
+
+    let bundle = ToCentralBundle {
+        push_v5: push_records(
+            connection,
+            KeyValueType::RemoteSyncPushCursor,
+            ToSyncRecordTranslationType::PushToLegacyCentral,
+        )?,
+        push_v6: push_records(
+            connection,
+            KeyValueType::SyncPushCursorV6,
+            ToSyncRecordTranslationType::PushToOmSupplyCentral,
+        )?,
+        acknowledged_sync_ids: pending_acknowledgements(connection)?,
+        central_pull_cursor: CursorController::new(KeyValueType::CentralSyncPullCursor)
+            .get(connection)?,
+        pull_v6_cursor: CursorController::new(KeyValueType::SyncPullCursorV6).get(connection)?,
+        v6_sync_tables: get_v6_sync_tables(connection)?,
+        sync_v5_settings: sync_v5_settings.clone(),
+    };
+
+    let header = SyncBundleHeader {
+        version: SYNC_BUNDLE_VERSION,
+        direction: SyncBundleDirection::ToCentral,
+        site_uuid: sync_v5_settings.site_uuid,
+        sequence,
+    };
+    let file = write_bundle(header, &bundle, passphrase, &sync_settings.password_sha256)?;
+
+    let static_file = StaticFileService::new(base_dir)
+        .and_then(|service| {
+            service.store_file(
+                &format!(
+                    "{}_{}_{}.{}",
+                    sync_settings.username,
+                    sequence,
+                    Utc::now().format("%Y%m%d_%H%M%S"),
+                    SYNC_BUNDLE_FILE_EXTENSION
+                ),
+                StaticFileCategory::Temporary,
+                &file,
+            )
+        })
+        .map_err(|e| SyncBundleError::FileError(e.to_string()))?;
+
+    sequence_controller.update(connection, sequence)?;
+
+    log::info!(
+        "Exported sync bundle {} with {} legacy and {} v6 records",
+        sequence,
+        bundle.push_v5.records.len(),
+        bundle.push_v6.records.len()
+    );
+
+    Ok(static_file.id)
+}
+
+/// Import return bundle produced by central server, records are integrated and cursors are advanced
+pub fn import_sync_bundle(
+    service_provider: &ServiceProvider,
+    passphrase: &str,
+    file: &[u8],
+) -> Result<ImportSyncBundleResult, SyncBundleError> {
+    use SyncBundleError as Error;
+
+    let ctx = service_provider.basic_context()?;
+    if !service_provider.sync_status_service.is_initialised(&ctx)? {
+        return Err(Error::NotInitialised);
+    }
+    let sync_settings = service_provider
+        .settings
+        .sync_settings(&ctx)?
+        .ok_or(Error::NotInitialised)?;
+    let hardware_id = service_provider
+        .app_data_service
+        .get_hardware_id()
+        .map_err(|e| Error::SyncError(e.to_string()))?;
+
+    let bundle = open_bundle(file, passphrase)?;
+    if bundle.header.direction != SyncBundleDirection::FromCentral {
+        return Err(Error::WrongDirection);
+    }
+    if bundle.header.site_uuid != hardware_id {
+        return Err(Error::WrongSite);
+    }
+    bundle.verify(&sync_settings.password_sha256)?;
+    let payload: FromCentralBundle = bundle.parse()?;
+    let sequence = bundle.header.sequence;
+
+    let connection = &ctx.connection;
+    let site_id = KeyValueStoreRepository::new(connection)
+        .get_i32(KeyValueType::SettingsSyncSiteId)?
+        .ok_or(Error::NotInitialised)?;
+    if payload.site_id != site_id {
+        return Err(Error::WrongSite);
+    }
+
+    let import_sequence_controller = CursorController::new(KeyValueType::SyncBundleImportSequence);
+    check_sequence(sequence, import_sequence_controller.get(connection)?)?;
+    if sequence > CursorController::new(KeyValueType::SyncBundleExportSequence).get(connection)? {
+        return Err(Error::UnknownSequence(sequence));
+    }
+
+    let records_received = (payload.central_records.len()
+        + payload.remote_records.len()
+        + payload.pull_v6_records.len()) as u64;
+    let has_more_records = payload.central_records.len() as u32 >= MAX_BUNDLE_RECORDS
+        || payload.remote_records.len() as u32 >= MAX_BUNDLE_RECORDS
+        || payload.pull_v6_records.len() as u32 >= MAX_BUNDLE_RECORDS;
+
+    connection
+        .transaction_sync(|connection| -> Result<(), SyncBundleError> {
+            receive_return_bundle(connection, payload)?;
+            import_sequence_controller.update(connection, sequence)?;
+            Ok(())
+        })
+        .map_err(|e| e.to_inner_error())?;
+
+    integrate_sync_buffer(connection, true, None)
+        .map_err(|e| Error::SyncError(format!("{:?}", e)))?;
+    ctx.processors_trigger
+        .trigger_requisition_transfer_processors();
+    ctx.processors_trigger
+        .trigger_shipment_transfer_processors();
+
+    log::info!(
+        "Imported return sync bundle {} with {} records",
+        sequence,
+        records_received
+    );
+
+    Ok(ImportSyncBundleResult {
+        sequence,
+        records_received,
+        has_more_records,
+    })
+}
+
+fn receive_return_bundle(
+    connection: &StorageConnection,
+    FromCentralBundle {
+        site_id: _,
+        push_v5_end_cursor,
+        push_v6_end_cursor,
+        acknowledged_sync_ids,
+        central_records,
+        central_pull_cursor,
+        remote_records,
+        pull_v6_records,
+        pull_v6_cursor,
+    }: FromCentralBundle,
+) -> Result<(), SyncBundleError> {
+    let buffer_repo = SyncBufferRowRepository::new(connection);
+
+    for record in central_records {
+        buffer_repo.upsert_one(
+            &record
+                .record
+                .to_buffer_row(None)
+                .map_err(SyncBundleError::sync_error)?,
+        )?;
+    }
+    CursorController::new(KeyValueType::CentralSyncPullCursor)
+        .update(connection, central_pull_cursor)?;
+
+    // Remote records are acknowledged in next exported bundle
+    let acknowledged: HashSet<String> = acknowledged_sync_ids.into_iter().collect();
+    let mut pending: Vec<String> = pending_acknowledgements(connection)?
+        .into_iter()
+        .filter(|sync_id| !acknowledged.contains(sync_id))
+        .collect();
+    for record in remote_records {
+        pending.push(record.sync_id.clone());
+        buffer_repo.upsert_one(
+            &record
+                .record
+                .to_buffer_row(None)
+                .map_err(SyncBundleError::sync_error)?,
+        )?;
+    }
+    KeyValueStoreRepository::new(connection).set_string(
+        KeyValueType::SyncBundlePendingAcknowledgements,
+        Some(serde_json::to_string(&pending).unwrap_or_default()),
+    )?;
+
+    for SyncRecordV6 { record, .. } in pull_v6_records {
+        buffer_repo.upsert_one(
+            &record
+                .to_buffer_row(None)
+                .map_err(SyncBundleError::sync_error)?,
+        )?;
+    }
+    CursorController::new(KeyValueType::SyncPullCursorV6).update(connection, pull_v6_cursor)?;
+
+    // Only advance push cursors for records central has accepted
+    if let Some(end_cursor) = push_v5_end_cursor {
+        CursorController::new(KeyValueType::RemoteSyncPushCursor)
+            .update(connection, end_cursor + 1)?;
+    }
+    if let Some(end_cursor) = push_v6_end_cursor {
+        CursorController::new(KeyValueType::SyncPushCursorV6).update(connection, end_cursor + 1)?;
+    }
+
+    Ok(())
+}
+
+fn push_records(
+    connection: &StorageConnection,
+    cursor_type: KeyValueType,
+    translation_type: ToSyncRecordTranslationType,
+) -> Result<BundlePushRecords, SyncBundleError> {
+    let filter = get_sync_push_changelogs_filter(connection, &translation_type)
+        .map_err(SyncBundleError::sync_error)?;
+    let changelog_repo = ChangelogRepository::new(connection);
+    let cursor = CursorController::new(cursor_type).get(connection)?;
+
+    let changelogs = changelog_repo.changelogs(cursor, MAX_BUNDLE_RECORDS, filter.clone())?;
+    let total = changelog_repo.count(cursor, filter)?;
+    let end_cursor = changelogs.last().map(|log| log.cursor as u64);
+    let changelog_count = changelogs.len() as u64;
+
+    let records = translate_changelogs_to_sync_records(connection, changelogs, translation_type)
+        .map_err(SyncBundleError::sync_error)?
+        .into_iter()
+        .map(SyncRecordV6::from)
+        .collect();
+
+    Ok(BundlePushRecords {
+        end_cursor,
+        remaining: total - changelog_count,
+        records,
+    })
+}
+
+fn pending_acknowledgements(
+    connection: &StorageConnection,
+) -> Result<Vec<String>, SyncBundleError> {
+    let pending = KeyValueStoreRepository::new(connection)
+        .get_string(KeyValueType::SyncBundlePendingAcknowledgements)?;
+
+    Ok(pending
+        .and_then(|pending| serde_json::from_str(&pending).ok())
+        .unwrap_or_default())
+}
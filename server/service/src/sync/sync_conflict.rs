@@ -0,0 +1,580 @@
+use chrono::Utc;
+use repository::{
+    ChangelogAction, ChangelogFilter, ChangelogRepository, ChangelogRow, ClinicianFilter,
+    ClinicianRepository, EqualFilter, NameFilter, NameRepository, PaginationOption,
+    RepositoryError, StorageConnection, SyncBufferAction, SyncBufferRow, SyncConflictResolution,
+    SyncConflictRow, SyncConflictRowRepository, SyncRecordVersionRow,
+    SyncRecordVersionRowRepository,
+};
+use serde_json::{Map, Value};
+use util::uuid::uuid;
+
+use crate::{get_default_pagination, service_provider::ServiceContext, ListError, ListResult};
+
+use super::{
+    translation_and_integration::integrate,
+    translations::{
+        all_translators, PullTranslateResult, PushTranslateResult, SyncTranslation, SyncTranslators,
+    },
+};
+
+pub const MAX_LIMIT: u32 = 1000;
+pub const MIN_LIMIT: u32 = 1;
+
+/// Sync tables of patient and shared-remote records, which are editable on more than one site.
+/// Patient documents are not included, they keep version history (parent ids) already
+const CONFLICT_TRACKED_TABLES: &[&str] = &["name", "barcode", "clinician"];
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum ConflictCheck {
+    /// No concurrent edits, integrate as received
+    Integrate,
+    /// Different fields were edited on this and other site, integrate merged sync record data
+    /// (merged record needs to be pushed)
+    IntegrateMerged(String),
+    /// Same fields were edited on this and other site, local version is kept until resolved
+    Conflict(SyncConflictRow),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ResolveSyncConflictError {
+    ConflictDoesNotExist,
+    /// Patient or clinician is not visible in the store
+    ConflictRecordNotInStore,
+    ConflictAlreadyResolved,
+    /// Chosen version could not be translated, e.g. referenced record no longer exists
+    IntegrationError(String),
+    DatabaseError(RepositoryError),
+}
+
+/// Compare received sync record with local version, both against the last received version
+/// (per field three way comparison)
+pub(crate) fn check_sync_conflict(
+    connection: &StorageConnection,
+    translators: &SyncTranslators,
+    sync_record: &SyncBufferRow,
+) -> Result<ConflictCheck, RepositoryError> {
+    if !is_tracked(sync_record) {
+        return Ok(ConflictCheck::Integrate);
+    }
+
+    let Some(version) = SyncRecordVersionRowRepository::new(connection)
+        .find_one(&sync_record.table_name, &sync_record.record_id)?
+    else {
+        return Ok(ConflictCheck::Integrate);
+    };
+
+    // Local record is only translated when it was edited on this site since the version was
+    // recorded, otherwise there is nothing to compare
+    if !has_local_change(connection, translators, sync_record)? {
+        return Ok(ConflictCheck::Integrate);
+    }
+
+    // Local version is only kept when it differed from the received version, otherwise local
+    // record was the same as received version
+    let (Some(local), Some(local_base), Some(remote_base), Some(remote)) = (
+        local_sync_record_data(connection, translators, sync_record),
+        to_object(version.local_data.as_ref().unwrap_or(&version.remote_data)),
+        to_object(&version.remote_data),
+        to_object(&sync_record.data),
+    ) else {
+        return Ok(ConflictCheck::Integrate);
+    };
+
+    let mut merged = remote.clone();
+    let mut conflicting_fields = Vec::new();
+    for (field, local_value) in local.iter() {
+        if is_same_value(local_base.get(field), local_value) {
+            continue;
+        }
+        // Field was changed on this site
+        let remote_value = remote.get(field);
+        if remote_value != remote_base.get(field) && remote_value != Some(local_value) {
+            conflicting_fields.push(field.clone());
+        } else {
+            merged.insert(field.clone(), local_value.clone());
+        }
+    }
+
+    if !conflicting_fields.is_empty() {
+        let existing_conflict = SyncConflictRowRepository::new(connection)
+            .find_unresolved(&sync_record.table_name, &sync_record.record_id)?;
+
+        let (id, created_datetime) = match existing_conflict {
+            // Conflict is updated with the latest received version
+            Some(conflict) => (conflict.id, conflict.created_datetime),
+            None => (uuid(), Utc::now().naive_utc()),
+        };
+
+        return Ok(ConflictCheck::Conflict(SyncConflictRow {
+            id,
+            table_name: sync_record.table_name.clone(),
+            record_id: sync_record.record_id.clone(),
+            local_data: Value::Object(local).to_string(),
+            remote_data: sync_record.data.clone(),
+            conflicting_fields: serde_json::to_string(&conflicting_fields).unwrap_or_default(),
+            source_site_id: sync_record.source_site_id,
+            created_datetime,
+            resolution: None,
+            resolved_datetime: None,
+            resolved_by_user_id: None,
+        }));
+    }
+
+    if merged != remote {
+        return Ok(ConflictCheck::IntegrateMerged(
+            Value::Object(merged).to_string(),
+        ));
+    }
+
+    Ok(ConflictCheck::Integrate)
+}
+
+/// Remember received version, local version is kept, i.e. local changes are still detected
+pub(crate) fn record_sync_conflict(
+    connection: &StorageConnection,
+    conflict: &SyncConflictRow,
+) -> Result<(), RepositoryError> {
+    SyncConflictRowRepository::new(connection).upsert_one(conflict)?;
+
+    let repository = SyncRecordVersionRowRepository::new(connection);
+    if let Some(version) = repository.find_one(&conflict.table_name, &conflict.record_id)? {
+        repository.upsert_one(&SyncRecordVersionRow {
+            remote_data: conflict.remote_data.clone(),
+            updated_datetime: Utc::now().naive_utc(),
+            ..version
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Remember received version after integration, if `is_local_change` (integrated record differs
+/// from received version) the latest change of the record is marked to be pushed and local
+/// version is remembered as well
+pub(crate) fn record_integrated_version(
+    connection: &StorageConnection,
+    translators: &SyncTranslators,
+    sync_record: &SyncBufferRow,
+    is_local_change: bool,
+) -> Result<(), RepositoryError> {
+    if !is_tracked(sync_record) {
+        return Ok(());
+    }
+
+    let mut local_data = None;
+    if is_local_change {
+        if let Some(change_log_type) =
+            find_translator(translators, sync_record).and_then(|t| t.change_log_type())
+        {
+            ChangelogRepository::new(connection).set_latest_is_sync_update(
+                change_log_type,
+                &sync_record.record_id,
+                false,
+            )?;
+        }
+        local_data = local_sync_record_data(connection, translators, sync_record)
+            .map(|local| Value::Object(local).to_string());
+    }
+
+    SyncRecordVersionRowRepository::new(connection).upsert_one(&SyncRecordVersionRow {
+        table_name: sync_record.table_name.clone(),
+        record_id: sync_record.record_id.clone(),
+        remote_data: sync_record.data.clone(),
+        local_data,
+        updated_datetime: Utc::now().naive_utc(),
+    })
+}
+
+pub fn get_sync_conflicts(
+    connection: &StorageConnection,
+    pagination: Option<PaginationOption>,
+    table_name: Option<String>,
+    is_resolved: Option<bool>,
+) -> Result<ListResult<SyncConflictRow>, ListError> {
+    let pagination = get_default_pagination(pagination, MAX_LIMIT, MIN_LIMIT)?;
+    let (rows, count) = SyncConflictRowRepository::new(connection).query(
+        table_name,
+        is_resolved,
+        pagination.offset,
+        pagination.limit,
+    )?;
+
+    Ok(ListResult {
+        rows,
+        count: count as u32,
+    })
+}
+
+/// Integrate chosen version as a local change, which is pushed and syncs to other sites like
+/// any other edit of the record
+pub fn resolve_sync_conflict(
+    ctx: &ServiceContext,
+    id: &str,
+    resolution: SyncConflictResolution,
+) -> Result<SyncConflictRow, ResolveSyncConflictError> {
+    use ResolveSyncConflictError as Error;
+
+    ctx.connection
+        .transaction_sync(|connection| -> Result<_, Error> {
+            let repository = SyncConflictRowRepository::new(connection);
+            let conflict = repository
+                .find_one_by_id(id)?
+                .ok_or(Error::ConflictDoesNotExist)?;
+            if !is_record_in_store(connection, &conflict, &ctx.store_id)? {
+                return Err(Error::ConflictRecordNotInStore);
+            }
+            if conflict.resolution.is_some() {
+                return Err(Error::ConflictAlreadyResolved);
+            }
+
+            let translators = all_translators();
+            let remote_sync_record = SyncBufferRow {
+                record_id: conflict.record_id.clone(),
+                table_name: conflict.table_name.clone(),
+                action: SyncBufferAction::Upsert,
+                data: conflict.remote_data.clone(),
+                ..Default::default()
+            };
+            let data = match resolution {
+                SyncConflictResolution::Remote => conflict.remote_data.clone(),
+                // Record may have been edited since conflict was detected
+                SyncConflictResolution::Local => {
+                    local_sync_record_data(connection, &translators, &remote_sync_record)
+                        .map(|local| Value::Object(local).to_string())
+                        .unwrap_or_else(|| conflict.local_data.clone())
+                }
+            };
+
+            let chosen_sync_record = SyncBufferRow {
+                data,
+                ..remote_sync_record.clone()
+            };
+            let operations = match find_translator(&translators, &chosen_sync_record)
+                .map(|t| t.try_translate_from_upsert_sync_record(connection, &chosen_sync_record))
+            {
+                Some(Ok(PullTranslateResult::IntegrationOperations(operations))) => operations,
+                Some(Err(error)) => return Err(Error::IntegrationError(format!("{:?}", error))),
+                _ => {
+                    return Err(Error::IntegrationError(
+                        "Translator for record not found".to_string(),
+                    ))
+                }
+            };
+            integrate(connection, &operations)?;
+            // Received version stays as the last known version of other sites
+            record_integrated_version(connection, &translators, &remote_sync_record, true)?;
+
+            let resolved = SyncConflictRow {
+                resolution: Some(resolution),
+                resolved_datetime: Some(Utc::now().naive_utc()),
+                resolved_by_user_id: Some(ctx.user_id.clone()),
+                ..conflict
+            };
+            repository.upsert_one(&resolved)?;
+
+            Ok(resolved)
+        })
+        .map_err(|error| error.to_inner_error())
+}
+
+fn is_tracked(sync_record: &SyncBufferRow) -> bool {
+    if sync_record.action != SyncBufferAction::Upsert
+        || !CONFLICT_TRACKED_TABLES.contains(&sync_record.table_name.as_str())
+    {
+        return false;
+    }
+
+    // Other names are central data
+    if sync_record.table_name == "name" {
+        return serde_json::from_str::<Value>(&sync_record.data)
+            .ok()
+            .and_then(|data| data.get("type").cloned())
+            == Some(Value::String("patient".to_string()));
+    }
+
+    true
+}
+
+/// Patients and clinicians are resolved in a store they are visible in, other records (barcodes)
+/// are shared by all stores
+fn is_record_in_store(
+    connection: &StorageConnection,
+    conflict: &SyncConflictRow,
+    store_id: &str,
+) -> Result<bool, RepositoryError> {
+    let filter = EqualFilter::equal_to(&conflict.record_id);
+    let is_in_store = match conflict.table_name.as_str() {
+        "name" => NameRepository::new(connection)
+            .query_one(store_id, NameFilter::new().id(filter))?
+            .map_or(false, |name| {
+                name.is_visible() || name.name_row.supplying_store_id.as_deref() == Some(store_id)
+            }),
+        "clinician" => ClinicianRepository::new(connection)
+            .query_one(store_id, ClinicianFilter::new().id(filter))?
+            .is_some(),
+        _ => true,
+    };
+
+    Ok(is_in_store)
+}
+
+/// False when the latest change of the record was integrated from sync
+fn has_local_change(
+    connection: &StorageConnection,
+    translators: &SyncTranslators,
+    sync_record: &SyncBufferRow,
+) -> Result<bool, RepositoryError> {
+    let Some(change_log_type) =
+        find_translator(translators, sync_record).and_then(|t| t.change_log_type())
+    else {
+        return Ok(true);
+    };
+
+    let latest_changelog = ChangelogRepository::new(connection).changelogs(
+        0,
+        1,
+        Some(
+            ChangelogFilter::new()
+                .table_name(change_log_type.equal_to())
+                .record_id(EqualFilter::equal_to(&sync_record.record_id)),
+        ),
+    )?;
+
+    Ok(latest_changelog
+        .first()
+        .map_or(true, |changelog| !changelog.is_sync_update))
+}
+
+fn find_translator<'a>(
+    translators: &'a SyncTranslators,
+    sync_record: &SyncBufferRow,
+) -> Option<&'a dyn SyncTranslation> {
+    translators
+        .iter()
+        .find(|translator| translator.table_name() == sync_record.table_name)
+        .map(|translator| translator.as_ref())
+}
+
+/// Local record translated the same way it would be pushed, None if record doesn't exist locally
+fn local_sync_record_data(
+    connection: &StorageConnection,
+    translators: &SyncTranslators,
+    sync_record: &SyncBufferRow,
+) -> Option<Map<String, Value>> {
+    let translator = find_translator(translators, sync_record)?;
+
+    let changelog = ChangelogRow {
+        cursor: 0,
+        table_name: translator.change_log_type()?,
+        record_id: sync_record.record_id.clone(),
+        row_action: ChangelogAction::Upsert,
+        name_id: None,
+        store_id: None,
+        is_sync_update: false,
+        source_site_id: None,
+    };
+
+    // Translation fails when the record doesn't exist locally
+    let Ok(PushTranslateResult::PushRecord(records)) =
+        translator.try_translate_to_upsert_sync_record(connection, &changelog)
+    else {
+        return None;
+    };
+
+    records
+        .into_iter()
+        .find(|push_record| push_record.record.record_id == sync_record.record_id)
+        .and_then(|push_record| match push_record.record.record_data {
+            Value::Object(data) => Some(data),
+            _ => None,
+        })
+}
+
+/// Legacy sync records use empty string for missing values, while translated local record can
+/// have null
+fn is_same_value(base: Option<&Value>, value: &Value) -> bool {
+    let is_empty = |value: &Value| match value {
+        Value::Null => true,
+        Value::String(value) => value.is_empty(),
+        _ => false,
+    };
+
+    match base {
+        Some(base) => base == value || (is_empty(base) && is_empty(value)),
+        None => is_empty(value),
+    }
+}
+
+fn to_object(data: &str) -> Option<Map<String, Value>> {
+    match serde_json::from_str(data).ok()? {
+        Value::Object(data) => Some(data),
+        _ => None,
+    }
+}
+
+impl From<RepositoryError> for ResolveSyncConflictError {
+    fn from(error: RepositoryError) -> Self {
+        ResolveSyncConflictError::DatabaseError(error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use repository::{
+        mock::{mock_item_a, MockDataInserts},
+        test_db::setup_all,
+        BarcodeRow, BarcodeRowRepository, ChangelogFilter, EqualFilter,
+    };
+    use serde_json::json;
+    use util::inline_init;
+
+    use crate::{
+        service_provider::ServiceProvider,
+        sync::{sync_buffer::SyncBuffer, translation_and_integration::TranslationAndIntegration},
+    };
+
+    use super::*;
+
+    fn barcode_sync_record(gtin: &str, pack_size: i32) -> SyncBufferRow {
+        inline_init(|r: &mut SyncBufferRow| {
+            r.record_id = "barcode".to_string();
+            r.table_name = "barcode".to_string();
+            r.action = SyncBufferAction::Upsert;
+            r.data = json!({
+                "ID": "barcode",
+                "barcode": gtin,
+                "itemID": mock_item_a().id,
+                "manufacturerID": "",
+                "packSize": pack_size,
+                "parentID": ""
+            })
+            .to_string();
+        })
+    }
+
+    fn integrate_sync_record(connection: &StorageConnection, sync_record: SyncBufferRow) {
+        TranslationAndIntegration::new(connection, &SyncBuffer::new(connection), true)
+            .translate_and_integrate_sync_records(vec![sync_record], &all_translators(), None)
+            .unwrap();
+    }
+
+    fn edit_pack_size(connection: &StorageConnection, pack_size: i32) {
+        let repository = BarcodeRowRepository::new(connection);
+        let barcode = repository.find_one_by_id("barcode").unwrap().unwrap();
+        repository
+            .upsert_one(&BarcodeRow {
+                pack_size: Some(pack_size),
+                ..barcode
+            })
+            .unwrap();
+    }
+
+    fn is_latest_change_pushed(connection: &StorageConnection) -> bool {
+        let changelogs = ChangelogRepository::new(connection)
+            .changelogs(
+                0,
+                1000,
+                Some(ChangelogFilter::new().record_id(EqualFilter::equal_to("barcode"))),
+            )
+            .unwrap();
+        !changelogs.last().unwrap().is_sync_update
+    }
+
+    #[actix_rt::test]
+    async fn test_sync_conflict() {
+        let (_, connection, connection_manager, _) = setup_all(
+            "test_sync_conflict",
+            MockDataInserts::none().units().items(),
+        )
+        .await;
+        let barcode = || {
+            BarcodeRowRepository::new(&connection)
+                .find_one_by_id("barcode")
+                .unwrap()
+                .unwrap()
+        };
+
+        let version = || {
+            SyncRecordVersionRowRepository::new(&connection)
+                .find_one("barcode", "barcode")
+                .unwrap()
+        };
+
+        // No versions are kept during initialisation
+        TranslationAndIntegration::new(&connection, &SyncBuffer::new(&connection), false)
+            .translate_and_integrate_sync_records(
+                vec![barcode_sync_record("gtin1", 1)],
+                &all_translators(),
+                None,
+            )
+            .unwrap();
+        assert_eq!(version(), None);
+
+        // Local version is only kept when it differs from received version
+        integrate_sync_record(&connection, barcode_sync_record("gtin1", 1));
+        assert!(!is_latest_change_pushed(&connection));
+        assert_eq!(version().unwrap().local_data, None);
+        let translators = all_translators();
+        assert_eq!(
+            has_local_change(&connection, &translators, &barcode_sync_record("gtin1", 1)),
+            Ok(false)
+        );
+
+        // Different fields edited, merged and pushed
+        edit_pack_size(&connection, 2);
+        assert_eq!(
+            has_local_change(&connection, &translators, &barcode_sync_record("gtin1", 1)),
+            Ok(true)
+        );
+        integrate_sync_record(&connection, barcode_sync_record("gtin2", 1));
+        assert_eq!(
+            (barcode().gtin, barcode().pack_size),
+            ("gtin2".to_string(), Some(2))
+        );
+        assert!(is_latest_change_pushed(&connection));
+        assert!(version().unwrap().local_data.is_some());
+
+        // Same field edited, local version kept
+        edit_pack_size(&connection, 3);
+        integrate_sync_record(&connection, barcode_sync_record("gtin2", 4));
+        assert_eq!(barcode().pack_size, Some(3));
+
+        let conflicts = get_sync_conflicts(&connection, None, None, Some(false)).unwrap();
+        assert_eq!(conflicts.count, 1);
+        let conflict = conflicts.rows[0].clone();
+        assert_eq!(conflict.conflicting_fields, r#"["packSize"]"#);
+
+        // Resolution is pushed
+        let service_provider = ServiceProvider::new(connection_manager, "");
+        let ctx = service_provider.basic_context().unwrap();
+        let resolved =
+            resolve_sync_conflict(&ctx, &conflict.id, SyncConflictResolution::Remote).unwrap();
+        assert_eq!(resolved.resolution, Some(SyncConflictResolution::Remote));
+        assert_eq!(barcode().pack_size, Some(4));
+        assert!(is_latest_change_pushed(&connection));
+
+        assert_eq!(
+            resolve_sync_conflict(&ctx, &conflict.id, SyncConflictResolution::Local),
+            Err(ResolveSyncConflictError::ConflictAlreadyResolved)
+        );
+
+        // Patient conflicts are resolved in a store the patient is visible in
+        SyncConflictRowRepository::new(&connection)
+            .upsert_one(&inline_init(|r: &mut SyncConflictRow| {
+                r.id = "patient_conflict".to_string();
+                r.table_name = "name".to_string();
+                r.record_id = "patient_in_other_store".to_string();
+            }))
+            .unwrap();
+        assert_eq!(
+            resolve_sync_conflict(&ctx, "patient_conflict", SyncConflictResolution::Local),
+            Err(ResolveSyncConflictError::ConflictRecordNotInStore)
+        );
+
+        // Received version without local changes is integrated as is
+        integrate_sync_record(&connection, barcode_sync_record("gtin3", 4));
+        assert_eq!(barcode().gtin, "gtin3");
+        assert!(!is_latest_change_pushed(&connection));
+    }
+}
@@ -0,0 +1,371 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{Read, Seek, SeekFrom, Write},
+};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::{Duration, Utc};
+use repository::{
+    RepositoryError, StorageConnection, SyncFileDirection, SyncFileReferenceRow,
+    SyncFileReferenceRowRepository, SyncFileStatus,
+};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use util::format_error;
+
+use crate::static_files::{StaticFileCategory, StaticFileService};
+
+use super::api_v6::{SyncApiErrorV6, SyncApiV6, SyncFileChunkV6, SyncUploadFileSuccessV6};
+
+pub(crate) const SYNC_FILE_CHUNK_SIZE: u32 = 256 * 1024;
+/// Largest chunk central server sends, regardless of requested chunk size
+pub(crate) const MAX_SYNC_FILE_CHUNK_SIZE: u32 = 4 * 1024 * 1024;
+/// Larger files are not transferred once daily sync bandwidth budget is exceeded
+pub(crate) const LARGE_SYNC_FILE_BYTES: i64 = 5 * 1024 * 1024;
+const RETRY_BASE_SECONDS: i64 = 30;
+const RETRY_MAX_SECONDS: i64 = 24 * 60 * 60;
+
+#[derive(Error, Debug)]
+pub(crate) enum SyncFileTransferError {
+    #[error(transparent)]
+    SyncApiError(#[from] SyncApiErrorV6),
+    #[error("Database error")]
+    DatabaseError(#[from] RepositoryError),
+    #[error("Problem reading or writing file")]
+    FileError(#[from] std::io::Error),
+    #[error("Problem locating file")]
+    StaticFileError(#[source] anyhow::Error),
+    #[error("Invalid file chunk: {0}")]
+    InvalidChunk(String),
+    #[error("File hash does not match, transfer restarts from the beginning")]
+    HashMismatch,
+    #[error("No progress transferring file at offset {0}")]
+    NoProgress(u64),
+}
+
+/// Uploads files added on this site to central server, and downloads files added on other sites.
+/// Transfer progress is saved after every chunk, interrupted transfers resume from the last chunk
+pub(crate) struct SyncFileTransfer {
+    pub(crate) sync_api_v6: SyncApiV6,
+    pub(crate) static_file_service: StaticFileService,
+}
+
+impl SyncFileTransfer {
+    /// Transfer pending files (smaller files first), failed transfers are retried with backoff.
+    /// Only database errors are returned, transfer errors are recorded on the file reference
+    pub(crate) async fn transfer_files(
+        &self,
+        connection: &StorageConnection,
+        is_over_budget: bool,
+    ) -> Result<(), RepositoryError> {
+        let repo = SyncFileReferenceRowRepository::new(connection);
+        let now = Utc::now().naive_utc();
+
+        for direction in [SyncFileDirection::Upload, SyncFileDirection::Download] {
+            for row in repo.find_pending_transfers(direction.clone(), now)? {
+                if is_over_budget && row.total_bytes > LARGE_SYNC_FILE_BYTES {
+                    continue;
+                }
+
+                let id = row.id.clone();
+                let result = match direction {
+                    SyncFileDirection::Upload => self.upload_file(connection, row).await,
+                    SyncFileDirection::Download => self.download_file(connection, row).await,
+                };
+
+                if let Err(error) = result {
+                    log::warn!(
+                        "Problem transferring sync file {}: {}",
+                        id,
+                        format_error(&error)
+                    );
+                    record_transfer_error(connection, &id, &error)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn upload_file(
+        &self,
+        connection: &StorageConnection,
+        mut row: SyncFileReferenceRow,
+    ) -> Result<(), SyncFileTransferError> {
+        let repo = SyncFileReferenceRowRepository::new(connection);
+
+        let file = self
+            .static_file_service
+            .find_file(&row.id, sync_file_category(&row))
+            .map_err(SyncFileTransferError::StaticFileError)?;
+        let Some(file) = file else {
+            // Reference was received before transfer state was tracked, file is on another site
+            row.direction = SyncFileDirection::Download;
+            row.status = SyncFileStatus::Pending;
+            row.transferred_bytes = 0;
+            repo.update_transfer(&row)?;
+            return Ok(());
+        };
+
+        if row.hash.is_none() {
+            row.total_bytes = std::fs::metadata(&file.path)?.len() as i64;
+            row.hash = Some(file_hash(&file.path)?);
+        }
+        row.status = SyncFileStatus::InProgress;
+        repo.update_transfer(&row)?;
+
+        loop {
+            let offset = row.transferred_bytes as u64;
+            let chunk = read_file_chunk(&file.path, &row, offset, SYNC_FILE_CHUNK_SIZE)?;
+            let SyncUploadFileSuccessV6 { received_bytes } =
+                self.sync_api_v6.upload_file_chunk(chunk).await?;
+
+            if received_bytes >= row.total_bytes as u64 {
+                row.transferred_bytes = row.total_bytes;
+                set_done(&mut row);
+                repo.update_transfer(&row)?;
+                return Ok(());
+            }
+            if received_bytes == offset {
+                return Err(SyncFileTransferError::NoProgress(offset));
+            }
+
+            // Central server may ask to resume from a different offset
+            row.transferred_bytes = received_bytes as i64;
+            repo.update_transfer(&row)?;
+        }
+    }
+
+    async fn download_file(
+        &self,
+        connection: &StorageConnection,
+        mut row: SyncFileReferenceRow,
+    ) -> Result<(), SyncFileTransferError> {
+        row.status = SyncFileStatus::InProgress;
+        SyncFileReferenceRowRepository::new(connection).update_transfer(&row)?;
+
+        loop {
+            let offset = row.transferred_bytes as u64;
+            let chunk = self
+                .sync_api_v6
+                .download_file_chunk(&row.id, offset, SYNC_FILE_CHUNK_SIZE)
+                .await?;
+
+            row = receive_file_chunk(connection, &self.static_file_service, row, chunk)?;
+
+            if row.status == SyncFileStatus::Done {
+                return Ok(());
+            }
+            if row.transferred_bytes as u64 == offset {
+                return Err(SyncFileTransferError::NoProgress(offset));
+            }
+        }
+    }
+}
+
+pub(crate) fn sync_file_category(row: &SyncFileReferenceRow) -> StaticFileCategory {
+    StaticFileCategory::SyncFile(row.table_name.clone(), row.record_id.clone())
+}
+
+/// Chunk of file starting at `offset`, file size and hash are taken from sync file reference
+pub(crate) fn read_file_chunk(
+    path: &str,
+    row: &SyncFileReferenceRow,
+    offset: u64,
+    chunk_size: u32,
+) -> Result<SyncFileChunkV6, SyncFileTransferError> {
+    let mut file = File::open(path)?;
+    file.seek(SeekFrom::Start(offset))?;
+    let mut data = Vec::new();
+    file.take(chunk_size as u64).read_to_end(&mut data)?;
+
+    Ok(SyncFileChunkV6 {
+        file_id: row.id.clone(),
+        offset,
+        total_bytes: row.total_bytes as u64,
+        hash: row.hash.clone().unwrap_or_default(),
+        data: STANDARD.encode(data),
+    })
+}
+
+/// Writes chunk to the file if it continues from transferred bytes, otherwise chunk is ignored and
+/// transfer resumes from transferred bytes. File hash is checked once all bytes are received
+pub(crate) fn receive_file_chunk(
+    connection: &StorageConnection,
+    static_file_service: &StaticFileService,
+    mut row: SyncFileReferenceRow,
+    chunk: SyncFileChunkV6,
+) -> Result<SyncFileReferenceRow, SyncFileTransferError> {
+    if chunk.offset != row.transferred_bytes as u64 {
+        return Ok(row);
+    }
+    let repo = SyncFileReferenceRowRepository::new(connection);
+    let data = STANDARD
+        .decode(&chunk.data)
+        .map_err(|error| SyncFileTransferError::InvalidChunk(error.to_string()))?;
+
+    let path = static_file_service
+        .file_path(&row.id, &row.file_name, &sync_file_category(&row))
+        .map_err(SyncFileTransferError::StaticFileError)?;
+    let mut file = OpenOptions::new().create(true).write(true).open(&path)?;
+    file.set_len(chunk.offset)?;
+    file.seek(SeekFrom::Start(chunk.offset))?;
+    file.write_all(&data)?;
+
+    row.status = SyncFileStatus::InProgress;
+    row.transferred_bytes += data.len() as i64;
+    row.total_bytes = chunk.total_bytes as i64;
+    row.hash = Some(chunk.hash);
+
+    if row.transferred_bytes >= row.total_bytes {
+        if Some(file_hash(&path)?) != row.hash {
+            file.set_len(0)?;
+            row.transferred_bytes = 0;
+            repo.update_transfer(&row)?;
+            return Err(SyncFileTransferError::HashMismatch);
+        }
+        set_done(&mut row);
+    }
+
+    repo.update_transfer(&row)?;
+    Ok(row)
+}
+
+/// Sha256 of file content (hex)
+pub(crate) fn file_hash(path: &str) -> Result<String, std::io::Error> {
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut File::open(path)?, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Doubles with every retry, up to a day
+pub(crate) fn retry_delay(retries: i32) -> Duration {
+    let exponent = (retries - 1).clamp(0, 16) as u32;
+    Duration::seconds((RETRY_BASE_SECONDS * 2i64.pow(exponent)).min(RETRY_MAX_SECONDS))
+}
+
+fn set_done(row: &mut SyncFileReferenceRow) {
+    row.status = SyncFileStatus::Done;
+    row.retries = 0;
+    row.retry_datetime = None;
+    row.error = None;
+}
+
+fn record_transfer_error(
+    connection: &StorageConnection,
+    id: &str,
+    error: &SyncFileTransferError,
+) -> Result<(), RepositoryError> {
+    let repo = SyncFileReferenceRowRepository::new(connection);
+    // Reload to keep progress made before the error
+    let Some(mut row) = repo.find_one_by_id(id)? else {
+        return Ok(());
+    };
+
+    row.status = SyncFileStatus::Error;
+    row.retries += 1;
+    row.retry_datetime = Some(Utc::now().naive_utc() + retry_delay(row.retries));
+    row.error = Some(format_error(error));
+    repo.update_transfer(&row)
+}
+
+#[cfg(test)]
+mod test {
+    use std::{fs, path::PathBuf, str::FromStr};
+
+    use repository::{mock::MockDataInserts, test_db::setup_all, Upsert};
+
+    use super::*;
+
+    const TEST_DIR: &str = "test_sync_file_transfer";
+
+    #[test]
+    fn test_retry_delay() {
+        assert_eq!(retry_delay(1), Duration::seconds(30));
+        assert_eq!(retry_delay(3), Duration::seconds(120));
+        assert_eq!(retry_delay(100), Duration::seconds(RETRY_MAX_SECONDS));
+    }
+
+    #[actix_rt::test]
+    async fn test_receive_file_chunk() {
+        let (_, connection, _, _) =
+            setup_all("test_receive_file_chunk", MockDataInserts::none()).await;
+
+        let mut static_file_service = StaticFileService::new(&None).unwrap();
+        static_file_service.dir = PathBuf::from_str(TEST_DIR).unwrap();
+        let _ = fs::remove_dir_all(TEST_DIR);
+
+        let content = "sync file content".as_bytes();
+        let hash = format!("{:x}", Sha256::digest(content));
+        let row = SyncFileReferenceRow {
+            id: "sync_file_id".to_string(),
+            table_name: "asset".to_string(),
+            record_id: "asset_id".to_string(),
+            file_name: "photo.jpg".to_string(),
+            direction: SyncFileDirection::Download,
+            ..Default::default()
+        };
+        row.upsert_sync(&connection).unwrap();
+
+        let chunk = |offset: usize, end: usize, hash: &str| SyncFileChunkV6 {
+            file_id: row.id.clone(),
+            offset: offset as u64,
+            total_bytes: content.len() as u64,
+            hash: hash.to_string(),
+            data: STANDARD.encode(&content[offset..end]),
+        };
+
+        // First chunk
+        let row = receive_file_chunk(
+            &connection,
+            &static_file_service,
+            row.clone(),
+            chunk(0, 5, &hash),
+        )
+        .unwrap();
+        assert_eq!(row.transferred_bytes, 5);
+        assert_eq!(row.status, SyncFileStatus::InProgress);
+
+        // Chunk not continuing from transferred bytes is ignored
+        let row = receive_file_chunk(&connection, &static_file_service, row, chunk(8, 10, &hash))
+            .unwrap();
+        assert_eq!(row.transferred_bytes, 5);
+
+        // Wrong hash, transfer restarts
+        let result = receive_file_chunk(
+            &connection,
+            &static_file_service,
+            row.clone(),
+            chunk(5, content.len(), "wrong"),
+        );
+        assert!(matches!(result, Err(SyncFileTransferError::HashMismatch)));
+        let row = SyncFileReferenceRowRepository::new(&connection)
+            .find_one_by_id(&row.id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(row.transferred_bytes, 0);
+
+        // Whole file
+        let row = receive_file_chunk(
+            &connection,
+            &static_file_service,
+            row,
+            chunk(0, content.len(), &hash),
+        )
+        .unwrap();
+        assert_eq!(row.status, SyncFileStatus::Done);
+        assert_eq!(row.transferred_bytes, content.len() as i64);
+
+        let file = static_file_service
+            .find_file(&row.id, sync_file_category(&row))
+            .unwrap()
+            .unwrap();
+        assert_eq!(fs::read(&file.path).unwrap(), content);
+        assert_eq!(
+            read_file_chunk(&file.path, &row, 5, 4).unwrap().data,
+            STANDARD.encode(&content[5..9])
+        );
+
+        fs::remove_dir_all(TEST_DIR).unwrap();
+    }
+}
@@ -0,0 +1,289 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    convert::TryInto,
+};
+
+use chrono::{Duration, NaiveDateTime, Utc};
+use repository::{
+    KeyValueStoreRepository, KeyValueType, RepositoryError, StorageConnection, StoreFilter,
+    StoreRepository, SyncHealthRow, SyncHealthRowRepository,
+};
+use thiserror::Error;
+use util::is_central_server;
+
+use crate::{
+    i64_to_u64,
+    service_provider::{ServiceContext, ServiceProvider},
+};
+
+use super::{
+    api::SyncApiSettings,
+    api_v6::{SyncApiErrorV6, SyncHealthReportV6},
+    sync_status::status::NumberOfRecordsInPushQueueError,
+};
+
+const DEFAULT_STALE_AFTER_HOURS: i64 = 24;
+const DEFAULT_MAX_PUSH_QUEUE_COUNT: u64 = 1000;
+
+/// Site is reported as having a problem when any of the thresholds are exceeded
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyncHealthThresholds {
+    /// Time since last successful sync
+    pub stale_after: Duration,
+    /// Number of change logs waiting to be pushed by the site
+    pub max_push_queue_count: u64,
+}
+
+impl Default for SyncHealthThresholds {
+    fn default() -> Self {
+        Self {
+            stale_after: Duration::hours(DEFAULT_STALE_AFTER_HOURS),
+            max_push_queue_count: DEFAULT_MAX_PUSH_QUEUE_COUNT,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SiteSyncHealth {
+    pub site_id: i32,
+    /// Reported site name, or names of the site's stores when the site never reported
+    pub site_name: String,
+    /// Latest report of the site, `None` if the site never reported its sync health
+    pub row: Option<SyncHealthRow>,
+    /// Site didn't sync successfully within `stale_after` (or never reported)
+    pub is_stale: bool,
+    /// Latest sync on the site failed
+    pub is_failing: bool,
+    /// More than `max_push_queue_count` change logs are waiting to be pushed
+    pub is_backlogged: bool,
+}
+
+impl SiteSyncHealth {
+    pub fn has_problem(&self) -> bool {
+        self.is_stale || self.is_failing || self.is_backlogged
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum GetSyncHealthError {
+    NotACentralServer,
+    DatabaseError(RepositoryError),
+}
+
+impl From<RepositoryError> for GetSyncHealthError {
+    fn from(error: RepositoryError) -> Self {
+        GetSyncHealthError::DatabaseError(error)
+    }
+}
+
+/// Health of remote sites known to this central server, sorted by site name.
+/// Sites are taken from the stores on central and from the received reports, sites
+/// that never reported their sync health are stale
+pub fn get_sync_health(
+    ctx: &ServiceContext,
+    thresholds: &SyncHealthThresholds,
+    problems_only: bool,
+) -> Result<Vec<SiteSyncHealth>, GetSyncHealthError> {
+    if !is_central_server() {
+        return Err(GetSyncHealthError::NotACentralServer);
+    }
+    let connection = &ctx.connection;
+
+    let central_site_id =
+        KeyValueStoreRepository::new(connection).get_i32(KeyValueType::SettingsSyncSiteId)?;
+
+    let mut store_names_by_site: BTreeMap<i32, Vec<String>> = BTreeMap::new();
+    for store in StoreRepository::new(connection).query_by_filter(StoreFilter::new())? {
+        let site_id = store.store_row.site_id;
+        if site_id <= 0 || Some(site_id) == central_site_id {
+            continue;
+        }
+        store_names_by_site
+            .entry(site_id)
+            .or_default()
+            .push(store.name_row.name);
+    }
+
+    let mut rows: HashMap<i32, SyncHealthRow> = SyncHealthRowRepository::new(connection)
+        .find_all()?
+        .into_iter()
+        .map(|row| (row.site_id, row))
+        .collect();
+
+    let stale_before = Utc::now().naive_utc() - thresholds.stale_after;
+    let mut result: Vec<SiteSyncHealth> = store_names_by_site
+        .into_iter()
+        .map(|(site_id, mut store_names)| match rows.remove(&site_id) {
+            Some(row) => site_sync_health(row, thresholds, stale_before),
+            None => {
+                store_names.sort();
+                unreported_site_sync_health(site_id, store_names.join(", "))
+            }
+        })
+        .collect();
+    // Sites that reported but have no stores on central (yet)
+    result.extend(
+        rows.into_values()
+            .map(|row| site_sync_health(row, thresholds, stale_before)),
+    );
+    result.retain(|health| !problems_only || health.has_problem());
+    result.sort_by(|a, b| a.site_name.cmp(&b.site_name));
+
+    Ok(result)
+}
+
+fn site_sync_health(
+    row: SyncHealthRow,
+    thresholds: &SyncHealthThresholds,
+    stale_before: NaiveDateTime,
+) -> SiteSyncHealth {
+    let is_stale = row
+        .last_successful_sync_datetime
+        .map_or(true, |datetime| datetime < stale_before);
+    let is_failing = row.error_code.is_some() || row.error_message.is_some();
+    let is_backlogged = i64_to_u64(row.push_queue_count) > thresholds.max_push_queue_count;
+
+    SiteSyncHealth {
+        site_id: row.site_id,
+        site_name: row.site_name.clone(),
+        row: Some(row),
+        is_stale,
+        is_failing,
+        is_backlogged,
+    }
+}
+
+/// Site stopped syncing before it could report, or only syncs with legacy central
+fn unreported_site_sync_health(site_id: i32, site_name: String) -> SiteSyncHealth {
+    SiteSyncHealth {
+        site_id,
+        site_name,
+        row: None,
+        is_stale: true,
+        is_failing: false,
+        is_backlogged: false,
+    }
+}
+
+#[derive(Error, Debug)]
+pub(crate) enum ReportSyncHealthError {
+    #[error("Database error while reporting sync health")]
+    DatabaseError(#[from] RepositoryError),
+    #[error(transparent)]
+    SyncApiError(#[from] SyncApiErrorV6),
+}
+
+/// Collected on remote site after the sync is logged, so that report includes the latest sync
+pub(crate) fn generate_health_report(
+    service_provider: &ServiceProvider,
+    ctx: &ServiceContext,
+) -> Result<SyncHealthReportV6, RepositoryError> {
+    let sync_status_service = &service_provider.sync_status_service;
+
+    let latest = sync_status_service.get_latest_sync_status(ctx)?;
+    let latest_successful = sync_status_service.get_latest_successful_sync_status(ctx)?;
+    let push_queue_count = match sync_status_service.number_of_records_in_push_queue(ctx) {
+        Ok(count) => count,
+        Err(NumberOfRecordsInPushQueueError::DatabaseError(error)) => return Err(error),
+        // Site info was not received yet, nothing can be pushed
+        Err(NumberOfRecordsInPushQueueError::SiteIdNotSet) => 0,
+    };
+    let database_size = SyncHealthRowRepository::new(&ctx.connection).database_size()?;
+
+    let error = latest.as_ref().and_then(|status| status.error.clone());
+
+    Ok(SyncHealthReportV6 {
+        last_sync_datetime: latest.map(|status| status.summary.started),
+        last_successful_sync_datetime: latest_successful.and_then(|status| status.summary.finished),
+        error_code: error.as_ref().and_then(|error| error.code.clone()),
+        error_message: error.map(|error| error.message),
+        push_queue_count,
+        database_size: i64_to_u64(database_size),
+    })
+}
+
+/// Stored on central server, replacing previous report of the site
+pub(crate) fn save_health_report(
+    connection: &StorageConnection,
+    site_id: i32,
+    sync_v5_settings: &SyncApiSettings,
+    SyncHealthReportV6 {
+        last_sync_datetime,
+        last_successful_sync_datetime,
+        error_code,
+        error_message,
+        push_queue_count,
+        database_size,
+    }: SyncHealthReportV6,
+) -> Result<(), RepositoryError> {
+    SyncHealthRowRepository::new(connection).upsert_one(&SyncHealthRow {
+        site_id,
+        site_name: sync_v5_settings.username.clone(),
+        app_version: sync_v5_settings.app_version.clone(),
+        reported_datetime: Utc::now().naive_utc(),
+        last_sync_datetime,
+        last_successful_sync_datetime,
+        error_code,
+        error_message,
+        push_queue_count: push_queue_count.try_into().unwrap_or(i64::MAX),
+        database_size: database_size.try_into().unwrap_or(i64::MAX),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{Duration, Utc};
+    use repository::{SyncHealthRow, SyncLogRowErrorCode};
+    use util::inline_init;
+
+    use super::{site_sync_health, unreported_site_sync_health, SyncHealthThresholds};
+
+    #[test]
+    fn sync_health_thresholds() {
+        let now = Utc::now().naive_utc();
+        let thresholds = SyncHealthThresholds {
+            stale_after: Duration::hours(2),
+            max_push_queue_count: 100,
+        };
+        let stale_before = now - thresholds.stale_after;
+
+        let healthy = inline_init(|r: &mut SyncHealthRow| {
+            r.site_id = 1;
+            r.last_successful_sync_datetime = Some(now - Duration::hours(1));
+            r.push_queue_count = 100;
+        });
+        let stale = inline_init(|r: &mut SyncHealthRow| {
+            r.site_id = 2;
+            r.last_successful_sync_datetime = Some(now - Duration::hours(3));
+        });
+        let never_synced = inline_init(|r: &mut SyncHealthRow| {
+            r.site_id = 3;
+        });
+        let failing = inline_init(|r: &mut SyncHealthRow| {
+            r.site_id = 4;
+            r.last_successful_sync_datetime = Some(now);
+            r.error_code = Some(SyncLogRowErrorCode::ConnectionError);
+        });
+        let backlogged = inline_init(|r: &mut SyncHealthRow| {
+            r.site_id = 5;
+            r.last_successful_sync_datetime = Some(now);
+            r.push_queue_count = 101;
+        });
+
+        let health = |row: &SyncHealthRow| {
+            let health = site_sync_health(row.clone(), &thresholds, stale_before);
+            (health.is_stale, health.is_failing, health.is_backlogged)
+        };
+
+        assert_eq!(health(&healthy), (false, false, false));
+        assert_eq!(health(&stale), (true, false, false));
+        assert_eq!(health(&never_synced), (true, false, false));
+        assert_eq!(health(&failing), (false, true, false));
+        assert_eq!(health(&backlogged), (false, false, true));
+
+        let unreported = unreported_site_sync_health(6, "Store A, Store B".to_string());
+        assert_eq!(unreported.row, None);
+        assert!(unreported.is_stale);
+        assert!(unreported.has_problem());
+    }
+}
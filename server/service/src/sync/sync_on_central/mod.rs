@@ -1,19 +1,46 @@
-use repository::{ChangelogRepository, SyncBufferRowRepository};
-use util::{format_error, is_central_server};
+use std::{collections::BTreeMap, sync::Mutex};
+
+use chrono::{Duration, NaiveDateTime, Utc};
+use repository::{
+    ChangelogRepository, ChangelogRow, ChangelogTableName, EqualFilter, SyncBufferRowRepository,
+    SyncFileDirection, SyncFileReferenceRowRepository, SyncFileStatus,
+};
+use util::{format_error, inline_init, is_central_server};
 
 use crate::{
     service_provider::ServiceProvider,
-    sync::{api::SyncApiV5, translations::ToSyncRecordTranslationType},
+    static_files::StaticFileService,
+    sync::{
+        api::{SyncApiSettings, SyncApiV5},
+        translations::ToSyncRecordTranslationType,
+    },
 };
 
 use super::{
     api_v6::{
-        SyncBatchV6, SyncParsedErrorV6, SyncPullRequestV6, SyncPushRequestV6, SyncPushSuccessV6,
-        SyncRecordV6,
+        batch_checksum, SyncBatchV6, SyncDownloadFileRequestV6, SyncFileChunkV6,
+        SyncInitialisationSummaryRequestV6, SyncInitialisationSummaryV6, SyncParsedErrorV6,
+        SyncPullRequestV6, SyncPullTableFilterV6, SyncPushRequestV6, SyncPushSuccessV6,
+        SyncRecordV6, SyncReportHealthRequestV6, SyncReportHealthSuccessV6, SyncTableCountV6,
+        SyncUploadFileRequestV6, SyncUploadFileSuccessV6,
+    },
+    sync_file_transfer::{
+        file_hash, read_file_chunk, receive_file_chunk, sync_file_category, SyncFileTransferError,
+        MAX_SYNC_FILE_CHUNK_SIZE,
+    },
+    sync_health::save_health_report,
+    translations::{
+        all_translators, translate_changelogs_to_sync_records, translation_type_for_table,
     },
-    translations::translate_changelogs_to_sync_records,
 };
 
+/// How long file transfer access is kept before credentials are checked again
+const FILE_TRANSFER_ACCESS_MINUTES: i64 = 60;
+
+/// When file transfer access was checked, by file id, username and password hash
+static FILE_TRANSFER_ACCESS: Mutex<BTreeMap<(String, String, String), NaiveDateTime>> =
+    Mutex::new(BTreeMap::new());
+
 /// Send Records to a remote open-mSupply Server
 pub async fn pull(
     service_provider: &ServiceProvider,
@@ -22,6 +49,8 @@ pub async fn pull(
         batch_size,
         sync_v5_settings,
         is_initialised,
+        v6_sync_tables,
+        table_filter,
     }: SyncPullRequestV6,
 ) -> Result<SyncBatchV6, SyncParsedErrorV6> {
     use SyncParsedErrorV6 as Error;
@@ -38,6 +67,14 @@ pub async fn pull(
 
     let ctx = service_provider.basic_context()?;
     let changelog_repo = ChangelogRepository::new(&ctx.connection);
+    let table_filter = table_filter.map(|table_filter| match table_filter {
+        SyncPullTableFilterV6::Only(tables) => {
+            inline_init(|r: &mut EqualFilter<ChangelogTableName>| r.equal_any = Some(tables))
+        }
+        SyncPullTableFilterV6::Exclude(tables) => {
+            inline_init(|r: &mut EqualFilter<ChangelogTableName>| r.not_equal_all = Some(tables))
+        }
+    });
 
     // We don't need a filter here, as we are filtering in the repository layer
     let changelogs = changelog_repo.outgoing_sync_records_from_central(
@@ -45,11 +82,15 @@ pub async fn pull(
         batch_size,
         response.site_id,
         is_initialised,
+        &v6_sync_tables,
+        table_filter.clone(),
     )?;
     let total_records = changelog_repo.count_outgoing_sync_records_from_central(
         cursor,
         response.site_id,
         is_initialised,
+        &v6_sync_tables,
+        table_filter,
     )?;
     let max_cursor = changelog_repo.latest_cursor()?;
 
@@ -78,10 +119,72 @@ pub async fn pull(
     Ok(SyncBatchV6 {
         total_records,
         end_cursor,
+        checksum: Some(batch_checksum(&records)),
         records,
     })
 }
 
+/// Number of records per table that will be sent to the site during initialisation,
+/// used by remote site to report initialisation progress and to verify initialisation is complete
+pub async fn initialisation_summary(
+    service_provider: &ServiceProvider,
+    SyncInitialisationSummaryRequestV6 {
+        sync_v5_settings,
+        v6_sync_tables,
+    }: SyncInitialisationSummaryRequestV6,
+) -> Result<SyncInitialisationSummaryV6, SyncParsedErrorV6> {
+    use SyncParsedErrorV6 as Error;
+
+    if !is_central_server() {
+        return Err(Error::NotACentralServer);
+    }
+    // Check credentials again mSupply central server
+    let response = SyncApiV5::new(sync_v5_settings)
+        .map_err(|e| Error::OtherServerError(format_error(&e)))?
+        .get_site_info()
+        .await
+        .map_err(Error::from)?;
+
+    let ctx = service_provider.basic_context()?;
+    let changelog_counts = ChangelogRepository::new(&ctx.connection)
+        .count_outgoing_sync_records_from_central_by_table(response.site_id, &v6_sync_tables)?;
+
+    // Changelog table names are mapped to sync record table names, which is what the site
+    // sees in sync buffer
+    let translators = all_translators();
+    let mut tables: Vec<SyncTableCountV6> = Vec::new();
+    for (table_name, count) in changelog_counts {
+        let changelog = ChangelogRow {
+            table_name,
+            ..Default::default()
+        };
+        let sync_table_names = translators
+            .iter()
+            .filter(|translator| {
+                translator.should_translate_to_sync_record(
+                    &changelog,
+                    translation_type_for_table(
+                        &changelog.table_name,
+                        &ToSyncRecordTranslationType::PullFromOmSupplyCentral,
+                    ),
+                )
+            })
+            .map(|translator| translator.table_name().to_string());
+
+        for sync_table_name in sync_table_names {
+            match tables.iter_mut().find(|t| t.table_name == sync_table_name) {
+                Some(table) => table.count += count,
+                None => tables.push(SyncTableCountV6 {
+                    table_name: sync_table_name,
+                    count,
+                }),
+            }
+        }
+    }
+
+    Ok(SyncInitialisationSummaryV6 { tables })
+}
+
 /// Receive Records from a remote open-mSupply Server
 pub async fn push(
     service_provider: &ServiceProvider,
@@ -113,9 +216,16 @@ pub async fn push(
     let SyncBatchV6 {
         records,
         total_records,
+        checksum,
         ..
     } = batch;
 
+    if let Some(checksum) = checksum {
+        if checksum != batch_checksum(&records) {
+            return Err(Error::ChecksumMismatch);
+        }
+    }
+
     let ctx = service_provider.basic_context()?;
     let repo = SyncBufferRowRepository::new(&ctx.connection);
 
@@ -136,3 +246,183 @@ pub async fn push(
         records_pushed: records_in_this_batch,
     })
 }
+
+/// Receive chunk of sync file uploaded by remote site, returns bytes of the file received so far
+/// (remote site resumes upload from there)
+pub async fn upload_file(
+    service_provider: &ServiceProvider,
+    base_dir: &Option<String>,
+    SyncUploadFileRequestV6 {
+        chunk,
+        sync_v5_settings,
+    }: SyncUploadFileRequestV6,
+) -> Result<SyncUploadFileSuccessV6, SyncParsedErrorV6> {
+    use SyncParsedErrorV6 as Error;
+
+    if !is_central_server() {
+        return Err(Error::NotACentralServer);
+    }
+    check_file_transfer_access(service_provider, &chunk.file_id, sync_v5_settings).await?;
+
+    let ctx = service_provider.basic_context()?;
+    // Reference is pushed (and integrated) before the file is uploaded
+    let row = SyncFileReferenceRowRepository::new(&ctx.connection)
+        .find_one_by_id(&chunk.file_id)?
+        .ok_or_else(|| Error::SyncFileNotFound(chunk.file_id.clone()))?;
+
+    if row.status == SyncFileStatus::Done {
+        return Ok(SyncUploadFileSuccessV6 {
+            received_bytes: row.total_bytes as u64,
+        });
+    }
+
+    let static_file_service = StaticFileService::new(base_dir)
+        .map_err(|e| Error::OtherServerError(format!("{:#}", e)))?;
+    let row = match receive_file_chunk(&ctx.connection, &static_file_service, row, chunk) {
+        Ok(row) => row,
+        Err(SyncFileTransferError::HashMismatch) => return Err(Error::SyncFileHashMismatch),
+        Err(error) => return Err(Error::OtherServerError(format_error(&error))),
+    };
+
+    Ok(SyncUploadFileSuccessV6 {
+        received_bytes: row.transferred_bytes as u64,
+    })
+}
+
+/// Send chunk of sync file to remote site, file has to be fully uploaded to central server
+/// (or added on central server)
+pub async fn download_file(
+    service_provider: &ServiceProvider,
+    base_dir: &Option<String>,
+    SyncDownloadFileRequestV6 {
+        file_id,
+        offset,
+        chunk_size,
+        sync_v5_settings,
+    }: SyncDownloadFileRequestV6,
+) -> Result<SyncFileChunkV6, SyncParsedErrorV6> {
+    use SyncParsedErrorV6 as Error;
+
+    if !is_central_server() {
+        return Err(Error::NotACentralServer);
+    }
+    check_file_transfer_access(service_provider, &file_id, sync_v5_settings).await?;
+
+    let ctx = service_provider.basic_context()?;
+    let repo = SyncFileReferenceRowRepository::new(&ctx.connection);
+    let mut row = repo
+        .find_one_by_id(&file_id)?
+        .ok_or_else(|| Error::SyncFileNotFound(file_id.clone()))?;
+
+    let is_added_on_central = row.direction == SyncFileDirection::Upload;
+    if row.status != SyncFileStatus::Done && !is_added_on_central {
+        return Err(Error::SyncFileNotAvailable(file_id));
+    }
+
+    let file = StaticFileService::new(base_dir)
+        .and_then(|service| service.find_file(&row.id, sync_file_category(&row)))
+        .map_err(|e| Error::OtherServerError(format!("{:#}", e)))?
+        .ok_or_else(|| Error::SyncFileNotAvailable(file_id.clone()))?;
+
+    if row.hash.is_none() {
+        let file_error = |e: std::io::Error| Error::OtherServerError(format_error(&e));
+        row.total_bytes = std::fs::metadata(&file.path).map_err(file_error)?.len() as i64;
+        row.hash = Some(file_hash(&file.path).map_err(file_error)?);
+        row.status = SyncFileStatus::Done;
+        repo.update_transfer(&row)?;
+    }
+
+    read_file_chunk(
+        &file.path,
+        &row,
+        offset,
+        chunk_size.min(MAX_SYNC_FILE_CHUNK_SIZE),
+    )
+    .map_err(|e| Error::OtherServerError(format_error(&e)))
+}
+
+/// Checks credentials against mSupply central server and that the site has access to the record
+/// the file belongs to. Access is kept for the file, so it's not checked for every chunk
+async fn check_file_transfer_access(
+    service_provider: &ServiceProvider,
+    file_id: &str,
+    sync_v5_settings: SyncApiSettings,
+) -> Result<(), SyncParsedErrorV6> {
+    use SyncParsedErrorV6 as Error;
+
+    let key = (
+        file_id.to_string(),
+        sync_v5_settings.username.clone(),
+        sync_v5_settings.password_sha256.clone(),
+    );
+    let now = Utc::now().naive_utc();
+    let is_expired = |checked_datetime: &NaiveDateTime| {
+        now - *checked_datetime > Duration::minutes(FILE_TRANSFER_ACCESS_MINUTES)
+    };
+    if let Ok(checked) = FILE_TRANSFER_ACCESS.lock() {
+        if checked
+            .get(&key)
+            .map_or(false, |datetime| !is_expired(datetime))
+        {
+            return Ok(());
+        }
+    }
+
+    // Check credentials again mSupply central server
+    let response = SyncApiV5::new(sync_v5_settings)
+        .map_err(|e| Error::OtherServerError(format_error(&e)))?
+        .get_site_info()
+        .await
+        .map_err(Error::from)?;
+
+    let ctx = service_provider.basic_context()?;
+    let row = SyncFileReferenceRowRepository::new(&ctx.connection)
+        .find_one_by_id(file_id)?
+        .ok_or_else(|| Error::SyncFileNotFound(file_id.to_string()))?;
+
+    let change_log_type = all_translators()
+        .iter()
+        .find(|translator| translator.table_name() == row.table_name)
+        .and_then(|translator| translator.change_log_type());
+    let has_access =
+        match change_log_type {
+            Some(table_name) => ChangelogRepository::new(&ctx.connection)
+                .is_record_visible_to_site(table_name, &row.record_id, response.site_id)?,
+            None => false,
+        };
+    if !has_access {
+        return Err(Error::SyncFileAccessDenied(file_id.to_string()));
+    }
+
+    if let Ok(mut checked) = FILE_TRANSFER_ACCESS.lock() {
+        checked.retain(|_, datetime| !is_expired(datetime));
+        checked.insert(key, now);
+    }
+    Ok(())
+}
+
+/// Store sync health reported by remote site, see `get_sync_health`
+pub async fn report_health(
+    service_provider: &ServiceProvider,
+    SyncReportHealthRequestV6 {
+        report,
+        sync_v5_settings,
+    }: SyncReportHealthRequestV6,
+) -> Result<SyncReportHealthSuccessV6, SyncParsedErrorV6> {
+    use SyncParsedErrorV6 as Error;
+
+    if !is_central_server() {
+        return Err(Error::NotACentralServer);
+    }
+    // Check credentials again mSupply central server
+    let response = SyncApiV5::new(sync_v5_settings.clone())
+        .map_err(|e| Error::OtherServerError(format_error(&e)))?
+        .get_site_info()
+        .await
+        .map_err(Error::from)?;
+
+    let ctx = service_provider.basic_context()?;
+    save_health_report(&ctx.connection, response.site_id, &sync_v5_settings, report)?;
+
+    Ok(SyncReportHealthSuccessV6 {})
+}
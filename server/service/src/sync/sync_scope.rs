@@ -0,0 +1,723 @@
+use std::collections::HashSet;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::{Months, NaiveDateTime, Utc};
+use repository::{
+    DatetimeFilter, EqualFilter, KeyValueStoreRepository, KeyValueType, Pagination,
+    RepositoryError, StorageConnection, SyncBufferAction, SyncBufferFilter, SyncBufferRepository,
+    SyncBufferRow, SyncBufferRowRepository, SyncScopeRepository,
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::cursor_controller::CursorController;
+
+use super::{
+    bandwidth::{gunzip, gzip},
+    ActiveStoresOnSite, GetActiveStoresOnSiteError,
+};
+
+const RESTORE_BATCH_SIZE: u32 = 1000;
+const COMPRESSED_PAYLOAD_PREFIX: &str = "gzip:";
+
+/// Limits records kept on the site, to save disk space on low end devices.
+///
+/// Out of scope records are still received and integrated (legacy central server can't filter them), but
+/// purged after integration, including during initialisation. Purged central records stay in sync buffer
+/// with compressed payload, to be integrated again when they come back into scope
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default)]
+pub struct SyncScope {
+    /// Only keep items on master lists of stores on this site
+    #[serde(default)]
+    pub master_list_items_only: bool,
+    /// Only keep patients visible in stores on this site (through name store joins)
+    #[serde(default)]
+    pub visible_patients_only: bool,
+    /// Only keep temperature logs of the last number of months
+    #[serde(default)]
+    pub temperature_log_months: Option<u32>,
+    /// Only keep activity logs of the last number of months
+    #[serde(default)]
+    pub activity_log_months: Option<u32>,
+}
+
+#[derive(Error, Debug)]
+pub(crate) enum PurgeOutOfScopeError {
+    #[error("Database error while purging out of scope records")]
+    DatabaseError(#[from] RepositoryError),
+    #[error(transparent)]
+    GetActiveStoresOnSiteError(#[from] GetActiveStoresOnSiteError),
+}
+
+/// Number of records purged per table
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct PurgeOutOfScopeResult {
+    pub(crate) master_list_lines: usize,
+    pub(crate) items: usize,
+    pub(crate) patients: usize,
+    pub(crate) temperature_logs: usize,
+    pub(crate) activity_logs: usize,
+}
+
+pub fn get_sync_scope(connection: &StorageConnection) -> Result<SyncScope, RepositoryError> {
+    let scope = KeyValueStoreRepository::new(connection).get_string(KeyValueType::SyncScope)?;
+
+    Ok(scope
+        .and_then(|scope| serde_json::from_str(&scope).ok())
+        .unwrap_or_default())
+}
+
+/// Purged records are integrated again on next sync, and purged according to the new scope
+pub fn set_sync_scope(
+    connection: &StorageConnection,
+    scope: SyncScope,
+) -> Result<SyncScope, RepositoryError> {
+    connection
+        .transaction_sync(|connection| -> Result<_, RepositoryError> {
+            KeyValueStoreRepository::new(connection)
+                .set_string(KeyValueType::SyncScope, serde_json::to_string(&scope).ok())?;
+            restore_out_of_scope(connection)?;
+            Ok(scope)
+        })
+        .map_err(|error| error.to_inner_error())
+}
+
+/// Called before integration, restores purged records if received records defining the scope change
+/// the scope of this site (e.g. master list joined to a store on this site)
+pub(crate) fn restore_if_scope_changed(
+    connection: &StorageConnection,
+) -> Result<(), RepositoryError> {
+    let scope = get_sync_scope(connection)?;
+    let mut table_names = Vec::new();
+    if scope.master_list_items_only {
+        table_names.push("list_master_name_join".to_string());
+    }
+    if scope.visible_patients_only {
+        table_names.push("name_store_join".to_string());
+    }
+    if table_names.is_empty() {
+        return Ok(());
+    }
+    table_names.push("store".to_string());
+
+    let scope_changes = SyncBufferRepository::new(connection).query_by_filter(
+        SyncBufferFilter::new()
+            .table_name(EqualFilter::equal_any(table_names))
+            .action(SyncBufferAction::Upsert.equal_to())
+            .integration_datetime(DatetimeFilter::is_null(true)),
+    )?;
+    if scope_changes.is_empty() {
+        return Ok(());
+    }
+
+    let site_id =
+        KeyValueStoreRepository::new(connection).get_i32(KeyValueType::SettingsSyncSiteId)?;
+    let stores = match ActiveStoresOnSite::get(connection) {
+        Ok(stores) => stores,
+        // Site is not initialised yet, nothing was purged
+        Err(GetActiveStoresOnSiteError::SiteIdNotSet) => return Ok(()),
+        Err(GetActiveStoresOnSiteError::DatabaseError(error)) => return Err(error),
+    };
+
+    if scope_changes
+        .iter()
+        .any(|row| changes_site_scope(row, site_id, &stores))
+    {
+        restore_out_of_scope(connection)?;
+    }
+    Ok(())
+}
+
+/// Deleted scope defining records only narrow the scope, records are purged on next purge
+fn changes_site_scope(
+    row: &SyncBufferRow,
+    site_id: Option<i32>,
+    stores: &ActiveStoresOnSite,
+) -> bool {
+    #[derive(Deserialize)]
+    struct ScopeDefiningRecord {
+        #[serde(rename = "ID")]
+        id: Option<String>,
+        #[serde(rename = "name_ID")]
+        name_id: Option<String>,
+        #[serde(rename = "store_ID")]
+        store_id: Option<String>,
+        #[serde(rename = "sync_id_remote_site")]
+        site_id: Option<i32>,
+    }
+
+    let Ok(record) = serde_json::from_str::<ScopeDefiningRecord>(&row.data) else {
+        // Can't tell, restore to be safe
+        return true;
+    };
+    let is_store_on_site = |store_id: &Option<String>| {
+        store_id
+            .as_ref()
+            .map(|store_id| stores.store_ids().contains(store_id))
+            .unwrap_or(false)
+    };
+
+    match row.table_name.as_str() {
+        "list_master_name_join" => record
+            .name_id
+            .map(|name_id| stores.name_ids().contains(&name_id))
+            .unwrap_or(true),
+        "name_store_join" => is_store_on_site(&record.store_id),
+        // Store moved to or from this site
+        "store" => record.site_id == site_id || is_store_on_site(&record.id),
+        _ => true,
+    }
+}
+
+/// Called after integration, records that failed integration since `integration_start` may reference
+/// purged records (e.g. transfer of an item that is not on store's master list). In this case purged
+/// records are restored and failed records are reset, returns true if they should be integrated again
+pub(crate) fn restore_for_failed_records(
+    connection: &StorageConnection,
+    integration_start: NaiveDateTime,
+) -> Result<bool, RepositoryError> {
+    let failed_record_ids = SyncBufferRepository::new(connection).query_record_ids(Some(
+        SyncBufferFilter::new()
+            .integration_datetime(DatetimeFilter::after_or_equal_to(integration_start))
+            .integration_error(EqualFilter::is_null(false)),
+    ))?;
+    if failed_record_ids.is_empty() {
+        return Ok(false);
+    }
+
+    if restore_out_of_scope(connection)? == 0 {
+        return Ok(false);
+    }
+    SyncBufferRowRepository::new(connection).reset_integration(&failed_record_ids)?;
+    Ok(true)
+}
+
+/// Marks purged records for integration on next sync, returns number of restored records
+pub(crate) fn restore_out_of_scope(
+    connection: &StorageConnection,
+) -> Result<usize, RepositoryError> {
+    let repo = SyncBufferRepository::new(connection);
+    let row_repo = SyncBufferRowRepository::new(connection);
+    let mut restored = 0;
+
+    loop {
+        let rows = repo.query_with_pagination(
+            Pagination {
+                offset: 0,
+                limit: RESTORE_BATCH_SIZE,
+            },
+            Some(SyncBufferFilter::new().is_out_of_scope(true)),
+        )?;
+        if rows.is_empty() {
+            break;
+        }
+        restored += rows.len();
+
+        for row in rows {
+            row_repo.upsert_one(&SyncBufferRow {
+                data: decompress_payload(row.data),
+                integration_datetime: None,
+                is_out_of_scope: false,
+                ..row
+            })?;
+        }
+    }
+
+    Ok(restored)
+}
+
+/// Purged records are kept in sync buffer to be integrated again when scope changes, their payload
+/// is compressed to save disk space
+fn set_out_of_scope(
+    connection: &StorageConnection,
+    table_name: &str,
+    record_ids: &[String],
+) -> Result<(), RepositoryError> {
+    let repo = SyncBufferRepository::new(connection);
+    let row_repo = SyncBufferRowRepository::new(connection);
+
+    for chunk in record_ids.chunks(RESTORE_BATCH_SIZE as usize) {
+        let rows = repo.query_by_filter(
+            SyncBufferFilter::new()
+                .table_name(EqualFilter::equal_to(table_name))
+                .record_id(EqualFilter::equal_any(chunk.to_vec())),
+        )?;
+        for row in rows {
+            row_repo.upsert_one(&SyncBufferRow {
+                data: compress_payload(row.data),
+                is_out_of_scope: true,
+                ..row
+            })?;
+        }
+    }
+    Ok(())
+}
+
+fn compress_payload(data: String) -> String {
+    match gzip(data.as_bytes()) {
+        Ok(compressed) => format!(
+            "{}{}",
+            COMPRESSED_PAYLOAD_PREFIX,
+            STANDARD.encode(compressed)
+        ),
+        Err(error) => {
+            log::error!("Failed to compress purged sync buffer record {}", error);
+            data
+        }
+    }
+}
+
+fn decompress_payload(data: String) -> String {
+    let Some(encoded) = data.strip_prefix(COMPRESSED_PAYLOAD_PREFIX) else {
+        return data;
+    };
+    let decompressed = STANDARD
+        .decode(encoded)
+        .map_err(|error| error.to_string())
+        .and_then(|compressed| gunzip(&compressed).map_err(|error| error.to_string()))
+        .and_then(|bytes| String::from_utf8(bytes).map_err(|error| error.to_string()));
+
+    match decompressed {
+        Ok(data) => data,
+        Err(error) => {
+            // Record fails integration and is shown in sync buffer errors
+            log::error!("Failed to decompress purged sync buffer record {}", error);
+            data
+        }
+    }
+}
+
+/// Deletes integrated records that are out of scope of the site (see SyncScope), purging is not synced.
+///
+/// Items and patients are only purged if they were received through sync (so they can be integrated
+/// again from sync buffer) and are not referenced by other records. Logs are only purged once pushed
+pub(crate) fn purge_out_of_scope(
+    connection: &StorageConnection,
+) -> Result<PurgeOutOfScopeResult, PurgeOutOfScopeError> {
+    let scope = get_sync_scope(connection)?;
+    let mut result = PurgeOutOfScopeResult::default();
+    if scope == SyncScope::default() {
+        return Ok(result);
+    }
+
+    let repo = SyncScopeRepository::new(connection);
+    let stores = ActiveStoresOnSite::get(connection)?;
+
+    if scope.master_list_items_only {
+        // Lines of master lists not used on this site reference items that would otherwise be kept
+        let master_list_ids = repo.master_list_ids(&stores.name_ids())?;
+        let line_ids = intersection(
+            repo.integrated_record_ids("list_master_line")?,
+            repo.master_list_line_ids_not_on_master_lists(&master_list_ids)?,
+        );
+        repo.delete_master_list_lines(&line_ids)?;
+        set_out_of_scope(connection, "list_master_line", &line_ids)?;
+        result.master_list_lines = line_ids.len();
+
+        let visible_item_ids: HashSet<String> = repo
+            .visible_item_ids(&stores.name_ids())?
+            .into_iter()
+            .collect();
+        let unreferenced_item_ids = repo.unreferenced_item_ids()?;
+        let item_ids: Vec<String> = repo
+            .integrated_record_ids("item")?
+            .into_iter()
+            .filter(|id| !visible_item_ids.contains(id) && unreferenced_item_ids.contains(id))
+            .collect();
+        repo.delete_items(&item_ids)?;
+        set_out_of_scope(connection, "item", &item_ids)?;
+        result.items = item_ids.len();
+    }
+
+    if scope.visible_patients_only {
+        let visible_patient_ids: HashSet<String> = repo
+            .visible_patient_ids(&stores.store_ids())?
+            .into_iter()
+            .collect();
+        let unreferenced_name_ids = repo.unreferenced_name_ids()?;
+        let patient_ids: Vec<String> =
+            intersection(repo.integrated_record_ids("name")?, repo.patient_ids()?)
+                .into_iter()
+                .filter(|id| {
+                    !visible_patient_ids.contains(id) && unreferenced_name_ids.contains(id)
+                })
+                .collect();
+        repo.delete_names(&patient_ids)?;
+        set_out_of_scope(connection, "name", &patient_ids)?;
+        result.patients = patient_ids.len();
+    }
+
+    // Logs received during initialisation are purged with their sync buffer rows, they are not restored
+    let pushed_cursor = pushed_cursor(connection)?;
+    let (temperature_logs, activity_logs) = connection
+        .transaction_sync(|connection| -> Result<_, RepositoryError> {
+            let repo = SyncScopeRepository::new(connection);
+            let sync_buffer_repo = SyncBufferRowRepository::new(connection);
+
+            let temperature_logs = match scope.temperature_log_months.and_then(months_ago) {
+                Some(before) => repo.delete_temperature_logs(before, pushed_cursor)?,
+                None => Vec::new(),
+            };
+            sync_buffer_repo.delete("temperature_log", &temperature_logs)?;
+
+            let activity_logs = match scope.activity_log_months.and_then(months_ago) {
+                Some(before) => repo.delete_activity_logs(before, pushed_cursor)?,
+                None => Vec::new(),
+            };
+            sync_buffer_repo.delete("om_activity_log", &activity_logs)?;
+
+            Ok((temperature_logs.len(), activity_logs.len()))
+        })
+        .map_err(|error| error.to_inner_error())?;
+    result.temperature_logs = temperature_logs;
+    result.activity_logs = activity_logs;
+
+    Ok(result)
+}
+
+/// Logs are pushed to legacy central server, changelogs from this cursor are not pushed yet. Changelogs
+/// deferred while over bandwidth budget are before the push cursor (from the deferred cursor)
+fn pushed_cursor(connection: &StorageConnection) -> Result<i64, RepositoryError> {
+    let push_cursor =
+        CursorController::new(KeyValueType::RemoteSyncPushCursor).get(connection)? as i64;
+    let deferred_cursor = KeyValueStoreRepository::new(connection)
+        .get_i32(KeyValueType::RemoteSyncPushDeferredCursor)?;
+
+    Ok(match deferred_cursor {
+        Some(deferred_cursor) => push_cursor.min(deferred_cursor as i64),
+        None => push_cursor,
+    })
+}
+
+/// Ids in both lists
+fn intersection(integrated_ids: Vec<String>, candidate_ids: Vec<String>) -> Vec<String> {
+    let candidate_ids: HashSet<String> = candidate_ids.into_iter().collect();
+    integrated_ids
+        .into_iter()
+        .filter(|id| candidate_ids.contains(id))
+        .collect()
+}
+
+fn months_ago(months: u32) -> Option<NaiveDateTime> {
+    Utc::now()
+        .naive_utc()
+        .checked_sub_months(Months::new(months))
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::Duration;
+    use repository::{
+        mock::{
+            mock_name_store_a, mock_name_store_b, mock_sensor_1, mock_store_a, MockDataInserts,
+        },
+        test_db::setup_all,
+        ChangeLogInsertRow, ChangelogAction, ChangelogFilter, ChangelogRepository,
+        ChangelogTableName, ItemLinkRow, ItemLinkRowRepository, ItemRow, ItemRowRepository,
+        MasterListLineRow, MasterListLineRowRepository, MasterListNameJoinRepository,
+        MasterListNameJoinRow, MasterListRow, MasterListRowRepository, StockLineRow,
+        StockLineRowRepository, SyncBufferAction, SyncBufferRow, TemperatureLogRow,
+        TemperatureLogRowRepository,
+    };
+    use util::inline_init;
+
+    use super::*;
+
+    fn integrated_buffer_row(table_name: &str, record_id: &str) -> SyncBufferRow {
+        SyncBufferRow {
+            record_id: record_id.to_string(),
+            table_name: table_name.to_string(),
+            action: SyncBufferAction::Upsert,
+            data: format!(r#"{{"ID": "{}"}}"#, record_id),
+            integration_datetime: Some(Utc::now().naive_utc()),
+            ..Default::default()
+        }
+    }
+
+    #[actix_rt::test]
+    async fn test_purge_out_of_scope() {
+        let (_, connection, _, _) = setup_all(
+            "test_purge_out_of_scope",
+            MockDataInserts::none().names().stores().units().sensors(),
+        )
+        .await;
+
+        KeyValueStoreRepository::new(&connection)
+            .set_i32(
+                KeyValueType::SettingsSyncSiteId,
+                Some(mock_store_a().site_id),
+            )
+            .unwrap();
+
+        // Item on master list of store_a, item only on master list of another store and item not on
+        // any master list that is still referenced by a stock line
+        let sync_buffer_repo = SyncBufferRowRepository::new(&connection);
+        for item_id in ["item_in_scope", "item_out_of_scope", "item_referenced"] {
+            ItemRowRepository::new(&connection)
+                .upsert_one(&inline_init(|r: &mut ItemRow| {
+                    r.id = item_id.to_string();
+                    r.name = item_id.to_string();
+                    r.code = item_id.to_string();
+                }))
+                .unwrap();
+            ItemLinkRowRepository::new(&connection)
+                .upsert_one(&ItemLinkRow {
+                    id: item_id.to_string(),
+                    item_id: item_id.to_string(),
+                })
+                .unwrap();
+            sync_buffer_repo
+                .upsert_one(&integrated_buffer_row("item", item_id))
+                .unwrap();
+        }
+        StockLineRowRepository::new(&connection)
+            .upsert_one(&inline_init(|r: &mut StockLineRow| {
+                r.id = "stock_line_referencing_item".to_string();
+                r.store_id = mock_store_a().id;
+                r.item_link_id = "item_referenced".to_string();
+                r.pack_size = 1;
+            }))
+            .unwrap();
+        for master_list_id in ["master_list_store_a", "master_list_other"] {
+            MasterListRowRepository::new(&connection)
+                .upsert_one(&MasterListRow {
+                    id: master_list_id.to_string(),
+                    name: master_list_id.to_string(),
+                    code: master_list_id.to_string(),
+                    description: String::new(),
+                    is_active: true,
+                })
+                .unwrap();
+        }
+        MasterListNameJoinRepository::new(&connection)
+            .upsert_one(&MasterListNameJoinRow {
+                id: "master_list_join".to_string(),
+                master_list_id: "master_list_store_a".to_string(),
+                name_link_id: mock_name_store_a().id,
+            })
+            .unwrap();
+        for (line_id, master_list_id, item_id) in [
+            ("line_in_scope", "master_list_store_a", "item_in_scope"),
+            (
+                "line_out_of_scope",
+                "master_list_other",
+                "item_out_of_scope",
+            ),
+        ] {
+            MasterListLineRowRepository::new(&connection)
+                .upsert_one(&MasterListLineRow {
+                    id: line_id.to_string(),
+                    item_link_id: item_id.to_string(),
+                    master_list_id: master_list_id.to_string(),
+                })
+                .unwrap();
+            sync_buffer_repo
+                .upsert_one(&integrated_buffer_row("list_master_line", line_id))
+                .unwrap();
+        }
+
+        // Old temperature logs, one pushed, one deferred while over bandwidth budget and one not yet pushed
+        let old = Utc::now().naive_utc() - Duration::days(100);
+        for log_id in ["log_pushed", "log_deferred", "log_not_pushed"] {
+            TemperatureLogRowRepository::new(&connection)
+                .upsert_one(&TemperatureLogRow {
+                    id: log_id.to_string(),
+                    sensor_id: mock_sensor_1().id,
+                    store_id: mock_store_a().id,
+                    datetime: old,
+                    ..Default::default()
+                })
+                .unwrap();
+        }
+        let changelog = |record_id: &str| {
+            ChangelogRepository::new(&connection)
+                .insert(&ChangeLogInsertRow {
+                    table_name: ChangelogTableName::TemperatureLog,
+                    record_id: record_id.to_string(),
+                    row_action: ChangelogAction::Upsert,
+                    name_link_id: None,
+                    store_id: None,
+                })
+                .unwrap()
+        };
+        sync_buffer_repo
+            .upsert_one(&integrated_buffer_row("temperature_log", "log_pushed"))
+            .unwrap();
+        let deferred_cursor = changelog("log_deferred");
+        let cursor = changelog("log_not_pushed");
+        CursorController::new(KeyValueType::RemoteSyncPushCursor)
+            .update(&connection, cursor as u64)
+            .unwrap();
+        KeyValueStoreRepository::new(&connection)
+            .set_i32(
+                KeyValueType::RemoteSyncPushDeferredCursor,
+                Some(deferred_cursor as i32),
+            )
+            .unwrap();
+
+        // Nothing is purged without scope
+        assert_eq!(
+            purge_out_of_scope(&connection).unwrap(),
+            PurgeOutOfScopeResult::default()
+        );
+
+        set_sync_scope(
+            &connection,
+            SyncScope {
+                master_list_items_only: true,
+                temperature_log_months: Some(2),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            purge_out_of_scope(&connection).unwrap(),
+            PurgeOutOfScopeResult {
+                master_list_lines: 1,
+                items: 1,
+                temperature_logs: 1,
+                ..Default::default()
+            }
+        );
+        let items = ItemRowRepository::new(&connection)
+            .find_many_by_id(&[
+                "item_in_scope".to_string(),
+                "item_out_of_scope".to_string(),
+                "item_referenced".to_string(),
+            ])
+            .unwrap();
+        let mut item_ids: Vec<String> = items.into_iter().map(|r| r.id).collect();
+        item_ids.sort();
+        assert_eq!(
+            item_ids,
+            vec!["item_in_scope".to_string(), "item_referenced".to_string()]
+        );
+        let log_repo = TemperatureLogRowRepository::new(&connection);
+        assert!(log_repo.find_one_by_id("log_pushed").unwrap().is_none());
+        assert!(log_repo.find_one_by_id("log_deferred").unwrap().is_some());
+        assert!(log_repo.find_one_by_id("log_not_pushed").unwrap().is_some());
+        // Purging is not pushed
+        assert_eq!(
+            ChangelogRepository::new(&connection)
+                .changelogs(
+                    0,
+                    1000,
+                    Some(
+                        ChangelogFilter::new()
+                            .action(ChangelogAction::Delete.equal_to())
+                            .table_name(ChangelogTableName::TemperatureLog.equal_to())
+                    )
+                )
+                .unwrap(),
+            vec![]
+        );
+
+        let purged_item = sync_buffer_repo
+            .find_one_by_record_id("item_out_of_scope")
+            .unwrap()
+            .unwrap();
+        assert!(purged_item.is_out_of_scope);
+        assert!(purged_item.data.starts_with(COMPRESSED_PAYLOAD_PREFIX));
+        // Sync buffer rows of purged logs are removed
+        assert_eq!(
+            sync_buffer_repo
+                .find_one_by_record_id("log_pushed")
+                .unwrap(),
+            None
+        );
+
+        // Changing scope restores purged records for integration
+        set_sync_scope(&connection, SyncScope::default()).unwrap();
+        let restored_item = sync_buffer_repo
+            .find_one_by_record_id("item_out_of_scope")
+            .unwrap()
+            .unwrap();
+        assert!(!restored_item.is_out_of_scope);
+        assert_eq!(restored_item.integration_datetime, None);
+        assert_eq!(restored_item.data, r#"{"ID": "item_out_of_scope"}"#);
+    }
+
+    #[actix_rt::test]
+    async fn test_restore_if_scope_changed() {
+        let (_, connection, _, _) = setup_all(
+            "test_restore_if_scope_changed",
+            MockDataInserts::none().names().stores(),
+        )
+        .await;
+
+        KeyValueStoreRepository::new(&connection)
+            .set_i32(
+                KeyValueType::SettingsSyncSiteId,
+                Some(mock_store_a().site_id),
+            )
+            .unwrap();
+        set_sync_scope(
+            &connection,
+            SyncScope {
+                master_list_items_only: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let sync_buffer_repo = SyncBufferRowRepository::new(&connection);
+        let purged = SyncBufferRow {
+            is_out_of_scope: true,
+            ..integrated_buffer_row("item", "purged_item")
+        };
+        sync_buffer_repo.upsert_one(&purged).unwrap();
+        let received = |record_id: &str, table_name: &str, data: String| SyncBufferRow {
+            record_id: record_id.to_string(),
+            table_name: table_name.to_string(),
+            action: SyncBufferAction::Upsert,
+            data,
+            ..Default::default()
+        };
+        let is_purged = || {
+            sync_buffer_repo
+                .find_one_by_record_id("purged_item")
+                .unwrap()
+                .unwrap()
+                .is_out_of_scope
+        };
+
+        // Master list joined to store of another site and name store join (not in scope) don't restore
+        sync_buffer_repo
+            .upsert_one(&received(
+                "join_other_site",
+                "list_master_name_join",
+                format!(
+                    r#"{{"ID": "join_other_site", "name_ID": "{}", "list_master_ID": "list"}}"#,
+                    mock_name_store_b().id
+                ),
+            ))
+            .unwrap();
+        sync_buffer_repo
+            .upsert_one(&received(
+                "name_store_join",
+                "name_store_join",
+                format!(
+                    r#"{{"ID": "name_store_join", "name_ID": "name", "store_ID": "{}"}}"#,
+                    mock_store_a().id
+                ),
+            ))
+            .unwrap();
+        restore_if_scope_changed(&connection).unwrap();
+        assert!(is_purged());
+
+        // Master list joined to store on this site
+        sync_buffer_repo
+            .upsert_one(&received(
+                "join_this_site",
+                "list_master_name_join",
+                format!(
+                    r#"{{"ID": "join_this_site", "name_ID": "{}", "list_master_ID": "list"}}"#,
+                    mock_name_store_a().id
+                ),
+            ))
+            .unwrap();
+        restore_if_scope_changed(&connection).unwrap();
+        assert!(!is_purged());
+    }
+}
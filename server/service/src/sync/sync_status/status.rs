@@ -1,7 +1,7 @@
 use chrono::{NaiveDateTime, Utc};
 use repository::{
     ChangelogRepository, DatetimeFilter, EqualFilter, KeyValueType, Pagination, RepositoryError,
-    Sort, SyncLogFilter, SyncLogRepository, SyncLogRow, SyncLogSortField,
+    Sort, SyncBufferRepository, SyncLogFilter, SyncLogRepository, SyncLogRow, SyncLogSortField,
 };
 use util::Defaults;
 
@@ -10,7 +10,13 @@ use crate::{
     i32_to_u32,
     service_provider::ServiceContext,
     settings_service::{SettingsService, SettingsServiceTrait},
-    sync::{get_sync_push_changelogs_filter, GetActiveStoresOnSiteError},
+    sync::{
+        central_data_synchroniser_v6::{get_initialisation_summary, initialisation_progress},
+        get_sync_push_changelogs_filter,
+        sync_buffer_errors::is_failed_count_growing,
+        translations::ToSyncRecordTranslationType,
+        GetActiveStoresOnSiteError,
+    },
 };
 
 use super::SyncLogError;
@@ -132,6 +138,15 @@ impl FullSyncStatus {
     }
 }
 
+/// Records received during initialisation for a table, out of expected total when it's known
+/// (expected counts are only reported by omSupply central server)
+#[derive(Debug, Clone, PartialEq)]
+pub struct InitialisationTableProgress {
+    pub table_name: String,
+    pub received: u64,
+    pub expected: Option<u64>,
+}
+
 #[derive(Eq, PartialEq, Hash, Copy, Clone, Debug)]
 pub enum SyncStatusType {
     Initial,
@@ -190,6 +205,21 @@ pub trait SyncStatusTrait: Sync + Send {
     ) -> Result<Option<FullSyncStatus>, RepositoryError> {
         get_latest_successful_sync_status(ctx)
     }
+
+    fn get_initialisation_progress(
+        &self,
+        ctx: &ServiceContext,
+    ) -> Result<Vec<InitialisationTableProgress>, RepositoryError> {
+        get_initialisation_progress(ctx)
+    }
+
+    /// Number of sync records failing integration increased in each of the last few syncs
+    fn is_integration_error_count_growing(
+        &self,
+        ctx: &ServiceContext,
+    ) -> Result<bool, RepositoryError> {
+        is_failed_count_growing(&ctx.connection)
+    }
 }
 
 pub(crate) struct SyncStatusService;
@@ -286,6 +316,42 @@ fn get_latest_successful_sync_status(
     Ok(result)
 }
 
+fn get_initialisation_progress(
+    ctx: &ServiceContext,
+) -> Result<Vec<InitialisationTableProgress>, RepositoryError> {
+    let summary = get_initialisation_summary(&ctx.connection)?.unwrap_or_default();
+
+    let mut result: Vec<InitialisationTableProgress> =
+        initialisation_progress(&ctx.connection, &summary)?
+            .into_iter()
+            .map(
+                |(table_name, received, expected)| InitialisationTableProgress {
+                    table_name,
+                    received,
+                    expected: Some(expected),
+                },
+            )
+            .collect();
+
+    // Tables without expected count (e.g. records from legacy central server)
+    let received_by_table = SyncBufferRepository::new(&ctx.connection).count_by_table_name()?;
+    for (table_name, received) in received_by_table {
+        let has_expected = result
+            .iter()
+            .any(|progress| progress.table_name == table_name);
+        if !has_expected {
+            result.push(InitialisationTableProgress {
+                table_name,
+                received,
+                expected: None,
+            });
+        }
+    }
+    result.sort_by(|a, b| a.table_name.cmp(&b.table_name));
+
+    Ok(result)
+}
+
 #[derive(Debug)]
 pub enum NumberOfRecordsInPushQueueError {
     DatabaseError(RepositoryError),
@@ -302,11 +368,14 @@ fn number_of_records_in_push_queue(
         .get(&ctx.connection)
         .map_err(Error::DatabaseError)?;
 
-    let changelog_filter =
-        get_sync_push_changelogs_filter(&ctx.connection).map_err(|error| match error {
-            GetActiveStoresOnSiteError::DatabaseError(error) => Error::DatabaseError(error),
-            GetActiveStoresOnSiteError::SiteIdNotSet => Error::SiteIdNotSet,
-        })?;
+    let changelog_filter = get_sync_push_changelogs_filter(
+        &ctx.connection,
+        &ToSyncRecordTranslationType::PushToLegacyCentral,
+    )
+    .map_err(|error| match error {
+        GetActiveStoresOnSiteError::DatabaseError(error) => Error::DatabaseError(error),
+        GetActiveStoresOnSiteError::SiteIdNotSet => Error::SiteIdNotSet,
+    })?;
 
     let change_logs_total = changelog_repo
         .count(cursor, changelog_filter)
@@ -346,13 +415,19 @@ impl SyncLogError {
 #[cfg(test)]
 mod test {
     use crate::{
-        sync::{settings::SyncSettings, sync_status::status::InitialisationStatus},
+        sync::{
+            api_v6::SyncTableCountV6,
+            central_data_synchroniser_v6::InitialisationSummary,
+            settings::SyncSettings,
+            sync_status::status::{InitialisationStatus, InitialisationTableProgress},
+        },
         test_helpers::{setup_all_and_service_provider, ServiceTestContext},
     };
     use chrono::Utc;
     use repository::{
         mock::{insert_extra_mock_data, MockData, MockDataInserts},
-        SyncLogRow, SyncLogRowRepository,
+        KeyValueStoreRepository, KeyValueType, SyncBufferRow, SyncBufferRowRepository, SyncLogRow,
+        SyncLogRowRepository,
     };
     use util::{assert_matches, inline_init};
 
@@ -471,4 +546,68 @@ mod test {
             Ok(true)
         );
     }
+
+    #[actix_rt::test]
+    async fn initialisation_progress() {
+        let ServiceTestContext {
+            connection,
+            service_provider,
+            service_context,
+            ..
+        } = setup_all_and_service_provider("initialisation_progress", MockDataInserts::none())
+            .await;
+
+        let buffer_repo = SyncBufferRowRepository::new(&connection);
+        for (record_id, table_name) in [("1", "asset"), ("2", "asset"), ("3", "item")] {
+            buffer_repo
+                .upsert_one(&inline_init(|r: &mut SyncBufferRow| {
+                    r.record_id = record_id.to_string();
+                    r.table_name = table_name.to_string();
+                }))
+                .unwrap();
+        }
+
+        let summary = InitialisationSummary {
+            tables: vec![
+                SyncTableCountV6 {
+                    table_name: "asset".to_string(),
+                    count: 3,
+                },
+                SyncTableCountV6 {
+                    table_name: "asset_log".to_string(),
+                    count: 1,
+                },
+            ],
+            is_repull_requested: false,
+        };
+        KeyValueStoreRepository::new(&connection)
+            .set_string(
+                KeyValueType::SyncInitialisationExpectedCounts,
+                Some(serde_json::to_string(&summary).unwrap()),
+            )
+            .unwrap();
+
+        assert_eq!(
+            service_provider
+                .sync_status_service
+                .get_initialisation_progress(&service_context),
+            Ok(vec![
+                InitialisationTableProgress {
+                    table_name: "asset".to_string(),
+                    received: 2,
+                    expected: Some(3),
+                },
+                InitialisationTableProgress {
+                    table_name: "asset_log".to_string(),
+                    received: 0,
+                    expected: Some(1),
+                },
+                InitialisationTableProgress {
+                    table_name: "item".to_string(),
+                    received: 1,
+                    expected: None,
+                },
+            ])
+        );
+    }
 }
@@ -20,7 +20,10 @@ use crate::{
             CentralSyncBatchV5, CentralSyncRecordV5, CommonSyncRecord, RemotePushResponseV5,
             RemoteSyncBatchV5, RemoteSyncRecordV5, SiteStatusCodeV5, SiteStatusV5,
         },
-        api_v6::{SyncBatchV6, SyncPullResponseV6, SyncPushResponseV6, SyncPushSuccessV6},
+        api_v6::{
+            SyncBatchV6, SyncInitialisationSummaryResponseV6, SyncInitialisationSummaryV6,
+            SyncPullResponseV6, SyncPushResponseV6, SyncPushSuccessV6,
+        },
         settings::{BatchSize, SyncSettings},
         sync_status::{status::InitialisationStatus, SyncLogError},
         synchroniser::{SyncError, Synchroniser},
@@ -562,6 +565,7 @@ async fn empty_v6_server(port: u16) -> Server {
             end_cursor: 0,
             total_records: 0,
             records: Vec::new(),
+            checksum: None,
         }))
     }
     async fn empty_push_response() -> impl Responder {
@@ -569,10 +573,19 @@ async fn empty_v6_server(port: u16) -> Server {
             records_pushed: 0,
         }))
     }
+    async fn empty_initialisation_summary_response() -> impl Responder {
+        web::Json(SyncInitialisationSummaryResponseV6::Data(
+            SyncInitialisationSummaryV6::default(),
+        ))
+    }
     HttpServer::new(move || {
         App::new()
             .route("/central/sync/pull", web::to(empty_pull_response))
             .route("/central/sync/push", web::to(empty_push_response))
+            .route(
+                "/central/sync/initialisation_summary",
+                web::to(empty_initialisation_summary_response),
+            )
     })
     .bind(("127.0.0.1", port + crate::sync::api_v6::PORT_OFFSET))
     .unwrap()
@@ -1,5 +1,6 @@
 use crate::{
     service_provider::{ServiceContext, ServiceProvider},
+    static_files::StaticFileService,
     sync::sync_status::logger::SyncStep,
 };
 use log::warn;
@@ -13,6 +14,7 @@ use util::{format_error, is_central_server};
 use super::{
     api::SyncApiV5,
     api_v6::SyncApiV6,
+    bandwidth::{add_daily_usage, is_over_budget, BandwidthMeter},
     central_data_synchroniser::{CentralDataSynchroniser, CentralPullError},
     central_data_synchroniser_v6::{
         CentralDataSynchroniserV6, CentralPullErrorV6, RemotePushErrorV6,
@@ -21,8 +23,12 @@ use super::{
         PostInitialisationError, RemoteDataSynchroniser, RemotePullError, RemotePushError,
         WaitForSyncOperationError,
     },
-    settings::{SyncSettings, SYNC_VERSION},
+    settings::{SyncBandwidthSettings, SyncSettings, SYNC_VERSION},
     sync_buffer::SyncBuffer,
+    sync_buffer_errors::update_failed_count_history,
+    sync_file_transfer::SyncFileTransfer,
+    sync_health::{generate_health_report, ReportSyncHealthError},
+    sync_scope::{purge_out_of_scope, restore_for_failed_records, restore_if_scope_changed},
     sync_status::logger::{SyncLogger, SyncLoggerError},
     translation_and_integration::{TranslationAndIntegration, TranslationAndIntegrationResults},
     translations::{all_translators, pull_integration_order},
@@ -37,6 +43,9 @@ pub struct Synchroniser {
     central: CentralDataSynchroniser,
     central_v6: CentralDataSynchroniserV6,
     remote: RemoteDataSynchroniser,
+    bandwidth_meter: BandwidthMeter,
+    bandwidth_settings: Option<SyncBandwidthSettings>,
+    file_transfer: Option<SyncFileTransfer>,
 }
 
 #[derive(Error)]
@@ -61,6 +70,8 @@ pub(crate) enum SyncError {
     RemotePullError(#[from] RemotePullError),
     #[error("Error while integrating records")]
     IntegrationError(anyhow::Error),
+    #[error("Initialisation is incomplete, records are missing for: {}", .0.join(", "))]
+    InitialisationIncomplete(Vec<String>),
 }
 
 // For unwrap and expect debug implementation is used
@@ -110,8 +121,11 @@ impl Synchroniser {
         sync_version: u32,
     ) -> anyhow::Result<Self> {
         let sync_v5_settings = SyncApiV5::new_settings(&settings, &service_provider, sync_version)?;
-        let sync_api_v5 = SyncApiV5::new(sync_v5_settings.clone())?;
-        let sync_api_v6 = SyncApiV6::new(sync_v5_settings)?;
+        let bandwidth_meter = BandwidthMeter::default();
+        let sync_api_v5 =
+            SyncApiV5::new(sync_v5_settings.clone())?.with_bandwidth_meter(bandwidth_meter.clone());
+        let sync_api_v6 =
+            SyncApiV6::new(sync_v5_settings)?.with_bandwidth_meter(bandwidth_meter.clone());
         Ok(Synchroniser {
             remote: RemoteDataSynchroniser {
                 sync_api_v5: sync_api_v5.clone(),
@@ -120,6 +134,36 @@ impl Synchroniser {
             service_provider,
             central: CentralDataSynchroniser { sync_api_v5 },
             central_v6: CentralDataSynchroniserV6 { sync_api_v6 },
+            bandwidth_meter,
+            bandwidth_settings: None,
+            file_transfer: None,
+        })
+    }
+
+    /// When daily budget is exceeded, non urgent records are not pushed or pulled (see DEFERRABLE_TABLES)
+    pub(crate) fn with_bandwidth_settings(
+        self,
+        bandwidth_settings: Option<SyncBandwidthSettings>,
+    ) -> Self {
+        Self {
+            bandwidth_settings,
+            ..self
+        }
+    }
+
+    /// Sync files (see SyncFileReferenceRow) are transferred after every successful sync,
+    /// files are stored in static files directory of `files_base_dir`
+    pub(crate) fn with_file_transfer(
+        self,
+        files_base_dir: &Option<String>,
+    ) -> anyhow::Result<Self> {
+        let file_transfer = SyncFileTransfer {
+            sync_api_v6: self.central_v6.sync_api_v6.clone(),
+            static_file_service: StaticFileService::new(files_base_dir)?,
+        };
+        Ok(Self {
+            file_transfer: Some(file_transfer),
+            ..self
         })
     }
 
@@ -129,15 +173,72 @@ impl Synchroniser {
 
         let sync_result = self.sync_inner(&mut logger, &ctx).await;
 
-        if let Err(error) = &sync_result {
-            logger.error(error)?;
+        // Bandwidth is used even if sync failed
+        if let Err(error) = add_daily_usage(&ctx.connection, self.bandwidth_meter.take()) {
+            log::error!(
+                "Problem recording sync bandwidth usage {}",
+                format_error(&error)
+            );
+        }
+
+        match &sync_result {
+            Ok(_) => logger.done()?,
+            Err(error) => logger.error(error)?,
         };
 
+        // Failed syncs are reported too, so that central server can list failing sites
+        if let Err(error) = self.report_health(&ctx).await {
+            log::info!("Problem reporting sync health {}", format_error(&error));
+        }
+
         sync_result?;
-        logger.done()?;
+
+        // After records are synced, so that large files don't hold up sync of records
+        if let Err(error) = self.transfer_files(&ctx).await {
+            log::error!("Problem transferring sync files {}", format_error(&error));
+        }
+        Ok(())
+    }
+
+    async fn report_health(&self, ctx: &ServiceContext) -> Result<(), ReportSyncHealthError> {
+        if is_central_server() || self.service_provider.settings.is_sync_disabled(ctx)? {
+            return Ok(());
+        }
+
+        let report = generate_health_report(&self.service_provider, ctx)?;
+        self.central_v6.sync_api_v6.report_health(report).await?;
+
+        add_daily_usage(&ctx.connection, self.bandwidth_meter.take())?;
         Ok(())
     }
 
+    async fn transfer_files(&self, ctx: &ServiceContext) -> Result<(), RepositoryError> {
+        let Some(file_transfer) = &self.file_transfer else {
+            return Ok(());
+        };
+        if is_central_server()
+            || self.service_provider.settings.is_sync_disabled(ctx)?
+            || !self
+                .service_provider
+                .sync_status_service
+                .is_initialised(ctx)?
+        {
+            return Ok(());
+        }
+
+        // Budget includes bandwidth used by records in this sync
+        let is_over_budget = is_over_budget(&ctx.connection, &self.bandwidth_settings)?;
+        if is_over_budget {
+            log::info!("Daily sync bandwidth budget exceeded, deferring large sync files");
+        }
+        let result = file_transfer
+            .transfer_files(&ctx.connection, is_over_budget)
+            .await;
+
+        add_daily_usage(&ctx.connection, self.bandwidth_meter.take())?;
+        result
+    }
+
     /// Sync must not be called concurrently (e.g. sync cursors are fetched/updated without DB tx)
     async fn sync_inner<'a>(
         &self,
@@ -159,6 +260,12 @@ impl Synchroniser {
         // Initialisation request was sent and successfully processed
         let is_sync_queue_initialised = sync_status_service.is_sync_queue_initialised(ctx)?;
 
+        // Checked once per sync, usage from this sync is added at the end of sync
+        let is_over_budget = is_over_budget(&ctx.connection, &self.bandwidth_settings)?;
+        if is_over_budget {
+            log::info!("Daily sync bandwidth budget exceeded, deferring non urgent records");
+        }
+
         // REQUEST INITIALISATION
         logger.start_step(SyncStep::PrepareInitial)?;
         if !is_sync_queue_initialised {
@@ -176,7 +283,12 @@ impl Synchroniser {
         if is_initialised && !is_central_server() {
             let result = self
                 .central_v6
-                .push(&ctx.connection, batch_size.remote_push, logger)
+                .push(
+                    &ctx.connection,
+                    batch_size.remote_push,
+                    is_over_budget,
+                    logger,
+                )
                 .await;
 
             if let Err(error) = result {
@@ -193,7 +305,12 @@ impl Synchroniser {
         logger.start_step(SyncStep::Push)?;
         if is_initialised {
             self.remote
-                .push(&ctx.connection, batch_size.remote_push, logger)
+                .push(
+                    &ctx.connection,
+                    batch_size.remote_push,
+                    is_over_budget,
+                    logger,
+                )
                 .await?;
             self.remote
                 .wait_for_sync_operation(
@@ -220,17 +337,44 @@ impl Synchroniser {
         logger.done_step(SyncStep::PullRemote)?;
 
         // PULL V6
-        if !is_central_server() {
-            logger.start_step(SyncStep::PullCentralV6)?;
-            if let Err(error) = self
+        // During initialisation expected record counts are requested from omSupply central server,
+        // if it's available v6 pull errors are not ignored, so that initialisation resumes on next sync
+        let is_verifying_initialisation = !is_initialised
+            && !is_central_server()
+            && match self
                 .central_v6
-                .pull(&ctx.connection, 20, is_initialised, logger)
+                .update_initialisation_summary(&ctx.connection)
                 .await
             {
-                // Log but ignore error for now, to allow omSupply to run without omSupply server
-                // TODO : Fix at some point!
-                log::info!("{}", format_error(&error));
-                let _ = logger.error(&error.into());
+                Ok(_) => true,
+                Err(error) => {
+                    log::info!("{}", format_error(&error));
+                    false
+                }
+            };
+
+        if !is_central_server() {
+            logger.start_step(SyncStep::PullCentralV6)?;
+            // Nothing is deferred during initialisation
+            let result = self
+                .central_v6
+                .pull(
+                    &ctx.connection,
+                    20,
+                    is_initialised,
+                    is_over_budget && is_initialised,
+                    logger,
+                )
+                .await;
+            match result {
+                Err(error) if is_verifying_initialisation => return Err(error.into()),
+                Err(error) => {
+                    // Log but ignore error for now, to allow omSupply to run without omSupply server
+                    // TODO : Fix at some point!
+                    log::info!("{}", format_error(&error));
+                    let _ = logger.error(&error.into());
+                }
+                Ok(_) => {}
             }
             logger.done_step(SyncStep::PullCentralV6)?;
         }
@@ -238,6 +382,9 @@ impl Synchroniser {
         // INTEGRATE RECORDS
         logger.start_step(SyncStep::Integrate)?;
 
+        restore_if_scope_changed(&ctx.connection)?;
+        let integration_start = chrono::Utc::now().naive_utc();
+
         let (upserts, deletes, merges) =
             integrate_and_translate_sync_buffer(&ctx.connection, is_initialised, logger)
                 .await
@@ -246,8 +393,47 @@ impl Synchroniser {
         warn!("Delete Integration result: {:?}", deletes);
         warn!("Merge Integration result: {:?}", merges);
 
+        // Failed records may reference records purged as out of sync scope, integrate again with them
+        if restore_for_failed_records(&ctx.connection, integration_start)? {
+            let (upserts, deletes, merges) =
+                integrate_and_translate_sync_buffer(&ctx.connection, is_initialised, logger)
+                    .await
+                    .map_err(SyncError::IntegrationError)?;
+            warn!("Upsert Integration result (restored scope): {:?}", upserts);
+            warn!("Delete Integration result (restored scope): {:?}", deletes);
+            warn!("Merge Integration result (restored scope): {:?}", merges);
+        }
+
+        // Also during initialisation, so that out of scope records don't accumulate until site is initialised
+        if !is_central_server() {
+            match purge_out_of_scope(&ctx.connection) {
+                Ok(result) => log::info!("Purged out of sync scope records: {:?}", result),
+                Err(error) => log::error!(
+                    "Problem purging out of sync scope records {}",
+                    format_error(&error)
+                ),
+            }
+        }
+
         logger.done_step(SyncStep::Integrate)?;
 
+        if update_failed_count_history(&ctx.connection)? {
+            warn!(
+                "Number of sync records failing integration keeps growing, see sync buffer errors"
+            );
+        }
+
+        // Final check that all records were received, before site is considered initialised
+        if is_verifying_initialisation {
+            let incomplete_tables = self
+                .central_v6
+                .verify_initialisation(&ctx.connection)
+                .await?;
+            if !incomplete_tables.is_empty() {
+                return Err(SyncError::InitialisationIncomplete(incomplete_tables));
+            }
+        }
+
         if !is_initialised {
             self.remote.advance_push_cursor(&ctx.connection)?;
             self.service_provider.site_is_initialised_trigger.trigger();
@@ -271,6 +457,20 @@ pub async fn integrate_and_translate_sync_buffer<'a>(
     TranslationAndIntegrationResults,
     TranslationAndIntegrationResults,
     TranslationAndIntegrationResults,
+)> {
+    integrate_sync_buffer(connection, is_initialised, Some(logger))
+}
+
+/// Same as integrate_and_translate_sync_buffer, but logger is optional (it's only used during initialisation),
+/// used by peer sync to integrate records without adding an entry to sync log
+pub(crate) fn integrate_sync_buffer(
+    connection: &StorageConnection,
+    is_initialised: bool,
+    logger: Option<&mut SyncLogger>,
+) -> anyhow::Result<(
+    TranslationAndIntegrationResults,
+    TranslationAndIntegrationResults,
+    TranslationAndIntegrationResults,
 )> {
     // Integration is done inside a transaction, to make sure all records are available at the same time
     // and maintain logical data integrity. During initialisation nested transactions cause significant
@@ -293,7 +493,8 @@ pub async fn integrate_and_translate_sync_buffer<'a>(
         let table_order = pull_integration_order(&translators);
 
         let sync_buffer = SyncBuffer::new(connection);
-        let translation_and_integration = TranslationAndIntegration::new(connection, &sync_buffer);
+        let translation_and_integration =
+            TranslationAndIntegration::new(connection, &sync_buffer, is_initialised);
         // Translate and integrate upserts (ordered by referential database constraints)
         let upsert_sync_buffer_records =
             sync_buffer.get_ordered_sync_buffer_records(SyncBufferAction::Upsert, &table_order)?;
@@ -306,7 +507,7 @@ pub async fn integrate_and_translate_sync_buffer<'a>(
                 upsert_sync_buffer_records.clone(),
                 &translators,
                 // Only pass Some(logger) during initalisation
-                is_initialised.not().then(|| logger),
+                logger.filter(|_| is_initialised.not()),
             )?;
 
         // pass the logger here
@@ -1,8 +1,13 @@
 use std::{future::Future, sync::Arc};
 
 use crate::service_provider::ServiceProvider;
+use util::format_error;
 
-use super::{settings::SyncSettings, synchroniser::Synchroniser};
+use super::{
+    peer_sync::PeerSynchroniser,
+    settings::{PeerSyncSettings, SyncBandwidthSettings, SyncSettings},
+    synchroniser::Synchroniser,
+};
 use tokio::{
     sync::mpsc::{self, Receiver, Sender},
     time::Duration,
@@ -20,6 +25,7 @@ pub struct SyncTrigger {
 /// Used to 'drive' synchronisation, it's tasks:
 /// * Expose channel for manually triggering sync
 /// * Trigger sync every SyncSettings.interval_seconds (only when initialised)
+/// * Sync with peers on local network after every sync (when PeerSyncSettings are configured)
 impl SynchroniserDriver {
     pub fn init() -> (SyncTrigger, SynchroniserDriver) {
         // We use a single-element channel so that we can only have one sync pending at a time.
@@ -35,6 +41,10 @@ impl SynchroniserDriver {
     /// should fail only when database is not accessible or when all receivers were dropped
     ///
     /// * `force_run` - should we trigger sync straight away regardless of initialisation stage ?
+    /// * `peer_sync_settings` - when set, transfer records are also pulled from peers after every sync
+    /// * `bandwidth_settings` - when set, non urgent records are not pushed once daily budget is exceeded
+    /// * `files_base_dir` - base directory of static files (server settings `base_dir`), sync files
+    ///    are uploaded and downloaded after every sync
     ///
     /// Operations:
     /// * Try to sync if already initialise or if `force_run`
@@ -42,9 +52,22 @@ impl SynchroniserDriver {
     ///    * If initialised await for manual trigger OR interval sec timeout
     ///    * If not initialised await only for manual trigger
     ///    * do sync if any of the above were triggered
-    pub async fn run(mut self, service_provider: Arc<ServiceProvider>, force_run: bool) {
+    pub async fn run(
+        mut self,
+        service_provider: Arc<ServiceProvider>,
+        force_run: bool,
+        peer_sync_settings: Option<PeerSyncSettings>,
+        bandwidth_settings: Option<SyncBandwidthSettings>,
+        files_base_dir: Option<String>,
+    ) {
         if force_run || is_initialised(&service_provider) {
-            self.sync(service_provider.clone()).await;
+            self.sync_with_settings(
+                service_provider.clone(),
+                &bandwidth_settings,
+                Some(&files_base_dir),
+            )
+            .await;
+            self.peer_sync(&service_provider, &peer_sync_settings).await;
         }
 
         loop {
@@ -69,17 +92,61 @@ impl SynchroniserDriver {
                 }
             }
 
-            self.sync(service_provider.clone()).await;
+            self.sync_with_settings(
+                service_provider.clone(),
+                &bandwidth_settings,
+                Some(&files_base_dir),
+            )
+            .await;
+            self.peer_sync(&service_provider, &peer_sync_settings).await;
         }
     }
 
     pub async fn sync(&self, service_provider: Arc<ServiceProvider>) {
+        self.sync_with_settings(service_provider, &None, None).await;
+    }
+
+    async fn sync_with_settings(
+        &self,
+        service_provider: Arc<ServiceProvider>,
+        bandwidth_settings: &Option<SyncBandwidthSettings>,
+        // When None sync files are not transferred
+        files_base_dir: Option<&Option<String>>,
+    ) {
         // Error is already logged, keeping result with `_` to avoid compilation warning
         // We initialise new instance of Syncrhoniser since SyncSettings could have changed
-        let _ = Synchroniser::new(get_sync_settings(&service_provider), service_provider)
-            .unwrap()
-            .sync()
-            .await;
+        let synchroniser =
+            Synchroniser::new(get_sync_settings(&service_provider), service_provider)
+                .unwrap()
+                .with_bandwidth_settings(bandwidth_settings.clone());
+
+        let synchroniser = match files_base_dir {
+            Some(files_base_dir) => match synchroniser.with_file_transfer(files_base_dir) {
+                Ok(synchroniser) => synchroniser,
+                Err(error) => {
+                    log::error!("Problem preparing sync file transfer {:#}", error);
+                    return;
+                }
+            },
+            None => synchroniser,
+        };
+
+        let _ = synchroniser.sync().await;
+    }
+
+    /// Peer sync is done in the same task as sync, so that sync buffer is never integrated concurrently
+    async fn peer_sync(
+        &self,
+        service_provider: &ServiceProvider,
+        peer_sync_settings: &Option<PeerSyncSettings>,
+    ) {
+        let Some(settings) = peer_sync_settings else {
+            return;
+        };
+
+        if let Err(error) = (PeerSynchroniser { settings }).sync(service_provider).await {
+            log::error!("Problem syncing with peers {}", format_error(&error));
+        }
     }
 }
 
@@ -150,6 +150,8 @@ impl SyncRecordTester for ProgramRequisitionTester {
             threshold_mos: 3.0,
             max_mos: 3.0,
             max_order_per_period: 1,
+            is_emergency: false,
+            max_items_in_emergency_order: 0,
         };
 
         let order_type2 = ProgramRequisitionOrderTypeRow {
@@ -159,6 +161,8 @@ impl SyncRecordTester for ProgramRequisitionTester {
             threshold_mos: 3.0,
             max_mos: 3.0,
             max_order_per_period: 1,
+            is_emergency: false,
+            max_items_in_emergency_order: 0,
         };
 
         let order_type3 = ProgramRequisitionOrderTypeRow {
@@ -168,6 +172,8 @@ impl SyncRecordTester for ProgramRequisitionTester {
             threshold_mos: 4.0,
             max_mos: 4.0,
             max_order_per_period: 1,
+            is_emergency: false,
+            max_items_in_emergency_order: 0,
         };
 
         let master_list_row2 = MasterListRow {
@@ -296,6 +302,8 @@ impl SyncRecordTester for ProgramRequisitionTester {
             threshold_mos: 3.0,
             max_mos: 6.0,
             max_order_per_period: 1,
+            is_emergency: false,
+            max_items_in_emergency_order: 0,
         };
 
         result.push(TestStepData {
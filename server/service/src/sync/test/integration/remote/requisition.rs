@@ -44,6 +44,8 @@ impl SyncRecordTester for RequisitionRecordTester {
             program_id: None,
             period_id: None,
             order_type: None,
+            is_emergency: false,
+            emergency_reason: None,
         };
         let requisition_row_1 = base_requisition_row.clone();
         let requisition_line_row_1 = RequisitionLineRow {
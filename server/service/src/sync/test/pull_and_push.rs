@@ -96,9 +96,14 @@ async fn test_sync_pull_and_push() {
             ToSyncRecordTranslationType::PushToLegacyCentral,
         )
         .unwrap(),
+        // Remote records that can be switched to v6 are translated the same way as for legacy push
         translate_changelogs_to_sync_records(
             &connection,
-            changelogs.clone(),
+            changelogs
+                .iter()
+                .filter(|changelog| !changelog.table_name.can_sync_through_v6())
+                .cloned()
+                .collect(),
             ToSyncRecordTranslationType::PullFromOmSupplyCentral,
         )
         .unwrap(),
@@ -34,6 +34,8 @@ fn asset1() -> AssetRow {
         created_datetime: Defaults::naive_date_time(),
         modified_datetime: Defaults::naive_date_time(),
         deleted_datetime: None,
+        decommissioned_date: None,
+        decommission_reason: None,
     }
 }
 
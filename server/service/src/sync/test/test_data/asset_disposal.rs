@@ -0,0 +1,50 @@
+use repository::assets::asset_disposal_row::{AssetDisposalMethod, AssetDisposalRow};
+use serde_json::json;
+use util::Defaults;
+
+use super::{TestSyncIncomingRecord, TestSyncOutgoingRecord};
+
+const TABLE_NAME: &'static str = "asset_disposal";
+
+const ASSET_DISPOSAL1: (&'static str, &'static str) = (
+    "b2f3c0d9-1e5a-4a37-9a44-2f0c6e8d7b15",
+    r#"{
+        "id": "b2f3c0d9-1e5a-4a37-9a44-2f0c6e8d7b15",
+        "asset_id": "3de161ed-93ef-4210-aa31-3ae9e53748e8",
+        "store_id": "store_a",
+        "disposal_date": "2020-01-22",
+        "method": "RECYCLED",
+        "comment": "test_comment",
+        "user_id": "user_account_a",
+        "created_datetime": "2020-01-22T15:16:00"
+    }"#,
+);
+
+fn asset_disposal1() -> AssetDisposalRow {
+    AssetDisposalRow {
+        id: ASSET_DISPOSAL1.0.to_string(),
+        asset_id: "3de161ed-93ef-4210-aa31-3ae9e53748e8".to_string(),
+        store_id: "store_a".to_string(),
+        disposal_date: Defaults::naive_date(),
+        method: AssetDisposalMethod::Recycled,
+        comment: Some("test_comment".to_string()),
+        user_id: "user_account_a".to_string(),
+        created_datetime: Defaults::naive_date_time(),
+    }
+}
+
+pub(crate) fn test_pull_upsert_records() -> Vec<TestSyncIncomingRecord> {
+    vec![TestSyncIncomingRecord::new_pull_upsert(
+        TABLE_NAME,
+        ASSET_DISPOSAL1,
+        asset_disposal1(),
+    )]
+}
+
+pub(crate) fn test_v6_records() -> Vec<TestSyncOutgoingRecord> {
+    vec![TestSyncOutgoingRecord {
+        table_name: TABLE_NAME.to_string(),
+        record_id: ASSET_DISPOSAL1.0.to_string(),
+        push_data: json!(asset_disposal1()),
+    }]
+}
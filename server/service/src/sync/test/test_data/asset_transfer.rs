@@ -0,0 +1,56 @@
+use repository::assets::asset_transfer_row::{AssetTransferRow, AssetTransferStatus};
+use serde_json::json;
+use util::Defaults;
+
+use super::{TestSyncIncomingRecord, TestSyncOutgoingRecord};
+
+const TABLE_NAME: &'static str = "asset_transfer";
+
+const ASSET_TRANSFER1: (&'static str, &'static str) = (
+    "5d4cba04-3b4f-4c4a-8f0d-7a51d3f0b9a1",
+    r#"{
+        "id": "5d4cba04-3b4f-4c4a-8f0d-7a51d3f0b9a1",
+        "asset_id": "3de161ed-93ef-4210-aa31-3ae9e53748e8",
+        "from_store_id": "store_a",
+        "to_store_id": "store_b",
+        "status": "DISPATCHED",
+        "comment": "test_comment",
+        "created_datetime": "2020-01-22T15:16:00",
+        "created_by_user_id": "user_account_a",
+        "dispatched_datetime": "2020-01-22T15:16:00",
+        "dispatched_by_user_id": "user_account_a"
+    }"#,
+);
+
+fn asset_transfer1() -> AssetTransferRow {
+    AssetTransferRow {
+        id: ASSET_TRANSFER1.0.to_string(),
+        asset_id: "3de161ed-93ef-4210-aa31-3ae9e53748e8".to_string(),
+        from_store_id: "store_a".to_string(),
+        to_store_id: "store_b".to_string(),
+        status: AssetTransferStatus::Dispatched,
+        comment: Some("test_comment".to_string()),
+        created_datetime: Defaults::naive_date_time(),
+        created_by_user_id: "user_account_a".to_string(),
+        dispatched_datetime: Some(Defaults::naive_date_time()),
+        dispatched_by_user_id: Some("user_account_a".to_string()),
+        received_datetime: None,
+        received_by_user_id: None,
+    }
+}
+
+pub(crate) fn test_pull_upsert_records() -> Vec<TestSyncIncomingRecord> {
+    vec![TestSyncIncomingRecord::new_pull_upsert(
+        TABLE_NAME,
+        ASSET_TRANSFER1,
+        asset_transfer1(),
+    )]
+}
+
+pub(crate) fn test_v6_records() -> Vec<TestSyncOutgoingRecord> {
+    vec![TestSyncOutgoingRecord {
+        table_name: TABLE_NAME.to_string(),
+        record_id: ASSET_TRANSFER1.0.to_string(),
+        push_data: json!(asset_transfer1()),
+    }]
+}
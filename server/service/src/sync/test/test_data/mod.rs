@@ -7,7 +7,9 @@ pub(crate) mod asset;
 pub(crate) mod asset_catalogue_item;
 pub(crate) mod asset_category;
 pub(crate) mod asset_class;
+pub(crate) mod asset_disposal;
 pub(crate) mod asset_log;
+pub(crate) mod asset_transfer;
 pub(crate) mod asset_type;
 pub(crate) mod barcode;
 pub(crate) mod currency;
@@ -74,6 +76,8 @@ pub(crate) fn get_all_pull_upsert_central_test_records() -> Vec<TestSyncIncoming
     test_records.append(&mut asset_catalogue_item::test_pull_upsert_records());
     test_records.append(&mut asset::test_pull_upsert_records());
     test_records.append(&mut asset_log::test_pull_upsert_records());
+    test_records.append(&mut asset_transfer::test_pull_upsert_records());
+    test_records.append(&mut asset_disposal::test_pull_upsert_records());
     test_records.append(&mut sync_file_reference::test_pull_upsert_records());
     test_records
 }
@@ -158,6 +162,8 @@ pub(crate) fn get_all_sync_v6_records() -> Vec<TestSyncOutgoingRecord> {
     test_records.append(&mut asset_catalogue_item::test_v6_central_push_records());
     test_records.append(&mut asset::test_v6_records());
     test_records.append(&mut asset_log::test_v6_records());
+    test_records.append(&mut asset_transfer::test_v6_records());
+    test_records.append(&mut asset_disposal::test_v6_records());
     test_records.append(&mut sync_file_reference::test_v6_records());
 
     test_records
@@ -147,6 +147,8 @@ pub(crate) fn test_pull_upsert_records() -> Vec<TestSyncIncomingRecord> {
                     threshold_mos: 3.0,
                     max_mos: 3.0,
                     max_order_per_period: 1,
+                    is_emergency: false,
+                    max_items_in_emergency_order: 0,
                 }),
                 IntegrationOperation::upsert(ProgramRequisitionOrderTypeRow {
                     id: MASTER_LIST_WITH_PROGRAM_1.0.to_owned()
@@ -158,6 +160,8 @@ pub(crate) fn test_pull_upsert_records() -> Vec<TestSyncIncomingRecord> {
                     threshold_mos: 3.0,
                     max_mos: 3.0,
                     max_order_per_period: 1,
+                    is_emergency: false,
+                    max_items_in_emergency_order: 0,
                 }),
                 IntegrationOperation::upsert(ProgramRequisitionSettingsRow {
                     id: MASTER_LIST_WITH_PROGRAM_1.0.to_owned() + &mock_name_tag_2().id,
@@ -175,6 +179,8 @@ pub(crate) fn test_pull_upsert_records() -> Vec<TestSyncIncomingRecord> {
                     threshold_mos: 4.0,
                     max_mos: 4.0,
                     max_order_per_period: 1,
+                    is_emergency: false,
+                    max_items_in_emergency_order: 0,
                 }),
                 IntegrationOperation::upsert(ProgramRequisitionSettingsRow {
                     id: MASTER_LIST_WITH_PROGRAM_1.0.to_owned() + &mock_name_tag_3().id,
@@ -192,6 +198,8 @@ pub(crate) fn test_pull_upsert_records() -> Vec<TestSyncIncomingRecord> {
                     threshold_mos: 2.0,
                     max_mos: 2.0,
                     max_order_per_period: 3,
+                    is_emergency: false,
+                    max_items_in_emergency_order: 0,
                 }),
             ]),
             sync_buffer_row: SyncBufferRow {
@@ -84,6 +84,8 @@ fn requisition_request_pull_record() -> TestSyncIncomingRecord {
             program_id: None,
             period_id: None,
             order_type: None,
+            is_emergency: false,
+            emergency_reason: None,
         },
     )
 }
@@ -127,6 +129,8 @@ fn requisition_request_push_record() -> TestSyncOutgoingRecord {
             orderType: None,
             periodID: None,
             programID: None,
+            is_emergency: false,
+            om_emergency_reason: None,
         }),
     }
 }
@@ -197,6 +201,8 @@ fn requisition_response_pull_record() -> TestSyncIncomingRecord {
             program_id: Some("missing_program".to_string()),
             period_id: Some("641A3560C84A44BC9E6DDC01F3D75923".to_string()),
             order_type: Some("Normal".to_string()),
+            is_emergency: false,
+            emergency_reason: None,
         },
     )
 }
@@ -240,6 +246,8 @@ fn requisition_response_push_record() -> TestSyncOutgoingRecord {
             orderType: Some("Normal".to_string()),
             periodID: Some("641A3560C84A44BC9E6DDC01F3D75923".to_string()),
             programID: Some("missing_program".to_string()),
+            is_emergency: false,
+            om_emergency_reason: None,
         }),
     }
 }
@@ -322,6 +330,8 @@ fn requisition_om_fields_pull_record() -> TestSyncIncomingRecord {
             program_id: None,
             period_id: Some("641A3560C84A44BC9E6DDC01F3D75923".to_string()),
             order_type: Some("Normal".to_string()),
+            is_emergency: false,
+            emergency_reason: None,
         },
     )
 }
@@ -370,6 +380,8 @@ fn requisition_om_fields_push_record() -> TestSyncOutgoingRecord {
             orderType: Some("Normal".to_string()),
             periodID: Some("641A3560C84A44BC9E6DDC01F3D75923".to_string()),
             programID: None,
+            is_emergency: false,
+            om_emergency_reason: None,
         }),
     }
 }
@@ -447,6 +459,8 @@ fn program_requisition_request_pull_record() -> TestSyncIncomingRecord {
             program_id: Some("missing_program".to_string()),
             period_id: Some("772B3984DBA14A5F941ED0EF857FDB31".to_string()),
             order_type: Some("Normal".to_string()),
+            is_emergency: false,
+            emergency_reason: None,
         },
     )
 }
@@ -490,6 +504,8 @@ fn program_requisition_request_push_record() -> TestSyncOutgoingRecord {
             orderType: Some("Normal".to_string()),
             periodID: Some("772B3984DBA14A5F941ED0EF857FDB31".to_string()),
             programID: Some("missing_program".to_string()),
+            is_emergency: false,
+            om_emergency_reason: None,
         }),
     }
 }
@@ -19,6 +19,8 @@ const STORE_PREFERENCE_1: (&str, &str) = (
         "round_up_distribute_quantities": false,
         "can_pack_items_into_multiple_boxes": false,
         "can_issue_in_foreign_currency": true,
+        "useLeadTimeInSuggestedQuantity": true,
+        "requisitionOverOrderThresholdPercentage": 50,
         "edit_sell_price_on_customer_invoice_lines": false,
         "purchase_order_must_be_authorised": false,
         "finalise_customer_invoices_automatically": false,
@@ -148,6 +150,8 @@ pub(crate) fn test_pull_upsert_records() -> Vec<TestSyncIncomingRecord> {
                 om_program_module: true,
                 vaccine_module: false,
                 issue_in_foreign_currency: true,
+                use_lead_time_in_suggested_quantity: true,
+                requisition_over_order_threshold_percentage: 50,
             },
         ),
         TestSyncIncomingRecord::new_pull_upsert(
@@ -163,6 +167,8 @@ pub(crate) fn test_pull_upsert_records() -> Vec<TestSyncIncomingRecord> {
                 om_program_module: false,
                 vaccine_module: true,
                 issue_in_foreign_currency: false,
+                use_lead_time_in_suggested_quantity: false,
+                requisition_over_order_threshold_percentage: 0,
             },
         ),
     ]
@@ -1,4 +1,4 @@
-use repository::db_diesel::sync_file_reference_row::SyncFileReferenceRow;
+use repository::db_diesel::sync_file_reference_row::{SyncFileDirection, SyncFileReferenceRow};
 use serde_json::json;
 use util::Defaults;
 
@@ -27,6 +27,9 @@ fn sync_file_reference1() -> SyncFileReferenceRow {
         mime_type: Some("image/jpeg".to_string()),
         created_datetime: Defaults::naive_date_time(),
         deleted_datetime: None,
+        // Reference pulled from central, file is downloaded
+        direction: SyncFileDirection::Download,
+        ..Default::default()
     }
 }
 
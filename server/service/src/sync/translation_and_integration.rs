@@ -1,6 +1,9 @@
 use super::sync_status::logger::{SyncLogger, SyncLoggerError, SyncStepProgress};
 use super::{
     sync_buffer::SyncBuffer,
+    sync_conflict::{
+        check_sync_conflict, record_integrated_version, record_sync_conflict, ConflictCheck,
+    },
     translations::{IntegrationOperation, PullTranslateResult, SyncTranslation, SyncTranslators},
 };
 use crate::usize_to_u64;
@@ -13,12 +16,15 @@ static PROGRESS_STEP_LEN: usize = 100;
 pub(crate) struct TranslationAndIntegration<'a> {
     connection: &'a StorageConnection,
     sync_buffer: &'a SyncBuffer<'a>,
+    /// Versions of conflict tracked records are not kept during initialisation
+    is_initialised: bool,
 }
 
 #[derive(Default, Debug)]
 pub(crate) struct TranslationAndIntegrationResult {
     pub(crate) integrated_count: u32,
     pub(crate) errors_count: u32,
+    pub(crate) conflicts_count: u32,
 }
 type TableName = String;
 #[derive(Default, Debug)]
@@ -28,10 +34,12 @@ impl<'a> TranslationAndIntegration<'a> {
     pub(crate) fn new(
         connection: &'a StorageConnection,
         sync_buffer: &'a SyncBuffer,
+        is_initialised: bool,
     ) -> TranslationAndIntegration<'a> {
         TranslationAndIntegration {
             connection,
             sync_buffer,
+            is_initialised,
         }
     }
 
@@ -107,7 +115,35 @@ impl<'a> TranslationAndIntegration<'a> {
         };
 
         for (number_of_records_integrated, sync_record) in sync_records.into_iter().enumerate() {
-            let translation_result = match self.translate_sync_record(&sync_record, translators) {
+            // Concurrent edits of patient and shared-remote records (there are no local edits
+            // during initialisation)
+            let conflict_check = if self.is_initialised {
+                check_sync_conflict(self.connection, translators, &sync_record)?
+            } else {
+                ConflictCheck::Integrate
+            };
+            let (record, is_merged) = match conflict_check {
+                ConflictCheck::Integrate => (sync_record.clone(), false),
+                ConflictCheck::IntegrateMerged(data) => {
+                    let mut merged = sync_record.clone();
+                    merged.data = data;
+                    (merged, true)
+                }
+                ConflictCheck::Conflict(conflict) => {
+                    // Local version is kept until conflict is resolved
+                    record_sync_conflict(self.connection, &conflict)?;
+                    self.sync_buffer
+                        .record_successful_integration(&sync_record)?;
+                    result.insert_conflict(&sync_record.table_name);
+                    warn!(
+                        "Sync conflict {:?} {:?} {:?}",
+                        conflict.conflicting_fields, sync_record.record_id, sync_record.table_name
+                    );
+                    continue;
+                }
+            };
+
+            let translation_result = match self.translate_sync_record(&record, translators) {
                 Ok(translation_result) => translation_result,
                 // Record error in sync buffer and in result, continue to next sync_record
                 Err(translation_error) => {
@@ -144,6 +180,14 @@ impl<'a> TranslationAndIntegration<'a> {
             let integration_result = integrate(self.connection, &integration_records);
             match integration_result {
                 Ok(_) => {
+                    if self.is_initialised {
+                        record_integrated_version(
+                            self.connection,
+                            translators,
+                            &sync_record,
+                            is_merged,
+                        )?;
+                    }
                     self.sync_buffer
                         .record_successful_integration(&sync_record)?;
                     result.insert_success(&sync_record.table_name)
@@ -230,6 +274,11 @@ impl TranslationAndIntegrationResults {
         let entry = self.0.entry(table_name.to_owned()).or_default();
         entry.integrated_count += 1;
     }
+
+    fn insert_conflict(&mut self, table_name: &str) {
+        let entry = self.0.entry(table_name.to_owned()).or_default();
+        entry.conflicts_count += 1;
+    }
 }
 
 #[cfg(test)]
@@ -9,9 +9,11 @@ use chrono::NaiveDate;
 use repository::{
     ChangelogRow, ChangelogTableName, EqualFilter, InvoiceLine, InvoiceLineFilter,
     InvoiceLineRepository, InvoiceLineRow, InvoiceLineRowDelete, InvoiceLineRowType,
-    ItemRowRepository, StockLineRowRepository, StorageConnection, SyncBufferRow,
+    ItemRowRepository, LocationRowRepository, StockLineRowRepository, StorageConnection,
+    SyncBufferRow,
 };
 use serde::{Deserialize, Serialize};
+use util::is_central_server;
 
 use super::{
     is_active_record_on_site, ActiveRecordCheck, PullTranslateResult, PushTranslateResult,
@@ -172,12 +174,24 @@ impl SyncTranslation for InvoiceLineTranslation {
             }
         };
 
-        let is_record_active_on_site = is_active_record_on_site(
-            connection,
-            ActiveRecordCheck::InvoiceLine {
-                invoice_id: invoice_id.clone(),
-            },
-        )?;
+        // Open mSupply central server keeps lines pushed by sites that sync invoices through it (v6),
+        // location is only kept if it was synced to central server as well
+        let is_pushed_to_central = is_central_server() && sync_record.source_site_id.is_some();
+        let is_record_active_on_site = if is_pushed_to_central {
+            match &location_id {
+                Some(location_id) => LocationRowRepository::new(connection)
+                    .find_one_by_id(location_id)?
+                    .is_some(),
+                None => true,
+            }
+        } else {
+            is_active_record_on_site(
+                connection,
+                ActiveRecordCheck::InvoiceLine {
+                    invoice_id: invoice_id.clone(),
+                },
+            )?
+        };
 
         // TODO: remove the stock_line_is_valid check once central server does not generate the inbound shipment
         // omSupply should be generating the inbound, with valid stock lines.
@@ -3,7 +3,9 @@ pub(crate) mod asset;
 pub(crate) mod asset_catalogue_item;
 pub(crate) mod asset_category;
 pub(crate) mod asset_class;
+pub(crate) mod asset_disposal;
 pub(crate) mod asset_log;
+pub(crate) mod asset_transfer;
 pub(crate) mod asset_type;
 pub(crate) mod barcode;
 pub(crate) mod clinician;
@@ -111,6 +113,8 @@ pub(crate) fn all_translators() -> SyncTranslators {
         asset_type::boxed(),
         asset_catalogue_item::boxed(),
         asset_log::boxed(),
+        asset_transfer::boxed(),
+        asset_disposal::boxed(),
         //Sync file reference
         sync_file_reference::boxed(),
     ]
@@ -401,6 +405,23 @@ pub(crate) fn translate_changelogs_to_sync_records(
     Ok(out_records)
 }
 
+/// Remote records switched to open mSupply central server (v6) are sent in the same (legacy) format,
+/// table switch is checked when selecting change logs for push and pull
+pub(crate) fn translation_type_for_table<'a>(
+    table_name: &ChangelogTableName,
+    r#type: &'a ToSyncRecordTranslationType,
+) -> &'a ToSyncRecordTranslationType {
+    match r#type {
+        ToSyncRecordTranslationType::PushToOmSupplyCentral
+        | ToSyncRecordTranslationType::PullFromOmSupplyCentral
+            if table_name.can_sync_through_v6() =>
+        {
+            &ToSyncRecordTranslationType::PushToLegacyCentral
+        }
+        _ => r#type,
+    }
+}
+
 fn translate_changelog(
     connection: &StorageConnection,
     translators: &SyncTranslators,
@@ -409,6 +430,8 @@ fn translate_changelog(
 ) -> Result<Vec<PushSyncRecord>, anyhow::Error> {
     let mut translation_results = Vec::new();
 
+    let r#type = translation_type_for_table(&changelog.table_name, r#type);
+
     for translator in translators.iter() {
         if !translator.should_translate_to_sync_record(changelog, r#type) {
             continue;
@@ -50,6 +50,12 @@ struct LegacyOrderType {
     max_mos: f64,
     #[serde(rename = "maxOrdersPerPeriod")]
     max_order_per_period: i32,
+    #[serde(rename = "isEmergency")]
+    #[serde(default)]
+    is_emergency: bool,
+    #[serde(rename = "maxEmergencyItems")]
+    #[serde(default)]
+    max_items_in_emergency_order: i32,
 }
 // Needs to be added to all_translators()
 #[deny(dead_code)]
@@ -228,6 +234,8 @@ fn generate_requisition_program(
                         threshold_mos: order_type.threshold_mos,
                         max_mos: order_type.max_mos,
                         max_order_per_period: order_type.max_order_per_period,
+                        is_emergency: order_type.is_emergency,
+                        max_items_in_emergency_order: order_type.max_items_in_emergency_order,
                     };
 
                     program_requisition_order_type_rows.push(program_requisition_order_type_row);
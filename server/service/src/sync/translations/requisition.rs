@@ -155,6 +155,12 @@ pub struct LegacyRequisitionRow {
     pub periodID: Option<String>,
     #[serde(deserialize_with = "empty_str_as_option_string")]
     pub programID: Option<String>,
+
+    #[serde(default)]
+    pub is_emergency: bool,
+    #[serde(deserialize_with = "empty_str_as_option_string")]
+    #[serde(default)]
+    pub om_emergency_reason: Option<String>,
 }
 // Needs to be added to all_translators()
 #[deny(dead_code)]
@@ -257,6 +263,8 @@ impl SyncTranslation for RequisitionTranslation {
             program_id,
             period_id: data.periodID,
             order_type: data.orderType,
+            is_emergency: data.is_emergency,
+            emergency_reason: data.om_emergency_reason,
         };
 
         Ok(PullTranslateResult::upsert(result))
@@ -302,6 +310,8 @@ impl SyncTranslation for RequisitionTranslation {
                     program_id,
                     period_id,
                     order_type,
+                    is_emergency,
+                    emergency_reason,
                 },
             name_row,
             ..
@@ -351,6 +361,8 @@ impl SyncTranslation for RequisitionTranslation {
             programID: program_id,
             periodID: period_id,
             orderType: order_type,
+            is_emergency,
+            om_emergency_reason: emergency_reason,
         };
 
         Ok(PushTranslateResult::upsert(
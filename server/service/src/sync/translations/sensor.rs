@@ -144,6 +144,7 @@ impl SyncTranslation for SensorTranslation {
             SensorType::BlueMaestro => "BLUE_MAESTRO",
             SensorType::Laird => "LAIRD",
             SensorType::Berlinger => "BERLINGER",
+            SensorType::Generic => "GENERIC",
         }
         .to_string();
 
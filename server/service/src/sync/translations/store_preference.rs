@@ -33,6 +33,12 @@ pub struct LegacyPrefData {
     pub vaccine_module: bool,
     #[serde(rename = "can_issue_in_foreign_currency")]
     pub issue_in_foreign_currency: bool,
+    #[serde(default)]
+    #[serde(rename = "useLeadTimeInSuggestedQuantity")]
+    pub use_lead_time_in_suggested_quantity: bool,
+    #[serde(default)]
+    #[serde(rename = "requisitionOverOrderThresholdPercentage")]
+    pub requisition_over_order_threshold_percentage: i32,
 }
 
 // Needs to be added to all_translators()
@@ -71,6 +77,8 @@ impl SyncTranslation for StorePreferenceTranslation {
             om_program_module,
             vaccine_module,
             issue_in_foreign_currency,
+            use_lead_time_in_suggested_quantity,
+            requisition_over_order_threshold_percentage,
         } = data;
 
         let result = StorePreferenceRow {
@@ -82,6 +90,8 @@ impl SyncTranslation for StorePreferenceTranslation {
             om_program_module,
             vaccine_module,
             issue_in_foreign_currency,
+            use_lead_time_in_suggested_quantity,
+            requisition_over_order_threshold_percentage,
         };
 
         Ok(PullTranslateResult::upsert(result))
@@ -1,5 +1,7 @@
 use repository::{
-    sync_file_reference_row::{SyncFileReferenceRow, SyncFileReferenceRowRepository},
+    sync_file_reference_row::{
+        SyncFileDirection, SyncFileReferenceRow, SyncFileReferenceRowRepository,
+    },
     ChangelogRow, ChangelogTableName, StorageConnection, SyncBufferRow,
 };
 
@@ -28,12 +30,31 @@ impl SyncTranslation for SyncFileReferenceTranslation {
 
     fn try_translate_from_upsert_sync_record(
         &self,
-        _: &StorageConnection,
+        connection: &StorageConnection,
         sync_record: &SyncBufferRow,
     ) -> Result<PullTranslateResult, anyhow::Error> {
-        Ok(PullTranslateResult::upsert(serde_json::from_str::<
-            SyncFileReferenceRow,
-        >(&sync_record.data)?))
+        let row = serde_json::from_str::<SyncFileReferenceRow>(&sync_record.data)?;
+
+        // Transfer state is local to the site, file of a new reference is on another site
+        let row = match SyncFileReferenceRowRepository::new(connection).find_one_by_id(&row.id)? {
+            Some(existing) => SyncFileReferenceRow {
+                direction: existing.direction,
+                status: existing.status,
+                total_bytes: existing.total_bytes,
+                transferred_bytes: existing.transferred_bytes,
+                hash: existing.hash,
+                retries: existing.retries,
+                retry_datetime: existing.retry_datetime,
+                error: existing.error,
+                ..row
+            },
+            None => SyncFileReferenceRow {
+                direction: SyncFileDirection::Download,
+                ..row
+            },
+        };
+
+        Ok(PullTranslateResult::upsert(row))
     }
 
     fn change_log_type(&self) -> Option<ChangelogTableName> {
@@ -0,0 +1,178 @@
+use repository::{
+    ChangelogFilter, ChangelogTableName, EqualFilter, KeyValueStoreRepository, KeyValueType,
+    RepositoryError, StorageConnection,
+};
+
+use crate::cursor_controller::CursorController;
+
+use super::translations::ToSyncRecordTranslationType;
+
+#[derive(Debug, PartialEq)]
+pub enum SetV6SyncTablesError {
+    TableCannotSyncThroughV6(ChangelogTableName),
+    DatabaseError(RepositoryError),
+}
+
+/// Remote record tables this site syncs through open mSupply central server (v6) instead of legacy central
+pub fn get_v6_sync_tables(
+    connection: &StorageConnection,
+) -> Result<Vec<ChangelogTableName>, RepositoryError> {
+    let tables = KeyValueStoreRepository::new(connection).get_string(KeyValueType::SyncV6Tables)?;
+
+    Ok(tables
+        .and_then(|tables| serde_json::from_str(&tables).ok())
+        .unwrap_or_default())
+}
+
+/// Switch remote record tables between legacy and open mSupply central server (v6) sync.
+///
+/// Push cursors are moved back so that change logs not yet pushed by the previous sync are
+/// pushed by the new one, and v6 pull is restarted to receive existing records of newly
+/// switched tables (e.g. transfers from other sites)
+pub fn set_v6_sync_tables(
+    connection: &StorageConnection,
+    tables: Vec<ChangelogTableName>,
+) -> Result<Vec<ChangelogTableName>, SetV6SyncTablesError> {
+    if let Some(table) = tables.iter().find(|table| !table.can_sync_through_v6()) {
+        return Err(SetV6SyncTablesError::TableCannotSyncThroughV6(
+            table.clone(),
+        ));
+    }
+
+    let result = connection
+        .transaction_sync(|connection| -> Result<_, RepositoryError> {
+            let existing = get_v6_sync_tables(connection)?;
+            let switched_on = tables.iter().any(|table| !existing.contains(table));
+            let switched_off = existing.iter().any(|table| !tables.contains(table));
+
+            let v6_push_cursor = CursorController::new(KeyValueType::SyncPushCursorV6);
+            let legacy_push_cursor = CursorController::new(KeyValueType::RemoteSyncPushCursor);
+            let earliest_push_cursor = v6_push_cursor
+                .get(connection)?
+                .min(legacy_push_cursor.get(connection)?);
+
+            if switched_on {
+                v6_push_cursor.update(connection, earliest_push_cursor)?;
+                CursorController::new(KeyValueType::SyncPullCursorV6).update(connection, 0)?;
+            }
+            if switched_off {
+                legacy_push_cursor.update(connection, earliest_push_cursor)?;
+            }
+
+            let tables: Vec<ChangelogTableName> = ChangelogTableName::v6_switchable_tables()
+                .into_iter()
+                .filter(|table| tables.contains(table))
+                .collect();
+
+            KeyValueStoreRepository::new(connection).set_string(
+                KeyValueType::SyncV6Tables,
+                serde_json::to_string(&tables).ok(),
+            )?;
+
+            Ok(tables)
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(result)
+}
+
+/// Excludes change logs of remote record tables that are synced through the other central server
+pub(crate) fn v6_sync_tables_push_filter(
+    connection: &StorageConnection,
+    filter: ChangelogFilter,
+    r#type: &ToSyncRecordTranslationType,
+) -> Result<ChangelogFilter, RepositoryError> {
+    let v6_sync_tables = get_v6_sync_tables(connection)?;
+
+    let excluded_tables = match r#type {
+        ToSyncRecordTranslationType::PushToLegacyCentral => v6_sync_tables,
+        ToSyncRecordTranslationType::PushToOmSupplyCentral => {
+            ChangelogTableName::v6_switchable_tables()
+                .into_iter()
+                .filter(|table| !v6_sync_tables.contains(table))
+                .collect()
+        }
+        ToSyncRecordTranslationType::PullFromOmSupplyCentral => Vec::new(),
+    };
+
+    if excluded_tables.is_empty() {
+        return Ok(filter);
+    }
+
+    Ok(filter.table_name(EqualFilter {
+        not_equal_all: Some(excluded_tables),
+        ..Default::default()
+    }))
+}
+
+impl From<RepositoryError> for SetV6SyncTablesError {
+    fn from(error: RepositoryError) -> Self {
+        SetV6SyncTablesError::DatabaseError(error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use repository::{mock::MockDataInserts, test_db::setup_all};
+
+    use super::*;
+
+    #[actix_rt::test]
+    async fn test_v6_sync_tables() {
+        let (_, connection, _, _) = setup_all("test_v6_sync_tables", MockDataInserts::none()).await;
+
+        assert_eq!(get_v6_sync_tables(&connection), Ok(Vec::new()));
+
+        assert_eq!(
+            set_v6_sync_tables(&connection, vec![ChangelogTableName::Name]),
+            Err(SetV6SyncTablesError::TableCannotSyncThroughV6(
+                ChangelogTableName::Name
+            ))
+        );
+
+        let v6_push_cursor = CursorController::new(KeyValueType::SyncPushCursorV6);
+        let legacy_push_cursor = CursorController::new(KeyValueType::RemoteSyncPushCursor);
+        let v6_pull_cursor = CursorController::new(KeyValueType::SyncPullCursorV6);
+        v6_push_cursor.update(&connection, 10).unwrap();
+        legacy_push_cursor.update(&connection, 5).unwrap();
+        v6_pull_cursor.update(&connection, 20).unwrap();
+
+        // Switch on, v6 push resumes from legacy push cursor and v6 pull restarts
+        assert_eq!(
+            set_v6_sync_tables(
+                &connection,
+                vec![ChangelogTableName::InvoiceLine, ChangelogTableName::Invoice]
+            ),
+            Ok(vec![
+                ChangelogTableName::Invoice,
+                ChangelogTableName::InvoiceLine
+            ])
+        );
+        assert_eq!(v6_push_cursor.get(&connection), Ok(5));
+        assert_eq!(v6_pull_cursor.get(&connection), Ok(0));
+
+        let filter = v6_sync_tables_push_filter(
+            &connection,
+            ChangelogFilter::new(),
+            &ToSyncRecordTranslationType::PushToLegacyCentral,
+        )
+        .unwrap();
+        assert_eq!(
+            filter.table_name.unwrap().not_equal_all,
+            Some(vec![
+                ChangelogTableName::Invoice,
+                ChangelogTableName::InvoiceLine
+            ])
+        );
+
+        // Switch off, legacy push resumes from v6 push cursor
+        v6_push_cursor.update(&connection, 3).unwrap();
+        v6_pull_cursor.update(&connection, 20).unwrap();
+        assert_eq!(
+            set_v6_sync_tables(&connection, vec![ChangelogTableName::Invoice]),
+            Ok(vec![ChangelogTableName::Invoice])
+        );
+        assert_eq!(legacy_push_cursor.get(&connection), Ok(3));
+        assert_eq!(v6_pull_cursor.get(&connection), Ok(20));
+    }
+}
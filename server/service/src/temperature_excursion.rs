@@ -1,79 +1,173 @@
-use chrono::{Days, Utc};
+use std::collections::BTreeMap;
+
+use chrono::{Duration, NaiveDateTime, Utc};
 use repository::{
-    DatetimeFilter, EqualFilter, RepositoryError, StorageConnection, TemperatureExcursion,
+    DatetimeFilter, EqualFilter, RepositoryError, StorageConnection, TemperatureBreachConfigFilter,
+    TemperatureBreachConfigRepository, TemperatureBreachRowType, TemperatureExcursion,
     TemperatureExcursionRepository, TemperatureLogFilter, TemperatureRow,
 };
 
 pub trait TemperatureExcursionServiceTrait: Sync + Send {
+    /// Ongoing excursions, logs are checked back to the longest lookback of the store's active
+    /// excursion configs
     fn excursions(
         &self,
         connection: &StorageConnection,
         store_id: &str,
     ) -> Result<Vec<TemperatureExcursion>, RepositoryError> {
+        let lookback_days = TemperatureBreachConfigRepository::new(connection)
+            .query_by_filter(
+                TemperatureBreachConfigFilter::new()
+                    .store_id(EqualFilter::equal_to(store_id))
+                    .r#type(TemperatureBreachRowType::Excursion.equal_to())
+                    .is_active(true),
+            )?
+            .into_iter()
+            .map(|config| config.temperature_breach_config_row.lookback_days)
+            .max();
+        let Some(lookback_days) = lookback_days else {
+            return Ok(Vec::new());
+        };
+
+        let now = Utc::now().naive_utc();
         let filter = TemperatureLogFilter::new()
             .store_id(EqualFilter::equal_to(store_id))
             .datetime(DatetimeFilter::after_or_equal_to(
-                Utc::now()
-                    .naive_utc()
-                    .checked_sub_days(Days::new(7))
-                    .unwrap(),
+                now - Duration::days(lookback_days as i64),
             ));
 
-        let log_data = TemperatureExcursionRepository::new(&connection).query(filter)?;
+        let log_data = TemperatureExcursionRepository::new(connection).query(filter)?;
 
-        temperature_excursions(log_data)
+        temperature_excursions(log_data, now)
     }
 }
 
 fn temperature_excursions(
     log_data: Vec<TemperatureRow>,
+    now: NaiveDateTime,
 ) -> Result<Vec<TemperatureExcursion>, RepositoryError> {
-    let mut excursion_data: Vec<TemperatureExcursion> = Vec::new();
-
-    for row in log_data.iter() {
-        if row.is_excursion == true {
-            let excursion_end = log_data.iter().find(|r| {
-                r.datetime > row.datetime
-                    && r.sensor_id == row.sensor_id
-                    && r.is_excursion == false
-                    && r.store_id == row.store_id
-                    && r.location_id == row.location_id
-            });
+    // Logs are grouped by sensor and location, log data is sorted by datetime so groups are too
+    let mut groups: BTreeMap<(String, String, Option<String>), Vec<TemperatureRow>> =
+        BTreeMap::new();
+    for row in applicable_rows(log_data) {
+        if row.datetime < now - Duration::days(row.config.lookback_days as i64) {
+            continue;
+        }
+        groups
+            .entry((
+                row.store_id.clone(),
+                row.sensor_id.clone(),
+                row.location_id.clone(),
+            ))
+            .or_default()
+            .push(row);
+    }
 
-            // if the temperature is back within range then we don't
-            // to need notify of the excursion
-            if excursion_end.is_some() {
-                continue;
-            }
+    let mut excursion_data: Vec<TemperatureExcursion> = groups
+        .into_values()
+        .filter_map(|rows| ongoing_excursion(&rows, now))
+        .collect();
+    excursion_data.sort_by_key(|excursion| excursion.datetime);
 
-            let existing_excursion = excursion_data.iter().find(|r| {
-                r.sensor_id == row.sensor_id
-                    && r.store_id == row.store_id
-                    && r.location_id == row.location_id
-            });
+    Ok(excursion_data)
+}
+
+/// Log data has a row for each excursion config of the store, only keep the row of the config
+/// for the log's location or, when there isn't one, the row of the store wide config
+fn applicable_rows(log_data: Vec<TemperatureRow>) -> Vec<TemperatureRow> {
+    let mut result: Vec<TemperatureRow> = Vec::new();
+    let mut index_by_log_id: BTreeMap<String, usize> = BTreeMap::new();
+
+    for row in log_data {
+        let is_for_location = match &row.config.location_id {
+            None => false,
+            Some(location_id) if Some(location_id) == row.location_id.as_ref() => true,
+            // Config for another location
+            Some(_) => continue,
+        };
 
-            // we may have multiple temperature log records for a given sensor
-            // in which case there is only one excursion to report
-            if existing_excursion.is_some() {
-                continue;
+        match index_by_log_id.get(&row.id) {
+            Some(index) => {
+                if is_for_location && result[*index].config.location_id.is_none() {
+                    result[*index] = row;
+                }
             }
+            None => {
+                index_by_log_id.insert(row.id.clone(), result.len());
+                result.push(row);
+            }
+        }
+    }
 
-            let duration = Utc::now().timestamp() - row.datetime.timestamp();
-            if duration > row.duration {
-                excursion_data.push(TemperatureExcursion {
-                    id: row.id.clone(),
-                    datetime: row.datetime,
-                    temperature: row.temperature,
-                    location_id: row.location_id.clone(),
-                    duration,
-                    sensor_id: row.sensor_id.clone(),
-                    store_id: row.store_id.clone(),
-                })
+    result
+}
+
+/// Excursion starts at the first out of range log and is closed once temperature is back within
+/// range (narrowed by config hysteresis temperature) for the config hysteresis duration.
+/// Excursion that is not closed is reported when its out of range logs span the minimum excursion
+/// duration and it has lasted longer than config duration. `rows` must be sorted by datetime
+fn ongoing_excursion(rows: &[TemperatureRow], now: NaiveDateTime) -> Option<TemperatureExcursion> {
+    let mut start: Option<&TemperatureRow> = None;
+    let mut last_out_of_range = None;
+    let mut recovered_since = None;
+
+    for row in rows {
+        if row.is_excursion {
+            if start.is_none() {
+                start = Some(row);
             }
-        };
+            last_out_of_range = Some(row.datetime);
+            recovered_since = None;
+            continue;
+        }
+
+        if start.is_none() {
+            continue;
+        }
+
+        let config = &row.config;
+        let is_recovered = row.temperature
+            >= config.minimum_temperature + config.hysteresis_temperature
+            && row.temperature <= config.maximum_temperature - config.hysteresis_temperature;
+        if !is_recovered {
+            recovered_since = None;
+            continue;
+        }
+
+        let since = *recovered_since.get_or_insert(row.datetime);
+        if row.datetime - since
+            >= Duration::milliseconds(config.hysteresis_duration_milliseconds as i64)
+        {
+            // if the temperature is back within range then we don't
+            // to need notify of the excursion
+            start = None;
+            recovered_since = None;
+        }
+    }
+
+    let start = start?;
+    let config = &start.config;
+    let out_of_range_duration = last_out_of_range? - start.datetime;
+    if out_of_range_duration
+        < Duration::milliseconds(config.minimum_excursion_duration_milliseconds as i64)
+    {
+        return None;
     }
 
-    Ok(excursion_data.into_iter().collect())
+    let duration = now.timestamp() - start.datetime.timestamp();
+    if duration <= start.duration {
+        return None;
+    }
+
+    Some(TemperatureExcursion {
+        id: start.id.clone(),
+        datetime: start.datetime,
+        temperature: start.temperature,
+        location_id: start.location_id.clone(),
+        duration,
+        sensor_id: start.sensor_id.clone(),
+        store_id: start.store_id.clone(),
+    })
 }
 
 #[cfg(test)]
@@ -83,10 +177,10 @@ mod test {
         test_db::setup_all_with_data,
         DatetimeFilter, LocationRow, NameRow, SensorRow, StoreRow, TemperatureBreachConfigRow,
         TemperatureBreachRowType, TemperatureExcursion, TemperatureExcursionRepository,
-        TemperatureLogFilter, TemperatureLogRow,
+        TemperatureLogFilter, TemperatureLogRow, TemperatureRow,
     };
 
-    use chrono::{Days, NaiveTime, Utc};
+    use chrono::{Days, Duration, NaiveDate, NaiveTime, Utc};
     use rand::{seq::SliceRandom, thread_rng};
 
     use crate::temperature_excursion::temperature_excursions;
@@ -133,6 +227,8 @@ mod test {
             is_active: true,
             duration_milliseconds: 24 * 60 * 60 * 1000, // one day
             r#type: TemperatureBreachRowType::Excursion,
+            lookback_days: 30,
+            ..Default::default()
         };
 
         // testing with two sensors
@@ -200,11 +296,8 @@ mod test {
                     .datetime(DatetimeFilter::after_or_equal_to(start_date_time)),
             )
             .unwrap();
-        let result = temperature_excursions(log_data).unwrap();
+        let result = temperature_excursions(log_data, today).unwrap();
 
-        // resetting `today` : when the test is run as part of a suite, it can take almost a second to get to this point
-        // which means the durations are not correct
-        let today = Utc::now().naive_utc();
         let datetime1 = today
             .checked_sub_days(Days::new(8))
             .unwrap()
@@ -242,6 +335,113 @@ mod test {
             ],
         );
     }
+
+    #[test]
+    fn temperature_excursion_hysteresis_and_location_config() {
+        let now = NaiveDate::from_ymd_opt(2024, 1, 10)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap();
+
+        let store_config = TemperatureBreachConfigRow {
+            id: "store_config".to_string(),
+            r#type: TemperatureBreachRowType::Excursion,
+            store_id: "store".to_string(),
+            is_active: true,
+            minimum_temperature: 2.0,
+            maximum_temperature: 8.0,
+            lookback_days: 7,
+            minimum_excursion_duration_milliseconds: 10 * 60 * 1000,
+            hysteresis_temperature: 0.5,
+            hysteresis_duration_milliseconds: 30 * 60 * 1000,
+            ..Default::default()
+        };
+        let fridge_config = TemperatureBreachConfigRow {
+            id: "fridge_config".to_string(),
+            location_id: Some("fridge".to_string()),
+            minimum_temperature: 0.0,
+            maximum_temperature: 10.0,
+            ..store_config.clone()
+        };
+        let freezer_config = TemperatureBreachConfigRow {
+            id: "freezer_config".to_string(),
+            location_id: Some("freezer".to_string()),
+            minimum_temperature: -30.0,
+            maximum_temperature: -10.0,
+            ..store_config.clone()
+        };
+
+        let row = |id: &str,
+                   minutes_ago: i64,
+                   temperature: f64,
+                   sensor_id: &str,
+                   location_id: Option<&str>,
+                   config: &TemperatureBreachConfigRow| TemperatureRow {
+            id: id.to_string(),
+            datetime: now - Duration::minutes(minutes_ago),
+            temperature,
+            store_id: "store".to_string(),
+            sensor_id: sensor_id.to_string(),
+            location_id: location_id.map(ToString::to_string),
+            is_excursion: temperature < config.minimum_temperature
+                || temperature > config.maximum_temperature,
+            duration: (config.duration_milliseconds / 1000) as i64,
+            config: config.clone(),
+        };
+
+        let mut log_data = vec![
+            // Sensor 1: outside of lookback
+            row("a0", 8 * 24 * 60, 9.0, "s1", None, &store_config),
+            // Sensor 1: back within range, but not by hysteresis temperature or for long enough
+            row("a1", 120, 9.0, "s1", None, &store_config),
+            row("a2", 110, 7.8, "s1", None, &store_config),
+            row("a3", 100, 9.0, "s1", None, &store_config),
+            row("a4", 90, 7.0, "s1", None, &store_config),
+            row("a5", 80, 7.0, "s1", None, &store_config),
+            row("a6", 70, 9.5, "s1", None, &store_config),
+            row("a7", 60, 7.0, "s1", None, &store_config),
+            row("a8", 50, 7.0, "s1", None, &store_config),
+            // Sensor 2: single out of range log is shorter than minimum excursion duration
+            row("b1", 30, 9.0, "s2", None, &store_config),
+            row("b1", 30, 9.0, "s2", None, &freezer_config),
+            row("b2", 20, 7.8, "s2", None, &store_config),
+            row("b2", 20, 7.8, "s2", None, &freezer_config),
+            // Sensor 1 in fridge: fridge config is used instead of store config
+            row("c1", 60, 9.0, "s1", Some("fridge"), &store_config),
+            row("c1", 60, 9.0, "s1", Some("fridge"), &fridge_config),
+            row("c2", 50, 11.0, "s1", Some("fridge"), &store_config),
+            row("c2", 50, 11.0, "s1", Some("fridge"), &fridge_config),
+            row("c3", 30, 11.0, "s1", Some("fridge"), &store_config),
+            row("c3", 30, 11.0, "s1", Some("fridge"), &fridge_config),
+        ];
+        log_data.sort_by_key(|row| row.datetime);
+
+        let result = temperature_excursions(log_data, now).unwrap();
+
+        assert_eq!(
+            result,
+            vec![
+                TemperatureExcursion {
+                    id: "a1".to_string(),
+                    datetime: now - Duration::minutes(120),
+                    temperature: 9.0,
+                    location_id: None,
+                    duration: 120 * 60,
+                    store_id: "store".to_string(),
+                    sensor_id: "s1".to_string(),
+                },
+                TemperatureExcursion {
+                    id: "c2".to_string(),
+                    datetime: now - Duration::minutes(50),
+                    temperature: 11.0,
+                    location_id: Some("fridge".to_string()),
+                    duration: 50 * 60,
+                    store_id: "store".to_string(),
+                    sensor_id: "s1".to_string(),
+                },
+            ],
+        );
+    }
 }
 
 pub struct TemperatureExcursionService {}
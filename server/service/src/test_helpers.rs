@@ -1,6 +1,7 @@
 use std::sync::Arc;
 
 use actix_rt::task::JoinHandle;
+use chrono::{NaiveDate, NaiveDateTime};
 use repository::{
     mock::{MockData, MockDataInserts},
     test_db::setup_all_with_data,
@@ -67,3 +68,11 @@ pub(crate) async fn setup_all_and_service_provider(
 ) -> ServiceTestContext {
     setup_all_with_data_and_service_provider(db_name, inserts, MockData::default()).await
 }
+
+/// Time of day on the date used by test data
+pub(crate) fn datetime(hour: u32, minute: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2024, 1, 1)
+        .unwrap()
+        .and_hms_opt(hour, minute, 0)
+        .unwrap()
+}
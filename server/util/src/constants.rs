@@ -4,6 +4,8 @@ use chrono::Duration;
 pub const INVENTORY_ADJUSTMENT_NAME_CODE: &str = "invad";
 /// Code for the special repack name
 pub const REPACK_NAME_CODE: &str = "repack";
+/// Id of the inventory adjustment reason for stock discarded after a temperature breach
+pub const COLD_CHAIN_BREACH_REASON_ID: &str = "cold_chain_breach";
 /// Number of days in a month (used in AMC calculation)
 pub const NUMBER_OF_DAYS_IN_A_MONTH: f64 = 30.0;
 /// For use when service item is not specified in service invoice line
@@ -20,6 +22,8 @@ pub fn expected_delivery_date_offset() -> Duration {
 }
 /// Default AMC calculation lookback
 pub const DEFAULT_AMC_LOOKBACK_MONTHS: u32 = 3;
+/// Lookback for supplier lead time measured from past requisitions (used in suggested quantity)
+pub const DEFAULT_LEAD_TIME_LOOKBACK_MONTHS: u32 = 12;
 // System user
 pub const SYSTEM_USER_ID: &str = "omsupply_system";
 // Missing program